@@ -0,0 +1,129 @@
+// Legacy response field aliases with deprecation headers
+// List endpoints now share the `{items, total, ...}` envelope. While legacy
+// fields are enabled, responses also carry the old list key (`results`,
+// `anime`, `episodes`) and announce it with Deprecation/Sunset headers.
+
+use axum::{
+    http::{HeaderName, HeaderValue, StatusCode},
+    Json,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Lists the legacy fields present in the body, e.g. `results`
+pub const DEPRECATED_FIELDS_HEADER: &str = "x-deprecated-fields";
+
+/// Legacy response field configuration
+#[derive(Clone, Debug)]
+pub struct LegacyFieldsConfig {
+    /// Include legacy aliases alongside the envelope fields
+    pub enabled: bool,
+    /// HTTP-date after which the aliases will be removed, sent as `Sunset`
+    pub sunset: Option<String>,
+}
+
+impl Default for LegacyFieldsConfig {
+    fn default() -> Self {
+        LegacyFieldsConfig {
+            enabled: true,
+            sunset: None,
+        }
+    }
+}
+
+impl LegacyFieldsConfig {
+    /// Reads LEGACY_RESPONSE_FIELDS and LEGACY_RESPONSE_FIELDS_SUNSET
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("LEGACY_RESPONSE_FIELDS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(true);
+
+        let sunset = std::env::var("LEGACY_RESPONSE_FIELDS_SUNSET")
+            .ok()
+            .filter(|s| match chrono::DateTime::parse_from_rfc2822(s) {
+                Ok(_) => true,
+                Err(e) => {
+                    tracing::warn!("Ignoring LEGACY_RESPONSE_FIELDS_SUNSET '{}': {}", s, e);
+                    false
+                }
+            });
+
+        LegacyFieldsConfig { enabled, sunset }
+    }
+}
+
+/// 200 response for a list envelope, adding `legacy_field` as an alias of `items`
+/// (plus deprecation headers) while legacy fields are enabled
+pub fn list_response<T: Serialize>(config: &LegacyFieldsConfig, legacy_field: &str, envelope: T) -> Response {
+    let mut body = match serde_json::to_value(envelope) {
+        Ok(body) => body,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(serde_json::json!({
+                    "error": format!("Failed to serialize response: {}", e)
+                }))
+            ).into_response();
+        }
+    };
+
+    if !config.enabled {
+        return (StatusCode::OK, Json(body)).into_response();
+    }
+
+    if let Value::Object(fields) = &mut body {
+        if let Some(items) = fields.get("items").cloned() {
+            fields.insert(legacy_field.to_string(), items);
+        }
+    }
+
+    let mut response = (StatusCode::OK, Json(body)).into_response();
+    let headers = response.headers_mut();
+    headers.insert(HeaderName::from_static("deprecation"), HeaderValue::from_static("true"));
+    if let Ok(value) = HeaderValue::from_str(legacy_field) {
+        headers.insert(HeaderName::from_static(DEPRECATED_FIELDS_HEADER), value);
+    }
+    if let Some(value) = config.sunset.as_deref().and_then(|s| HeaderValue::from_str(s).ok()) {
+        headers.insert(HeaderName::from_static("sunset"), value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn body(response: Response) -> Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_legacy_shape_is_flagged() {
+        let config = LegacyFieldsConfig {
+            enabled: true,
+            sunset: Some("Wed, 01 Jul 2026 00:00:00 GMT".to_string()),
+        };
+        let response = list_response(&config, "results", json!({ "items": [1, 2], "total": 2 }));
+
+        assert_eq!(response.headers()["deprecation"], "true");
+        assert_eq!(response.headers()[DEPRECATED_FIELDS_HEADER], "results");
+        assert_eq!(response.headers()["sunset"], "Wed, 01 Jul 2026 00:00:00 GMT");
+        assert_eq!(body(response).await["results"], json!([1, 2]));
+    }
+
+    #[tokio::test]
+    async fn test_envelope_only_when_legacy_disabled() {
+        let config = LegacyFieldsConfig { enabled: false, sunset: None };
+        let response = list_response(&config, "results", json!({ "items": [1, 2], "total": 2 }));
+
+        assert!(response.headers().get("deprecation").is_none());
+        let body = body(response).await;
+        assert_eq!(body["items"], json!([1, 2]));
+        assert!(body.get("results").is_none());
+    }
+}
@@ -0,0 +1,192 @@
+// Admin maintenance endpoints
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+use crate::api::handlers::episodes::invalidate_episode_etags;
+use crate::db::connection::AppState;
+use crate::middleware::AdminUser;
+use crate::middleware::cors::normalize_origin;
+
+// DELETE /api/admin/cache/{version}
+// Purges a cache schema-version namespace ahead of its natural expiry
+pub async fn purge_cache_version(
+    Path(version): Path<u32>,
+    State(state): State<AppState>,
+    _admin: AdminUser,
+) -> impl IntoResponse {
+    let mut cache = state.cache.lock().await;
+    
+    if version == cache.version() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Refusing to purge the active cache version"
+            }))
+        ).into_response();
+    }
+    
+    match cache.purge_version(version).await {
+        Ok(purged) => {
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "version": version,
+                    "purged": purged
+                }))
+            ).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to purge cache: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// GET /api/admin/recommendations/rebuild
+// Recomputes tag similarities now instead of waiting for the hourly run
+pub async fn rebuild_recommendations(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+) -> impl IntoResponse {
+    match state.recommendations.rebuild_similarities().await {
+        Ok(report) => (StatusCode::OK, Json(report)).into_response(),
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to rebuild recommendations: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CorsOriginRequest {
+    pub origin: String,
+}
+
+fn invalid_origin() -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({
+            "error": "Origin must be an http(s) scheme and host, with an optional port"
+        }))
+    ).into_response()
+}
+
+// POST /api/admin/cors/origins
+// Allows an origin at runtime; every instance picks it up via Redis
+pub async fn add_cors_origin(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    Json(req): Json<CorsOriginRequest>,
+) -> impl IntoResponse {
+    let Some(origin) = normalize_origin(&req.origin) else {
+        return invalid_origin();
+    };
+    
+    match state.cache.lock().await.add_cors_origin(&origin).await {
+        Ok(added) => {
+            let status = if added { StatusCode::CREATED } else { StatusCode::OK };
+            (status, Json(json!({ "origin": origin, "added": added }))).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to add CORS origin: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// DELETE /api/admin/cors/origins/{origin}
+// `origin` is percent-encoded, e.g. https%3A%2F%2Fpartner.example
+pub async fn remove_cors_origin(
+    Path(origin): Path<String>,
+    State(state): State<AppState>,
+    _admin: AdminUser,
+) -> impl IntoResponse {
+    let Some(origin) = normalize_origin(&origin) else {
+        return invalid_origin();
+    };
+    
+    match state.cache.lock().await.remove_cors_origin(&origin).await {
+        Ok(true) => (StatusCode::OK, Json(json!({ "origin": origin, "removed": true }))).into_response(),
+        Ok(false) => {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Origin is not in the allow-list"
+                }))
+            ).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to remove CORS origin: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// POST /api/admin/anime/{id}/episodes/renumber
+// Renumbers episodes imported with absolute numbers (e.g. 1042..) to per-season
+// order, keeping the originals as `absolute_number`
+pub async fn renumber_episodes(
+    Path(anime_id): Path<Uuid>,
+    State(state): State<AppState>,
+    _admin: AdminUser,
+) -> impl IntoResponse {
+    match state.db.get_anime(anime_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Anime not found"
+                }))
+            ).into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch anime: {}", e)
+                }))
+            ).into_response();
+        }
+    }
+    
+    match state.db.renumber_anime_episodes(anime_id).await {
+        Ok(report) => {
+            if report.renumbered > 0 {
+                invalidate_episode_etags(&state, anime_id).await;
+            }
+            (StatusCode::OK, Json(report)).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to renumber episodes: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
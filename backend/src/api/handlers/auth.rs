@@ -2,20 +2,28 @@
 // Reference: contracts/openapi.yaml lines 145-230
 
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::{StatusCode, HeaderMap},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use crate::db::connection::AppState;
+use crate::middleware::csrf::{self, cookie_value, REFRESH_COOKIE, SESSION_COOKIE};
 use crate::middleware::json_extractor::ValidatedJson;
+use crate::api::handlers::devices::register_device;
+use crate::models::{DeviceInfo, SessionResponse, UserRegistrationRequest};
+use crate::services::auth::{RefreshTokenError, RegistrationError};
+use validator::Validate;
 
 #[derive(Debug, Deserialize)]
 pub struct LoginRequest {
     email: String,
     password: String,
+    /// Names the device signing in, for session management
+    #[serde(default)]
+    device: Option<DeviceInfo>,
 }
 
 #[derive(Debug, Serialize)]
@@ -25,22 +33,190 @@ pub struct LoginResponse {
     refresh_token: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct VerifyEmailParams {
+    token: String,
+}
+
+/// Hand a new session to the client: tokens in the body for bearer mode, or as
+/// HttpOnly cookies plus a CSRF cookie in cookie mode
+fn issue_session(state: &AppState, status: StatusCode, session: SessionResponse) -> Response {
+    if !state.session_cookies.cookie_mode() {
+        let response = LoginResponse {
+            token: session.token,
+            expires_at: session.expires_at,
+            refresh_token: session.refresh_token,
+        };
+        
+        return (status, Json(response)).into_response();
+    }
+    
+    let csrf_token = csrf::generate_csrf_token();
+    let cookies = state.session_cookies.session_cookies(
+        &session.token,
+        session.refresh_token.as_deref(),
+        &csrf_token,
+        session.expires_at,
+    );
+    
+    let response = (status, Json(json!({
+        "expires_at": session.expires_at
+    }))).into_response();
+    
+    csrf::with_cookies(response, cookies)
+}
+
+// POST /api/auth/register
+// The account cannot log in until its email address is verified
+pub async fn register(
+    State(state): State<AppState>,
+    ValidatedJson(req): ValidatedJson<UserRegistrationRequest>,
+) -> impl IntoResponse {
+    if let Err(errors) = req.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Validation failed",
+                "details": errors.to_string()
+            }))
+        ).into_response();
+    }
+    
+    // Hash before taking the auth lock, on the blocking pool, so other
+    // requests needing AuthService aren't held up behind argon2
+    let passwords = state.auth.lock().await.password_config();
+    let password_hash = match passwords.hash_password_async(&req.password).await {
+        Ok(hash) => hash,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Registration failed: {}", e)
+                }))
+            ).into_response();
+        }
+    };
+    
+    match state.auth.lock().await.register(&state.db, &state.mail, &req, password_hash).await {
+        Ok(user_id) => {
+            (
+                StatusCode::CREATED,
+                Json(json!({
+                    "user_id": user_id,
+                    "verification_required": true
+                }))
+            ).into_response()
+        }
+        Err(e @ (RegistrationError::UsernameTaken | RegistrationError::EmailTaken)) => {
+            (
+                StatusCode::CONFLICT,
+                Json(json!({
+                    "error": e.to_string()
+                }))
+            ).into_response()
+        }
+        Err(RegistrationError::Other(e)) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Registration failed: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// GET /api/auth/verify?token=
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Query(params): Query<VerifyEmailParams>,
+) -> impl IntoResponse {
+    match state.auth.lock().await.verify_email(&state.db, &params.token).await {
+        Ok(true) => {
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "verified": true
+                }))
+            ).into_response()
+        }
+        Ok(false) => {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Verification link is invalid or has expired"
+                }))
+            ).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Verification failed: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
 // T038: POST /api/auth/login
 pub async fn login(
     State(state): State<AppState>,
     ValidatedJson(req): ValidatedJson<LoginRequest>,
 ) -> impl IntoResponse {
+    let device = match req.device.as_ref().map(DeviceInfo::normalized).transpose() {
+        Ok(device) => device,
+        Err(message) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": message
+                }))
+            ).into_response();
+        }
+    };
+    
+    // Local accounts are checked against their stored argon2 hash first
+    let local_user = state.db.get_user_by_email(&req.email).await.unwrap_or(None);
+    // Verified before taking the auth lock, on the blocking pool, so other
+    // requests needing AuthService aren't held up behind argon2
+    let passwords = state.auth.lock().await.password_config();
+    let password_check = match &local_user {
+        Some(user) => Some(passwords.verify_password_async(&req.password, &user.password_hash).await),
+        None => None,
+    };
+    
     let mut auth = state.auth.lock().await;
     
-    match auth.login(&req.email, &req.password).await {
-        Ok(session_response) => {
-            let response = LoginResponse {
-                token: session_response.token,
-                expires_at: session_response.expires_at,
-                refresh_token: session_response.refresh_token,
-            };
-            
-            (StatusCode::OK, Json(response)).into_response()
+    let result = match local_user.zip(password_check) {
+        Some((user, password_check)) => match password_check {
+            Ok(true) if !user.email_verified => {
+                return (
+                    StatusCode::FORBIDDEN,
+                    Json(json!({
+                        "error": "Email address not verified"
+                    }))
+                ).into_response();
+            }
+            Ok(true) => auth.create_session_with_role(&user.id.to_string(), String::new(), user.is_admin).await,
+            Ok(false) => Err(anyhow::anyhow!("Invalid email or password")),
+            Err(e) => {
+                tracing::warn!("Stored password hash for {} is unusable: {}", user.email, e);
+                Err(anyhow::anyhow!("Invalid email or password"))
+            }
+        },
+        None => auth.login(&req.email, &req.password).await,
+    };
+    
+    match result {
+        Ok(session) => {
+            // The login stands even if the device can't be recorded
+            if let Some(device) = device {
+                if let Err(e) = register_device(&state, &mut auth, &session.token, device).await {
+                    tracing::warn!("Failed to register device for {}: {}", req.email, e);
+                }
+            }
+            issue_session(&state, StatusCode::OK, session)
         }
         Err(e) => {
             (
@@ -60,33 +236,42 @@ pub async fn logout(
 ) -> impl IntoResponse {
     let mut auth = state.auth.lock().await;
     
-    // Extract token from Authorization header
+    // Extract token from Authorization header, or the session cookie in cookie mode
     let token = match headers.get("authorization") {
         Some(value) => {
             let value_str = value.to_str().unwrap_or("");
-            if value_str.starts_with("Bearer ") {
-                &value_str[7..]
+            if let Some(token) = value_str.strip_prefix("Bearer ") {
+                token.to_string()
             } else {
                 return (StatusCode::UNAUTHORIZED, Json(json!({
                     "error": "Invalid authorization header"
                 }))).into_response();
             }
         }
-        None => {
-            return (StatusCode::UNAUTHORIZED, Json(json!({
-                "error": "Missing authorization header"
-            }))).into_response();
-        }
+        None => match cookie_value(&headers, SESSION_COOKIE) {
+            Some(token) if state.session_cookies.cookie_mode() => token,
+            _ => {
+                return (StatusCode::UNAUTHORIZED, Json(json!({
+                    "error": "Missing authorization header"
+                }))).into_response();
+            }
+        },
     };
     
-    match auth.logout(token).await {
+    match auth.logout(&token).await {
         Ok(_) => {
-            (
+            let response = (
                 StatusCode::OK,
                 Json(json!({
                     "message": "Logged out successfully"
                 }))
-            ).into_response()
+            ).into_response();
+            
+            if state.session_cookies.cookie_mode() {
+                csrf::with_cookies(response, state.session_cookies.clear_cookies())
+            } else {
+                response
+            }
         }
         Err(e) => {
             (
@@ -101,27 +286,47 @@ pub async fn logout(
 
 #[derive(Debug, Deserialize)]
 pub struct RefreshRequest {
-    refresh_token: String,
+    // Optional in cookie mode, where the refresh token arrives as a cookie
+    #[serde(default)]
+    refresh_token: Option<String>,
 }
 
 // T040: POST /api/auth/refresh
 pub async fn refresh(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<RefreshRequest>,
 ) -> impl IntoResponse {
+    let cookie_token = if state.session_cookies.cookie_mode() {
+        cookie_value(&headers, REFRESH_COOKIE)
+    } else {
+        None
+    };
+    
+    let Some(refresh_token) = req.refresh_token.or(cookie_token) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Missing refresh token"
+            }))
+        ).into_response();
+    };
+    
     let mut auth = state.auth.lock().await;
     
-    match auth.refresh_session(&req.refresh_token).await {
-        Ok(session_response) => {
-            let response = LoginResponse {
-                token: session_response.token,
-                expires_at: session_response.expires_at,
-                refresh_token: session_response.refresh_token,
-            };
-            
-            (StatusCode::OK, Json(response)).into_response()
-        }
+    match auth.refresh_session(&refresh_token).await {
+        Ok(session) => issue_session(&state, StatusCode::OK, session),
         Err(e) => {
+            if let Some(RefreshTokenError::ReuseDetected) = e.downcast_ref::<RefreshTokenError>() {
+                return (
+                    StatusCode::UNAUTHORIZED,
+                    Json(json!({
+                        "error": "Refresh token reuse detected; please log in again",
+                        "code": "TOKEN_REUSE_DETECTED"
+                    }))
+                ).into_response();
+            }
+            
             (
                 StatusCode::UNAUTHORIZED,
                 Json(json!({
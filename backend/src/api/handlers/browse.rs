@@ -2,49 +2,401 @@
 // Reference: contracts/openapi.yaml lines 79-117
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, RawQuery, State},
     http::StatusCode,
     Json,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::api::deprecation::list_response;
+use crate::api::handlers::anime::{parse_cursor, AnimePage};
 use crate::db::connection::AppState;
+use crate::middleware::error::ValidationError;
+use crate::middleware::response_cache::SEASON_BROWSE_CACHE_TTL;
+use crate::middleware::Locale;
+use crate::models::anime::{ANIME_STATUS_NAMES, ANIME_TYPE_NAMES};
+use crate::models::{AnimeSummary, Season};
+use crate::services::search::BROWSE_SORT_FIELDS;
+use crate::services::{BrowseSort, CacheService, SeasonFilter};
 
-pub async fn browse_season(
-    Path((year, season)): Path<(u16, String)>,
-    State(state): State<AppState>,
-) -> impl IntoResponse {
-    // Validate season
+/// Filters shared by the season and year listings; the year listing has no paging
+#[derive(Debug, Deserialize)]
+pub struct BrowseParams {
+    #[serde(rename = "type")]
+    anime_type: Option<String>,
+    status: Option<String>,
+    /// `field[:asc|desc]`, see `BrowseSort`
+    sort: Option<String>,
+    /// 1-based page of `limit` items in `sort` order
+    page: Option<usize>,
+    /// Without `page`, either of these switches to cursor paging in
+    /// (created_at, id) order; with neither the whole season is returned
+    limit: Option<usize>,
+    cursor: Option<String>,
+}
+
+const MAX_BROWSE_LIMIT: usize = 100;
+const DEFAULT_BROWSE_LIMIT: usize = 20;
+
+/// Repeated `?tag=` values; `Query` only keeps single-valued parameters
+fn tag_params(query: Option<&str>) -> Vec<String> {
+    url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+        .filter(|(key, value)| key == "tag" && !value.trim().is_empty())
+        .map(|(_, value)| value.trim().to_string())
+        .collect()
+}
+
+fn parse_param<T: std::str::FromStr<Err = String>>(
+    field: &str,
+    value: Option<&str>,
+    accepted: &[&str],
+    errors: &mut Vec<ValidationError>,
+) -> Option<T> {
+    match value?.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            errors.push(ValidationError {
+                field: field.to_string(),
+                message: format!("{}. Accepted values: {}", e, accepted.join(", ")),
+            });
+            None
+        }
+    }
+}
+
+/// The filter and sort requested, or a 400 listing every invalid value
+fn parse_filter(params: &BrowseParams, raw_query: Option<&str>) -> Result<(SeasonFilter, Option<BrowseSort>), Response> {
+    let mut errors = Vec::new();
+    let filter = SeasonFilter {
+        anime_type: parse_param("type", params.anime_type.as_deref(), ANIME_TYPE_NAMES, &mut errors),
+        status: parse_param("status", params.status.as_deref(), ANIME_STATUS_NAMES, &mut errors),
+        tags: tag_params(raw_query),
+    };
+    let sort = parse_param("sort", params.sort.as_deref(), BROWSE_SORT_FIELDS, &mut errors);
+    
+    if !errors.is_empty() {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Invalid browse parameters",
+                "details": { "errors": errors }
+            }))
+        ).into_response());
+    }
+    Ok((filter, sort))
+}
+
+/// 400 unless `season` is one of the four broadcast seasons
+fn validate_season(season: &str) -> Result<(), Response> {
     let valid_seasons = ["spring", "summer", "fall", "winter"];
     if !valid_seasons.contains(&season.to_lowercase().as_str()) {
-        return (
+        return Err((
             StatusCode::BAD_REQUEST,
             Json(json!({
                 "error": "Invalid season. Must be one of: spring, summer, fall, winter"
             }))
-        ).into_response();
+        ).into_response());
+    }
+    Ok(())
+}
+
+fn browse_failed(e: anyhow::Error) -> Response {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({
+            "error": format!("Failed to browse season: {}", e)
+        }))
+    ).into_response()
+}
+
+pub async fn browse_season(
+    Path((year, season)): Path<(u16, String)>,
+    Query(params): Query<BrowseParams>,
+    RawQuery(raw_query): RawQuery,
+    State(state): State<AppState>,
+    locale: Locale,
+) -> impl IntoResponse {
+    if let Err(response) = validate_season(&season) {
+        return response;
     }
     
-    // Search by season
-    match state.search.search_by_season(year, &season).await {
-        Ok(results) => {
-            (
-                StatusCode::OK,
+    let (filter, sort) = match parse_filter(&params, raw_query.as_deref()) {
+        Ok(parsed) => parsed,
+        Err(response) => return response,
+    };
+    let limit = params.limit.unwrap_or(DEFAULT_BROWSE_LIMIT).clamp(1, MAX_BROWSE_LIMIT);
+    
+    if params.page.is_none() && (params.limit.is_some() || params.cursor.is_some()) {
+        if sort.is_some_and(|sort| sort != BrowseSort::default()) {
+            return (
+                StatusCode::BAD_REQUEST,
                 Json(json!({
-                    "year": year,
-                    "season": season,
-                    "anime": results,
-                    "total": results.len()
+                    "error": "Cursor pagination only supports the default sort"
                 }))
-            ).into_response()
+            ).into_response();
         }
-        Err(e) => {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "error": format!("Failed to browse season: {}", e)
-                }))
-            ).into_response()
+        let cursor = match parse_cursor(params.cursor.as_deref()) {
+            Ok(cursor) => cursor,
+            Err(response) => return response,
+        };
+        
+        let rows = match state.db.get_seasonal_anime_after(year, &season, &filter, cursor, limit + 1).await {
+            Ok(rows) => rows,
+            Err(e) => return browse_failed(e),
+        };
+        let total = match state.db.count_seasonal_anime(year, &season, &filter).await {
+            Ok(total) => total,
+            Err(e) => return browse_failed(e),
+        };
+        
+        let page = AnimePage::from_rows(rows, limit);
+        return list_response(&state.legacy_fields, "anime", json!({
+            "year": year,
+            "season": season,
+            "season_display": locale.season_display(&season, year),
+            "total": total,
+            "items": page.items,
+            "next_cursor": page.next_cursor,
+            "has_more": page.has_more
+        }));
+    }
+    
+    let page = params.page.map(|page| (limit, (page.max(1) - 1) * limit));
+    let sort = sort.unwrap_or_default();
+    
+    // Cached apart from the response cache so every language and API version
+    // shares one database read per page
+    let listing = format!(
+        "{}:{}:{}",
+        filter.cache_key(),
+        sort.cache_key(),
+        page.map_or("all".to_string(), |(limit, offset)| format!("{}+{}", offset, limit)),
+    );
+    let cache = state.cache.lock().await.handle();
+    let loaded = cache
+        .get_or_set(&CacheService::season_browse_key(year, &season, &listing), SEASON_BROWSE_CACHE_TTL, || {
+            state.search.search_by_season(year, &season, &filter, sort, page)
+        })
+        .await;
+    
+    match loaded {
+        Ok((results, total)) => {
+            let shown = page.map_or(0, |(_, offset)| offset) + results.len();
+            list_response(&state.legacy_fields, "anime", json!({
+                "year": year,
+                "season": season,
+                "season_display": locale.season_display(&season, year),
+                "total": total,
+                "items": results,
+                "next_cursor": null,
+                "has_more": shown < total
+            }))
+        }
+        Err(e) => browse_failed(e),
+    }
+}
+
+/// Per-season values of one year, serialized in broadcast order
+#[derive(Debug, Default, Serialize)]
+pub struct SeasonGroups<T> {
+    pub winter: T,
+    pub spring: T,
+    pub summer: T,
+    pub fall: T,
+}
+
+impl<T> SeasonGroups<T> {
+    fn get_mut(&mut self, season: &Season) -> &mut T {
+        match season {
+            Season::Winter => &mut self.winter,
+            Season::Spring => &mut self.spring,
+            Season::Summer => &mut self.summer,
+            Season::Fall => &mut self.fall,
+        }
+    }
+    
+    fn map<U>(&self, f: impl Fn(&T) -> U) -> SeasonGroups<U> {
+        SeasonGroups {
+            winter: f(&self.winter),
+            spring: f(&self.spring),
+            summer: f(&self.summer),
+            fall: f(&self.fall),
+        }
+    }
+}
+
+// GET /api/browse/year/{year} handler
+// A whole year grouped by season, with the seasonal listing's filters and sort
+pub async fn browse_year(
+    Path(year): Path<u16>,
+    Query(params): Query<BrowseParams>,
+    RawQuery(raw_query): RawQuery,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let (filter, sort) = match parse_filter(&params, raw_query.as_deref()) {
+        Ok(parsed) => parsed,
+        Err(response) => return response,
+    };
+    
+    let anime = match state.db.get_year_anime(year, &filter, sort.unwrap_or_default()).await {
+        Ok(anime) => anime,
+        Err(e) => return browse_failed(e),
+    };
+    
+    let total = anime.len();
+    let mut seasons: SeasonGroups<Vec<AnimeSummary>> = SeasonGroups::default();
+    for anime in anime {
+        seasons.get_mut(&anime.anime_season.season).push(AnimeSummary::from(anime));
+    }
+    
+    let totals = seasons.map(Vec::len);
+    
+    (StatusCode::OK, Json(json!({
+        "year": year,
+        "seasons": seasons,
+        "totals": totals,
+        "total": total
+    }))).into_response()
+}
+
+/// One title on a season's airing calendar, placed by its episodes' air dates
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CalendarEntry {
+    pub anime_id: Uuid,
+    pub title: String,
+    /// Lowercase English weekday of the first episode, e.g. "monday"
+    pub weekday: &'static str,
+    /// Broadcast time as "HH:MM"; not tracked yet, so always null
+    pub air_time: Option<String>,
+    pub first_air_date: NaiveDate,
+    /// Air date of the final episode, once every episode has one
+    pub last_air_date: Option<NaiveDate>,
+    pub episodes: u32,
+}
+
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "monday",
+        Weekday::Tue => "tuesday",
+        Weekday::Wed => "wednesday",
+        Weekday::Thu => "thursday",
+        Weekday::Fri => "friday",
+        Weekday::Sat => "saturday",
+        Weekday::Sun => "sunday",
+    }
+}
+
+/// Calendar entries for `anime` in first-airing order; titles without a dated
+/// episode can't be placed and are left out. `air_dates` are earliest first.
+pub fn calendar_entries(anime: Vec<AnimeSummary>, air_dates: &HashMap<Uuid, Vec<NaiveDate>>) -> Vec<CalendarEntry> {
+    let mut entries: Vec<CalendarEntry> = anime
+        .into_iter()
+        .filter_map(|anime| {
+            let dates = air_dates.get(&anime.id)?;
+            let first_air_date = *dates.first()?;
+            let fully_dated = anime.episodes > 0 && dates.len() >= anime.episodes as usize;
+            Some(CalendarEntry {
+                anime_id: anime.id,
+                title: anime.title,
+                weekday: weekday_name(first_air_date.weekday()),
+                air_time: None,
+                first_air_date,
+                last_air_date: fully_dated.then(|| dates.last().copied()).flatten(),
+                episodes: anime.episodes,
+            })
+        })
+        .collect();
+    
+    entries.sort_by(|a, b| a.first_air_date.cmp(&b.first_air_date).then_with(|| a.title.cmp(&b.title)));
+    entries
+}
+
+// GET /api/browse/season/{year}/{season}/calendar handler
+// Every title of the season with the weekday it airs on, for the season planner
+pub async fn season_calendar(
+    Path((year, season)): Path<(u16, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    if let Err(response) = validate_season(&season) {
+        return response;
+    }
+    
+    let anime = match state.db
+        .get_seasonal_anime(year, &season, &SeasonFilter::default(), BrowseSort::default(), None, 0)
+        .await
+    {
+        Ok(anime) => anime,
+        Err(e) => return browse_failed(e),
+    };
+    let anime_ids: Vec<Uuid> = anime.iter().map(|anime| anime.id).collect();
+    let air_dates = match state.db.get_episode_air_dates(&anime_ids).await {
+        Ok(air_dates) => air_dates,
+        Err(e) => return browse_failed(e),
+    };
+    
+    (StatusCode::OK, Json(json!({
+        "year": year,
+        "season": season.to_lowercase(),
+        "entries": calendar_entries(anime, &air_dates)
+    }))).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AnimeStatus, AnimeType};
+    
+    fn summary(title: &str, episodes: u32) -> AnimeSummary {
+        AnimeSummary {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            poster_url: String::new(),
+            episodes,
+            status: AnimeStatus::Ongoing,
+            anime_type: AnimeType::TV,
+            imdb_rating: None,
         }
     }
-}
\ No newline at end of file
+    
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+    
+    #[test]
+    fn test_calendar_entries_from_air_dates() {
+        let (airing, finished, undated) = (summary("Airing", 12), summary("Finished", 2), summary("Undated", 12));
+        let air_dates = HashMap::from([
+            (airing.id, vec![date(4, 6), date(4, 13)]),
+            (finished.id, vec![date(4, 2), date(4, 9)]),
+        ]);
+        
+        let (airing_id, finished_id) = (airing.id, finished.id);
+        
+        let entries = calendar_entries(vec![airing, finished, undated], &air_dates);
+        
+        assert_eq!(entries, vec![
+            CalendarEntry {
+                anime_id: finished_id,
+                title: "Finished".to_string(),
+                weekday: "tuesday",
+                air_time: None,
+                first_air_date: date(4, 2),
+                last_air_date: Some(date(4, 9)),
+                episodes: 2,
+            },
+            CalendarEntry {
+                anime_id: airing_id,
+                title: "Airing".to_string(),
+                weekday: "saturday",
+                air_time: None,
+                first_air_date: date(4, 6),
+                last_air_date: None,
+                episodes: 12,
+            },
+        ]);
+    }
+}
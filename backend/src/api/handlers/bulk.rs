@@ -0,0 +1,224 @@
+// POST /api/anime/bulk handler
+// Admin-only ingest of anime-offline-database entries, sent either as a JSON
+// array of entries or as a multipart upload of the raw anime-offline-database.json
+
+use axum::{
+    body::Bytes,
+    extract::{FromRequest, Multipart, Request, State},
+    http::{header::CONTENT_TYPE, StatusCode},
+    Json,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use validator::Validate;
+use crate::db::connection::AppState;
+use crate::middleware::AdminUser;
+use crate::middleware::response_cache::invalidate_catalog_responses;
+use crate::models::anime_offline_db::{AnimeOfflineEntry, ScoreRange};
+use crate::services::DatabaseService;
+
+/// Entries converted and written per database round trip
+const BULK_IMPORT_BATCH_SIZE: usize = 500;
+
+/// Default body limit for bulk uploads, see `RequestSizeLimitConfig`; the full
+/// offline database is tens of megabytes
+pub const BULK_IMPORT_BODY_LIMIT: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, Default, Serialize)]
+pub struct BulkImportReport {
+    pub imported: usize,
+    /// Entries whose sources already belong to a stored (or earlier uploaded) anime
+    pub skipped: usize,
+    pub errors: Vec<String>,
+}
+
+/// Accepted upload shapes: the raw database file, or just its `data` array
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum BulkUpload {
+    Database {
+        #[serde(rename = "scoreRange", default)]
+        score_range: ScoreRange,
+        data: Vec<serde_json::Value>,
+    },
+    Entries(Vec<serde_json::Value>),
+}
+
+impl BulkUpload {
+    /// Entries with the range their scores are on; bare arrays use the published range
+    fn into_entries(self) -> (ScoreRange, Vec<serde_json::Value>) {
+        match self {
+            BulkUpload::Database { score_range, data } => (score_range, data),
+            BulkUpload::Entries(entries) => (ScoreRange::default(), entries),
+        }
+    }
+}
+
+pub async fn bulk_import_anime(
+    State(state): State<AppState>,
+    admin: AdminUser,
+    request: Request,
+) -> impl IntoResponse {
+    tracing::info!("Bulk anime import started by {}", admin.session.user_id);
+
+    let is_multipart = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("multipart/form-data"))
+        .unwrap_or(false);
+
+    let body = if is_multipart {
+        read_multipart_file(request, &state).await
+    } else {
+        Bytes::from_request(request, &state)
+            .await
+            .map_err(|e| read_error(e.status(), format!("Failed to read request body: {}", e)))
+    };
+
+    let body = match body {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+
+    let (score_range, entries) = match serde_json::from_slice::<BulkUpload>(&body) {
+        Ok(upload) => upload.into_entries(),
+        Err(e) => {
+            return bad_request(format!(
+                "Expected a JSON array of entries or an anime-offline-database document: {}",
+                e
+            ));
+        }
+    };
+
+    match import_entries(&state.db, &score_range, entries).await {
+        Ok(report) => {
+            tracing::info!(
+                "Bulk anime import finished: {} imported, {} skipped, {} errors",
+                report.imported,
+                report.skipped,
+                report.errors.len()
+            );
+            if report.imported > 0 {
+                invalidate_catalog_responses(&state).await;
+            }
+            (StatusCode::OK, Json(report)).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Bulk import failed: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+/// Read the first file field of a multipart upload chunk by chunk
+async fn read_multipart_file(request: Request, state: &AppState) -> Result<Bytes, Response> {
+    let mut multipart = Multipart::from_request(request, state)
+        .await
+        .map_err(|e| bad_request(format!("Invalid multipart upload: {}", e)))?;
+
+    let mut field = multipart
+        .next_field()
+        .await
+        .map_err(|e| bad_request(format!("Invalid multipart upload: {}", e)))?
+        .ok_or_else(|| bad_request("Multipart upload contains no file".to_string()))?;
+
+    let mut contents = Vec::new();
+    while let Some(chunk) = field
+        .chunk()
+        .await
+        .map_err(|e| read_error(e.status(), format!("Failed to read uploaded file: {}", e)))?
+    {
+        contents.extend_from_slice(&chunk);
+    }
+
+    Ok(Bytes::from(contents))
+}
+
+/// Convert and store entries in batches, skipping any whose sources are already known
+async fn import_entries(
+    db: &DatabaseService,
+    score_range: &ScoreRange,
+    entries: Vec<serde_json::Value>,
+) -> anyhow::Result<BulkImportReport> {
+    let mut report = BulkImportReport::default();
+    let mut seen_sources = HashSet::new();
+
+    for (batch_index, batch) in entries.chunks(BULK_IMPORT_BATCH_SIZE).enumerate() {
+        let offset = batch_index * BULK_IMPORT_BATCH_SIZE;
+        let mut parsed = Vec::with_capacity(batch.len());
+
+        for (i, value) in batch.iter().enumerate() {
+            let entry = match AnimeOfflineEntry::deserialize(value) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    report.errors.push(format!("Entry {}: {}", offset + i, e));
+                    continue;
+                }
+            };
+
+            if let Err(e) = entry.validate() {
+                report.errors.push(format!("Entry {} ('{}'): {}", offset + i, entry.title, e));
+                continue;
+            }
+
+            parsed.push(entry);
+        }
+
+        let batch_sources: Vec<String> = parsed
+            .iter()
+            .flat_map(|entry| entry.sources.iter().cloned())
+            .collect();
+        seen_sources.extend(db.find_existing_sources(batch_sources).await?);
+
+        let mut anime_list = Vec::with_capacity(parsed.len());
+        for entry in parsed {
+            if entry.sources.iter().any(|source| seen_sources.contains(source)) {
+                report.skipped += 1;
+                continue;
+            }
+
+            seen_sources.extend(entry.sources.iter().cloned());
+            anime_list.push(entry.to_anime_model(score_range));
+        }
+
+        let attempted = anime_list.len();
+        let created = db.batch_create_anime(anime_list).await?;
+        report.imported += created;
+
+        if created < attempted {
+            report.errors.push(format!(
+                "{} of {} entries starting at {} failed to insert",
+                attempted - created,
+                attempted,
+                offset
+            ));
+        }
+    }
+
+    Ok(report)
+}
+
+/// 413 when the upload ran over the size limit, 400 otherwise
+fn read_error(status: StatusCode, message: String) -> Response {
+    let status = match status {
+        StatusCode::PAYLOAD_TOO_LARGE => status,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    (status, Json(json!({ "error": message }))).into_response()
+}
+
+fn bad_request(message: String) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({
+            "error": message
+        }))
+    ).into_response()
+}
@@ -0,0 +1,110 @@
+// Catalog change feed for partners mirroring the catalog
+// Consumers bootstrap from a full dump, then poll with `since` set to the last
+// `latest_change` they saw, following `next_cursor` until `has_more` is false.
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+use crate::db::connection::AppState;
+use crate::middleware::ApiKey;
+use crate::middleware::api_key::CATALOG_READ_SCOPE;
+use crate::models::catalog::DELETION_RETENTION_DAYS;
+use crate::models::{CatalogChange, ChangeCursor};
+
+const MAX_CHANGES_LIMIT: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct CatalogChangesParams {
+    since: DateTime<Utc>,
+    cursor: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+fn default_limit() -> usize {
+    100
+}
+
+#[derive(Debug, Serialize)]
+pub struct CatalogChangesResponse {
+    pub items: Vec<CatalogChange>,
+    /// Pass back as `cursor` (with the same `since`) for the next page
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+    /// Timestamp of the newest change returned so far; once `has_more` is
+    /// false, poll again with this as `since`
+    pub latest_change: DateTime<Utc>,
+}
+
+// GET /api/catalog/changes?since=<timestamp>
+pub async fn get_catalog_changes(
+    Query(params): Query<CatalogChangesParams>,
+    State(state): State<AppState>,
+    api_key: ApiKey,
+) -> impl IntoResponse {
+    if let Err(e) = api_key.require(CATALOG_READ_SCOPE) {
+        return e.into_response();
+    }
+
+    if params.since < Utc::now() - chrono::Duration::days(DELETION_RETENTION_DAYS) {
+        return (
+            StatusCode::GONE,
+            Json(json!({
+                "error": format!(
+                    "Changes are only retained for {} days; resync from a full catalog dump",
+                    DELETION_RETENTION_DAYS
+                )
+            }))
+        ).into_response();
+    }
+
+    // Without a cursor, start strictly after `since`: no id sorts above the max UUID
+    let position = match params.cursor.as_deref().map(str::parse::<ChangeCursor>) {
+        Some(Ok(cursor)) => cursor,
+        Some(Err(_)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid cursor"
+                }))
+            ).into_response();
+        }
+        None => ChangeCursor { updated_at: params.since, id: Uuid::max() },
+    };
+
+    let limit = params.limit.clamp(1, MAX_CHANGES_LIMIT);
+
+    // One extra row of each kind tells us whether another page exists
+    let (anime, tombstones) = match state.db.get_catalog_changes(position, limit + 1).await {
+        Ok(changes) => changes,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch catalog changes: {}", e)
+                }))
+            ).into_response();
+        }
+    };
+
+    let mut items = CatalogChange::merge(params.since, anime, tombstones, limit + 1);
+    let has_more = items.len() > limit;
+    items.truncate(limit);
+
+    let last = items.last().map(CatalogChange::cursor);
+    let response = CatalogChangesResponse {
+        next_cursor: last.filter(|_| has_more).map(|cursor| cursor.to_string()),
+        has_more,
+        latest_change: last.map_or(position.updated_at, |cursor| cursor.updated_at),
+        items,
+    };
+
+    (StatusCode::OK, Json(response)).into_response()
+}
@@ -0,0 +1,169 @@
+// Device registry: the named clients a user is signed in on, with their
+// sessions, and revocation of everything running on one of them
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use serde_json::json;
+use uuid::Uuid;
+use crate::db::connection::AppState;
+use crate::middleware::AuthUser;
+use crate::models::{Device, DeviceInfo};
+use crate::services::AuthService;
+
+/// Link the session behind a freshly issued access token to the user's device
+/// described by `info`, creating the device on its first sign-in
+pub(crate) async fn register_device(
+    state: &AppState,
+    auth: &mut AuthService,
+    token: &str,
+    info: DeviceInfo,
+) -> anyhow::Result<Device> {
+    state.db.prune_stale_devices().await?;
+
+    let session = auth.verify_session(token).await?;
+    let mut device = match state.db.find_user_device(&session.user_id, &info).await? {
+        Some(device) => device,
+        None => Device::new(&session.user_id, info.clone()),
+    };
+    device.session_ids = auth.live_sessions(&device.session_ids).await?;
+    device.session_ids.push(session.id);
+    device.client_version = info.client_version;
+
+    let device = state.db.save_device(&device).await?;
+    auth.attach_device(token, device.id).await?;
+    Ok(device)
+}
+
+// GET /api/auth/devices
+// The user's devices with their signed-in session counts, most recently seen first
+pub async fn list_devices(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> impl IntoResponse {
+    let devices = match state.db.get_user_devices(&auth.session.user_id).await {
+        Ok(devices) => devices,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to list devices: {}", e)
+                }))
+            ).into_response();
+        }
+    };
+
+    let mut auth_service = state.auth.lock().await;
+    let mut items = Vec::with_capacity(devices.len());
+    for device in devices {
+        let sessions = match auth_service.live_sessions(&device.session_ids).await {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "error": format!("Failed to list devices: {}", e)
+                    }))
+                ).into_response();
+            }
+        };
+
+        items.push(json!({
+            "id": device.id,
+            "name": device.name,
+            "platform": device.platform,
+            "client_version": device.client_version,
+            "created_at": device.created_at,
+            "last_seen_at": device.last_seen_at,
+            "sessions": sessions,
+            "current": auth.session.device_id == Some(device.id)
+        }));
+    }
+
+    (StatusCode::OK, Json(json!({ "devices": items }))).into_response()
+}
+
+// DELETE /api/auth/devices/{id}
+// Signs the device out everywhere: ends its sessions (and their refresh
+// tokens), stops counting its streams, and forgets the device
+pub async fn revoke_device(
+    Path(device_id): Path<Uuid>,
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> impl IntoResponse {
+    let user_id = auth.session.user_id.as_str();
+    let device = match state.db.get_user_devices(user_id).await {
+        Ok(devices) => devices.into_iter().find(|device| device.id == device_id),
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to revoke device: {}", e)
+                }))
+            ).into_response();
+        }
+    };
+    let Some(device) = device else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "Device not found"
+            }))
+        ).into_response();
+    };
+
+    let revoked = {
+        let mut auth_service = state.auth.lock().await;
+        let result = async {
+            let live = auth_service.live_sessions(&device.session_ids).await?;
+            for &session_id in &device.session_ids {
+                auth_service.revoke_session(user_id, session_id).await?;
+            }
+            anyhow::Ok(live.len())
+        }.await;
+
+        match result {
+            Ok(revoked) => revoked,
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "error": format!("Failed to revoke device sessions: {}", e)
+                    }))
+                ).into_response();
+            }
+        }
+    };
+
+    let ended = match state.stream_sessions.end_device_streams(user_id, device.id).await {
+        Ok(ended) => ended,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to end device streams: {}", e)
+                }))
+            ).into_response();
+        }
+    };
+
+    // Forgotten last, so a failure above can be retried
+    if let Err(e) = state.db.delete_user_device(user_id, device.id).await {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({
+                "error": format!("Failed to revoke device: {}", e)
+            }))
+        ).into_response();
+    }
+
+    (StatusCode::OK, Json(json!({
+        "message": "Device revoked",
+        "device_id": device.id,
+        "revoked_sessions": revoked,
+        "ended_streams": ended
+    }))).into_response()
+}
@@ -2,33 +2,87 @@
 // Reference: contracts/openapi.yaml lines 119-143
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
     Json,
     response::IntoResponse,
 };
+use chrono::NaiveDate;
+use futures::TryStreamExt;
 use uuid::Uuid;
 use serde_json::json;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use validator::Validate;
+use crate::api::deprecation::list_response;
 use crate::db::connection::AppState;
-use crate::models::{Episode, EpisodeListResponse};
+use crate::middleware::json_extractor::ValidatedJson;
+use crate::middleware::{AppError, CatalogWriter, Locale};
+use crate::middleware::response_cache::invalidate_anime_detail_response;
+use crate::models::episode::validate_unique_episode_numbers;
+use crate::models::{
+    Episode, EpisodeBatchError, EpisodeBatchResult, EpisodeCreate, EpisodeItemError, EpisodeListResponse, EpisodeNumbering,
+    EpisodeResponse,
+};
+
+/// Episode response with display fields formatted for `locale`
+fn localized_episode(episode: Episode, locale: Locale) -> EpisodeResponse {
+    let mut response = EpisodeResponse::from(episode);
+    response.air_date_display = response.air_date.map(|date| locale.format_date(date));
+    response
+}
+
+/// Report the absolute number as `episode_number` where one is known
+fn with_numbering(mut response: EpisodeResponse, numbering: EpisodeNumbering) -> EpisodeResponse {
+    if numbering == EpisodeNumbering::Absolute {
+        response.episode_number = response.absolute_number.unwrap_or(response.episode_number);
+    }
+    response
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EpisodeListParams {
+    /// Air date window, inclusive; ISO dates
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    /// `season` (default) or `absolute`
+    #[serde(default)]
+    pub numbering: EpisodeNumbering,
+}
 
 pub async fn get_episodes(
     Path(anime_id): Path<Uuid>,
+    Query(params): Query<EpisodeListParams>,
     State(state): State<AppState>,
+    locale: Locale,
 ) -> impl IntoResponse {
+    if let (Some(from), Some(to)) = (params.from, params.to) {
+        if from > to {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "'from' must not be after 'to'"
+                }))
+            ).into_response();
+        }
+    }
+    
     // Check if anime exists
     match state.db.get_anime(anime_id).await {
         Ok(Some(_anime)) => {
             // Get episodes for this anime
-            match state.db.get_anime_episodes(anime_id).await {
+            match state.db.get_anime_episodes_in_range(anime_id, params.from, params.to).await {
                 Ok(episodes) => {
                     let response = EpisodeListResponse {
                         total: episodes.len(),
-                        episodes: episodes.into_iter().map(|e| e.into()).collect(),
+                        items: episodes
+                            .into_iter()
+                            .map(|e| with_numbering(localized_episode(e, locale), params.numbering))
+                            .collect(),
                     };
                     
-                    (StatusCode::OK, Json(response)).into_response()
+                    list_response(&state.legacy_fields, "episodes", response)
                 }
                 Err(e) => {
                     (
@@ -59,88 +113,262 @@ pub async fn get_episodes(
     }
 }
 
-// Request DTO for creating episodes
 #[derive(Debug, Deserialize)]
-pub struct CreateEpisodeRequest {
-    pub episode_number: u32,
-    pub title: Option<String>,
-    pub duration: Option<u32>,
-    pub air_date: Option<String>,
-    pub synopsis: Option<String>,
-    pub thumbnail_url: Option<String>,
+pub struct EpisodeStreamParams {
+    pub from: Option<u32>,
+    pub to: Option<u32>,
+}
+
+// GET /api/anime/{id}/episodes/stream handler
+// Streams episodes as NDJSON, one EpisodeResponse per line, ordered by number
+pub async fn stream_episodes(
+    Path(anime_id): Path<Uuid>,
+    Query(params): Query<EpisodeStreamParams>,
+    State(state): State<AppState>,
+    locale: Locale,
+) -> impl IntoResponse {
+    if let (Some(from), Some(to)) = (params.from, params.to) {
+        if from > to {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "'from' must not be greater than 'to'"
+                }))
+            ).into_response();
+        }
+    }
+    
+    match state.db.get_anime(anime_id).await {
+        Ok(Some(_anime)) => {
+            // Headers are already sent once streaming starts, so a failing page
+            // can only abort the body; clients see a truncated stream
+            let lines = state.db.clone()
+                .stream_anime_episodes(anime_id, params.from, params.to)
+                .and_then(move |episode| async move {
+                    let mut line = serde_json::to_vec(&localized_episode(episode, locale))?;
+                    line.push(b'\n');
+                    Ok(Bytes::from(line))
+                })
+                .inspect_err(|e| tracing::error!("Episode stream failed: {}", e));
+            
+            (
+                StatusCode::OK,
+                [(header::CONTENT_TYPE, "application/x-ndjson")],
+                Body::from_stream(lines),
+            ).into_response()
+        }
+        Ok(None) => {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Anime not found"
+                }))
+            ).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch anime: {}", e)
+                }))
+            ).into_response()
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 pub struct CreateEpisodesRequest {
-    pub episodes: Vec<CreateEpisodeRequest>,
+    pub episodes: Vec<EpisodeCreate>,
 }
 
 // POST /api/anime/{id}/episodes handler
+// Creates the whole batch in one transaction or nothing at all; the created
+// episodes are returned in request order
 pub async fn create_episodes(
     Path(anime_id): Path<Uuid>,
     State(state): State<AppState>,
+    _writer: CatalogWriter,
     Json(payload): Json<CreateEpisodesRequest>,
 ) -> impl IntoResponse {
-    // Check if anime exists
-    match state.db.get_anime(anime_id).await {
-        Ok(Some(_anime)) => {
-            let mut created_episodes = Vec::new();
-            let mut errors = Vec::new();
-            
-            for ep_request in payload.episodes {
-                let episode = Episode {
-                    id: Uuid::new_v4(),
-                    anime_id,
-                    episode_number: ep_request.episode_number,
-                    title: ep_request.title,
-                    duration: ep_request.duration,
-                    air_date: None, // Would need to parse from string
-                    synopsis: ep_request.synopsis,
-                    thumbnail_url: ep_request.thumbnail_url,
-                    created_at: chrono::Utc::now(),
-                    updated_at: chrono::Utc::now(),
-                };
-                
-                match state.db.create_episode(&episode).await {
-                    Ok(_) => created_episodes.push(episode),
-                    Err(e) => errors.push(format!("Episode {}: {}", ep_request.episode_number, e)),
-                }
+    let episodes = payload.episodes
+        .into_iter()
+        .map(|episode| episode.into_episode(anime_id))
+        .collect();
+    
+    match state.db.batch_create_episodes(anime_id, episodes).await {
+        Ok(episodes) => {
+            invalidate_episode_etags(&state, anime_id).await;
+            let ids: Vec<Uuid> = episodes.iter().map(|episode| episode.id).collect();
+            let episodes: Vec<EpisodeResponse> = episodes.into_iter().map(EpisodeResponse::from).collect();
+            (
+                StatusCode::CREATED,
+                Json(json!({
+                    "created": ids.len(),
+                    "ids": ids,
+                    "episodes": episodes
+                }))
+            ).into_response()
+        }
+        Err(e) => match (e.downcast_ref::<AppError>(), e.downcast_ref::<EpisodeBatchError>()) {
+            (Some(AppError::ValidationError(errors)), _) => {
+                let details: BTreeMap<&str, &str> = errors
+                    .iter()
+                    .map(|error| (error.field.as_str(), error.message.as_str()))
+                    .collect();
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    Json(json!({
+                        "error": "Validation failed",
+                        "details": details
+                    }))
+                ).into_response()
             }
-            
-            if errors.is_empty() {
+            (Some(AppError::NotFound(_)), _) => {
                 (
-                    StatusCode::CREATED,
+                    StatusCode::NOT_FOUND,
                     Json(json!({
-                        "created": created_episodes.len(),
-                        "episodes": created_episodes
+                        "error": "Anime not found"
                     }))
                 ).into_response()
-            } else {
+            }
+            (_, Some(EpisodeBatchError::Duplicate(details))) => {
                 (
-                    StatusCode::PARTIAL_CONTENT,
+                    StatusCode::CONFLICT,
                     Json(json!({
-                        "created": created_episodes.len(),
-                        "episodes": created_episodes,
-                        "errors": errors
+                        "error": "Duplicate episode numbers",
+                        "details": details
                     }))
                 ).into_response()
             }
-        }
+            _ => {
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "error": format!("Failed to create episodes: {}", e)
+                    }))
+                ).into_response()
+            }
+        },
+    }
+}
+
+/// The episode list and the detail's available count both change with new episodes
+pub(crate) async fn invalidate_episode_etags(state: &AppState, anime_id: Uuid) {
+    if let Err(e) = state.cache.lock().await.invalidate_etags(&anime_id.to_string()).await {
+        tracing::warn!("Failed to invalidate ETags for anime {}: {}", anime_id, e);
+    }
+    invalidate_anime_detail_response(state, anime_id).await;
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateEpisodesBatchRequest {
+    #[validate(
+        length(min = 1, message = "At least one episode is required"),
+        custom(function = "validate_unique_episode_numbers"),
+        nested
+    )]
+    pub episodes: Vec<EpisodeCreate>,
+}
+
+// POST /api/anime/{id}/episodes/batch handler
+// Unlike POST /episodes, episodes whose number is already taken are skipped and
+// reported rather than failing the batch: 201 when every episode was created,
+// 207 Multi-Status when only some were, 409 when none were. A malformed batch
+// (bad number or URL, numbers repeated within it) is still rejected whole.
+pub async fn create_episodes_batch(
+    Path(anime_id): Path<Uuid>,
+    State(state): State<AppState>,
+    _writer: CatalogWriter,
+    ValidatedJson(payload): ValidatedJson<CreateEpisodesBatchRequest>,
+) -> impl IntoResponse {
+    if let Err(errors) = payload.validate() {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({
+                "error": "Validation failed",
+                "details": errors
+            }))
+        ).into_response();
+    }
+    
+    let existing: HashSet<u32> = match state.db.get_anime(anime_id).await {
+        Ok(Some(_)) => match state.db.get_anime_episodes(anime_id).await {
+            Ok(episodes) => episodes.into_iter().map(|episode| episode.episode_number).collect(),
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "error": format!("Failed to fetch episodes: {}", e)
+                    }))
+                ).into_response();
+            }
+        },
         Ok(None) => {
-            (
+            return (
                 StatusCode::NOT_FOUND,
                 Json(json!({
                     "error": "Anime not found"
                 }))
-            ).into_response()
+            ).into_response();
         }
         Err(e) => {
-            (
+            return (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({
                     "error": format!("Failed to fetch anime: {}", e)
                 }))
-            ).into_response()
+            ).into_response();
+        }
+    };
+    
+    let mut errors = Vec::new();
+    let mut fresh = Vec::new();
+    for (index, episode) in payload.episodes.into_iter().enumerate() {
+        if existing.contains(&episode.episode_number) {
+            errors.push(EpisodeItemError {
+                index,
+                episode_number: episode.episode_number,
+                message: format!("Episode {} already exists", episode.episode_number),
+            });
+        } else {
+            fresh.push(episode);
         }
     }
-}
\ No newline at end of file
+    
+    // The remaining episodes go in together, in one transaction
+    let created = if fresh.is_empty() {
+        Vec::new()
+    } else {
+        let episodes = fresh.into_iter().map(|episode| episode.into_episode(anime_id)).collect();
+        match state.db.batch_create_episodes(anime_id, episodes).await {
+            Ok(episodes) => {
+                invalidate_episode_etags(&state, anime_id).await;
+                episodes.into_iter().map(EpisodeResponse::from).collect()
+            }
+            // Another writer took one of the numbers since we looked
+            Err(e) if matches!(e.downcast_ref::<EpisodeBatchError>(), Some(EpisodeBatchError::Duplicate(_))) => {
+                return (
+                    StatusCode::CONFLICT,
+                    Json(json!({
+                        "error": "Episode numbers changed during the batch; retry it"
+                    }))
+                ).into_response();
+            }
+            Err(e) => {
+                return (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(json!({
+                        "error": format!("Failed to create episodes: {}", e)
+                    }))
+                ).into_response();
+            }
+        }
+    };
+    
+    let status = match (created.is_empty(), errors.is_empty()) {
+        (_, true) => StatusCode::CREATED,
+        (false, false) => StatusCode::MULTI_STATUS,
+        (true, false) => StatusCode::CONFLICT,
+    };
+    (status, Json(EpisodeBatchResult { created, errors })).into_response()
+}
@@ -8,8 +8,7 @@ use axum::{
     Json,
 };
 use crate::db::connection::AppState;
-use crate::services::health::{HealthService, HealthStatus};
-use std::sync::Arc;
+use crate::services::health::HealthStatus;
 
 /// GET /health/live - Kubernetes liveness probe
 /// Returns 200 if the application is alive
@@ -75,10 +74,19 @@ pub async fn component_health(
     )
 }
 
+/// GET /health/metrics - Prometheus text format, alongside the JSON checks
+/// Same metrics as GET /metrics, for probes that only reach /api
+#[cfg(feature = "metrics")]
+pub async fn metrics_handler(
+    state: State<AppState>,
+) -> impl IntoResponse {
+    crate::api::handlers::metrics::get_metrics(state).await
+}
+
 #[cfg(test)]
 mod tests {
-    use super::*;
-    use crate::services::health::LivenessResponse;
+    
+    
 
     #[tokio::test]
     async fn test_liveness_endpoint() {
@@ -0,0 +1,44 @@
+// Prometheus scrape endpoint
+
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use crate::db::connection::AppState;
+
+/// Content type of the Prometheus text exposition format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+// GET /metrics and GET /api/health/metrics
+// Request metrics plus circuit breaker, component health, anime count and cache
+// connectivity gauges, refreshed on each scrape
+pub async fn get_metrics(
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let circuits = state.streaming.api_probe().circuit_states().await;
+    state.metrics.set_circuit_states(&circuits);
+    
+    match state.db.get_anime_count().await {
+        Ok(count) => state.metrics.set_anime_count(count),
+        Err(e) => tracing::warn!("Failed to count anime for metrics: {}", e),
+    }
+    
+    let mut cache = state.cache.lock().await.handle();
+    state.metrics.set_redis_connected(cache.exists("health:probe").await.is_ok());
+    
+    let health = state.health.check_health().await;
+    state.metrics.set_component_health(&health.checks);
+    
+    match state.metrics.render() {
+        Ok(body) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)],
+            body,
+        ).into_response(),
+        Err(e) => {
+            tracing::error!("Failed to encode metrics: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
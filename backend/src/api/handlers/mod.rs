@@ -1,8 +1,21 @@
+pub mod admin;
 pub mod anime;
 pub mod auth;
 pub mod browse;
+pub mod bulk;
+pub mod catalog;
+pub mod devices;
 pub mod episodes;
 pub mod health;
 pub mod logs;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod playback;
+pub mod preferences;
+pub mod recommendations;
 pub mod search;
-pub mod stream;
\ No newline at end of file
+pub mod stream;
+pub mod tags;
+pub mod watch;
+pub mod watch_history;
+pub mod watchlist;
\ No newline at end of file
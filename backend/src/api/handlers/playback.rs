@@ -0,0 +1,110 @@
+// Playback position endpoints for resuming episodes
+// Storage is handled by WatchProgressService: positions are cached while hot
+// and flushed to the database in the background.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+use crate::db::connection::AppState;
+use crate::middleware::AuthUser;
+use crate::middleware::json_extractor::ValidatedJson;
+use crate::models::PlaybackPosition;
+
+#[derive(Debug, Deserialize)]
+pub struct SavePositionRequest {
+    episode_id: Uuid,
+    /// Seconds into the episode
+    #[serde(alias = "position_secs")]
+    position: u32,
+    /// Episode length in seconds
+    #[serde(alias = "duration_secs")]
+    duration: u32,
+}
+
+// POST /api/user/playback-position
+pub async fn save_playback_position(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    ValidatedJson(req): ValidatedJson<SavePositionRequest>,
+) -> impl IntoResponse {
+    let episode = match state.db.get_episode(req.episode_id).await {
+        Ok(Some(episode)) => episode,
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Episode not found"
+                }))
+            ).into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch episode: {}", e)
+                }))
+            ).into_response();
+        }
+    };
+    
+    let position = PlaybackPosition::new(&episode, req.position, req.duration);
+    
+    match state.watch_progress.save_position(&auth.session.user_id, &position).await {
+        Ok(()) => (StatusCode::OK, Json(position)).into_response(),
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to save playback position: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// GET /api/user/playback-position/{episode_id}
+pub async fn get_playback_position(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(episode_id): Path<Uuid>,
+) -> impl IntoResponse {
+    position_response(state.watch_progress.get_position(&auth.session.user_id, episode_id).await)
+}
+
+// GET /api/user/playback-position/anime/{anime_id}
+// Most recently played episode of the anime, for "Continue watching"
+pub async fn get_latest_playback_position(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(anime_id): Path<Uuid>,
+) -> impl IntoResponse {
+    position_response(state.watch_progress.latest_for_anime(&auth.session.user_id, anime_id).await)
+}
+
+fn position_response(position: anyhow::Result<Option<PlaybackPosition>>) -> axum::response::Response {
+    match position {
+        Ok(Some(position)) => (StatusCode::OK, Json(position)).into_response(),
+        Ok(None) => {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "No saved playback position"
+                }))
+            ).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch playback position: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
@@ -0,0 +1,111 @@
+// User preferences endpoints
+// Preferences are stored per user in the database and cached; users who never
+// saved anything get the defaults.
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use serde_json::json;
+use std::time::Duration;
+use validator::Validate;
+use crate::db::connection::AppState;
+use crate::middleware::AuthUser;
+use crate::middleware::json_extractor::ValidatedJson;
+use crate::models::{PreferencesUpdate, UserPreferences};
+
+const PREFERENCES_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// The user's saved preferences, or the defaults if none were saved
+pub async fn load_preferences(state: &AppState, user_id: &str) -> anyhow::Result<UserPreferences> {
+    match state.cache.lock().await.get_preferences(user_id).await {
+        Ok(Some(preferences)) => return Ok(preferences),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to read cached preferences for {}: {}", user_id, e),
+    }
+
+    let preferences = state.db.get_user_preferences(user_id).await?;
+
+    if let Some(preferences) = &preferences {
+        if let Err(e) = state.cache.lock().await
+            .store_preferences(user_id, preferences, PREFERENCES_CACHE_TTL)
+            .await
+        {
+            tracing::warn!("Failed to cache preferences for {}: {}", user_id, e);
+        }
+    }
+
+    Ok(preferences.unwrap_or_default())
+}
+
+// GET /api/user/preferences
+pub async fn get_preferences(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> impl IntoResponse {
+    match load_preferences(&state, &auth.session.user_id).await {
+        Ok(preferences) => (StatusCode::OK, Json(preferences)).into_response(),
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch preferences: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// PUT /api/user/preferences
+// Partial update: fields missing from the body keep their current value
+pub async fn update_preferences(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    ValidatedJson(update): ValidatedJson<PreferencesUpdate>,
+) -> impl IntoResponse {
+    let user_id = &auth.session.user_id;
+
+    let mut preferences = match load_preferences(&state, user_id).await {
+        Ok(preferences) => preferences,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch preferences: {}", e)
+                }))
+            ).into_response();
+        }
+    };
+
+    preferences.merge(update);
+
+    if let Err(errors) = preferences.validate() {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({
+                "error": "Validation failed",
+                "details": errors.to_string()
+            }))
+        ).into_response();
+    }
+
+    if let Err(e) = state.db.save_user_preferences(user_id, &preferences).await {
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({
+                "error": format!("Failed to save preferences: {}", e)
+            }))
+        ).into_response();
+    }
+
+    if let Err(e) = state.cache.lock().await
+        .store_preferences(user_id, &preferences, PREFERENCES_CACHE_TTL)
+        .await
+    {
+        tracing::warn!("Failed to cache preferences for {}: {}", user_id, e);
+    }
+
+    (StatusCode::OK, Json(preferences)).into_response()
+}
@@ -0,0 +1,187 @@
+// Recommendation endpoints backed by the graph relationships in DatabaseService
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+use crate::db::connection::AppState;
+use crate::middleware::AuthUser;
+
+const MAX_LIMIT: usize = 50;
+
+#[derive(Debug, Deserialize)]
+pub struct RecommendationParams {
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+fn default_limit() -> usize {
+    10
+}
+
+impl RecommendationParams {
+    fn capped_limit(&self) -> usize {
+        self.limit.clamp(1, MAX_LIMIT)
+    }
+}
+
+// GET /api/recommendations
+pub async fn get_recommendations(
+    Query(params): Query<RecommendationParams>,
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> impl IntoResponse {
+    let limit = params.capped_limit();
+    
+    // Users without a UUID identity (e.g. Crunchyroll-only sessions) have no graph history
+    let user_id = Uuid::parse_str(&auth.session.user_id).ok();
+    
+    let recommendations = state.recommendations
+        .recommend_for_user(user_id, limit)
+        .await;
+    let fallback = recommendations.is_fallback(&state.recommendations.config().chain);
+    
+    (
+        StatusCode::OK,
+        Json(json!({
+            "recommendations": recommendations.items,
+            "total": recommendations.items.len(),
+            "source": recommendations.source.map(|s| s.as_str()),
+            "fallback": fallback
+        }))
+    ).into_response()
+}
+
+// GET /api/user/recommendations
+// "Because you watched" picks, each with the reason it was recommended
+pub async fn get_user_recommendations(
+    Query(params): Query<RecommendationParams>,
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> impl IntoResponse {
+    match state.recommendations.because_you_watched(&auth.session.user_id, params.capped_limit()).await {
+        Ok(recommendations) => (StatusCode::OK, Json(recommendations)).into_response(),
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch recommendations: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// GET /api/anime/{id}/similar
+pub async fn get_similar(
+    Path(id): Path<Uuid>,
+    Query(params): Query<RecommendationParams>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.db.get_anime(id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Anime not found"
+                }))
+            ).into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch anime: {}", e)
+                }))
+            ).into_response();
+        }
+    }
+    
+    match state.db.get_similar_anime(id, params.capped_limit()).await {
+        Ok(similar) => {
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "anime_id": id,
+                    "similar": similar,
+                    "total": similar.len()
+                }))
+            ).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch similar anime: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// GET /api/anime/{id}/recommendations
+// Content-based picks from the tag similarity rebuilt in the background
+pub async fn get_anime_recommendations(
+    Path(id): Path<Uuid>,
+    Query(params): Query<RecommendationParams>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.db.get_anime(id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Anime not found"
+                }))
+            ).into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch anime: {}", e)
+                }))
+            ).into_response();
+        }
+    }
+    
+    match state.recommendations.similar_to(id, params.capped_limit()).await {
+        Ok(recommendations) => {
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "anime_id": id,
+                    "recommendations": recommendations,
+                    "total": recommendations.len()
+                }))
+            ).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch recommendations: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_limit_is_capped() {
+        assert_eq!(RecommendationParams { limit: 500 }.capped_limit(), MAX_LIMIT);
+        assert_eq!(RecommendationParams { limit: 0 }.capped_limit(), 1);
+        assert_eq!(RecommendationParams { limit: default_limit() }.capped_limit(), 10);
+    }
+}
@@ -7,9 +7,17 @@ use axum::{
     Json,
     response::IntoResponse,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::time::Duration;
+use crate::api::deprecation::list_response;
 use crate::db::connection::AppState;
+use crate::models::AnimeSummary;
+use crate::services::search::{normalize_suggest_query, SUGGEST_LIMIT};
+use crate::services::{CacheService, SortOrder};
+
+/// How long a typeahead prefix's suggestions are reused
+const SUGGEST_CACHE_TTL: Duration = Duration::from_secs(60);
 
 #[derive(Debug, Deserialize)]
 pub struct SearchParams {
@@ -18,42 +26,90 @@ pub struct SearchParams {
     limit: usize,
     #[serde(default)]
     offset: usize,
+    #[serde(default)]
+    sort: SortOrder,
 }
 
 fn default_limit() -> usize {
     20
 }
 
+/// Search results page; `total` counts every match, not just this page
+#[derive(Debug, Serialize)]
+pub struct SearchResponse {
+    pub items: Vec<AnimeSummary>,
+    pub total: usize,
+    /// 1-based page number derived from offset and limit
+    pub page: usize,
+    pub limit: usize,
+    pub offset: usize,
+    pub has_more: bool,
+}
+
+impl SearchResponse {
+    pub fn new(items: Vec<AnimeSummary>, total: usize, limit: usize, offset: usize) -> Self {
+        SearchResponse {
+            has_more: offset + items.len() < total,
+            page: offset / limit.max(1) + 1,
+            items,
+            total,
+            limit,
+            offset,
+        }
+    }
+}
+
 pub async fn search(
     Query(params): Query<SearchParams>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    // Perform search
-    match state.search.search_anime(&params.q).await {
-        Ok(mut results) => {
-            // Apply pagination
-            let total = results.len();
-            results = results
-                .into_iter()
-                .skip(params.offset)
-                .take(params.limit)
-                .collect();
-            
+    match state.search.search_anime(&params.q, params.sort, params.limit, params.offset).await {
+        Ok((results, total)) => {
+            let response = SearchResponse::new(results, total, params.limit, params.offset);
+            list_response(&state.legacy_fields, "results", response)
+        }
+        Err(e) => {
             (
-                StatusCode::OK,
+                StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({
-                    "results": results,
-                    "total": total,
-                    "offset": params.offset,
-                    "limit": params.limit
+                    "error": format!("Search failed: {}", e)
                 }))
             ).into_response()
         }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestParams {
+    #[serde(default)]
+    q: String,
+}
+
+// GET /api/search/suggest?q=
+// Typeahead: up to 8 `{id, title}` whose title or a synonym starts with the
+// query, best rated first. Queries under 2 characters get an empty list.
+pub async fn suggest(
+    Query(params): Query<SuggestParams>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let Some(query) = normalize_suggest_query(&params.q) else {
+        return (StatusCode::OK, Json(json!([]))).into_response();
+    };
+    
+    let cache = state.cache.lock().await.handle();
+    let suggestions = cache
+        .get_or_set(&CacheService::suggest_key(&query), SUGGEST_CACHE_TTL, || {
+            state.db.suggest_anime(&query, SUGGEST_LIMIT)
+        })
+        .await;
+    
+    match suggestions {
+        Ok(suggestions) => (StatusCode::OK, Json(suggestions)).into_response(),
         Err(e) => {
             (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({
-                    "error": format!("Search failed: {}", e)
+                    "error": format!("Suggest failed: {}", e)
                 }))
             ).into_response()
         }
@@ -68,4 +124,27 @@ mod tests {
     fn test_default_limit() {
         assert_eq!(default_limit(), 20);
     }
+    
+    fn summaries(count: usize) -> Vec<AnimeSummary> {
+        (0..count)
+            .map(|i| AnimeSummary {
+                id: uuid::Uuid::new_v4(),
+                title: format!("Anime {}", i),
+                poster_url: String::new(),
+                episodes: 12,
+                status: crate::models::AnimeStatus::Finished,
+                anime_type: crate::models::AnimeType::TV,
+                imdb_rating: None,
+            })
+            .collect()
+    }
+    
+    #[test]
+    fn test_search_response_page_info() {
+        let first = SearchResponse::new(summaries(10), 25, 10, 0);
+        assert_eq!((first.page, first.has_more), (1, true));
+        
+        let last = SearchResponse::new(summaries(5), 25, 10, 20);
+        assert_eq!((last.page, last.has_more), (3, false));
+    }
 }
\ No newline at end of file
@@ -2,26 +2,49 @@
 // Reference: contracts/openapi.yaml lines 233-296
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{StatusCode, HeaderMap},
     Json,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
+use serde::Deserialize;
 use serde_json::json;
 use crate::db::connection::AppState;
+use crate::api::handlers::preferences::load_preferences;
+use crate::middleware::AuthUser;
+use crate::middleware::json_extractor::ValidatedJson;
+use crate::models::Session;
+use crate::services::stream_sessions::{ResumeError, StreamGrant, StreamLimitError, RESUME_TOKEN_TTL};
+use crate::services::streaming::{negotiate_quality, DEFAULT_RENDITIONS};
+
+/// Lifetime of the placeholder stream URLs handed out for the POC
+const MOCK_STREAM_TTL_MINUTES: i64 = 15;
+
+#[derive(Debug, Deserialize)]
+pub struct StreamQuery {
+    /// Explicit quality; falls back to the user's saved preference
+    quality: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResumeStreamRequest {
+    resume_token: String,
+}
 
 pub async fn get_stream(
     Path((anime_id, episode_num)): Path<(Uuid, u32)>,
     State(state): State<AppState>,
+    Query(params): Query<StreamQuery>,
     headers: HeaderMap,
 ) -> impl IntoResponse {
     // Extract token from Authorization header
     let token = match headers.get("authorization") {
         Some(value) => {
             let value_str = value.to_str().unwrap_or("");
-            if value_str.starts_with("Bearer ") {
-                &value_str[7..]
+            if let Some(token) = value_str.strip_prefix("Bearer ") {
+                token
             } else {
                 return (StatusCode::UNAUTHORIZED, Json(json!({
                     "error": "Invalid authorization header"
@@ -48,79 +71,249 @@ pub async fn get_stream(
             ).into_response();
         }
     };
+    drop(auth);
     
-    // Get the anime to verify it exists
-    match state.db.get_anime(anime_id).await {
-        Ok(Some(anime)) => {
-            // Get episodes to find the specific one
-            match state.db.get_anime_episodes(anime_id).await {
-                Ok(episodes) => {
-                    // Find the requested episode
-                    if let Some(episode) = episodes.iter().find(|e| e.episode_number == episode_num) {
-                        // For POC, we'll create a mock Crunchyroll episode ID
-                        // In production, this would be stored in the database
-                        let cr_episode_id = format!("CR_{}_E{}", anime_id, episode_num);
-                        
-                        // Get streaming manifest
-                        match state.streaming.get_episode_stream(&session, &cr_episode_id).await {
-                            Ok(manifest) => {
-                                (StatusCode::OK, Json(manifest)).into_response()
-                            }
-                            Err(e) => {
-                                // For POC, return a mock stream URL
-                                (
-                                    StatusCode::OK,
-                                    Json(json!({
-                                        "episode_id": episode.id,
-                                        "crunchyroll_id": cr_episode_id,
-                                        "streams": [{
-                                            "url": format!("https://example.com/stream/{}/{}.m3u8", anime_id, episode_num),
-                                            "resolution": "1080p",
-                                            "audio_language": "en-US",
-                                            "subtitle_language": null,
-                                            "hardsub": false,
-                                            "expires_at": chrono::Utc::now() + chrono::Duration::minutes(15)
-                                        }],
-                                        "thumbnail": episode.thumbnail_url,
-                                        "duration": episode.duration.unwrap_or(1440)
-                                    }))
-                                ).into_response()
-                            }
-                        }
-                    } else {
-                        (
-                            StatusCode::NOT_FOUND,
-                            Json(json!({
-                                "error": "Episode not found"
-                            }))
-                        ).into_response()
-                    }
-                }
-                Err(e) => {
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(json!({
-                            "error": format!("Failed to fetch episodes: {}", e)
-                        }))
-                    ).into_response()
-                }
+    let quality = match params.quality {
+        Some(quality) => quality,
+        None => match load_preferences(&state, &session.user_id).await {
+            Ok(preferences) => preferences.quality,
+            Err(e) => {
+                tracing::warn!("Failed to load preferences for {}: {}", session.user_id, e);
+                "auto".to_string()
             }
+        },
+    };
+    
+    let manifest = match resolve_manifest(&state, &session, anime_id, episode_num, &quality).await {
+        Ok(manifest) => manifest,
+        Err(response) => return response,
+    };
+    
+    // Streams are attributed to the session's device, if it named one
+    let device = match session.device_id {
+        Some(device_id) => state.db.touch_device(device_id).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to look up device {}: {}", device_id, e);
+            None
+        }),
+        None => None,
+    };
+    
+    let grant = StreamGrant {
+        user_id: session.user_id.clone(),
+        device_id: device.as_ref().map(|device| device.id),
+        device_name: device.map(|device| device.name),
+        anime_id,
+        episode_number: episode_num,
+        quality,
+        manifest_expires_at: manifest_expiry(&manifest),
+        manifest,
+    };
+    let resume_token = state.stream_sessions.start(&grant).await;
+    if let Some(limit) = resume_token.as_ref().err().and_then(|e| e.downcast_ref::<StreamLimitError>()) {
+        return stream_limit_response(limit);
+    }
+    
+    stream_response(&state, grant, resume_token).await
+}
+
+/// 409 naming the devices that are using up the user's concurrent streams
+fn stream_limit_response(limit: &StreamLimitError) -> Response {
+    (
+        StatusCode::CONFLICT,
+        Json(json!({
+            "error": "Concurrent stream limit reached",
+            "message": limit.to_string(),
+            "code": "CONCURRENT_STREAM_LIMIT",
+            "limit": limit.limit,
+            "active_streams": limit.active
+        }))
+    ).into_response()
+}
+
+// POST /api/stream/resume
+// Trades a resumption token for the same stream after a network blip. Not rate
+// limited and not counted as a new stream; the provider is only asked again
+// once the original URLs have expired.
+pub async fn resume_stream(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    ValidatedJson(req): ValidatedJson<ResumeStreamRequest>,
+) -> impl IntoResponse {
+    let mut grant = match state.stream_sessions.resume(&auth.session.user_id, &req.resume_token).await {
+        Ok(grant) => grant,
+        Err(e) => {
+            let status = match e.downcast_ref::<ResumeError>() {
+                Some(ResumeError::Expired) => StatusCode::UNAUTHORIZED,
+                Some(ResumeError::WrongUser) => StatusCode::FORBIDDEN,
+                None => StatusCode::INTERNAL_SERVER_ERROR,
+            };
+            return (
+                status,
+                Json(json!({
+                    "error": format!("Failed to resume stream: {}", e)
+                }))
+            ).into_response();
         }
+    };
+    
+    if !grant.manifest_is_valid(Utc::now()) {
+        let manifest = match resolve_manifest(&state, &auth.session, grant.anime_id, grant.episode_number, &grant.quality).await {
+            Ok(manifest) => manifest,
+            Err(response) => return response,
+        };
+        grant.manifest_expires_at = manifest_expiry(&manifest);
+        grant.manifest = manifest;
+    }
+    
+    let resume_token = state.stream_sessions.issue(&grant).await;
+    stream_response(&state, grant, resume_token).await
+}
+
+/// Look up the episode and resolve its streams. The requested quality is
+/// negotiated against the episode's renditions; the manifest reports the chosen
+/// `quality` and `available_qualities` and lists the chosen rendition first.
+async fn resolve_manifest(
+    state: &AppState,
+    session: &Session,
+    anime_id: Uuid,
+    episode_num: u32,
+    quality: &str,
+) -> Result<serde_json::Value, Response> {
+    // Get the anime to verify it exists
+    match state.db.get_anime(anime_id).await {
+        Ok(Some(_)) => {}
         Ok(None) => {
-            (
+            return Err((
                 StatusCode::NOT_FOUND,
                 Json(json!({
                     "error": "Anime not found"
                 }))
-            ).into_response()
+            ).into_response());
         }
         Err(e) => {
-            (
+            return Err((
                 StatusCode::INTERNAL_SERVER_ERROR,
                 Json(json!({
                     "error": format!("Failed to fetch anime: {}", e)
                 }))
-            ).into_response()
+            ).into_response());
+        }
+    }
+    
+    // Get episodes to find the specific one
+    let episodes = match state.db.get_anime_episodes(anime_id).await {
+        Ok(episodes) => episodes,
+        Err(e) => {
+            return Err((
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch episodes: {}", e)
+                }))
+            ).into_response());
         }
+    };
+    
+    let Some(episode) = episodes.iter().find(|e| e.episode_number == episode_num) else {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "Episode not found"
+            }))
+        ).into_response());
+    };
+    
+    // For POC, we'll create a mock Crunchyroll episode ID
+    // In production, this would be stored in the database
+    let cr_episode_id = format!("CR_{}_E{}", anime_id, episode_num);
+    
+    let available_qualities = match state.streaming.available_renditions(session, &cr_episode_id).await {
+        Ok(renditions) => renditions,
+        // For POC, assume the full ladder when Crunchyroll can't be reached
+        Err(_) => DEFAULT_RENDITIONS.iter().map(|quality| quality.to_string()).collect(),
+    };
+    let quality = negotiate_quality(quality, &available_qualities).unwrap_or_else(|| quality.to_string());
+    
+    // Get streaming manifest
+    let mut manifest = match state.streaming.get_episode_stream(session, &cr_episode_id).await {
+        Ok(mut manifest) => {
+            manifest.streams.sort_by_key(|stream| stream.resolution != quality);
+            serde_json::to_value(manifest).unwrap_or_default()
+        }
+        Err(_) => {
+            // For POC, return a mock stream URL
+            json!({
+                "episode_id": episode.id,
+                "crunchyroll_id": cr_episode_id,
+                "streams": [{
+                    "url": format!("https://example.com/stream/{}/{}.m3u8", anime_id, episode_num),
+                    "resolution": quality,
+                    "audio_language": "en-US",
+                    "subtitle_language": null,
+                    "hardsub": false,
+                    "expires_at": Utc::now() + chrono::Duration::minutes(MOCK_STREAM_TTL_MINUTES)
+                }],
+                "thumbnail": episode.thumbnail_url,
+                "duration": episode.duration.unwrap_or(1440)
+            })
+        }
+    };
+    
+    manifest["quality"] = json!(quality);
+    manifest["available_qualities"] = json!(available_qualities);
+    Ok(manifest)
+}
+
+/// Earliest expiry among the manifest's stream URLs
+fn manifest_expiry(manifest: &serde_json::Value) -> DateTime<Utc> {
+    manifest["streams"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|stream| stream["expires_at"].as_str())
+        .filter_map(|expires_at| expires_at.parse::<DateTime<Utc>>().ok())
+        .min()
+        .unwrap_or_else(|| Utc::now() + chrono::Duration::minutes(MOCK_STREAM_TTL_MINUTES))
+}
+
+/// The manifest plus its resumption token and the user's active stream count;
+/// streaming still works if either is unavailable
+async fn stream_response(state: &AppState, grant: StreamGrant, resume_token: anyhow::Result<String>) -> Response {
+    let mut body = grant.manifest;
+    
+    match resume_token {
+        Ok(token) => {
+            body["resume_token"] = json!(token);
+            body["resume_expires_at"] = json!(Utc::now() + chrono::Duration::from_std(RESUME_TOKEN_TTL).unwrap_or_default());
+        }
+        Err(e) => tracing::warn!("Failed to issue stream resumption token for {}: {}", grant.user_id, e),
+    }
+    
+    match state.stream_sessions.active_streams(&grant.user_id).await {
+        Ok(count) => body["active_streams"] = json!(count),
+        Err(e) => tracing::warn!("Failed to count active streams for {}: {}", grant.user_id, e),
+    }
+    
+    (StatusCode::OK, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_manifest_expiry_uses_earliest_stream() {
+        let soon = Utc::now() + chrono::Duration::minutes(5);
+        let later = soon + chrono::Duration::minutes(10);
+        let manifest = json!({
+            "streams": [
+                { "url": "a", "expires_at": later },
+                { "url": "b", "expires_at": soon }
+            ]
+        });
+        
+        assert_eq!(manifest_expiry(&manifest), soon);
+        
+        // No expiry information: assume the mock lifetime
+        assert!(manifest_expiry(&json!({ "streams": [] })) > Utc::now());
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,189 @@
+// GET/POST /api/tags and GET /api/tags/{id}/anime handlers
+// Tags grouped by category for filter chips, and the anime carrying a tag
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+use validator::Validate;
+use crate::api::handlers::anime::{parse_cursor, AnimePage};
+use crate::db::connection::AppState;
+use crate::middleware::AdminUser;
+use crate::middleware::json_extractor::ValidatedJson;
+use crate::models::{Tag, TagCategory, TagGroup, TagSummary};
+use crate::services::database_v2::{violates_unique_index, TAG_NAME_INDEX};
+
+const MAX_TAG_ANIME_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct TagAnimeParams {
+    #[serde(default = "default_tag_anime_limit")]
+    limit: usize,
+    cursor: Option<String>,
+}
+
+fn default_tag_anime_limit() -> usize {
+    20
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TagListParams {
+    category: Option<TagCategory>,
+}
+
+// GET /api/tags?category=genre
+// Every tag with its anime count, one group per category; with `category`,
+// only that category's group
+pub async fn list_tags(
+    Query(params): Query<TagListParams>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match state.db.get_tags_with_anime_counts(params.category.clone()).await {
+        Ok(tags) => {
+            let total = tags.len();
+            let summaries = tags
+                .into_iter()
+                .map(|(tag, anime_count)| TagSummary::new(tag, anime_count))
+                .collect();
+            let mut groups = TagGroup::group(summaries);
+            if let Some(category) = params.category {
+                groups.retain(|group| group.category == category);
+            }
+            
+            (StatusCode::OK, Json(json!({
+                "items": groups,
+                "total": total
+            }))).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to list tags: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTagRequest {
+    name: String,
+    category: TagCategory,
+    description: Option<String>,
+}
+
+// POST /api/tags
+// Tag names are unique ignoring case: "Action" and "action" are the same tag
+pub async fn create_tag(
+    State(state): State<AppState>,
+    _admin: AdminUser,
+    ValidatedJson(req): ValidatedJson<CreateTagRequest>,
+) -> impl IntoResponse {
+    let mut tag = Tag::new(req.name.trim().to_string(), req.category);
+    tag.description = req.description;
+    
+    if let Err(errors) = tag.validate() {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({
+                "error": "Validation failed",
+                "details": errors
+            }))
+        ).into_response();
+    }
+    
+    match state.db.find_tag_by_name(&tag.name).await {
+        Ok(None) => {}
+        Ok(Some(existing)) => {
+            return (
+                StatusCode::CONFLICT,
+                Json(json!({
+                    "error": format!("Tag '{}' already exists", existing.name),
+                    "id": existing.id
+                }))
+            ).into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to check tag name: {}", e)
+                }))
+            ).into_response();
+        }
+    }
+    
+    // A concurrent request can take the name after the check above; the
+    // unique index on the lowercased name still refuses the second tag
+    match state.db.create_tag(&tag).await {
+        Ok(tag) => (StatusCode::CREATED, Json(TagSummary::new(tag, 0))).into_response(),
+        Err(e) if violates_unique_index(&e, TAG_NAME_INDEX) => {
+            (
+                StatusCode::CONFLICT,
+                Json(json!({
+                    "error": format!("Tag '{}' already exists", tag.name)
+                }))
+            ).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to create tag: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// GET /api/tags/{id}/anime
+// Anime with the tag, paged by cursor in (created_at, id) order
+pub async fn get_tag_anime(
+    Path(tag_id): Path<Uuid>,
+    Query(params): Query<TagAnimeParams>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let limit = params.limit.clamp(1, MAX_TAG_ANIME_LIMIT);
+    let cursor = match parse_cursor(params.cursor.as_deref()) {
+        Ok(cursor) => cursor,
+        Err(response) => return response,
+    };
+    
+    match state.db.get_tag(tag_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Tag not found"
+                }))
+            ).into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch tag: {}", e)
+                }))
+            ).into_response();
+        }
+    }
+    
+    match state.db.get_tag_anime_after(tag_id, cursor, limit + 1).await {
+        Ok(rows) => (StatusCode::OK, Json(AnimePage::from_rows(rows, limit))).into_response(),
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to list tag anime: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
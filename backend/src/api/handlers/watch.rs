@@ -0,0 +1,120 @@
+// Live watch heartbeat: an SSE stream of server-driven events per episode,
+// with the player's position POSTed back on the same path.
+// Sessions are kept by WatchHeartbeatService.
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+    response::{sse::{Event, Sse}, IntoResponse},
+};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::MissedTickBehavior;
+use uuid::Uuid;
+use crate::db::connection::AppState;
+use crate::middleware::AuthUser;
+use crate::middleware::json_extractor::ValidatedJson;
+use crate::models::{Episode, PlaybackPosition};
+use crate::services::watch_heartbeat::{HeartbeatEvent, PING_INTERVAL};
+
+#[derive(Debug, Deserialize)]
+pub struct HeartbeatRequest {
+    /// Seconds into the episode
+    #[serde(alias = "position_secs")]
+    position: u32,
+    /// Episode length in seconds
+    #[serde(alias = "duration_secs")]
+    duration: u32,
+    /// Throughput the player measured, for rendition recommendations
+    #[serde(default)]
+    bandwidth_kbps: Option<u32>,
+}
+
+// GET /api/watch/{episode_id}/heartbeat
+// Pings straight away and every PING_INTERVAL after; the position reported
+// last is saved when the client disconnects
+pub async fn heartbeat_stream(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(episode_id): Path<Uuid>,
+) -> impl IntoResponse {
+    if let Err(response) = find_episode(&state, episode_id).await {
+        return response;
+    }
+
+    let subscription = state.watch_heartbeat.subscribe(&auth.session.user_id, episode_id);
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+    ping.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    // The subscription lives as long as the stream, so dropping the stream
+    // on disconnect ends the session
+    let events = futures::stream::unfold((subscription, ping), |(mut subscription, mut ping)| async move {
+        let event = loop {
+            tokio::select! {
+                _ = ping.tick() => break HeartbeatEvent::Ping,
+                received = subscription.events.recv() => match received {
+                    Ok(event) => break event,
+                    // Only pings and superseded recommendations were missed
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                },
+            }
+        };
+        Some((Event::default().json_data(&event), (subscription, ping)))
+    });
+
+    Sse::new(events).into_response()
+}
+
+// POST /api/watch/{episode_id}/heartbeat
+// Without an open stream the position is saved straight away
+pub async fn report_heartbeat(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Path(episode_id): Path<Uuid>,
+    ValidatedJson(req): ValidatedJson<HeartbeatRequest>,
+) -> impl IntoResponse {
+    let episode = match find_episode(&state, episode_id).await {
+        Ok(episode) => episode,
+        Err(response) => return response,
+    };
+
+    let position = PlaybackPosition::new(&episode, req.position, req.duration);
+    let user_id = &auth.session.user_id;
+
+    if state.watch_heartbeat.record_position(user_id, position.clone(), req.bandwidth_kbps) {
+        return (StatusCode::OK, Json(position)).into_response();
+    }
+
+    match state.watch_progress.save_position(user_id, &position).await {
+        Ok(()) => (StatusCode::OK, Json(position)).into_response(),
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to save playback position: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+async fn find_episode(state: &AppState, episode_id: Uuid) -> Result<Episode, axum::response::Response> {
+    match state.db.get_episode(episode_id).await {
+        Ok(Some(episode)) => Ok(episode),
+        Ok(None) => Err((
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "Episode not found"
+            }))
+        ).into_response()),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(json!({
+                "error": format!("Failed to fetch episode: {}", e)
+            }))
+        ).into_response()),
+    }
+}
@@ -0,0 +1,134 @@
+// Watch history endpoints for the authenticated user
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+use crate::api::deprecation::list_response;
+use crate::db::connection::AppState;
+use crate::middleware::AuthUser;
+use crate::middleware::json_extractor::ValidatedJson;
+use crate::models::WatchProgress;
+
+const MAX_LIMIT: usize = 100;
+
+#[derive(Debug, Deserialize)]
+pub struct RecordWatchRequest {
+    episode_id: Uuid,
+    progress: u32,
+    total_duration: u32,
+    #[serde(default)]
+    completed: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WatchHistoryParams {
+    #[serde(default = "default_limit")]
+    limit: usize,
+    #[serde(default)]
+    offset: usize,
+}
+
+fn default_limit() -> usize {
+    20
+}
+
+// POST /api/user/watch-history
+// Records progress for an episode; posting again for the same episode updates it
+pub async fn record_watch_progress(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    ValidatedJson(req): ValidatedJson<RecordWatchRequest>,
+) -> impl IntoResponse {
+    if req.total_duration > 0 && req.progress > req.total_duration {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "progress must not exceed total_duration"
+            }))
+        ).into_response();
+    }
+    
+    let completed = WatchProgress::is_completed(req.progress, req.total_duration, req.completed);
+    
+    match state.db.upsert_watch_progress(&auth.session.user_id, req.episode_id, req.progress, req.total_duration, completed).await {
+        Ok(Some(progress)) => (StatusCode::OK, Json(progress)).into_response(),
+        Ok(None) => {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Episode not found"
+                }))
+            ).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to record watch progress: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// GET /api/user/watch-history
+pub async fn get_watch_history(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Query(params): Query<WatchHistoryParams>,
+) -> impl IntoResponse {
+    let limit = params.limit.clamp(1, MAX_LIMIT);
+    
+    match state.db.get_watch_history(&auth.session.user_id, limit, params.offset).await {
+        Ok((episodes, total)) => {
+            list_response(&state.legacy_fields, "episodes", json!({
+                "items": episodes,
+                "total": total,
+                "limit": limit,
+                "offset": params.offset
+            }))
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch watch history: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ContinueWatchingParams {
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+// GET /api/user/continue-watching
+// Unfinished episodes, most recently touched first, one per anime
+pub async fn get_continue_watching(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    Query(params): Query<ContinueWatchingParams>,
+) -> impl IntoResponse {
+    let limit = params.limit.clamp(1, MAX_LIMIT);
+    
+    match state.watch_progress.continue_watching(&auth.session.user_id, limit).await {
+        Ok(items) => (StatusCode::OK, Json(items)).into_response(),
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch continue watching: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
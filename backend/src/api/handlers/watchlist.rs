@@ -0,0 +1,149 @@
+// Watchlist endpoints for the authenticated user
+
+use axum::{
+    extract::State,
+    http::StatusCode,
+    Json,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+use crate::db::connection::AppState;
+use crate::middleware::AuthUser;
+use crate::middleware::json_extractor::ValidatedJson;
+use crate::models::WatchlistStatus;
+
+#[derive(Debug, Deserialize)]
+pub struct AddWatchlistRequest {
+    anime_id: Uuid,
+    #[serde(default)]
+    status: WatchlistStatus,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateWatchlistRequest {
+    anime_id: Uuid,
+    status: WatchlistStatus,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RemoveWatchlistRequest {
+    anime_id: Uuid,
+}
+
+// GET /api/user/watchlist
+pub async fn get_watchlist(
+    State(state): State<AppState>,
+    auth: AuthUser,
+) -> impl IntoResponse {
+    match state.db.get_watchlist(&auth.session.user_id).await {
+        Ok(items) => {
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "items": items,
+                    "total": items.len()
+                }))
+            ).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch watchlist: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// POST /api/user/watchlist
+// Adding an anime that is already listed updates its status instead
+pub async fn add_to_watchlist(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    ValidatedJson(req): ValidatedJson<AddWatchlistRequest>,
+) -> impl IntoResponse {
+    match state.db.get_anime(req.anime_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Anime not found"
+                }))
+            ).into_response();
+        }
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to fetch anime: {}", e)
+                }))
+            ).into_response();
+        }
+    }
+    
+    match state.db.upsert_watchlist_entry(&auth.session.user_id, req.anime_id, req.status).await {
+        Ok((entry, created)) => {
+            let status = if created { StatusCode::CREATED } else { StatusCode::OK };
+            (status, Json(entry)).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to update watchlist: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// PUT /api/user/watchlist
+pub async fn update_watchlist(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    ValidatedJson(req): ValidatedJson<UpdateWatchlistRequest>,
+) -> impl IntoResponse {
+    match state.db.update_watchlist_status(&auth.session.user_id, req.anime_id, req.status).await {
+        Ok(Some(entry)) => (StatusCode::OK, Json(entry)).into_response(),
+        Ok(None) => {
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Anime is not on your watchlist"
+                }))
+            ).into_response()
+        }
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to update watchlist: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
+
+// DELETE /api/user/watchlist
+// Idempotent: removing an anime that is not listed still succeeds
+pub async fn remove_from_watchlist(
+    State(state): State<AppState>,
+    auth: AuthUser,
+    ValidatedJson(req): ValidatedJson<RemoveWatchlistRequest>,
+) -> impl IntoResponse {
+    match state.db.remove_from_watchlist(&auth.session.user_id, req.anime_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": format!("Failed to update watchlist: {}", e)
+                }))
+            ).into_response()
+        }
+    }
+}
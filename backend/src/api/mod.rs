@@ -1,2 +1,4 @@
+pub mod deprecation;
 pub mod routes;
-pub mod handlers;
\ No newline at end of file
+pub mod handlers;
+pub mod versioning;
\ No newline at end of file
@@ -3,76 +3,188 @@
 
 use axum::{
     Router,
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    routing::{get, post, put, patch, delete},
     middleware as axum_middleware,
     http::StatusCode,
     response::{IntoResponse, Json},
 };
-use tower_http::{
-    compression::CompressionLayer,
-    limit::RequestBodyLimitLayer,
-};
+use tower_http::compression::CompressionLayer;
+use crate::api::versioning::{version_negotiation_middleware, VersionRouter};
 use crate::db::connection::AppState;
 use crate::middleware::{
-    get_cors_layer,
+    dynamic_cors_middleware,
     logging_middleware,
+    etag_middleware,
+    season_etag_middleware,
+    anime_detail_cache_middleware,
+    season_browse_cache_middleware,
+    search_cache_middleware,
     create_trace_layer,
+    rate_limit_middleware,
+    csrf_middleware,
+    RequestSizeLimitLayer,
 };
 use serde_json::json;
 
 pub fn create_router(state: AppState) -> Router {
+    // Streaming
+    let stream_routes = Router::new()
+        .route("/:anime_id/:episode", get(crate::api::handlers::stream::get_stream))
+        .route("/resume", post(crate::api::handlers::stream::resume_stream));
+    
+    // Live watch sessions: SSE events out, position reports in
+    let watch_routes = Router::new()
+        .route(
+            "/:episode_id/heartbeat",
+            get(crate::api::handlers::watch::heartbeat_stream)
+                .post(crate::api::handlers::watch::report_heartbeat),
+        );
+    
+    // Per-user data
+    let user_routes = Router::new()
+        .route("/watchlist", get(crate::api::handlers::watchlist::get_watchlist))
+        .route("/watchlist", post(crate::api::handlers::watchlist::add_to_watchlist))
+        .route("/watchlist", put(crate::api::handlers::watchlist::update_watchlist))
+        .route("/watchlist", delete(crate::api::handlers::watchlist::remove_from_watchlist))
+        .route("/watch-history", get(crate::api::handlers::watch_history::get_watch_history))
+        .route("/watch-history", post(crate::api::handlers::watch_history::record_watch_progress))
+        .route("/continue-watching", get(crate::api::handlers::watch_history::get_continue_watching))
+        .route("/recommendations", get(crate::api::handlers::recommendations::get_user_recommendations))
+        .route("/playback-position", post(crate::api::handlers::playback::save_playback_position))
+        .route("/playback-position/:episode_id", get(crate::api::handlers::playback::get_playback_position))
+        .route("/playback-position/anime/:anime_id", get(crate::api::handlers::playback::get_latest_playback_position))
+        .route("/preferences", get(crate::api::handlers::preferences::get_preferences))
+        .route("/preferences", put(crate::api::handlers::preferences::update_preferences));
+    
+    // Bulk ingest accepts uploads far larger than the global request limit
+    let bulk_routes = Router::new()
+        .route("/anime/bulk", post(crate::api::handlers::bulk::bulk_import_anime))
+        .layer(RequestSizeLimitLayer { max_bytes: state.request_size.bulk_import_max_bytes });
+    
     // API routes
     let api_routes = Router::new()
         // Anime endpoints
-        .route("/anime", post(crate::api::handlers::anime::create_anime))
-        .route("/anime/:id", get(crate::api::handlers::anime::get_anime))
-        .route("/anime/:id/episodes", get(crate::api::handlers::episodes::get_episodes))
+        .route("/anime", get(crate::api::handlers::anime::list_anime)
+            .post(crate::api::handlers::anime::create_anime))
+        .route("/anime/:id", get(crate::api::handlers::anime::get_anime)
+            .layer(axum_middleware::from_fn_with_state(state.clone(), etag_middleware))
+            .layer(axum_middleware::from_fn_with_state(state.clone(), anime_detail_cache_middleware)))
+        .route("/anime/:id", patch(crate::api::handlers::anime::update_anime)
+            .delete(crate::api::handlers::anime::delete_anime))
+        .route("/anime/:id/similar", get(crate::api::handlers::recommendations::get_similar))
+        .route("/anime/:id/recommendations", get(crate::api::handlers::recommendations::get_anime_recommendations))
+        .route("/anime/:id/keywords", get(crate::api::handlers::anime::get_anime_keywords))
+        .route("/anime/:id/episodes", get(crate::api::handlers::episodes::get_episodes)
+            .layer(axum_middleware::from_fn_with_state(state.clone(), etag_middleware)))
         .route("/anime/:id/episodes", post(crate::api::handlers::episodes::create_episodes))
+        .route("/anime/:id/episodes/batch", post(crate::api::handlers::episodes::create_episodes_batch))
+        .route("/anime/:id/episodes/stream", get(crate::api::handlers::episodes::stream_episodes))
         
         // Search and browse
-        .route("/search", get(crate::api::handlers::search::search))
-        .route("/browse/season/:year/:season", get(crate::api::handlers::browse::browse_season))
+        .route("/search", get(crate::api::handlers::search::search)
+            .layer(axum_middleware::from_fn_with_state(state.clone(), search_cache_middleware)))
+        .route("/search/suggest", get(crate::api::handlers::search::suggest))
+        .route("/browse/season/:year/:season", get(crate::api::handlers::browse::browse_season)
+            .layer(axum_middleware::from_fn_with_state(state.clone(), season_etag_middleware))
+            .layer(axum_middleware::from_fn_with_state(state.clone(), season_browse_cache_middleware)))
+        .route("/browse/season/:year/:season/calendar", get(crate::api::handlers::browse::season_calendar))
+        .route("/browse/year/:year", get(crate::api::handlers::browse::browse_year))
+        
+        // Tags
+        .route("/tags", get(crate::api::handlers::tags::list_tags)
+            .post(crate::api::handlers::tags::create_tag))
+        .route("/tags/:id/anime", get(crate::api::handlers::tags::get_tag_anime))
+        
+        // Partner catalog mirroring (API key)
+        .route("/catalog/changes", get(crate::api::handlers::catalog::get_catalog_changes))
+        
+        // Recommendations
+        .route("/recommendations", get(crate::api::handlers::recommendations::get_recommendations))
         
         // Authentication
+        .route("/auth/register", post(crate::api::handlers::auth::register))
+        .route("/auth/verify", get(crate::api::handlers::auth::verify_email))
         .route("/auth/login", post(crate::api::handlers::auth::login))
         .route("/auth/logout", post(crate::api::handlers::auth::logout))
         .route("/auth/refresh", post(crate::api::handlers::auth::refresh))
-        
-        // Streaming
-        .route("/stream/:anime_id/:episode", get(crate::api::handlers::stream::get_stream))
+        .route("/auth/devices", get(crate::api::handlers::devices::list_devices))
+        .route("/auth/devices/:id", delete(crate::api::handlers::devices::revoke_device))
         
         // Frontend logging endpoints
         .route("/logs/frontend", post(crate::api::handlers::logs::receive_frontend_logs))
         .route("/logs/error", post(crate::api::handlers::logs::report_frontend_error))
         .route("/logs/performance", post(crate::api::handlers::logs::report_performance_metrics))
         
-        // Legacy health check
-        .route("/health", get(health_check))
+        // Admin maintenance
+        .route("/admin/cache/:version", delete(crate::api::handlers::admin::purge_cache_version))
+        .route("/admin/recommendations/rebuild", get(crate::api::handlers::admin::rebuild_recommendations))
+        .route("/admin/cors/origins", post(crate::api::handlers::admin::add_cors_origin))
+        .route("/admin/cors/origins/:origin", delete(crate::api::handlers::admin::remove_cors_origin))
+        .route("/admin/anime/:id/episodes/renumber", post(crate::api::handlers::admin::renumber_episodes))
         
-        // Production health checks (T066)
+        // Production health checks (T066); /health/full is the older name of /health
+        .route("/health", get(crate::api::handlers::health::health))
         .route("/health/live", get(crate::api::handlers::health::liveness))
         .route("/health/ready", get(crate::api::handlers::health::readiness))
         .route("/health/full", get(crate::api::handlers::health::health))
         .route("/health/components", get(crate::api::handlers::health::component_health))
         
-        .with_state(state);
+        .nest("/stream", stream_routes)
+        .nest("/watch", watch_routes)
+        .nest("/user", user_routes)
+        
+        .layer(RequestSizeLimitLayer { max_bytes: state.request_size.max_bytes })
+        .merge(bulk_routes)
+        // RequestSizeLimitLayer is the only body limit, in place of axum's 2 MB default
+        .layer(DefaultBodyLimit::disable())
+        
+        // Double-submit CSRF check; a no-op unless cookie sessions are enabled
+        .layer(axum_middleware::from_fn_with_state(state.session_cookies.clone(), csrf_middleware))
+        // Per user with a session, per client IP otherwise; see RateLimitConfig
+        .layer(axum_middleware::from_fn_with_state(state.clone(), rate_limit_middleware))
+        
+        .with_state(state.clone());
+    
+    // /api/v1, /api/v2 and unprefixed /api (v1); no breaking changes in v2 yet
+    let router = Router::new().merge(VersionRouter::new(api_routes).into_router());
+    
+    // Prometheus scrape endpoints, on their own port when METRICS_PORT is set,
+    // and per-route request metrics; a route layer so the matched path is known
+    #[cfg(feature = "metrics")]
+    let router = {
+        let metrics_routes = match state.metrics_endpoint.port {
+            Some(_) => Router::new(),
+            None => create_metrics_router(state.clone()),
+        };
+        router
+            .merge(metrics_routes)
+            .route_layer(axum_middleware::from_fn_with_state(state.metrics.clone(), crate::middleware::metrics_middleware))
+    };
     
     // Main router with middleware
-    Router::new()
-        .nest("/api", api_routes)
+    router
         // Add fallback for 404 handling
         .fallback(handle_404)
+        // 406 for unsupported versions, X-Kensho-API-Version on every response
+        .layer(axum_middleware::from_fn(version_negotiation_middleware))
         // Add custom logging middleware
         .layer(axum_middleware::from_fn(logging_middleware))
         // Add middleware layers
-        .layer(get_cors_layer())
+        // CORS for the static origins plus the Redis allow-list
+        .layer(axum_middleware::from_fn_with_state(state.clone(), dynamic_cors_middleware))
         .layer(CompressionLayer::new())
         .layer(create_trace_layer())
-        .layer(RequestBodyLimitLayer::new(10 * 1024 * 1024))
 }
 
-async fn health_check() -> &'static str {
-    "OK"
+/// Just GET /metrics and GET /api/health/metrics, for serving on
+/// `MetricsEndpointConfig::port`. Unauthenticated: keep that port off the public network.
+#[cfg(feature = "metrics")]
+pub fn create_metrics_router(state: AppState) -> Router {
+    Router::new()
+        .route("/metrics", get(crate::api::handlers::metrics::get_metrics))
+        .route("/api/health/metrics", get(crate::api::handlers::health::metrics_handler))
+        .with_state(state)
 }
 
 async fn handle_404() -> impl IntoResponse {
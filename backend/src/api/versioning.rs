@@ -0,0 +1,206 @@
+// API versioning: the API is mounted under /api/v1, /api/v2 and, for existing
+// clients, unprefixed /api (which serves v1). Breaking changes land in v2 first.
+// Clients on /api may ask for a version with
+// `Accept: application/vnd.kensho.v2+json` instead of a prefix. Every response
+// names the version that served it in X-Kensho-API-Version.
+
+use axum::{
+    extract::Request,
+    http::{header::ACCEPT, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use serde_json::json;
+use std::fmt;
+use crate::middleware::error::ErrorResponse;
+use crate::middleware::logging::RequestId;
+
+pub const API_VERSION_HEADER: HeaderName = HeaderName::from_static("x-kensho-api-version");
+
+/// Vendor media type prefix; the version follows, e.g. `application/vnd.kensho.v2+json`
+const VENDOR_MEDIA_TYPE: &str = "application/vnd.kensho.";
+
+/// A supported API version. Available to handlers as a request extension.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    pub const SUPPORTED: [ApiVersion; 2] = [ApiVersion::V1, ApiVersion::V2];
+
+    /// What unprefixed /api serves when the request names no version
+    pub const DEFAULT: ApiVersion = ApiVersion::V1;
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "v1",
+            ApiVersion::V2 => "v2",
+        }
+    }
+
+    /// `v1`, `v2`, ...; None for unsupported versions
+    fn parse(value: &str) -> Option<ApiVersion> {
+        ApiVersion::SUPPORTED.into_iter().find(|version| version.as_str() == value)
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Version named by a request, before it is checked against `ApiVersion::SUPPORTED`
+#[derive(Debug, PartialEq)]
+enum Requested<'a> {
+    None,
+    Version(&'a str),
+}
+
+/// The version segment of `/api/{version}/...`, when the first segment after
+/// /api looks like one (`v` and digits)
+fn path_version(path: &str) -> Requested<'_> {
+    let segment = path
+        .strip_prefix("/api/")
+        .and_then(|rest| rest.split('/').next())
+        .unwrap_or("");
+    let is_version = segment.len() > 1
+        && segment.starts_with('v')
+        && segment[1..].bytes().all(|b| b.is_ascii_digit());
+
+    if is_version { Requested::Version(segment) } else { Requested::None }
+}
+
+/// The version of the first `application/vnd.kensho.{version}+json` in Accept
+fn accept_version(headers: &HeaderMap) -> Requested<'_> {
+    headers
+        .get_all(ACCEPT)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|media_type| media_type.split(';').next())
+        .map(str::trim)
+        .find_map(|media_type| media_type.strip_prefix(VENDOR_MEDIA_TYPE))
+        .map(|rest| Requested::Version(rest.strip_suffix("+json").unwrap_or(rest)))
+        .unwrap_or(Requested::None)
+}
+
+/// `path` with a version prefix removed, so `/api/v1/auth/login` and
+/// `/api/auth/login` are the same route for per-route configuration
+pub fn unversioned_path(path: &str) -> String {
+    match path_version(path) {
+        Requested::Version(version) => format!("/api{}", &path["/api/".len() + version.len()..]),
+        Requested::None => path.to_string(),
+    }
+}
+
+/// Mounts the API under each version prefix. v2 starts as a copy of v1;
+/// `v2` replaces it with the routes that changed incompatibly.
+pub struct VersionRouter {
+    v1: Router,
+    v2: Router,
+}
+
+impl VersionRouter {
+    pub fn new(v1: Router) -> Self {
+        VersionRouter { v2: v1.clone(), v1 }
+    }
+
+    /// Apply breaking changes to the v2 routes only
+    pub fn v2(mut self, change: impl FnOnce(Router) -> Router) -> Self {
+        self.v2 = change(self.v2);
+        self
+    }
+
+    /// `/api/v1`, `/api/v2`, and `/api` serving v1 for backward compatibility.
+    /// Layer `version_negotiation_middleware` around the result.
+    pub fn into_router(self) -> Router {
+        Router::new()
+            .nest("/api/v1", self.v1.clone())
+            .nest("/api/v2", self.v2)
+            .nest("/api", self.v1)
+    }
+}
+
+/// Resolve the API version from the path prefix or, on unprefixed /api, the
+/// Accept header. Unsupported or conflicting versions get 406 Not Acceptable.
+/// The version is stored as an `ApiVersion` request extension and echoed in
+/// X-Kensho-API-Version.
+pub async fn version_negotiation_middleware(mut req: Request, next: Next) -> Response {
+    let from_path = path_version(req.uri().path());
+    let from_accept = accept_version(req.headers());
+
+    let resolved = match (from_path, from_accept) {
+        (Requested::Version(path), Requested::Version(accept)) if path != accept => {
+            Err(format!("URL names API version {} but Accept asks for {}", path, accept))
+        }
+        (Requested::Version(version), _) | (Requested::None, Requested::Version(version)) => {
+            ApiVersion::parse(version).ok_or_else(|| format!("API version {} is not supported", version))
+        }
+        (Requested::None, Requested::None) => Ok(ApiVersion::DEFAULT),
+    };
+
+    let version = match resolved {
+        Ok(version) => version,
+        Err(message) => return not_acceptable(message),
+    };
+
+    req.extensions_mut().insert(version);
+    let mut response = next.run(req).await;
+    response.headers_mut().insert(API_VERSION_HEADER, HeaderValue::from_static(version.as_str()));
+    response
+}
+
+fn not_acceptable(message: String) -> Response {
+    let supported: Vec<&str> = ApiVersion::SUPPORTED.iter().map(ApiVersion::as_str).collect();
+    (
+        StatusCode::NOT_ACCEPTABLE,
+        Json(ErrorResponse {
+            code: "UNSUPPORTED_API_VERSION".to_string(),
+            message,
+            details: Some(json!({ "supported": supported })),
+            request_id: RequestId::current(),
+        }),
+    ).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accept(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_path_version() {
+        assert_eq!(path_version("/api/v2/anime"), Requested::Version("v2"));
+        assert_eq!(path_version("/api/v10"), Requested::Version("v10"));
+        assert_eq!(path_version("/api/anime"), Requested::None);
+        assert_eq!(path_version("/api/videos"), Requested::None);
+        assert_eq!(path_version("/metrics"), Requested::None);
+    }
+
+    #[test]
+    fn test_accept_version() {
+        assert_eq!(accept_version(&accept("application/vnd.kensho.v2+json")), Requested::Version("v2"));
+        assert_eq!(
+            accept_version(&accept("text/html, application/vnd.kensho.v1+json;q=0.9")),
+            Requested::Version("v1"),
+        );
+        assert_eq!(accept_version(&accept("application/json")), Requested::None);
+        assert_eq!(accept_version(&HeaderMap::new()), Requested::None);
+    }
+
+    #[test]
+    fn test_unversioned_path() {
+        assert_eq!(unversioned_path("/api/v1/auth/login"), "/api/auth/login");
+        assert_eq!(unversioned_path("/api/v2"), "/api");
+        assert_eq!(unversioned_path("/api/auth/login"), "/api/auth/login");
+    }
+}
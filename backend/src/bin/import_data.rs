@@ -25,12 +25,9 @@ struct AnimeEntry {
     anime_season: Option<AnimeSeasonRaw>,
     picture: String,
     thumbnail: String,
-    duration: Option<Duration>,
     score: Option<Score>,
     synonyms: Vec<String>,
     studios: Vec<String>,
-    producers: Vec<String>,
-    related_anime: Vec<String>,
     tags: Vec<String>,
 }
 
@@ -40,12 +37,6 @@ struct AnimeSeasonRaw {
     year: Option<i32>,
 }
 
-#[derive(Debug, Deserialize)]
-struct Duration {
-    value: i32,
-    unit: String,
-}
-
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct Score {
@@ -90,6 +81,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "FINISHED" => AnimeStatus::Finished,
             "ONGOING" => AnimeStatus::Ongoing,
             "UPCOMING" => AnimeStatus::Upcoming,
+            "CANCELLED" => AnimeStatus::Cancelled,
             _ => AnimeStatus::Unknown,
         };
 
@@ -118,6 +110,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 id: format!("tt{:07}", index), // Generate fake IMDB ID
                 rating,
                 votes: 1000, // Default vote count
+                last_updated: Utc::now(),
             })
         });
 
@@ -141,6 +134,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 entry.tags.join(", ")
             ),
             poster_url: entry.picture.clone(),
+            posters: Anime::collect_posters(vec![entry.picture.clone(), entry.thumbnail.clone()]),
+            stored_episode_count: 0,
+            popularity: 0.0,
+            romaji_titles: Vec::new(),
             imdb,
             created_at: Utc::now(),
             updated_at: Utc::now(),
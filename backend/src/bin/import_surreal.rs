@@ -1,6 +1,6 @@
 use kensho_backend::{
-    models::{Anime, AnimeStatus, AnimeType, AnimeSeason, Season, ImdbData, Tag, TagCategory},
-    services::database_v2::DatabaseService,
+    models::{Anime, AnimeStatus, AnimeType, AnimeSeason, Season, Tag, TagCategory},
+    services::{database_v2::DatabaseService, BrowseSort, SeasonFilter},
 };
 use chrono::Utc;
 use serde::Deserialize;
@@ -24,6 +24,8 @@ struct AnimeEntry {
     status: String,
     anime_season: Option<AnimeSeasonRaw>,
     picture: String,
+    #[serde(default)]
+    thumbnail: Option<String>,
     synonyms: Vec<String>,
     studios: Vec<String>,
     tags: Vec<String>,
@@ -50,6 +52,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("Database already contains {} anime entries", existing_count);
         print!("Do you want to continue and add more? (y/n): ");
         use std::io::{self, Write};
+        #[allow(clippy::disallowed_methods)] // Interactive prompt
         io::stdout().flush()?;
         
         let mut input = String::new();
@@ -97,6 +100,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "FINISHED" => AnimeStatus::Finished,
             "ONGOING" => AnimeStatus::Ongoing,
             "UPCOMING" => AnimeStatus::Upcoming,
+            "CANCELLED" => AnimeStatus::Cancelled,
             _ => AnimeStatus::Unknown,
         };
         
@@ -139,6 +143,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 entry.tags.join(", ")
             ),
             poster_url: entry.picture.clone(),
+            posters: Anime::collect_posters(
+                std::iter::once(entry.picture.clone()).chain(entry.thumbnail.clone())
+            ),
+            stored_episode_count: 0,
+            popularity: 0.0,
+            romaji_titles: Vec::new(),
             imdb: None,  // No IMDB data in this dataset
             created_at: Utc::now(),
             updated_at: Utc::now(),
@@ -215,10 +225,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\nTesting queries...");
     
     // Test seasonal query
-    let fall_2020 = db.get_seasonal_anime(2020, "fall").await?;
+    let fall_2020 = db.get_seasonal_anime(2020, "fall", &SeasonFilter::default(), BrowseSort::default(), None, 0).await?;
     println!("Found {} anime for Fall 2020", fall_2020.len());
     
-    if fall_2020.len() > 0 {
+    if !fall_2020.is_empty() {
         println!("Sample: {}", fall_2020[0].title);
         
         // Test similarity query
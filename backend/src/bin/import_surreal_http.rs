@@ -1,8 +1,4 @@
-use kensho_backend::{
-    models::{Anime, AnimeStatus, AnimeType, AnimeSeason, Season, Tag, TagCategory},
-};
-use chrono::Utc;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use std::fs::File;
 use std::io::BufReader;
 use uuid::Uuid;
@@ -23,6 +19,8 @@ struct AnimeEntry {
     status: String,
     anime_season: Option<AnimeSeasonRaw>,
     picture: String,
+    #[serde(default)]
+    thumbnail: Option<String>,
     synonyms: Vec<String>,
     studios: Vec<String>,
     tags: Vec<String>,
@@ -34,11 +32,6 @@ struct AnimeSeasonRaw {
     year: Option<i32>,
 }
 
-#[derive(Serialize)]
-struct SqlQuery {
-    query: String,
-    vars: Option<serde_json::Value>,
-}
 
 async fn execute_query(query: &str) -> Result<String, Box<dyn std::error::Error>> {
     let client = reqwest::Client::new();
@@ -104,6 +97,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "FINISHED" => "finished",
             "ONGOING" => "ongoing",
             "UPCOMING" => "upcoming",
+            "CANCELLED" => "cancelled",
             _ => "unknown",
         };
         
@@ -144,6 +138,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 }},
                 synopsis: "{}",
                 poster_url: "{}",
+                posters: {:?},
+                stored_episode_count: 0,
+                popularity: 0.0,
+                romaji_titles: Vec::new(),
                 imdb: null,
                 created_at: time::now(),
                 updated_at: time::now()
@@ -165,7 +163,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 entry.studios.join(", "),
                 entry.tags.join(", ")
             ).replace('"', r#"\""#).replace('\n', " "),
-            entry.picture
+            entry.picture,
+            [Some(entry.picture.clone()), entry.thumbnail.clone()]
+                .into_iter()
+                .flatten()
+                .filter(|p| !p.is_empty())
+                .collect::<Vec<_>>()
         );
         
         // Execute insert
@@ -11,6 +11,8 @@ use std::net::SocketAddr;
 // Import our custom JSON extractor
 use kensho_backend::middleware::json_extractor::ValidatedJson;
 
+// Only deserialized, to exercise the extractors
+#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 struct TestRequest {
     email: String,
@@ -0,0 +1,52 @@
+// Database health checks and repairs
+// Reconciles derived counters that can drift after partial imports
+
+use anyhow::Result;
+use clap::Parser;
+use kensho_backend::services::DatabaseService;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about = "Check and repair Kensho database consistency", long_about = None)]
+struct Args {
+    /// SurrealDB connection URL
+    #[arg(long, env = "DATABASE_URL", default_value = "localhost:8000")]
+    database_url: String,
+    
+    /// Report problems without writing fixes
+    #[arg(long)]
+    dry_run: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+    
+    let db = DatabaseService::new(&args.database_url).await?;
+    tracing::info!("Connected to {}", args.database_url);
+    
+    let anime_count = db.get_anime_count().await?;
+    tracing::info!("Anime records: {}", anime_count);
+    
+    // Episode counters: recompute stored_episode_count from episode rows
+    let fixes = db.reconcile_episode_counts(!args.dry_run).await?;
+    for fix in &fixes {
+        tracing::warn!(
+            "{} ({}): stored_episode_count {} -> {}",
+            fix.title, fix.anime_id, fix.recorded, fix.actual
+        );
+    }
+    
+    if fixes.is_empty() {
+        tracing::info!("Episode counts are consistent");
+    } else if args.dry_run {
+        tracing::warn!("{} episode counts out of date (dry run, nothing written)", fixes.len());
+    } else {
+        tracing::info!("Reconciled {} episode counts", fixes.len());
+    }
+    
+    Ok(())
+}
@@ -2,11 +2,9 @@ use anyhow::{Result, Context};
 use clap::Parser;
 use kensho_backend::services::metadata::{MetadataService, OfflineAnimeEntry};
 use kensho_backend::services::database_simplified::DatabaseService;
-use kensho_backend::models::{Anime, Episode};
-use serde_json;
+use kensho_backend::models::Episode;
 use std::fs;
 use uuid::Uuid;
-use tracing_subscriber;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -58,7 +56,7 @@ async fn main() -> Result<()> {
     tracing::info!("Found {} anime entries", data_array.len());
     
     // Initialize services
-    let mut metadata_service = MetadataService::new(args.file.clone());
+    let metadata_service = MetadataService::new(args.file.clone());
     
     // For now, we'll use the simplified database service
     // In production, this would connect to SurrealDB
@@ -98,6 +96,7 @@ async fn main() -> Result<()> {
                                         air_date: None,
                                         synopsis: None,
                                         thumbnail_url: None,
+                                        absolute_number: None,
                                         created_at: chrono::Utc::now(),
                                         updated_at: chrono::Utc::now(),
                                     };
@@ -1,3 +1,3 @@
 // CLI modules
-pub mod ingest;
-pub mod db_init;
\ No newline at end of file
+// ingest and db_init are binaries of their own
+pub mod import;
\ No newline at end of file
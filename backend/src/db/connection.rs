@@ -17,6 +17,16 @@ impl ConnectionPool {
         // Initialize schema
         db.initialize_schema().await?;
         
+        // Data migrations
+        let migrated = db.migrate_cancelled_status().await?;
+        if migrated > 0 {
+            tracing::info!("Migrated {} anime to the cancelled status", migrated);
+        }
+        let restamped = db.migrate_anime_updated_at().await?;
+        if restamped > 0 {
+            tracing::info!("Restamped updated_at on {} anime", restamped);
+        }
+        
         // Load initial data if database is empty
         crate::services::data_loader::load_initial_data(&db).await?;
         
@@ -40,6 +50,23 @@ pub struct AppState {
     pub streaming: Arc<crate::services::StreamingService>,
     pub metadata: Arc<tokio::sync::Mutex<crate::services::MetadataService>>,
     pub health: Arc<crate::services::HealthService>,
+    pub recommendations: Arc<crate::services::RecommendationService>,
+    pub similarity_rebuild: Arc<crate::services::recommendations::SimilarityRebuildTask>,
+    pub watch_progress: Arc<crate::services::WatchProgressService>,
+    pub watch_heartbeat: Arc<crate::services::WatchHeartbeatService>,
+    pub stream_sessions: Arc<crate::services::StreamSessionService>,
+    #[cfg(feature = "metrics")]
+    pub metrics: Arc<crate::middleware::Metrics>,
+    #[cfg(feature = "metrics")]
+    pub metrics_endpoint: crate::middleware::MetricsEndpointConfig,
+    pub rate_limit: crate::middleware::RateLimitConfig,
+    pub request_size: crate::middleware::RequestSizeLimitConfig,
+    pub session_cookies: crate::middleware::SessionCookieConfig,
+    pub legacy_fields: crate::api::deprecation::LegacyFieldsConfig,
+    pub api_keys: crate::middleware::ApiKeyConfig,
+    pub write_access: crate::middleware::WriteAccessConfig,
+    pub cors: crate::middleware::CorsConfig,
+    pub mail: crate::services::mailer::MailerConfig,
 }
 
 impl AppState {
@@ -69,8 +96,10 @@ impl AppState {
             }
         };
         
+        // CACHE_URL=memory:// runs the response cache in-process; defaults to Redis
+        let cache_url = std::env::var("CACHE_URL").unwrap_or_else(|_| redis_url.to_string());
         tracing::debug!("Initializing cache service...");
-        let cache = match crate::services::CacheService::new(redis_url).await {
+        let cache = match crate::services::CacheService::new(&cache_url).await {
             Ok(service) => {
                 tracing::info!("Cache service initialized successfully");
                 Arc::new(tokio::sync::Mutex::new(service))
@@ -82,11 +111,18 @@ impl AppState {
         };
         
         tracing::debug!("Initializing search service...");
-        let search = Arc::new(crate::services::SearchService::new(db.clone()));
+        let search = Arc::new(crate::services::SearchService::new(
+            db.clone(),
+            crate::services::SearchConfig::from_env(),
+        ));
         tracing::info!("Search service initialized");
         
         tracing::debug!("Initializing streaming service...");
-        let streaming = Arc::new(crate::services::StreamingService::new(auth.clone()));
+        let streaming = Arc::new(crate::services::StreamingService::new(
+            auth.clone(),
+            cache.clone(),
+            crate::services::streaming::CrunchyrollProbe::from_env()?,
+        ).with_demo(crate::services::streaming::DemoStreamConfig::from_env()));
         tracing::info!("Streaming service initialized");
         
         tracing::debug!("Initializing metadata service...");
@@ -100,6 +136,27 @@ impl AppState {
         let health = Arc::new(crate::services::HealthService::new(version));
         tracing::info!("Health service initialized");
         
+        tracing::debug!("Initializing recommendation service...");
+        let recommendations = Arc::new(crate::services::RecommendationService::new(
+            db.clone(),
+            crate::services::RecommendationConfig::from_env(),
+        ));
+        tracing::info!("Recommendation service initialized");
+        
+        // Hourly tag-similarity rebuild; the handle lives as long as the state
+        let similarity_rebuild = Arc::new(
+            crate::services::recommendations::SimilarityRebuildTask::spawn(recommendations.clone())
+        );
+        
+        let watch_progress = Arc::new(crate::services::WatchProgressService::new(cache.clone(), db.clone()));
+        let watch_heartbeat = Arc::new(crate::services::WatchHeartbeatService::new(watch_progress.clone()));
+        let stream_sessions = Arc::new(
+            crate::services::StreamSessionService::new(cache.clone())
+                .with_max_concurrent(crate::services::stream_sessions::max_concurrent_streams_from_env())
+        );
+        #[cfg(feature = "metrics")]
+        let metrics = Arc::new(crate::middleware::Metrics::new()?);
+        
         tracing::info!("AppState initialization complete");
         Ok(AppState {
             db,
@@ -109,6 +166,23 @@ impl AppState {
             streaming,
             metadata,
             health,
+            recommendations,
+            similarity_rebuild,
+            watch_progress,
+            watch_heartbeat,
+            stream_sessions,
+            #[cfg(feature = "metrics")]
+            metrics,
+            #[cfg(feature = "metrics")]
+            metrics_endpoint: crate::middleware::MetricsEndpointConfig::from_env(),
+            rate_limit: crate::middleware::RateLimitConfig::from_env(),
+            request_size: crate::middleware::RequestSizeLimitConfig::from_env(),
+            session_cookies: crate::middleware::SessionCookieConfig::from_env(),
+            legacy_fields: crate::api::deprecation::LegacyFieldsConfig::from_env(),
+            api_keys: crate::middleware::ApiKeyConfig::from_env(),
+            write_access: crate::middleware::WriteAccessConfig::from_env(),
+            cors: crate::middleware::CorsConfig::from_env(),
+            mail: crate::services::mailer::MailerConfig::from_env()?,
         })
     }
 }
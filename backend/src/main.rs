@@ -1,25 +1,108 @@
 use anyhow::Result;
+use clap::{Parser, Subcommand};
 use std::net::SocketAddr;
-use tracing_subscriber;
+use std::path::PathBuf;
+
+use kensho_backend::{api, db, middleware, models, services};
 
-mod api;
-mod db;
-mod models;
-mod services;
-mod middleware;
 mod cli;
 
+#[derive(Parser, Debug)]
+#[command(name = "kensho", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Run the API server (the default)
+    Serve,
+    /// Import anime-offline-database.json into SurrealDB
+    Import {
+        /// Path to the anime-offline-database.json file
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+        /// The file as a positional argument, as accepted before --file
+        #[arg(conflicts_with = "file", hide = true)]
+        path: Option<PathBuf>,
+        /// Entries written per database round trip
+        #[arg(long, default_value_t = cli::import::IMPORT_BATCH_SIZE)]
+        batch_size: usize,
+        /// Only import entries scored at least this, on the file's score scale
+        #[arg(long)]
+        min_score: Option<f64>,
+        /// Only import these types, e.g. TV,MOVIE
+        #[arg(long, value_delimiter = ',', value_parser = cli::import::parse_offline_type)]
+        types: Vec<models::anime_offline_db::OfflineAnimeType>,
+        /// Exit with an error when more than this percentage of entries fail or are malformed
+        #[arg(long, default_value_t = 5.0)]
+        max_failed_percent: f64,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
-    
     // Load environment variables
     dotenvy::dotenv().ok();
     
+    let cli = Cli::parse();
+    
+    // Export traces over OTLP when a Collector is configured
+    let otel = match std::env::var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+        Ok(endpoint) if !endpoint.trim().is_empty() => {
+            let config = middleware::OpenTelemetryConfig {
+                endpoint,
+                service_name: std::env::var("OTEL_SERVICE_NAME")
+                    .unwrap_or_else(|_| "kensho-backend".to_string()),
+            };
+            match middleware::init_otel_tracing(&config) {
+                Ok(provider) => Some(provider),
+                Err(e) => {
+                    eprintln!("Failed to set up OpenTelemetry export to {}: {}", config.endpoint, e);
+                    None
+                }
+            }
+        }
+        _ => None,
+    };
+    
+    // Initialize tracing (LOG_FORMAT, LOG_LEVEL)
+    middleware::init_logging(otel.as_ref());
+    
     // Get configuration from environment
     let database_url = std::env::var("DATABASE_URL")
         .unwrap_or_else(|_| "ws://localhost:8000".to_string());
+    
+    let result = match cli.command.unwrap_or(Command::Serve) {
+        Command::Serve => serve(&database_url).await,
+        Command::Import { file, path, batch_size, min_score, types, max_failed_percent } => {
+            let path = file.or(path).unwrap_or_else(|| PathBuf::from("anime-offline-database.json"));
+            let options = cli::import::ImportOptions { batch_size, min_score, types, max_failed_percent };
+            cli::import::run_import(&path, &database_url, &options).await.and_then(|report| {
+                println!("{}", report);
+                if report.failed_percent() > options.max_failed_percent {
+                    anyhow::bail!(
+                        "{:.1}% of entries failed to import, above the {}% limit",
+                        report.failed_percent(), options.max_failed_percent
+                    );
+                }
+                Ok(())
+            })
+        }
+    };
+    
+    // Flush the last batch of spans
+    if let Some(provider) = otel {
+        if let Err(e) = provider.shutdown() {
+            eprintln!("Failed to flush OpenTelemetry spans: {}", e);
+        }
+    }
+    
+    result
+}
+
+async fn serve(database_url: &str) -> Result<()> {
     let redis_url = std::env::var("REDIS_URL")
         .unwrap_or_else(|_| "redis://:kensho_redis_pass@localhost:6379".to_string());
     let jwt_secret = std::env::var("JWT_SECRET")
@@ -30,7 +113,7 @@ async fn main() -> Result<()> {
     
     // Initialize application state
     tracing::info!("Creating application state...");
-    let state = match db::connection::AppState::new(&database_url, &redis_url, jwt_secret).await {
+    let state = match db::connection::AppState::new(database_url, &redis_url, jwt_secret).await {
         Ok(s) => {
             tracing::info!("Application state created successfully");
             s
@@ -41,6 +124,33 @@ async fn main() -> Result<()> {
         }
     };
     
+    // Flush hot playback positions to the database in the background
+    tokio::spawn(services::watch_progress::watch_progress_flush_worker(state.watch_progress.clone()));
+    
+    // Refresh component health (database, Redis, Crunchyroll, ...) for the health endpoints
+    tokio::spawn(services::health::health_check_worker(
+        state.health.clone(),
+        state.clone(),
+        services::health::HealthCheckIntervals::from_env(),
+    ));
+    
+    // Halve popularity scores monthly so old hits fade from the popularity sort
+    tokio::spawn(services::popularity::popularity_decay_worker(state.db.clone()));
+    
+    // Serve /metrics on its own port when configured, away from public traffic
+    #[cfg(feature = "metrics")]
+    if let Some(metrics_port) = state.metrics_endpoint.port {
+        let addr = SocketAddr::from(([0, 0, 0, 0], metrics_port));
+        tracing::info!("Serving metrics on {}", addr);
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let metrics_app = api::routes::create_metrics_router(state.clone());
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, metrics_app).await {
+                tracing::error!("Metrics server failed: {}", e);
+            }
+        });
+    }
+    
     // Create router
     let app = api::routes::create_router(state);
     
@@ -49,7 +159,8 @@ async fn main() -> Result<()> {
     tracing::info!("Starting server on {}", addr);
     
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    // Peer addresses key anonymous rate limits
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
     
     Ok(())
 }
\ No newline at end of file
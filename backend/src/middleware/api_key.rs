@@ -0,0 +1,136 @@
+// API key authentication for partner integrations
+// Keys are configured out of band and each carries a set of scopes; handlers
+// check the scope they need after extraction.
+
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use crate::db::connection::AppState;
+
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Read access to the catalog change feed
+pub const CATALOG_READ_SCOPE: &str = "catalog:read";
+
+/// Configured API keys and their scopes
+#[derive(Clone, Debug, Default)]
+pub struct ApiKeyConfig {
+    keys: HashMap<String, HashSet<String>>,
+}
+
+impl ApiKeyConfig {
+    /// Reads API_KEYS: comma-separated `<key>=<scope>[+<scope>...]` entries,
+    /// e.g. "partner-abc=catalog:read". No keys are accepted when unset.
+    pub fn from_env() -> Self {
+        std::env::var("API_KEYS")
+            .map(|s| Self::parse(&s))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(s: &str) -> Self {
+        let keys = s
+            .split(',')
+            .filter_map(|entry| {
+                let Some((key, scopes)) = entry.trim().split_once('=') else {
+                    if !entry.trim().is_empty() {
+                        tracing::warn!("Ignoring API_KEYS entry without scopes");
+                    }
+                    return None;
+                };
+                let scopes = scopes
+                    .split('+')
+                    .map(str::trim)
+                    .filter(|scope| !scope.is_empty())
+                    .map(str::to_string)
+                    .collect();
+                Some((key.trim().to_string(), scopes))
+            })
+            .filter(|(key, _)| !key.is_empty())
+            .collect();
+
+        ApiKeyConfig { keys }
+    }
+
+    /// Scopes granted to `key`, if it is configured
+    pub fn scopes(&self, key: &str) -> Option<&HashSet<String>> {
+        self.keys.get(key)
+    }
+}
+
+/// Extractor for requests authenticated with an `X-API-Key` header
+pub struct ApiKey {
+    pub scopes: HashSet<String>,
+}
+
+impl ApiKey {
+    pub fn require(&self, scope: &str) -> Result<(), ApiKeyError> {
+        if self.scopes.contains(scope) {
+            Ok(())
+        } else {
+            Err(ApiKeyError::MissingScope)
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<AppState> for ApiKey {
+    type Rejection = ApiKeyError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let key = parts
+            .headers
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(ApiKeyError::Missing)?;
+
+        let scopes = state.api_keys.scopes(key).ok_or(ApiKeyError::Invalid)?;
+
+        Ok(ApiKey { scopes: scopes.clone() })
+    }
+}
+
+/// API key errors
+#[derive(Debug)]
+pub enum ApiKeyError {
+    Missing,
+    Invalid,
+    MissingScope,
+}
+
+impl IntoResponse for ApiKeyError {
+    fn into_response(self) -> Response {
+        let (status, error_message) = match self {
+            ApiKeyError::Missing => (StatusCode::UNAUTHORIZED, "Missing API key"),
+            ApiKeyError::Invalid => (StatusCode::UNAUTHORIZED, "Invalid API key"),
+            ApiKeyError::MissingScope => (StatusCode::FORBIDDEN, "API key lacks the required scope"),
+        };
+
+        let body = Json(json!({
+            "error": error_message,
+            "code": status.as_u16()
+        }));
+
+        (status, body).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_api_keys() {
+        let config = ApiKeyConfig::parse("partner-a=catalog:read, partner-b=catalog:read+catalog:write,bad,=x");
+
+        assert!(config.scopes("partner-a").unwrap().contains(CATALOG_READ_SCOPE));
+        assert_eq!(config.scopes("partner-b").unwrap().len(), 2);
+        assert!(config.scopes("bad").is_none());
+        assert!(config.scopes("partner-").is_none());
+        assert!(ApiKeyConfig::default().scopes("").is_none());
+    }
+}
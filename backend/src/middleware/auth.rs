@@ -3,13 +3,16 @@
 // Reference: spec.md FR-004, FR-005, FR-007
 
 use axum::{
-    extract::{FromRequestParts, State},
+    extract::FromRequestParts,
     http::{header::AUTHORIZATION, request::Parts, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use serde_json::json;
 use crate::db::connection::AppState;
+use crate::middleware::csrf::{cookie_value, SESSION_COOKIE};
+use crate::middleware::error::AppError;
+use crate::middleware::logging::record_user_id;
 use crate::models::Session;
 
 /// Extractor for authenticated requests
@@ -26,20 +29,40 @@ impl FromRequestParts<AppState> for AuthUser {
         parts: &mut Parts,
         state: &AppState,
     ) -> Result<Self, Self::Rejection> {
-        // Extract the Authorization header
+        // Extract the Authorization header, falling back to the session cookie in cookie mode
         let auth_header = parts
             .headers
             .get(AUTHORIZATION)
-            .and_then(|value| value.to_str().ok())
-            .ok_or(AuthError::MissingToken)?;
+            .and_then(|value| value.to_str().ok());
 
-        // Check for Bearer token format
-        let token = auth_header
-            .strip_prefix("Bearer ")
-            .ok_or(AuthError::InvalidToken)?;
+        let token = match auth_header {
+            // Check for Bearer token format
+            Some(header) => header
+                .strip_prefix("Bearer ")
+                .ok_or(AuthError::InvalidToken)?
+                .to_string(),
+            None if state.session_cookies.cookie_mode() => {
+                cookie_value(&parts.headers, SESSION_COOKIE).ok_or(AuthError::MissingToken)?
+            }
+            None => return Err(AuthError::MissingToken),
+        };
+        let token = token.as_str();
 
         // Verify the session using the auth service
         let mut auth_service = state.auth.lock().await;
+
+        // Reject tokens revoked by logout before they expire
+        let revoked = auth_service
+            .is_token_revoked(token)
+            .await
+            .map_err(|e| {
+                tracing::debug!("Token revocation check failed: {}", e);
+                AuthError::InvalidSession
+            })?;
+        if revoked {
+            return Err(AuthError::RevokedToken);
+        }
+
         let session = auth_service
             .verify_session(token)
             .await
@@ -53,10 +76,83 @@ impl FromRequestParts<AppState> for AuthUser {
             return Err(AuthError::ExpiredSession);
         }
 
+        record_user_id(&session.user_id);
         Ok(AuthUser { session })
     }
 }
 
+/// Extractor for admin-only endpoints
+/// Requires a valid session whose token carries the `admin` claim
+pub struct AdminUser {
+    pub session: Session,
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<AppState> for AdminUser {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let AuthUser { session } = AuthUser::from_request_parts(parts, state).await?;
+
+        if !session.admin {
+            return Err(AuthError::Forbidden);
+        }
+
+        Ok(AdminUser { session })
+    }
+}
+
+/// Catalog write access configuration
+#[derive(Clone, Debug, Default)]
+pub struct WriteAccessConfig {
+    /// Let anonymous requests create and edit catalog content.
+    /// For test suites that build fixtures over HTTP; never enable in production.
+    pub open_write: bool,
+}
+
+impl WriteAccessConfig {
+    /// Reads KENSHO_OPEN_WRITE ("true" to disable the admin check)
+    pub fn from_env() -> Self {
+        let open_write = std::env::var("KENSHO_OPEN_WRITE")
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(false);
+
+        if open_write {
+            tracing::warn!("KENSHO_OPEN_WRITE is set: catalog writes do not require an admin session");
+        }
+
+        WriteAccessConfig { open_write }
+    }
+}
+
+/// Extractor guarding anime, episode and tag mutations.
+/// Requires an admin session unless `WriteAccessConfig::open_write` is set,
+/// in which case `session` is None.
+pub struct CatalogWriter {
+    pub session: Option<Session>,
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<AppState> for CatalogWriter {
+    type Rejection = AuthError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        if state.write_access.open_write {
+            return Ok(CatalogWriter { session: None });
+        }
+
+        let AdminUser { session } = AdminUser::from_request_parts(parts, state).await?;
+        Ok(CatalogWriter { session: Some(session) })
+    }
+}
+
 /// Optional authentication extractor
 /// Use this for endpoints that work with or without authentication
 pub struct OptionalAuthUser {
@@ -88,11 +184,17 @@ pub enum AuthError {
     InvalidToken,
     InvalidSession,
     ExpiredSession,
+    RevokedToken,
+    Forbidden,
 }
 
 impl IntoResponse for AuthError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
+            // Same ErrorResponse body as every other authorization failure
+            AuthError::Forbidden => {
+                return AppError::Forbidden("Admin privileges required".to_string()).into_response();
+            }
             AuthError::MissingToken => (
                 StatusCode::UNAUTHORIZED,
                 "Missing authentication token",
@@ -109,6 +211,10 @@ impl IntoResponse for AuthError {
                 StatusCode::UNAUTHORIZED,
                 "Session has expired, please login again",
             ),
+            AuthError::RevokedToken => (
+                StatusCode::UNAUTHORIZED,
+                "Token has been revoked, please login again",
+            ),
         };
 
         let body = Json(json!({
@@ -139,7 +245,7 @@ pub fn require_permission(session: &Session, permission: &str) -> Result<(), Aut
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    
 
     #[test]
     fn test_bearer_token_extraction() {
@@ -3,57 +3,85 @@
 // Reference: plan.md line 38 for constraints
 
 use tower_http::cors::{CorsLayer, Any};
-use axum::http::{Method, HeaderName, header};
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use crate::db::connection::AppState;
+
+/// How long browsers may cache a preflight response, in seconds
+const PREFLIGHT_MAX_AGE_SECS: u64 = 3600;
+
+const ALLOWED_METHODS: [Method; 6] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::DELETE,
+    Method::OPTIONS,
+    Method::HEAD,
+];
+
+fn allowed_headers() -> [HeaderName; 7] {
+    [
+        header::AUTHORIZATION,
+        header::CONTENT_TYPE,
+        header::ACCEPT,
+        header::ORIGIN,
+        HeaderName::from_static("x-requested-with"),
+        HeaderName::from_static("x-request-id"),
+        HeaderName::from_static("x-correlation-id"),
+    ]
+}
+
+fn exposed_headers() -> [HeaderName; 7] {
+    [
+        header::CONTENT_LENGTH,
+        header::CONTENT_TYPE,
+        HeaderName::from_static("x-request-id"),
+        HeaderName::from_static("x-kensho-api-version"),
+        HeaderName::from_static("x-ratelimit-limit"),
+        HeaderName::from_static("x-ratelimit-remaining"),
+        HeaderName::from_static("x-ratelimit-reset"),
+    ]
+}
+
+/// Origins the frontend is served from: CORS_ORIGIN plus the local dev servers
+fn static_origins() -> Vec<String> {
+    let frontend_origin = std::env::var("CORS_ORIGIN")
+        .unwrap_or_else(|_| "http://localhost:8080".to_string());
+    
+    vec![
+        frontend_origin,
+        "http://localhost:3000".to_string(), // Backend dev server
+        "http://localhost:8080".to_string(), // Frontend dev server
+        "http://127.0.0.1:8080".to_string(),
+    ]
+}
 
 /// Configure CORS for the application
 /// Allows the frontend to communicate with the backend
 pub fn cors_layer() -> CorsLayer {
     // Get allowed origins from environment or use defaults
-    let frontend_origin = std::env::var("CORS_ORIGIN")
-        .unwrap_or_else(|_| "http://localhost:8080".to_string());
-    
-    let allowed_origins = vec![
-        frontend_origin.parse().unwrap(),
-        "http://localhost:3000".parse().unwrap(), // Backend dev server
-        "http://localhost:8080".parse().unwrap(), // Frontend dev server
-        "http://127.0.0.1:8080".parse().unwrap(),
-    ];
+    let allowed_origins: Vec<HeaderValue> = static_origins()
+        .iter()
+        .map(|origin| origin.parse().unwrap())
+        .collect();
 
     CorsLayer::new()
         // Allow specific origins
         .allow_origin(allowed_origins)
         // Allow common HTTP methods
-        .allow_methods([
-            Method::GET,
-            Method::POST,
-            Method::PUT,
-            Method::DELETE,
-            Method::OPTIONS,
-            Method::HEAD,
-        ])
+        .allow_methods(ALLOWED_METHODS)
         // Allow common headers
-        .allow_headers([
-            header::AUTHORIZATION,
-            header::CONTENT_TYPE,
-            header::ACCEPT,
-            header::ORIGIN,
-            HeaderName::from_static("x-requested-with"),
-            HeaderName::from_static("x-request-id"),
-            HeaderName::from_static("x-correlation-id"),
-        ])
+        .allow_headers(allowed_headers())
         // Expose headers that the frontend might need
-        .expose_headers([
-            header::CONTENT_LENGTH,
-            header::CONTENT_TYPE,
-            HeaderName::from_static("x-request-id"),
-            HeaderName::from_static("x-ratelimit-limit"),
-            HeaderName::from_static("x-ratelimit-remaining"),
-            HeaderName::from_static("x-ratelimit-reset"),
-        ])
+        .expose_headers(exposed_headers())
         // Allow credentials (cookies, auth headers)
         .allow_credentials(true)
         // Cache preflight requests for 1 hour
-        .max_age(std::time::Duration::from_secs(3600))
+        .max_age(std::time::Duration::from_secs(PREFLIGHT_MAX_AGE_SECS))
 }
 
 /// Permissive CORS for development
@@ -64,24 +92,9 @@ pub fn cors_layer_permissive() -> CorsLayer {
     CorsLayer::new()
         .allow_origin(Any)
         .allow_methods(Any)
-        .allow_headers([
-            header::AUTHORIZATION,
-            header::CONTENT_TYPE,
-            header::ACCEPT,
-            header::ORIGIN,
-            HeaderName::from_static("x-requested-with"),
-            HeaderName::from_static("x-request-id"),
-            HeaderName::from_static("x-correlation-id"),
-        ])
-        .expose_headers([
-            header::CONTENT_LENGTH,
-            header::CONTENT_TYPE,
-            HeaderName::from_static("x-request-id"),
-            HeaderName::from_static("x-ratelimit-limit"),
-            HeaderName::from_static("x-ratelimit-remaining"),
-            HeaderName::from_static("x-ratelimit-reset"),
-        ])
-        .max_age(std::time::Duration::from_secs(3600))
+        .allow_headers(allowed_headers())
+        .expose_headers(exposed_headers())
+        .max_age(std::time::Duration::from_secs(PREFLIGHT_MAX_AGE_SECS))
 }
 
 /// Get the appropriate CORS layer based on environment
@@ -101,6 +114,120 @@ pub fn get_cors_layer() -> CorsLayer {
     }
 }
 
+/// CORS settings for `dynamic_cors_middleware`
+#[derive(Clone, Debug)]
+pub struct CorsConfig {
+    /// Origins allowed without consulting the runtime allow-list
+    pub static_origins: Vec<String>,
+    /// Allow every origin, without credentials; development only
+    pub permissive: bool,
+}
+
+impl CorsConfig {
+    /// Static origins as in `cors_layer`; permissive unless RUST_ENV=production
+    pub fn from_env() -> Self {
+        let permissive = std::env::var("RUST_ENV")
+            .map(|env| env != "production")
+            .unwrap_or(true);
+        
+        if permissive {
+            tracing::warn!("Using permissive CORS configuration - DO NOT use in production!");
+        }
+        
+        CorsConfig {
+            static_origins: static_origins(),
+            permissive,
+        }
+    }
+}
+
+/// Canonical form of an allow-list entry (`scheme://host[:port]`), or None if
+/// `origin` is not an http(s) origin. Matches what browsers send in `Origin`.
+pub fn normalize_origin(origin: &str) -> Option<String> {
+    let url = url::Url::parse(origin.trim()).ok()?;
+    
+    let bare = matches!(url.scheme(), "http" | "https")
+        && url.host().is_some()
+        && url.username().is_empty()
+        && url.password().is_none()
+        && url.path() == "/"
+        && url.query().is_none()
+        && url.fragment().is_none();
+    
+    bare.then(|| url.origin().ascii_serialization())
+}
+
+/// Value for `Access-Control-Allow-Origin`, if `origin` may make requests
+async fn allowed_origin(state: &AppState, origin: &HeaderValue) -> Option<HeaderValue> {
+    if state.cors.permissive {
+        return Some(HeaderValue::from_static("*"));
+    }
+    
+    let origin_str = origin.to_str().ok()?;
+    if state.cors.static_origins.iter().any(|allowed| allowed == origin_str) {
+        return Some(origin.clone());
+    }
+    
+    match state.cache.lock().await.cors_origins().await {
+        Ok(origins) => origins.iter().any(|allowed| allowed == origin_str).then(|| origin.clone()),
+        Err(e) => {
+            tracing::warn!("Failed to load CORS allow-list: {}", e);
+            None
+        }
+    }
+}
+
+fn join_header_values<T: AsRef<str>>(values: impl IntoIterator<Item = T>) -> HeaderValue {
+    let joined = values.into_iter().map(|v| v.as_ref().to_string()).collect::<Vec<_>>().join(",");
+    HeaderValue::from_str(&joined).expect("header names and methods are valid header values")
+}
+
+/// CORS for the static origins plus the runtime allow-list kept in Redis
+/// under `cors:allowed_origins` (managed via /api/admin/cors/origins).
+/// Answers preflights itself; other requests get the CORS headers added to
+/// the response. Disallowed origins simply get no `Access-Control-*` headers.
+pub async fn dynamic_cors_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let allow_origin = match request.headers().get(header::ORIGIN) {
+        Some(origin) => allowed_origin(&state, origin).await,
+        None => None,
+    };
+    
+    let preflight = request.method() == Method::OPTIONS
+        && request.headers().contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
+    
+    let mut response = if preflight {
+        StatusCode::NO_CONTENT.into_response()
+    } else {
+        next.run(request).await
+    };
+    
+    let headers = response.headers_mut();
+    headers.append(header::VARY, HeaderValue::from_static("origin"));
+    
+    let Some(allow_origin) = allow_origin else {
+        return response;
+    };
+    
+    headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, allow_origin);
+    if !state.cors.permissive {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_CREDENTIALS, HeaderValue::from_static("true"));
+    }
+    
+    if preflight {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, join_header_values(ALLOWED_METHODS.iter().map(Method::as_str)));
+        headers.insert(header::ACCESS_CONTROL_ALLOW_HEADERS, join_header_values(allowed_headers()));
+        headers.insert(header::ACCESS_CONTROL_MAX_AGE, HeaderValue::from(PREFLIGHT_MAX_AGE_SECS));
+    } else {
+        headers.insert(header::ACCESS_CONTROL_EXPOSE_HEADERS, join_header_values(exposed_headers()));
+    }
+    
+    response
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,4 +239,16 @@ mod tests {
         let _ = cors_layer_permissive();
         let _ = get_cors_layer();
     }
+
+    #[test]
+    fn test_normalize_origin() {
+        assert_eq!(normalize_origin("https://partner.example").as_deref(), Some("https://partner.example"));
+        assert_eq!(normalize_origin("https://Partner.Example/").as_deref(), Some("https://partner.example"));
+        assert_eq!(normalize_origin("https://partner.example:443").as_deref(), Some("https://partner.example"));
+        assert_eq!(normalize_origin("http://localhost:8081").as_deref(), Some("http://localhost:8081"));
+        assert_eq!(normalize_origin("https://partner.example/app"), None);
+        assert_eq!(normalize_origin("ftp://partner.example"), None);
+        assert_eq!(normalize_origin("null"), None);
+        assert_eq!(normalize_origin("*"), None);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,291 @@
+// Cookie session mode with double-submit CSRF protection
+// In cookie mode the access and refresh tokens travel in HttpOnly cookies, so
+// every mutating request must echo the readable CSRF cookie in X-CSRF-Token.
+
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde_json::json;
+
+/// HttpOnly cookie carrying the access token
+pub const SESSION_COOKIE: &str = "kensho_session";
+/// HttpOnly cookie carrying the refresh token, only sent to /api/auth
+pub const REFRESH_COOKIE: &str = "kensho_refresh";
+/// Script-readable cookie the frontend echoes back in `CSRF_HEADER`
+pub const CSRF_COOKIE: &str = "kensho_csrf";
+pub const CSRF_HEADER: &str = "x-csrf-token";
+
+/// How session tokens are handed to the client
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SessionMode {
+    /// Tokens are returned in the response body and sent back as Bearer headers
+    #[default]
+    Bearer,
+    /// Tokens are set as HttpOnly cookies; mutating requests need a CSRF token
+    Cookie,
+}
+
+/// Session cookie configuration
+#[derive(Clone, Debug)]
+pub struct SessionCookieConfig {
+    pub mode: SessionMode,
+    /// Mark cookies Secure; only disable for local HTTP development
+    pub secure: bool,
+}
+
+impl Default for SessionCookieConfig {
+    fn default() -> Self {
+        SessionCookieConfig {
+            mode: SessionMode::Bearer,
+            secure: true,
+        }
+    }
+}
+
+impl SessionCookieConfig {
+    /// Reads SESSION_MODE ("bearer" or "cookie") and SESSION_COOKIE_SECURE
+    pub fn from_env() -> Self {
+        let mode = match std::env::var("SESSION_MODE").ok().as_deref().map(str::trim) {
+            Some("cookie") => SessionMode::Cookie,
+            Some("bearer") | None => SessionMode::Bearer,
+            Some(other) => {
+                tracing::warn!("Ignoring unknown SESSION_MODE '{}', using bearer", other);
+                SessionMode::Bearer
+            }
+        };
+
+        let secure = std::env::var("SESSION_COOKIE_SECURE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(true);
+
+        SessionCookieConfig { mode, secure }
+    }
+
+    pub fn cookie_mode(&self) -> bool {
+        self.mode == SessionMode::Cookie
+    }
+
+    /// Set-Cookie values for a freshly issued session
+    pub fn session_cookies(
+        &self,
+        token: &str,
+        refresh_token: Option<&str>,
+        csrf_token: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Vec<String> {
+        let max_age = (expires_at - Utc::now()).num_seconds().max(0);
+        let mut cookies = vec![
+            self.cookie(SESSION_COOKIE, token, "/api", true, max_age),
+            self.cookie(CSRF_COOKIE, csrf_token, "/", false, max_age),
+        ];
+
+        if let Some(refresh_token) = refresh_token {
+            cookies.push(self.cookie(REFRESH_COOKIE, refresh_token, "/api/auth", true, max_age));
+        }
+
+        cookies
+    }
+
+    /// Set-Cookie values that remove every session cookie
+    pub fn clear_cookies(&self) -> Vec<String> {
+        vec![
+            self.cookie(SESSION_COOKIE, "", "/api", true, 0),
+            self.cookie(REFRESH_COOKIE, "", "/api/auth", true, 0),
+            self.cookie(CSRF_COOKIE, "", "/", false, 0),
+        ]
+    }
+
+    fn cookie(&self, name: &str, value: &str, path: &str, http_only: bool, max_age: i64) -> String {
+        let mut cookie = format!("{}={}; Path={}; Max-Age={}; SameSite=Strict", name, value, path, max_age);
+        if http_only {
+            cookie.push_str("; HttpOnly");
+        }
+        if self.secure {
+            cookie.push_str("; Secure");
+        }
+        cookie
+    }
+}
+
+/// Random token for the double-submit cookie
+pub fn generate_csrf_token() -> String {
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .expect("System RNG unavailable");
+
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Value of the named cookie from the request's Cookie headers
+pub fn cookie_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.to_string())
+}
+
+/// True when the request authenticates with an Authorization: Bearer header.
+/// Browsers never attach those automatically, so they cannot be forged cross-site.
+fn has_bearer_token(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.starts_with("Bearer "))
+        .unwrap_or(false)
+}
+
+// Compare without short-circuiting so the check doesn't leak a matching prefix
+fn tokens_match(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes().zip(b.bytes()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Double-submit CSRF check for cookie-authenticated mutating requests
+pub async fn csrf_middleware(
+    State(config): State<SessionCookieConfig>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let safe_method = matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    let headers = request.headers();
+
+    if !config.cookie_mode() || safe_method || has_bearer_token(headers) {
+        return next.run(request).await;
+    }
+
+    // Requests without a session cookie aren't riding on ambient credentials
+    if cookie_value(headers, SESSION_COOKIE).is_none() && cookie_value(headers, REFRESH_COOKIE).is_none() {
+        return next.run(request).await;
+    }
+
+    let cookie_token = cookie_value(headers, CSRF_COOKIE);
+    let header_token = headers
+        .get(CSRF_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    match (cookie_token, header_token) {
+        (Some(cookie), Some(header)) if !cookie.is_empty() && tokens_match(&cookie, header) => {
+            next.run(request).await
+        }
+        (_, None) => csrf_error("Missing CSRF token"),
+        _ => csrf_error("CSRF token mismatch"),
+    }
+}
+
+fn csrf_error(message: &str) -> Response {
+    (
+        StatusCode::FORBIDDEN,
+        Json(json!({
+            "error": message,
+            "code": "CSRF_TOKEN_INVALID"
+        })),
+    ).into_response()
+}
+
+/// Append Set-Cookie headers to a response
+pub fn with_cookies(mut response: Response, cookies: Vec<String>) -> Response {
+    for cookie in cookies {
+        if let Ok(value) = HeaderValue::from_str(&cookie) {
+            response.headers_mut().append(header::SET_COOKIE, value);
+        }
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::post, Router};
+    use tower::ServiceExt;
+
+    fn app(mode: SessionMode) -> Router {
+        let config = SessionCookieConfig { mode, secure: false };
+        Router::new()
+            .route("/mutate", post(|| async { "ok" }).get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(config, csrf_middleware))
+    }
+
+    async fn status(app: Router, request: axum::http::Request<Body>) -> StatusCode {
+        app.oneshot(request).await.unwrap().status()
+    }
+
+    fn cookie_post(csrf_header: Option<&str>) -> axum::http::Request<Body> {
+        let mut builder = axum::http::Request::post("/mutate")
+            .header(header::COOKIE, format!("{}=jwt; {}=abc123", SESSION_COOKIE, CSRF_COOKIE));
+        if let Some(token) = csrf_header {
+            builder = builder.header(CSRF_HEADER, token);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cookie_request_without_csrf_header_is_rejected() {
+        assert_eq!(status(app(SessionMode::Cookie), cookie_post(None)).await, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_cookie_request_with_mismatched_token_is_rejected() {
+        assert_eq!(status(app(SessionMode::Cookie), cookie_post(Some("abc124"))).await, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_cookie_request_with_matching_token_passes() {
+        assert_eq!(status(app(SessionMode::Cookie), cookie_post(Some("abc123"))).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_bearer_request_bypasses_csrf_check() {
+        let request = axum::http::Request::post("/mutate")
+            .header(header::AUTHORIZATION, "Bearer jwt")
+            .header(header::COOKIE, format!("{}=jwt", SESSION_COOKIE))
+            .body(Body::empty())
+            .unwrap();
+
+        assert_eq!(status(app(SessionMode::Cookie), request).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_safe_methods_and_bearer_mode_skip_check() {
+        let get = axum::http::Request::get("/mutate")
+            .header(header::COOKIE, format!("{}=jwt", SESSION_COOKIE))
+            .body(Body::empty())
+            .unwrap();
+
+        assert_eq!(status(app(SessionMode::Cookie), get).await, StatusCode::OK);
+        assert_eq!(status(app(SessionMode::Bearer), cookie_post(None)).await, StatusCode::OK);
+    }
+
+    #[test]
+    fn test_cookie_value_parsing() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, HeaderValue::from_static("a=1; kensho_csrf=tok; b=2"));
+
+        assert_eq!(cookie_value(&headers, CSRF_COOKIE).as_deref(), Some("tok"));
+        assert_eq!(cookie_value(&headers, SESSION_COOKIE), None);
+    }
+
+    #[test]
+    fn test_session_cookie_attributes() {
+        let config = SessionCookieConfig { mode: SessionMode::Cookie, secure: true };
+        let cookies = config.session_cookies("jwt", Some("rt"), "csrf", Utc::now() + chrono::Duration::minutes(15));
+
+        let session = cookies.iter().find(|c| c.starts_with(SESSION_COOKIE)).unwrap();
+        assert!(session.contains("HttpOnly") && session.contains("Secure"));
+
+        let csrf = cookies.iter().find(|c| c.starts_with(CSRF_COOKIE)).unwrap();
+        assert!(!csrf.contains("HttpOnly"), "The CSRF cookie must be readable by the frontend");
+        assert_eq!(generate_csrf_token().len(), 64);
+    }
+}
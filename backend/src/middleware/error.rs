@@ -10,6 +10,7 @@ use axum::{
 use serde::{Serialize, Deserialize};
 use serde_json::json;
 use std::fmt;
+use crate::middleware::logging::RequestId;
 
 /// Standard error response structure
 #[derive(Debug, Serialize, Deserialize)]
@@ -82,6 +83,8 @@ impl fmt::Display for AppError {
     }
 }
 
+impl std::error::Error for AppError {}
+
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, code, message, details) = match self {
@@ -199,7 +202,7 @@ impl IntoResponse for AppError {
             code: code.to_string(),
             message,
             details,
-            request_id: None, // TODO: Extract from request extensions
+            request_id: RequestId::current(),
         };
 
         (status, Json(response)).into_response()
@@ -209,6 +212,12 @@ impl IntoResponse for AppError {
 /// Convert from anyhow::Error to AppError
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
+        // Services may return an AppError wrapped in anyhow
+        let err = match err.downcast::<AppError>() {
+            Ok(app_err) => return app_err,
+            Err(err) => err,
+        };
+        
         // Try to downcast to specific error types
         if let Some(db_err) = err.downcast_ref::<surrealdb::Error>() {
             return AppError::DatabaseError(db_err.to_string());
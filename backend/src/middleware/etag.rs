@@ -0,0 +1,169 @@
+// Conditional GETs for anime reads: strong ETags from a SHA-256 of the body,
+// and 304 Not Modified when If-None-Match still matches.
+// The last ETag per request is kept in the cache under `etag:<scope>` (an anime
+// id, or `season:<year>:<season>` for browse pages), so a matching revalidation
+// is answered without running the handler at all. A route opts in with one
+// `.layer(from_fn_with_state(state, <middleware>))`.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Path, Request, State},
+    http::{
+        header::{ACCEPT_LANGUAGE, ETAG, IF_NONE_MATCH},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::time::Duration;
+use uuid::Uuid;
+use crate::db::connection::AppState;
+use crate::models::AnimeSeason;
+
+/// How long a remembered ETag is trusted. Writes invalidate it explicitly;
+/// the TTL bounds staleness from paths that do not (bulk import, `doctor`).
+pub const ETAG_TTL: Duration = Duration::from_secs(60 * 60);
+pub const SEASON_ETAG_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Quoted, strong ETag of a response body
+pub fn compute_etag(body: &[u8]) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, body);
+    let hex: String = digest.as_ref().iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!("\"{}\"", hex)
+}
+
+/// Whether `If-None-Match` lists `etag` (or is `*`). Weak comparison, as RFC 9110
+/// requires for If-None-Match, so a `W/` prefix added by a proxy still matches.
+pub fn if_none_match_matches(headers: &HeaderMap, etag: &str) -> bool {
+    let Some(value) = headers.get(IF_NONE_MATCH).and_then(|value| value.to_str().ok()) else {
+        return false;
+    };
+
+    value
+        .split(',')
+        .map(|candidate| candidate.trim())
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+pub(crate) fn not_modified(etag: &str) -> Response {
+    let mut response = StatusCode::NOT_MODIFIED.into_response();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        response.headers_mut().insert(ETAG, value);
+    }
+    response
+}
+
+/// Route layer for `GET /api/anime/:id` and `GET /api/anime/:id/episodes`
+pub async fn etag_middleware(
+    Path(anime_id): Path<Uuid>,
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    conditional_response(&state, &anime_id.to_string(), ETAG_TTL, req, next).await
+}
+
+/// Route layer for `GET /api/browse/season/:year/:season`. Popularity moves the
+/// order without any catalog write, so its ETags are trusted for less long.
+pub async fn season_etag_middleware(
+    Path((year, season)): Path<(u16, String)>,
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    conditional_response(&state, &season_scope(year, &season), SEASON_ETAG_TTL, req, next).await
+}
+
+/// Forget a season's browse ETags, e.g. after one of its anime changed
+pub async fn invalidate_season_etags(state: &AppState, season: &AnimeSeason) {
+    let scope = season_scope(season.year, season.season.as_str());
+    if let Err(e) = state.cache.lock().await.invalidate_etags(&scope).await {
+        tracing::warn!("Failed to invalidate ETags for {}: {}", scope, e);
+    }
+}
+
+fn season_scope(year: u16, season: &str) -> String {
+    format!("season:{}:{}", year, season.to_lowercase())
+}
+
+async fn conditional_response(state: &AppState, scope: &str, ttl: Duration, req: Request, next: Next) -> Response {
+    // The same resource is served in several shapes; each gets its own entry
+    let request_key = format!(
+        "{}|{}",
+        req.uri().path_and_query().map(|pq| pq.as_str()).unwrap_or(""),
+        req.headers().get(ACCEPT_LANGUAGE).and_then(|value| value.to_str().ok()).unwrap_or(""),
+    );
+
+    let cached = state.cache.lock().await
+        .get_etags(scope)
+        .await
+        .unwrap_or_default()
+        .remove(&request_key);
+    if let Some(etag) = cached {
+        if if_none_match_matches(req.headers(), &etag) {
+            return not_modified(&etag);
+        }
+    }
+
+    let request_headers = req.headers().clone();
+    let response = next.run(req).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!("Failed to buffer response for ETag: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let etag = compute_etag(&bytes);
+    if let Err(e) = state.cache.lock().await
+        .store_etag(scope, &request_key, &etag, ttl)
+        .await
+    {
+        tracing::debug!("Failed to cache ETag for {}: {}", scope, e);
+    }
+
+    if if_none_match_matches(&request_headers, &etag) {
+        return not_modified(&etag);
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        parts.headers.insert(ETAG, value);
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_etag_is_quoted_sha256() {
+        let etag = compute_etag(b"{}");
+        assert_eq!(etag.len(), 66);
+        assert!(etag.starts_with('"') && etag.ends_with('"'));
+        assert_eq!(etag, compute_etag(b"{}"));
+        assert_ne!(etag, compute_etag(b"[]"));
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let etag = compute_etag(b"body");
+        let headers = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(IF_NONE_MATCH, HeaderValue::from_str(value).unwrap());
+            headers
+        };
+
+        assert!(if_none_match_matches(&headers(&etag), &etag));
+        assert!(if_none_match_matches(&headers(&format!("\"other\", W/{}", etag)), &etag));
+        assert!(if_none_match_matches(&headers("*"), &etag));
+        assert!(!if_none_match_matches(&headers("\"other\""), &etag));
+        assert!(!if_none_match_matches(&HeaderMap::new(), &etag));
+    }
+}
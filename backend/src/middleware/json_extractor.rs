@@ -32,12 +32,18 @@ where
 
 /// JSON parsing error with detailed error messages
 pub struct JsonError {
+    status: StatusCode,
     message: String,
     details: Option<String>,
 }
 
 impl JsonError {
     fn from_rejection(rejection: JsonRejection) -> Self {
+        // 400, except a body cut off by RequestSizeLimitLayer, which stays 413
+        let status = match rejection.status() {
+            StatusCode::PAYLOAD_TOO_LARGE => StatusCode::PAYLOAD_TOO_LARGE,
+            _ => StatusCode::BAD_REQUEST,
+        };
         let (message, details) = match rejection {
             JsonRejection::JsonDataError(err) => (
                 "Invalid JSON format".to_string(),
@@ -51,6 +57,10 @@ impl JsonError {
                 "Missing Content-Type header".to_string(),
                 Some("Content-Type must be 'application/json'".to_string()),
             ),
+            JsonRejection::BytesRejection(err) if status == StatusCode::PAYLOAD_TOO_LARGE => (
+                "Request body too large".to_string(),
+                Some(err.body_text()),
+            ),
             JsonRejection::BytesRejection(_) => (
                 "Failed to read request body".to_string(),
                 None,
@@ -61,13 +71,13 @@ impl JsonError {
             ),
         };
 
-        JsonError { message, details }
+        JsonError { status, message, details }
     }
 }
 
 impl IntoResponse for JsonError {
     fn into_response(self) -> Response {
-        let status = StatusCode::BAD_REQUEST;
+        let status = self.status;
         
         let body = json!({
             "error": self.message,
@@ -0,0 +1,145 @@
+// Accept-Language driven localization of display-only response fields
+// Canonical machine fields (season, year, air_date) are never localized; handlers
+// add `*_display` companions formatted for the resolved locale.
+
+use axum::{
+    extract::FromRequestParts,
+    http::{header::ACCEPT_LANGUAGE, request::Parts},
+};
+use chrono::{Datelike, NaiveDate};
+use std::convert::Infallible;
+
+/// Supported display locales; anything else falls back to English
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    Ja,
+    De,
+}
+
+impl Locale {
+    fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split(['-', '_']).next()?.trim().to_lowercase();
+        match primary.as_str() {
+            "en" => Some(Locale::En),
+            "ja" => Some(Locale::Ja),
+            "de" => Some(Locale::De),
+            _ => None,
+        }
+    }
+
+    /// Best supported locale from an Accept-Language header, honouring q-values
+    pub fn from_accept_language(header: &str) -> Self {
+        let mut candidates: Vec<(&str, f32)> = header
+            .split(',')
+            .filter_map(|part| {
+                let mut pieces = part.split(';');
+                let tag = pieces.next()?.trim();
+                let quality = pieces
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .and_then(|q| q.trim().parse().ok())
+                    .unwrap_or(1.0);
+                Some((tag, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .collect();
+
+        // Stable sort keeps header order between equal weights
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        candidates
+            .into_iter()
+            .find_map(|(tag, _)| Self::from_tag(tag))
+            .unwrap_or_default()
+    }
+
+    /// Season and year for display, e.g. "Fall 2024", "Herbst 2024", "2024年秋"
+    pub fn season_display(&self, season: &str, year: u16) -> String {
+        let season = season.to_lowercase();
+        let name = match (self, season.as_str()) {
+            (Locale::En, "winter") => "Winter",
+            (Locale::En, "spring") => "Spring",
+            (Locale::En, "summer") => "Summer",
+            (Locale::En, "fall") => "Fall",
+            (Locale::De, "winter") => "Winter",
+            (Locale::De, "spring") => "Frühling",
+            (Locale::De, "summer") => "Sommer",
+            (Locale::De, "fall") => "Herbst",
+            (Locale::Ja, "winter") => "冬",
+            (Locale::Ja, "spring") => "春",
+            (Locale::Ja, "summer") => "夏",
+            (Locale::Ja, "fall") => "秋",
+            _ => season.as_str(),
+        };
+
+        match self {
+            Locale::Ja => format!("{}年{}", year, name),
+            _ => format!("{} {}", name, year),
+        }
+    }
+
+    /// Date for display, e.g. "March 10, 2024", "10. März 2024", "2024年3月10日"
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        const EN_MONTHS: [&str; 12] = [
+            "January", "February", "March", "April", "May", "June",
+            "July", "August", "September", "October", "November", "December",
+        ];
+        const DE_MONTHS: [&str; 12] = [
+            "Januar", "Februar", "März", "April", "Mai", "Juni",
+            "Juli", "August", "September", "Oktober", "November", "Dezember",
+        ];
+
+        let month = date.month0() as usize;
+        match self {
+            Locale::En => format!("{} {}, {}", EN_MONTHS[month], date.day(), date.year()),
+            Locale::De => format!("{}. {} {}", date.day(), DE_MONTHS[month], date.year()),
+            Locale::Ja => format!("{}年{}月{}日", date.year(), date.month(), date.day()),
+        }
+    }
+}
+
+/// Resolves the request locale from Accept-Language; never rejects
+#[async_trait::async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for Locale {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .headers
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .map(Locale::from_accept_language)
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accept_language_resolution() {
+        assert_eq!(Locale::from_accept_language("de-DE,de;q=0.9,en;q=0.8"), Locale::De);
+        assert_eq!(Locale::from_accept_language("fr-FR, ja;q=0.5, en;q=0.4"), Locale::Ja);
+        assert_eq!(Locale::from_accept_language("en;q=0.3, ja"), Locale::Ja);
+        assert_eq!(Locale::from_accept_language("fr, *;q=0.1"), Locale::En);
+        assert_eq!(Locale::from_accept_language("ja;q=0, de"), Locale::De);
+    }
+
+    #[test]
+    fn test_season_display() {
+        assert_eq!(Locale::En.season_display("fall", 2024), "Fall 2024");
+        assert_eq!(Locale::De.season_display("fall", 2024), "Herbst 2024");
+        assert_eq!(Locale::Ja.season_display("FALL", 2024), "2024年秋");
+    }
+
+    #[test]
+    fn test_format_date() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+
+        assert_eq!(Locale::En.format_date(date), "March 10, 2024");
+        assert_eq!(Locale::De.format_date(date), "10. März 2024");
+        assert_eq!(Locale::Ja.format_date(date), "2024年3月10日");
+    }
+}
@@ -3,92 +3,125 @@
 // Reference: plan.md lines 69-71
 
 use axum::{
-    body::{Body, Bytes},
     extract::Request,
-    http::{StatusCode, HeaderMap},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
     middleware::Next,
-    response::{IntoResponse, Response},
+    response::Response,
 };
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::Resource;
 use std::time::Instant;
 use tower_http::trace::{TraceLayer, DefaultMakeSpan, DefaultOnRequest, DefaultOnResponse};
-use tracing::{Level, Span};
+use tracing::{Instrument, Level, Span};
+use tracing_subscriber::filter::LevelFilter;
 use uuid::Uuid;
 
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Longest client-supplied request id that is honored; longer ones are replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    /// Request being handled on the current task, set by `logging_middleware`
+    static CURRENT_REQUEST_ID: RequestId;
+}
+
 /// Request ID extension for tracing requests through the system
 #[derive(Clone, Debug)]
 pub struct RequestId(pub String);
 
 impl RequestId {
+    /// Time-ordered UUID v7, so ids sort by arrival in logs
     pub fn new() -> Self {
-        RequestId(Uuid::new_v4().to_string())
+        RequestId(Uuid::now_v7().to_string())
+    }
+
+    /// The incoming X-Request-Id when it is short, printable ASCII, so a proxy's
+    /// id carries through; a new id otherwise
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .filter(|id| !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic()))
+            .map(|id| RequestId(id.to_string()))
+            .unwrap_or_else(RequestId::new)
+    }
+
+    /// Id of the request handled on this task, for error bodies built away
+    /// from the request, e.g. `AppError::into_response`
+    pub fn current() -> Option<String> {
+        CURRENT_REQUEST_ID.try_with(|id| id.0.clone()).ok()
+    }
+
+    /// Span carrying this request id. Work done inside it, including service
+    /// calls and their spans, is logged with the `request_id` field.
+    pub fn span(&self) -> Span {
+        tracing::info_span!("request", request_id = %self.0)
     }
 }
 
-/// Logging middleware that adds request ID and logs request/response details
+impl Default for RequestId {
+    fn default() -> Self {
+        RequestId::new()
+    }
+}
+
+/// Record the authenticated user on the request span, so the completion log
+/// and everything logged after authentication carry `user_id`
+pub fn record_user_id(user_id: &str) {
+    Span::current().record("user_id", user_id);
+}
+
+/// Logging middleware: assigns the request id, echoes it in X-Request-Id, and
+/// logs each request once it completes with method, path, status, latency and
+/// the user when one authenticated
 pub async fn logging_middleware(
     mut req: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    // Generate or extract request ID
-    let request_id = req
-        .headers()
-        .get("x-request-id")
-        .and_then(|v| v.to_str().ok())
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| Uuid::new_v4().to_string());
-
-    // Add request ID to extensions for use in handlers
-    req.extensions_mut().insert(RequestId(request_id.clone()));
-
-    // Log request details
+    let id = RequestId::from_headers(req.headers());
     let method = req.method().clone();
-    let uri = req.uri().clone();
-    let start = Instant::now();
-
-    tracing::info!(
-        request_id = %request_id,
+    let path = req.uri().path().to_string();
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id.0,
         method = %method,
-        uri = %uri,
-        "Incoming request"
+        path = %path,
+        user_id = tracing::field::Empty,
     );
 
-    // Call the next middleware/handler
-    let response = next.run(req).await;
-    
-    // Calculate request duration
-    let duration = start.elapsed();
-    let status = response.status();
+    // Add request ID to extensions for use in handlers; work spawned off the
+    // request task can re-enter it with `RequestId::span`
+    req.extensions_mut().insert(id.clone());
+    let start = Instant::now();
 
-    // Log response details
-    if status.is_success() {
-        tracing::info!(
-            request_id = %request_id,
-            method = %method,
-            uri = %uri,
-            status = %status,
-            duration_ms = %duration.as_millis(),
-            "Request completed successfully"
-        );
-    } else if status.is_client_error() {
-        tracing::warn!(
-            request_id = %request_id,
-            method = %method,
-            uri = %uri,
-            status = %status,
-            duration_ms = %duration.as_millis(),
-            "Client error response"
-        );
-    } else {
-        tracing::error!(
-            request_id = %request_id,
-            method = %method,
-            uri = %uri,
-            status = %status,
-            duration_ms = %duration.as_millis(),
-            "Server error response"
-        );
+    // Call the next middleware/handler inside the request span so service
+    // spans nest under it
+    let mut response = CURRENT_REQUEST_ID
+        .scope(id.clone(), next.run(req).instrument(span.clone()))
+        .await;
+    
+    if let Ok(value) = HeaderValue::from_str(&id.0) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
     }
 
+    let latency_ms = start.elapsed().as_millis() as u64;
+    let status = response.status().as_u16();
+
+    // Inside the span so user_id, recorded during the request, is included
+    span.in_scope(|| {
+        if response.status().is_server_error() {
+            tracing::error!(request_id = %id.0, method = %method, path = %path, status, latency_ms, "Server error response");
+        } else if response.status().is_client_error() {
+            tracing::warn!(request_id = %id.0, method = %method, path = %path, status, latency_ms, "Client error response");
+        } else {
+            tracing::info!(request_id = %id.0, method = %method, path = %path, status, latency_ms, "Request completed");
+        }
+    });
+
     Ok(response)
 }
 
@@ -131,29 +164,144 @@ pub async fn slow_request_logger(
     Ok(response)
 }
 
+/// Log output format
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// One JSON object per event, including the current span and its parents
+    #[default]
+    Json,
+    /// Multi-line human readable output for local development
+    Pretty,
+}
+
 /// Structured logging configuration
-pub fn init_logging() {
+#[derive(Clone, Debug)]
+pub struct LoggingConfig {
+    pub format: LogFormat,
+    /// Default level; RUST_LOG directives take precedence when set
+    pub level: LevelFilter,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig {
+            format: LogFormat::Json,
+            level: LevelFilter::INFO,
+        }
+    }
+}
+
+impl LoggingConfig {
+    /// Reads LOG_FORMAT ("json" or "pretty") and LOG_LEVEL ("trace" to "error", or "off")
+    pub fn from_env() -> Self {
+        let format = match std::env::var("LOG_FORMAT").ok().as_deref().map(str::trim) {
+            Some("pretty") => LogFormat::Pretty,
+            Some("json") | None => LogFormat::Json,
+            Some(other) => {
+                eprintln!("Ignoring unknown LOG_FORMAT '{}', using json", other);
+                LogFormat::Json
+            }
+        };
+
+        let level = match std::env::var("LOG_LEVEL") {
+            Ok(level) => level.trim().parse().unwrap_or_else(|_| {
+                eprintln!("Ignoring unknown LOG_LEVEL '{}', using info", level);
+                LevelFilter::INFO
+            }),
+            Err(_) => LevelFilter::INFO,
+        };
+
+        LoggingConfig { format, level }
+    }
+
+    fn env_filter(&self) -> tracing_subscriber::EnvFilter {
+        tracing_subscriber::EnvFilter::builder()
+            .with_default_directive(self.level.into())
+            .from_env_lossy()
+    }
+}
+
+/// Where spans are exported over OTLP, and the service they are reported as
+#[derive(Clone, Debug)]
+pub struct OpenTelemetryConfig {
+    /// Collector endpoint, e.g. http://localhost:4317 (OTLP over gRPC)
+    pub endpoint: String,
+    pub service_name: String,
+}
+
+impl OpenTelemetryConfig {
+    /// Resource attributes every exported span carries: `service.name`,
+    /// `service.version`, and `deployment.environment` from RUST_ENV
+    fn resource(&self) -> Resource {
+        let environment = std::env::var("RUST_ENV").unwrap_or_else(|_| "development".to_string());
+        
+        Resource::new([
+            KeyValue::new("service.name", self.service_name.clone()),
+            KeyValue::new("service.version", env!("CARGO_PKG_VERSION")),
+            KeyValue::new("deployment.environment", environment),
+        ])
+    }
+}
+
+/// Set up batched OTLP span export to the configured Collector and register
+/// it as the global tracer provider. Pass the provider to `init_logging` so
+/// `tracing` spans are exported, and shut it down on exit to flush the last batch.
+pub fn init_otel_tracing(config: &OpenTelemetryConfig) -> anyhow::Result<TracerProvider> {
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_tonic()
+        .with_endpoint(config.endpoint.clone())
+        .build()?;
+    
+    let provider = TracerProvider::builder()
+        .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+        .with_resource(config.resource())
+        .build();
+    
+    opentelemetry::global::set_tracer_provider(provider.clone());
+    Ok(provider)
+}
+
+/// Layer turning `tracing` spans into OpenTelemetry spans of `provider`
+fn otel_layer<S>(provider: &TracerProvider) -> tracing_opentelemetry::OpenTelemetryLayer<S, opentelemetry_sdk::trace::Tracer>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+}
+
+/// Install the global subscriber configured from LOG_FORMAT and LOG_LEVEL,
+/// also exporting spans through `otel` when given
+pub fn init_logging(otel: Option<&TracerProvider>) {
     use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
     
-    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| {
-            // Default to info level for our crate, warn for others
-            "kensho_backend=info,tower_http=debug,warn".into()
-        });
+    let config = LoggingConfig::from_env();
+    let registry = tracing_subscriber::registry()
+        .with(config.env_filter())
+        .with(otel.map(otel_layer));
 
-    let fmt_layer = tracing_subscriber::fmt::layer()
-        .with_target(true)
-        .with_level(true)
-        .with_thread_ids(true)
-        .with_thread_names(true)
-        .json(); // Use JSON format for structured logging
-
-    tracing_subscriber::registry()
-        .with(env_filter)
-        .with(fmt_layer)
-        .init();
+    match config.format {
+        LogFormat::Json => registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_target(true)
+                    .with_thread_ids(true)
+                    .with_thread_names(true)
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(true),
+            )
+            .init(),
+        LogFormat::Pretty => registry
+            .with(tracing_subscriber::fmt::layer().with_target(true).pretty())
+            .init(),
+    }
     
-    tracing::info!("Logging initialized");
+    tracing::info!(
+        format = ?config.format,
+        level = %config.level,
+        otel = otel.is_some(),
+        "Logging initialized"
+    );
 }
 
 #[cfg(test)]
@@ -168,8 +316,108 @@ mod tests {
         // Should generate different IDs
         assert_ne!(id1.0, id2.0);
         
-        // Should be valid UUIDs
-        assert!(Uuid::parse_str(&id1.0).is_ok());
-        assert!(Uuid::parse_str(&id2.0).is_ok());
+        // Should be valid, time-ordered UUIDs
+        assert_eq!(Uuid::parse_str(&id1.0).unwrap().get_version_num(), 7);
+        assert_eq!(Uuid::parse_str(&id2.0).unwrap().get_version_num(), 7);
+    }
+
+    #[test]
+    fn test_request_id_honors_sane_incoming_header() {
+        let with = |value: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(REQUEST_ID_HEADER, HeaderValue::from_str(value).unwrap());
+            RequestId::from_headers(&headers).0
+        };
+
+        assert_eq!(with("proxy-abc-123"), "proxy-abc-123");
+        assert_ne!(with("has spaces"), "has spaces");
+        assert_ne!(with(&"x".repeat(MAX_REQUEST_ID_LEN + 1)).len(), MAX_REQUEST_ID_LEN + 1);
+        assert!(Uuid::parse_str(&RequestId::from_headers(&HeaderMap::new()).0).is_ok());
+    }
+
+    #[test]
+    fn test_logging_config_defaults() {
+        let config = LoggingConfig::default();
+        assert_eq!(config.format, LogFormat::Json);
+        assert_eq!(config.level, LevelFilter::INFO);
+    }
+
+    #[tokio::test]
+    async fn test_database_spans_are_exported_with_db_system() {
+        use opentelemetry_sdk::testing::trace::InMemorySpanExporterBuilder;
+        use tracing_subscriber::layer::SubscriberExt;
+        use crate::services::DatabaseService;
+
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let config = OpenTelemetryConfig {
+            endpoint: "http://localhost:4317".to_string(),
+            service_name: "kensho-test".to_string(),
+        };
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .with_resource(config.resource())
+            .build();
+        let subscriber = tracing_subscriber::registry().with(otel_layer(&provider));
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        // Never connected, so the query fails, but its span is still exported
+        let db = DatabaseService::disconnected();
+        assert!(db.get_anime(Uuid::nil()).await.is_err());
+        provider.force_flush();
+
+        let spans = exporter.get_finished_spans().unwrap();
+        let span = spans
+            .iter()
+            .find(|span| span.name == "get_anime")
+            .expect("get_anime span should be exported");
+        assert!(span.attributes.contains(&KeyValue::new("db.system", "surrealdb")));
+
+        let resource = config.resource();
+        assert_eq!(resource.get(opentelemetry::Key::new("service.name")), Some("kensho-test".into()));
+        assert!(resource.get(opentelemetry::Key::new("service.version")).is_some());
+        assert!(resource.get(opentelemetry::Key::new("deployment.environment")).is_some());
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn test_database_error_spans_carry_request_id() {
+        use axum::{body::Body, routing::get, Router};
+        use std::sync::Arc;
+        use tower::ServiceExt;
+        use crate::services::DatabaseService;
+
+        // Never connected, so every query fails
+        let db = Arc::new(DatabaseService::disconnected());
+        let app = Router::new()
+            .route("/anime", get(move || {
+                let db = db.clone();
+                async move {
+                    match db.get_anime(Uuid::nil()).await {
+                        Ok(_) => StatusCode::OK,
+                        Err(_) => StatusCode::INTERNAL_SERVER_ERROR,
+                    }
+                }
+            }))
+            .layer(axum::middleware::from_fn(logging_middleware));
+
+        let request = Request::get("/anime")
+            .header("x-request-id", "req-db-error-42")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        // The error event is emitted inside get_anime's span, nested in the request span
+        logs_assert(|lines: &[&str]| {
+            let errors: Vec<_> = lines
+                .iter()
+                .filter(|line| line.contains("ERROR") && line.contains("get_anime"))
+                .collect();
+            match errors.as_slice() {
+                [] => Err("no database error was logged".to_string()),
+                errors if errors.iter().all(|line| line.contains("request_id=req-db-error-42")) => Ok(()),
+                errors => Err(format!("database error without request id: {:?}", errors)),
+            }
+        });
     }
 }
\ No newline at end of file
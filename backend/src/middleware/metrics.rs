@@ -0,0 +1,332 @@
+// Prometheus metrics: per-route request instrumentation and dependency gauges
+// Scraped from GET /metrics in the Prometheus text exposition format
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use anyhow::Result;
+use prometheus::{
+    Encoder, GaugeVec, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use std::sync::Arc;
+use std::time::Instant;
+use crate::services::health::{ComponentHealth, HealthStatus};
+use crate::services::resilient::CircuitState;
+
+/// Latency buckets in seconds, from cache hits up to slow upstream calls
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Health statuses exported as one series each, so exactly one is set per component
+const HEALTH_STATUSES: [HealthStatus; 3] = [
+    HealthStatus::Healthy,
+    HealthStatus::Degraded,
+    HealthStatus::Unhealthy,
+];
+
+/// Where /metrics is served
+#[derive(Clone, Debug, Default)]
+pub struct MetricsEndpointConfig {
+    /// Serve /metrics on its own listener on this port, and not on the API
+    /// port, so it can stay off the public network. None serves it alongside the API.
+    pub port: Option<u16>,
+}
+
+impl MetricsEndpointConfig {
+    /// Reads METRICS_PORT
+    pub fn from_env() -> Self {
+        let port = std::env::var("METRICS_PORT").ok().and_then(|port| match port.trim().parse() {
+            Ok(port) => Some(port),
+            Err(_) => {
+                tracing::warn!("Ignoring invalid METRICS_PORT '{}'", port);
+                None
+            }
+        });
+
+        MetricsEndpointConfig { port }
+    }
+}
+
+/// Metric registry shared by the request middleware and the /metrics endpoint
+pub struct Metrics {
+    registry: Registry,
+    requests_total: IntCounterVec,
+    responses_total: IntCounterVec,
+    errors_total: IntCounterVec,
+    request_duration: HistogramVec,
+    circuit_breaker_state: IntGaugeVec,
+    circuit_breaker_open_count: IntGauge,
+    db_anime_count: IntGauge,
+    redis_connected: IntGauge,
+    component_health: IntGaugeVec,
+    component_latency: GaugeVec,
+    cache_requests_total: IntCounterVec,
+}
+
+impl Metrics {
+    pub fn new() -> Result<Self> {
+        let registry = Registry::new();
+
+        let requests_total = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by route, method and status code"),
+            &["method", "route", "status"],
+        )?;
+        let responses_total = IntCounterVec::new(
+            Opts::new("http_responses_total", "HTTP responses by status class (2xx, 4xx, ...)"),
+            &["class"],
+        )?;
+        let errors_total = IntCounterVec::new(
+            Opts::new("http_errors_total", "HTTP responses with a 4xx or 5xx status, by status code"),
+            &["status"],
+        )?;
+        let request_duration = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency by route")
+                .buckets(LATENCY_BUCKETS.to_vec()),
+            &["method", "route"],
+        )?;
+        let circuit_breaker_state = IntGaugeVec::new(
+            Opts::new(
+                "circuit_breaker_state",
+                "Circuit breaker state per upstream host (0 = closed, 1 = open, 2 = half-open)",
+            ),
+            &["host"],
+        )?;
+        let circuit_breaker_open_count = IntGauge::new(
+            "circuit_breaker_open_count",
+            "Number of upstream hosts whose circuit breaker is open",
+        )?;
+        let db_anime_count = IntGauge::new("db_anime_count", "Anime records in the database")?;
+        let redis_connected = IntGauge::new(
+            "redis_connected",
+            "1 if the cache backend (Redis in production) answered the last probe, else 0",
+        )?;
+        let component_health = IntGaugeVec::new(
+            Opts::new(
+                "health_component_status",
+                "Last health check result per component; 1 for the current status",
+            ),
+            &["component", "status"],
+        )?;
+
+        let component_latency = GaugeVec::new(
+            Opts::new(
+                "health_component_latency_seconds",
+                "Latency of the last health check per component",
+            ),
+            &["component"],
+        )?;
+        let cache_requests_total = IntCounterVec::new(
+            Opts::new("cache_requests_total", "Response cache lookups by cache and result (hit or miss)"),
+            &["cache", "result"],
+        )?;
+
+        registry.register(Box::new(requests_total.clone()))?;
+        registry.register(Box::new(responses_total.clone()))?;
+        registry.register(Box::new(errors_total.clone()))?;
+        registry.register(Box::new(request_duration.clone()))?;
+        registry.register(Box::new(circuit_breaker_state.clone()))?;
+        registry.register(Box::new(circuit_breaker_open_count.clone()))?;
+        registry.register(Box::new(db_anime_count.clone()))?;
+        registry.register(Box::new(redis_connected.clone()))?;
+        registry.register(Box::new(component_health.clone()))?;
+        registry.register(Box::new(component_latency.clone()))?;
+        registry.register(Box::new(cache_requests_total.clone()))?;
+
+        Ok(Metrics {
+            registry,
+            requests_total,
+            responses_total,
+            errors_total,
+            request_duration,
+            circuit_breaker_state,
+            circuit_breaker_open_count,
+            db_anime_count,
+            redis_connected,
+            component_health,
+            component_latency,
+            cache_requests_total,
+        })
+    }
+
+    /// Record one completed request
+    pub fn observe_request(&self, method: &str, route: &str, status: u16, seconds: f64) {
+        self.requests_total
+            .with_label_values(&[method, route, status.to_string().as_str()])
+            .inc();
+        self.responses_total
+            .with_label_values(&[format!("{}xx", status / 100).as_str()])
+            .inc();
+        if status >= 400 {
+            self.errors_total
+                .with_label_values(&[status.to_string().as_str()])
+                .inc();
+        }
+        self.request_duration
+            .with_label_values(&[method, route])
+            .observe(seconds);
+    }
+
+    /// Record one lookup in `cache`
+    pub fn observe_cache(&self, cache: &str, hit: bool) {
+        self.cache_requests_total
+            .with_label_values(&[cache, if hit { "hit" } else { "miss" }])
+            .inc();
+    }
+
+    /// Replace the circuit breaker gauges with a fresh snapshot
+    pub fn set_circuit_states(&self, states: &[(String, CircuitState)]) {
+        self.circuit_breaker_state.reset();
+        for (host, state) in states {
+            self.circuit_breaker_state
+                .with_label_values(&[host.as_str()])
+                .set(state.gauge_value());
+        }
+        let open = states.iter().filter(|(_, state)| matches!(state, CircuitState::Open(_))).count();
+        self.circuit_breaker_open_count.set(open as i64);
+    }
+
+    pub fn set_anime_count(&self, count: usize) {
+        self.db_anime_count.set(count as i64);
+    }
+
+    pub fn set_redis_connected(&self, connected: bool) {
+        self.redis_connected.set(connected as i64);
+    }
+
+    /// Replace the component health gauges with the latest checks
+    pub fn set_component_health(&self, checks: &[ComponentHealth]) {
+        self.component_health.reset();
+        self.component_latency.reset();
+        for check in checks {
+            self.component_latency
+                .with_label_values(&[check.name.as_str()])
+                .set(check.latency_ms as f64 / 1000.0);
+            for status in &HEALTH_STATUSES {
+                self.component_health
+                    .with_label_values(&[check.name.as_str(), status_label(status)])
+                    .set((check.status == *status) as i64);
+            }
+        }
+    }
+
+    /// Encode every registered metric in the Prometheus text format
+    pub fn render(&self) -> Result<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+fn status_label(status: &HealthStatus) -> &'static str {
+    match status {
+        HealthStatus::Healthy => "healthy",
+        HealthStatus::Degraded => "degraded",
+        HealthStatus::Unhealthy => "unhealthy",
+    }
+}
+
+/// Count and time every routed request. Installed as a route layer so the
+/// matched route template (e.g. `/api/anime/:id`) is available as the label;
+/// raw paths would give one series per id.
+pub async fn metrics_middleware(
+    State(metrics): State<Arc<Metrics>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let method = req.method().to_string();
+    let start = Instant::now();
+
+    let response = next.run(req).await;
+
+    metrics.observe_request(
+        &method,
+        &route,
+        response.status().as_u16(),
+        start.elapsed().as_secs_f64(),
+    );
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_render_includes_request_counter_labels() {
+        let metrics = Metrics::new().unwrap();
+        metrics.observe_request("GET", "/api/anime/:id", 200, 0.01);
+        metrics.observe_request("GET", "/api/anime/:id", 200, 0.02);
+
+        let output = metrics.render().unwrap();
+        assert!(output.contains(
+            r#"http_requests_total{method="GET",route="/api/anime/:id",status="200"} 2"#
+        ));
+        assert!(output.contains("http_request_duration_seconds_bucket"));
+        assert!(output.contains(r#"http_responses_total{class="2xx"} 2"#));
+    }
+
+    #[test]
+    fn test_errors_are_counted_by_status_code() {
+        let metrics = Metrics::new().unwrap();
+        metrics.observe_request("GET", "/api/anime/:id", 200, 0.01);
+        metrics.observe_request("GET", "/api/anime/:id", 404, 0.01);
+        metrics.observe_request("POST", "/api/anime", 503, 0.01);
+
+        let output = metrics.render().unwrap();
+        assert!(output.contains(r#"http_errors_total{status="404"} 1"#));
+        assert!(output.contains(r#"http_errors_total{status="503"} 1"#));
+        assert!(!output.contains(r#"http_errors_total{status="200"}"#));
+    }
+
+    #[test]
+    fn test_open_circuits_are_counted() {
+        let metrics = Metrics::new().unwrap();
+        metrics.set_circuit_states(&[
+            ("a.example".to_string(), CircuitState::Open(Utc::now())),
+            ("b.example".to_string(), CircuitState::HalfOpen),
+            ("c.example".to_string(), CircuitState::Closed),
+        ]);
+
+        let output = metrics.render().unwrap();
+        assert!(output.contains("circuit_breaker_open_count 1"));
+    }
+
+    #[test]
+    fn test_cache_lookups_are_counted_by_result() {
+        let metrics = Metrics::new().unwrap();
+        metrics.observe_cache("search", false);
+        metrics.observe_cache("search", true);
+        metrics.observe_cache("search", true);
+
+        let output = metrics.render().unwrap();
+        assert!(output.contains(r#"cache_requests_total{cache="search",result="hit"} 2"#));
+        assert!(output.contains(r#"cache_requests_total{cache="search",result="miss"} 1"#));
+    }
+
+    #[test]
+    fn test_component_health_sets_only_current_status() {
+        let metrics = Metrics::new().unwrap();
+        metrics.set_component_health(&[ComponentHealth {
+            name: "database".to_string(),
+            status: HealthStatus::Degraded,
+            message: None,
+            latency_ms: 5,
+            last_check: Utc::now(),
+            metadata: HashMap::new(),
+        }]);
+
+        let output = metrics.render().unwrap();
+        assert!(output.contains(r#"health_component_status{component="database",status="degraded"} 1"#));
+        assert!(output.contains(r#"health_component_status{component="database",status="healthy"} 0"#));
+        assert!(output.contains(r#"health_component_latency_seconds{component="database"} 0.005"#));
+    }
+}
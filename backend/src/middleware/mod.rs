@@ -1,14 +1,30 @@
 // Middleware modules
+pub mod api_key;
 pub mod auth;
 pub mod cors;
+pub mod csrf;
 pub mod error;
+pub mod etag;
 pub mod json_extractor;
+pub mod locale;
 pub mod logging;
+#[cfg(feature = "metrics")]
+pub mod metrics;
 pub mod rate_limit;
+pub mod request_size_limit;
+pub mod response_cache;
 
 // Re-export commonly used types
-pub use auth::{AuthUser, OptionalAuthUser};
-pub use cors::{cors_layer, cors_layer_permissive, get_cors_layer};
+pub use api_key::{ApiKey, ApiKeyConfig};
+pub use auth::{AdminUser, AuthUser, CatalogWriter, OptionalAuthUser, WriteAccessConfig};
+pub use cors::{CorsConfig, cors_layer, cors_layer_permissive, dynamic_cors_middleware, get_cors_layer};
+pub use csrf::{SessionCookieConfig, csrf_middleware};
 pub use error::{AppError, AppResult, ErrorResponse};
-pub use logging::{logging_middleware, create_trace_layer, init_logging, RequestId};
-pub use rate_limit::{RateLimiter, RateLimitConfig, rate_limit_middleware};
\ No newline at end of file
+pub use etag::{etag_middleware, season_etag_middleware};
+pub use locale::Locale;
+pub use logging::{logging_middleware, create_trace_layer, init_logging, init_otel_tracing, OpenTelemetryConfig, RequestId};
+#[cfg(feature = "metrics")]
+pub use metrics::{Metrics, MetricsEndpointConfig, metrics_middleware};
+pub use response_cache::{anime_detail_cache_middleware, season_browse_cache_middleware, search_cache_middleware};
+pub use rate_limit::{RateLimitConfig, RouteRateLimit, UserRateLimitConfig, rate_limit_middleware};
+pub use request_size_limit::{RequestSizeLimitConfig, RequestSizeLimitLayer};
\ No newline at end of file
@@ -1,30 +1,47 @@
 // T058: Rate limiting middleware
 // Reference: research.md section 2 "Limitations Discovered" for rate limiting need
+//
+// Fixed-window counters in Redis, so limits hold across backend instances.
+// Requests with a valid session are counted per user, anonymous ones per client
+// IP; route overrides get buckets of their own.
 
 use axum::{
-    extract::{Request, State},
-    http::{StatusCode, HeaderMap, HeaderValue},
+    extract::{ConnectInfo, FromRequestParts, OriginalUri, Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
 use serde_json::json;
 use std::{
-    collections::HashMap,
-    sync::Arc,
-    time::{Duration, Instant},
+    net::{IpAddr, SocketAddr},
+    time::Duration,
 };
-use tokio::sync::RwLock;
+use crate::api::versioning::unversioned_path;
+use crate::db::connection::AppState;
+use crate::middleware::auth::AuthUser;
+use crate::middleware::error::ErrorResponse;
+use crate::middleware::logging::RequestId;
 
 /// Rate limit configuration
 #[derive(Clone, Debug)]
 pub struct RateLimitConfig {
-    /// Maximum requests per window
+    /// Maximum anonymous requests per client IP per window
     pub max_requests: u32,
     /// Time window duration
     pub window: Duration,
     /// Burst allowance above max_requests
     pub burst: u32,
+    /// Limits applied per authenticated user
+    pub user: UserRateLimitConfig,
+    /// Whether anonymous requests are limited at all
+    pub limit_anonymous: bool,
+    /// Take the client IP from the first X-Forwarded-For hop. Only safe behind
+    /// a proxy that sets the header, since clients can send anything.
+    pub trust_forwarded_for: bool,
+    /// Limits replacing the above for requests under a path prefix, users and
+    /// anonymous clients alike; the first matching prefix wins
+    pub routes: Vec<RouteRateLimit>,
 }
 
 impl Default for RateLimitConfig {
@@ -33,10 +50,93 @@ impl Default for RateLimitConfig {
             max_requests: 60,  // 60 requests
             window: Duration::from_secs(60), // per minute
             burst: 10, // Allow 10 extra requests in burst
+            user: UserRateLimitConfig::default(),
+            limit_anonymous: true,
+            trust_forwarded_for: false,
+            routes: RouteRateLimit::defaults(),
         }
     }
 }
 
+/// Limit for the requests under one path prefix, counted in its own bucket
+#[derive(Clone, Debug)]
+pub struct RouteRateLimit {
+    /// Full request path prefix, e.g. `/api/auth/login`
+    pub prefix: String,
+    pub max_requests: u32,
+    pub window: Duration,
+    pub burst: u32,
+}
+
+impl RouteRateLimit {
+    /// Stricter on login to slow password guessing, looser on search-as-you-type
+    pub fn defaults() -> Vec<RouteRateLimit> {
+        vec![
+            RouteRateLimit {
+                prefix: "/api/auth/login".to_string(),
+                max_requests: 5,
+                window: Duration::from_secs(300), // 5 requests per 5 minutes
+                burst: 2,
+            },
+            RouteRateLimit {
+                prefix: "/api/search".to_string(),
+                max_requests: 300,
+                window: Duration::from_secs(60),
+                burst: 60,
+            },
+        ]
+    }
+    
+    /// Total requests allowed in one window, including burst
+    pub fn limit(&self) -> u32 {
+        self.max_requests + self.burst
+    }
+}
+
+/// Per-user rate limit configuration, enforced with Redis counters
+#[derive(Clone, Debug)]
+pub struct UserRateLimitConfig {
+    /// Maximum requests per user per minute
+    pub requests_per_minute: u32,
+    /// Burst allowance above requests_per_minute
+    pub burst: u32,
+}
+
+impl Default for UserRateLimitConfig {
+    fn default() -> Self {
+        UserRateLimitConfig {
+            requests_per_minute: 120,
+            burst: 20,
+        }
+    }
+}
+
+impl UserRateLimitConfig {
+    pub fn from_env() -> Self {
+        let defaults = UserRateLimitConfig::default();
+        
+        let requests_per_minute = std::env::var("USER_RATE_LIMIT_REQUESTS_PER_MINUTE")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(defaults.requests_per_minute);
+        
+        let burst = std::env::var("USER_RATE_LIMIT_BURST")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(defaults.burst);
+        
+        UserRateLimitConfig {
+            requests_per_minute,
+            burst,
+        }
+    }
+    
+    /// Total requests allowed in one window, including burst
+    pub fn limit(&self) -> u32 {
+        self.requests_per_minute + self.burst
+    }
+}
+
 impl RateLimitConfig {
     pub fn from_env() -> Self {
         let max_requests = std::env::var("RATE_LIMIT_REQUESTS_PER_MINUTE")
@@ -49,238 +149,204 @@ impl RateLimitConfig {
             .and_then(|s| s.parse().ok())
             .unwrap_or(10);
         
+        let trust_forwarded_for = std::env::var("RATE_LIMIT_TRUST_PROXY")
+            .ok()
+            .and_then(|s| s.trim().parse().ok())
+            .unwrap_or(false);
+        
         RateLimitConfig {
             max_requests,
-            window: Duration::from_secs(60),
             burst,
+            user: UserRateLimitConfig::from_env(),
+            trust_forwarded_for,
+            ..RateLimitConfig::default()
         }
     }
+    
+    /// The override for `path`, if any
+    fn route(&self, path: &str) -> Option<&RouteRateLimit> {
+        self.routes.iter().find(|route| path.starts_with(&route.prefix))
+    }
 }
 
-/// Rate limiter state for tracking requests
-#[derive(Clone)]
-pub struct RateLimiter {
-    /// Map of client ID to their request history
-    clients: Arc<RwLock<HashMap<String, ClientRateLimit>>>,
-    config: RateLimitConfig,
-}
-
-/// Per-client rate limit tracking
-#[derive(Debug)]
-struct ClientRateLimit {
-    /// Number of requests in current window
-    requests: u32,
-    /// Start of current window
-    window_start: Instant,
-    /// Burst tokens available
-    burst_tokens: u32,
+/// Whose requests a bucket counts
+#[derive(Debug, Clone, PartialEq)]
+enum RateLimitClient {
+    User(String),
+    Ip(String),
 }
 
-impl RateLimiter {
-    pub fn new(config: RateLimitConfig) -> Self {
-        RateLimiter {
-            clients: Arc::new(RwLock::new(HashMap::new())),
-            config,
-        }
-    }
-
-    /// Check if a client is rate limited
-    async fn check_rate_limit(&self, client_id: &str) -> RateLimitResult {
-        let mut clients = self.clients.write().await;
-        let now = Instant::now();
+impl RateLimitClient {
+    /// The session's user when the request carries a valid one, else the client IP
+    async fn of(req: Request, state: &AppState) -> (Self, Request) {
+        let (mut parts, body) = req.into_parts();
+        let user = match AuthUser::from_request_parts(&mut parts, state).await {
+            Ok(auth) => Some(auth.session.user_id),
+            Err(_) => None,
+        };
+        let req = Request::from_parts(parts, body);
         
-        let client = clients.entry(client_id.to_string()).or_insert_with(|| {
-            ClientRateLimit {
-                requests: 0,
-                window_start: now,
-                burst_tokens: self.config.burst,
+        let client = match user {
+            Some(user_id) => RateLimitClient::User(user_id),
+            None => {
+                let peer = req.extensions().get::<ConnectInfo<SocketAddr>>().map(|info| info.0.ip());
+                RateLimitClient::Ip(client_ip(req.headers(), peer, state.rate_limit.trust_forwarded_for))
             }
-        });
+        };
+        (client, req)
+    }
+}
 
-        // Check if we need to reset the window
-        if now.duration_since(client.window_start) >= self.config.window {
-            client.requests = 0;
-            client.window_start = now;
-            client.burst_tokens = self.config.burst;
-        }
+/// Client IP: the first X-Forwarded-For hop if trusted and valid, else the peer
+fn client_ip(headers: &HeaderMap, peer: Option<IpAddr>, trust_forwarded_for: bool) -> String {
+    let forwarded = trust_forwarded_for
+        .then(|| headers.get("x-forwarded-for"))
+        .flatten()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(',').next())
+        .and_then(|hop| hop.trim().parse::<IpAddr>().ok());
+    
+    forwarded
+        .or(peer)
+        .map(|ip| ip.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
 
-        // Calculate remaining capacity
-        let limit = self.config.max_requests + client.burst_tokens;
-        let remaining = limit.saturating_sub(client.requests);
-        let reset_time = client.window_start + self.config.window;
-        
-        if client.requests >= self.config.max_requests {
-            // Use burst tokens if available
-            if client.burst_tokens > 0 {
-                client.burst_tokens -= 1;
-                client.requests += 1;
-                
-                RateLimitResult::Allowed {
-                    limit,
-                    remaining: remaining.saturating_sub(1),
-                    reset: reset_time,
-                }
-            } else {
-                RateLimitResult::Limited {
-                    limit,
-                    reset: reset_time,
-                }
-            }
-        } else {
-            client.requests += 1;
-            
-            RateLimitResult::Allowed {
-                limit,
-                remaining: remaining.saturating_sub(1),
-                reset: reset_time,
-            }
-        }
+/// Redis key of the bucket for `client` in `window`, under `route` for overrides
+fn rate_limit_key(route: Option<&str>, client: &RateLimitClient, window: u64) -> String {
+    let client = match client {
+        RateLimitClient::User(user_id) => format!("user:{}", user_id),
+        RateLimitClient::Ip(ip) => format!("ip:{}", ip),
+    };
+    match route {
+        Some(route) => format!("rate_limit:{}:{}:{}", route, client, window),
+        None => format!("rate_limit:{}:{}", client, window),
     }
+}
 
-    /// Clean up old entries periodically (call this in a background task)
-    pub async fn cleanup(&self) {
-        let mut clients = self.clients.write().await;
-        let now = Instant::now();
-        
-        clients.retain(|_, client| {
-            now.duration_since(client.window_start) < self.config.window * 2
-        });
-    }
+/// Index of the fixed window `now_secs` falls in, and seconds until it ends
+fn current_window(now_secs: u64, window: Duration) -> (u64, u64) {
+    let window = window.as_secs().max(1);
+    (now_secs / window, window - (now_secs % window))
 }
 
-#[derive(Debug)]
-enum RateLimitResult {
-    Allowed {
-        limit: u32,
-        remaining: u32,
-        reset: Instant,
-    },
-    Limited {
-        limit: u32,
-        reset: Instant,
-    },
+fn insert_limit_headers(headers: &mut HeaderMap, limit: u32, remaining: u64, reset_in: u64) {
+    headers.insert("X-RateLimit-Limit", HeaderValue::from(limit));
+    headers.insert("X-RateLimit-Remaining", HeaderValue::from(remaining));
+    headers.insert("X-RateLimit-Reset", HeaderValue::from(reset_in));
 }
 
-/// Rate limiting middleware
+/// Rate limiting middleware for the API. Fails open when Redis is unavailable:
+/// an outage should not take the API down with it.
 pub async fn rate_limit_middleware(
-    State(limiter): State<RateLimiter>,
+    State(state): State<AppState>,
     req: Request,
     next: Next,
-) -> Result<Response, StatusCode> {
-    // Get client identifier (use IP, user ID, or API key)
-    let client_id = extract_client_id(&req);
+) -> Response {
+    let config = &state.rate_limit;
+    // Nested routers see their path with the prefix stripped; /api/v1/... is
+    // limited like /api/...
+    let path = unversioned_path(req.extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path())
+        .unwrap_or_else(|| req.uri().path()));
     
-    // Check rate limit
-    let result = limiter.check_rate_limit(&client_id).await;
+    let (client, req) = RateLimitClient::of(req, &state).await;
+    let route = config.route(&path);
+    let (limit, window) = match (route, &client) {
+        (Some(route), _) => (route.limit(), route.window),
+        (None, RateLimitClient::User(_)) => (config.user.limit(), Duration::from_secs(USER_WINDOW_SECS)),
+        (None, RateLimitClient::Ip(_)) if config.limit_anonymous => (config.max_requests + config.burst, config.window),
+        (None, RateLimitClient::Ip(_)) => return next.run(req).await,
+    };
     
-    match result {
-        RateLimitResult::Allowed { limit, remaining, reset } => {
-            // Add rate limit headers
-            let mut response = next.run(req).await;
-            let headers = response.headers_mut();
-            
-            headers.insert("X-RateLimit-Limit", HeaderValue::from(limit));
-            headers.insert("X-RateLimit-Remaining", HeaderValue::from(remaining));
-            headers.insert(
-                "X-RateLimit-Reset",
-                HeaderValue::from(reset.elapsed().as_secs()),
-            );
-            
-            Ok(response)
-        }
-        RateLimitResult::Limited { limit, reset } => {
-            // Return 429 Too Many Requests
-            let mut response = (
-                StatusCode::TOO_MANY_REQUESTS,
-                Json(json!({
-                    "error": "Rate limit exceeded",
-                    "message": "Too many requests. Please slow down.",
-                    "retry_after_seconds": reset.elapsed().as_secs()
-                }))
-            ).into_response();
-            
-            let headers = response.headers_mut();
-            headers.insert("X-RateLimit-Limit", HeaderValue::from(limit));
-            headers.insert("X-RateLimit-Remaining", HeaderValue::from(0u32));
-            headers.insert(
-                "X-RateLimit-Reset",
-                HeaderValue::from(reset.elapsed().as_secs()),
-            );
-            headers.insert(
-                "Retry-After",
-                HeaderValue::from(reset.elapsed().as_secs()),
-            );
-            
-            Ok(response)
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (window_index, reset_in) = current_window(now, window);
+    let key = rate_limit_key(route.map(|route| route.prefix.as_str()), &client, window_index);
+    
+    let count = match state.cache.lock().await
+        .increment_counter(&key, window)
+        .await
+    {
+        Ok(count) => count,
+        Err(e) => {
+            tracing::warn!("Rate limit check failed for {:?}: {}", client, e);
+            return next.run(req).await;
         }
-    }
-}
-
-/// Extract client identifier from request
-fn extract_client_id(req: &Request) -> String {
-    // Try to get authenticated user ID from extensions
-    if let Some(auth_user) = req.extensions().get::<crate::middleware::auth::AuthUser>() {
-        return auth_user.session.user_id.clone();
+    };
+    
+    if count > limit as u64 {
+        let mut response = (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(ErrorResponse {
+                code: "RATE_LIMITED".to_string(),
+                message: "Too many requests. Please slow down.".to_string(),
+                details: Some(json!({
+                    "limit": limit,
+                    "retry_after_seconds": reset_in
+                })),
+                request_id: RequestId::current(),
+            }),
+        ).into_response();
+        
+        let headers = response.headers_mut();
+        insert_limit_headers(headers, limit, 0, reset_in);
+        headers.insert("Retry-After", HeaderValue::from(reset_in));
+        
+        return response;
     }
     
-    // Fall back to IP address
-    req.headers()
-        .get("x-forwarded-for")
-        .or_else(|| req.headers().get("x-real-ip"))
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("unknown")
-        .to_string()
+    let mut response = next.run(req).await;
+    insert_limit_headers(response.headers_mut(), limit, (limit as u64).saturating_sub(count), reset_in);
+    response
 }
 
-/// Per-endpoint rate limiting with different limits
-pub fn endpoint_rate_limiter(endpoint: &str) -> RateLimitConfig {
-    match endpoint {
-        "/api/auth/login" => RateLimitConfig {
-            max_requests: 5,
-            window: Duration::from_secs(300), // 5 requests per 5 minutes
-            burst: 2,
-        },
-        "/api/stream" => RateLimitConfig {
-            max_requests: 10,
-            window: Duration::from_secs(60), // 10 requests per minute
-            burst: 5,
-        },
-        _ => RateLimitConfig::default(),
-    }
-}
+const USER_WINDOW_SECS: u64 = 60;
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_rate_limiter() {
-        let config = RateLimitConfig {
-            max_requests: 3,
-            window: Duration::from_secs(1),
-            burst: 1,
-        };
-        
-        let limiter = RateLimiter::new(config);
-        
-        // Should allow first 3 requests
-        for _ in 0..3 {
-            match limiter.check_rate_limit("test_client").await {
-                RateLimitResult::Allowed { .. } => {}
-                _ => panic!("Should allow request"),
-            }
-        }
-        
-        // Should allow one more with burst
-        match limiter.check_rate_limit("test_client").await {
-            RateLimitResult::Allowed { .. } => {}
-            _ => panic!("Should allow burst request"),
-        }
-        
-        // Should be rate limited now
-        match limiter.check_rate_limit("test_client").await {
-            RateLimitResult::Limited { .. } => {}
-            _ => panic!("Should be rate limited"),
-        }
+    #[test]
+    fn test_rate_limit_key() {
+        let user = RateLimitClient::User("user-1".to_string());
+        let ip = RateLimitClient::Ip("203.0.113.7".to_string());
+
+        assert_eq!(rate_limit_key(None, &user, 42), "rate_limit:user:user-1:42");
+        assert_eq!(rate_limit_key(None, &ip, 42), "rate_limit:ip:203.0.113.7:42");
+        assert_eq!(
+            rate_limit_key(Some("/api/auth/login"), &ip, 7),
+            "rate_limit:/api/auth/login:ip:203.0.113.7:7"
+        );
+        assert_eq!(UserRateLimitConfig { requests_per_minute: 10, burst: 5 }.limit(), 15);
+    }
+
+    #[test]
+    fn test_client_ip_trusts_forwarded_for_only_when_configured() {
+        let peer: Option<IpAddr> = Some("10.0.0.2".parse().unwrap());
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", HeaderValue::from_static("203.0.113.7, 10.0.0.1"));
+
+        assert_eq!(client_ip(&headers, peer, true), "203.0.113.7");
+        assert_eq!(client_ip(&headers, peer, false), "10.0.0.2");
+
+        headers.insert("x-forwarded-for", HeaderValue::from_static("not-an-ip"));
+        assert_eq!(client_ip(&headers, peer, true), "10.0.0.2");
+        assert_eq!(client_ip(&HeaderMap::new(), None, true), "unknown");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_route_overrides_and_windows() {
+        let config = RateLimitConfig::default();
+
+        assert_eq!(config.route("/api/auth/login").unwrap().limit(), 7);
+        assert!(config.route("/api/search").unwrap().limit() > config.max_requests + config.burst);
+        assert!(config.route("/api/anime").is_none());
+
+        assert_eq!(current_window(125, Duration::from_secs(60)), (2, 55));
+        assert_eq!(current_window(300, Duration::from_secs(300)), (1, 300));
+    }
+}
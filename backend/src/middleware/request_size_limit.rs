@@ -0,0 +1,162 @@
+// Request body size limits: bodies above the limit are refused with 413 before
+// they are buffered. A declared Content-Length over the limit is rejected up
+// front; bodies without one are read through a counter that aborts at the
+// limit, which extractors report as 413 too.
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header::CONTENT_LENGTH, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::future::{ready, Either, Ready};
+use http_body_util::Limited;
+use serde_json::json;
+use std::task::{Context, Poll};
+use tower::{Layer, Service};
+use crate::api::handlers::bulk::BULK_IMPORT_BODY_LIMIT;
+use crate::middleware::error::ErrorResponse;
+use crate::middleware::logging::RequestId;
+
+/// Default limit for request bodies, 10 MiB
+pub const DEFAULT_MAX_REQUEST_BODY_BYTES: usize = 10 * 1024 * 1024;
+
+/// Body size limits for the API
+#[derive(Clone, Debug)]
+pub struct RequestSizeLimitConfig {
+    /// Limit for every route without an override
+    pub max_bytes: usize,
+    /// Override for `POST /api/anime/bulk`, which takes whole database dumps
+    pub bulk_import_max_bytes: usize,
+}
+
+impl Default for RequestSizeLimitConfig {
+    fn default() -> Self {
+        RequestSizeLimitConfig {
+            max_bytes: DEFAULT_MAX_REQUEST_BODY_BYTES,
+            bulk_import_max_bytes: BULK_IMPORT_BODY_LIMIT,
+        }
+    }
+}
+
+impl RequestSizeLimitConfig {
+    /// Reads MAX_REQUEST_BODY_BYTES and BULK_IMPORT_MAX_BODY_BYTES
+    pub fn from_env() -> Self {
+        let defaults = RequestSizeLimitConfig::default();
+        let env_or = |name: &str, default: usize| {
+            std::env::var(name).ok().and_then(|value| value.trim().parse().ok()).unwrap_or(default)
+        };
+
+        RequestSizeLimitConfig {
+            max_bytes: env_or("MAX_REQUEST_BODY_BYTES", defaults.max_bytes),
+            bulk_import_max_bytes: env_or("BULK_IMPORT_MAX_BODY_BYTES", defaults.bulk_import_max_bytes),
+        }
+    }
+}
+
+/// Layer refusing request bodies larger than `max_bytes` with 413 Payload Too Large.
+/// The innermost limit wins, so a route layer can raise the limit of its router.
+#[derive(Clone, Copy, Debug)]
+pub struct RequestSizeLimitLayer {
+    pub max_bytes: usize,
+}
+
+impl<S> Layer<S> for RequestSizeLimitLayer {
+    type Service = RequestSizeLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestSizeLimit { inner, max_bytes: self.max_bytes }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RequestSizeLimit<S> {
+    inner: S,
+    max_bytes: usize,
+}
+
+impl<S> Service<Request> for RequestSizeLimit<S>
+where
+    S: Service<Request, Response = Response>,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Either<Ready<Result<Response, S::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let declared = req
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+
+        if declared.is_some_and(|length| length > self.max_bytes as u64) {
+            return Either::Left(ready(Ok(payload_too_large(self.max_bytes))));
+        }
+
+        let max_bytes = self.max_bytes;
+        Either::Right(self.inner.call(req.map(|body| Body::new(Limited::new(body, max_bytes)))))
+    }
+}
+
+fn payload_too_large(max_bytes: usize) -> Response {
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Json(ErrorResponse {
+            code: "PAYLOAD_TOO_LARGE".to_string(),
+            message: format!("Request body exceeds the {} byte limit", max_bytes),
+            details: Some(json!({ "max_bytes": max_bytes })),
+            request_id: RequestId::current(),
+        }),
+    ).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Bytes, routing::post, Router};
+    use futures::stream;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/", post(|body: Bytes| async move { body.len().to_string() }))
+            .layer(RequestSizeLimitLayer { max_bytes: 8 })
+    }
+
+    #[tokio::test]
+    async fn test_declared_length_over_limit_is_rejected() {
+        let request = Request::post("/")
+            .header(CONTENT_LENGTH, "9")
+            .body(Body::from("123456789"))
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let request = Request::post("/")
+            .header(CONTENT_LENGTH, "8")
+            .body(Body::from("12345678"))
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_streamed_body_is_cut_off_at_limit() {
+        let streamed = |chunks: Vec<&'static str>| {
+            let chunks = stream::iter(chunks.into_iter().map(Ok::<_, std::io::Error>));
+            Request::post("/").body(Body::from_stream(chunks)).unwrap()
+        };
+
+        let response = app().oneshot(streamed(vec!["1234", "56789"])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let response = app().oneshot(streamed(vec!["1234", "5678"])).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,248 @@
+// Response caching for hot anime reads: the detail, seasonal browse and search
+// GETs are served from the cache (Redis in production) until their TTL runs out
+// or a catalog write drops them. Responses carry `X-Cache: HIT` or `MISS`.
+// Layered outside the ETag middleware, so an entry keeps its ETag alongside the
+// body and a matching If-None-Match is answered 304 straight from the cache.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Path, Request, State},
+    http::{
+        header::{ACCEPT_LANGUAGE, CONTENT_LENGTH, ETAG},
+        HeaderMap, HeaderName, HeaderValue, StatusCode, Uri,
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use uuid::Uuid;
+use crate::api::versioning::ApiVersion;
+use crate::db::connection::AppState;
+use crate::middleware::etag::{if_none_match_matches, not_modified};
+use crate::models::Anime;
+use crate::services::CacheService;
+
+pub const ANIME_DETAIL_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+pub const SEASON_BROWSE_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+pub const SEARCH_CACHE_TTL: Duration = Duration::from_secs(60);
+
+pub const X_CACHE: HeaderName = HeaderName::from_static("x-cache");
+
+const SEARCH_GROUP: &str = "search";
+
+fn anime_group(anime_id: Uuid) -> String {
+    format!("anime:{}", anime_id)
+}
+
+fn season_group(year: u16, season: &str) -> String {
+    format!("season:{}:{}", year, season.to_lowercase())
+}
+
+/// A 200 response as stored in the cache
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedResponse {
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+/// Path, query with its parameters sorted, and request language, so
+/// `?a=1&b=2` and `?b=2&a=1` share an entry but translations do not
+pub fn normalized_request_key(uri: &Uri, headers: &HeaderMap) -> String {
+    let mut params: Vec<&str> = uri
+        .query()
+        .unwrap_or("")
+        .split('&')
+        .filter(|param| !param.is_empty())
+        .collect();
+    params.sort_unstable();
+
+    format!(
+        "{}?{}|{}",
+        uri.path(),
+        params.join("&"),
+        headers.get(ACCEPT_LANGUAGE).and_then(|value| value.to_str().ok()).unwrap_or(""),
+    )
+}
+
+fn with_x_cache(mut response: Response, status: &'static str) -> Response {
+    response.headers_mut().insert(X_CACHE, HeaderValue::from_static(status));
+    response
+}
+
+async fn cached_response(state: &AppState, group: &str, ttl: Duration, req: Request, next: Next) -> Response {
+    // The path here has its /api/{version} prefix stripped, so the version keeps
+    // v1 and v2 responses apart
+    let version = req.extensions().get::<ApiVersion>().copied().unwrap_or(ApiVersion::DEFAULT);
+    let key = CacheService::response_key(group, &format!("{}:{}", version, normalized_request_key(req.uri(), req.headers())));
+
+    let cached = state.cache.lock().await
+        .get_response::<CachedResponse>(&key)
+        .await
+        .unwrap_or_else(|e| {
+            tracing::debug!("Failed to read cached response {}: {}", key, e);
+            None
+        });
+    // Group kind ("anime", "season" or "search") as the metrics label
+    #[cfg(feature = "metrics")]
+    state.metrics.observe_cache(group.split(':').next().unwrap_or(group), cached.is_some());
+
+    if let Some(cached) = cached {
+        let etag = cached.headers.iter().find(|(name, _)| *name == ETAG.as_str()).map(|(_, value)| value.as_str());
+        if let Some(etag) = etag.filter(|etag| if_none_match_matches(req.headers(), etag)) {
+            return with_x_cache(not_modified(etag), "HIT");
+        }
+
+        let mut response = Response::new(Body::from(cached.body));
+        for (name, value) in cached.headers {
+            if let (Ok(name), Ok(value)) = (HeaderName::try_from(name), HeaderValue::try_from(value)) {
+                response.headers_mut().insert(name, value);
+            }
+        }
+        return with_x_cache(response, "HIT");
+    }
+
+    let response = next.run(req).await;
+    if response.status() != StatusCode::OK {
+        return with_x_cache(response, "MISS");
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::warn!("Failed to buffer response for caching: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    if let Ok(body) = std::str::from_utf8(&bytes) {
+        let cached = CachedResponse {
+            headers: parts.headers
+                .iter()
+                .filter(|(name, _)| *name != CONTENT_LENGTH)
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+                .collect(),
+            body: body.to_string(),
+        };
+        if let Err(e) = state.cache.lock().await.store_response(&key, &cached, ttl).await {
+            tracing::debug!("Failed to cache response {}: {}", key, e);
+        }
+    }
+
+    with_x_cache(Response::from_parts(parts, Body::from(bytes)), "MISS")
+}
+
+/// Route layer for `GET /api/anime/:id`
+pub async fn anime_detail_cache_middleware(
+    Path(anime_id): Path<Uuid>,
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    cached_response(&state, &anime_group(anime_id), ANIME_DETAIL_CACHE_TTL, req, next).await
+}
+
+/// Route layer for `GET /api/browse/season/:year/:season`
+pub async fn season_browse_cache_middleware(
+    Path((year, season)): Path<(u16, String)>,
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    cached_response(&state, &season_group(year, &season), SEASON_BROWSE_CACHE_TTL, req, next).await
+}
+
+/// Route layer for `GET /api/search`
+pub async fn search_cache_middleware(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    cached_response(&state, SEARCH_GROUP, SEARCH_CACHE_TTL, req, next).await
+}
+
+/// Drop the cached responses a write to `anime` can change: its detail, its
+/// season's browse pages, and search results
+pub async fn invalidate_anime_responses(state: &AppState, anime: &Anime) {
+    let groups = [
+        anime_group(anime.id),
+        season_group(anime.anime_season.year, anime.anime_season.season.as_str()),
+        SEARCH_GROUP.to_string(),
+    ];
+    invalidate_groups(state, &groups).await;
+    invalidate_anime_record(state, anime.id).await;
+    invalidate_season_pages(
+        state,
+        &CacheService::season_browse_pattern(anime.anime_season.year, anime.anime_season.season.as_str()),
+    ).await;
+}
+
+/// After a bulk write: every season and search, since any of them may have changed
+pub async fn invalidate_catalog_responses(state: &AppState) {
+    invalidate_groups(state, &["season:*".to_string(), SEARCH_GROUP.to_string()]).await;
+    invalidate_season_pages(state, "browse:season:*").await;
+}
+
+/// Only the anime's detail, e.g. after its episodes changed
+pub async fn invalidate_anime_detail_response(state: &AppState, anime_id: Uuid) {
+    invalidate_groups(state, &[anime_group(anime_id)]).await;
+    invalidate_anime_record(state, anime_id).await;
+}
+
+/// The anime record cached by `get_anime` through `CacheService::get_or_set`
+async fn invalidate_anime_record(state: &AppState, anime_id: Uuid) {
+    let key = CacheService::anime_key(&anime_id.to_string());
+    if let Err(e) = state.cache.lock().await.delete(&key).await {
+        tracing::warn!("Failed to invalidate cached anime {}: {}", anime_id, e);
+    }
+}
+
+/// Season pages cached by `browse_season` through `CacheService::get_or_set`
+async fn invalidate_season_pages(state: &AppState, pattern: &str) {
+    if let Err(e) = state.cache.lock().await.invalidate_pattern(pattern).await {
+        tracing::warn!("Failed to invalidate cached season pages {}: {}", pattern, e);
+    }
+}
+
+async fn invalidate_groups(state: &AppState, groups: &[String]) {
+    let mut cache = state.cache.lock().await;
+    for group in groups {
+        if let Err(e) = cache.invalidate_responses(group).await {
+            tracing::warn!("Failed to invalidate cached responses for {}: {}", group, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_key_sorts_query_and_includes_language() {
+        let uri = |value: &str| value.parse::<Uri>().unwrap();
+        let none = HeaderMap::new();
+
+        assert_eq!(
+            normalized_request_key(&uri("/api/search?q=naruto&limit=10"), &none),
+            normalized_request_key(&uri("/api/search?limit=10&q=naruto"), &none),
+        );
+        assert_ne!(
+            normalized_request_key(&uri("/api/search?q=naruto"), &none),
+            normalized_request_key(&uri("/api/search?q=bleach"), &none),
+        );
+        assert_eq!(normalized_request_key(&uri("/api/anime/1"), &none), "/api/anime/1?|");
+
+        let mut japanese = HeaderMap::new();
+        japanese.insert(ACCEPT_LANGUAGE, HeaderValue::from_static("ja"));
+        assert_ne!(
+            normalized_request_key(&uri("/api/browse/season/2024/spring"), &none),
+            normalized_request_key(&uri("/api/browse/season/2024/spring"), &japanese),
+        );
+    }
+
+    #[test]
+    fn test_season_group_is_case_insensitive() {
+        assert_eq!(season_group(2024, "Spring"), season_group(2024, "spring"));
+    }
+}
@@ -1,8 +1,12 @@
 // T023: Anime model with validation
 // Reference: data-model.md lines 15-65 for Anime struct definition and validation rules
 
-use chrono::{DateTime, Utc, Datelike};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, TimeZone, Utc, Datelike};
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 use uuid::Uuid;
 use validator::{Validate, ValidationError};
 
@@ -36,6 +40,26 @@ pub struct Anime {
     #[validate(url(message = "Poster URL must be valid"))]
     pub poster_url: String,
     
+    // Alternative key-art gallery, in addition to the primary poster
+    #[serde(default)]
+    #[validate(custom(function = "validate_poster_urls"))]
+    pub posters: Vec<String>,
+    
+    // Episode rows actually stored, as opposed to the declared `episodes` count.
+    // Maintained by the episode write paths; `doctor` recomputes it from rows.
+    #[serde(default)]
+    pub stored_episode_count: u32,
+    
+    // Decayed all-time activity score; see services::popularity
+    #[serde(default)]
+    pub popularity: f64,
+    
+    // Compact romaji keys of the title and synonyms, with kana transliterated,
+    // so kana queries find romaji titles; see services::transliteration.
+    // Rebuilt by DatabaseService on every write.
+    #[serde(default)]
+    pub romaji_titles: Vec<String>,
+    
     pub imdb: Option<ImdbData>,
     
     #[serde(default = "Utc::now")]
@@ -47,33 +71,68 @@ pub struct Anime {
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
+#[derive(Default)]
 pub enum AnimeStatus {
     Finished,
     Ongoing,
     Upcoming,
+    /// Production halted before the planned run finished
+    Cancelled,
+    #[default]
     Unknown,
 }
 
-impl Default for AnimeStatus {
-    fn default() -> Self {
-        AnimeStatus::Unknown
+
+/// Values `AnimeStatus` parses from, as listed in error messages
+pub const ANIME_STATUS_NAMES: &[&str] = &["FINISHED", "ONGOING", "UPCOMING", "CANCELLED", "UNKNOWN"];
+
+impl std::str::FromStr for AnimeStatus {
+    type Err = String;
+
+    /// Case-insensitive, so both `cancelled` and the offline database's `CANCELLED` parse
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "finished" => Ok(AnimeStatus::Finished),
+            "ongoing" => Ok(AnimeStatus::Ongoing),
+            "upcoming" => Ok(AnimeStatus::Upcoming),
+            "cancelled" => Ok(AnimeStatus::Cancelled),
+            "unknown" => Ok(AnimeStatus::Unknown),
+            other => Err(format!("Unknown anime status: {}", other)),
+        }
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "UPPERCASE")]
+#[derive(Default)]
 pub enum AnimeType {
     TV,
     Movie,
     OVA,
     ONA,
     Special,
+    #[default]
     Unknown,
 }
 
-impl Default for AnimeType {
-    fn default() -> Self {
-        AnimeType::Unknown
+
+/// Values `AnimeType` parses from, as listed in error messages
+pub const ANIME_TYPE_NAMES: &[&str] = &["TV", "MOVIE", "OVA", "ONA", "SPECIAL", "UNKNOWN"];
+
+impl std::str::FromStr for AnimeType {
+    type Err = String;
+
+    /// Case-insensitive, matching the serialized names
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "TV" => Ok(AnimeType::TV),
+            "MOVIE" => Ok(AnimeType::Movie),
+            "OVA" => Ok(AnimeType::OVA),
+            "ONA" => Ok(AnimeType::ONA),
+            "SPECIAL" => Ok(AnimeType::Special),
+            "UNKNOWN" => Ok(AnimeType::Unknown),
+            other => Err(format!("Unknown anime type: {}", other)),
+        }
     }
 }
 
@@ -81,23 +140,40 @@ impl Default for AnimeType {
 pub struct AnimeSeason {
     pub season: Season,
     
-    // Custom validation temporarily removed for POC
-    // #[validate(custom(function = "validate_year"))]
+    #[validate(custom(function = "validate_year"))]
     pub year: u16,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
+#[derive(Default)]
 pub enum Season {
+    #[default]
     Spring,
     Summer,
     Fall,
     Winter,
 }
 
-impl Default for Season {
-    fn default() -> Self {
-        Season::Spring
+
+impl Season {
+    /// Map a calendar month (1-12) to its broadcast season
+    pub fn from_month(month: u32) -> Self {
+        match month {
+            1..=3 => Season::Winter,
+            4..=6 => Season::Spring,
+            7..=9 => Season::Summer,
+            _ => Season::Fall,
+        }
+    }
+    
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Season::Spring => "spring",
+            Season::Summer => "summer",
+            Season::Fall => "fall",
+            Season::Winter => "winter",
+        }
     }
 }
 
@@ -108,18 +184,81 @@ pub struct ImdbData {
     #[validate(range(min = 0.0, max = 10.0, message = "Rating must be between 0.0 and 10.0"))]
     pub rating: f32,
     
+    /// 0 when the source has no vote count
     pub votes: u32,
+    
+    // Records stored before this field existed read as fetched now
+    #[serde(default = "Utc::now")]
+    pub last_updated: DateTime<Utc>,
 }
 
 // Custom validators
-fn validate_year(year: &u16) -> Result<(), ValidationError> {
+fn validate_year(year: u16) -> Result<(), ValidationError> {
     let current_year = Utc::now().year() as u16;
-    if *year < 1900 || *year > current_year + 5 {
+    if year < 1900 || year > current_year + 5 {
         return Err(ValidationError::new("invalid_year"));
     }
     Ok(())
 }
 
+fn validate_poster_urls(posters: &Vec<String>) -> Result<(), ValidationError> {
+    for poster in posters {
+        if url::Url::parse(poster).is_err() {
+            return Err(ValidationError::new("invalid_poster_url"));
+        }
+    }
+    Ok(())
+}
+
+impl Anime {
+    /// Build a poster gallery from candidate image URLs, dropping blanks and duplicates
+    pub fn collect_posters<I>(candidates: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut posters: Vec<String> = Vec::new();
+        for candidate in candidates {
+            if !candidate.is_empty() && !posters.contains(&candidate) {
+                posters.push(candidate);
+            }
+        }
+        posters
+    }
+
+    /// Merge `newer` data for the same anime, e.g. from a re-import, into this
+    /// record. Its synopsis and poster win unless blank; synonyms, sources and
+    /// posters are unioned. The id and everything else stay, so edges and episodes
+    /// keep pointing here. Returns whether anything changed.
+    pub fn merge_newer(&mut self, newer: Anime) -> bool {
+        let mut changed = false;
+
+        if !newer.synopsis.trim().is_empty() && newer.synopsis != self.synopsis {
+            self.synopsis = newer.synopsis;
+            changed = true;
+        }
+        if !newer.poster_url.is_empty() && newer.poster_url != self.poster_url {
+            self.poster_url = newer.poster_url;
+            changed = true;
+        }
+
+        let posters = Anime::collect_posters(self.posters.iter().cloned().chain(newer.posters));
+        if posters != self.posters {
+            self.posters = posters;
+            changed = true;
+        }
+
+        for (merged, incoming) in [(&mut self.synonyms, newer.synonyms), (&mut self.sources, newer.sources)] {
+            for value in incoming {
+                if !merged.contains(&value) {
+                    merged.push(value);
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+}
+
 // Response DTOs for API
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AnimeSummary {
@@ -146,10 +285,80 @@ impl From<Anime> for AnimeSummary {
     }
 }
 
+/// Typeahead entry from `GET /api/search/suggest`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnimeSuggestion {
+    pub id: Uuid,
+    pub title: String,
+}
+
+/// Keyset position in the catalog ordered by (`created_at`, `id`): everything
+/// strictly after it. Serialized as opaque URL-safe base64 so clients treat it
+/// as a token rather than building their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnimeCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl AnimeCursor {
+    pub fn after(anime: &Anime) -> Self {
+        AnimeCursor {
+            created_at: anime.created_at,
+            id: anime.id,
+        }
+    }
+}
+
+impl fmt::Display for AnimeCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.created_at.timestamp_nanos_opt().unwrap_or_default();
+        let raw = format!("{}_{}", nanos, self.id.simple());
+        f.write_str(&URL_SAFE_NO_PAD.encode(raw))
+    }
+}
+
+impl FromStr for AnimeCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let raw = String::from_utf8(URL_SAFE_NO_PAD.decode(s)?)?;
+        let (nanos, id) = raw
+            .split_once('_')
+            .ok_or_else(|| anyhow::anyhow!("Malformed cursor"))?;
+
+        Ok(AnimeCursor {
+            created_at: Utc.timestamp_nanos(nanos.parse()?),
+            id: id.parse()?,
+        })
+    }
+}
+
+/// A recommended anime with a human-readable explanation of why it was picked
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecommendedAnime {
+    #[serde(flatten)]
+    pub anime: AnimeSummary,
+    pub reason: String,
+}
+
+/// An anime scored by content similarity to another one
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SimilarAnime {
+    #[serde(flatten)]
+    pub anime: AnimeSummary,
+    /// Cosine similarity of the two titles' tag vectors, in (0, 1]
+    pub score: f32,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AnimeDetail {
     #[serde(flatten)]
     pub anime: Anime,
+    /// Episode count announced for the series
+    pub declared_episodes: u32,
+    /// Episodes actually stored and playable
+    pub available_episodes: u32,
     pub tags: Vec<crate::models::tag::Tag>,
     pub related_anime: RelatedAnime,
 }
@@ -181,6 +390,10 @@ mod tests {
             },
             synopsis: "Test synopsis".to_string(),
             poster_url: "https://example.com/poster.jpg".to_string(),
+            posters: vec![],
+            stored_episode_count: 0,
+            popularity: 0.0,
+            romaji_titles: Vec::new(),
             imdb: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
@@ -202,4 +415,125 @@ mod tests {
         anime.poster_url = "not-a-url".to_string();
         assert!(anime.validate().is_err());
     }
+
+    #[test]
+    fn test_posters_round_trip_and_validation() {
+        let mut anime = Anime {
+            id: Uuid::new_v4(),
+            title: "Gallery Anime".to_string(),
+            synonyms: vec![],
+            sources: vec![],
+            episodes: 12,
+            status: AnimeStatus::Finished,
+            anime_type: AnimeType::TV,
+            anime_season: AnimeSeason {
+                season: Season::Fall,
+                year: 2023,
+            },
+            synopsis: "Test synopsis".to_string(),
+            poster_url: "https://example.com/poster.jpg".to_string(),
+            posters: vec![
+                "https://example.com/key-art-1.jpg".to_string(),
+                "https://example.com/key-art-2.jpg".to_string(),
+            ],
+            stored_episode_count: 0,
+            popularity: 0.0,
+            romaji_titles: Vec::new(),
+            imdb: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        assert!(anime.validate().is_ok());
+
+        let json = serde_json::to_string(&anime).unwrap();
+        let restored: Anime = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.posters, anime.posters);
+
+        anime.posters.push("not-a-url".to_string());
+        assert!(anime.validate().is_err());
+    }
+
+    #[test]
+    fn test_collect_posters_dedups() {
+        let posters = Anime::collect_posters(vec![
+            "https://example.com/a.jpg".to_string(),
+            String::new(),
+            "https://example.com/a.jpg".to_string(),
+            "https://example.com/b.jpg".to_string(),
+        ]);
+        assert_eq!(posters, vec!["https://example.com/a.jpg", "https://example.com/b.jpg"]);
+    }
+
+    #[test]
+    fn test_cancelled_status_round_trip() {
+        let json = serde_json::to_string(&AnimeStatus::Cancelled).unwrap();
+        assert_eq!(json, r#""cancelled""#);
+        assert_eq!(serde_json::from_str::<AnimeStatus>(&json).unwrap(), AnimeStatus::Cancelled);
+
+        assert_eq!("CANCELLED".parse::<AnimeStatus>(), Ok(AnimeStatus::Cancelled));
+        assert!("halted".parse::<AnimeStatus>().is_err());
+    }
+
+    #[test]
+    fn test_anime_type_parsing() {
+        assert_eq!("tv".parse::<AnimeType>(), Ok(AnimeType::TV));
+        assert_eq!("MOVIE".parse::<AnimeType>(), Ok(AnimeType::Movie));
+        assert!("series".parse::<AnimeType>().is_err());
+
+        for name in ANIME_TYPE_NAMES {
+            let parsed: AnimeType = name.parse().unwrap();
+            assert_eq!(serde_json::to_string(&parsed).unwrap(), format!("\"{}\"", name));
+        }
+    }
+
+    #[test]
+    fn test_merge_newer_keeps_id_and_unions_lists() {
+        let mut stored = Anime {
+            id: Uuid::new_v4(),
+            title: "Stored Title".to_string(),
+            synonyms: vec!["Alias".to_string()],
+            sources: vec!["https://myanimelist.net/anime/1".to_string()],
+            episodes: 12,
+            status: AnimeStatus::Finished,
+            anime_type: AnimeType::TV,
+            anime_season: AnimeSeason {
+                season: Season::Spring,
+                year: 2024,
+            },
+            synopsis: "Written by an editor".to_string(),
+            poster_url: "https://example.com/old.jpg".to_string(),
+            posters: vec!["https://example.com/old.jpg".to_string()],
+            stored_episode_count: 3,
+            popularity: 0.0,
+            romaji_titles: Vec::new(),
+            imdb: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let id = stored.id;
+
+        let mut newer = stored.clone();
+        newer.id = Uuid::new_v4();
+        newer.title = "Imported Title".to_string();
+        newer.synonyms = vec!["Alias".to_string(), "Other Alias".to_string()];
+        newer.sources = vec!["https://anilist.co/anime/1".to_string()];
+        newer.synopsis = String::new();
+        newer.poster_url = "https://example.com/new.jpg".to_string();
+        newer.posters = vec!["https://example.com/new.jpg".to_string()];
+        newer.stored_episode_count = 0;
+
+        assert!(stored.merge_newer(newer.clone()));
+        assert_eq!(stored.id, id);
+        assert_eq!(stored.title, "Stored Title");
+        assert_eq!(stored.synonyms, vec!["Alias", "Other Alias"]);
+        assert_eq!(stored.sources, vec!["https://myanimelist.net/anime/1", "https://anilist.co/anime/1"]);
+        // A blank synopsis does not replace one
+        assert_eq!(stored.synopsis, "Written by an editor");
+        assert_eq!(stored.poster_url, "https://example.com/new.jpg");
+        assert_eq!(stored.posters, vec!["https://example.com/old.jpg", "https://example.com/new.jpg"]);
+        assert_eq!(stored.stored_episode_count, 3);
+
+        // Merging the same data again changes nothing
+        assert!(!stored.merge_newer(newer));
+    }
 }
\ No newline at end of file
@@ -1,12 +1,35 @@
 // Anime Offline Database Models
 // Generated from anime-offline-database.json with enhancements for Kensho project
 
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use anyhow::{anyhow, bail, Context};
+use chrono::Utc;
+use flate2::read::GzDecoder;
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::sync::Arc;
 use uuid::Uuid;
 use validator::{Validate, ValidationError};
 
 use crate::models::{Anime, AnimeStatus, AnimeType, AnimeSeason, Season};
+use crate::services::{ResilienceConfig, ResilientHttpClient};
+
+/// Name every anime-offline-database `$schema` URL and repository contains
+const UPSTREAM_PROJECT: &str = "anime-offline-database";
+
+/// First bytes of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The full dump is tens of megabytes, so downloads get far longer than API calls
+const DOWNLOAD_TIMEOUT_SECS: u64 = 300;
+
+/// Parsed entries `stream_entries` buffers ahead of its consumer
+const STREAM_BUFFER: usize = 256;
 
 /// Root structure of the anime-offline-database.json
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,14 +47,113 @@ pub struct AnimeOfflineDatabase {
 impl AnimeOfflineDatabase {
     /// Load the database from the JSON file
     pub fn load_from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        let content = std::fs::read_to_string(path)?;
-        let db: AnimeOfflineDatabase = serde_json::from_str(&content)?;
+        let file = File::open(path)?;
+        Ok(Self::from_json_reader(BufReader::new(file))?)
+    }
+
+    /// Stream the entries of the JSON file at `path` without loading the whole
+    /// database: entries are parsed on a background thread as the file is read
+    /// and handed over a few hundred at a time. Entries that do not match the
+    /// entry schema are skipped with a warning and counted in
+    /// `OfflineEntryStream::malformed`; a file that is not valid JSON or not an
+    /// anime-offline-database dump ends the stream with an error.
+    pub fn stream_entries(path: impl AsRef<Path>) -> anyhow::Result<OfflineEntryStream> {
+        let path = path.as_ref();
+        let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+
+        let (header_tx, header_rx) = mpsc::sync_channel(1);
+        let (entry_tx, entry_rx) = mpsc::sync_channel(STREAM_BUFFER);
+        let malformed = Arc::new(AtomicUsize::new(0));
+
+        let visitor = DatabaseVisitor {
+            header: header_tx.clone(),
+            entries: entry_tx.clone(),
+            malformed: malformed.clone(),
+        };
+        std::thread::spawn(move || {
+            let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(file));
+            let outcome = deserializer
+                .deserialize_map(visitor)
+                .and_then(|()| deserializer.end())
+                .map_err(|e| anyhow!("Failed to parse anime-offline-database JSON: {}", e));
+
+            // Whichever side is still listening hears how parsing ended: the
+            // header channel if `data` was never reached, the entries otherwise.
+            // A consumer that hung up is not told anything.
+            match outcome {
+                Ok(()) => {
+                    let _ = header_tx.try_send(Err(anyhow!("Not an anime-offline-database dump: no data array")));
+                }
+                Err(e) if e.to_string().contains(CONSUMER_GONE) => {}
+                Err(e) => {
+                    let _ = header_tx.try_send(Err(anyhow!("{}", e)));
+                    let _ = entry_tx.send(Err(e));
+                }
+            }
+        });
+
+        let score_range = header_rx
+            .recv()
+            .map_err(|_| anyhow!("Parser for {} stopped before the data array", path.display()))?
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+
+        Ok(OfflineEntryStream {
+            score_range,
+            entries: entry_rx,
+            malformed,
+        })
+    }
+
+    /// Load the database from a gzipped JSON file (`anime-offline-database.json.gz`),
+    /// decompressing while parsing so the JSON text is never held whole
+    pub fn load_from_gzip(path: &str) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(path).with_context(|| format!("Failed to open {}", path))?;
+        Self::from_json_reader(GzDecoder::new(BufReader::new(file)))
+            .with_context(|| format!("Failed to read {}", path))
+    }
+
+    /// Download the database, gzipped or plain JSON. The body is held once,
+    /// compressed, and decompressed while parsing.
+    pub async fn load_from_url(url: &str) -> anyhow::Result<Self> {
+        let http = ResilientHttpClient::new(ResilienceConfig {
+            timeout_secs: DOWNLOAD_TIMEOUT_SECS,
+            pool_size: 1,
+            ..ResilienceConfig::default()
+        })?;
+
+        let response = http
+            .request(url, |client| {
+                let request = client.get(url);
+                async move { Ok(request.send().await?.error_for_status()?) }
+            })
+            .await
+            .with_context(|| format!("Failed to download {}", url))?;
+        let body = response.bytes().await.with_context(|| format!("Failed to download {}", url))?;
+
+        // Sniff rather than trust the URL or headers: mirrors serve the .gz
+        // as application/octet-stream, and some add Content-Encoding
+        let database = if body.starts_with(&GZIP_MAGIC) {
+            Self::from_json_reader(GzDecoder::new(body.as_ref()))
+        } else {
+            Self::from_json_reader(body.as_ref())
+        };
+        database.with_context(|| format!("Failed to read {}", url))
+    }
+
+    fn from_json_reader(reader: impl Read) -> anyhow::Result<Self> {
+        let db: AnimeOfflineDatabase = serde_json::from_reader(reader)?;
+        db.validate_source()?;
         Ok(db)
     }
 
+    /// Reject JSON that parses but is not an anime-offline-database dump
+    fn validate_source(&self) -> anyhow::Result<()> {
+        check_source(&self.schema, &self.repository)
+    }
+
     /// Convert all entries to Kensho Anime models
     pub fn to_anime_models(&self) -> Vec<Anime> {
-        self.data.iter().map(|entry| entry.to_anime_model()).collect()
+        self.data.iter().map(|entry| entry.to_anime_model(&self.score_range)).collect()
     }
 
     /// Filter entries by type
@@ -56,6 +178,130 @@ impl AnimeOfflineDatabase {
     }
 }
 
+fn check_source(schema: &str, repository: &str) -> anyhow::Result<()> {
+    if !schema.contains(UPSTREAM_PROJECT) {
+        bail!("Not an anime-offline-database dump: unexpected $schema '{}'", schema);
+    }
+    if !repository.contains(UPSTREAM_PROJECT) {
+        bail!("Not an anime-offline-database dump: unexpected repository '{}'", repository);
+    }
+    Ok(())
+}
+
+/// Entries of an offline database file, in file order, from
+/// `AnimeOfflineDatabase::stream_entries`. Dropping it stops the parser.
+pub struct OfflineEntryStream {
+    /// Score range the file declares, read before its entries
+    pub score_range: ScoreRange,
+    entries: Receiver<anyhow::Result<AnimeOfflineEntry>>,
+    malformed: Arc<AtomicUsize>,
+}
+
+impl OfflineEntryStream {
+    /// Entries skipped so far because they did not match the entry schema
+    pub fn malformed(&self) -> usize {
+        self.malformed.load(Ordering::Relaxed)
+    }
+}
+
+impl Iterator for OfflineEntryStream {
+    type Item = anyhow::Result<AnimeOfflineEntry>;
+
+    /// Blocks until the parser has the next entry
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.recv().ok()
+    }
+}
+
+/// Parse error the stream's visitors raise to stop once the consumer is gone
+const CONSUMER_GONE: &str = "entry stream dropped";
+
+/// The root object: header fields are kept until `data`, which is checked
+/// against them and then streamed entry by entry
+struct DatabaseVisitor {
+    header: SyncSender<anyhow::Result<ScoreRange>>,
+    entries: SyncSender<anyhow::Result<AnimeOfflineEntry>>,
+    malformed: Arc<AtomicUsize>,
+}
+
+impl<'de> Visitor<'de> for DatabaseVisitor {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an anime-offline-database object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut schema = String::new();
+        let mut repository = String::new();
+        let mut score_range = None;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "$schema" => schema = map.next_value()?,
+                "repository" => repository = map.next_value()?,
+                "scoreRange" => score_range = Some(map.next_value()?),
+                "data" => {
+                    check_source(&schema, &repository).map_err(de::Error::custom)?;
+                    if self.header.send(Ok(score_range.take().unwrap_or_default())).is_err() {
+                        return Err(de::Error::custom(CONSUMER_GONE));
+                    }
+                    map.next_value_seed(EntriesSeed {
+                        entries: &self.entries,
+                        malformed: &self.malformed,
+                    })?;
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The `data` array. Each element is read as a JSON value first, so one that
+/// does not fit `AnimeOfflineEntry` can be skipped without losing the stream.
+struct EntriesSeed<'a> {
+    entries: &'a SyncSender<anyhow::Result<AnimeOfflineEntry>>,
+    malformed: &'a AtomicUsize,
+}
+
+impl<'de> DeserializeSeed<'de> for EntriesSeed<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for EntriesSeed<'_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of anime entries")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        let mut index = 0;
+        while let Some(value) = seq.next_element::<serde_json::Value>()? {
+            match serde_json::from_value::<AnimeOfflineEntry>(value) {
+                Ok(entry) => {
+                    if self.entries.send(Ok(entry)).is_err() {
+                        return Err(de::Error::custom(CONSUMER_GONE));
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Skipping malformed offline database entry {}: {}", index, e);
+                    self.malformed.fetch_add(1, Ordering::Relaxed);
+                }
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+}
+
 /// Individual anime entry from the offline database
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
@@ -100,8 +346,8 @@ pub struct AnimeOfflineEntry {
 }
 
 impl AnimeOfflineEntry {
-    /// Convert to Kensho Anime model
-    pub fn to_anime_model(&self) -> Anime {
+    /// Convert to Kensho Anime model; scores are read on the database's `score_range`
+    pub fn to_anime_model(&self, score_range: &ScoreRange) -> Anime {
         Anime {
             id: Uuid::new_v4(),
             title: self.title.clone(),
@@ -111,14 +357,20 @@ impl AnimeOfflineEntry {
             status: self.status.to_anime_status(),
             anime_type: self.anime_type.to_anime_type(),
             anime_season: self.anime_season.to_anime_season(),
-            synopsis: format!("Imported from anime-offline-database. Studios: {}. Tags: {}.", 
-                            self.studios.join(", "), 
-                            self.tags.join(", ")),
+            // The database has no synopses; tags are linked as has_tag edges by
+            // services::data_loader rather than written into the text
+            synopsis: String::new(),
             poster_url: self.picture.clone(),
+            posters: Anime::collect_posters(vec![self.picture.clone(), self.thumbnail.clone()]),
+            stored_episode_count: 0,
+            popularity: 0.0,
+            romaji_titles: Vec::new(),
             imdb: self.score.as_ref().map(|s| crate::models::ImdbData {
                 id: format!("offline-{}", self.title.replace(" ", "-").to_lowercase()),
-                rating: (s.arithmetic_mean * 10.0 / 10.0) as f32, // Normalize to 0-10 scale
-                votes: 100, // Default placeholder
+                rating: normalize_score(s.arithmetic_mean, score_range),
+                // The database publishes aggregate scores without vote counts
+                votes: 0,
+                last_updated: Utc::now(),
             }),
             created_at: Utc::now(),
             updated_at: Utc::now(),
@@ -129,7 +381,7 @@ impl AnimeOfflineEntry {
     pub fn get_mal_id(&self) -> Option<String> {
         self.sources.iter()
             .find(|s| s.contains("myanimelist.net"))
-            .and_then(|s| s.split('/').last())
+            .and_then(|s| s.split('/').next_back())
             .map(|s| s.to_string())
     }
 
@@ -137,7 +389,7 @@ impl AnimeOfflineEntry {
     pub fn get_anilist_id(&self) -> Option<String> {
         self.sources.iter()
             .find(|s| s.contains("anilist.co"))
-            .and_then(|s| s.split('/').last())
+            .and_then(|s| s.split('/').next_back())
             .map(|s| s.to_string())
     }
 }
@@ -179,6 +431,8 @@ pub enum OfflineAnimeStatus {
     Ongoing,
     #[serde(rename = "UPCOMING")]
     Upcoming,
+    #[serde(rename = "CANCELLED")]
+    Cancelled,
     #[serde(rename = "UNKNOWN")]
     Unknown,
 }
@@ -189,6 +443,7 @@ impl OfflineAnimeStatus {
             OfflineAnimeStatus::Finished => AnimeStatus::Finished,
             OfflineAnimeStatus::Ongoing => AnimeStatus::Ongoing,
             OfflineAnimeStatus::Upcoming => AnimeStatus::Upcoming,
+            OfflineAnimeStatus::Cancelled => AnimeStatus::Cancelled,
             OfflineAnimeStatus::Unknown => AnimeStatus::Unknown,
         }
     }
@@ -296,6 +551,34 @@ pub struct ScoreRange {
     pub max_inclusive: f64,
 }
 
+impl Default for ScoreRange {
+    /// The range the published database declares
+    fn default() -> Self {
+        ScoreRange {
+            min_inclusive: 1.0,
+            max_inclusive: 10.0,
+        }
+    }
+}
+
+/// Upper bound of the 0-10 scale ratings are stored on
+const RATING_SCALE_MAX: f64 = 10.0;
+
+/// Linearly map `raw` from `source_range` onto 0-10, clamping values outside
+/// the range. A range without width carries no scale, so its scores are only
+/// clamped to 0-10.
+pub fn normalize_score(raw: f64, source_range: &ScoreRange) -> f32 {
+    let (min, max) = (source_range.min_inclusive, source_range.max_inclusive);
+
+    let rating = if max > min {
+        (raw - min) / (max - min) * RATING_SCALE_MAX
+    } else {
+        raw
+    };
+
+    rating.clamp(0.0, RATING_SCALE_MAX) as f32
+}
+
 // Custom validation functions
 fn validate_urls(urls: &Vec<String>) -> Result<(), ValidationError> {
     for url in urls {
@@ -319,7 +602,7 @@ impl AnimeOfflineDatabase {
         for (index, chunk) in self.data.chunks(batch_size).enumerate() {
             let batch_start = index * batch_size;
             for entry in chunk {
-                imported.push(entry.to_anime_model());
+                imported.push(entry.to_anime_model(&self.score_range));
             }
             progress_callback(batch_start + chunk.len(), total);
         }
@@ -371,6 +654,85 @@ pub struct DatabaseStats {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/anime-offline-database-10.json");
+
+    fn gzip(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_gzip_round_trip() {
+        let json = std::fs::read(FIXTURE).unwrap();
+        let path = std::env::temp_dir().join(format!("anime-offline-database-{}.json.gz", Uuid::new_v4()));
+        std::fs::write(&path, gzip(&json)).unwrap();
+
+        let loaded = AnimeOfflineDatabase::load_from_gzip(path.to_str().unwrap());
+        std::fs::remove_file(&path).ok();
+
+        let loaded = loaded.unwrap();
+        let plain = AnimeOfflineDatabase::load_from_file(FIXTURE).unwrap();
+        assert_eq!(loaded.data.len(), 10);
+        assert_eq!(loaded.last_update, plain.last_update);
+        let titles = |db: &AnimeOfflineDatabase| db.data.iter().map(|e| e.title.clone()).collect::<Vec<_>>();
+        assert_eq!(titles(&loaded), titles(&plain));
+    }
+
+    #[test]
+    fn test_foreign_json_is_rejected() {
+        let mut database: serde_json::Value = serde_json::from_slice(&std::fs::read(FIXTURE).unwrap()).unwrap();
+        database["repository"] = serde_json::json!("https://example.com/some-other-dataset");
+
+        let error = AnimeOfflineDatabase::from_json_reader(database.to_string().as_bytes()).unwrap_err();
+        assert!(error.to_string().contains("unexpected repository"), "{}", error);
+    }
+
+    #[test]
+    fn test_stream_entries_skips_malformed_entries() {
+        let mut database: serde_json::Value = serde_json::from_slice(&std::fs::read(FIXTURE).unwrap()).unwrap();
+        database["data"][3] = serde_json::json!({ "title": 42 });
+        let path = std::env::temp_dir().join(format!("anime-offline-database-{}.json", Uuid::new_v4()));
+        std::fs::write(&path, database.to_string()).unwrap();
+
+        let mut entries = AnimeOfflineDatabase::stream_entries(&path).unwrap();
+        let titles: Vec<String> = entries.by_ref().map(|entry| entry.unwrap().title).collect();
+        assert_eq!(titles.len(), 9);
+        assert_eq!(entries.malformed(), 1);
+        assert_eq!(entries.score_range.max_inclusive, 10.0);
+
+        // A foreign file fails before any entry is read
+        database["repository"] = serde_json::json!("https://example.com/some-other-dataset");
+        std::fs::write(&path, database.to_string()).unwrap();
+        let error = AnimeOfflineDatabase::stream_entries(&path).err().expect("foreign file rejected");
+        assert!(format!("{:#}", error).contains("unexpected repository"), "{:#}", error);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[tokio::test]
+    async fn test_load_from_url_accepts_gzip_and_plain() {
+        use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+        let json = std::fs::read(FIXTURE).unwrap();
+        let server = MockServer::start().await;
+        Mock::given(path("/anime-offline-database.json.gz"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(gzip(&json)))
+            .mount(&server)
+            .await;
+        Mock::given(path("/anime-offline-database.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(json))
+            .mount(&server)
+            .await;
+
+        for file in ["anime-offline-database.json.gz", "anime-offline-database.json"] {
+            let database = AnimeOfflineDatabase::load_from_url(&format!("{}/{}", server.uri(), file)).await.unwrap();
+            assert_eq!(database.data.len(), 10, "{}", file);
+        }
+    }
 
     #[test]
     fn test_anime_type_conversion() {
@@ -378,6 +740,13 @@ mod tests {
         assert_eq!(OfflineAnimeType::Movie.to_anime_type(), AnimeType::Movie);
     }
 
+    #[test]
+    fn test_cancelled_status_conversion() {
+        let status: OfflineAnimeStatus = serde_json::from_str(r#""CANCELLED""#).unwrap();
+        assert_eq!(status, OfflineAnimeStatus::Cancelled);
+        assert_eq!(status.to_anime_status(), AnimeStatus::Cancelled);
+    }
+
     #[test]
     fn test_season_conversion() {
         assert_eq!(OfflineSeason::Spring.to_season(), Season::Spring);
@@ -405,9 +774,30 @@ mod tests {
         assert_eq!(score.best_score(), 8.7);
     }
 
-    #[test] 
-    fn test_url_extraction() {
-        let entry = AnimeOfflineEntry {
+    #[test]
+    fn test_score_normalization() {
+        let unit = ScoreRange { min_inclusive: 0.0, max_inclusive: 1.0 };
+        assert_eq!(normalize_score(0.0, &unit), 0.0);
+        assert_eq!(normalize_score(1.0, &unit), 10.0);
+        assert_eq!(normalize_score(0.87, &unit), 8.7);
+
+        let published = ScoreRange::default();
+        assert_eq!(normalize_score(1.0, &published), 0.0);
+        assert_eq!(normalize_score(10.0, &published), 10.0);
+        assert_eq!(normalize_score(5.5, &published), 5.0);
+
+        // Out of range values clamp
+        assert_eq!(normalize_score(-0.5, &unit), 0.0);
+        assert_eq!(normalize_score(12.0, &published), 10.0);
+
+        // No width: only clamped
+        let point = ScoreRange { min_inclusive: 7.0, max_inclusive: 7.0 };
+        assert_eq!(normalize_score(7.0, &point), 7.0);
+        assert_eq!(normalize_score(42.0, &point), 10.0);
+    }
+
+    fn entry(score: Option<AnimeScore>) -> AnimeOfflineEntry {
+        AnimeOfflineEntry {
             sources: vec![
                 "https://myanimelist.net/anime/12345".to_string(),
                 "https://anilist.co/anime/67890".to_string(),
@@ -423,13 +813,42 @@ mod tests {
             picture: "https://example.com/picture.jpg".to_string(),
             thumbnail: "https://example.com/thumbnail.jpg".to_string(),
             duration: None,
-            score: None,
+            score,
             synonyms: vec![],
             studios: vec![],
             producers: vec![],
             related_anime: vec![],
             tags: vec![],
+        }
+    }
+
+    #[test]
+    fn test_conversion_rating_and_votes() {
+        let score = AnimeScore {
+            arithmetic_geometric_mean: 8.4,
+            arithmetic_mean: 8.2,
+            median: 8.5,
         };
+        let mut scored = entry(Some(score));
+        scored.studios = vec!["Studio Deen".to_string()];
+        scored.tags = vec!["action".to_string()];
+
+        let anime = scored.to_anime_model(&ScoreRange::default());
+        let imdb = anime.imdb.expect("scored entries carry a rating");
+        // 8.2 on the published 1-10 range
+        assert!((imdb.rating - 8.0).abs() < 1e-4, "rating {}", imdb.rating);
+        assert!((0.0..=10.0).contains(&imdb.rating));
+        assert_eq!(imdb.votes, 0);
+        assert!(imdb.last_updated <= Utc::now());
+        assert!(!anime.synopsis.contains("Studio Deen"));
+        assert!(!anime.synopsis.contains("action"));
+
+        assert!(entry(None).to_anime_model(&ScoreRange::default()).imdb.is_none());
+    }
+
+    #[test] 
+    fn test_url_extraction() {
+        let entry = entry(None);
 
         assert_eq!(entry.get_mal_id(), Some("12345".to_string()));
         assert_eq!(entry.get_anilist_id(), Some("67890".to_string()));
@@ -0,0 +1,160 @@
+// Catalog change feed model: incremental updates for partners mirroring the catalog
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+use crate::models::Anime;
+
+/// Deletion markers are kept this long; older `since` values need a full resync
+pub const DELETION_RETENTION_DAYS: i64 = 90;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+/// Marker left behind when an anime is deleted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimeTombstone {
+    pub anime_id: Uuid,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// One entry of the change feed. Deletions carry no record.
+#[derive(Debug, Clone, Serialize)]
+pub struct CatalogChange {
+    pub id: Uuid,
+    pub change: ChangeKind,
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anime: Option<Anime>,
+}
+
+impl CatalogChange {
+    pub fn cursor(&self) -> ChangeCursor {
+        ChangeCursor {
+            updated_at: self.updated_at,
+            id: self.id,
+        }
+    }
+
+    /// Merge live records and deletion markers into one feed ordered by
+    /// (updated_at, id), keeping the first `limit`. Records created after
+    /// `since` are reported as created, the rest as updated.
+    pub fn merge(
+        since: DateTime<Utc>,
+        anime: Vec<Anime>,
+        tombstones: Vec<AnimeTombstone>,
+        limit: usize,
+    ) -> Vec<CatalogChange> {
+        let upserts = anime.into_iter().map(|anime| CatalogChange {
+            id: anime.id,
+            change: if anime.created_at > since { ChangeKind::Created } else { ChangeKind::Updated },
+            updated_at: anime.updated_at,
+            anime: Some(anime),
+        });
+        let deletions = tombstones.into_iter().map(|tombstone| CatalogChange {
+            id: tombstone.anime_id,
+            change: ChangeKind::Deleted,
+            updated_at: tombstone.deleted_at,
+            anime: None,
+        });
+
+        let mut changes: Vec<CatalogChange> = upserts.chain(deletions).collect();
+        changes.sort_by_key(|change| (change.updated_at, change.id));
+        changes.truncate(limit);
+        changes
+    }
+}
+
+/// Keyset position in the change feed: everything strictly after
+/// (`updated_at`, `id`). Serialized as `<unix nanos>_<uuid>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangeCursor {
+    pub updated_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl fmt::Display for ChangeCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nanos = self.updated_at.timestamp_nanos_opt().unwrap_or_default();
+        write!(f, "{}_{}", nanos, self.id.simple())
+    }
+}
+
+impl FromStr for ChangeCursor {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (nanos, id) = s
+            .split_once('_')
+            .ok_or_else(|| anyhow::anyhow!("Malformed cursor"))?;
+
+        Ok(ChangeCursor {
+            updated_at: Utc.timestamp_nanos(nanos.parse()?),
+            id: id.parse()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anime(created_at: DateTime<Utc>, updated_at: DateTime<Utc>) -> Anime {
+        let mut anime: Anime = serde_json::from_value(serde_json::json!({
+            "title": "Test",
+            "episodes": 12,
+            "status": "finished",
+            "type": "TV",
+            "anime_season": { "season": "spring", "year": 2024 },
+            "synopsis": "",
+            "poster_url": "https://example.com/poster.jpg",
+            "imdb": null,
+        }))
+        .unwrap();
+        anime.created_at = created_at;
+        anime.updated_at = updated_at;
+        anime
+    }
+
+    #[test]
+    fn test_merge_orders_and_classifies_changes() {
+        let since = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let at = |secs: i64| since + chrono::Duration::seconds(secs);
+
+        let updated = anime(at(-60), at(3));
+        let created = anime(at(1), at(1));
+        let deleted = AnimeTombstone { anime_id: Uuid::new_v4(), deleted_at: at(2) };
+
+        let changes = CatalogChange::merge(since, vec![updated.clone(), created.clone()], vec![deleted.clone()], 10);
+        let feed: Vec<(Uuid, ChangeKind)> = changes.iter().map(|c| (c.id, c.change)).collect();
+        assert_eq!(feed, vec![
+            (created.id, ChangeKind::Created),
+            (deleted.anime_id, ChangeKind::Deleted),
+            (updated.id, ChangeKind::Updated),
+        ]);
+        assert!(changes[1].anime.is_none());
+
+        let page = CatalogChange::merge(since, vec![updated, created.clone()], vec![deleted], 2);
+        assert_eq!(page.len(), 2);
+        assert_eq!(page[0].id, created.id);
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = ChangeCursor {
+            updated_at: Utc.timestamp_opt(1_700_000_000, 123_456_789).unwrap(),
+            id: Uuid::new_v4(),
+        };
+
+        assert_eq!(cursor.to_string().parse::<ChangeCursor>().unwrap(), cursor);
+        assert!("garbage".parse::<ChangeCursor>().is_err());
+        assert!("12_not-a-uuid".parse::<ChangeCursor>().is_err());
+    }
+}
@@ -0,0 +1,128 @@
+// Device model: a named client a user signs in from, so sessions and streams
+// can be listed and revoked per device
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Devices not seen for this long are deleted
+pub const DEVICE_RETENTION_DAYS: u32 = 90;
+
+/// Longest accepted device name, platform or client version
+pub const MAX_DEVICE_FIELD_LENGTH: usize = 64;
+
+/// Optional `device` object on POST /api/auth/login
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceInfo {
+    pub name: String,
+    #[serde(default)]
+    pub platform: Option<String>,
+    #[serde(default)]
+    pub client_version: Option<String>,
+}
+
+impl DeviceInfo {
+    /// Trimmed copy, or why the device can't be registered
+    pub fn normalized(&self) -> Result<DeviceInfo, String> {
+        let trim = |value: &Option<String>| {
+            value.as_deref().map(str::trim).filter(|value| !value.is_empty()).map(str::to_string)
+        };
+        let info = DeviceInfo {
+            name: self.name.trim().to_string(),
+            platform: trim(&self.platform),
+            client_version: trim(&self.client_version),
+        };
+
+        if info.name.is_empty() {
+            return Err("Device name must not be empty".to_string());
+        }
+        let too_long = [Some(&info.name), info.platform.as_ref(), info.client_version.as_ref()]
+            .into_iter()
+            .flatten()
+            .any(|value| value.chars().count() > MAX_DEVICE_FIELD_LENGTH);
+        if too_long {
+            return Err(format!("Device fields must be at most {} characters", MAX_DEVICE_FIELD_LENGTH));
+        }
+        Ok(info)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Device {
+    #[serde(default = "Uuid::new_v4")]
+    pub id: Uuid,
+
+    pub user_id: String,
+
+    pub name: String,
+
+    pub platform: Option<String>,
+
+    pub client_version: Option<String>,
+
+    /// Sessions signed in on this device; ids of ended sessions are dropped
+    /// the next time the device signs in
+    #[serde(default)]
+    pub session_ids: Vec<Uuid>,
+
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+
+    /// Stamped by the database on every write
+    #[serde(default = "Utc::now")]
+    pub last_seen_at: DateTime<Utc>,
+}
+
+impl Device {
+    pub fn new(user_id: &str, info: DeviceInfo) -> Self {
+        Device {
+            id: Uuid::new_v4(),
+            user_id: user_id.to_string(),
+            name: info.name,
+            platform: info.platform,
+            client_version: info.client_version,
+            session_ids: Vec::new(),
+            created_at: Utc::now(),
+            last_seen_at: Utc::now(),
+        }
+    }
+
+    /// True if `info` describes this device; the client version may change between sign-ins
+    pub fn matches(&self, info: &DeviceInfo) -> bool {
+        self.name == info.name && self.platform == info.platform
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(name: &str, platform: Option<&str>) -> DeviceInfo {
+        DeviceInfo {
+            name: name.to_string(),
+            platform: platform.map(str::to_string),
+            client_version: Some(" 1.2.0 ".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_device_info_normalization() {
+        let normalized = info("  Living Room TV ", Some("")).normalized().unwrap();
+        assert_eq!(normalized.name, "Living Room TV");
+        assert_eq!(normalized.platform, None);
+        assert_eq!(normalized.client_version.as_deref(), Some("1.2.0"));
+
+        assert!(info("   ", None).normalized().is_err());
+        assert!(info(&"x".repeat(MAX_DEVICE_FIELD_LENGTH + 1), None).normalized().is_err());
+    }
+
+    #[test]
+    fn test_device_matching_ignores_client_version() {
+        let device = Device::new("u1", info("Phone", Some("ios")).normalized().unwrap());
+
+        let mut upgraded = info("Phone", Some("ios"));
+        upgraded.client_version = Some("2.0.0".to_string());
+        assert!(device.matches(&upgraded));
+        assert!(!device.matches(&info("Phone", Some("android"))));
+    }
+}
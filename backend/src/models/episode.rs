@@ -3,6 +3,7 @@
 
 use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use uuid::Uuid;
 use validator::{Validate, ValidationError};
 
@@ -18,8 +19,7 @@ pub struct Episode {
     
     pub title: Option<String>,
     
-    // Custom validation temporarily removed for POC
-    // #[validate(custom(function = "validate_duration"))]
+    #[validate(custom(function = "validate_duration"))]
     pub duration: Option<u32>, // Duration in seconds
     
     pub air_date: Option<NaiveDate>,
@@ -29,6 +29,11 @@ pub struct Episode {
     #[validate(url(message = "Thumbnail URL must be valid"))]
     pub thumbnail_url: Option<String>,
     
+    /// Number counted across every season of the series (e.g. 1042), when
+    /// known; `episode_number` is always per-season
+    #[serde(default)]
+    pub absolute_number: Option<u32>,
+    
     #[serde(default = "Utc::now")]
     pub created_at: DateTime<Utc>,
     
@@ -47,6 +52,7 @@ impl Episode {
             air_date: None,
             synopsis: None,
             thumbnail_url: None,
+            absolute_number: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
@@ -80,15 +86,221 @@ impl Episode {
 }
 
 // Custom validators
-fn validate_duration(duration: &Option<u32>) -> Result<(), ValidationError> {
-    if let Some(d) = duration {
-        if *d == 0 {
-            return Err(ValidationError::new("invalid_duration"));
-        }
+fn validate_duration(duration: u32) -> Result<(), ValidationError> {
+    if duration == 0 {
+        return Err(ValidationError::new("invalid_duration"));
     }
     Ok(())
 }
 
+// Payload for one episode in a batch create
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct EpisodeCreate {
+    #[validate(range(min = 1, message = "Episode number must be > 0"))]
+    pub episode_number: u32,
+    pub title: Option<String>,
+    pub duration: Option<u32>,
+    pub air_date: Option<String>,
+    pub synopsis: Option<String>,
+    #[validate(url(message = "Thumbnail URL must be valid"))]
+    pub thumbnail_url: Option<String>,
+    /// Supplied alongside a per-season `episode_number`; both are stored as given
+    #[serde(default)]
+    pub absolute_number: Option<u32>,
+}
+
+impl EpisodeCreate {
+    pub fn into_episode(self, anime_id: Uuid) -> Episode {
+        // Air dates arrive as ISO dates; anything else is dropped
+        let air_date = self.air_date
+            .as_deref()
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+        
+        let mut episode = Episode::new(anime_id, self.episode_number).with_metadata(
+            self.title,
+            self.duration,
+            air_date,
+            self.synopsis,
+            self.thumbnail_url,
+        );
+        episode.absolute_number = self.absolute_number;
+        episode
+    }
+}
+
+/// Which number `GET /episodes` reports as `episode_number`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EpisodeNumbering {
+    /// Per-season order, starting at 1
+    #[default]
+    Season,
+    /// Counted across the whole series, where an absolute number is known
+    Absolute,
+}
+
+/// Offset of a run of absolute episode numbers: at least two numbers, starting
+/// after 1 and contiguous, like 1042..=1053 for a season that should be 1..=12
+pub fn detect_absolute_offset(numbers: &[u32]) -> Option<u32> {
+    let distinct: BTreeSet<u32> = numbers.iter().copied().collect();
+    let (&first, &last) = (distinct.first()?, distinct.last()?);
+    let contiguous = distinct.len() == numbers.len() && (last - first) as usize + 1 == distinct.len();
+    
+    (first > 1 && distinct.len() > 1 && contiguous).then_some(first - 1)
+}
+
+/// Renumber `incoming` episodes of an anime to per-season order when they
+/// arrive with absolute numbers, keeping the original as `absolute_number`.
+///
+/// With nothing stored yet the incoming numbers must form an offset run (see
+/// `detect_absolute_offset`). Otherwise they are renumbered only when every
+/// stored episode was renumbered by the same offset and the incoming numbers
+/// continue past the stored absolute ones. Episodes that carry an explicit
+/// `absolute_number` are never touched. Returns the offset that was applied.
+pub fn normalize_episode_numbers(existing: &[Episode], incoming: &mut [Episode]) -> Option<u32> {
+    if incoming.iter().any(|episode| episode.absolute_number.is_some()) {
+        return None;
+    }
+    
+    let numbers: Vec<u32> = incoming.iter().map(|episode| episode.episode_number).collect();
+    let offset = if existing.is_empty() {
+        detect_absolute_offset(&numbers)?
+    } else {
+        let offsets: HashSet<Option<u32>> = existing
+            .iter()
+            .map(|episode| episode.absolute_number.map(|absolute| absolute.saturating_sub(episode.episode_number)))
+            .collect();
+        let offset = match offsets.into_iter().collect::<Vec<_>>().as_slice() {
+            [Some(offset)] if *offset > 0 => *offset,
+            _ => return None,
+        };
+        let last_absolute = existing.iter().filter_map(|episode| episode.absolute_number).max()?;
+        if numbers.iter().any(|&number| number <= last_absolute) {
+            return None;
+        }
+        offset
+    };
+    
+    for episode in incoming.iter_mut() {
+        episode.absolute_number = Some(episode.episode_number);
+        episode.episode_number -= offset;
+    }
+    Some(offset)
+}
+
+/// Batch create rejections, with details keyed by field path (e.g. `episodes[2].episode_number`)
+#[derive(Debug, thiserror::Error)]
+pub enum EpisodeBatchError {
+    #[error("Validation failed")]
+    Invalid(BTreeMap<String, String>),
+    #[error("Duplicate episode numbers")]
+    Duplicate(BTreeMap<String, String>),
+}
+
+/// Check a batch before writing: numbers start at 1 and may not repeat within
+/// the batch or collide with `existing` episode numbers of the anime
+pub fn validate_episode_batch(batch: &[EpisodeCreate], existing: &HashSet<u32>) -> Result<(), EpisodeBatchError> {
+    let mut invalid = BTreeMap::new();
+    let mut duplicates = BTreeMap::new();
+    let mut first_seen = HashMap::new();
+
+    for (index, episode) in batch.iter().enumerate() {
+        let field = format!("episodes[{}].episode_number", index);
+        let number = episode.episode_number;
+
+        if number < 1 {
+            invalid.insert(field, "Episode number must be > 0".to_string());
+            continue;
+        }
+
+        let first = *first_seen.entry(number).or_insert(index);
+        if first != index {
+            duplicates.insert(field, format!("Duplicate episode number {} (also at episodes[{}])", number, first));
+        } else if existing.contains(&number) {
+            duplicates.insert(field, format!("Episode {} already exists", number));
+        }
+    }
+
+    if !invalid.is_empty() {
+        Err(EpisodeBatchError::Invalid(invalid))
+    } else if !duplicates.is_empty() {
+        Err(EpisodeBatchError::Duplicate(duplicates))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check new episodes for `anime_id` before writing: each passes its own
+/// validation and belongs to the anime, and no number repeats within the batch.
+/// Problems are keyed by field path like `validate_episode_batch`'s.
+pub fn validate_new_episodes(anime_id: Uuid, episodes: &[Episode]) -> Result<(), BTreeMap<String, String>> {
+    let mut problems = BTreeMap::new();
+    let mut first_seen = HashMap::new();
+
+    for (index, episode) in episodes.iter().enumerate() {
+        if let Err(errors) = episode.validate() {
+            for (field, errors) in errors.field_errors() {
+                if let Some(error) = errors.first() {
+                    let message = error.message.as_deref().map(str::to_string).unwrap_or_else(|| error.code.to_string());
+                    problems.insert(format!("episodes[{}].{}", index, field), message);
+                }
+            }
+        }
+
+        if episode.anime_id != anime_id {
+            problems.insert(
+                format!("episodes[{}].anime_id", index),
+                format!("Episode belongs to anime {}, not {}", episode.anime_id, anime_id),
+            );
+        }
+
+        let number = episode.episode_number;
+        let first = *first_seen.entry(number).or_insert(index);
+        if first != index {
+            problems
+                .entry(format!("episodes[{}].episode_number", index))
+                .or_insert_with(|| format!("Duplicate episode number {} (also at episodes[{}])", number, first));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+/// Validator for a batch's episode list: numbers may not repeat within it
+pub fn validate_unique_episode_numbers(batch: &[EpisodeCreate]) -> Result<(), ValidationError> {
+    match validate_episode_batch(batch, &HashSet::new()) {
+        Err(EpisodeBatchError::Duplicate(details)) => {
+            let mut error = ValidationError::new("duplicate_episode_number");
+            error.message = Some("Episode numbers must be unique within a batch".into());
+            for (field, message) in details {
+                error.add_param(field.into(), &message);
+            }
+            Err(error)
+        }
+        // Invalid numbers are reported by each episode's own validation
+        _ => Ok(()),
+    }
+}
+
+/// An episode of a batch that was not created, by its position in the request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpisodeItemError {
+    pub index: usize,
+    pub episode_number: u32,
+    pub message: String,
+}
+
+/// Outcome of a partial-success batch create
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EpisodeBatchResult {
+    pub created: Vec<EpisodeResponse>,
+    pub errors: Vec<EpisodeItemError>,
+}
+
 // Response DTO
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EpisodeResponse {
@@ -97,8 +309,13 @@ pub struct EpisodeResponse {
     pub title: Option<String>,
     pub duration: Option<u32>,
     pub air_date: Option<NaiveDate>,
+    /// `air_date` formatted for the request locale; display only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub air_date_display: Option<String>,
     pub synopsis: Option<String>,
     pub thumbnail_url: Option<String>,
+    #[serde(default)]
+    pub absolute_number: Option<u32>,
 }
 
 impl From<Episode> for EpisodeResponse {
@@ -109,15 +326,17 @@ impl From<Episode> for EpisodeResponse {
             title: episode.title,
             duration: episode.duration,
             air_date: episode.air_date,
+            air_date_display: None,
             synopsis: episode.synopsis,
             thumbnail_url: episode.thumbnail_url,
+            absolute_number: episode.absolute_number,
         }
     }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EpisodeListResponse {
-    pub episodes: Vec<EpisodeResponse>,
+    pub items: Vec<EpisodeResponse>,
     pub total: usize,
 }
 
@@ -159,4 +378,144 @@ mod tests {
         assert_eq!(episode.title, Some("Episode 5: The Test".to_string()));
         assert_eq!(episode.duration, Some(1440));
     }
-}
\ No newline at end of file
+    
+    fn create(episode_number: u32) -> EpisodeCreate {
+        EpisodeCreate {
+            episode_number,
+            title: None,
+            duration: None,
+            air_date: None,
+            synopsis: None,
+            thumbnail_url: None,
+            absolute_number: None,
+        }
+    }
+    
+    #[test]
+    fn test_batch_validation_errors_are_indexed() {
+        let none = HashSet::new();
+        assert!(validate_episode_batch(&[create(1), create(2)], &none).is_ok());
+        
+        match validate_episode_batch(&[create(1), create(2), create(1), create(1)], &none) {
+            Err(EpisodeBatchError::Duplicate(details)) => {
+                assert_eq!(details.len(), 2);
+                assert!(details["episodes[2].episode_number"].contains("episodes[0]"));
+                assert!(details["episodes[3].episode_number"].contains("episodes[0]"));
+            }
+            other => panic!("expected duplicates, got {:?}", other),
+        }
+        
+        let existing = HashSet::from([3]);
+        match validate_episode_batch(&[create(3), create(4)], &existing) {
+            Err(EpisodeBatchError::Duplicate(details)) => {
+                assert_eq!(details.keys().collect::<Vec<_>>(), ["episodes[0].episode_number"]);
+            }
+            other => panic!("expected duplicates, got {:?}", other),
+        }
+        
+        // Invalid numbers are reported ahead of duplicates
+        assert!(matches!(
+            validate_episode_batch(&[create(0), create(3)], &existing),
+            Err(EpisodeBatchError::Invalid(_))
+        ));
+    }
+    
+    #[test]
+    fn test_episode_create_validation() {
+        assert!(create(1).validate().is_ok());
+        assert!(create(0).validate().is_err());
+        
+        let mut episode = create(2);
+        episode.thumbnail_url = Some("not a url".to_string());
+        assert!(episode.validate().is_err());
+        
+        assert!(validate_unique_episode_numbers(&[create(1), create(2)]).is_ok());
+        assert!(validate_unique_episode_numbers(&[create(1), create(0)]).is_ok());
+        let error = validate_unique_episode_numbers(&[create(1), create(2), create(1)]).unwrap_err();
+        assert!(error.params.contains_key("episodes[2].episode_number"));
+    }
+    
+    #[test]
+    fn test_new_episodes_validation_is_aggregated() {
+        let anime_id = Uuid::new_v4();
+        assert!(validate_new_episodes(anime_id, &[Episode::new(anime_id, 1), Episode::new(anime_id, 2)]).is_ok());
+        
+        let mut bad_thumbnail = Episode::new(anime_id, 3);
+        bad_thumbnail.thumbnail_url = Some("not a url".to_string());
+        let problems = validate_new_episodes(anime_id, &[
+            Episode::new(anime_id, 1),
+            Episode::new(anime_id, 0),
+            Episode::new(anime_id, 1),
+            bad_thumbnail,
+            Episode::new(Uuid::new_v4(), 4),
+        ]).unwrap_err();
+        
+        assert_eq!(
+            problems.keys().collect::<Vec<_>>(),
+            [
+                "episodes[1].episode_number",
+                "episodes[2].episode_number",
+                "episodes[3].thumbnail_url",
+                "episodes[4].anime_id",
+            ]
+        );
+        assert!(problems["episodes[2].episode_number"].contains("episodes[0]"));
+    }
+    
+    fn numbered(anime_id: Uuid, numbers: std::ops::RangeInclusive<u32>) -> Vec<Episode> {
+        numbers.map(|number| Episode::new(anime_id, number)).collect()
+    }
+    
+    #[test]
+    fn test_detect_absolute_offset() {
+        assert_eq!(detect_absolute_offset(&[1042, 1043, 1044]), Some(1041));
+        assert_eq!(detect_absolute_offset(&[1044, 1042, 1043]), Some(1041));
+        
+        // Already per-season, gapped, repeated, or too short to tell
+        assert_eq!(detect_absolute_offset(&[1, 2, 3]), None);
+        assert_eq!(detect_absolute_offset(&[1042, 1044]), None);
+        assert_eq!(detect_absolute_offset(&[1042, 1042, 1043]), None);
+        assert_eq!(detect_absolute_offset(&[1042]), None);
+        assert_eq!(detect_absolute_offset(&[]), None);
+    }
+    
+    #[test]
+    fn test_normalize_renumbers_and_keeps_absolute() {
+        let anime_id = Uuid::new_v4();
+        let mut episodes = numbered(anime_id, 1042..=1053);
+        
+        assert_eq!(normalize_episode_numbers(&[], &mut episodes), Some(1041));
+        assert_eq!(episodes[0].episode_number, 1);
+        assert_eq!(episodes[0].absolute_number, Some(1042));
+        assert_eq!(episodes[11].episode_number, 12);
+        assert_eq!(episodes[11].absolute_number, Some(1053));
+        
+        // The next batch continues the stored run
+        let mut next = numbered(anime_id, 1054..=1055);
+        assert_eq!(normalize_episode_numbers(&episodes, &mut next), Some(1041));
+        assert_eq!((next[1].episode_number, next[1].absolute_number), (14, Some(1055)));
+        
+        // Per-season numbers for the same anime are left alone
+        let mut season = numbered(anime_id, 15..=16);
+        assert_eq!(normalize_episode_numbers(&episodes, &mut season), None);
+        assert_eq!(season[0].episode_number, 15);
+    }
+    
+    #[test]
+    fn test_normalize_leaves_explicit_and_plain_numbering() {
+        let anime_id = Uuid::new_v4();
+        
+        let mut explicit = numbered(anime_id, 1..=2);
+        explicit[0].absolute_number = Some(1042);
+        explicit[1].absolute_number = Some(1043);
+        assert_eq!(normalize_episode_numbers(&[], &mut explicit), None);
+        assert_eq!((explicit[0].episode_number, explicit[0].absolute_number), (1, Some(1042)));
+        
+        // A second cour numbered 13..=24 after a stored 1..=12 is not an offset
+        let stored = numbered(anime_id, 1..=12);
+        let mut second_cour = numbered(anime_id, 13..=24);
+        assert_eq!(normalize_episode_numbers(&stored, &mut second_cour), None);
+        assert_eq!(second_cour[0].episode_number, 13);
+        assert_eq!(second_cour[0].absolute_number, None);
+    }
+}
@@ -1,14 +1,29 @@
 pub mod anime;
+pub mod anime_offline_db;
+pub mod catalog;
+pub mod device;
 pub mod episode;
 pub mod tag;
+pub mod playback;
+pub mod preferences;
 pub mod session;
 pub mod relationships;
+pub mod user;
+pub mod watch_history;
+pub mod watchlist;
 
 #[cfg(test)]
 mod tests;
 
-pub use anime::{Anime, AnimeStatus, AnimeType, AnimeSeason, Season, ImdbData, AnimeSummary, AnimeDetail, RelatedAnime};
-pub use episode::{Episode, EpisodeResponse, EpisodeListResponse};
-pub use tag::{Tag, TagCategory, TagResponse};
-pub use session::{Session, SessionCreate, SessionResponse, Claims};
+pub use anime::{Anime, AnimeCursor, AnimeStatus, AnimeType, AnimeSeason, Season, ImdbData, AnimeSummary, AnimeSuggestion, AnimeDetail, RelatedAnime, RecommendedAnime, SimilarAnime};
+pub use catalog::{AnimeTombstone, CatalogChange, ChangeCursor};
+pub use device::{Device, DeviceInfo};
+pub use episode::{Episode, EpisodeBatchError, EpisodeBatchResult, EpisodeCreate, EpisodeItemError, EpisodeNumbering, EpisodeResponse, EpisodeListResponse};
+pub use tag::{Tag, TagCategory, TagGroup, TagResponse, TagSummary, TagWeight};
+pub use playback::PlaybackPosition;
+pub use preferences::{UserPreferences, PreferencesUpdate};
+pub use session::{Session, SessionCreate, SessionResponse, Claims, RefreshTokenRecord};
+pub use user::{User, UserRegistrationRequest};
+pub use watch_history::{ContinueWatchingItem, WatchHistoryItem, WatchProgress};
+pub use watchlist::{WatchlistEntry, WatchlistItem, WatchlistStatus};
 pub use relationships::{HasTag, IsSequelOf, IsPrequelOf, RelatedTo, RelationType, BelongsTo, RelationshipQueries};
\ No newline at end of file
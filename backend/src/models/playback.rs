@@ -0,0 +1,47 @@
+// Playback position model: where a user left off in an episode, for resuming
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::models::Episode;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlaybackPosition {
+    pub episode_id: Uuid,
+    pub anime_id: Uuid,
+    pub episode_number: u32,
+    /// Seconds into the episode
+    pub position: u32,
+    /// Episode length in seconds
+    pub duration: u32,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PlaybackPosition {
+    /// Position for `episode`, clamped so it never points past the end
+    pub fn new(episode: &Episode, position: u32, duration: u32) -> Self {
+        PlaybackPosition {
+            episode_id: episode.id,
+            anime_id: episode.anime_id,
+            episode_number: episode.episode_number,
+            position: if duration > 0 { position.min(duration) } else { position },
+            duration,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_is_clamped_to_duration() {
+        let episode = Episode::new(Uuid::new_v4(), 3);
+
+        assert_eq!(PlaybackPosition::new(&episode, 600, 1440).position, 600);
+        assert_eq!(PlaybackPosition::new(&episode, 1500, 1440).position, 1440);
+        // Unknown duration leaves the position as reported
+        assert_eq!(PlaybackPosition::new(&episode, 1500, 0).position, 1500);
+    }
+}
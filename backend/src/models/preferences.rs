@@ -0,0 +1,155 @@
+// User preferences model: playback settings that follow a user across sessions
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+/// Stream qualities a user can pin; "auto" lets the player adapt
+pub const QUALITY_OPTIONS: [&str; 5] = ["auto", "1080p", "720p", "480p", "360p"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, PartialEq)]
+pub struct UserPreferences {
+    #[validate(custom(function = "validate_language_code"))]
+    pub language: String,
+
+    #[validate(custom(function = "validate_language_code"))]
+    pub subtitle_language: String,
+
+    #[validate(custom(function = "validate_quality"))]
+    pub quality: String,
+
+    pub autoplay: bool,
+
+    pub skip_intro: bool,
+
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Default for UserPreferences {
+    fn default() -> Self {
+        UserPreferences {
+            language: "en".to_string(),
+            subtitle_language: "en".to_string(),
+            quality: "auto".to_string(),
+            autoplay: true,
+            skip_intro: false,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Partial update body for PUT /api/user/preferences; omitted fields keep their value
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PreferencesUpdate {
+    pub language: Option<String>,
+    pub subtitle_language: Option<String>,
+    pub quality: Option<String>,
+    pub autoplay: Option<bool>,
+    pub skip_intro: Option<bool>,
+}
+
+impl UserPreferences {
+    /// Apply the fields present in `update`, leaving the rest untouched
+    pub fn merge(&mut self, update: PreferencesUpdate) {
+        if let Some(language) = update.language {
+            self.language = language;
+        }
+        if let Some(subtitle_language) = update.subtitle_language {
+            self.subtitle_language = subtitle_language;
+        }
+        if let Some(quality) = update.quality {
+            self.quality = quality;
+        }
+        if let Some(autoplay) = update.autoplay {
+            self.autoplay = autoplay;
+        }
+        if let Some(skip_intro) = update.skip_intro {
+            self.skip_intro = skip_intro;
+        }
+        self.updated_at = Utc::now();
+    }
+}
+
+// ISO 639-1 code with an optional ISO 3166-1 region, e.g. "ja" or "en-US"
+fn validate_language_code(code: &str) -> Result<(), ValidationError> {
+    let mut parts = code.splitn(2, '-');
+    let language = parts.next().unwrap_or_default();
+    let region_ok = parts
+        .next()
+        .map_or(true, |region| region.len() == 2 && region.chars().all(|c| c.is_ascii_uppercase()));
+
+    if language.len() == 2 && language.chars().all(|c| c.is_ascii_lowercase()) && region_ok {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalid_language_code"))
+    }
+}
+
+fn validate_quality(quality: &str) -> Result<(), ValidationError> {
+    if QUALITY_OPTIONS.contains(&quality) {
+        Ok(())
+    } else {
+        Err(ValidationError::new("invalid_quality"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preferences_validation() {
+        assert!(UserPreferences::default().validate().is_ok());
+
+        let prefs = UserPreferences {
+            quality: "4k".to_string(),
+            subtitle_language: "english".to_string(),
+            ..UserPreferences::default()
+        };
+        let errors = prefs.validate().unwrap_err();
+        let fields = errors.field_errors();
+        assert!(fields.contains_key("quality"));
+        assert!(fields.contains_key("subtitle_language"));
+        assert!(!fields.contains_key("language"));
+
+        assert!(validate_language_code("en-US").is_ok());
+        assert!(validate_language_code("en-us").is_err());
+        assert!(validate_language_code("EN").is_err());
+    }
+
+    #[test]
+    fn test_quality_must_be_an_allowed_option() {
+        for quality in QUALITY_OPTIONS {
+            assert!(validate_quality(quality).is_ok(), "{} should be allowed", quality);
+        }
+        for quality in ["4k", "1080", "AUTO", ""] {
+            assert!(validate_quality(quality).is_err(), "{} should be rejected", quality);
+        }
+
+        let prefs = UserPreferences {
+            quality: "1440p".to_string(),
+            ..UserPreferences::default()
+        };
+        assert!(prefs.validate().unwrap_err().field_errors().contains_key("quality"));
+    }
+
+    #[test]
+    fn test_partial_merge_keeps_other_fields() {
+        let mut prefs = UserPreferences {
+            language: "ja".to_string(),
+            skip_intro: true,
+            ..UserPreferences::default()
+        };
+
+        prefs.merge(PreferencesUpdate {
+            quality: Some("720p".to_string()),
+            ..PreferencesUpdate::default()
+        });
+
+        assert_eq!(prefs.quality, "720p");
+        assert_eq!(prefs.language, "ja");
+        assert!(prefs.skip_intro);
+        assert!(prefs.autoplay);
+    }
+}
@@ -27,6 +27,14 @@ pub struct Session {
     
     #[serde(default = "Utc::now")]
     pub last_activity: DateTime<Utc>,
+    
+    /// Copied into the `admin` JWT claim; grants access to admin-only endpoints
+    #[serde(default)]
+    pub admin: bool,
+    
+    /// Device the session was signed in on, when the client named one
+    #[serde(default)]
+    pub device_id: Option<Uuid>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -36,6 +44,9 @@ pub struct Claims {
     pub exp: i64,           // Expiry timestamp
     pub iat: i64,           // Issued at
     pub cr_token_key: String,
+    pub jti: Uuid,          // Token id, used to blacklist the token on logout
+    #[serde(default)]
+    pub admin: bool,        // Admin-only endpoints require this claim
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -44,6 +55,15 @@ pub struct SessionCreate {
     pub crunchyroll_token: String,
 }
 
+/// Server-side state of an issued refresh token, stored under its hash
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RefreshTokenRecord {
+    /// Token family; every token rotated from the same login shares the session id
+    pub family: Uuid,
+    /// Set once the token has been exchanged; presenting it again signals theft
+    pub rotated: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct SessionResponse {
     pub token: String,
@@ -53,6 +73,10 @@ pub struct SessionResponse {
 
 impl Session {
     pub fn new(user_id: String, cr_token_key: String, jwt_secret: &str) -> Result<Self> {
+        Self::new_with_role(user_id, cr_token_key, jwt_secret, false)
+    }
+    
+    pub fn new_with_role(user_id: String, cr_token_key: String, jwt_secret: &str, admin: bool) -> Result<Self> {
         let session_id = Uuid::new_v4();
         let expires_at = Utc::now() + Duration::minutes(15);
         
@@ -62,6 +86,8 @@ impl Session {
             exp: expires_at.timestamp(),
             iat: Utc::now().timestamp(),
             cr_token_key: cr_token_key.clone(),
+            jti: Uuid::new_v4(),
+            admin,
         };
         
         let token = encode(
@@ -79,6 +105,8 @@ impl Session {
             refresh_token: Some(Uuid::new_v4().to_string()),
             created_at: Utc::now(),
             last_activity: Utc::now(),
+            admin,
+            device_id: None,
         })
     }
     
@@ -106,6 +134,8 @@ impl Session {
             exp: self.expires_at.timestamp(),
             iat: Utc::now().timestamp(),
             cr_token_key: self.cr_token_key.clone(),
+            jti: Uuid::new_v4(),
+            admin: self.admin,
         };
         
         let new_token = encode(
@@ -176,6 +206,8 @@ mod tests {
         let claims = Session::verify_token(&session.jwt_token, TEST_SECRET).unwrap();
         assert_eq!(claims.sub, "user456");
         assert_eq!(claims.session_id, session.id);
+        assert!(!claims.jti.is_nil());
+        assert!(!claims.admin);
     }
     
     #[test]
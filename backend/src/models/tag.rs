@@ -31,6 +31,16 @@ pub enum TagCategory {
     Content,    // Violence, Romance
 }
 
+impl TagCategory {
+    /// Every category, in the order tag listings group them
+    pub const ALL: [TagCategory; 4] = [
+        TagCategory::Genre,
+        TagCategory::Theme,
+        TagCategory::Demographic,
+        TagCategory::Content,
+    ];
+}
+
 impl Tag {
     pub fn new(name: String, category: TagCategory) -> Self {
         Tag {
@@ -46,6 +56,18 @@ impl Tag {
         self.description = Some(description);
         self
     }
+    
+    /// URL-friendly form of the name: lowercase alphanumeric runs joined by
+    /// hyphens ("Slice of Life" → "slice-of-life"). Depends only on the name,
+    /// so it stays the same across requests and deployments.
+    pub fn slug(&self) -> String {
+        self.name
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect::<Vec<_>>()
+            .join("-")
+    }
 }
 
 // Common tags for seeding
@@ -104,6 +126,99 @@ impl From<Tag> for TagResponse {
     }
 }
 
+/// A tag in the public tag listing, with the number of anime carrying it
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagSummary {
+    pub id: Uuid,
+    pub name: String,
+    pub slug: String,
+    pub category: TagCategory,
+    pub description: Option<String>,
+    pub anime_count: usize,
+}
+
+impl TagSummary {
+    pub fn new(tag: Tag, anime_count: usize) -> Self {
+        TagSummary {
+            id: tag.id,
+            slug: tag.slug(),
+            name: tag.name,
+            category: tag.category,
+            description: tag.description,
+            anime_count,
+        }
+    }
+}
+
+/// The tags of one category, sorted by name
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagGroup {
+    pub category: TagCategory,
+    pub tags: Vec<TagSummary>,
+}
+
+impl TagGroup {
+    /// One group per category in `TagCategory::ALL` order, empty ones included
+    /// so clients can rely on the shape
+    pub fn group(tags: Vec<TagSummary>) -> Vec<TagGroup> {
+        let mut groups: Vec<TagGroup> = TagCategory::ALL
+            .into_iter()
+            .map(|category| TagGroup { category, tags: Vec::new() })
+            .collect();
+        
+        for tag in tags {
+            if let Some(group) = groups.iter_mut().find(|group| group.category == tag.category) {
+                group.tags.push(tag);
+            }
+        }
+        
+        for group in &mut groups {
+            group.tags.sort_by(|a, b| {
+                a.name.to_lowercase().cmp(&b.name.to_lowercase()).then_with(|| a.id.cmp(&b.id))
+            });
+        }
+        
+        groups
+    }
+}
+
+/// A tag sized for a tag cloud: `weight` is the tag's share of the anime's total
+/// relevance, so the weights of one anime sum to 1.0
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagWeight {
+    pub id: Uuid,
+    pub name: String,
+    pub category: TagCategory,
+    pub relevance: f32,
+    pub weight: f32,
+}
+
+impl TagWeight {
+    /// Normalize `(tag, has_tag.relevance)` pairs into weights, heaviest first.
+    /// If no tag has any relevance the weight is spread evenly.
+    pub fn cloud(tags: Vec<(Tag, f32)>) -> Vec<TagWeight> {
+        let total: f32 = tags.iter().map(|(_, relevance)| relevance.max(0.0)).sum();
+        let count = tags.len() as f32;
+
+        let mut weights: Vec<TagWeight> = tags
+            .into_iter()
+            .map(|(tag, relevance)| {
+                let relevance = relevance.max(0.0);
+                TagWeight {
+                    id: tag.id,
+                    name: tag.name,
+                    category: tag.category,
+                    relevance,
+                    weight: if total > 0.0 { relevance / total } else { 1.0 / count },
+                }
+            })
+            .collect();
+
+        weights.sort_by(|a, b| b.weight.total_cmp(&a.weight).then_with(|| a.name.cmp(&b.name)));
+        weights
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,6 +240,50 @@ mod tests {
         assert!(long_tag.validate().is_err());
     }
     
+    #[test]
+    fn test_tag_slug() {
+        assert_eq!(Tag::action().slug(), "action");
+        assert_eq!(Tag::new("Slice of Life".to_string(), TagCategory::Genre).slug(), "slice-of-life");
+        assert_eq!(Tag::new(" Sci-Fi ".to_string(), TagCategory::Genre).slug(), "sci-fi");
+        assert_eq!(Tag::new("Boys' Love".to_string(), TagCategory::Genre).slug(), "boys-love");
+    }
+    
+    #[test]
+    fn test_tag_grouping() {
+        let groups = TagGroup::group(vec![
+            TagSummary::new(Tag::shounen(), 2),
+            TagSummary::new(Tag::drama(), 1),
+            TagSummary::new(Tag::action(), 5),
+        ]);
+        
+        let categories: Vec<&TagCategory> = groups.iter().map(|group| &group.category).collect();
+        assert_eq!(categories, [&TagCategory::Genre, &TagCategory::Theme, &TagCategory::Demographic, &TagCategory::Content]);
+        let genres: Vec<&str> = groups[0].tags.iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(genres, ["Action", "Drama"]);
+        assert!(groups[1].tags.is_empty());
+        assert_eq!(groups[2].tags[0].anime_count, 2);
+    }
+    
+    #[test]
+    fn test_tag_cloud_weights() {
+        let cloud = TagWeight::cloud(vec![
+            (Tag::comedy(), 0.2),
+            (Tag::action(), 0.6),
+            (Tag::drama(), 0.2),
+        ]);
+
+        let names: Vec<&str> = cloud.iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(names, ["Action", "Comedy", "Drama"]);
+        assert!((cloud[0].weight / cloud[1].weight - 3.0).abs() < 1e-5);
+        assert!((cloud.iter().map(|tag| tag.weight).sum::<f32>() - 1.0).abs() < 1e-5);
+
+        // No relevance recorded anywhere: equal shares
+        let flat = TagWeight::cloud(vec![(Tag::action(), 0.0), (Tag::drama(), 0.0)]);
+        assert!(flat.iter().all(|tag| (tag.weight - 0.5).abs() < 1e-5));
+
+        assert!(TagWeight::cloud(Vec::new()).is_empty());
+    }
+    
     #[test]
     fn test_preset_tags() {
         let action = Tag::action();
@@ -0,0 +1,127 @@
+// Local user account model
+// Passwords are only ever stored as argon2 PHC hash strings
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate)]
+pub struct User {
+    #[serde(default = "Uuid::new_v4")]
+    pub id: Uuid,
+    
+    /// Unique, stored lowercased. Accounts from before usernames are given a
+    /// `user_<id>` placeholder when the schema is initialized.
+    #[serde(default)]
+    pub username: String,
+    
+    #[validate(email(message = "Email must be valid"))]
+    pub email: String,
+    
+    /// Set by following the link from registration; login is refused until then.
+    /// Accounts from before verification existed count as verified.
+    #[serde(default = "verified_by_default")]
+    pub email_verified: bool,
+    
+    pub password_hash: String,
+    
+    /// Granted out of band (e.g. `UPDATE user:<id> SET is_admin = true`); never via the API
+    #[serde(default)]
+    pub is_admin: bool,
+    
+    #[serde(default = "Utc::now")]
+    pub created_at: DateTime<Utc>,
+    
+    #[serde(default = "Utc::now")]
+    pub updated_at: DateTime<Utc>,
+}
+
+fn verified_by_default() -> bool {
+    true
+}
+
+impl User {
+    /// A new account, waiting for its email address to be verified
+    pub fn new(username: String, email: String, password_hash: String) -> Self {
+        User {
+            id: Uuid::new_v4(),
+            username: username.to_lowercase(),
+            email: email.to_lowercase(),
+            email_verified: false,
+            password_hash,
+            is_admin: false,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Body of `POST /api/auth/register`
+#[derive(Debug, Deserialize, Validate)]
+pub struct UserRegistrationRequest {
+    #[validate(
+        length(min = 3, max = 32, message = "Username must be between 3 and 32 characters"),
+        custom(function = "validate_username")
+    )]
+    pub username: String,
+    
+    #[validate(email(message = "Email must be valid"))]
+    pub email: String,
+    
+    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    pub password: String,
+}
+
+/// Letters, digits, `_`, `-` and `.`, so usernames are safe in URLs and mentions
+fn validate_username(username: &str) -> Result<(), ValidationError> {
+    if username.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+        Ok(())
+    } else {
+        let mut error = ValidationError::new("username_characters");
+        error.message = Some("Username may only contain letters, digits, '_', '-' and '.'".into());
+        Err(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_email_normalized_and_validated() {
+        let user = User::new("Fan".to_string(), "Fan@Example.com".to_string(), "$argon2id$...".to_string());
+        assert_eq!(user.username, "fan");
+        assert_eq!(user.email, "fan@example.com");
+        assert!(!user.email_verified);
+        assert!(user.validate().is_ok());
+
+        let invalid = User::new("fan".to_string(), "not-an-email".to_string(), String::new());
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_registration_request_validation() {
+        let request = |username: &str, email: &str, password: &str| UserRegistrationRequest {
+            username: username.to_string(),
+            email: email.to_string(),
+            password: password.to_string(),
+        };
+
+        assert!(request("fan_01", "fan@example.com", "long enough").validate().is_ok());
+        assert!(request("fan_01", "fan@example.com", "short").validate().is_err());
+        assert!(request("fan_01", "not-an-email", "long enough").validate().is_err());
+        assert!(request("fa", "fan@example.com", "long enough").validate().is_err());
+        assert!(request("fan 01", "fan@example.com", "long enough").validate().is_err());
+    }
+
+    #[test]
+    fn test_accounts_from_before_verification_count_as_verified() {
+        let user: User = serde_json::from_value(serde_json::json!({
+            "email": "old@example.com",
+            "password_hash": "$argon2id$..."
+        })).unwrap();
+        assert!(user.email_verified);
+        assert_eq!(user.username, "");
+    }
+}
@@ -0,0 +1,67 @@
+// Watch history model: user -> user_watched -> anime edges, one per episode
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::models::{AnimeSummary, EpisodeResponse};
+
+/// Share of an episode that has to be watched for it to count as completed
+pub const COMPLETION_THRESHOLD_PERCENT: u64 = 95;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchProgress {
+    pub episode_id: Uuid,
+    pub anime_id: Uuid,
+    pub episode_number: u32,
+    /// Seconds watched
+    pub progress: u32,
+    /// Episode length in seconds
+    pub total_duration: u32,
+    pub completed: bool,
+    pub watched_at: DateTime<Utc>,
+}
+
+impl WatchProgress {
+    /// Completed when the client says so or once at least 95% has been watched
+    pub fn is_completed(progress: u32, total_duration: u32, reported: bool) -> bool {
+        reported
+            || (total_duration > 0
+                && progress as u64 * 100 >= total_duration as u64 * COMPLETION_THRESHOLD_PERCENT)
+    }
+}
+
+/// Watch progress joined with its episode and anime for rendering history lists
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WatchHistoryItem {
+    #[serde(flatten)]
+    pub progress: WatchProgress,
+    pub episode: EpisodeResponse,
+    pub anime: AnimeSummary,
+}
+
+/// An episode the user started but has not finished, for the "Continue watching" row
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContinueWatchingItem {
+    pub anime_id: Uuid,
+    pub anime_title: String,
+    pub poster_url: String,
+    pub episode_id: Uuid,
+    pub episode_number: u32,
+    pub progress_ms: u64,
+    pub total_duration_ms: u64,
+    pub watched_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_threshold() {
+        assert!(!WatchProgress::is_completed(720, 1440, false));
+        assert!(!WatchProgress::is_completed(1367, 1440, false));
+        assert!(WatchProgress::is_completed(1368, 1440, false));
+        assert!(WatchProgress::is_completed(10, 1440, true));
+        assert!(!WatchProgress::is_completed(0, 0, false));
+    }
+}
@@ -0,0 +1,57 @@
+// Watchlist model: user -> watchlist_entry -> anime edges
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::models::AnimeSummary;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum WatchlistStatus {
+    #[default]
+    #[serde(alias = "plan_to_watch")]
+    Planned,
+    Watching,
+    Completed,
+    Dropped,
+    /// Paused for now, meant to be picked up again
+    #[serde(rename = "on_hold")]
+    OnHold,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistEntry {
+    pub anime_id: Uuid,
+    pub status: WatchlistStatus,
+    pub added_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Watchlist entry joined with its anime, so clients can render posters directly
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WatchlistItem {
+    #[serde(flatten)]
+    pub entry: WatchlistEntry,
+    pub anime: AnimeSummary,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_serialization() {
+        assert_eq!(serde_json::to_string(&WatchlistStatus::Watching).unwrap(), "\"watching\"");
+        assert_eq!(
+            serde_json::from_str::<WatchlistStatus>("\"dropped\"").unwrap(),
+            WatchlistStatus::Dropped
+        );
+        assert_eq!(serde_json::to_string(&WatchlistStatus::OnHold).unwrap(), "\"on_hold\"");
+        assert_eq!(
+            serde_json::from_str::<WatchlistStatus>("\"plan_to_watch\"").unwrap(),
+            WatchlistStatus::Planned
+        );
+        assert!(serde_json::from_str::<WatchlistStatus>("\"paused\"").is_err());
+        assert_eq!(WatchlistStatus::default(), WatchlistStatus::Planned);
+    }
+}
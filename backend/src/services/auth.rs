@@ -2,16 +2,154 @@
 // Reference: plan.md "Crunchyroll Integration" section
 
 use anyhow::{Result, Context, bail};
+use argon2::{Algorithm, Argon2, Params, PasswordHasher, PasswordVerifier, Version};
+use argon2::password_hash::{PasswordHash, SaltString, rand_core::OsRng};
 use crunchyroll_rs::Crunchyroll;
 use redis::AsyncCommands;
+use ring::rand::{SecureRandom, SystemRandom};
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
-use crate::models::{Session, SessionCreate, SessionResponse};
+use crate::models::{Session, SessionResponse, RefreshTokenRecord, User, UserRegistrationRequest};
+use crate::services::{CacheService, DatabaseService};
+use crate::services::mailer::MailerConfig;
+use crate::services::database_v2::{violates_unique_index, USER_EMAIL_INDEX, USER_USERNAME_INDEX};
+
+/// Argon2id cost parameters for password hashing
+#[derive(Debug, Clone)]
+pub struct PasswordHashConfig {
+    /// Memory cost in KiB
+    pub memory_kib: u32,
+    /// Number of iterations (time cost)
+    pub time_cost: u32,
+    /// Degree of parallelism
+    pub parallelism: u32,
+}
+
+impl Default for PasswordHashConfig {
+    fn default() -> Self {
+        // OWASP-recommended minimums for argon2id
+        PasswordHashConfig {
+            memory_kib: 19 * 1024,
+            time_cost: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+impl PasswordHashConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        
+        let memory_kib = std::env::var("ARGON2_MEMORY_KIB")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(defaults.memory_kib);
+        
+        let time_cost = std::env::var("ARGON2_TIME_COST")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(defaults.time_cost);
+        
+        let parallelism = std::env::var("ARGON2_PARALLELISM")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(defaults.parallelism);
+        
+        PasswordHashConfig {
+            memory_kib,
+            time_cost,
+            parallelism,
+        }
+    }
+    
+    fn hasher(&self) -> Result<Argon2<'static>> {
+        let params = Params::new(self.memory_kib, self.time_cost, self.parallelism, None)
+            .map_err(|e| anyhow::anyhow!("Invalid argon2 parameters: {}", e))?;
+        
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+    
+    /// Hash a password into a PHC string (salt and parameters embedded)
+    pub fn hash_password(&self, password: &str) -> Result<String> {
+        let salt = SaltString::generate(&mut OsRng);
+        let hash = self.hasher()?
+            .hash_password(password.as_bytes(), &salt)
+            .map_err(|e| anyhow::anyhow!("Failed to hash password: {}", e))?;
+        
+        Ok(hash.to_string())
+    }
+    
+    /// Verify a password against a stored PHC string
+    /// Returns Ok(false) on mismatch and Err if the stored hash is malformed
+    pub fn verify_password(&self, password: &str, hash: &str) -> Result<bool> {
+        let parsed = PasswordHash::new(hash)
+            .map_err(|e| anyhow::anyhow!("Invalid password hash: {}", e))?;
+        
+        match self.hasher()?.verify_password(password.as_bytes(), &parsed) {
+            Ok(()) => Ok(true),
+            Err(argon2::password_hash::Error::Password) => Ok(false),
+            Err(e) => Err(anyhow::anyhow!("Password verification failed: {}", e)),
+        }
+    }
+    
+    /// `hash_password` on the blocking thread pool. Argon2 spends tens of
+    /// milliseconds of CPU by design, which would stall an async worker.
+    pub async fn hash_password_async(&self, password: &str) -> Result<String> {
+        let (config, password) = (self.clone(), password.to_string());
+        tokio::task::spawn_blocking(move || config.hash_password(&password)).await?
+    }
+    
+    /// `verify_password` on the blocking thread pool
+    pub async fn verify_password_async(&self, password: &str, hash: &str) -> Result<bool> {
+        let (config, password, hash) = (self.clone(), password.to_string(), hash.to_string());
+        tokio::task::spawn_blocking(move || config.verify_password(&password, &hash)).await?
+    }
+}
+
+/// Lifetime of a session (and its refresh token) in Redis
+const SESSION_TTL_SECS: u64 = 900;
+
+/// Refresh failures the API maps to distinct responses
+#[derive(Debug, thiserror::Error)]
+pub enum RefreshTokenError {
+    #[error("Invalid refresh token")]
+    Invalid,
+    /// An already-rotated token was presented; the whole family has been revoked
+    #[error("Refresh token reuse detected")]
+    ReuseDetected,
+}
+
+/// How long the link sent on registration can verify the email address
+const EMAIL_VERIFICATION_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Registration failures the API maps to distinct responses
+#[derive(Debug, thiserror::Error)]
+pub enum RegistrationError {
+    #[error("That username is taken")]
+    UsernameTaken,
+    #[error("An account with this email already exists")]
+    EmailTaken,
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// 32 random bytes, hex encoded
+fn generate_verification_token() -> String {
+    let mut bytes = [0u8; 32];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .expect("System RNG unavailable");
+
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
 
 pub struct AuthService {
     crunchyroll: Option<Arc<Crunchyroll>>,
     redis_client: Arc<tokio::sync::Mutex<redis::aio::ConnectionManager>>,
     jwt_secret: String,
+    password_config: PasswordHashConfig,
+    token_store: CacheService,
 }
 
 impl AuthService {
@@ -21,7 +159,7 @@ impl AuthService {
             .context("Failed to create Redis client")?;
         
         tracing::debug!("Establishing Redis connection...");
-        let redis_conn = redis::aio::ConnectionManager::new(redis_client).await
+        let redis_conn = crate::services::cache::connect_redis(redis_client).await
             .context("Failed to establish Redis connection")?;
         
         tracing::debug!("Redis connection established successfully");
@@ -29,13 +167,74 @@ impl AuthService {
         // Initialize without a session - will be set on login
         let crunchyroll = None;
         
+        let token_store = CacheService::new(redis_url).await?;
+        
         Ok(AuthService {
             crunchyroll,
             redis_client: Arc::new(tokio::sync::Mutex::new(redis_conn)),
             jwt_secret,
+            password_config: PasswordHashConfig::from_env(),
+            token_store,
         })
     }
     
+    /// Argon2 settings, for hashing and verifying passwords without holding
+    /// the AuthService lock
+    pub fn password_config(&self) -> PasswordHashConfig {
+        self.password_config.clone()
+    }
+    
+    /// Create an account that cannot log in until its email address is verified,
+    /// and mail it a verification link that lasts 24 hours. `password_hash` comes
+    /// from `password_config()`. Returns the new user's id.
+    pub async fn register(
+        &mut self,
+        db: &DatabaseService,
+        mail: &MailerConfig,
+        request: &UserRegistrationRequest,
+        password_hash: String,
+    ) -> Result<Uuid, RegistrationError> {
+        if db.get_user_by_username(&request.username).await?.is_some() {
+            return Err(RegistrationError::UsernameTaken);
+        }
+        if db.get_user_by_email(&request.email).await?.is_some() {
+            return Err(RegistrationError::EmailTaken);
+        }
+        
+        let user = User::new(request.username.clone(), request.email.clone(), password_hash);
+        // A concurrent registration can take the name after the checks above;
+        // the unique indexes still refuse the second account
+        if let Err(e) = db.create_user(&user).await {
+            return Err(if violates_unique_index(&e, USER_USERNAME_INDEX) {
+                RegistrationError::UsernameTaken
+            } else if violates_unique_index(&e, USER_EMAIL_INDEX) {
+                RegistrationError::EmailTaken
+            } else {
+                RegistrationError::Other(e)
+            });
+        }
+        
+        let token = generate_verification_token();
+        self.token_store
+            .store_email_verification(&token, user.id, EMAIL_VERIFICATION_TTL)
+            .await?;
+        tracing::info!(user_id = %user.id, "Email verification token issued");
+        mail.send_verification(&user.email, &token)
+            .await
+            .context("Failed to send verification email")?;
+        
+        Ok(user.id)
+    }
+    
+    /// Verify the email address `token` was issued for, consuming the token.
+    /// False for unknown, used or expired tokens.
+    pub async fn verify_email(&mut self, db: &DatabaseService, token: &str) -> Result<bool> {
+        match self.token_store.take_email_verification(token).await? {
+            Some(user_id) => db.mark_email_verified(user_id).await,
+            None => Ok(false),
+        }
+    }
+    
     pub async fn login(&mut self, email: &str, password: &str) -> Result<SessionResponse> {
         // For testing without Crunchyroll, provide a mock authentication path
         let (user_id, cr_token) = if email == "test@example.com" && password == "password" {
@@ -58,29 +257,45 @@ impl AuthService {
             (user_id, cr_token)
         };
         
+        self.create_session(&user_id, cr_token).await
+    }
+    
+    /// Issue a new session for an already-authenticated user
+    pub async fn create_session(&mut self, user_id: &str, cr_token: String) -> Result<SessionResponse> {
+        self.create_session_with_role(user_id, cr_token, false).await
+    }
+    
+    /// Issue a new session, carrying the user's admin flag into the JWT claims
+    pub async fn create_session_with_role(&mut self, user_id: &str, cr_token: String, admin: bool) -> Result<SessionResponse> {
+        let user_id = user_id.to_string();
+        
         // Store Crunchyroll session in Redis
         let cr_token_key = format!("cr_token:{}", user_id);
         
         // Store with 15-minute expiry
         self.redis_client.lock().await
-            .set_ex(&cr_token_key, cr_token, 900)
+            .set_ex::<_, _, ()>(&cr_token_key, cr_token, SESSION_TTL_SECS)
             .await?;
         
-        // Create our session
-        let session = Session::new(user_id.clone(), cr_token_key, &self.jwt_secret)?;
+        // Create our session; the refresh token lives hashed in the token store, not on the session
+        let mut session = Session::new_with_role(user_id.clone(), cr_token_key, &self.jwt_secret, admin)?;
+        session.refresh_token = None;
+        let refresh_token = self.issue_refresh_token(&user_id, session.id).await?;
         
         // Store session in Redis
         let session_data = serde_json::to_string(&session)?;
         self.redis_client.lock().await
-            .set_ex(&session.redis_key(), session_data, 900)
+            .set_ex::<_, _, ()>(&session.redis_key(), session_data, SESSION_TTL_SECS)
             .await?;
         
         // Map user to session for quick lookup
         self.redis_client.lock().await
-            .set_ex(&Session::redis_user_key(&user_id), session.id.to_string(), 900)
+            .set_ex::<_, _, ()>(&Session::redis_user_key(&user_id), session.id.to_string(), SESSION_TTL_SECS)
             .await?;
         
-        Ok(session.to_response())
+        let mut response = session.to_response();
+        response.refresh_token = Some(refresh_token);
+        Ok(response)
     }
     
     pub async fn verify_session(&mut self, token: &str) -> Result<Session> {
@@ -113,54 +328,181 @@ impl AuthService {
         // Save updated session
         let updated_data = serde_json::to_string(&session)?;
         self.redis_client.lock().await
-            .set_ex(&session_key, updated_data, 900)
+            .set_ex::<_, _, ()>(&session_key, updated_data, SESSION_TTL_SECS)
             .await?;
         
         Ok(session)
     }
     
     pub async fn refresh_session(&mut self, refresh_token: &str) -> Result<SessionResponse> {
-        // Find session by refresh token
-        let pattern = "session:*";
-        let keys: Vec<String> = self.redis_client.lock().await.keys(pattern).await?;
+        let (token, refresh_token) = self.rotate_refresh_token(refresh_token).await?;
+        let claims = Session::verify_token(&token, &self.jwt_secret)?;
+        let expires_at = chrono::DateTime::from_timestamp(claims.exp, 0)
+            .context("Invalid token expiry")?;
         
-        for key in keys {
-            let session_data: String = self.redis_client.lock().await.get(&key).await?;
-            let mut session: Session = serde_json::from_str(&session_data)?;
-            
-            if session.refresh_token.as_ref() == Some(&refresh_token.to_string()) {
-                // Refresh the session
-                let new_token = session.refresh(&self.jwt_secret)?;
-                
-                // Update in Redis
-                let updated_data = serde_json::to_string(&session)?;
-                self.redis_client.lock().await
-                    .set_ex(&key, updated_data, 900)
-                    .await?;
-                
-                return Ok(session.to_response());
+        Ok(SessionResponse {
+            token,
+            expires_at,
+            refresh_token: Some(refresh_token),
+        })
+    }
+    
+    /// Exchange a refresh token for a new (access token, refresh token) pair.
+    /// The old token is marked rotated; presenting it again revokes the whole
+    /// token family and fails with `RefreshTokenError::ReuseDetected`.
+    pub async fn rotate_refresh_token(&mut self, old_refresh_token: &str) -> Result<(String, String)> {
+        let (user_id, _) = old_refresh_token
+            .rsplit_once('.')
+            .ok_or(RefreshTokenError::Invalid)?;
+        let token_hash = Self::hash_refresh_token(old_refresh_token);
+        
+        let record = self.token_store
+            .get_refresh_token(user_id, &token_hash)
+            .await?
+            .ok_or(RefreshTokenError::Invalid)?;
+        
+        if record.rotated {
+            tracing::warn!("Refresh token reuse detected for user {}; revoking family {}", user_id, record.family);
+            self.revoke_token_family(user_id, record.family).await?;
+            return Err(RefreshTokenError::ReuseDetected.into());
+        }
+        
+        let session_key = format!("session:{}", record.family);
+        let session_data: Option<String> = self.redis_client.lock().await.get(&session_key).await?;
+        let mut session: Session = match session_data {
+            Some(data) => serde_json::from_str(&data)?,
+            None => return Err(RefreshTokenError::Invalid.into()),
+        };
+        
+        // Keep the spent token as a tripwire for as long as the family can live
+        let rotated = RefreshTokenRecord { family: record.family, rotated: true };
+        self.token_store
+            .store_refresh_token(user_id, &token_hash, &rotated, Duration::from_secs(SESSION_TTL_SECS))
+            .await?;
+        
+        let access_token = session.refresh(&self.jwt_secret)?;
+        let updated_data = serde_json::to_string(&session)?;
+        self.redis_client.lock().await
+            .set_ex::<_, _, ()>(&session_key, updated_data, SESSION_TTL_SECS)
+            .await?;
+        
+        let refresh_token = self.issue_refresh_token(user_id, record.family).await?;
+        
+        Ok((access_token, refresh_token))
+    }
+    
+    async fn issue_refresh_token(&mut self, user_id: &str, family: Uuid) -> Result<String> {
+        // The user id prefix lets the store be keyed by user without a reverse index
+        let token = format!("{}.{}", user_id, Uuid::new_v4().simple());
+        let record = RefreshTokenRecord { family, rotated: false };
+        
+        self.token_store
+            .store_refresh_token(user_id, &Self::hash_refresh_token(&token), &record, Duration::from_secs(SESSION_TTL_SECS))
+            .await?;
+        
+        Ok(token)
+    }
+    
+    /// Revoke every refresh token in a family and end the session it belongs to
+    async fn revoke_token_family(&mut self, user_id: &str, family: Uuid) -> Result<()> {
+        self.token_store.revoke_refresh_family(user_id, family).await?;
+        
+        let mut redis = self.redis_client.lock().await;
+        let _: () = redis.del(format!("session:{}", family)).await?;
+        
+        let user_key = Session::redis_user_key(user_id);
+        let current: Option<String> = redis.get(&user_key).await?;
+        if current.as_deref() == Some(family.to_string().as_str()) {
+            let _: () = redis.del(&user_key).await?;
+        }
+        
+        Ok(())
+    }
+    
+    /// Record the device a freshly issued access token was signed in on;
+    /// returns the token's session
+    pub async fn attach_device(&mut self, token: &str, device_id: Uuid) -> Result<Session> {
+        let claims = Session::verify_token(token, &self.jwt_secret)?;
+        let session_key = format!("session:{}", claims.session_id);
+        let session_data: String = self.redis_client.lock().await
+            .get(&session_key)
+            .await
+            .context("Session not found")?;
+        
+        let mut session: Session = serde_json::from_str(&session_data)?;
+        session.device_id = Some(device_id);
+        
+        let updated_data = serde_json::to_string(&session)?;
+        self.redis_client.lock().await
+            .set_ex::<_, _, ()>(&session_key, updated_data, SESSION_TTL_SECS)
+            .await?;
+        
+        Ok(session)
+    }
+    
+    /// The ids in `session_ids` whose sessions have not ended
+    pub async fn live_sessions(&mut self, session_ids: &[Uuid]) -> Result<Vec<Uuid>> {
+        let mut redis = self.redis_client.lock().await;
+        let mut live = Vec::new();
+        for &session_id in session_ids {
+            let exists: bool = redis.exists(format!("session:{}", session_id)).await?;
+            if exists {
+                live.push(session_id);
             }
         }
         
-        bail!("Invalid refresh token")
+        Ok(live)
+    }
+    
+    /// End a session and its refresh tokens; its access tokens stop verifying
+    pub async fn revoke_session(&mut self, user_id: &str, session_id: Uuid) -> Result<()> {
+        self.revoke_token_family(user_id, session_id).await
+    }
+    
+    fn hash_refresh_token(token: &str) -> String {
+        ring::digest::digest(&ring::digest::SHA256, token.as_bytes())
+            .as_ref()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+    
+    /// True if the access token was blacklisted by a logout
+    pub async fn is_token_revoked(&mut self, token: &str) -> Result<bool> {
+        let claims = Session::verify_token(token, &self.jwt_secret)?;
+        self.token_store.is_token_blacklisted(&claims.jti.to_string()).await
     }
     
     pub async fn logout(&mut self, token: &str) -> Result<()> {
         let claims = Session::verify_token(token, &self.jwt_secret)?;
         
+        // Blacklist the access token for the rest of its lifetime
+        let remaining = (claims.exp - chrono::Utc::now().timestamp()).max(1) as u64;
+        self.token_store
+            .blacklist_token(&claims.jti.to_string(), Duration::from_secs(remaining))
+            .await?;
+        
         // Delete session from Redis
         let session_key = format!("session:{}", claims.session_id);
-        self.redis_client.lock().await.del(&session_key).await?;
+        self.redis_client.lock().await.del::<_, ()>(&session_key).await?;
         
         // Delete user mapping
-        self.redis_client.lock().await.del(&Session::redis_user_key(&claims.sub)).await?;
+        self.redis_client.lock().await.del::<_, ()>(&Session::redis_user_key(&claims.sub)).await?;
         
         // Delete Crunchyroll token
-        self.redis_client.lock().await.del(&claims.cr_token_key).await?;
+        self.redis_client.lock().await.del::<_, ()>(&claims.cr_token_key).await?;
+        
+        // Refresh tokens from this session must not outlive it
+        self.token_store.revoke_refresh_family(&claims.sub, claims.session_id).await?;
         
         Ok(())
     }
     
+    /// The most recent Crunchyroll login, if any, for service-level calls such as health checks
+    pub fn service_crunchyroll_client(&self) -> Option<Arc<Crunchyroll>> {
+        self.crunchyroll.clone()
+    }
+    
     pub async fn get_crunchyroll_client(&mut self, session: &Session) -> Result<Arc<Crunchyroll>> {
         // Try to get cached Crunchyroll session from Redis
         let cr_token: Option<String> = self.redis_client.lock().await
@@ -168,7 +510,7 @@ impl AuthService {
             .await
             .ok();
         
-        if let Some(token) = cr_token {
+        if let Some(_token) = cr_token {
             // Deserialize and create Crunchyroll client
             // Note: crunchyroll-rs doesn't expose session serialization directly
             // This would need custom implementation or PR to the library
@@ -183,13 +525,6 @@ impl AuthService {
         // Actual implementation depends on crunchyroll-rs internals
         Ok("serialized_session".to_string())
     }
-    
-    async fn deserialize_cr_session(&self, _data: &str) -> Result<Crunchyroll> {
-        // This would deserialize the Crunchyroll session
-        // Note: crunchyroll-rs doesn't expose session serialization
-        // Would need to re-authenticate
-        bail!("Session deserialization not supported - re-authentication required")
-    }
 }
 
 // Auth middleware helper
@@ -216,6 +551,58 @@ impl AuthMiddleware {
 mod tests {
     use super::*;
     
+    fn fast_config() -> PasswordHashConfig {
+        // Keep unit tests quick; production uses from_env()/default()
+        PasswordHashConfig {
+            memory_kib: 1024,
+            time_cost: 1,
+            parallelism: 1,
+        }
+    }
+    
+    #[test]
+    fn test_password_hash_verifies() {
+        let config = fast_config();
+        let hash = config.hash_password("demo123").unwrap();
+        
+        assert!(hash.starts_with("$argon2id$"));
+        assert!(config.verify_password("demo123", &hash).unwrap());
+        assert!(!config.verify_password("wrong", &hash).unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_password_hash_verifies_on_blocking_pool() {
+        let config = fast_config();
+        let hash = config.hash_password_async("demo123").await.unwrap();
+        
+        assert!(config.verify_password("demo123", &hash).unwrap());
+        assert!(config.verify_password_async("demo123", &hash).await.unwrap());
+        assert!(!config.verify_password_async("wrong", &hash).await.unwrap());
+        assert!(config.verify_password_async("demo123", "not-a-phc-string").await.is_err());
+    }
+    
+    #[test]
+    fn test_refresh_token_hash_is_stable_hex() {
+        let hash = AuthService::hash_refresh_token("user.abc");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, AuthService::hash_refresh_token("user.abc"));
+        assert_ne!(hash, AuthService::hash_refresh_token("user.abd"));
+    }
+    
+    #[test]
+    fn test_tampered_hash_fails() {
+        let config = fast_config();
+        let hash = config.hash_password("demo123").unwrap();
+        
+        // Flip the last character of the digest
+        let mut tampered = hash.clone();
+        let last = tampered.pop().unwrap();
+        tampered.push(if last == 'A' { 'B' } else { 'A' });
+        
+        assert!(!matches!(config.verify_password("demo123", &tampered), Ok(true)));
+        assert!(config.verify_password("demo123", "not-a-phc-string").is_err());
+    }
+    
     #[tokio::test]
     #[ignore] // Requires Redis running
     async fn test_auth_service_creation() {
@@ -239,4 +626,26 @@ mod tests {
         let result = service.login("test@example.com", "password").await;
         assert!(result.is_err()); // Expected to fail with test credentials
     }
-}
\ No newline at end of file
+    
+    #[tokio::test]
+    #[ignore] // Requires Redis
+    async fn test_refresh_token_rotation_rejects_reuse() {
+        let mut service = AuthService::new(
+            "redis://localhost:6379",
+            "test_secret".to_string()
+        ).await.unwrap();
+        
+        let session = service.create_session("rotation_user", String::new()).await.unwrap();
+        let original = session.refresh_token.unwrap();
+        
+        let (access_token, rotated) = service.rotate_refresh_token(&original).await.unwrap();
+        assert_ne!(rotated, original);
+        assert!(service.verify_session(&access_token).await.is_ok());
+        
+        // Replaying the spent token revokes the family, including the rotated token
+        let reuse = service.rotate_refresh_token(&original).await.unwrap_err();
+        assert!(matches!(reuse.downcast_ref::<RefreshTokenError>(), Some(RefreshTokenError::ReuseDetected)));
+        assert!(service.rotate_refresh_token(&rotated).await.is_err());
+        assert!(service.verify_session(&access_token).await.is_err());
+    }
+}
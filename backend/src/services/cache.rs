@@ -1,34 +1,408 @@
-// Cache service for Redis operations
+// Cache service with pluggable storage backends
+// Redis is used in production; `memory://` selects an in-process store so
+// tests and local development can run without a Redis server.
 
 use anyhow::{Result, Context};
+use async_trait::async_trait;
 use redis::AsyncCommands;
 use serde::{Serialize, de::DeserializeOwned};
-use std::time::Duration;
+use std::collections::{BTreeSet, HashMap};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+use crate::models::{PlaybackPosition, RefreshTokenRecord, UserPreferences};
 
-pub struct CacheService {
+/// Schema version baked into every cache key.
+///
+/// Bump this whenever the serialized shape of a cached response model changes
+/// (e.g. adding/removing/renaming a field on `AnimeSummary` or `AnimeDetail`).
+/// New deployments then read and write under a fresh `v{N}:` namespace, so they
+/// never deserialize blobs written by older code; the previous namespace simply
+/// expires via its TTLs. To reclaim memory early after a deploy, call
+/// `DELETE /api/admin/cache/{old_version}`.
+pub const CACHE_SCHEMA_VERSION: u32 = 1;
+
+/// Redis set holding origins allowed by `dynamic_cors_middleware` at runtime
+pub const CORS_ORIGINS_KEY: &str = "cors:allowed_origins";
+
+/// Redis set of playback positions saved since the last flush, as `user_id:episode_id`
+const PROGRESS_DIRTY_KEY: &str = "progress_dirty";
+
+/// How long a snapshot of the CORS allow-list is served before Redis is re-read
+pub const CORS_ORIGINS_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// How long `get_or_set_with_not_found` remembers that a value does not exist
+pub const NOT_FOUND_TTL: Duration = Duration::from_secs(60);
+
+/// Longest a `get_or_set` loader holds its key's lock; a crashed holder
+/// blocks other callers no longer than this
+pub const LOAD_LOCK_TTL: Duration = Duration::from_secs(10);
+
+/// How long `get_or_set` callers wait on another caller's load before
+/// running their own loader
+pub const LOAD_LOCK_WAIT: Duration = Duration::from_secs(2);
+
+/// Poll interval while waiting on another caller's load
+const LOAD_LOCK_POLL: Duration = Duration::from_millis(20);
+
+/// Deletes KEYS[1] only while it still holds ARGV[1], in one step
+const COMPARE_AND_DELETE_SCRIPT: &str =
+    "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) else return 0 end";
+
+/// URL scheme that selects the in-memory backend
+pub const MEMORY_CACHE_SCHEME: &str = "memory://";
+
+/// How long each attempt to reach Redis may take, so an unreachable server
+/// fails startup instead of hanging it
+const REDIS_CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Attempts to reach Redis made after the first one fails
+const REDIS_CONNECT_RETRIES: usize = 2;
+
+/// Longest wait between attempts, in milliseconds. Left uncapped, the
+/// connection manager's backoff grows a hundredfold per attempt.
+const REDIS_CONNECT_MAX_DELAY_MS: u64 = 1_000;
+
+/// Connect to Redis within `REDIS_CONNECT_TIMEOUT` per attempt
+pub async fn connect_redis(client: redis::Client) -> redis::RedisResult<redis::aio::ConnectionManager> {
+    let config = redis::aio::ConnectionManagerConfig::new()
+        .set_connection_timeout(REDIS_CONNECT_TIMEOUT)
+        .set_number_of_retries(REDIS_CONNECT_RETRIES)
+        .set_max_delay(REDIS_CONNECT_MAX_DELAY_MS);
+    redis::aio::ConnectionManager::new_with_config(client, config).await
+}
+
+/// Raw key/value storage behind `CacheService`.
+/// Keys passed here are already fully namespaced; values are serialized JSON.
+#[async_trait]
+pub trait Cache: Send + Sync {
+    async fn get(&self, key: &str) -> Result<Option<String>>;
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<()>;
+    async fn delete(&self, key: &str) -> Result<()>;
+    async fn exists(&self, key: &str) -> Result<bool>;
+    async fn expire(&self, key: &str, ttl: Duration) -> Result<()>;
+    /// Set `key` only if it does not exist (`SET NX PX`); false if it does
+    async fn set_nx(&self, key: &str, value: String, ttl: Duration) -> Result<bool>;
+    /// Delete `key` only while it holds `value`; false if it holds anything else
+    async fn delete_if_equals(&self, key: &str, value: &str) -> Result<bool>;
+    /// Keys matching a glob pattern (`*` and `?` wildcards)
+    async fn keys(&self, pattern: &str) -> Result<Vec<String>>;
+    /// Atomically increment a counter and refresh its expiry
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<u64>;
+    /// Add a member to a set that does not expire; false if already present
+    async fn set_add(&self, key: &str, member: &str) -> Result<bool>;
+    /// Remove a member from a set; false if it was not present
+    async fn set_remove(&self, key: &str, member: &str) -> Result<bool>;
+    async fn set_members(&self, key: &str) -> Result<Vec<String>>;
+    /// The Redis connection behind this backend, for health checks; None when not Redis
+    fn redis_connection(&self) -> Option<redis::aio::ConnectionManager> {
+        None
+    }
+}
+
+pub struct RedisCache {
     client: redis::aio::ConnectionManager,
 }
 
-impl CacheService {
+impl RedisCache {
     pub async fn new(redis_url: &str) -> Result<Self> {
         tracing::debug!("Creating Redis client for cache service with URL: {}", redis_url);
         let client = redis::Client::open(redis_url)
             .context("Failed to create Redis client for cache")?;
         
         tracing::debug!("Establishing Redis connection for cache...");
-        let conn = redis::aio::ConnectionManager::new(client).await
+        let conn = connect_redis(client).await
             .context("Failed to establish Redis connection for cache")?;
         
         tracing::debug!("Cache service Redis connection established");
         
-        Ok(CacheService { client: conn })
+        Ok(RedisCache { client: conn })
+    }
+    
+    // ConnectionManager is a cheap handle onto a shared multiplexed connection
+    fn conn(&self) -> redis::aio::ConnectionManager {
+        self.client.clone()
+    }
+}
+
+#[async_trait]
+impl Cache for RedisCache {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.conn().get(key).await?)
     }
     
+    fn redis_connection(&self) -> Option<redis::aio::ConnectionManager> {
+        Some(self.conn())
+    }
+    
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<()> {
+        let _: () = self.conn().set_ex(key, value, ttl.as_secs().max(1)).await?;
+        Ok(())
+    }
+    
+    async fn delete(&self, key: &str) -> Result<()> {
+        let _: () = self.conn().del(key).await?;
+        Ok(())
+    }
+    
+    async fn exists(&self, key: &str) -> Result<bool> {
+        Ok(self.conn().exists(key).await?)
+    }
+    
+    async fn expire(&self, key: &str, ttl: Duration) -> Result<()> {
+        let _: bool = self.conn().expire(key, ttl.as_secs() as i64).await?;
+        Ok(())
+    }
+    
+    async fn set_nx(&self, key: &str, value: String, ttl: Duration) -> Result<bool> {
+        let set: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("NX")
+            .arg("PX")
+            .arg(ttl.as_millis().max(1) as u64)
+            .query_async(&mut self.conn())
+            .await?;
+        Ok(set.is_some())
+    }
+    
+    async fn delete_if_equals(&self, key: &str, value: &str) -> Result<bool> {
+        let deleted: u64 = redis::Script::new(COMPARE_AND_DELETE_SCRIPT)
+            .key(key)
+            .arg(value)
+            .invoke_async(&mut self.conn())
+            .await?;
+        Ok(deleted > 0)
+    }
+    
+    async fn keys(&self, pattern: &str) -> Result<Vec<String>> {
+        Ok(self.conn().keys(pattern).await?)
+    }
+    
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<u64> {
+        let (count,): (u64,) = redis::pipe()
+            .atomic()
+            .incr(key, 1u64)
+            .expire(key, ttl.as_secs() as i64)
+            .ignore()
+            .query_async(&mut self.conn())
+            .await?;
+        
+        Ok(count)
+    }
+    
+    async fn set_add(&self, key: &str, member: &str) -> Result<bool> {
+        let added: u64 = self.conn().sadd(key, member).await?;
+        Ok(added > 0)
+    }
+    
+    async fn set_remove(&self, key: &str, member: &str) -> Result<bool> {
+        let removed: u64 = self.conn().srem(key, member).await?;
+        Ok(removed > 0)
+    }
+    
+    async fn set_members(&self, key: &str) -> Result<Vec<String>> {
+        Ok(self.conn().smembers(key).await?)
+    }
+}
+
+/// In-process cache with per-key TTLs; expired entries are dropped lazily on access
+#[derive(Default)]
+pub struct MemoryCache {
+    entries: Mutex<HashMap<String, (String, Instant)>>,
+    sets: Mutex<HashMap<String, BTreeSet<String>>>,
+}
+
+impl MemoryCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    fn entries(&self) -> std::sync::MutexGuard<'_, HashMap<String, (String, Instant)>> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        entries.retain(|_, (_, expires_at)| *expires_at > now);
+        entries
+    }
+    
+    fn sets(&self) -> std::sync::MutexGuard<'_, HashMap<String, BTreeSet<String>>> {
+        self.sets.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait]
+impl Cache for MemoryCache {
+    async fn get(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.entries().get(key).map(|(value, _)| value.clone()))
+    }
+    
+    async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<()> {
+        self.entries().insert(key.to_string(), (value, Instant::now() + ttl));
+        Ok(())
+    }
+    
+    async fn delete(&self, key: &str) -> Result<()> {
+        self.entries().remove(key);
+        self.sets().remove(key);
+        Ok(())
+    }
+    
+    async fn exists(&self, key: &str) -> Result<bool> {
+        Ok(self.entries().contains_key(key) || self.sets().contains_key(key))
+    }
+    
+    async fn expire(&self, key: &str, ttl: Duration) -> Result<()> {
+        if let Some((_, expires_at)) = self.entries().get_mut(key) {
+            *expires_at = Instant::now() + ttl;
+        }
+        Ok(())
+    }
+    
+    async fn set_nx(&self, key: &str, value: String, ttl: Duration) -> Result<bool> {
+        let mut entries = self.entries();
+        if entries.contains_key(key) {
+            return Ok(false);
+        }
+        entries.insert(key.to_string(), (value, Instant::now() + ttl));
+        Ok(true)
+    }
+    
+    async fn delete_if_equals(&self, key: &str, value: &str) -> Result<bool> {
+        let mut entries = self.entries();
+        if !entries.get(key).is_some_and(|(held, _)| held == value) {
+            return Ok(false);
+        }
+        entries.remove(key);
+        Ok(true)
+    }
+    
+    async fn keys(&self, pattern: &str) -> Result<Vec<String>> {
+        Ok(self.entries()
+            .keys()
+            .filter(|key| glob_match(pattern, key))
+            .cloned()
+            .collect())
+    }
+    
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<u64> {
+        let mut entries = self.entries();
+        let count = match entries.get(key) {
+            Some((value, _)) => value.parse::<u64>().context("Cached value is not a counter")? + 1,
+            None => 1,
+        };
+        entries.insert(key.to_string(), (count.to_string(), Instant::now() + ttl));
+        Ok(count)
+    }
+    
+    async fn set_add(&self, key: &str, member: &str) -> Result<bool> {
+        Ok(self.sets().entry(key.to_string()).or_default().insert(member.to_string()))
+    }
+    
+    async fn set_remove(&self, key: &str, member: &str) -> Result<bool> {
+        let mut sets = self.sets();
+        let Some(members) = sets.get_mut(key) else {
+            return Ok(false);
+        };
+        let removed = members.remove(member);
+        // Like Redis, an emptied set no longer exists
+        if members.is_empty() {
+            sets.remove(key);
+        }
+        Ok(removed)
+    }
+    
+    async fn set_members(&self, key: &str) -> Result<Vec<String>> {
+        Ok(self.sets().get(key).map(|members| members.iter().cloned().collect()).unwrap_or_default())
+    }
+}
+
+/// Redis-style glob matching for the `*` and `?` wildcards
+fn glob_match(pattern: &str, key: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let key: Vec<char> = key.chars().collect();
+    let (mut p, mut k) = (0, 0);
+    let mut backtrack = None;
+    
+    while k < key.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == key[k]) {
+            p += 1;
+            k += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, k));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            k = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Lookups of cached GET responses since this instance started
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+pub struct ResponseCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+pub struct CacheService {
+    backend: Arc<dyn Cache>,
+    version: u32,
+    response_stats: ResponseCacheStats,
+}
+
+impl CacheService {
+    /// Connect to the backend named by `url`: `memory://` or a Redis URL
+    pub async fn new(url: &str) -> Result<Self> {
+        let backend: Arc<dyn Cache> = if url.starts_with(MEMORY_CACHE_SCHEME) {
+            tracing::debug!("Using in-memory cache backend");
+            Arc::new(MemoryCache::new())
+        } else {
+            Arc::new(RedisCache::new(url).await?)
+        };
+        
+        Ok(Self::with_backend(backend))
+    }
+    
+    pub fn with_backend(backend: Arc<dyn Cache>) -> Self {
+        CacheService {
+            backend,
+            version: CACHE_SCHEMA_VERSION,
+            response_stats: ResponseCacheStats::default(),
+        }
+    }
+    
+    /// Override the schema version namespace (mainly useful for tests and migrations)
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.version = version;
+        self
+    }
+    
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+    
+    /// Prefix a logical key with a schema version namespace
+    pub fn namespaced_key(version: u32, key: &str) -> String {
+        format!("v{}:{}", version, key)
+    }
+    
+    fn versioned(&self, key: &str) -> String {
+        Self::namespaced_key(self.version, key)
+    }
+    
+    #[tracing::instrument(skip_all)]
     pub async fn get<T: DeserializeOwned>(&mut self, key: &str) -> Result<Option<T>> {
-        let data: Option<String> = self.client
-            .get(key)
+        let data = self.backend
+            .get(&self.versioned(key))
             .await
-            .ok();
+            .ok()
+            .flatten();
         
         match data {
             Some(json) => {
@@ -39,32 +413,166 @@ impl CacheService {
         }
     }
     
+    #[tracing::instrument(skip_all)]
     pub async fn set<T: Serialize>(&mut self, key: &str, value: &T, ttl: Duration) -> Result<()> {
         let json = serde_json::to_string(value)?;
         
-        self.client
-            .set_ex(key, json, ttl.as_secs())
-            .await?;
-        
-        Ok(())
+        self.backend.set(&self.versioned(key), json, ttl).await
     }
     
+    #[tracing::instrument(skip_all)]
     pub async fn delete(&mut self, key: &str) -> Result<()> {
-        let _: () = self.client.del(key).await?;
-        Ok(())
+        self.backend.delete(&self.versioned(key)).await
     }
     
+    #[tracing::instrument(skip_all)]
     pub async fn exists(&mut self, key: &str) -> Result<bool> {
-        let exists: bool = self.client.exists(key).await?;
-        Ok(exists)
+        self.backend.exists(&self.versioned(key)).await
     }
     
+    #[tracing::instrument(skip_all)]
     pub async fn expire(&mut self, key: &str, ttl: Duration) -> Result<()> {
-        let _: bool = self.client
-            .expire(key, ttl.as_secs() as i64)
-            .await?;
+        self.backend.expire(&self.versioned(key), ttl).await
+    }
+    
+    /// The Redis connection when the backend is Redis, for `HealthService::check_redis`
+    pub fn redis_connection(&self) -> Option<redis::aio::ConnectionManager> {
+        self.backend.redis_connection()
+    }
+    
+    /// A service on the same backend and namespace, with its own response stats.
+    /// Lets a caller release the shared `Mutex<CacheService>` before slow work,
+    /// such as the database call inside `get_or_set`.
+    pub fn handle(&self) -> CacheService {
+        CacheService {
+            backend: self.backend.clone(),
+            version: self.version,
+            response_stats: ResponseCacheStats::default(),
+        }
+    }
+    
+    /// The cached value at `key`, or else the result of `f`, which is cached for
+    /// `ttl`. Cache failures only cost the caching: `f` still runs and its
+    /// result is returned. Errors from `f` are returned and not cached.
+    ///
+    /// Concurrent misses on one key run `f` once: the first caller takes a lock
+    /// on the key and the others wait for its value, up to `LOAD_LOCK_WAIT`,
+    /// before running `f` themselves.
+    pub async fn get_or_set<T, F, Fut>(&self, key: &str, ttl: Duration, f: F) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        self.load_once(key, |_| ttl, f).await
+    }
+    
+    /// `get_or_set` for lookups that may find nothing: `Ok(None)` from `f` is
+    /// cached too, for `NOT_FOUND_TTL`, so repeated requests for a missing id
+    /// do not all reach the database
+    pub async fn get_or_set_with_not_found<T, F, Fut>(&self, key: &str, ttl: Duration, f: F) -> Result<Option<T>>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<Option<T>>>,
+    {
+        // `null` is the not-found sentinel; found values serialize as themselves
+        self.load_once(key, |value: &Option<T>| if value.is_some() { ttl } else { NOT_FOUND_TTL }, f).await
+    }
+    
+    /// Cached value at `key`, or else `f`'s, loaded by one caller at a time
+    async fn load_once<T, F, Fut>(&self, key: &str, ttl: impl Fn(&T) -> Duration, f: F) -> Result<T>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        if let Some(value) = self.read(key).await {
+            return Ok(value);
+        }
         
-        Ok(())
+        let lock = self.versioned(&Self::load_lock_key(key));
+        // Identifies this caller's hold on the lock, so it never releases another's
+        let token = Uuid::new_v4().to_string();
+        let deadline = Instant::now() + LOAD_LOCK_WAIT;
+        let locked = loop {
+            match self.backend.set_nx(&lock, token.clone(), LOAD_LOCK_TTL).await {
+                Ok(true) => break true,
+                Ok(false) if Instant::now() < deadline => {
+                    tokio::time::sleep(LOAD_LOCK_POLL).await;
+                    if let Some(value) = self.read(key).await {
+                        return Ok(value);
+                    }
+                }
+                Ok(false) => {
+                    tracing::debug!("Gave up waiting on the load of {}", key);
+                    break false;
+                }
+                Err(e) => {
+                    tracing::debug!("Cache lock failed for {}: {}", key, e);
+                    break false;
+                }
+            }
+        };
+        
+        // The previous holder may have written the value and released the lock
+        // between the last read and taking it
+        if locked {
+            if let Some(value) = self.read(key).await {
+                self.release_load_lock(key, &lock, &token).await;
+                return Ok(value);
+            }
+        }
+        
+        let loaded = f().await;
+        if let Ok(value) = &loaded {
+            self.write(key, value, ttl(value)).await;
+        }
+        // Waiters fall back to their own loader if the value was not written
+        if locked {
+            self.release_load_lock(key, &lock, &token).await;
+        }
+        loaded
+    }
+    
+    /// Release `lock` if this caller still holds it. A load that outlived
+    /// LOAD_LOCK_TTL has lost it, possibly to another caller, whose hold stays.
+    async fn release_load_lock(&self, key: &str, lock: &str, token: &str) {
+        match self.backend.delete_if_equals(lock, token).await {
+            Ok(true) => {}
+            Ok(false) => tracing::debug!("Cache lock for {} expired before its load finished", key),
+            Err(e) => tracing::debug!("Failed to release cache lock for {}: {}", key, e),
+        }
+    }
+    
+    /// Cached value at `key`; unreadable entries count as misses
+    async fn read<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let json = match self.backend.get(&self.versioned(key)).await {
+            Ok(json) => json?,
+            Err(e) => {
+                tracing::debug!("Cache read failed for {}: {}", key, e);
+                return None;
+            }
+        };
+        
+        serde_json::from_str(&json)
+            .map_err(|e| tracing::debug!("Ignoring undecodable cache entry {}: {}", key, e))
+            .ok()
+    }
+    
+    async fn write<T: Serialize>(&self, key: &str, value: &T, ttl: Duration) {
+        let result = match serde_json::to_string(value) {
+            Ok(json) => self.backend.set(&self.versioned(key), json, ttl).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Cache write failed for {}: {}", key, e);
+        }
+    }
+    
+    /// Held while one caller loads `key` for `get_or_set`
+    fn load_lock_key(key: &str) -> String {
+        format!("lock:{}", key)
     }
     
     // Cache keys for different entities
@@ -80,11 +588,82 @@ impl CacheService {
         format!("search:{}", query.to_lowercase().replace(" ", "_"))
     }
     
+    /// `query` as normalized by `search::normalize_suggest_query`
+    pub fn suggest_key(query: &str) -> String {
+        format!("suggest:{}", query)
+    }
+    
+    /// One page of a season listing; `listing` names its filter, sort and page
+    pub fn season_browse_key(year: u16, season: &str, listing: &str) -> String {
+        format!("browse:season:{}:{}:{}", year, season.to_lowercase(), listing)
+    }
+    
+    /// Every cached page of a season, for `invalidate_pattern`
+    pub fn season_browse_pattern(year: u16, season: &str) -> String {
+        Self::season_browse_key(year, season, "*")
+    }
+    
     pub fn stream_key(episode_id: &str) -> String {
         format!("stream:{}", episode_id)
     }
     
+    pub fn renditions_key(episode_id: &str) -> String {
+        format!("renditions:{}", episode_id)
+    }
+    
+    pub fn etag_key(scope: &str) -> String {
+        format!("etag:{}", scope)
+    }
+    
+    /// ETags of a scope's cached GET responses (an anime, or a season's browse
+    /// pages), keyed by request (path, query and language); one key per scope so
+    /// an edit invalidates all of them at once
+    pub async fn get_etags(&mut self, scope: &str) -> Result<HashMap<String, String>> {
+        Ok(self.get(&Self::etag_key(scope)).await?.unwrap_or_default())
+    }
+    
+    pub async fn store_etag(&mut self, scope: &str, request: &str, etag: &str, ttl: Duration) -> Result<()> {
+        let mut etags = self.get_etags(scope).await?;
+        etags.insert(request.to_string(), etag.to_string());
+        self.set(&Self::etag_key(scope), &etags, ttl).await
+    }
+    
+    pub async fn invalidate_etags(&mut self, scope: &str) -> Result<()> {
+        self.delete(&Self::etag_key(scope)).await
+    }
+    
+    /// Cached GET response for `request` within `group`. Groups are what
+    /// writes invalidate: `anime:<id>`, `season:<year>:<season>`, `search`.
+    pub fn response_key(group: &str, request: &str) -> String {
+        format!("response:{}|{}", group, request)
+    }
+    
+    pub async fn get_response<T: DeserializeOwned>(&mut self, key: &str) -> Result<Option<T>> {
+        let cached = self.get(key).await?;
+        if cached.is_some() {
+            self.response_stats.hits += 1;
+        } else {
+            self.response_stats.misses += 1;
+        }
+        Ok(cached)
+    }
+    
+    pub async fn store_response<T: Serialize>(&mut self, key: &str, response: &T, ttl: Duration) -> Result<()> {
+        self.set(key, response, ttl).await
+    }
+    
+    /// Drop every cached response of the groups matching `group` (a glob, so
+    /// `season:*` covers all seasons); returns how many were dropped
+    pub async fn invalidate_responses(&mut self, group: &str) -> Result<usize> {
+        self.invalidate_pattern(&format!("response:{}|*", group)).await
+    }
+    
+    pub fn response_stats(&self) -> ResponseCacheStats {
+        self.response_stats
+    }
+    
     // Batch operations
+    #[tracing::instrument(skip_all)]
     pub async fn get_many<T: DeserializeOwned>(&mut self, keys: &[String]) -> Result<Vec<Option<T>>> {
         let mut results = Vec::new();
         
@@ -95,16 +674,301 @@ impl CacheService {
         Ok(results)
     }
     
+    #[tracing::instrument(skip_all)]
     pub async fn invalidate_pattern(&mut self, pattern: &str) -> Result<usize> {
-        let keys: Vec<String> = self.client.keys(pattern).await?;
+        let keys = self.backend.keys(&self.versioned(pattern)).await?;
         let count = keys.len();
         
         for key in keys {
-            self.delete(&key).await?;
+            self.backend.delete(&key).await?;
         }
         
         Ok(count)
     }
+    
+    /// Increment a counter and refresh its expiry.
+    /// Counters are not cached data, so the key is not version-namespaced.
+    #[tracing::instrument(skip_all)]
+    pub async fn increment_counter(&mut self, key: &str, ttl: Duration) -> Result<u64> {
+        self.backend.increment(key, ttl).await
+    }
+    
+    // Refresh token store. Like counters, these keys are not version-namespaced:
+    // a cache purge must not log everybody out.
+    pub fn refresh_token_key(user_id: &str, token_hash: &str) -> String {
+        format!("refresh_token:{}:{}", user_id, token_hash)
+    }
+    
+    pub async fn store_refresh_token(
+        &mut self,
+        user_id: &str,
+        token_hash: &str,
+        record: &RefreshTokenRecord,
+        ttl: Duration,
+    ) -> Result<()> {
+        let data = serde_json::to_string(record)?;
+        self.backend.set(&Self::refresh_token_key(user_id, token_hash), data, ttl).await
+    }
+    
+    pub async fn get_refresh_token(&mut self, user_id: &str, token_hash: &str) -> Result<Option<RefreshTokenRecord>> {
+        let data = self.backend
+            .get(&Self::refresh_token_key(user_id, token_hash))
+            .await?;
+        
+        match data {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+    
+    /// Delete every refresh token the user holds in the given family
+    pub async fn revoke_refresh_family(&mut self, user_id: &str, family: Uuid) -> Result<usize> {
+        let keys = self.backend
+            .keys(&Self::refresh_token_key(user_id, "*"))
+            .await?;
+        let mut revoked = 0;
+        
+        for key in keys {
+            let data = self.backend.get(&key).await?;
+            let in_family = data
+                .and_then(|json| serde_json::from_str::<RefreshTokenRecord>(&json).ok())
+                .map(|record| record.family == family)
+                .unwrap_or(false);
+            
+            if in_family {
+                self.backend.delete(&key).await?;
+                revoked += 1;
+            }
+        }
+        
+        Ok(revoked)
+    }
+    
+    // Runtime CORS allow-list. The set itself is configuration, so it is
+    // unversioned; requests read a versioned snapshot that lives for a minute,
+    // so direct edits to the set reach every instance within that window.
+    fn cors_snapshot_key() -> String {
+        format!("{}:snapshot", CORS_ORIGINS_KEY)
+    }
+    
+    /// Allowed origins, served from the snapshot while it is fresh
+    pub async fn cors_origins(&mut self) -> Result<Vec<String>> {
+        if let Some(origins) = self.get(&Self::cors_snapshot_key()).await? {
+            return Ok(origins);
+        }
+        
+        let origins = self.backend.set_members(CORS_ORIGINS_KEY).await?;
+        self.set(&Self::cors_snapshot_key(), &origins, CORS_ORIGINS_CACHE_TTL).await?;
+        Ok(origins)
+    }
+    
+    /// Add an origin and drop the snapshot; false if it was already allowed
+    pub async fn add_cors_origin(&mut self, origin: &str) -> Result<bool> {
+        let added = self.backend.set_add(CORS_ORIGINS_KEY, origin).await?;
+        self.delete(&Self::cors_snapshot_key()).await?;
+        Ok(added)
+    }
+    
+    /// Remove an origin and drop the snapshot; false if it was not allowed
+    pub async fn remove_cors_origin(&mut self, origin: &str) -> Result<bool> {
+        let removed = self.backend.set_remove(CORS_ORIGINS_KEY, origin).await?;
+        self.delete(&Self::cors_snapshot_key()).await?;
+        Ok(removed)
+    }
+    
+    // Playback positions are user data rather than cached responses, so they
+    // are unversioned too; they live here until flushed to the database.
+    // Each user's episodes are indexed in a set, and every save marks the
+    // position dirty, so neither reads nor the flush have to list keys.
+    pub fn progress_key(user_id: &str, episode_id: &str) -> String {
+        format!("progress:{}:{}", user_id, episode_id)
+    }
+    
+    fn progress_index_key(user_id: &str) -> String {
+        format!("progress_index:{}", user_id)
+    }
+    
+    pub async fn store_progress(&mut self, user_id: &str, position: &PlaybackPosition, ttl: Duration) -> Result<()> {
+        let episode_id = position.episode_id.to_string();
+        let data = serde_json::to_string(position)?;
+        self.backend
+            .set(&Self::progress_key(user_id, &episode_id), data, ttl)
+            .await?;
+        
+        let index = Self::progress_index_key(user_id);
+        self.backend.set_add(&index, &episode_id).await?;
+        self.backend.expire(&index, ttl).await?;
+        self.backend.set_add(PROGRESS_DIRTY_KEY, &format!("{}:{}", user_id, episode_id)).await?;
+        Ok(())
+    }
+    
+    pub async fn get_progress(&mut self, user_id: &str, episode_id: &str) -> Result<Option<PlaybackPosition>> {
+        match self.backend.get(&Self::progress_key(user_id, episode_id)).await? {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+    
+    pub async fn delete_progress(&mut self, user_id: &str, episode_id: &str) -> Result<()> {
+        self.backend.delete(&Self::progress_key(user_id, episode_id)).await?;
+        self.backend.set_remove(&Self::progress_index_key(user_id), episode_id).await?;
+        Ok(())
+    }
+    
+    /// Episodes `user_id` has a cached position for. Positions may have
+    /// expired since; `get_progress_many` drops those from the index.
+    pub async fn progress_episode_ids(&mut self, user_id: &str) -> Result<Vec<String>> {
+        self.backend.set_members(&Self::progress_index_key(user_id)).await
+    }
+    
+    /// The cached positions of `user_id` among `episode_ids`
+    pub async fn get_progress_many(&mut self, user_id: &str, episode_ids: &[String]) -> Result<Vec<PlaybackPosition>> {
+        let mut positions = Vec::with_capacity(episode_ids.len());
+        
+        for episode_id in episode_ids {
+            match self.get_progress(user_id, episode_id).await? {
+                Some(position) => positions.push(position),
+                None => {
+                    self.backend.set_remove(&Self::progress_index_key(user_id), episode_id).await?;
+                }
+            }
+        }
+        
+        Ok(positions)
+    }
+    
+    /// Positions saved since they were last taken, as `user_id:episode_id`
+    pub async fn dirty_progress(&mut self) -> Result<Vec<String>> {
+        self.backend.set_members(PROGRESS_DIRTY_KEY).await
+    }
+    
+    /// Clear the dirty marks in `dirty` (from `dirty_progress`) and read their
+    /// positions as (user_id, position). A save after this marks it again.
+    pub async fn take_dirty_progress(&mut self, dirty: &[String]) -> Result<Vec<(String, PlaybackPosition)>> {
+        let mut positions = Vec::with_capacity(dirty.len());
+        
+        for member in dirty {
+            self.backend.set_remove(PROGRESS_DIRTY_KEY, member).await?;
+            let Some((user_id, episode_id)) = member.rsplit_once(':') else { continue };
+            // The position may have expired since it was saved
+            if let Some(position) = self.get_progress(user_id, episode_id).await? {
+                positions.push((user_id.to_string(), position));
+            }
+        }
+        
+        Ok(positions)
+    }
+    
+    /// Mark positions dirty again after a failed flush
+    pub async fn mark_progress_dirty(&mut self, dirty: &[String]) -> Result<()> {
+        for member in dirty {
+            self.backend.set_add(PROGRESS_DIRTY_KEY, member).await?;
+        }
+        Ok(())
+    }
+    
+    // User preferences are user data too, so unversioned; the database is authoritative
+    pub fn preferences_key(user_id: &str) -> String {
+        format!("preferences:{}", user_id)
+    }
+    
+    pub async fn store_preferences(&mut self, user_id: &str, preferences: &UserPreferences, ttl: Duration) -> Result<()> {
+        let data = serde_json::to_string(preferences)?;
+        self.backend.set(&Self::preferences_key(user_id), data, ttl).await
+    }
+    
+    pub async fn get_preferences(&mut self, user_id: &str) -> Result<Option<UserPreferences>> {
+        match self.backend.get(&Self::preferences_key(user_id)).await? {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+    
+    // Stream grants and active stream lists back live playback sessions, so
+    // they are unversioned as well
+    pub fn stream_grant_key(token: &str) -> String {
+        format!("stream_grant:{}", token)
+    }
+    
+    pub fn active_streams_key(user_id: &str) -> String {
+        format!("active_stream_list:{}", user_id)
+    }
+    
+    pub async fn store_stream_grant<T: Serialize>(&mut self, token: &str, grant: &T, ttl: Duration) -> Result<()> {
+        let data = serde_json::to_string(grant)?;
+        self.backend.set(&Self::stream_grant_key(token), data, ttl).await
+    }
+    
+    pub async fn get_stream_grant<T: DeserializeOwned>(&mut self, token: &str) -> Result<Option<T>> {
+        match self.backend.get(&Self::stream_grant_key(token)).await? {
+            Some(json) => Ok(Some(serde_json::from_str(&json)?)),
+            None => Ok(None),
+        }
+    }
+    
+    pub async fn delete_stream_grant(&mut self, token: &str) -> Result<()> {
+        self.backend.delete(&Self::stream_grant_key(token)).await
+    }
+    
+    pub async fn store_active_streams<T: Serialize>(&mut self, user_id: &str, streams: &[T], ttl: Duration) -> Result<()> {
+        let data = serde_json::to_string(streams)?;
+        self.backend.set(&Self::active_streams_key(user_id), data, ttl).await
+    }
+    
+    pub async fn get_active_streams<T: DeserializeOwned>(&mut self, user_id: &str) -> Result<Vec<T>> {
+        match self.backend.get(&Self::active_streams_key(user_id)).await? {
+            Some(json) => Ok(serde_json::from_str(&json)?),
+            None => Ok(Vec::new()),
+        }
+    }
+    
+    // Access token blacklist, unversioned for the same reason as refresh tokens
+    pub fn blacklist_key(jti: &str) -> String {
+        format!("token_blacklist:{}", jti)
+    }
+    
+    /// Revoke an access token until it would have expired anyway
+    pub async fn blacklist_token(&mut self, jti: &str, ttl: Duration) -> Result<()> {
+        self.backend.set(&Self::blacklist_key(jti), "1".to_string(), ttl).await
+    }
+    
+    pub async fn is_token_blacklisted(&mut self, jti: &str) -> Result<bool> {
+        self.backend.exists(&Self::blacklist_key(jti)).await
+    }
+    
+    // Email verification tokens outlive deploys too, so they are unversioned
+    pub fn email_verification_key(token: &str) -> String {
+        format!("email_verification:{}", token)
+    }
+    
+    pub async fn store_email_verification(&mut self, token: &str, user_id: Uuid, ttl: Duration) -> Result<()> {
+        self.backend.set(&Self::email_verification_key(token), user_id.to_string(), ttl).await
+    }
+    
+    /// The user a verification token was issued to, consuming the token
+    pub async fn take_email_verification(&mut self, token: &str) -> Result<Option<Uuid>> {
+        let key = Self::email_verification_key(token);
+        let Some(user_id) = self.backend.get(&key).await? else {
+            return Ok(None);
+        };
+        self.backend.delete(&key).await?;
+        Ok(Uuid::parse_str(&user_id).ok())
+    }
+    
+    /// Remove every key written under the given schema version namespace
+    pub async fn purge_version(&mut self, version: u32) -> Result<usize> {
+        let pattern = Self::namespaced_key(version, "*");
+        let keys = self.backend.keys(&pattern).await?;
+        let count = keys.len();
+        
+        for key in keys {
+            self.backend.delete(&key).await?;
+        }
+        
+        tracing::info!("Purged {} cache keys from version namespace v{}", count, version);
+        
+        Ok(count)
+    }
 }
 
 // Cache-aside pattern helper
@@ -178,11 +1042,416 @@ mod tests {
         assert_eq!(deleted, None);
     }
     
+    #[tokio::test]
+    async fn test_version_bump_misses_stale_entries() {
+        // Both deployments share one backend, as they would share Redis
+        let backend: Arc<dyn Cache> = Arc::new(MemoryCache::new());
+        let mut cache_a = CacheService::with_backend(backend.clone()).with_version(9001);
+        
+        cache_a.set("anime:versioned", &vec!["old", "shape"], Duration::from_secs(60)).await.unwrap();
+        
+        // A deployment with a newer schema must not see the old blob
+        let mut cache_b = CacheService::with_backend(backend).with_version(9002);
+        let stale: Option<std::collections::HashMap<String, i32>> = cache_b.get("anime:versioned").await.unwrap();
+        assert!(stale.is_none());
+        
+        // Purging the old namespace removes its keys
+        let purged = cache_b.purge_version(9001).await.unwrap();
+        assert_eq!(purged, 1);
+        assert!(!cache_a.exists("anime:versioned").await.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_memory_cache_get_set_expire() {
+        let mut cache = CacheService::new(MEMORY_CACHE_SCHEME).await.unwrap();
+        
+        cache.set("anime:123", &vec![1, 2, 3], Duration::from_secs(60)).await.unwrap();
+        let cached: Option<Vec<i32>> = cache.get("anime:123").await.unwrap();
+        assert_eq!(cached, Some(vec![1, 2, 3]));
+        
+        // Shortening the TTL makes the entry disappear once it elapses
+        cache.expire("anime:123", Duration::from_millis(20)).await.unwrap();
+        assert!(cache.exists("anime:123").await.unwrap());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let expired: Option<Vec<i32>> = cache.get("anime:123").await.unwrap();
+        assert_eq!(expired, None);
+        
+        cache.set("search:a", &"a", Duration::from_secs(60)).await.unwrap();
+        cache.set("search:b", &"b", Duration::from_secs(60)).await.unwrap();
+        assert_eq!(cache.invalidate_pattern("search:*").await.unwrap(), 2);
+        assert!(!cache.exists("search:a").await.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_memory_cache_counter_and_purge() {
+        let mut cache = CacheService::with_backend(Arc::new(MemoryCache::new()));
+        
+        assert_eq!(cache.increment_counter("ratelimit:u1", Duration::from_secs(60)).await.unwrap(), 1);
+        assert_eq!(cache.increment_counter("ratelimit:u1", Duration::from_secs(60)).await.unwrap(), 2);
+        
+        let mut old = CacheService::with_backend(Arc::new(MemoryCache::new())).with_version(9001);
+        old.set("anime:versioned", &"stale", Duration::from_secs(60)).await.unwrap();
+        assert_eq!(old.purge_version(9001).await.unwrap(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_memory_cache_cors_origins() {
+        let backend = Arc::new(MemoryCache::new());
+        let mut cache = CacheService::with_backend(backend.clone());
+        
+        assert!(cache.add_cors_origin("https://partner.example").await.unwrap());
+        assert!(!cache.add_cors_origin("https://partner.example").await.unwrap());
+        assert_eq!(cache.cors_origins().await.unwrap(), vec!["https://partner.example"]);
+        
+        // Writes that bypass the service only show up once the snapshot expires
+        backend.set_add(CORS_ORIGINS_KEY, "https://other.example").await.unwrap();
+        assert_eq!(cache.cors_origins().await.unwrap().len(), 1);
+        
+        assert!(cache.remove_cors_origin("https://partner.example").await.unwrap());
+        assert_eq!(cache.cors_origins().await.unwrap(), vec!["https://other.example"]);
+        assert!(!cache.remove_cors_origin("https://partner.example").await.unwrap());
+    }
+    
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    
+    /// Backend answering reads from a fixed set of entries and recording writes
+    #[derive(Default)]
+    struct MockCache {
+        entries: Mutex<HashMap<String, String>>,
+        writes: Mutex<Vec<(String, String, Duration)>>,
+        sets: Mutex<HashMap<String, BTreeSet<String>>>,
+        fail_reads: bool,
+        /// Reads that miss whatever is stored, as if run just before a concurrent write
+        stale_reads: std::sync::atomic::AtomicUsize,
+    }
+    
+    #[async_trait]
+    impl Cache for MockCache {
+        async fn get(&self, key: &str) -> Result<Option<String>> {
+            if self.fail_reads {
+                anyhow::bail!("connection refused");
+            }
+            let stale = self.stale_reads.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1));
+            if stale.is_ok() {
+                return Ok(None);
+            }
+            Ok(self.entries.lock().unwrap().get(key).cloned())
+        }
+        async fn set(&self, key: &str, value: String, ttl: Duration) -> Result<()> {
+            self.writes.lock().unwrap().push((key.to_string(), value.clone(), ttl));
+            self.entries.lock().unwrap().insert(key.to_string(), value);
+            Ok(())
+        }
+        async fn delete(&self, key: &str) -> Result<()> {
+            self.entries.lock().unwrap().remove(key);
+            self.sets.lock().unwrap().remove(key);
+            Ok(())
+        }
+        async fn exists(&self, key: &str) -> Result<bool> {
+            Ok(self.entries.lock().unwrap().contains_key(key) || self.sets.lock().unwrap().contains_key(key))
+        }
+        async fn expire(&self, key: &str, ttl: Duration) -> Result<()> {
+            // Entries never expire here; record the new TTL like a rewrite
+            if let Some(value) = self.entries.lock().unwrap().get(key) {
+                self.writes.lock().unwrap().push((key.to_string(), value.clone(), ttl));
+            }
+            Ok(())
+        }
+        async fn set_nx(&self, key: &str, value: String, _ttl: Duration) -> Result<bool> {
+            if self.fail_reads {
+                anyhow::bail!("connection refused");
+            }
+            let mut entries = self.entries.lock().unwrap();
+            if entries.contains_key(key) {
+                return Ok(false);
+            }
+            entries.insert(key.to_string(), value);
+            Ok(true)
+        }
+        async fn delete_if_equals(&self, key: &str, value: &str) -> Result<bool> {
+            let mut entries = self.entries.lock().unwrap();
+            if entries.get(key).map(String::as_str) != Some(value) {
+                return Ok(false);
+            }
+            entries.remove(key);
+            Ok(true)
+        }
+        async fn keys(&self, pattern: &str) -> Result<Vec<String>> {
+            let mut keys: Vec<String> = self.entries.lock().unwrap()
+                .keys()
+                .filter(|key| glob_match(pattern, key))
+                .cloned()
+                .collect();
+            keys.sort();
+            Ok(keys)
+        }
+        async fn increment(&self, key: &str, ttl: Duration) -> Result<u64> {
+            let current = self.entries.lock().unwrap().get(key).cloned();
+            let count = match current {
+                Some(value) => value.parse::<u64>().context("Cached value is not a counter")? + 1,
+                None => 1,
+            };
+            self.set(key, count.to_string(), ttl).await?;
+            Ok(count)
+        }
+        async fn set_add(&self, key: &str, member: &str) -> Result<bool> {
+            Ok(self.sets.lock().unwrap().entry(key.to_string()).or_default().insert(member.to_string()))
+        }
+        async fn set_remove(&self, key: &str, member: &str) -> Result<bool> {
+            let mut sets = self.sets.lock().unwrap();
+            let Some(members) = sets.get_mut(key) else {
+                return Ok(false);
+            };
+            let removed = members.remove(member);
+            if members.is_empty() {
+                sets.remove(key);
+            }
+            Ok(removed)
+        }
+        async fn set_members(&self, key: &str) -> Result<Vec<String>> {
+            Ok(self.sets.lock().unwrap().get(key).map(|members| members.iter().cloned().collect()).unwrap_or_default())
+        }
+    }
+    
+    const TTL: Duration = Duration::from_secs(300);
+    
+    #[tokio::test]
+    async fn test_get_or_set_hit_skips_loader() {
+        let backend = Arc::new(MockCache::default());
+        backend.entries.lock().unwrap().insert("v1:answer".to_string(), "42".to_string());
+        let cache = CacheService::with_backend(backend.clone());
+        
+        let value: u32 = cache.get_or_set("answer", TTL, || async { panic!("loader ran on a hit") }).await.unwrap();
+        assert_eq!(value, 42);
+        assert!(backend.writes.lock().unwrap().is_empty());
+    }
+    
+    #[tokio::test]
+    async fn test_get_or_set_miss_stores_serialized_value() {
+        let backend = Arc::new(MockCache::default());
+        let cache = CacheService::with_backend(backend.clone());
+        
+        let value = cache.get_or_set("list", TTL, || async { Ok(vec!["a".to_string()]) }).await.unwrap();
+        assert_eq!(value, vec!["a"]);
+        assert_eq!(
+            *backend.writes.lock().unwrap(),
+            vec![("v1:list".to_string(), r#"["a"]"#.to_string(), TTL)]
+        );
+        
+        // Loader errors are returned and leave nothing behind
+        let failed: Result<u32> = cache.get_or_set("broken", TTL, || async { anyhow::bail!("db down") }).await;
+        assert!(failed.is_err());
+        assert_eq!(backend.writes.lock().unwrap().len(), 1);
+    }
+    
+    #[tokio::test]
+    async fn test_get_or_set_falls_back_to_loader_when_cache_fails() {
+        let backend = Arc::new(MockCache { fail_reads: true, ..MockCache::default() });
+        let cache = CacheService::with_backend(backend);
+        
+        let value = cache.get_or_set("answer", TTL, || async { Ok(7) }).await.unwrap();
+        assert_eq!(value, 7);
+    }
+    
+    #[tokio::test]
+    async fn test_concurrent_misses_run_loader_once() {
+        let cache = CacheService::with_backend(Arc::new(MemoryCache::new()));
+        let loads = AtomicUsize::new(0);
+        let loads = &loads;
+        
+        let lookups = (0..20).map(|_| {
+            cache.get_or_set("season:2024:spring", TTL, move || async move {
+                loads.fetch_add(1, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                Ok(vec!["Frieren".to_string()])
+            })
+        });
+        let values = futures::future::join_all(lookups).await;
+        
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert!(values.into_iter().all(|value| value.unwrap() == ["Frieren"]));
+        // The lock is released once the value is cached
+        assert!(!cache.backend.exists(&cache.versioned(&CacheService::load_lock_key("season:2024:spring"))).await.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_load_rechecks_cache_after_taking_lock() {
+        let backend = Arc::new(MockCache { stale_reads: AtomicUsize::new(1), ..MockCache::default() });
+        backend.entries.lock().unwrap().insert("v1:answer".to_string(), "42".to_string());
+        let cache = CacheService::with_backend(backend.clone());
+        
+        // The first read ran before the previous holder cached the value and let go
+        let value: u32 = cache.get_or_set("answer", TTL, || async { panic!("loader ran after the value was cached") }).await.unwrap();
+        assert_eq!(value, 42);
+        assert!(backend.writes.lock().unwrap().is_empty());
+        assert!(!backend.entries.lock().unwrap().contains_key("v1:lock:answer"));
+    }
+    
+    #[tokio::test]
+    async fn test_load_outliving_its_lock_leaves_the_next_holder_alone() {
+        let backend = Arc::new(MemoryCache::new());
+        let cache = CacheService::with_backend(backend.clone());
+        let lock = cache.versioned(&CacheService::load_lock_key("slow"));
+        
+        let value = cache.get_or_set("slow", TTL, || {
+            let (backend, lock) = (backend.clone(), lock.clone());
+            async move {
+                // The lock expires mid-load and another caller takes it
+                backend.delete(&lock).await?;
+                backend.set_nx(&lock, "other caller".to_string(), LOAD_LOCK_TTL).await?;
+                Ok(1)
+            }
+        }).await.unwrap();
+        
+        assert_eq!(value, 1);
+        assert_eq!(backend.get(&lock).await.unwrap().as_deref(), Some("other caller"));
+    }
+    
+    #[tokio::test]
+    async fn test_mock_cache_counters_patterns_and_sets() {
+        let backend = Arc::new(MockCache::default());
+        let mut cache = CacheService::with_backend(backend.clone());
+        
+        assert_eq!(cache.increment_counter("hits", TTL).await.unwrap(), 1);
+        assert_eq!(cache.increment_counter("hits", TTL).await.unwrap(), 2);
+        
+        cache.store_response(&CacheService::response_key("anime:1", "/a"), &1, TTL).await.unwrap();
+        cache.store_response(&CacheService::response_key("anime:1", "/b"), &2, TTL).await.unwrap();
+        cache.store_response(&CacheService::response_key("anime:2", "/a"), &3, TTL).await.unwrap();
+        assert!(cache.exists(&CacheService::response_key("anime:1", "/a")).await.unwrap());
+        
+        assert_eq!(cache.invalidate_responses("anime:1").await.unwrap(), 2);
+        assert!(!cache.exists(&CacheService::response_key("anime:1", "/a")).await.unwrap());
+        assert!(cache.exists(&CacheService::response_key("anime:2", "/a")).await.unwrap());
+        
+        cache.expire(&CacheService::response_key("anime:2", "/a"), Duration::from_secs(5)).await.unwrap();
+        assert_eq!(backend.writes.lock().unwrap().last().unwrap().2, Duration::from_secs(5));
+        
+        assert!(backend.set_add("origins", "https://a.example").await.unwrap());
+        assert!(!backend.set_add("origins", "https://a.example").await.unwrap());
+        assert!(backend.set_add("origins", "https://b.example").await.unwrap());
+        assert_eq!(backend.set_members("origins").await.unwrap(), vec!["https://a.example", "https://b.example"]);
+        assert!(backend.set_remove("origins", "https://a.example").await.unwrap());
+        assert!(backend.set_remove("origins", "https://b.example").await.unwrap());
+        assert!(!backend.exists("origins").await.unwrap());
+    }
+    
+    #[tokio::test]
+    async fn test_not_found_is_cached_briefly() {
+        let backend = Arc::new(MockCache::default());
+        let cache = CacheService::with_backend(backend.clone());
+        
+        let missing: Option<String> = cache
+            .get_or_set_with_not_found("anime:gone", TTL, || async { Ok(None) })
+            .await
+            .unwrap();
+        assert_eq!(missing, None);
+        assert_eq!(
+            *backend.writes.lock().unwrap(),
+            vec![("v1:anime:gone".to_string(), "null".to_string(), NOT_FOUND_TTL)]
+        );
+        
+        // The sentinel answers the next lookup without the loader
+        let again: Option<String> = cache
+            .get_or_set_with_not_found("anime:gone", TTL, || async { panic!("loader ran on a cached miss") })
+            .await
+            .unwrap();
+        assert_eq!(again, None);
+        
+        let found = cache
+            .get_or_set_with_not_found("anime:here", TTL, || async { Ok(Some("Mushishi".to_string())) })
+            .await
+            .unwrap();
+        assert_eq!(found.as_deref(), Some("Mushishi"));
+        assert_eq!(backend.writes.lock().unwrap()[1], ("v1:anime:here".to_string(), r#""Mushishi""#.to_string(), TTL));
+    }
+    
+    #[tokio::test]
+    async fn test_season_browse_page_cached_until_season_invalidated() {
+        let mut cache = CacheService::with_backend(Arc::new(MemoryCache::new()));
+        let key = CacheService::season_browse_key(2024, "Spring", "TV:any::Title:asc:0+20");
+        
+        let page = cache.get_or_set(&key, TTL, || async { Ok(vec!["Frieren".to_string()]) }).await.unwrap();
+        let cached: Vec<String> = cache
+            .get_or_set(&key, TTL, || async { panic!("loader ran for a cached page") })
+            .await
+            .unwrap();
+        assert_eq!(cached, page);
+        
+        let other_season = CacheService::season_browse_key(2024, "summer", "any:any::Title:asc:all");
+        cache.set(&other_season, &Vec::<String>::new(), TTL).await.unwrap();
+        
+        let dropped = cache.invalidate_pattern(&CacheService::season_browse_pattern(2024, "spring")).await.unwrap();
+        assert_eq!(dropped, 1);
+        let reloaded: Vec<String> = cache.get_or_set(&key, TTL, || async { Ok(Vec::new()) }).await.unwrap();
+        assert!(reloaded.is_empty());
+        assert!(cache.exists(&other_season).await.unwrap());
+    }
+    
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("v1:*", "v1:anime:123"));
+        assert!(glob_match("refresh_token:u?:*", "refresh_token:u1:abc"));
+        assert!(!glob_match("v1:*", "v2:anime:123"));
+        assert!(glob_match("*:123", "anime:123"));
+    }
+    
+    #[test]
+    fn test_namespaced_key() {
+        assert_eq!(CacheService::namespaced_key(1, "anime:123"), "v1:anime:123");
+        assert_ne!(
+            CacheService::namespaced_key(1, "anime:123"),
+            CacheService::namespaced_key(2, "anime:123")
+        );
+    }
+    
     #[test]
     fn test_cache_keys() {
         assert_eq!(CacheService::anime_key("123"), "anime:123");
         assert_eq!(CacheService::episode_key("456", 5), "episode:456:5");
         assert_eq!(CacheService::search_key("spy family"), "search:spy_family");
         assert_eq!(CacheService::stream_key("789"), "stream:789");
+        assert_eq!(CacheService::renditions_key("789"), "renditions:789");
+        assert_eq!(CacheService::progress_key("u1", "789"), "progress:u1:789");
+    }
+    
+    #[tokio::test]
+    async fn test_progress_indexed_per_user_and_marked_dirty() {
+        let backend = Arc::new(MemoryCache::new());
+        let mut cache = CacheService::with_backend(backend.clone());
+        let position = |episode_id: Uuid| PlaybackPosition {
+            episode_id,
+            anime_id: Uuid::nil(),
+            episode_number: 1,
+            position: 90,
+            duration: 1440,
+            updated_at: chrono::Utc::now(),
+        };
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        cache.store_progress("u1", &position(first), TTL).await.unwrap();
+        cache.store_progress("u1", &position(second), TTL).await.unwrap();
+        cache.store_progress("u2", &position(first), TTL).await.unwrap();
+        
+        let mut ids = cache.progress_episode_ids("u1").await.unwrap();
+        ids.sort();
+        let mut expected = vec![first.to_string(), second.to_string()];
+        expected.sort();
+        assert_eq!(ids, expected);
+        
+        // Expired positions leave the index as they are read
+        backend.delete(&CacheService::progress_key("u1", &second.to_string())).await.unwrap();
+        let positions = cache.get_progress_many("u1", &ids).await.unwrap();
+        assert_eq!(positions.iter().map(|position| position.episode_id).collect::<Vec<_>>(), vec![first]);
+        assert_eq!(cache.progress_episode_ids("u1").await.unwrap(), vec![first.to_string()]);
+        
+        // Taking positions clears their marks; saving again marks them again
+        let dirty = cache.dirty_progress().await.unwrap();
+        assert_eq!(dirty.len(), 3);
+        let taken = cache.take_dirty_progress(&dirty).await.unwrap();
+        assert_eq!(taken.len(), 2);
+        assert!(cache.dirty_progress().await.unwrap().is_empty());
+        
+        cache.store_progress("u2", &position(first), TTL).await.unwrap();
+        assert_eq!(cache.dirty_progress().await.unwrap(), vec![format!("u2:{}", first)]);
     }
 }
\ No newline at end of file
@@ -1,12 +1,19 @@
-use crate::models::{Anime, AnimeStatus, AnimeType, AnimeSeason, Season, ImdbData};
+use crate::models::{Anime, AnimeStatus, AnimeType, AnimeSeason, Season, Tag};
+use crate::models::anime_offline_db::AnimeOfflineDatabase as OfflineDatabase;
 use crate::services::database_v2::DatabaseService;
+use crate::services::metadata::categorize_tag;
 use chrono::Utc;
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::BufReader;
+use std::path::Path;
 use uuid::Uuid;
 use anyhow::Result;
 
+/// Similarity score given to anime the offline database lists as related
+const RELATED_ANIME_SIMILARITY: f32 = 0.8;
+
 #[derive(Debug, Deserialize)]
 struct AnimeOfflineDatabase {
     data: Vec<AnimeEntry>,
@@ -23,6 +30,8 @@ struct AnimeEntry {
     status: String,
     anime_season: Option<AnimeSeasonRaw>,
     picture: String,
+    #[serde(default)]
+    thumbnail: Option<String>,
     synonyms: Vec<String>,
     studios: Vec<String>,
     tags: Vec<String>,
@@ -61,7 +70,7 @@ pub async fn load_initial_data(db: &DatabaseService) -> Result<()> {
     let mut skipped = 0;
     
     // Import first 500 for quick loading
-    for (index, entry) in database.data.iter().take(500).enumerate() {
+    for entry in database.data.iter().take(500) {
         // Skip entries without season data or year
         let Some(season_raw) = &entry.anime_season else {
             skipped += 1;
@@ -78,6 +87,7 @@ pub async fn load_initial_data(db: &DatabaseService) -> Result<()> {
             "FINISHED" => AnimeStatus::Finished,
             "ONGOING" => AnimeStatus::Ongoing,
             "UPCOMING" => AnimeStatus::Upcoming,
+            "CANCELLED" => AnimeStatus::Cancelled,
             _ => AnimeStatus::Unknown,
         };
         
@@ -120,13 +130,19 @@ pub async fn load_initial_data(db: &DatabaseService) -> Result<()> {
                 entry.tags.join(", ")
             ),
             poster_url: entry.picture.clone(),
+            posters: Anime::collect_posters(
+                std::iter::once(entry.picture.clone()).chain(entry.thumbnail.clone())
+            ),
+            stored_episode_count: 0,
+            popularity: 0.0,
+            romaji_titles: Vec::new(),
             imdb: None,  // No IMDB data in this dataset
             created_at: Utc::now(),
             updated_at: Utc::now(),
         };
         
         // Insert into database
-        if let Ok(_) = db.create_anime(&anime).await {
+        if db.create_anime(&anime).await.is_ok() {
             imported += 1;
             if imported % 100 == 0 {
                 tracing::debug!("Imported {} anime...", imported);
@@ -137,4 +153,192 @@ pub async fn load_initial_data(db: &DatabaseService) -> Result<()> {
     tracing::info!("Data import complete: imported {}, skipped {}", imported, skipped);
     
     Ok(())
-}
\ No newline at end of file
+}
+/// Outcome of `import_offline_database`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportReport {
+    pub created: usize,
+    /// Entries without sources, or sharing one with a stored anime or an earlier entry
+    pub skipped: usize,
+    /// Entries that did not match the entry schema and were skipped while parsing
+    pub malformed: usize,
+    pub tags_created: usize,
+    /// `is_sequel` and `is_similar` edges between imported anime
+    pub relationships_created: usize,
+}
+
+/// Import the anime-offline-database file at `path` in batches of `batch_size`.
+/// Entries sharing a source URL with a stored anime are skipped. Every tag of an
+/// imported anime is linked with `has_tag`, creating tags not seen before, and
+/// `relatedAnime` links between two anime imported in this run become
+/// relationship edges: `is_sequel` when one title extends the other and airs
+/// later, `is_similar` otherwise. The file is streamed rather than loaded whole;
+/// only the imported anime are kept until the relationships are linked.
+pub async fn import_offline_database(db: &DatabaseService, path: &Path, batch_size: usize) -> Result<ImportReport> {
+    let mut entries = OfflineDatabase::stream_entries(path)?;
+    let score_range = entries.score_range.clone();
+
+    let mut report = ImportReport::default();
+    // Sources imported by this run; stored ones are looked up batch by batch
+    let mut seen_sources = HashSet::new();
+    // Imported anime with their relatedAnime URLs, and their indexes by each
+    // of their source URLs for resolving those
+    let mut imported: Vec<(Anime, Vec<String>)> = Vec::new();
+    let mut by_source: HashMap<String, usize> = HashMap::new();
+    let mut tags: HashMap<String, Tag> = HashMap::new();
+
+    loop {
+        let batch = entries.by_ref().take(batch_size.max(1)).collect::<Result<Vec<_>>>()?;
+        if batch.is_empty() {
+            break;
+        }
+        let batch_sources = batch.iter().flat_map(|entry| entry.sources.iter().cloned()).collect();
+        let stored_before = db.find_existing_sources(batch_sources).await?;
+
+        let mut pending = Vec::new();
+        for entry in batch {
+            // Sources are an entry's identity; without one it cannot be deduplicated
+            let known = |source: &String| stored_before.contains(source) || seen_sources.contains(source);
+            if entry.sources.is_empty() || entry.sources.iter().any(known) {
+                report.skipped += 1;
+                continue;
+            }
+            seen_sources.extend(entry.sources.iter().cloned());
+            let anime = entry.to_anime_model(&score_range);
+            pending.push((entry, anime));
+        }
+        if pending.is_empty() {
+            continue;
+        }
+
+        db.batch_create_anime(pending.iter().map(|(_, anime)| anime.clone()).collect()).await?;
+
+        // batch_create_anime skips anime that fail to insert; only link the stored ones
+        let pending_sources = pending.iter().flat_map(|(entry, _)| entry.sources.iter().cloned()).collect();
+        let stored = db.find_existing_sources(pending_sources).await?;
+
+        for (entry, anime) in pending {
+            if !stored.contains(&entry.sources[0]) {
+                continue;
+            }
+            report.created += 1;
+
+            for name in &entry.tags {
+                let key = name.to_lowercase();
+                if !tags.contains_key(&key) {
+                    let tag = match db.find_tag_by_name(name).await? {
+                        Some(tag) => tag,
+                        None => {
+                            report.tags_created += 1;
+                            db.create_tag(&Tag::new(name.clone(), categorize_tag(name))).await?
+                        }
+                    };
+                    tags.insert(key.clone(), tag);
+                }
+                db.create_anime_tag_relationship(anime.id, tags[&key].id, 1.0).await?;
+            }
+
+            for source in entry.sources {
+                by_source.insert(source, imported.len());
+            }
+            imported.push((anime, entry.related_anime));
+        }
+    }
+    report.malformed = entries.malformed();
+
+    // Related lists usually name each other, so link every pair once
+    let mut linked = HashSet::new();
+    for (anime, related) in &imported {
+        for url in related {
+            let Some(other) = by_source.get(url).map(|&index| &imported[index].0) else {
+                continue;
+            };
+            let pair = if anime.id < other.id { (anime.id, other.id) } else { (other.id, anime.id) };
+            if other.id == anime.id || !linked.insert(pair) {
+                continue;
+            }
+
+            if let Some((sequel, prequel)) = sequel_pair(anime, other) {
+                db.create_sequel_relationship(sequel.id, prequel.id).await?;
+            } else {
+                db.create_similarity_relationship(anime.id, other.id, RELATED_ANIME_SIMILARITY).await?;
+            }
+            report.relationships_created += 1;
+        }
+    }
+
+    tracing::info!(
+        "Offline database import: {} created, {} skipped, {} malformed, {} tags created, {} relationships",
+        report.created, report.skipped, report.malformed, report.tags_created, report.relationships_created
+    );
+    Ok(report)
+}
+
+/// (sequel, prequel) when one title starts with the other's and airs later,
+/// as in "Title" and "Title Season 2"
+fn sequel_pair<'a>(a: &'a Anime, b: &'a Anime) -> Option<(&'a Anime, &'a Anime)> {
+    let extends = |longer: &Anime, shorter: &Anime| {
+        longer.title.len() > shorter.title.len()
+            && longer.title.to_lowercase().starts_with(&shorter.title.to_lowercase())
+            && airing_order(&longer.anime_season) > airing_order(&shorter.anime_season)
+    };
+
+    if extends(a, b) {
+        Some((a, b))
+    } else if extends(b, a) {
+        Some((b, a))
+    } else {
+        None
+    }
+}
+
+/// Sort key placing seasons in broadcast order within a year
+fn airing_order(season: &AnimeSeason) -> (u16, u8) {
+    let index = match season.season {
+        Season::Winter => 0,
+        Season::Spring => 1,
+        Season::Summer => 2,
+        Season::Fall => 3,
+    };
+    (season.year, index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anime(title: &str, season: Season, year: u16) -> Anime {
+        Anime {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            synonyms: Vec::new(),
+            sources: Vec::new(),
+            episodes: 12,
+            status: AnimeStatus::Finished,
+            anime_type: AnimeType::TV,
+            anime_season: AnimeSeason { season, year },
+            synopsis: String::new(),
+            poster_url: "https://cdn.example.com/poster.jpg".to_string(),
+            posters: Vec::new(),
+            stored_episode_count: 0,
+            popularity: 0.0,
+            romaji_titles: Vec::new(),
+            imdb: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_sequel_pair_needs_extended_title_and_later_season() {
+        let first = anime("Mushishi", Season::Fall, 2005);
+        let second = anime("Mushishi Zoku Shou", Season::Spring, 2014);
+        let special = anime("Mushishi Special", Season::Winter, 2005);
+
+        let (sequel, prequel) = sequel_pair(&first, &second).unwrap();
+        assert_eq!((sequel.id, prequel.id), (second.id, first.id));
+        // Same title prefix, but aired before the original
+        assert!(sequel_pair(&first, &special).is_none());
+        assert!(sequel_pair(&first, &anime("Natsume", Season::Summer, 2008)).is_none());
+    }
+}
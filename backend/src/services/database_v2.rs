@@ -2,33 +2,278 @@
 // Fixed for SurrealDB 2.1 API changes
 
 use anyhow::{Result, Context};
-use surrealdb::{Surreal, Response};
-use surrealdb::engine::remote::ws::{Client, Ws};
+use chrono::NaiveDate;
+use futures::stream::{self, Stream, TryStreamExt};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use surrealdb::Surreal;
+use surrealdb::engine::any::{self, Any};
 use surrealdb::opt::auth::Root;
 use uuid::Uuid;
 use serde::{Serialize, Deserialize};
+use serde_json::json;
 use crate::models::{
-    Anime, AnimeSummary, Episode, Tag,
-    HasTag, IsSequelOf, RelatedTo
+    Anime, AnimeCursor, AnimeStatus, AnimeTombstone, ChangeCursor, AnimeSuggestion, AnimeSummary, Device, DeviceInfo, Episode, EpisodeBatchError, RecommendedAnime, SimilarAnime, PlaybackPosition, Tag, TagCategory, User, UserPreferences,
+    ContinueWatchingItem, WatchHistoryItem, WatchProgress,
+    WatchlistEntry, WatchlistItem, WatchlistStatus
 };
+use crate::models::anime_offline_db::{AnimeOfflineEntry, ScoreRange};
+use crate::models::catalog::DELETION_RETENTION_DAYS;
+use crate::models::device::DEVICE_RETENTION_DAYS;
+use crate::models::episode::{normalize_episode_numbers, validate_new_episodes};
+use crate::middleware::error::{AppError, ValidationError};
+use crate::services::popularity::PopularityEvent;
+use crate::services::recommendations::rank_by_shared_tags;
+use crate::services::resilient::{retry_with_backoff, ResilienceConfig};
+use crate::services::search::{BrowseSort, ListSortField, ListSortOrder, SearchQuery, SeasonFilter};
+use crate::services::transliteration::romaji_titles;
+
+/// `db.system` attribute on every query span, per the OpenTelemetry database conventions
+pub const DB_SYSTEM: &str = "surrealdb";
+
+/// Recompute an anime's stored episode counter from its episode rows.
+/// Expects `$anime_key` (record key) and `$anime_id` bound.
+const RECOUNT_STORED_EPISODES: &str =
+    "UPDATE type::thing('anime', $anime_key) SET stored_episode_count = count(SELECT id FROM episode WHERE anime_id = $anime_id);";
+
+/// Projection of a user_watched edge onto `WatchProgress`
+const WATCH_PROGRESS_FIELDS: &str =
+    "episode_id, meta::id(out) AS anime_id, episode AS episode_number, progress, total_duration, completed, watched_at";
+
+/// Page size used when streaming episode lists
+const EPISODE_STREAM_BATCH: usize = 100;
+
+/// A copy of `anime` with its romaji search keys rebuilt from the current titles
+fn with_romaji_titles(anime: &Anime) -> Anime {
+    let mut anime = anime.clone();
+    let titles = std::iter::once(anime.title.as_str()).chain(anime.synonyms.iter().map(String::as_str));
+    anime.romaji_titles = romaji_titles(titles);
+    anime
+}
+
+/// WHERE conditions selecting one year's anime narrowed by `filter`; the
+/// parameters come from `bind_browse_filter`
+fn browse_conditions(filter: &SeasonFilter) -> Vec<String> {
+    let mut conditions = vec!["anime_season.year = $year".to_string()];
+    if filter.anime_type.is_some() {
+        conditions.push("anime_type = $anime_type".to_string());
+    }
+    if filter.status.is_some() {
+        conditions.push("status = $status".to_string());
+    }
+    for i in 0..filter.tags.len() {
+        conditions.push(format!(
+            "count(->has_tag->(tag WHERE string::lowercase(name) = $tag_{})) > 0",
+            i
+        ));
+    }
+    conditions
+}
+
+/// `browse_conditions` for a single season; see `bind_season_filter`
+fn season_conditions(filter: &SeasonFilter) -> Vec<String> {
+    let mut conditions = browse_conditions(filter);
+    conditions.insert(1, "anime_season.season = $season".to_string());
+    conditions
+}
+
+fn bind_browse_filter<'r>(
+    mut query: surrealdb::method::Query<'r, Any>,
+    year: u16,
+    filter: &SeasonFilter,
+) -> surrealdb::method::Query<'r, Any> {
+    query = query
+        .bind(("year", year as i64))
+        .bind(("anime_type", filter.anime_type.clone()))
+        .bind(("status", filter.status.clone()));
+    for (i, tag) in filter.tags.iter().enumerate() {
+        query = query.bind((format!("tag_{}", i), tag.to_lowercase()));
+    }
+    query
+}
+
+fn bind_season_filter<'r>(
+    query: surrealdb::method::Query<'r, Any>,
+    year: u16,
+    season: &str,
+    filter: &SeasonFilter,
+) -> surrealdb::method::Query<'r, Any> {
+    bind_browse_filter(query.bind(("season", season.to_lowercase())), year, filter)
+}
+
+/// Text conditions of a search for `query`, whose text `bind_search_text`
+/// binds as `$<prefix>query` and `$<prefix>title`
+fn search_text_conditions(query: &SearchQuery, prefix: &str) -> Vec<String> {
+    let mut conditions = Vec::new();
+    if query.romaji {
+        // Compact romaji keys; rows written before the keys existed still
+        // match on their title with spaces removed
+        let matches_key = |param: &str| format!(
+            "(string::contains(array::join(romaji_titles, '|'), ${p}{param}) \
+             OR string::contains(string::replace(string::lowercase(title), ' ', ''), ${p}{param}))",
+            p = prefix,
+            param = param
+        );
+        if !query.free_text.is_empty() {
+            conditions.push(matches_key("query"));
+        }
+        if query.title.is_some() {
+            conditions.push(matches_key("title"));
+        }
+    } else {
+        if !query.free_text.is_empty() {
+            conditions.push(format!("(title @@ ${p}query OR ${p}query IN synonyms)", p = prefix));
+        }
+        if query.title.is_some() {
+            conditions.push(format!("string::contains(string::lowercase(title), ${}title)", prefix));
+        }
+    }
+    conditions
+}
+
+/// SurrealQL scoring how well a title matches `$<prefix>text`: 4 for an exact
+/// match, 3 a prefix, 2 a substring, 1 a synonym, otherwise (and for queries
+/// without text) 0. Romaji queries compare the compact keys of the titles.
+fn search_relevance(query: &SearchQuery, prefix: &str) -> String {
+    if query.match_text().is_empty() {
+        return "0".to_string();
+    }
+    
+    let (title, synonyms) = if query.romaji {
+        ("(romaji_titles[0] ?? string::replace(string::lowercase(title), ' ', ''))", "array::join(romaji_titles, '|')")
+    } else {
+        ("string::lowercase(title)", "string::lowercase(array::join(synonyms, '|'))")
+    };
+    format!(
+        "IF {title} = ${p}text THEN 4 \
+         ELSE IF string::starts_with({title}, ${p}text) THEN 3 \
+         ELSE IF string::contains({title}, ${p}text) THEN 2 \
+         ELSE IF string::contains({synonyms}, ${p}text) THEN 1 \
+         ELSE 0 END",
+        title = title,
+        synonyms = synonyms,
+        p = prefix
+    )
+}
+
+fn bind_search_text<'r>(
+    query: surrealdb::method::Query<'r, Any>,
+    search: &SearchQuery,
+    prefix: &str,
+) -> surrealdb::method::Query<'r, Any> {
+    query
+        .bind((format!("{}query", prefix), search.free_text.clone()))
+        .bind((format!("{}title", prefix), search.title.clone().unwrap_or_default().to_lowercase()))
+        .bind((format!("{}text", prefix), search.match_text()))
+}
+
+/// Position of an anime's season within its year, for ordering search results
+const SEASON_INDEX: &str = "IF anime_season.season = 'winter' THEN 0 \
+    ELSE IF anime_season.season = 'spring' THEN 1 \
+    ELSE IF anime_season.season = 'summer' THEN 2 \
+    ELSE 3 END";
+
+/// Compound index on season and status for `get_seasonal_anime`
+pub const SEASON_BROWSE_INDEX: &str = "anime_season_composite";
+
+/// Unique index on user emails
+pub const USER_EMAIL_INDEX: &str = "user_email";
+
+/// Unique index on usernames. It replaces the non-unique `user_username`, which
+/// had to allow the empty usernames of accounts from before usernames.
+pub const USER_USERNAME_INDEX: &str = "user_username_unique";
+
+/// Unique index on `name_key`, the lowercased tag name, so tag names are
+/// unique ignoring case
+pub const TAG_NAME_INDEX: &str = "tag_name_key_unique";
+
+/// Whether `error` is a write rejected because `index`, a UNIQUE index, already
+/// held the value. Embedded and remote engines report it in the same words.
+pub fn violates_unique_index(error: &anyhow::Error, index: &str) -> bool {
+    error.chain().any(|cause| {
+        cause.to_string().contains(&format!("Database index `{}` already contains", index))
+    })
+}
+
+/// What `upsert_anime_from_offline_entry` did with an entry
+#[derive(Debug, Clone)]
+pub enum UpsertOutcome {
+    /// No stored anime shared a source; the entry was created
+    Created(Anime),
+    /// The entry was merged into the stored anime sharing a source
+    Updated(Anime),
+    /// The stored anime already had everything the entry would merge in
+    Unchanged(Anime),
+}
+
+impl UpsertOutcome {
+    pub fn anime(&self) -> &Anime {
+        match self {
+            UpsertOutcome::Created(anime) | UpsertOutcome::Updated(anime) | UpsertOutcome::Unchanged(anime) => anime,
+        }
+    }
+}
+
+/// An anime whose stored episode counter disagreed with its episode rows
+#[derive(Debug, Clone, Serialize)]
+pub struct EpisodeCountFix {
+    pub anime_id: Uuid,
+    pub title: String,
+    pub recorded: u32,
+    pub actual: u32,
+}
+
+/// Outcome of renumbering an anime's absolutely numbered episodes
+#[derive(Debug, Clone, Serialize)]
+pub struct EpisodeRenumbering {
+    pub anime_id: Uuid,
+    /// Subtracted from each absolute number; None when nothing needed renumbering
+    pub offset: Option<u32>,
+    pub renumbered: usize,
+}
+
+/// What deleting an anime with `delete_anime_cascade` removed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletionReport {
+    pub anime_deleted: bool,
+    pub episodes_deleted: u32,
+    /// `belongs_to`, `has_tag`, `is_sequel` and `is_similar` edges touching the anime
+    pub edges_deleted: u32,
+}
+
+/// URL scheme that selects an embedded in-memory datastore, fresh on every
+/// connect; anything after the scheme is ignored
+pub const MEMORY_DB_SCHEME: &str = "memory://";
 
 pub struct DatabaseService {
-    db: Surreal<Client>,
+    db: Surreal<Any>,
+}
+
+/// Retry settings for connecting at startup, when SurrealDB may not be up yet.
+/// Reads SURREAL_CONNECT_RETRIES (default 5) and SURREAL_CONNECT_BACKOFF_MS
+/// (initial delay, doubled per attempt up to 10s; default 500).
+pub fn connect_retry_from_env() -> ResilienceConfig {
+    let env_or = |name: &str, default: u64| {
+        std::env::var(name).ok().and_then(|value| value.trim().parse().ok()).unwrap_or(default)
+    };
+
+    ResilienceConfig {
+        max_retries: env_or("SURREAL_CONNECT_RETRIES", 5) as u32,
+        base_delay_ms: env_or("SURREAL_CONNECT_BACKOFF_MS", 500),
+        max_delay_ms: 10_000,
+        ..ResilienceConfig::default()
+    }
 }
 
 impl DatabaseService {
+    /// Connect with the retry settings from `connect_retry_from_env`
     pub async fn new(url: &str) -> Result<Self> {
-        // Connect to SurrealDB
-        let db = Surreal::new::<Ws>(url).await?;
-        
-        // Sign in as root user (use env vars in production)
-        let username = std::env::var("SURREAL_USER").unwrap_or_else(|_| "root".to_string());
-        let password = std::env::var("SURREAL_PASS").unwrap_or_else(|_| "root".to_string());
-        
-        db.signin(Root {
-            username: &username,
-            password: &password,
-        }).await?;
+        Self::connect(url, &connect_retry_from_env()).await
+    }
+
+    /// Connect and sign in, retrying both with `retry`'s backoff
+    pub async fn connect(url: &str, retry: &ResilienceConfig) -> Result<Self> {
+        let db = retry_with_backoff(retry, &format!("Connecting to SurrealDB at {}", url), || Self::connect_once(url)).await?;
         
         // Create namespace if it doesn't exist
         let _: surrealdb::Response = db.query("DEFINE NAMESPACE IF NOT EXISTS kensho").await?;
@@ -42,65 +287,279 @@ impl DatabaseService {
         
         Ok(DatabaseService { db })
     }
+
+    async fn connect_once(url: &str) -> Result<Surreal<Any>> {
+        // Embedded datastores have no users to sign in as
+        if url.starts_with(MEMORY_DB_SCHEME) {
+            return Ok(any::connect("memory").await?);
+        }
+        
+        // Connect to SurrealDB; a bare host:port means WebSocket
+        let db = if url.contains("://") {
+            any::connect(url).await?
+        } else {
+            any::connect(format!("ws://{}", url)).await?
+        };
+        
+        // Sign in as root user (use env vars in production)
+        let username = std::env::var("SURREAL_USER").unwrap_or_else(|_| "root".to_string());
+        let password = std::env::var("SURREAL_PASS").unwrap_or_else(|_| "root".to_string());
+        
+        db.signin(Root {
+            username: &username,
+            password: &password,
+        }).await?;
+        
+        Ok(db)
+    }
     
+    /// Drop an index, e.g. to time a query without it. Names must be plain
+    /// identifiers.
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn remove_index(&self, table: &str, index: &str) -> Result<()> {
+        let is_identifier = |name: &str| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_identifier(table) || !is_identifier(index) {
+            anyhow::bail!("Invalid index '{}' on '{}'", index, table);
+        }
+        
+        self.db.query(format!("REMOVE INDEX IF EXISTS {} ON {}", index, table))
+            .await?
+            .check()?;
+        Ok(())
+    }
+    
+    /// A client that was never connected; every query fails. For tests of
+    /// behaviour while the database is down.
+    pub fn disconnected() -> Self {
+        DatabaseService { db: Surreal::init() }
+    }
+    
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
     pub async fn initialize_schema(&self) -> Result<()> {
-        // Create tables with proper result handling for v2
-        self.db.query("DEFINE TABLE IF NOT EXISTS anime SCHEMAFULL")
+        // Create tables with proper result handling for v2. Schemaless: the
+        // records' fields are not declared, and a SCHEMAFULL table would drop them.
+        self.db.query("DEFINE TABLE IF NOT EXISTS anime SCHEMALESS")
             .await?
             .check()?;
             
-        self.db.query("DEFINE TABLE IF NOT EXISTS episode SCHEMAFULL")
+        self.db.query("DEFINE TABLE IF NOT EXISTS episode SCHEMALESS")
+            .await?
+            .check()?;
+        
+        // Episode -> anime edges written by batch creation
+        self.db.query("DEFINE TABLE IF NOT EXISTS belongs_to SCHEMALESS")
             .await?
             .check()?;
             
-        self.db.query("DEFINE TABLE IF NOT EXISTS tag SCHEMAFULL")
+        self.db.query("DEFINE TABLE IF NOT EXISTS tag SCHEMALESS")
             .await?
             .check()?;
             
-        self.db.query("DEFINE TABLE IF NOT EXISTS user SCHEMAFULL")
+        self.db.query("DEFINE TABLE IF NOT EXISTS user SCHEMALESS")
             .await?
             .check()?;
         
         // Define indexes
-        self.db.query("DEFINE INDEX IF NOT EXISTS anime_title ON anime FIELDS title SEARCH ANALYZER ascii TOKENIZERS lowercase, class")
+        // Full-text title search (`@@`) needs an analyzer and a search index
+        self.db.query("DEFINE ANALYZER IF NOT EXISTS ascii TOKENIZERS class FILTERS lowercase, ascii")
+            .await?
+            .check()?;
+        
+        self.db.query("DEFINE INDEX IF NOT EXISTS anime_title ON anime FIELDS title SEARCH ANALYZER ascii BM25")
             .await?
             .check()?;
             
         self.db.query("DEFINE INDEX IF NOT EXISTS anime_season ON anime FIELDS anime_season.year, anime_season.season")
             .await?
             .check()?;
+        
+        // Imports look anime up by any one of their source URLs
+        self.db.query("DEFINE INDEX IF NOT EXISTS anime_sources ON anime FIELDS sources")
+            .await?
+            .check()?;
+        
+        // Seasonal browse, the hot path, filters on status as well
+        self.db.query(format!("DEFINE INDEX IF NOT EXISTS {} ON anime FIELDS anime_season.year, anime_season.season, status", SEASON_BROWSE_INDEX))
+            .await?
+            .check()?;
             
         self.db.query("DEFINE INDEX IF NOT EXISTS episode_anime ON episode FIELDS anime_id")
             .await?
             .check()?;
+            
+        self.db.query(format!("DEFINE INDEX IF NOT EXISTS {} ON user FIELDS email UNIQUE", USER_EMAIL_INDEX))
+            .await?
+            .check()?;
+        
+        // Accounts from before usernames have none, so they get a placeholder
+        // from their id first. At 37 characters it is longer than registration
+        // allows, so no one can register it.
+        self.db.query("UPDATE user SET username = string::concat('user_', string::replace(<string> meta::id(id), '-', '')) WHERE username = NONE OR username = ''")
+            .await?
+            .check()?;
+        self.db.query("REMOVE INDEX IF EXISTS user_username ON user")
+            .await?
+            .check()?;
+        self.db.query(format!("DEFINE INDEX IF NOT EXISTS {} ON user FIELDS username UNIQUE", USER_USERNAME_INDEX))
+            .await?
+            .check()?;
         
         // Define graph edge tables for relationships
-        self.db.query("DEFINE TABLE IF NOT EXISTS has_tag SCHEMAFULL")
+        self.db.query("DEFINE TABLE IF NOT EXISTS has_tag SCHEMALESS")
+            .await?
+            .check()?;
+        
+        // Tag names are unique ignoring case. Tags from before the index can
+        // differ only in case; they are merged into the oldest of them first.
+        self.db.query("DEFINE FIELD IF NOT EXISTS name_key ON tag VALUE string::lowercase(name)")
+            .await?
+            .check()?;
+        let merged = self.merge_case_duplicate_tags().await?;
+        if merged > 0 {
+            tracing::info!("Merged {} tags differing from another only in case", merged);
+        }
+        self.db.query("UPDATE tag SET name_key = string::lowercase(name) WHERE name_key = NONE")
+            .await?
+            .check()?;
+        self.db.query(format!("DEFINE INDEX IF NOT EXISTS {} ON tag FIELDS name_key UNIQUE", TAG_NAME_INDEX))
+            .await?
+            .check()?;
+            
+        self.db.query("DEFINE TABLE IF NOT EXISTS is_sequel SCHEMALESS")
+            .await?
+            .check()?;
+            
+        self.db.query("DEFINE TABLE IF NOT EXISTS is_similar SCHEMALESS")
+            .await?
+            .check()?;
+            
+        self.db.query("DEFINE TABLE IF NOT EXISTS user_watched SCHEMALESS")
             .await?
             .check()?;
             
-        self.db.query("DEFINE TABLE IF NOT EXISTS is_sequel SCHEMAFULL")
+        self.db.query("DEFINE TABLE IF NOT EXISTS user_likes SCHEMALESS")
             .await?
             .check()?;
             
-        self.db.query("DEFINE TABLE IF NOT EXISTS is_similar SCHEMAFULL")
+        self.db.query("DEFINE TABLE IF NOT EXISTS watchlist_entry SCHEMALESS")
             .await?
             .check()?;
             
-        self.db.query("DEFINE TABLE IF NOT EXISTS user_watched SCHEMAFULL")
+        // One edge per (user, anime); repeated adds update the existing entry
+        self.db.query("DEFINE INDEX IF NOT EXISTS watchlist_user_anime ON watchlist_entry FIELDS in, out UNIQUE")
+            .await?
+            .check()?;
+        
+        // Cold store for playback positions flushed out of the cache,
+        // keyed by [user_id, episode_id] so saves overwrite in place
+        self.db.query("DEFINE TABLE IF NOT EXISTS watch_progress SCHEMALESS")
+            .await?
+            .check()?;
+            
+        self.db.query("DEFINE INDEX IF NOT EXISTS watch_progress_user_anime ON watch_progress FIELDS user_id, anime_id")
+            .await?
+            .check()?;
+        
+        // One preferences record per user, keyed by user_id
+        self.db.query("DEFINE TABLE IF NOT EXISTS user_preferences SCHEMALESS")
+            .await?
+            .check()?;
+        
+        // Catalog change feed: every anime write stamps updated_at server-side,
+        // and deletions leave a marker keyed by the anime id. A write that only
+        // moves the popularity score keeps the old stamp: the record as stored
+        // is read back and compared without it, so activity and the decay job
+        // don't put every watched title back on the feed. The type is checked
+        // before VALUE runs, so creates need the DEFAULT. OVERWRITE so existing
+        // databases pick up the exemption.
+        self.db.query(r#"
+            DEFINE FIELD OVERWRITE updated_at ON anime TYPE datetime DEFAULT time::now() VALUE
+                IF type::is::datetime($before)
+                    AND (SELECT * OMIT popularity, updated_at FROM ONLY $this)
+                        == (SELECT * OMIT popularity, updated_at FROM ONLY $this.id)
+                THEN $before
+                ELSE time::now()
+                END
+        "#)
+            .await?
+            .check()?;
+            
+        self.db.query("DEFINE INDEX IF NOT EXISTS anime_updated_at ON anime FIELDS updated_at")
+            .await?
+            .check()?;
+            
+        // Maintained incrementally by activity and decayed daily; indexed so
+        // sorting by popularity is an ordered scan
+        self.db.query("DEFINE FIELD IF NOT EXISTS popularity ON anime TYPE float DEFAULT 0.0")
+            .await?
+            .check()?;
+            
+        self.db.query("DEFINE INDEX IF NOT EXISTS anime_popularity ON anime FIELDS popularity")
+            .await?
+            .check()?;
+            
+        // Devices users sign in from; last_seen_at is stamped on every write
+        // so stale devices can be pruned
+        self.db.query("DEFINE TABLE IF NOT EXISTS device SCHEMALESS")
+            .await?
+            .check()?;
+            
+        self.db.query("DEFINE FIELD IF NOT EXISTS last_seen_at ON device TYPE datetime VALUE time::now()")
+            .await?
+            .check()?;
+            
+        self.db.query("DEFINE INDEX IF NOT EXISTS device_user ON device FIELDS user_id")
+            .await?
+            .check()?;
+        
+        // One record holding when popularity scores were last decayed, shared
+        // by every instance so each decay is applied exactly once
+        self.db.query("DEFINE TABLE IF NOT EXISTS popularity_decay SCHEMALESS")
+            .await?
+            .check()?;
+        
+        self.db.query("DEFINE TABLE IF NOT EXISTS anime_tombstone SCHEMALESS")
             .await?
             .check()?;
             
-        self.db.query("DEFINE TABLE IF NOT EXISTS user_likes SCHEMAFULL")
+        self.db.query("DEFINE INDEX IF NOT EXISTS anime_tombstone_deleted_at ON anime_tombstone FIELDS deleted_at")
             .await?
             .check()?;
         
         Ok(())
     }
     
+    /// Rewrite cancelled statuses stored as raw strings (e.g. "CANCELLED" from
+    /// direct imports) to the serialized `AnimeStatus::Cancelled` form so the
+    /// records deserialize. Returns the number of records rewritten.
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn migrate_cancelled_status(&self) -> Result<usize> {
+        let mut response = self.db
+            .query("UPDATE anime SET status = $status WHERE string::lowercase(status) = 'cancelled' AND status != $status RETURN id")
+            .bind(("status", AnimeStatus::Cancelled))
+            .await?;
+        
+        let migrated: Vec<serde_json::Value> = response.take(0)?;
+        Ok(migrated.len())
+    }
+    
+    /// Restamp anime whose updated_at predates the server-side datetime field
+    /// (stored as a string), so they sort into the change feed. Returns the
+    /// number of records restamped.
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn migrate_anime_updated_at(&self) -> Result<usize> {
+        let mut response = self.db
+            .query("UPDATE anime SET updated_at = time::now() WHERE !type::is::datetime(updated_at) RETURN id")
+            .await?;
+        
+        let migrated: Vec<serde_json::Value> = response.take(0)?;
+        Ok(migrated.len())
+    }
+    
     // Anime CRUD operations
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
     pub async fn create_anime(&self, anime: &Anime) -> Result<Anime> {
-        let anime_clone = anime.clone();
+        let anime_clone = with_romaji_titles(anime);
         let created: Option<Anime> = self.db
             .create(("anime", anime.id.to_string()))
             .content(anime_clone)
@@ -109,6 +568,7 @@ impl DatabaseService {
         created.context("Failed to create anime")
     }
     
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
     pub async fn get_anime(&self, id: Uuid) -> Result<Option<Anime>> {
         let anime: Option<Anime> = self.db
             .select(("anime", id.to_string()))
@@ -117,8 +577,9 @@ impl DatabaseService {
         Ok(anime)
     }
     
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
     pub async fn update_anime(&self, anime: &Anime) -> Result<Anime> {
-        let anime_clone = anime.clone();
+        let anime_clone = with_romaji_titles(anime);
         let updated: Option<Anime> = self.db
             .update(("anime", anime.id.to_string()))
             .content(anime_clone)
@@ -127,196 +588,987 @@ impl DatabaseService {
         updated.context("Failed to update anime")
     }
     
+    /// Delete an anime, leaving a deletion marker for the change feed and
+    /// dropping markers past their retention
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
     pub async fn delete_anime(&self, id: Uuid) -> Result<()> {
-        let _: Option<Anime> = self.db
-            .delete(("anime", id.to_string()))
-            .await?;
+        self.db
+            .query(r#"
+                BEGIN TRANSACTION;
+                DELETE type::thing('anime', $id);
+                UPSERT type::thing('anime_tombstone', $id) CONTENT {
+                    anime_id: $id,
+                    deleted_at: time::now()
+                };
+                DELETE anime_tombstone WHERE deleted_at < time::now() - type::duration($retention);
+                COMMIT TRANSACTION;
+            "#)
+            .bind(("id", id.to_string()))
+            .bind(("retention", format!("{}d", DELETION_RETENTION_DAYS)))
+            .await?
+            .check()?;
         
         Ok(())
     }
     
-    // Search operations
-    pub async fn search_anime(&self, query: &str) -> Result<Vec<AnimeSummary>> {
-        let query_string = query.to_string();
+    /// Delete an anime together with its episodes and every catalog edge touching
+    /// it, in one transaction. A deletion marker is left for the change feed as
+    /// with `delete_anime`. Deleting an unknown id changes nothing and reports
+    /// `anime_deleted: false`.
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn delete_anime_cascade(&self, id: Uuid) -> Result<DeletionReport> {
         let mut response = self.db
-            .query("SELECT * FROM anime WHERE title @@ $query OR $query IN synonyms LIMIT 20")
-            .bind(("query", query_string))
+            .query(r#"
+                BEGIN TRANSACTION;
+                LET $anime = type::thing('anime', $id);
+                LET $found = (SELECT VALUE id FROM $anime);
+                LET $episodes = (DELETE episode WHERE anime_id = $anime_id RETURN BEFORE);
+                LET $belongs_to = (DELETE belongs_to WHERE out = $anime RETURN BEFORE);
+                LET $has_tag = (DELETE has_tag WHERE in = $anime RETURN BEFORE);
+                LET $is_sequel = (DELETE is_sequel WHERE in = $anime OR out = $anime RETURN BEFORE);
+                LET $is_similar = (DELETE is_similar WHERE in = $anime OR out = $anime RETURN BEFORE);
+                IF array::len($found) > 0 {
+                    DELETE $anime;
+                    UPSERT type::thing('anime_tombstone', $id) CONTENT {
+                        anime_id: $id,
+                        deleted_at: time::now()
+                    };
+                };
+                DELETE anime_tombstone WHERE deleted_at < time::now() - type::duration($retention);
+                RETURN {
+                    anime_deleted: array::len($found) > 0,
+                    episodes_deleted: array::len($episodes),
+                    edges_deleted: array::len($belongs_to) + array::len($has_tag)
+                        + array::len($is_sequel) + array::len($is_similar)
+                };
+                COMMIT TRANSACTION;
+            "#)
+            .bind(("id", id.to_string()))
+            .bind(("anime_id", id))
+            .bind(("retention", format!("{}d", DELETION_RETENTION_DAYS)))
+            .await?
+            .check()?;
+        
+        // The report is the transaction's last statement
+        let report: Option<DeletionReport> = response.take(response.num_statements() - 1)?;
+        report.context("Deletion report missing")
+    }
+    
+    /// One page of the catalog change feed after `after`: up to `limit` live
+    /// anime and up to `limit` deletion markers, each in (timestamp, id) order.
+    /// `CatalogChange::merge` interleaves them.
+    #[tracing::instrument(skip(self, after), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_catalog_changes(
+        &self,
+        after: ChangeCursor,
+        limit: usize,
+    ) -> Result<(Vec<Anime>, Vec<AnimeTombstone>)> {
+        let mut response = self.db
+            .query(r#"
+                SELECT * FROM anime
+                WHERE updated_at > $after OR (updated_at = $after AND id > type::thing('anime', $after_id))
+                ORDER BY updated_at, id LIMIT $limit;
+                SELECT anime_id, deleted_at FROM anime_tombstone
+                WHERE deleted_at > $after OR (deleted_at = $after AND anime_id > $after_id)
+                ORDER BY deleted_at, anime_id LIMIT $limit;
+            "#)
+            .bind(("after", surrealdb::Datetime::from(after.updated_at)))
+            .bind(("after_id", after.id.to_string()))
+            .bind(("limit", limit))
             .await?;
         
         let anime: Vec<Anime> = response.take(0)?;
-        Ok(anime.into_iter().map(AnimeSummary::from).collect())
+        let tombstones: Vec<AnimeTombstone> = response.take(1)?;
+        Ok((anime, tombstones))
     }
     
-    pub async fn get_seasonal_anime(&self, year: u16, season: &str) -> Result<Vec<AnimeSummary>> {
+    // Search operations
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn search_anime(&self, query: &str) -> Result<Vec<AnimeSummary>> {
+        let query_string = query.to_string();
         let mut response = self.db
-            .query("SELECT * FROM anime WHERE anime_season.year = $year AND anime_season.season = $season ORDER BY title")
-            .bind(("year", year as i64))
-            .bind(("season", season.to_lowercase()))
+            .query("SELECT * FROM anime WHERE title @@ $query OR $query IN synonyms LIMIT 20")
+            .bind(("query", query_string))
             .await?;
         
         let anime: Vec<Anime> = response.take(0)?;
         Ok(anime.into_iter().map(AnimeSummary::from).collect())
     }
     
-    pub async fn list_anime(&self, limit: usize, offset: usize) -> Result<Vec<AnimeSummary>> {
+    /// Up to `limit` anime whose title or a synonym starts with `prefix`
+    /// (lowercase), best rated first. Reads three fields, not whole records.
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn suggest_anime(&self, prefix: &str, limit: usize) -> Result<Vec<AnimeSuggestion>> {
         let mut response = self.db
-            .query("SELECT * FROM anime ORDER BY created_at DESC LIMIT $limit START $offset")
+            .query(
+                "SELECT meta::id(id) AS id, title, imdb.rating AS rating FROM anime \
+                 WHERE string::starts_with(string::lowercase(title), $prefix) \
+                 OR array::len(synonyms[WHERE string::starts_with(string::lowercase($this), $prefix)]) > 0 \
+                 ORDER BY rating DESC, title ASC LIMIT $limit"
+            )
+            .bind(("prefix", prefix.to_string()))
             .bind(("limit", limit))
-            .bind(("offset", offset))
             .await?;
         
-        let anime: Vec<Anime> = response.take(0)?;
-        Ok(anime.into_iter().map(AnimeSummary::from).collect())
+        Ok(response.take(0)?)
     }
     
-    pub async fn get_anime_count(&self) -> Result<usize> {
+    /// One page of the anime matching `query`, in `order_by` order, and the
+    /// total number of matches. Every present field of the query narrows the
+    /// result set; kana queries also match through their transliteration.
+    ///
+    /// Besides the anime fields `order_by` can use `relevance` (see
+    /// `search_relevance`), `transliterated` (matched only through the
+    /// transliteration), `season_index` and `title_key`, the lowercased title.
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn search_anime_matches(
+        &self,
+        query: &SearchQuery,
+        order_by: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(Vec<Anime>, usize)> {
         #[derive(Deserialize)]
         struct CountResult {
             count: i64,
         }
         
-        let mut response = self.db
-            .query("SELECT count() as count FROM anime GROUP ALL")
-            .await?;
+        let romaji = query.transliterated();
+        let mut conditions = Vec::new();
+        let text = search_text_conditions(query, "");
         
-        let result: Option<CountResult> = response.take(0)?;
-        Ok(result.map(|r| r.count as usize).unwrap_or(0))
+        // Matches of the query as typed keep its relevance; the rest only
+        // matched once transliterated
+        let (relevance, transliterated) = match &romaji {
+            Some(romaji) => {
+                let as_typed = format!("({})", text.join(" AND "));
+                conditions.push(format!(
+                    "({} OR ({}))",
+                    as_typed,
+                    search_text_conditions(romaji, "romaji_").join(" AND ")
+                ));
+                (
+                    format!(
+                        "IF {} THEN ({}) ELSE ({}) END",
+                        as_typed,
+                        search_relevance(query, ""),
+                        search_relevance(romaji, "romaji_")
+                    ),
+                    format!("!{}", as_typed),
+                )
+            }
+            None => {
+                conditions.extend(text);
+                (search_relevance(query, ""), "false".to_string())
+            }
+        };
+        if query.year.is_some() {
+            conditions.push("anime_season.year = $year".to_string());
+        }
+        for i in 0..query.tags.len() {
+            conditions.push(format!(
+                "count(->has_tag->(tag WHERE string::lowercase(name) = $tag_{})) > 0",
+                i
+            ));
+        }
+        
+        let where_clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+        
+        let mut request = self.db
+            .query(format!(
+                "SELECT *, <uuid> meta::id(id) AS id, ({}) AS relevance, {} AS transliterated, \
+                 ({}) AS season_index, string::lowercase(title) AS title_key \
+                 FROM anime{} ORDER BY {} LIMIT $limit START $offset",
+                relevance, transliterated, SEASON_INDEX, where_clause, order_by
+            ))
+            .query(format!("SELECT count() AS count FROM anime{} GROUP ALL", where_clause))
+            .bind(("year", query.year.map(|year| year as i64)))
+            .bind(("limit", limit))
+            .bind(("offset", offset));
+        request = bind_search_text(request, query, "");
+        if let Some(romaji) = &romaji {
+            request = bind_search_text(request, romaji, "romaji_");
+        }
+        for (i, tag) in query.tags.iter().enumerate() {
+            request = request.bind((format!("tag_{}", i), tag.to_lowercase()));
+        }
+        
+        let mut response = request.await?;
+        let anime: Vec<Anime> = response.take(0)?;
+        let total: Option<CountResult> = response.take(1)?;
+        Ok((anime, total.map_or(0, |total| total.count as usize)))
     }
     
-    // Graph relationship operations for recommendations
-    pub async fn create_anime_tag_relationship(&self, anime_id: Uuid, tag_id: Uuid, relevance: f32) -> Result<()> {
-        self.db
-            .query("RELATE $anime->has_tag->$tag SET relevance = $relevance, created_at = time::now()")
-            .bind(("anime", format!("anime:{}", anime_id)))
-            .bind(("tag", format!("tag:{}", tag_id)))
-            .bind(("relevance", relevance))
-            .await?
-            .check()?;
+    /// One season narrowed by `filter`, in `sort` order. With `limit` a page
+    /// starting at `offset` is returned, otherwise the whole listing.
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_seasonal_anime(
+        &self,
+        year: u16,
+        season: &str,
+        filter: &SeasonFilter,
+        sort: BrowseSort,
+        limit: Option<usize>,
+        offset: usize,
+    ) -> Result<Vec<AnimeSummary>> {
+        let paging = if limit.is_some() { " LIMIT $limit START $offset" } else { "" };
+        let query = self.db
+            .query(format!(
+                "SELECT * FROM anime WHERE {} ORDER BY {}{}",
+                season_conditions(filter).join(" AND "),
+                sort.order_by(),
+                paging
+            ))
+            .bind(("limit", limit.unwrap_or_default()))
+            .bind(("offset", offset));
         
-        Ok(())
+        let mut response = bind_season_filter(query, year, season, filter).await?;
+        let anime: Vec<Anime> = response.take(0)?;
+        Ok(anime.into_iter().map(AnimeSummary::from).collect())
     }
     
-    pub async fn create_sequel_relationship(&self, sequel_id: Uuid, prequel_id: Uuid) -> Result<()> {
-        self.db
-            .query("RELATE $prequel->is_sequel->$sequel SET created_at = time::now()")
-            .bind(("prequel", format!("anime:{}", prequel_id)))
-            .bind(("sequel", format!("anime:{}", sequel_id)))
-            .await?
-            .check()?;
+    /// A whole year narrowed by `filter`, every season in one query, each in
+    /// `sort` order
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_year_anime(&self, year: u16, filter: &SeasonFilter, sort: BrowseSort) -> Result<Vec<Anime>> {
+        let query = self.db.query(format!(
+            "SELECT * FROM anime WHERE {} ORDER BY {}",
+            browse_conditions(filter).join(" AND "),
+            sort.order_by()
+        ));
         
-        Ok(())
+        let mut response = bind_browse_filter(query, year, filter).await?;
+        let anime: Vec<Anime> = response.take(0)?;
+        Ok(anime)
     }
     
-    pub async fn create_similarity_relationship(&self, anime1_id: Uuid, anime2_id: Uuid, similarity_score: f32) -> Result<()> {
-        self.db
-            .query("RELATE $anime1->is_similar->$anime2 SET score = $score, created_at = time::now()")
-            .bind(("anime1", format!("anime:{}", anime1_id)))
-            .bind(("anime2", format!("anime:{}", anime2_id)))
-            .bind(("score", similarity_score))
-            .await?
-            .check()?;
+    /// Size of the listing `get_seasonal_anime` pages through
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn count_seasonal_anime(&self, year: u16, season: &str, filter: &SeasonFilter) -> Result<usize> {
+        #[derive(Deserialize)]
+        struct CountResult {
+            count: i64,
+        }
         
-        Ok(())
+        let query = self.db.query(format!(
+            "SELECT count() as count FROM anime WHERE {} GROUP ALL",
+            season_conditions(filter).join(" AND ")
+        ));
+        
+        let mut response = bind_season_filter(query, year, season, filter).await?;
+        let result: Option<CountResult> = response.take(0)?;
+        Ok(result.map(|r| r.count as usize).unwrap_or(0))
+    }
+    
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_top_rated_seasonal(&self, year: u16, season: &str, limit: usize) -> Result<Vec<AnimeSummary>> {
+        let mut response = self.db
+            .query("SELECT * FROM anime WHERE anime_season.year = $year AND anime_season.season = $season ORDER BY imdb.rating DESC LIMIT $limit")
+            .bind(("year", year as i64))
+            .bind(("season", season.to_lowercase()))
+            .bind(("limit", limit))
+            .await?;
+        
+        let anime: Vec<Anime> = response.take(0)?;
+        Ok(anime.into_iter().map(AnimeSummary::from).collect())
+    }
+    
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn list_anime(&self, limit: usize, offset: usize) -> Result<Vec<AnimeSummary>> {
+        let mut response = self.db
+            .query("SELECT * FROM anime ORDER BY created_at DESC LIMIT $limit START $offset")
+            .bind(("limit", limit))
+            .bind(("offset", offset))
+            .await?;
+        
+        let anime: Vec<Anime> = response.take(0)?;
+        Ok(anime.into_iter().map(AnimeSummary::from).collect())
+    }
+    
+    /// One LIMIT/START page of the catalog ordered by `field`. Anime without a
+    /// rating sort after every rated one, whichever the order.
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn list_anime_sorted(
+        &self,
+        field: ListSortField,
+        order: ListSortOrder,
+        limit: usize,
+        offset: usize,
+    ) -> Result<Vec<AnimeSummary>> {
+        let mut response = self.db
+            .query(format!(
+                "SELECT *, (imdb.rating = NONE OR imdb.rating = NULL) AS unrated FROM anime \
+                 ORDER BY {} LIMIT $limit START $offset",
+                field.order_by(order)
+            ))
+            .bind(("limit", limit))
+            .bind(("offset", offset))
+            .await?;
+        
+        let anime: Vec<Anime> = response.take(0)?;
+        Ok(anime.into_iter().map(AnimeSummary::from).collect())
+    }
+    
+    /// Up to `limit` anime strictly after `after` in (created_at, id) order,
+    /// oldest first. Records inserted while a client pages land after its
+    /// cursor, so pages never repeat or skip an anime.
+    #[tracing::instrument(skip(self, after), fields(db.system = DB_SYSTEM), err)]
+    pub async fn list_anime_after(&self, after: Option<AnimeCursor>, limit: usize) -> Result<Vec<Anime>> {
+        self.anime_page_after(Vec::new(), None, None, after, limit).await
+    }
+    
+    /// `list_anime_after` restricted to one season narrowed by `filter`
+    #[tracing::instrument(skip(self, after), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_seasonal_anime_after(
+        &self,
+        year: u16,
+        season: &str,
+        filter: &SeasonFilter,
+        after: Option<AnimeCursor>,
+        limit: usize,
+    ) -> Result<Vec<Anime>> {
+        let conditions = season_conditions(filter);
+        self.anime_page_after(conditions, Some((year, season, filter)), None, after, limit).await
+    }
+    
+    /// `list_anime_after` restricted to anime with a `has_tag` edge to the tag
+    #[tracing::instrument(skip(self, after), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_tag_anime_after(&self, tag_id: Uuid, after: Option<AnimeCursor>, limit: usize) -> Result<Vec<Anime>> {
+        let conditions = vec!["type::thing('tag', $tag_id) INSIDE ->has_tag->tag".to_string()];
+        self.anime_page_after(conditions, None, Some(tag_id), after, limit).await
+    }
+    
+    async fn anime_page_after(
+        &self,
+        mut conditions: Vec<String>,
+        season: Option<(u16, &str, &SeasonFilter)>,
+        tag_id: Option<Uuid>,
+        after: Option<AnimeCursor>,
+        limit: usize,
+    ) -> Result<Vec<Anime>> {
+        // created_at is stored as an RFC 3339 string, which does not sort
+        // chronologically when fractional digits vary; compare it as a datetime
+        if after.is_some() {
+            conditions.push("(<datetime> created_at > $after OR (<datetime> created_at = $after AND id > type::thing('anime', $after_id)))".to_string());
+        }
+        let filter = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", conditions.join(" AND "))
+        };
+        
+        let mut query = self.db
+            .query(format!("SELECT *, <datetime> created_at AS created_order FROM anime {} ORDER BY created_order, id LIMIT $limit", filter))
+            .bind(("limit", limit));
+        if let Some((year, season, filter)) = season {
+            query = bind_season_filter(query, year, season, filter);
+        }
+        if let Some(tag_id) = tag_id {
+            query = query.bind(("tag_id", tag_id.to_string()));
+        }
+        if let Some(after) = after {
+            query = query
+                .bind(("after", surrealdb::Datetime::from(after.created_at)))
+                .bind(("after_id", after.id.to_string()));
+        }
+        
+        let mut response = query.await?;
+        let anime: Vec<Anime> = response.take(0)?;
+        Ok(anime)
+    }
+    
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_anime_count(&self) -> Result<usize> {
+        #[derive(Deserialize)]
+        struct CountResult {
+            count: i64,
+        }
+        
+        let mut response = self.db
+            .query("SELECT count() as count FROM anime GROUP ALL")
+            .await?;
+        
+        let result: Option<CountResult> = response.take(0)?;
+        Ok(result.map(|r| r.count as usize).unwrap_or(0))
+    }
+    
+    // Graph relationship operations for recommendations
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn create_anime_tag_relationship(&self, anime_id: Uuid, tag_id: Uuid, relevance: f32) -> Result<()> {
+        self.db
+            .query(r#"
+                LET $anime = type::thing('anime', $anime_id);
+                LET $tag = type::thing('tag', $tag_id);
+                RELATE $anime->has_tag->$tag SET relevance = $relevance, created_at = time::now();
+            "#)
+            .bind(("anime_id", anime_id.to_string()))
+            .bind(("tag_id", tag_id.to_string()))
+            .bind(("relevance", relevance))
+            .await?
+            .check()?;
+        
+        Ok(())
+    }
+    
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn create_sequel_relationship(&self, sequel_id: Uuid, prequel_id: Uuid) -> Result<()> {
+        self.db
+            .query(r#"
+                LET $prequel = type::thing('anime', $prequel_id);
+                LET $sequel = type::thing('anime', $sequel_id);
+                RELATE $prequel->is_sequel->$sequel SET created_at = time::now();
+            "#)
+            .bind(("prequel_id", prequel_id.to_string()))
+            .bind(("sequel_id", sequel_id.to_string()))
+            .await?
+            .check()?;
+        
+        Ok(())
+    }
+    
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn create_similarity_relationship(&self, anime1_id: Uuid, anime2_id: Uuid, similarity_score: f32) -> Result<()> {
+        self.db
+            .query(r#"
+                LET $anime1 = type::thing('anime', $anime1_id);
+                LET $anime2 = type::thing('anime', $anime2_id);
+                RELATE $anime1->is_similar->$anime2 SET score = $score, created_at = time::now();
+            "#)
+            .bind(("anime1_id", anime1_id.to_string()))
+            .bind(("anime2_id", anime2_id.to_string()))
+            .bind(("score", similarity_score))
+            .await?
+            .check()?;
+        
+        Ok(())
     }
     
     // Recommendation queries using graph traversal
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
     pub async fn get_similar_anime(&self, anime_id: Uuid, limit: usize) -> Result<Vec<AnimeSummary>> {
-        // Get anime with similar tags (2-hop graph traversal)
+        // Get anime with similar tags (2-hop graph traversal: anime -> tag <- anime)
         let mut response = self.db
             .query(r#"
-                SELECT * FROM anime 
+                LET $anime = type::thing('anime', $anime_id);
+                LET $tags = (SELECT VALUE out FROM has_tag WHERE in = $anime);
+                SELECT * FROM anime
                 WHERE id IN (
-                    SELECT out FROM has_tag 
-                    WHERE in IN (
-                        SELECT in FROM has_tag 
-                        WHERE out = $anime_id
-                    ) AND out != $anime_id
+                    SELECT VALUE in FROM has_tag
+                    WHERE out IN $tags AND in != $anime
                 )
                 LIMIT $limit
             "#)
-            .bind(("anime_id", format!("anime:{}", anime_id)))
+            .bind(("anime_id", anime_id.to_string()))
             .bind(("limit", limit))
             .await?;
         
-        let anime: Vec<Anime> = response.take(0)?;
+        let anime: Vec<Anime> = response.take(2)?;
         Ok(anime.into_iter().map(AnimeSummary::from).collect())
     }
     
-    pub async fn get_recommendations_for_user(&self, user_id: Uuid, limit: usize) -> Result<Vec<AnimeSummary>> {
-        // Get recommendations based on user's watch history and preferences
+    /// Anime sharing tags with what the user liked or watched, each explained by
+    /// the seed anime it overlaps most. Empty for users without history.
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_recommendations_for_user(&self, user_id: &str, limit: usize) -> Result<Vec<RecommendedAnime>> {
+        #[derive(Deserialize)]
+        struct TaggedRow {
+            anime_id: Uuid,
+            #[serde(default)]
+            title: String,
+            tag_names: Vec<String>,
+        }
+        
         let mut response = self.db
             .query(r#"
-                LET $watched = (SELECT out FROM user_watched WHERE in = $user_id);
-                LET $liked_tags = (
-                    SELECT DISTINCT out FROM has_tag 
-                    WHERE in IN (
-                        SELECT out FROM user_likes WHERE in = $user_id
-                    )
-                );
+                LET $user = type::thing('user', $user_id);
+                LET $seeds = array::distinct(array::concat(
+                    (SELECT VALUE out FROM user_likes WHERE in = $user),
+                    (SELECT VALUE out FROM user_watched WHERE in = $user)
+                ));
+                LET $seed_tags = array::distinct(array::flatten((SELECT VALUE ->has_tag->tag.name FROM $seeds)));
                 
-                SELECT * FROM anime 
-                WHERE id NOT IN $watched
-                AND id IN (
-                    SELECT DISTINCT in FROM has_tag 
-                    WHERE out IN $liked_tags
-                )
-                ORDER BY (
-                    SELECT count() FROM has_tag 
-                    WHERE in = anime.id AND out IN $liked_tags
-                ) DESC
+                SELECT meta::id(id) AS anime_id, title, ->has_tag->tag.name AS tag_names FROM $seeds;
+                SELECT meta::id(id) AS anime_id, ->has_tag->tag.name AS tag_names FROM anime
+                    WHERE id NOTINSIDE $seeds AND ->has_tag->tag.name ANYINSIDE $seed_tags;
+            "#)
+            .bind(("user_id", user_id.to_string()))
+            .await?;
+        
+        let seeds: Vec<TaggedRow> = response.take(3)?;
+        let candidates: Vec<TaggedRow> = response.take(4)?;
+        
+        let ranked = rank_by_shared_tags(
+            &seeds.into_iter().map(|row| (row.title, row.tag_names)).collect::<Vec<_>>(),
+            candidates.into_iter().map(|row| (row.anime_id, row.tag_names)).collect(),
+            limit,
+        );
+        if ranked.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let ids: Vec<String> = ranked.iter().map(|(id, _)| id.to_string()).collect();
+        let mut response = self.db
+            .query("SELECT * FROM anime WHERE meta::id(id) IN $ids")
+            .bind(("ids", ids))
+            .await?;
+        let mut anime: HashMap<Uuid, Anime> = response.take::<Vec<Anime>>(0)?
+            .into_iter()
+            .map(|anime| (anime.id, anime))
+            .collect();
+        
+        Ok(ranked.into_iter()
+            .filter_map(|(id, reason)| Some(RecommendedAnime {
+                anime: AnimeSummary::from(anime.remove(&id)?),
+                reason,
+            }))
+            .collect())
+    }
+    
+    /// Every anime with the names of its tags, for the similarity rebuild
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_anime_tag_names(&self) -> Result<Vec<(Uuid, Vec<String>)>> {
+        #[derive(Deserialize)]
+        struct TaggedRow {
+            anime_id: Uuid,
+            #[serde(default)]
+            tag_names: Vec<String>,
+        }
+        
+        let mut response = self.db
+            .query("SELECT meta::id(id) AS anime_id, ->has_tag->tag.name AS tag_names FROM anime")
+            .await?;
+        
+        let rows: Vec<TaggedRow> = response.take(0)?;
+        Ok(rows.into_iter().map(|row| (row.anime_id, row.tag_names)).collect())
+    }
+    
+    /// Replace the tag-similarity edges of each anime in `neighbors`. Each anime's
+    /// edges are swapped in one transaction, so readers never see a half-written
+    /// list; edges created from user likes are left alone.
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn replace_tag_similarities(&self, neighbors: Vec<(Uuid, Vec<(Uuid, f32)>)>) -> Result<usize> {
+        let mut written = 0;
+        
+        for (anime_id, similar) in neighbors {
+            written += similar.len();
+            let similar: Vec<serde_json::Value> = similar
+                .into_iter()
+                .map(|(id, score)| json!({ "id": id.to_string(), "score": score }))
+                .collect();
+            
+            self.db
+                .query(r#"
+                    BEGIN TRANSACTION;
+                    LET $anime = type::thing('anime', $anime_id);
+                    DELETE is_similar WHERE in = $anime AND source = 'tags';
+                    FOR $other IN $similar {
+                        LET $target = type::thing('anime', $other.id);
+                        RELATE $anime->is_similar->$target
+                            SET score = $other.score, source = 'tags', created_at = time::now();
+                    };
+                    COMMIT TRANSACTION;
+                "#)
+                .bind(("anime_id", anime_id.to_string()))
+                .bind(("similar", similar))
+                .await?
+                .check()?;
+        }
+        
+        Ok(written)
+    }
+    
+    /// Anime most similar to `anime_id` by tag content, as of the last rebuild
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_tag_similar_anime(&self, anime_id: Uuid, limit: usize) -> Result<Vec<SimilarAnime>> {
+        #[derive(Deserialize)]
+        struct ScoreRow {
+            anime_id: Uuid,
+            score: f32,
+        }
+        
+        let mut response = self.db
+            .query(r#"
+                SELECT meta::id(out) AS anime_id, score FROM is_similar
+                WHERE in = type::thing('anime', $anime_id) AND source = 'tags'
+                ORDER BY score DESC
                 LIMIT $limit
             "#)
-            .bind(("user_id", format!("user:{}", user_id)))
+            .bind(("anime_id", anime_id.to_string()))
             .bind(("limit", limit))
             .await?;
         
-        let anime: Vec<Anime> = response.take(0)?;
+        let scores: Vec<ScoreRow> = response.take(0)?;
+        if scores.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let ids: Vec<String> = scores.iter().map(|row| row.anime_id.to_string()).collect();
+        let mut response = self.db
+            .query("SELECT * FROM anime WHERE meta::id(id) IN $ids")
+            .bind(("ids", ids))
+            .await?;
+        let mut anime: HashMap<Uuid, Anime> = response.take::<Vec<Anime>>(0)?
+            .into_iter()
+            .map(|anime| (anime.id, anime))
+            .collect();
+        
+        Ok(scores.into_iter()
+            .filter_map(|row| Some(SimilarAnime {
+                anime: AnimeSummary::from(anime.remove(&row.anime_id)?),
+                score: row.score,
+            }))
+            .collect())
+    }
+    
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_trending_anime(&self, window_days: u32, limit: usize) -> Result<Vec<AnimeSummary>> {
+        // Rank by number of watch events inside the window
+        let mut response = self.db
+            .query(r#"
+                LET $trending = (
+                    SELECT out, count() AS watches FROM user_watched
+                    WHERE watched_at > time::now() - type::duration($window)
+                    GROUP BY out
+                    ORDER BY watches DESC
+                    LIMIT $limit
+                );
+                
+                SELECT * FROM $trending.out;
+            "#)
+            .bind(("window", format!("{}d", window_days)))
+            .bind(("limit", limit))
+            .await?;
+        
+        let anime: Vec<Anime> = response.take(1)?;
         Ok(anime.into_iter().map(AnimeSummary::from).collect())
     }
     
+    /// Add `delta` to an anime's popularity score
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn increment_popularity(&self, anime_id: Uuid, delta: f64) -> Result<()> {
+        self.db
+            .query("UPDATE type::thing('anime', $anime_id) SET popularity += $delta")
+            .bind(("anime_id", anime_id.to_string()))
+            .bind(("delta", delta))
+            .await?
+            .check()?;
+        
+        Ok(())
+    }
+    
+    /// When popularity scores were last decayed. The first caller on a fresh
+    /// database records `now`, so scores start decaying from then.
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn popularity_decayed_at(&self, now: chrono::DateTime<chrono::Utc>) -> Result<chrono::DateTime<chrono::Utc>> {
+        let mut response = self.db
+            .query("UPSERT popularity_decay:clock SET last_run = last_run ?? $now RETURN VALUE last_run")
+            .bind(("now", surrealdb::Datetime::from(now)))
+            .await?;
+        
+        let last_run: Option<chrono::DateTime<chrono::Utc>> = response.take(0)?;
+        last_run.context("Popularity decay clock missing after write")
+    }
+    
+    /// Multiply every popularity score by `factor` and move the decay clock from
+    /// `since` to `now`, as one transaction. Returns false, changing nothing, when
+    /// the clock no longer reads `since` because another instance got there first.
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn decay_popularity(
+        &self,
+        since: chrono::DateTime<chrono::Utc>,
+        now: chrono::DateTime<chrono::Utc>,
+        factor: f64,
+    ) -> Result<bool> {
+        let mut response = self.db
+            .query(r#"
+                BEGIN TRANSACTION;
+                LET $claimed = (UPDATE popularity_decay:clock SET last_run = $now WHERE last_run = $since RETURN VALUE id);
+                IF array::len($claimed) > 0 {
+                    UPDATE anime SET popularity = popularity * $factor WHERE popularity > 0;
+                };
+                SELECT VALUE array::len($claimed) > 0 FROM ONLY {};
+                COMMIT TRANSACTION;
+            "#)
+            .bind(("since", surrealdb::Datetime::from(since)))
+            .bind(("now", surrealdb::Datetime::from(now)))
+            .bind(("factor", factor))
+            .await?
+            .check()?;
+        
+        let last = response.num_statements() - 1;
+        let claimed: Option<bool> = response.take(last)?;
+        Ok(claimed.unwrap_or(false))
+    }
+    
     // User interaction tracking for personalization
-    pub async fn track_user_watched(&self, user_id: Uuid, anime_id: Uuid, episode: u32) -> Result<()> {
+    /// Record watch progress for an episode. There is one edge per (user, episode),
+    /// so re-posting progress updates it instead of appending a duplicate.
+    #[tracing::instrument(skip(self, episode), fields(db.system = DB_SYSTEM), err)]
+    pub async fn track_user_watched(
+        &self,
+        user_id: &str,
+        episode: &Episode,
+        progress: u32,
+        total_duration: u32,
+        completed: bool,
+    ) -> Result<WatchProgress> {
         self.db
             .query(r#"
-                RELATE $user->user_watched->$anime 
-                SET episode = $episode, 
-                    watched_at = time::now(),
-                    completed = $episode >= (SELECT episodes FROM $anime)
+                BEGIN TRANSACTION;
+                LET $user = type::thing('user', $user_id);
+                LET $anime = type::thing('anime', $anime_id);
+                LET $existing = (SELECT VALUE id FROM user_watched WHERE in = $user AND episode_id = $episode_id);
+                IF array::len($existing) > 0 {
+                    UPDATE $existing SET progress = $progress,
+                        total_duration = $total_duration,
+                        completed = $completed,
+                        watched_at = time::now();
+                } ELSE {
+                    RELATE $user->user_watched->$anime
+                        SET episode_id = $episode_id,
+                            episode = $episode,
+                            progress = $progress,
+                            total_duration = $total_duration,
+                            completed = $completed,
+                            watched_at = time::now();
+                    UPDATE $anime SET popularity += $popularity;
+                };
+                COMMIT TRANSACTION;
             "#)
-            .bind(("user", format!("user:{}", user_id)))
-            .bind(("anime", format!("anime:{}", anime_id)))
-            .bind(("episode", episode))
+            .bind(("user_id", user_id.to_string()))
+            .bind(("anime_id", episode.anime_id.to_string()))
+            .bind(("episode_id", episode.id.to_string()))
+            .bind(("episode", episode.episode_number))
+            .bind(("progress", progress))
+            .bind(("total_duration", total_duration))
+            .bind(("completed", completed))
+            .bind(("popularity", PopularityEvent::Watch.weight()))
+            .await?
+            .check()?;
+        
+        let mut response = self.db
+            .query(format!("SELECT {} FROM user_watched WHERE in = type::thing('user', $user_id) AND episode_id = $episode_id", WATCH_PROGRESS_FIELDS))
+            .bind(("user_id", user_id.to_string()))
+            .bind(("episode_id", episode.id.to_string()))
+            .await?;
+        
+        let written: Option<WatchProgress> = response.take(0)?;
+        written.context("Watch progress missing after write")
+    }
+    
+    /// Record watch progress by episode id, looking the episode up for its anime.
+    /// Returns None when the episode does not exist.
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn upsert_watch_progress(
+        &self,
+        user_id: &str,
+        episode_id: Uuid,
+        progress_secs: u32,
+        total_secs: u32,
+        completed: bool,
+    ) -> Result<Option<WatchProgress>> {
+        let Some(episode) = self.get_episode(episode_id).await? else {
+            return Ok(None);
+        };
+        
+        self.track_user_watched(user_id, &episode, progress_secs, total_secs, completed)
+            .await
+            .map(Some)
+    }
+    
+    /// Store the user's position in an episode, replacing any earlier one
+    #[tracing::instrument(skip(self, position), fields(db.system = DB_SYSTEM), err)]
+    pub async fn save_playback_position(&self, user_id: &str, position: &PlaybackPosition) -> Result<()> {
+        self.db
+            .query("UPSERT type::thing('watch_progress', [$user_id, $episode_id]) CONTENT $position")
+            .bind(("user_id", user_id.to_string()))
+            .bind(("episode_id", position.episode_id.to_string()))
+            .bind(("position", json!({
+                "user_id": user_id,
+                "episode_id": position.episode_id,
+                "anime_id": position.anime_id,
+                "episode_number": position.episode_number,
+                "position": position.position,
+                "duration": position.duration,
+                "updated_at": position.updated_at,
+            })))
+            .await?
+            .check()?;
+        
+        Ok(())
+    }
+    
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_playback_position(&self, user_id: &str, episode_id: Uuid) -> Result<Option<PlaybackPosition>> {
+        let mut response = self.db
+            .query("SELECT * OMIT id, user_id FROM type::thing('watch_progress', [$user_id, $episode_id])")
+            .bind(("user_id", user_id.to_string()))
+            .bind(("episode_id", episode_id.to_string()))
+            .await?;
+        
+        let position: Option<PlaybackPosition> = response.take(0)?;
+        Ok(position)
+    }
+    
+    /// The position in whichever episode of the anime the user played most recently
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_latest_playback_position(&self, user_id: &str, anime_id: Uuid) -> Result<Option<PlaybackPosition>> {
+        let mut response = self.db
+            .query("SELECT * OMIT id, user_id FROM watch_progress WHERE user_id = $user_id AND anime_id = $anime_id ORDER BY updated_at DESC LIMIT 1")
+            .bind(("user_id", user_id.to_string()))
+            .bind(("anime_id", anime_id.to_string()))
+            .await?;
+        
+        let position: Option<PlaybackPosition> = response.take(0)?;
+        Ok(position)
+    }
+    
+    /// Store the user's full preferences, replacing any earlier record
+    #[tracing::instrument(skip(self, preferences), fields(db.system = DB_SYSTEM), err)]
+    pub async fn save_user_preferences(&self, user_id: &str, preferences: &UserPreferences) -> Result<()> {
+        self.db
+            .query("UPSERT type::thing('user_preferences', $user_id) CONTENT $preferences")
+            .bind(("user_id", user_id.to_string()))
+            .bind(("preferences", preferences.clone()))
             .await?
             .check()?;
         
         Ok(())
     }
     
-    pub async fn track_user_likes(&self, user_id: Uuid, anime_id: Uuid, rating: f32) -> Result<()> {
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_user_preferences(&self, user_id: &str) -> Result<Option<UserPreferences>> {
+        let mut response = self.db
+            .query("SELECT * OMIT id FROM type::thing('user_preferences', $user_id)")
+            .bind(("user_id", user_id.to_string()))
+            .await?;
+        
+        let preferences: Option<UserPreferences> = response.take(0)?;
+        Ok(preferences)
+    }
+    
+    /// A page of the user's watch history, most recently watched first, and the total entry count
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_watch_history(&self, user_id: &str, limit: usize, offset: usize) -> Result<(Vec<WatchHistoryItem>, usize)> {
+        #[derive(Deserialize)]
+        struct HistoryRow {
+            #[serde(flatten)]
+            progress: WatchProgress,
+            anime: Anime,
+            episode_record: Option<Episode>,
+        }
+        
+        #[derive(Deserialize)]
+        struct CountRow {
+            count: usize,
+        }
+        
+        // Edges written before progress tracking have no episode_id and are left out
+        let mut response = self.db
+            .query(format!(
+                "SELECT {}, out.* AS anime, (SELECT * FROM type::thing('episode', $parent.episode_id))[0] AS episode_record \
+                 FROM user_watched WHERE in = type::thing('user', $user_id) AND episode_id != NONE \
+                 ORDER BY watched_at DESC LIMIT $limit START $offset;
+                 SELECT count() FROM user_watched WHERE in = type::thing('user', $user_id) AND episode_id != NONE GROUP ALL;",
+                WATCH_PROGRESS_FIELDS
+            ))
+            .bind(("user_id", user_id.to_string()))
+            .bind(("limit", limit))
+            .bind(("offset", offset))
+            .await?;
+        
+        let rows: Vec<HistoryRow> = response.take(0)?;
+        let total: Option<CountRow> = response.take(1)?;
+        
+        // Episodes deleted since they were watched drop out of the history
+        let items = rows.into_iter()
+            .filter_map(|row| Some(WatchHistoryItem {
+                progress: row.progress,
+                episode: row.episode_record?.into(),
+                anime: AnimeSummary::from(row.anime),
+            }))
+            .collect();
+        
+        Ok((items, total.map(|row| row.count).unwrap_or(0)))
+    }
+    
+    /// The user's most recently touched unfinished episodes, newest first, one per anime
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_continue_watching(&self, user_id: &str, limit: usize) -> Result<Vec<ContinueWatchingItem>> {
+        #[derive(Deserialize)]
+        struct ProgressRow {
+            #[serde(flatten)]
+            progress: WatchProgress,
+            anime_title: String,
+            poster_url: String,
+        }
+        
+        let mut response = self.db
+            .query(format!(
+                "SELECT {}, out.title AS anime_title, out.poster_url AS poster_url \
+                 FROM user_watched WHERE in = type::thing('user', $user_id) AND episode_id != NONE AND completed = false \
+                 ORDER BY watched_at DESC",
+                WATCH_PROGRESS_FIELDS
+            ))
+            .bind(("user_id", user_id.to_string()))
+            .await?;
+        
+        let rows: Vec<ProgressRow> = response.take(0)?;
+        
+        // Rows are newest first, so the first one seen for each anime is the one to keep
+        let mut seen = HashSet::new();
+        Ok(rows.into_iter()
+            .filter(|row| seen.insert(row.progress.anime_id))
+            .take(limit)
+            .map(|row| ContinueWatchingItem {
+                anime_id: row.progress.anime_id,
+                anime_title: row.anime_title,
+                poster_url: row.poster_url,
+                episode_id: row.progress.episode_id,
+                episode_number: row.progress.episode_number,
+                progress_ms: row.progress.progress as u64 * 1000,
+                total_duration_ms: row.progress.total_duration as u64 * 1000,
+                watched_at: row.progress.watched_at,
+            })
+            .collect())
+    }
+    
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn track_user_likes(&self, user_id: &str, anime_id: Uuid, rating: f32) -> Result<()> {
         self.db
             .query(r#"
+                LET $user = type::thing('user', $user_id);
+                LET $anime = type::thing('anime', $anime_id);
                 RELATE $user->user_likes->$anime 
                 SET rating = $rating,
                     liked_at = time::now()
             "#)
-            .bind(("user", format!("user:{}", user_id)))
-            .bind(("anime", format!("anime:{}", anime_id)))
+            .bind(("user_id", user_id.to_string()))
+            .bind(("anime_id", anime_id.to_string()))
             .bind(("rating", rating))
             .await?
             .check()?;
         
+        self.increment_popularity(anime_id, PopularityEvent::Rating(rating).weight()).await?;
+        
         // Update similarity relationships based on user preferences
         self.update_similarities_from_user_preference(user_id, anime_id).await?;
         
         Ok(())
     }
     
-    async fn update_similarities_from_user_preference(&self, user_id: Uuid, anime_id: Uuid) -> Result<()> {
+    async fn update_similarities_from_user_preference(&self, user_id: &str, anime_id: Uuid) -> Result<()> {
         // Find other anime this user liked and increase their similarity scores
         self.db
             .query(r#"
+                LET $user = type::thing('user', $user_id);
+                LET $anime = type::thing('anime', $anime_id);
                 LET $other_liked = (
-                    SELECT out FROM user_likes 
+                    SELECT VALUE out FROM user_likes 
                     WHERE in = $user AND out != $anime AND rating >= 4.0
                 );
                 
@@ -329,57 +1581,696 @@ impl DatabaseService {
                     }
                 }
             "#)
-            .bind(("user", format!("user:{}", user_id)))
-            .bind(("anime", format!("anime:{}", anime_id)))
+            .bind(("user_id", user_id.to_string()))
+            .bind(("anime_id", anime_id.to_string()))
             .await?
             .check()?;
         
         Ok(())
     }
     
-    // Batch import optimizations
-    pub async fn batch_create_anime(&self, anime_list: Vec<Anime>) -> Result<usize> {
-        let mut count = 0;
-        
-        // Use transaction for consistency
-        self.db.query("BEGIN TRANSACTION").await?.check()?;
-        
-        for anime in anime_list {
-            match self.create_anime(&anime).await {
-                Ok(_) => count += 1,
-                Err(e) => {
-                    tracing::warn!("Failed to import anime '{}': {}", anime.title, e);
-                }
-            }
+    // Watchlist operations
+    // User ids come from sessions and are not guaranteed to be UUIDs, so they are bound as record keys
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_watchlist(&self, user_id: &str) -> Result<Vec<WatchlistItem>> {
+        #[derive(Deserialize)]
+        struct WatchlistRow {
+            status: WatchlistStatus,
+            added_at: chrono::DateTime<chrono::Utc>,
+            updated_at: chrono::DateTime<chrono::Utc>,
+            anime: Anime,
         }
         
-        self.db.query("COMMIT TRANSACTION").await?.check()?;
-        
-        Ok(count)
-    }
-    
-    // Episode operations
-    pub async fn create_episode(&self, episode: &Episode) -> Result<Episode> {
-        let episode_clone = episode.clone();
-        let created: Option<Episode> = self.db
-            .create(("episode", episode.id.to_string()))
-            .content(episode_clone)
+        let mut response = self.db
+            .query("SELECT status, added_at, updated_at, out.* AS anime FROM watchlist_entry WHERE in = type::thing('user', $user_id) ORDER BY added_at DESC")
+            .bind(("user_id", user_id.to_string()))
             .await?;
         
-        created.context("Failed to create episode")
+        let rows: Vec<WatchlistRow> = response.take(0)?;
+        Ok(rows.into_iter().map(|row| WatchlistItem {
+            entry: WatchlistEntry {
+                anime_id: row.anime.id,
+                status: row.status,
+                added_at: row.added_at,
+                updated_at: row.updated_at,
+            },
+            anime: AnimeSummary::from(row.anime),
+        }).collect())
     }
     
-    pub async fn get_anime_episodes(&self, anime_id: Uuid) -> Result<Vec<Episode>> {
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_watchlist_entry(&self, user_id: &str, anime_id: Uuid) -> Result<Option<WatchlistEntry>> {
+        #[derive(Deserialize)]
+        struct EntryRow {
+            status: WatchlistStatus,
+            added_at: chrono::DateTime<chrono::Utc>,
+            updated_at: chrono::DateTime<chrono::Utc>,
+        }
+        
         let mut response = self.db
-            .query("SELECT * FROM episode WHERE anime_id = $anime_id ORDER BY episode_number")
-            .bind(("anime_id", anime_id))
+            .query("SELECT status, added_at, updated_at FROM watchlist_entry WHERE in = type::thing('user', $user_id) AND out = type::thing('anime', $anime_id)")
+            .bind(("user_id", user_id.to_string()))
+            .bind(("anime_id", anime_id.to_string()))
             .await?;
         
-        let episodes: Vec<Episode> = response.take(0)?;
-        Ok(episodes)
+        let row: Option<EntryRow> = response.take(0)?;
+        Ok(row.map(|row| WatchlistEntry {
+            anime_id,
+            status: row.status,
+            added_at: row.added_at,
+            updated_at: row.updated_at,
+        }))
     }
     
-    // Tag operations
+    /// Add an anime to the watchlist, or update the status if it is already there.
+    /// Returns the entry and whether a new edge was created.
+    #[tracing::instrument(skip(self, status), fields(db.system = DB_SYSTEM), err)]
+    pub async fn upsert_watchlist_entry(&self, user_id: &str, anime_id: Uuid, status: WatchlistStatus) -> Result<(WatchlistEntry, bool)> {
+        let existed = self.get_watchlist_entry(user_id, anime_id).await?.is_some();
+        
+        self.db
+            .query(r#"
+                BEGIN TRANSACTION;
+                LET $user = type::thing('user', $user_id);
+                LET $anime = type::thing('anime', $anime_id);
+                LET $existing = (SELECT VALUE id FROM watchlist_entry WHERE in = $user AND out = $anime);
+                IF array::len($existing) > 0 {
+                    UPDATE $existing SET status = $status, updated_at = time::now();
+                } ELSE {
+                    RELATE $user->watchlist_entry->$anime
+                        SET status = $status, added_at = time::now(), updated_at = time::now();
+                };
+                COMMIT TRANSACTION;
+            "#)
+            .bind(("user_id", user_id.to_string()))
+            .bind(("anime_id", anime_id.to_string()))
+            .bind(("status", status))
+            .await?
+            .check()?;
+        
+        let entry = self.get_watchlist_entry(user_id, anime_id).await?
+            .context("Watchlist entry missing after write")?;
+        
+        if !existed {
+            self.increment_popularity(anime_id, PopularityEvent::WatchlistAdd.weight()).await?;
+        }
+        
+        Ok((entry, !existed))
+    }
+    
+    /// Change the status of an existing entry; None if the anime is not on the watchlist
+    #[tracing::instrument(skip(self, status), fields(db.system = DB_SYSTEM), err)]
+    pub async fn update_watchlist_status(&self, user_id: &str, anime_id: Uuid, status: WatchlistStatus) -> Result<Option<WatchlistEntry>> {
+        self.db
+            .query("UPDATE watchlist_entry SET status = $status, updated_at = time::now() WHERE in = type::thing('user', $user_id) AND out = type::thing('anime', $anime_id)")
+            .bind(("user_id", user_id.to_string()))
+            .bind(("anime_id", anime_id.to_string()))
+            .bind(("status", status))
+            .await?
+            .check()?;
+        
+        self.get_watchlist_entry(user_id, anime_id).await
+    }
+    
+    /// Remove an anime from the watchlist; removing an absent entry is not an error
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn remove_from_watchlist(&self, user_id: &str, anime_id: Uuid) -> Result<()> {
+        self.db
+            .query("DELETE watchlist_entry WHERE in = type::thing('user', $user_id) AND out = type::thing('anime', $anime_id)")
+            .bind(("user_id", user_id.to_string()))
+            .bind(("anime_id", anime_id.to_string()))
+            .await?
+            .check()?;
+        
+        Ok(())
+    }
+    
+    /// The stored anime listing `source` among its source URLs, if any
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn find_anime_by_source(&self, source: &str) -> Result<Option<Anime>> {
+        let mut response = self.db
+            .query("SELECT * FROM anime WHERE sources CONTAINS $source LIMIT 1")
+            .bind(("source", source.to_string()))
+            .await?;
+        
+        let anime: Option<Anime> = response.take(0)?;
+        Ok(anime)
+    }
+    
+    /// Store an anime-offline-database entry without duplicating it: an entry
+    /// sharing a source URL with a stored anime is merged into that record with
+    /// `Anime::merge_newer`, keeping its id so episodes and edges stay attached,
+    /// and any other entry is created.
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM, title = %entry.title), err)]
+    pub async fn upsert_anime_from_offline_entry(&self, entry: &AnimeOfflineEntry, score_range: &ScoreRange) -> Result<UpsertOutcome> {
+        let incoming = entry.to_anime_model(score_range);
+        
+        let mut stored = None;
+        for source in &entry.sources {
+            stored = self.find_anime_by_source(source).await?;
+            if stored.is_some() {
+                break;
+            }
+        }
+        
+        let Some(mut anime) = stored else {
+            return Ok(UpsertOutcome::Created(self.create_anime(&incoming).await?));
+        };
+        if !anime.merge_newer(incoming) {
+            return Ok(UpsertOutcome::Unchanged(anime));
+        }
+        Ok(UpsertOutcome::Updated(self.update_anime(&anime).await?))
+    }
+    
+    /// Which of the given source URLs already belong to a stored anime
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn find_existing_sources(&self, sources: Vec<String>) -> Result<HashSet<String>> {
+        let mut response = self.db
+            .query("SELECT VALUE sources FROM anime WHERE sources CONTAINSANY $sources")
+            .bind(("sources", sources))
+            .await?;
+        
+        let stored: Vec<Vec<String>> = response.take(0)?;
+        Ok(stored.into_iter().flatten().collect())
+    }
+    
+    /// Every source URL of every stored anime, for deduplicating large imports up front
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_all_sources(&self) -> Result<HashSet<String>> {
+        let mut response = self.db
+            .query("SELECT VALUE sources FROM anime")
+            .await?;
+        
+        let stored: Vec<Vec<String>> = response.take(0)?;
+        Ok(stored.into_iter().flatten().collect())
+    }
+    
+    // Batch import optimizations
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn batch_create_anime(&self, anime_list: Vec<Anime>) -> Result<usize> {
+        let mut count = 0;
+        
+        // Use transaction for consistency
+        self.db.query("BEGIN TRANSACTION").await?.check()?;
+        
+        for anime in anime_list {
+            match self.create_anime(&anime).await {
+                Ok(_) => count += 1,
+                Err(e) => {
+                    tracing::warn!("Failed to import anime '{}': {}", anime.title, e);
+                }
+            }
+        }
+        
+        self.db.query("COMMIT TRANSACTION").await?.check()?;
+        
+        Ok(count)
+    }
+    
+    // Episode operations
+    // Every write path recomputes the owning anime's stored_episode_count from
+    // the episode rows in the same transaction, so retries cannot double-count.
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn create_episode(&self, episode: &Episode) -> Result<Episode> {
+        self.write_episode("CREATE", episode).await
+    }
+    
+    /// Create a batch of episodes for an anime, each linked to it by a `belongs_to`
+    /// edge, in one transaction. Nothing is written unless the whole batch is good:
+    /// every episode is validated first and all problems are returned together as
+    /// `AppError::ValidationError` (numbers repeated within the batch included),
+    /// a missing anime is `AppError::NotFound`, and numbers the anime already has
+    /// are `EpisodeBatchError::Duplicate`. Absolute numbers are renumbered to
+    /// per-season order first (see `normalize_episode_numbers`). Returns the new
+    /// episodes in batch order.
+    #[tracing::instrument(skip(self, episodes), fields(db.system = DB_SYSTEM), err)]
+    pub async fn batch_create_episodes(&self, anime_id: Uuid, mut episodes: Vec<Episode>) -> Result<Vec<Episode>> {
+        if let Err(problems) = validate_new_episodes(anime_id, &episodes) {
+            let errors = problems
+                .into_iter()
+                .map(|(field, message)| ValidationError { field, message })
+                .collect();
+            return Err(AppError::ValidationError(errors).into());
+        }
+        
+        if self.get_anime(anime_id).await?.is_none() {
+            return Err(AppError::NotFound(format!("Anime {} not found", anime_id)).into());
+        }
+        
+        let stored = self.get_anime_episodes(anime_id).await?;
+        normalize_episode_numbers(&stored, &mut episodes);
+        
+        let existing: HashSet<u32> = stored
+            .into_iter()
+            .map(|episode| episode.episode_number)
+            .collect();
+        let taken: BTreeMap<String, String> = episodes.iter()
+            .enumerate()
+            .filter(|(_, episode)| existing.contains(&episode.episode_number))
+            .map(|(index, episode)| {
+                (format!("episodes[{}].episode_number", index), format!("Episode {} already exists", episode.episode_number))
+            })
+            .collect();
+        if !taken.is_empty() {
+            return Err(EpisodeBatchError::Duplicate(taken).into());
+        }
+        
+        let numbers: Vec<u32> = episodes.iter().map(|episode| episode.episode_number).collect();
+        
+        // Re-checked inside the transaction so a concurrent batch can't slip in between
+        let mut query = String::from(
+            "BEGIN TRANSACTION;
+            LET $anime = type::thing('anime', $anime_key);
+            IF count(SELECT id FROM episode WHERE anime_id = $anime_id AND episode_number IN $numbers) > 0 {
+                THROW 'Episode numbers already exist';
+            };"
+        );
+        for index in 0..episodes.len() {
+            query.push_str(&format!(
+                "CREATE type::thing('episode', $id_{i}) CONTENT $episode_{i};
+                LET $record_{i} = type::thing('episode', $id_{i});
+                RELATE $record_{i}->belongs_to->$anime SET created_at = time::now();",
+                i = index
+            ));
+        }
+        query.push_str(RECOUNT_STORED_EPISODES);
+        query.push_str("COMMIT TRANSACTION;");
+        
+        let mut request = self.db
+            .query(query)
+            .bind(("anime_key", anime_id.to_string()))
+            .bind(("anime_id", anime_id))
+            .bind(("numbers", numbers));
+        for (index, episode) in episodes.iter().enumerate() {
+            request = request
+                .bind((format!("id_{}", index), episode.id.to_string()))
+                .bind((format!("episode_{}", index), episode.clone()));
+        }
+        request.await?.check()?;
+        
+        Ok(episodes)
+    }
+    
+    /// Write an episode, replacing any stored one with its id. An absolute
+    /// number continuing the anime's renumbered run is renumbered to match.
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn upsert_episode(&self, episode: &Episode) -> Result<Episode> {
+        let stored: Vec<Episode> = self.get_anime_episodes(episode.anime_id).await?
+            .into_iter()
+            .filter(|stored| stored.id != episode.id)
+            .collect();
+        
+        let mut episode = episode.clone();
+        normalize_episode_numbers(&stored, std::slice::from_mut(&mut episode));
+        self.write_episode("UPSERT", &episode).await
+    }
+    
+    /// Renumber an anime's stored episodes that were imported with absolute
+    /// numbers to per-season order, keeping the originals as `absolute_number`.
+    /// Episodes that already have an absolute number are left as they are.
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn renumber_anime_episodes(&self, anime_id: Uuid) -> Result<EpisodeRenumbering> {
+        let (numbered, mut raw): (Vec<Episode>, Vec<Episode>) = self.get_anime_episodes(anime_id).await?
+            .into_iter()
+            .partition(|episode| episode.absolute_number.is_some());
+        
+        let offset = normalize_episode_numbers(&numbered, &mut raw);
+        if offset.is_some() {
+            let mut query = String::from("BEGIN TRANSACTION;");
+            for index in 0..raw.len() {
+                query.push_str(&format!(
+                    "UPDATE type::thing('episode', $id_{i}) SET episode_number = $number_{i}, absolute_number = $absolute_{i}, updated_at = time::now();",
+                    i = index
+                ));
+            }
+            query.push_str("COMMIT TRANSACTION;");
+            
+            let mut request = self.db.query(query);
+            for (index, episode) in raw.iter().enumerate() {
+                request = request
+                    .bind((format!("id_{}", index), episode.id.to_string()))
+                    .bind((format!("number_{}", index), episode.episode_number))
+                    .bind((format!("absolute_{}", index), episode.absolute_number));
+            }
+            request.await?.check()?;
+        }
+        
+        Ok(EpisodeRenumbering {
+            anime_id,
+            offset,
+            renumbered: if offset.is_some() { raw.len() } else { 0 },
+        })
+    }
+    
+    async fn write_episode(&self, statement: &str, episode: &Episode) -> Result<Episode> {
+        let query = format!(
+            "BEGIN TRANSACTION;
+            {} type::thing('episode', $id) CONTENT $episode;
+            {}
+            COMMIT TRANSACTION;",
+            statement, RECOUNT_STORED_EPISODES
+        );
+        
+        let mut response = self.db
+            .query(query)
+            .bind(("id", episode.id.to_string()))
+            .bind(("episode", episode.clone()))
+            .bind(("anime_key", episode.anime_id.to_string()))
+            .bind(("anime_id", episode.anime_id))
+            .await?
+            .check()?;
+        
+        let written: Option<Episode> = response.take(0)?;
+        written.context("Failed to write episode")
+    }
+    
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_episode(&self, episode_id: Uuid) -> Result<Option<Episode>> {
+        let episode: Option<Episode> = self.db
+            .select(("episode", episode_id.to_string()))
+            .await?;
+        
+        Ok(episode)
+    }
+    
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn delete_episode(&self, anime_id: Uuid, episode_id: Uuid) -> Result<()> {
+        let query = format!(
+            "BEGIN TRANSACTION;
+            DELETE type::thing('episode', $id);
+            {}
+            COMMIT TRANSACTION;",
+            RECOUNT_STORED_EPISODES
+        );
+        
+        self.db
+            .query(query)
+            .bind(("id", episode_id.to_string()))
+            .bind(("anime_key", anime_id.to_string()))
+            .bind(("anime_id", anime_id))
+            .await?
+            .check()?;
+        
+        Ok(())
+    }
+    
+    /// Recompute stored_episode_count for every anime from the episode rows.
+    /// Returns the records whose counter had drifted; with `apply` false nothing is written.
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn reconcile_episode_counts(&self, apply: bool) -> Result<Vec<EpisodeCountFix>> {
+        #[derive(Deserialize)]
+        struct EpisodeCountRow {
+            anime_id: Uuid,
+            count: u32,
+        }
+        
+        let mut response = self.db
+            .query("SELECT anime_id, count() AS count FROM episode GROUP BY anime_id")
+            .await?;
+        let rows: Vec<EpisodeCountRow> = response.take(0)?;
+        let actual: std::collections::HashMap<Uuid, u32> = rows
+            .into_iter()
+            .map(|row| (row.anime_id, row.count))
+            .collect();
+        
+        let mut response = self.db.query("SELECT * FROM anime").await?;
+        let anime: Vec<Anime> = response.take(0)?;
+        
+        let mut fixes = Vec::new();
+        for record in anime {
+            let stored = actual.get(&record.id).copied().unwrap_or(0);
+            if record.stored_episode_count == stored {
+                continue;
+            }
+            
+            if apply {
+                self.db
+                    .query(RECOUNT_STORED_EPISODES)
+                    .bind(("anime_key", record.id.to_string()))
+                    .bind(("anime_id", record.id))
+                    .await?
+                    .check()?;
+            }
+            
+            fixes.push(EpisodeCountFix {
+                anime_id: record.id,
+                title: record.title,
+                recorded: record.stored_episode_count,
+                actual: stored,
+            });
+        }
+        
+        Ok(fixes)
+    }
+    
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_anime_episodes(&self, anime_id: Uuid) -> Result<Vec<Episode>> {
+        let mut response = self.db
+            .query("SELECT * FROM episode WHERE anime_id = $anime_id ORDER BY episode_number")
+            .bind(("anime_id", anime_id))
+            .await?;
+        
+        let episodes: Vec<Episode> = response.take(0)?;
+        Ok(episodes)
+    }
+    
+    /// An anime's episodes aired within [`from`, `to`], ordered by episode
+    /// number. Either bound may be open; once one is set, episodes without an
+    /// air date are left out.
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_anime_episodes_in_range(
+        &self,
+        anime_id: Uuid,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<Episode>> {
+        let mut conditions = vec!["anime_id = $anime_id"];
+        if from.is_some() || to.is_some() {
+            // Air dates are stored as ISO strings, which sort chronologically
+            conditions.push("type::is::string(air_date)");
+        }
+        if from.is_some() {
+            conditions.push("air_date >= $from");
+        }
+        if to.is_some() {
+            conditions.push("air_date <= $to");
+        }
+        
+        let mut response = self.db
+            .query(format!("SELECT * FROM episode WHERE {} ORDER BY episode_number", conditions.join(" AND ")))
+            .bind(("anime_id", anime_id))
+            .bind(("from", from.map(|date| date.to_string())))
+            .bind(("to", to.map(|date| date.to_string())))
+            .await?;
+        
+        let episodes: Vec<Episode> = response.take(0)?;
+        Ok(episodes)
+    }
+    
+    /// Air dates of the given anime's dated episodes, per anime, earliest first
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_episode_air_dates(&self, anime_ids: &[Uuid]) -> Result<HashMap<Uuid, Vec<NaiveDate>>> {
+        #[derive(Deserialize)]
+        struct AirDateRow {
+            anime_id: Uuid,
+            air_date: NaiveDate,
+        }
+        
+        // Air dates are stored as ISO strings, which sort chronologically
+        let mut response = self.db
+            .query("SELECT anime_id, air_date FROM episode WHERE anime_id IN $anime_ids AND type::is::string(air_date) ORDER BY air_date")
+            .bind(("anime_ids", anime_ids.to_vec()))
+            .await?;
+        
+        let rows: Vec<AirDateRow> = response.take(0)?;
+        let mut air_dates: HashMap<Uuid, Vec<NaiveDate>> = HashMap::new();
+        for row in rows {
+            air_dates.entry(row.anime_id).or_default().push(row.air_date);
+        }
+        Ok(air_dates)
+    }
+    
+    /// One page of a keyset cursor over an anime's episodes: numbers in
+    /// (`after`, `to`], ordered by episode number
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_anime_episodes_after(
+        &self,
+        anime_id: Uuid,
+        after: u32,
+        to: Option<u32>,
+        limit: usize,
+    ) -> Result<Vec<Episode>> {
+        let upper_bound = if to.is_some() { " AND episode_number <= $to" } else { "" };
+        let query = format!(
+            "SELECT * FROM episode WHERE anime_id = $anime_id AND episode_number > $after{} \
+             ORDER BY episode_number LIMIT $limit",
+            upper_bound
+        );
+        
+        let mut response = self.db
+            .query(query)
+            .bind(("anime_id", anime_id))
+            .bind(("after", after))
+            .bind(("to", to))
+            .bind(("limit", limit))
+            .await?;
+        
+        let episodes: Vec<Episode> = response.take(0)?;
+        Ok(episodes)
+    }
+    
+    /// Stream an anime's episodes in order, fetching `EPISODE_STREAM_BATCH` at a
+    /// time so arbitrarily long series never have to be held in memory at once
+    pub fn stream_anime_episodes(
+        self: Arc<Self>,
+        anime_id: Uuid,
+        from: Option<u32>,
+        to: Option<u32>,
+    ) -> impl Stream<Item = Result<Episode>> + Send + 'static {
+        // Episode numbers start at 1, so "after 0" covers the whole series
+        let after = from.map(|n| n.saturating_sub(1)).unwrap_or(0);
+        
+        stream::try_unfold(Some(after), move |cursor| {
+            let db = self.clone();
+            async move {
+                let Some(after) = cursor else { return Ok::<_, anyhow::Error>(None) };
+                let batch = db
+                    .get_anime_episodes_after(anime_id, after, to, EPISODE_STREAM_BATCH)
+                    .await?;
+                
+                // A short page means the cursor is exhausted
+                let next = match batch.last() {
+                    Some(last) if batch.len() == EPISODE_STREAM_BATCH => Some(last.episode_number),
+                    _ => None,
+                };
+                
+                if batch.is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some((stream::iter(batch.into_iter().map(Ok)), next)))
+                }
+            }
+        })
+        .try_flatten()
+    }
+    
+    // User operations
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn create_user(&self, user: &User) -> Result<User> {
+        let user_clone = user.clone();
+        let created: Option<User> = self.db
+            .create(("user", user.id.to_string()))
+            .content(user_clone)
+            .await?;
+        
+        created.context("Failed to create user")
+    }
+    
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_user_by_email(&self, email: &str) -> Result<Option<User>> {
+        let mut response = self.db
+            .query("SELECT * FROM user WHERE email = $email LIMIT 1")
+            .bind(("email", email.to_lowercase()))
+            .await?;
+        
+        let users: Vec<User> = response.take(0)?;
+        Ok(users.into_iter().next())
+    }
+    
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_user_by_username(&self, username: &str) -> Result<Option<User>> {
+        let mut response = self.db
+            .query("SELECT * FROM user WHERE username = $username LIMIT 1")
+            .bind(("username", username.to_lowercase()))
+            .await?;
+        
+        let users: Vec<User> = response.take(0)?;
+        Ok(users.into_iter().next())
+    }
+    
+    /// Mark the user's email address verified; false if there is no such user
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn mark_email_verified(&self, user_id: Uuid) -> Result<bool> {
+        let mut response = self.db
+            .query("UPDATE type::thing('user', $id) SET email_verified = true, updated_at = time::now() RETURN AFTER")
+            .bind(("id", user_id.to_string()))
+            .await?;
+        
+        let updated: Vec<User> = response.take(0)?;
+        Ok(!updated.is_empty())
+    }
+    
+    // Device operations
+    
+    /// The user's device described by `info`, if it has signed in before
+    #[tracing::instrument(skip(self, info), fields(db.system = DB_SYSTEM), err)]
+    pub async fn find_user_device(&self, user_id: &str, info: &DeviceInfo) -> Result<Option<Device>> {
+        let devices = self.get_user_devices(user_id).await?;
+        Ok(devices.into_iter().find(|device| device.matches(info)))
+    }
+    
+    /// Create or replace a device record
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn save_device(&self, device: &Device) -> Result<Device> {
+        let saved: Option<Device> = self.db
+            .upsert(("device", device.id.to_string()))
+            .content(device.clone())
+            .await?;
+        
+        saved.context("Failed to save device")
+    }
+    
+    /// Delete devices unseen for `DEVICE_RETENTION_DAYS`
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn prune_stale_devices(&self) -> Result<()> {
+        self.db
+            .query("DELETE device WHERE last_seen_at < time::now() - type::duration($retention)")
+            .bind(("retention", format!("{}d", DEVICE_RETENTION_DAYS)))
+            .await?
+            .check()?;
+        
+        Ok(())
+    }
+    
+    /// The user's devices, most recently seen first
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_user_devices(&self, user_id: &str) -> Result<Vec<Device>> {
+        let mut response = self.db
+            .query("SELECT * FROM device WHERE user_id = $user_id ORDER BY last_seen_at DESC")
+            .bind(("user_id", user_id.to_string()))
+            .await?;
+        
+        let devices: Vec<Device> = response.take(0)?;
+        Ok(devices)
+    }
+    
+    /// Mark the device as in use now; None if it has been revoked or pruned
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn touch_device(&self, id: Uuid) -> Result<Option<Device>> {
+        let mut response = self.db
+            .query("UPDATE type::thing('device', $id) SET last_seen_at = time::now() RETURN AFTER")
+            .bind(("id", id.to_string()))
+            .await?;
+        
+        let devices: Vec<Device> = response.take(0)?;
+        Ok(devices.into_iter().next())
+    }
+    
+    /// Delete one of the user's devices, returning it; None if the user has no such device
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn delete_user_device(&self, user_id: &str, id: Uuid) -> Result<Option<Device>> {
+        let mut response = self.db
+            .query("DELETE type::thing('device', $id) WHERE user_id = $user_id RETURN BEFORE")
+            .bind(("id", id.to_string()))
+            .bind(("user_id", user_id.to_string()))
+            .await?;
+        
+        let devices: Vec<Device> = response.take(0)?;
+        Ok(devices.into_iter().next())
+    }
+    
+    // Tag operations
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
     pub async fn create_tag(&self, tag: &Tag) -> Result<Tag> {
         let tag_clone = tag.clone();
         let created: Option<Tag> = self.db
@@ -390,6 +2281,7 @@ impl DatabaseService {
         created.context("Failed to create tag")
     }
     
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
     pub async fn get_tags(&self) -> Result<Vec<Tag>> {
         let tags: Vec<Tag> = self.db
             .select("tag")
@@ -398,13 +2290,420 @@ impl DatabaseService {
         Ok(tags)
     }
     
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_tag(&self, tag_id: Uuid) -> Result<Option<Tag>> {
+        let tag: Option<Tag> = self.db
+            .select(("tag", tag_id.to_string()))
+            .await?;
+        
+        Ok(tag)
+    }
+    
+    /// Tag whose name matches `name` ignoring case, if any
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn find_tag_by_name(&self, name: &str) -> Result<Option<Tag>> {
+        let mut response = self.db
+            .query("SELECT meta::id(id) AS id, name, category, description, created_at FROM tag \
+                    WHERE string::lowercase(name) = string::lowercase($name) LIMIT 1")
+            .bind(("name", name.to_string()))
+            .await?;
+        
+        let tag: Option<Tag> = response.take(0)?;
+        Ok(tag)
+    }
+    
+    /// The tag named `tag.name` ignoring case, creating `tag` when there is none
+    #[tracing::instrument(skip_all, fields(db.system = DB_SYSTEM), err)]
+    pub async fn find_or_create_tag(&self, tag: &Tag) -> Result<Tag> {
+        if let Some(existing) = self.find_tag_by_name(&tag.name).await? {
+            return Ok(existing);
+        }
+        match self.create_tag(tag).await {
+            Ok(created) => Ok(created),
+            // Created concurrently since the lookup
+            Err(e) if violates_unique_index(&e, TAG_NAME_INDEX) => {
+                self.find_tag_by_name(&tag.name).await?.context("Tag missing after a duplicate name was rejected")
+            }
+            Err(e) => Err(e),
+        }
+    }
+    
+    /// Merge tags whose names differ only in case into the oldest of them,
+    /// moving their anime over; returns how many tags were merged away
+    async fn merge_case_duplicate_tags(&self) -> Result<usize> {
+        #[derive(Deserialize)]
+        struct TagKeyRow {
+            id: String,
+            key: String,
+        }
+        
+        let mut response = self.db
+            .query("SELECT meta::id(id) AS id, string::lowercase(name) AS key, created_at FROM tag ORDER BY created_at, id")
+            .await?;
+        let rows: Vec<TagKeyRow> = response.take(0)?;
+        
+        let mut kept: HashMap<String, String> = HashMap::new();
+        let mut merged = 0;
+        for row in rows {
+            let Some(keep_id) = kept.get(&row.key) else {
+                kept.insert(row.key, row.id);
+                continue;
+            };
+            
+            self.db
+                .query(r#"
+                    BEGIN TRANSACTION;
+                    LET $keep = type::thing('tag', $keep_id);
+                    LET $duplicate = type::thing('tag', $duplicate_id);
+                    FOR $edge IN (SELECT in, relevance, created_at FROM has_tag WHERE out = $duplicate) {
+                        LET $anime = $edge.in;
+                        IF count(SELECT id FROM has_tag WHERE in = $anime AND out = $keep) = 0 {
+                            RELATE $anime->has_tag->$keep SET relevance = $edge.relevance, created_at = $edge.created_at;
+                        };
+                    };
+                    DELETE has_tag WHERE out = $duplicate;
+                    DELETE $duplicate;
+                    COMMIT TRANSACTION;
+                "#)
+                .bind(("keep_id", keep_id.clone()))
+                .bind(("duplicate_id", row.id))
+                .await?
+                .check()?;
+            merged += 1;
+        }
+        
+        Ok(merged)
+    }
+    
+    /// Every tag, or only those of `category`, with the number of distinct anime
+    /// tagged with it, counted from the `has_tag` edges in the same query
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_tags_with_anime_counts(&self, category: Option<TagCategory>) -> Result<Vec<(Tag, usize)>> {
+        #[derive(Deserialize)]
+        struct TagCountRow {
+            #[serde(flatten)]
+            tag: Tag,
+            anime_count: usize,
+        }
+        
+        let filter = if category.is_some() { " WHERE category = $category" } else { "" };
+        let mut response = self.db
+            .query(format!(
+                "SELECT meta::id(id) AS id, name, category, description, created_at, \
+                 count(array::distinct(<-has_tag.in)) AS anime_count FROM tag{}",
+                filter,
+            ))
+            .bind(("category", category))
+            .await?;
+        
+        let rows: Vec<TagCountRow> = response.take(0)?;
+        Ok(rows.into_iter().map(|row| (row.tag, row.anime_count)).collect())
+    }
+    
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
     pub async fn get_anime_tags(&self, anime_id: Uuid) -> Result<Vec<Tag>> {
+        let tags = self.get_anime_tags_with_relevance(anime_id).await?;
+        Ok(tags.into_iter().map(|(tag, _)| tag).collect())
+    }
+    
+    /// The anime's tags paired with the `has_tag` edge relevance (1.0 when unset)
+    #[tracing::instrument(skip(self), fields(db.system = DB_SYSTEM), err)]
+    pub async fn get_anime_tags_with_relevance(&self, anime_id: Uuid) -> Result<Vec<(Tag, f32)>> {
+        #[derive(Deserialize)]
+        struct TagRow {
+            #[serde(flatten)]
+            tag: Tag,
+            relevance: Option<f32>,
+        }
+        
         let mut response = self.db
-            .query("SELECT out.* FROM has_tag WHERE in = $anime_id")
-            .bind(("anime_id", format!("anime:{}", anime_id)))
+            .query("SELECT meta::id(out) AS id, out.name AS name, out.category AS category, \
+                    out.description AS description, out.created_at AS created_at, relevance \
+                    FROM has_tag WHERE in = type::thing('anime', $anime_id)")
+            .bind(("anime_id", anime_id.to_string()))
             .await?;
         
-        let tags: Vec<Tag> = response.take(0)?;
-        Ok(tags)
+        let rows: Vec<TagRow> = response.take(0)?;
+        Ok(rows.into_iter()
+            .map(|row| (row.tag, row.relevance.unwrap_or(1.0)))
+            .collect())
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::JitterStrategy;
+    use crate::services::search::{SortOrder, TieBreaker};
+
+    #[tokio::test]
+    async fn test_connect_retries_configured_times_before_failing() {
+        let retry = ResilienceConfig {
+            max_retries: 2,
+            base_delay_ms: 1,
+            jitter: JitterStrategy::None,
+            ..ResilienceConfig::default()
+        };
+
+        // Nothing listens on port 1, so every attempt is refused
+        match DatabaseService::connect("127.0.0.1:1", &retry).await {
+            Ok(_) => panic!("connecting to a closed port should fail"),
+            Err(e) => assert!(e.to_string().ends_with("failed after 3 attempts"), "unexpected error: {}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_legacy_usernames_are_backfilled_before_the_unique_index() {
+        let db = DatabaseService::new(MEMORY_DB_SCHEME).await.unwrap();
+        // Accounts from before usernames, under the old non-unique index
+        db.db
+            .query("DEFINE INDEX user_username ON user FIELDS username")
+            .query("CREATE user:a SET email = 'a@example.com'")
+            .query("CREATE user:b SET email = 'b@example.com', username = ''")
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        db.initialize_schema().await.unwrap();
+
+        let mut response = db.db.query("SELECT VALUE username FROM user ORDER BY username").await.unwrap();
+        let usernames: Vec<String> = response.take(0).unwrap();
+        assert_eq!(usernames, vec!["user_a", "user_b"]);
+
+        let taken = db.db
+            .query("CREATE user:c SET email = 'c@example.com', username = 'user_a'")
+            .await
+            .unwrap()
+            .check()
+            .map_err(anyhow::Error::from)
+            .unwrap_err();
+        assert!(violates_unique_index(&taken, USER_USERNAME_INDEX));
+        assert!(!violates_unique_index(&taken, USER_EMAIL_INDEX));
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_case_duplicate_tags_are_merged_before_the_unique_index() {
+        let db = DatabaseService::new(MEMORY_DB_SCHEME).await.unwrap();
+        // Tags from before the index, repeating a name in another case
+        db.db
+            .query("CREATE tag:old SET name = 'Action', category = 'genre', created_at = d'2024-01-01T00:00:00Z'")
+            .query("CREATE tag:new SET name = 'action', category = 'genre', created_at = d'2024-02-01T00:00:00Z'")
+            .query("CREATE anime:a SET title = 'A'")
+            .query("CREATE anime:b SET title = 'B'")
+            .query("RELATE anime:a->has_tag->tag:old SET relevance = 1.0")
+            .query("RELATE anime:a->has_tag->tag:new SET relevance = 1.0")
+            .query("RELATE anime:b->has_tag->tag:new SET relevance = 0.5")
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        db.initialize_schema().await.unwrap();
+
+        let mut response = db.db
+            .query("SELECT VALUE meta::id(id) FROM tag")
+            .query("SELECT VALUE meta::id(in) FROM has_tag WHERE out = tag:old")
+            .await
+            .unwrap();
+        let tags: Vec<String> = response.take(0).unwrap();
+        let mut tagged: Vec<String> = response.take(1).unwrap();
+        tagged.sort();
+        assert_eq!(tags, vec!["old"]);
+        assert_eq!(tagged, vec!["a", "b"]);
+
+        let taken = db.db
+            .query("CREATE tag:shouted SET name = 'ACTION', category = 'genre'")
+            .await
+            .unwrap()
+            .check()
+            .map_err(anyhow::Error::from)
+            .unwrap_err();
+        assert!(violates_unique_index(&taken, TAG_NAME_INDEX));
+    }
+
+    #[tokio::test]
+    async fn test_episode_air_dates_are_grouped_by_anime() {
+        let db = DatabaseService::new(MEMORY_DB_SCHEME).await.unwrap();
+        db.initialize_schema().await.unwrap();
+        let (airing, undated, other) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        db.db
+            .query("CREATE episode SET anime_id = $airing, episode_number = 2, air_date = '2024-04-13'")
+            .query("CREATE episode SET anime_id = $airing, episode_number = 1, air_date = '2024-04-06'")
+            .query("CREATE episode SET anime_id = $airing, episode_number = 3")
+            .query("CREATE episode SET anime_id = $undated, episode_number = 1")
+            .query("CREATE episode SET anime_id = $other, episode_number = 1, air_date = '2024-04-01'")
+            .bind(("airing", airing))
+            .bind(("undated", undated))
+            .bind(("other", other))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let air_dates = db.get_episode_air_dates(&[airing, undated]).await.unwrap();
+        let date = |day| NaiveDate::from_ymd_opt(2024, 4, day).unwrap();
+        assert_eq!(air_dates.len(), 1);
+        assert_eq!(air_dates[&airing], vec![date(6), date(13)]);
+    }
+
+    /// An anime to search for: title, synonyms, rating, year and popularity
+    type SearchFixture = (&'static str, &'static [&'static str], Option<f32>, u16, f64);
+    
+    /// A schema-initialized database holding `anime`
+    async fn search_catalog(anime: &[SearchFixture]) -> DatabaseService {
+        let db = DatabaseService::new(MEMORY_DB_SCHEME).await.unwrap();
+        db.initialize_schema().await.unwrap();
+        
+        for (title, synonyms, rating, year, popularity) in anime {
+            let titles = std::iter::once(*title).chain(synonyms.iter().copied());
+            db.db
+                .query(
+                    "CREATE type::thing('anime', $id) SET title = $title, synonyms = $synonyms, \
+                     romaji_titles = $romaji_titles, episodes = 12, status = 'finished', type = 'TV', \
+                     anime_season = { season: 'spring', year: $year }, synopsis = '', \
+                     poster_url = 'https://example.com/poster.jpg', popularity = $popularity, \
+                     imdb = IF $rating != NONE THEN { id: 'tt0', rating: $rating, votes: 1 } END",
+                )
+                .bind(("id", Uuid::new_v4().to_string()))
+                .bind(("title", title.to_string()))
+                .bind(("synonyms", synonyms.iter().map(|synonym| synonym.to_string()).collect::<Vec<_>>()))
+                .bind(("romaji_titles", romaji_titles(titles)))
+                .bind(("year", *year as i64))
+                .bind(("popularity", *popularity))
+                .bind(("rating", *rating))
+                .await
+                .unwrap()
+                .check()
+                .unwrap();
+        }
+        db
+    }
+    
+    /// Titles of the first page of `query`'s matches, in `order_by` order
+    async fn search_titles(db: &DatabaseService, query: &str, order_by: String) -> Vec<String> {
+        let (page, _) = db.search_anime_matches(&SearchQuery::parse(query), &order_by, 20, 0).await.unwrap();
+        page.into_iter().map(|anime| anime.title).collect()
+    }
+    
+    const TITANS: &[SearchFixture] = &[
+        ("Titan B", &[], Some(7.5), 2013, 0.0),
+        ("Titan A", &[], Some(9.0), 2010, 0.0),
+        ("Attack on Titan", &[], None, 2020, 12.0),
+    ];
+    
+    #[tokio::test]
+    async fn test_search_equal_scores_ordered_by_tie_breaker() {
+        let db = search_catalog(TITANS).await;
+        let order = |tie_breaker| SortOrder::Default.order_by(tie_breaker);
+        
+        // Both prefix matches outrank the substring match; higher rating wins the tie
+        assert_eq!(
+            search_titles(&db, "titan", order(TieBreaker::Rating)).await,
+            vec!["Titan A", "Titan B", "Attack on Titan"]
+        );
+        assert_eq!(
+            search_titles(&db, "titan", order(TieBreaker::Recent)).await,
+            vec!["Titan B", "Titan A", "Attack on Titan"]
+        );
+        assert_eq!(
+            search_titles(&db, "titan", order(TieBreaker::Alphabetical)).await,
+            vec!["Titan A", "Titan B", "Attack on Titan"]
+        );
+    }
+    
+    #[tokio::test]
+    async fn test_search_popularity_sort_keeps_relevance_for_ties() {
+        let db = search_catalog(TITANS).await;
+        
+        assert_eq!(
+            search_titles(&db, "titan", SortOrder::Popularity.order_by(TieBreaker::Rating)).await,
+            vec!["Attack on Titan", "Titan A", "Titan B"]
+        );
+    }
+    
+    #[tokio::test]
+    async fn test_search_pages_in_the_database() {
+        let db = search_catalog(TITANS).await;
+        let query = SearchQuery::parse("titan");
+        let order_by = SortOrder::Default.order_by(TieBreaker::Rating);
+        
+        let (page, total) = db.search_anime_matches(&query, &order_by, 1, 1).await.unwrap();
+        assert_eq!(page.iter().map(|anime| anime.title.as_str()).collect::<Vec<_>>(), vec!["Titan B"]);
+        assert_eq!(total, 3);
+        
+        let (page, total) = db.search_anime_matches(&query, &order_by, 20, 3).await.unwrap();
+        assert!(page.is_empty());
+        assert_eq!(total, 3);
+    }
+    
+    #[tokio::test]
+    async fn test_search_kana_matches_rank_after_matches_as_typed() {
+        let db = search_catalog(&[
+            ("Shingeki no Kyojin Season 2", &[], None, 2017, 0.0),
+            ("Shingeki no Kyojin", &[], None, 2013, 0.0),
+            ("Attack on Titan", &["しんげきのきょじん"], None, 2013, 0.0),
+            ("Kyojin Gaiden", &[], None, 2015, 0.0),
+        ]).await;
+        
+        // The kana synonym matches as typed; compact keys match the romaji
+        // titles across their spaces, exact before prefix
+        assert_eq!(
+            search_titles(&db, "しんげきのきょじん", SortOrder::Default.order_by(TieBreaker::Rating)).await,
+            vec!["Attack on Titan", "Shingeki no Kyojin", "Shingeki no Kyojin Season 2"]
+        );
+    }
+    
+    #[tokio::test]
+    async fn test_popularity_writes_leave_updated_at_alone() {
+        let db = DatabaseService::new(MEMORY_DB_SCHEME).await.unwrap();
+        db.initialize_schema().await.unwrap();
+        let anime_id = Uuid::new_v4();
+        db.db.query("CREATE type::thing('anime', $id) SET title = 'Popular', popularity = 1.0")
+            .bind(("id", anime_id.to_string()))
+            .await.unwrap()
+            .check().unwrap();
+        let updated_at = || async {
+            let mut response = db.db.query("SELECT VALUE updated_at FROM ONLY type::thing('anime', $id)")
+                .bind(("id", anime_id.to_string()))
+                .await.unwrap();
+            let updated_at: Option<chrono::DateTime<chrono::Utc>> = response.take(0).unwrap();
+            updated_at.unwrap()
+        };
+        let created = updated_at().await;
+        
+        db.increment_popularity(anime_id, 2.0).await.unwrap();
+        let start = created - chrono::Duration::days(1);
+        db.popularity_decayed_at(start).await.unwrap();
+        assert!(db.decay_popularity(start, created, 0.5).await.unwrap());
+        assert_eq!(updated_at().await, created);
+        
+        // Catalog edits are still stamped
+        db.db.query("UPDATE type::thing('anime', $id) SET title = 'Renamed'")
+            .bind(("id", anime_id.to_string()))
+            .await.unwrap()
+            .check().unwrap();
+        assert!(updated_at().await > created);
+    }
+    
+    #[tokio::test]
+    async fn test_popularity_decay_applies_once_per_clock_reading() {
+        let db = DatabaseService::new(MEMORY_DB_SCHEME).await.unwrap();
+        db.initialize_schema().await.unwrap();
+        db.db.query("CREATE anime:a SET popularity = 8.0").await.unwrap().check().unwrap();
+
+        let start = chrono::Utc::now();
+        assert_eq!(db.popularity_decayed_at(start).await.unwrap(), start);
+        // Later readers get the recorded clock, not their own time
+        assert_eq!(db.popularity_decayed_at(start + chrono::Duration::hours(1)).await.unwrap(), start);
+
+        // Two instances both saw `start`; only the first decay lands
+        let next = start + chrono::Duration::days(1);
+        assert!(db.decay_popularity(start, next, 0.5).await.unwrap());
+        assert!(!db.decay_popularity(start, next, 0.5).await.unwrap());
+        assert_eq!(db.popularity_decayed_at(next).await.unwrap(), next);
+
+        let mut response = db.db.query("SELECT VALUE popularity FROM ONLY anime:a").await.unwrap();
+        let popularity: Option<f64> = response.take(0).unwrap();
+        assert_eq!(popularity, Some(4.0));
+    }
+}
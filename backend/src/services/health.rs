@@ -1,11 +1,11 @@
 // T066 & T067: Health check service with dependency monitoring
 // Reference: plan.md Phase 4 - Production Hardening
 
-use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 
 /// Overall health status of the application
@@ -54,11 +54,15 @@ pub struct ReadinessResponse {
     pub failing_checks: Vec<String>,
 }
 
+/// Crunchyroll responses slower than this report as degraded; half the probe timeout
+const CRUNCHYROLL_SLOW_MS: u64 = 1000;
+
 /// Health check service that monitors all dependencies
 pub struct HealthService {
     start_time: DateTime<Utc>,
     version: String,
     checks: Arc<RwLock<Vec<ComponentHealth>>>,
+    system_thresholds: SystemThresholds,
 }
 
 impl HealthService {
@@ -67,6 +71,7 @@ impl HealthService {
             start_time: Utc::now(),
             version,
             checks: Arc::new(RwLock::new(Vec::new())),
+            system_thresholds: SystemThresholds::from_env(),
         }
     }
 
@@ -172,6 +177,9 @@ impl HealthService {
 
         let latency_ms = start.elapsed().as_millis() as u64;
         metadata.insert("latency_ms".to_string(), serde_json::Value::Number(latency_ms.into()));
+        if let Some(message) = &message {
+            metadata.insert("error".to_string(), serde_json::Value::String(message.clone()));
+        }
 
         ComponentHealth {
             name: "redis".to_string(),
@@ -208,88 +216,354 @@ impl HealthService {
         }
     }
 
-    /// Check Crunchyroll API health
-    pub async fn check_crunchyroll(&self, _streaming: &crate::services::StreamingService) -> ComponentHealth {
+    /// Check the response cache (Redis, or the in-memory backend) with a key lookup.
+    /// Metadata reports this instance's cached GET response hits and misses.
+    pub async fn check_cache(&self, cache: &mut crate::services::CacheService) -> ComponentHealth {
         let start = std::time::Instant::now();
-        let metadata = HashMap::new();
 
-        // For now, we'll consider Crunchyroll healthy if we can create a client
-        // In production, you'd want to make a lightweight API call
-        let (status, message) = (
-            HealthStatus::Healthy,
-            Some("Crunchyroll check not implemented".to_string())
-        );
+        let (status, message) = match cache.exists("health:probe").await {
+            Ok(_) => (HealthStatus::Healthy, None),
+            Err(e) => (HealthStatus::Unhealthy, Some(format!("Cache error: {}", e))),
+        };
 
-        let latency_ms = start.elapsed().as_millis() as u64;
+        let stats = cache.response_stats();
+        let mut metadata = HashMap::new();
+        metadata.insert("response_hits".to_string(), serde_json::Value::Number(stats.hits.into()));
+        metadata.insert("response_misses".to_string(), serde_json::Value::Number(stats.misses.into()));
 
         ComponentHealth {
-            name: "crunchyroll".to_string(),
+            name: "cache".to_string(),
             status,
             message,
-            latency_ms,
+            latency_ms: start.elapsed().as_millis() as u64,
             last_check: Utc::now(),
             metadata,
         }
     }
 
-    /// Check system resources
-    pub async fn check_system(&self) -> ComponentHealth {
+    /// Check Crunchyroll API health with an authenticated probe of the API index
+    pub async fn check_crunchyroll(&self, streaming: &crate::services::StreamingService) -> ComponentHealth {
+        let token = streaming.probe_token().await;
+        self.check_crunchyroll_probe(streaming.api_probe(), token.as_deref()).await
+    }
+
+    /// Classify a probe: 2xx is healthy (degraded if slow), a rejected token is
+    /// unhealthy, other client responses are degraded, and server errors or
+    /// timeouts are unhealthy
+    pub async fn check_crunchyroll_probe(
+        &self,
+        probe: &crate::services::streaming::CrunchyrollProbe,
+        access_token: Option<&str>,
+    ) -> ComponentHealth {
         let start = std::time::Instant::now();
         let mut metadata = HashMap::new();
+        metadata.insert("authenticated".to_string(), serde_json::Value::Bool(access_token.is_some()));
 
-        // Get memory info using sysinfo or similar
-        // For now, we'll use a simplified check
-        let memory_usage = 0.75; // Placeholder
-        metadata.insert("memory_usage".to_string(), serde_json::Value::Number(
-            serde_json::Number::from_f64(memory_usage).unwrap_or(0.into())
-        ));
+        let result = probe.ping(access_token).await;
+        let latency_ms = start.elapsed().as_millis() as u64;
 
-        let status = if memory_usage > 0.9 {
-            HealthStatus::Unhealthy
-        } else if memory_usage > 0.8 {
-            HealthStatus::Degraded
-        } else {
-            HealthStatus::Healthy
+        let (status, message) = match result {
+            Ok(code) => {
+                metadata.insert("status_code".to_string(), serde_json::Value::Number(code.as_u16().into()));
+                let auth_rejected = code == reqwest::StatusCode::UNAUTHORIZED || code == reqwest::StatusCode::FORBIDDEN;
+                if access_token.is_some() && auth_rejected {
+                    (HealthStatus::Unhealthy, Some(format!("Crunchyroll rejected the service token: {}", code)))
+                } else if !code.is_success() {
+                    (HealthStatus::Degraded, Some(format!("Crunchyroll API returned {}", code)))
+                } else if latency_ms > CRUNCHYROLL_SLOW_MS {
+                    (HealthStatus::Degraded, Some(format!("Slow response: {}ms", latency_ms)))
+                } else {
+                    (HealthStatus::Healthy, None)
+                }
+            }
+            Err(e) => (HealthStatus::Unhealthy, Some(format!("Crunchyroll error: {}", e))),
         };
-
-        let latency_ms = start.elapsed().as_millis() as u64;
+        metadata.insert("latency_ms".to_string(), serde_json::Value::Number(latency_ms.into()));
+        if let Some(message) = &message {
+            metadata.insert("error".to_string(), serde_json::Value::String(message.clone()));
+        }
 
         ComponentHealth {
-            name: "system".to_string(),
+            name: "crunchyroll".to_string(),
             status,
-            message: Some(format!("Memory usage: {:.1}%", memory_usage * 100.0)),
+            message,
             latency_ms,
             last_check: Utc::now(),
             metadata,
         }
     }
+
+    /// Check system resources
+    pub async fn check_system(&self) -> ComponentHealth {
+        let start = std::time::Instant::now();
+        let readings = SystemReadings::read();
+        system_health(&readings, &self.system_thresholds, start.elapsed().as_millis() as u64)
+    }
+}
+
+/// System memory use, as a fraction of total memory, above which the system
+/// check reports degraded or unhealthy
+#[derive(Debug, Clone)]
+pub struct SystemThresholds {
+    pub mem_degraded: f64,
+    pub mem_unhealthy: f64,
+}
+
+impl Default for SystemThresholds {
+    fn default() -> Self {
+        SystemThresholds {
+            mem_degraded: 0.8,
+            mem_unhealthy: 0.9,
+        }
+    }
+}
+
+impl SystemThresholds {
+    /// Reads HEALTH_MEM_DEGRADED and HEALTH_MEM_UNHEALTHY, fractions such as 0.85
+    pub fn from_env() -> Self {
+        let defaults = SystemThresholds::default();
+        let env_or = |name: &str, default: f64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.trim().parse::<f64>().ok())
+                .filter(|fraction| (0.0..=1.0).contains(fraction))
+                .unwrap_or(default)
+        };
+
+        SystemThresholds {
+            mem_degraded: env_or("HEALTH_MEM_DEGRADED", defaults.mem_degraded),
+            mem_unhealthy: env_or("HEALTH_MEM_UNHEALTHY", defaults.mem_unhealthy),
+        }
+    }
+}
+
+/// Host and process resource usage. A metric the platform does not expose is
+/// None and left out of the health metadata.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemReadings {
+    pub process_rss_bytes: Option<u64>,
+    /// Fraction of system memory in use, excluding reclaimable caches
+    pub memory_usage: Option<f64>,
+    /// 1, 5 and 15 minute load averages
+    pub load_average: Option<[f64; 3]>,
+    pub open_fds: Option<u64>,
+}
+
+impl SystemReadings {
+    /// Current readings from /proc
+    #[cfg(target_os = "linux")]
+    pub fn read() -> Self {
+        let proc_file = |path: &str| std::fs::read_to_string(path).ok();
+
+        SystemReadings {
+            process_rss_bytes: proc_file("/proc/self/status").and_then(|status| meminfo_bytes(&status, "VmRSS")),
+            memory_usage: proc_file("/proc/meminfo").and_then(|meminfo| memory_usage(&meminfo)),
+            load_average: proc_file("/proc/loadavg").and_then(|loadavg| load_average(&loadavg)),
+            open_fds: std::fs::read_dir("/proc/self/fd").ok().map(|fds| fds.count() as u64),
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn read() -> Self {
+        SystemReadings::default()
+    }
+}
+
+/// `field` of a /proc status or meminfo file (`VmRSS:    1024 kB`) in bytes
+fn meminfo_bytes(contents: &str, field: &str) -> Option<u64> {
+    let kib = contents
+        .lines()
+        .find_map(|line| line.strip_prefix(field)?.strip_prefix(':'))?
+        .split_whitespace()
+        .next()?
+        .parse::<u64>()
+        .ok()?;
+    Some(kib * 1024)
+}
+
+fn memory_usage(meminfo: &str) -> Option<f64> {
+    let total = meminfo_bytes(meminfo, "MemTotal")?;
+    let available = meminfo_bytes(meminfo, "MemAvailable")?;
+    (total > 0).then(|| 1.0 - available.min(total) as f64 / total as f64)
+}
+
+fn load_average(loadavg: &str) -> Option<[f64; 3]> {
+    let mut fields = loadavg.split_whitespace().map(|field| field.parse::<f64>().ok());
+    Some([fields.next()??, fields.next()??, fields.next()??])
+}
+
+/// The system check's result for `readings`. Only memory use decides the status;
+/// without a memory reading the system reports healthy.
+fn system_health(readings: &SystemReadings, thresholds: &SystemThresholds, latency_ms: u64) -> ComponentHealth {
+    let mut metadata = HashMap::new();
+    if let Some(rss) = readings.process_rss_bytes {
+        metadata.insert("process_rss_bytes".to_string(), serde_json::json!(rss));
+    }
+    if let Some(usage) = readings.memory_usage {
+        metadata.insert("memory_usage".to_string(), serde_json::json!(usage));
+    }
+    if let Some(load) = readings.load_average {
+        metadata.insert("load_average".to_string(), serde_json::json!(load));
+    }
+    if let Some(fds) = readings.open_fds {
+        metadata.insert("open_fds".to_string(), serde_json::json!(fds));
+    }
+
+    let (status, message) = match readings.memory_usage {
+        Some(usage) => {
+            let status = if usage > thresholds.mem_unhealthy {
+                HealthStatus::Unhealthy
+            } else if usage > thresholds.mem_degraded {
+                HealthStatus::Degraded
+            } else {
+                HealthStatus::Healthy
+            };
+            (status, format!("Memory usage: {:.1}%", usage * 100.0))
+        }
+        None => (HealthStatus::Healthy, "Memory usage unavailable on this platform".to_string()),
+    };
+
+    ComponentHealth {
+        name: "system".to_string(),
+        status,
+        message: Some(message),
+        latency_ms,
+        last_check: Utc::now(),
+        metadata,
+    }
+}
+
+/// Components checked by `health_check_worker`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthComponent {
+    Database,
+    Cache,
+    Redis,
+    System,
+    Crunchyroll,
+}
+
+impl HealthComponent {
+    pub const ALL: [HealthComponent; 5] = [
+        HealthComponent::Database,
+        HealthComponent::Cache,
+        HealthComponent::Redis,
+        HealthComponent::System,
+        HealthComponent::Crunchyroll,
+    ];
+}
+
+/// How often `health_check_worker` runs each component check. The Crunchyroll
+/// probe calls a third party, so it runs far less often than the local checks.
+#[derive(Debug, Clone)]
+pub struct HealthCheckIntervals {
+    /// Database, cache and system checks
+    pub default: Duration,
+    pub redis: Duration,
+    pub crunchyroll: Duration,
+}
+
+impl Default for HealthCheckIntervals {
+    fn default() -> Self {
+        HealthCheckIntervals {
+            default: Duration::from_secs(30),
+            redis: Duration::from_secs(30),
+            crunchyroll: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+impl HealthCheckIntervals {
+    /// Reads HEALTH_CHECK_INTERVAL_SECS, HEALTH_CHECK_REDIS_INTERVAL_SECS and
+    /// HEALTH_CHECK_CRUNCHYROLL_INTERVAL_SECS
+    pub fn from_env() -> Self {
+        let defaults = HealthCheckIntervals::default();
+        let env_or = |name: &str, default: Duration| {
+            std::env::var(name)
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .filter(|secs| *secs > 0)
+                .map(Duration::from_secs)
+                .unwrap_or(default)
+        };
+
+        HealthCheckIntervals {
+            default: env_or("HEALTH_CHECK_INTERVAL_SECS", defaults.default),
+            redis: env_or("HEALTH_CHECK_REDIS_INTERVAL_SECS", defaults.redis),
+            crunchyroll: env_or("HEALTH_CHECK_CRUNCHYROLL_INTERVAL_SECS", defaults.crunchyroll),
+        }
+    }
+
+    pub fn of(&self, component: HealthComponent) -> Duration {
+        match component {
+            HealthComponent::Redis => self.redis,
+            HealthComponent::Crunchyroll => self.crunchyroll,
+            HealthComponent::Database | HealthComponent::Cache | HealthComponent::System => self.default,
+        }
+    }
 }
 
-/// Background task to periodically update health checks
+/// Background task to periodically update health checks, each component on
+/// its own interval
 pub async fn health_check_worker(
     health_service: Arc<HealthService>,
     app_state: crate::db::connection::AppState,
+    intervals: HealthCheckIntervals,
 ) {
-    let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
-    
-    loop {
-        interval.tick().await;
-        
-        // Check all components
-        let checks = vec![
-            health_service.check_database(&app_state.db).await,
-            health_service.check_system().await,
-            // Add Redis check if available
-            // Add Crunchyroll check if available
-        ];
+    let loops = HealthComponent::ALL.map(|component| {
+        let health_service = health_service.clone();
+        let app_state = app_state.clone();
+        let mut interval = tokio::time::interval(intervals.of(component));
+        // A slow check should not be followed by a burst of catch-up checks
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
         
-        // Update health status for each component
-        for check in checks {
+        async move {
+            loop {
+                interval.tick().await;
+                if let Some(check) = check_component(&health_service, &app_state, component).await {
+                    health_service.update_component_health(check).await;
+                }
+            }
+        }
+    });
+    
+    futures::future::join_all(loops).await;
+}
+
+/// Run every component check once and record the results
+pub async fn refresh_component_health(
+    health_service: &HealthService,
+    app_state: &crate::db::connection::AppState,
+) {
+    for component in HealthComponent::ALL {
+        if let Some(check) = check_component(health_service, app_state, component).await {
             health_service.update_component_health(check).await;
         }
     }
 }
 
+/// Result of one check; None for Redis when the cache does not run on it
+/// (CACHE_URL=memory:// has no server to ping)
+async fn check_component(
+    health_service: &HealthService,
+    app_state: &crate::db::connection::AppState,
+    component: HealthComponent,
+) -> Option<ComponentHealth> {
+    match component {
+        HealthComponent::Database => Some(health_service.check_database(&app_state.db).await),
+        HealthComponent::Cache => {
+            let mut cache = app_state.cache.lock().await;
+            Some(health_service.check_cache(&mut cache).await)
+        }
+        HealthComponent::Redis => {
+            let mut redis = app_state.cache.lock().await.redis_connection()?;
+            Some(health_service.check_redis(&mut redis).await)
+        }
+        HealthComponent::System => Some(health_service.check_system().await),
+        HealthComponent::Crunchyroll => Some(health_service.check_crunchyroll(&app_state.streaming).await),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -301,6 +575,25 @@ mod tests {
         assert!(response.alive);
     }
 
+    #[tokio::test]
+    async fn test_cache_check_reports_response_hits_and_misses() {
+        use crate::services::cache::MemoryCache;
+        use crate::services::CacheService;
+
+        let service = HealthService::new("1.0.0".to_string());
+        let mut cache = CacheService::with_backend(Arc::new(MemoryCache::new()));
+        let key = CacheService::response_key("search", "/api/search?q=a|");
+
+        assert!(cache.get_response::<String>(&key).await.unwrap().is_none());
+        cache.store_response(&key, &"{}".to_string(), std::time::Duration::from_secs(60)).await.unwrap();
+        assert!(cache.get_response::<String>(&key).await.unwrap().is_some());
+
+        let health = service.check_cache(&mut cache).await;
+        assert_eq!(health.status, HealthStatus::Healthy);
+        assert_eq!(health.metadata["response_hits"], 1);
+        assert_eq!(health.metadata["response_misses"], 1);
+    }
+
     #[tokio::test]
     async fn test_readiness_reflects_component_health() {
         let service = HealthService::new("1.0.0".to_string());
@@ -371,4 +664,130 @@ mod tests {
         let response = service.check_health().await;
         assert_eq!(response.status, HealthStatus::Unhealthy);
     }
+
+    #[tokio::test]
+    async fn test_crunchyroll_unavailable_reports_unhealthy() {
+        use crate::services::streaming::CrunchyrollProbe;
+        use wiremock::{matchers::{header, method, path}, Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/index/v2"))
+            .and(header("authorization", "Bearer probe-token"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2) // first attempt plus one retry
+            .mount(&server)
+            .await;
+
+        let service = HealthService::new("1.0.0".to_string());
+        let probe = CrunchyrollProbe::new(&server.uri()).unwrap();
+        let health = service.check_crunchyroll_probe(&probe, Some("probe-token")).await;
+
+        assert_eq!(health.name, "crunchyroll");
+        assert_eq!(health.status, HealthStatus::Unhealthy);
+        assert!(health.message.unwrap().contains("503"));
+    }
+
+    #[tokio::test]
+    async fn test_crunchyroll_client_error_reports_degraded() {
+        use crate::services::streaming::CrunchyrollProbe;
+        use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/index/v2"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let service = HealthService::new("1.0.0".to_string());
+        let probe = CrunchyrollProbe::new(&server.uri()).unwrap();
+        let health = service.check_crunchyroll_probe(&probe, None).await;
+
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert_eq!(health.metadata["status_code"], 401);
+    }
+
+    #[tokio::test]
+    async fn test_crunchyroll_rejected_token_reports_unhealthy() {
+        use crate::services::streaming::CrunchyrollProbe;
+        use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/index/v2"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+
+        let service = HealthService::new("1.0.0".to_string());
+        let probe = CrunchyrollProbe::new(&server.uri()).unwrap();
+        let health = service.check_crunchyroll_probe(&probe, Some("expired-token")).await;
+
+        assert_eq!(health.status, HealthStatus::Unhealthy);
+        assert!(health.metadata["error"].as_str().unwrap().contains("401"));
+        assert!(health.metadata.contains_key("latency_ms"));
+    }
+
+    #[tokio::test]
+    async fn test_crunchyroll_slow_response_reports_degraded() {
+        use crate::services::streaming::CrunchyrollProbe;
+        use wiremock::{matchers::path, Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(path("/index/v2"))
+            .respond_with(ResponseTemplate::new(200).set_delay(std::time::Duration::from_millis(1200)))
+            .mount(&server)
+            .await;
+
+        let service = HealthService::new("1.0.0".to_string());
+        let probe = CrunchyrollProbe::new(&server.uri()).unwrap();
+        let health = service.check_crunchyroll_probe(&probe, Some("probe-token")).await;
+
+        assert_eq!(health.status, HealthStatus::Degraded);
+        assert!(health.message.unwrap().contains("Slow response"));
+    }
+
+    #[test]
+    fn test_system_status_follows_memory_thresholds() {
+        let thresholds = SystemThresholds { mem_degraded: 0.8, mem_unhealthy: 0.9 };
+        let status = |memory_usage: Option<f64>| {
+            let readings = SystemReadings { memory_usage, ..Default::default() };
+            system_health(&readings, &thresholds, 0).status
+        };
+
+        assert_eq!(status(Some(0.5)), HealthStatus::Healthy);
+        assert_eq!(status(Some(0.8)), HealthStatus::Healthy);
+        assert_eq!(status(Some(0.85)), HealthStatus::Degraded);
+        assert_eq!(status(Some(0.95)), HealthStatus::Unhealthy);
+        assert_eq!(status(None), HealthStatus::Healthy);
+
+        let readings = SystemReadings {
+            process_rss_bytes: Some(64 * 1024 * 1024),
+            memory_usage: Some(0.85),
+            load_average: None,
+            open_fds: Some(42),
+        };
+        let health = system_health(&readings, &thresholds, 0);
+        assert_eq!(health.metadata["process_rss_bytes"], 64 * 1024 * 1024);
+        assert_eq!(health.metadata["open_fds"], 42);
+        assert!(!health.metadata.contains_key("load_average"));
+    }
+
+    #[test]
+    fn test_proc_parsing() {
+        let meminfo = "MemTotal:        8000000 kB\nMemFree:          500000 kB\nMemAvailable:    2000000 kB\n";
+        assert_eq!(meminfo_bytes(meminfo, "MemTotal"), Some(8_000_000 * 1024));
+        assert_eq!(memory_usage(meminfo), Some(0.75));
+        assert_eq!(memory_usage("MemTotal: 8000000 kB\n"), None);
+
+        assert_eq!(meminfo_bytes("Name:\tkensho\nVmRSS:\t  20480 kB\n", "VmRSS"), Some(20480 * 1024));
+        assert_eq!(load_average("0.52 0.58 0.59 1/467 12345\n"), Some([0.52, 0.58, 0.59]));
+        assert_eq!(load_average(""), None);
+    }
+
+    #[test]
+    fn test_crunchyroll_checked_less_often_than_redis() {
+        let intervals = HealthCheckIntervals::default();
+        assert_eq!(intervals.of(HealthComponent::Redis), Duration::from_secs(30));
+        assert_eq!(intervals.of(HealthComponent::Crunchyroll), Duration::from_secs(300));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,122 @@
+// Outgoing email: the verification link sent on registration
+// MAILER picks the delivery backend; there is no SMTP transport yet
+
+use anyhow::{bail, Result};
+use std::sync::{Arc, Mutex};
+
+/// Where verification links point when EMAIL_VERIFY_URL is unset
+pub const DEFAULT_VERIFY_URL: &str = "http://localhost:3000/api/auth/verify";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Email {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl Email {
+    /// The message carrying `token` to `to`, as a link to `verify_url`
+    pub fn verification(to: &str, verify_url: &str, token: &str) -> Self {
+        Email {
+            to: to.to_string(),
+            subject: "Verify your Kensho email address".to_string(),
+            body: format!(
+                "Follow this link within 24 hours to verify your email address:\n\n{}?token={}\n",
+                verify_url, token
+            ),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, email: Email) -> Result<()>;
+}
+
+/// Writes each message to the log instead of delivering it. For development:
+/// the verification link appears in the server output.
+pub struct LogMailer;
+
+#[async_trait::async_trait]
+impl Mailer for LogMailer {
+    async fn send(&self, email: Email) -> Result<()> {
+        tracing::info!(to = %email.to, subject = %email.subject, body = %email.body, "Email not delivered (MAILER=log)");
+        Ok(())
+    }
+}
+
+/// Keeps every message in memory, for tests to read back
+#[derive(Default)]
+pub struct MemoryMailer {
+    outbox: Mutex<Vec<Email>>,
+}
+
+impl MemoryMailer {
+    /// Messages sent so far, oldest first
+    pub fn sent(&self) -> Vec<Email> {
+        self.outbox.lock().unwrap().clone()
+    }
+
+    /// Messages sent to `to`, oldest first
+    pub fn sent_to(&self, to: &str) -> Vec<Email> {
+        self.sent().into_iter().filter(|email| email.to == to).collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl Mailer for MemoryMailer {
+    async fn send(&self, email: Email) -> Result<()> {
+        self.outbox.lock().unwrap().push(email);
+        Ok(())
+    }
+}
+
+/// Mail delivery and the link verification emails point at
+#[derive(Clone)]
+pub struct MailerConfig {
+    pub mailer: Arc<dyn Mailer>,
+    pub verify_url: String,
+}
+
+impl MailerConfig {
+    /// Reads MAILER (log, the default, or memory) and EMAIL_VERIFY_URL
+    pub fn from_env() -> Result<Self> {
+        let mailer: Arc<dyn Mailer> = match std::env::var("MAILER").unwrap_or_default().to_lowercase().as_str() {
+            "" | "log" => Arc::new(LogMailer),
+            "memory" => Arc::new(MemoryMailer::default()),
+            other => bail!("Unknown MAILER '{}': expected log or memory", other),
+        };
+        let verify_url = std::env::var("EMAIL_VERIFY_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| DEFAULT_VERIFY_URL.to_string());
+
+        Ok(MailerConfig { mailer, verify_url })
+    }
+
+    /// Send `token` to `to` as a verification link
+    pub async fn send_verification(&self, to: &str, token: &str) -> Result<()> {
+        self.mailer.send(Email::verification(to, &self.verify_url, token)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_verification_email_links_to_the_verify_url() {
+        let outbox = Arc::new(MemoryMailer::default());
+        let config = MailerConfig {
+            mailer: outbox.clone(),
+            verify_url: "https://kensho.example/api/auth/verify".to_string(),
+        };
+
+        config.send_verification("fan@example.com", "abc123").await.unwrap();
+
+        let sent = outbox.sent_to("fan@example.com");
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].body.contains("https://kensho.example/api/auth/verify?token=abc123"));
+        assert!(outbox.sent_to("other@example.com").is_empty());
+    }
+}
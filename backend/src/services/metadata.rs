@@ -3,10 +3,9 @@
 // Aggregates from anime-offline-database and IMDb
 
 use anyhow::{Result, Context};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use std::collections::HashMap;
 use uuid::Uuid;
-use chrono::NaiveDate;
 use crate::models::{Anime, AnimeStatus, AnimeType, AnimeSeason, Season, ImdbData, Tag, TagCategory, Episode};
 
 // anime-offline-database format
@@ -108,6 +107,7 @@ impl MetadataService {
             "FINISHED" => AnimeStatus::Finished,
             "ONGOING" => AnimeStatus::Ongoing,
             "UPCOMING" => AnimeStatus::Upcoming,
+            "CANCELLED" => AnimeStatus::Cancelled,
             _ => AnimeStatus::Unknown,
         };
         
@@ -130,8 +130,13 @@ impl MetadataService {
                 id: data.id.clone(),
                 rating: data.rating,
                 votes: data.votes,
+                last_updated: chrono::Utc::now(),
             });
         
+        let posters = Anime::collect_posters(
+            std::iter::once(entry.picture.clone()).chain(entry.thumbnail.clone())
+        );
+        
         Ok(Anime {
             id: Uuid::new_v4(),
             title: entry.title,
@@ -143,6 +148,10 @@ impl MetadataService {
             anime_season,
             synopsis: String::new(), // To be enriched from other sources
             poster_url: entry.picture,
+            posters,
+            stored_episode_count: 0,
+            popularity: 0.0,
+            romaji_titles: Vec::new(),
             imdb,
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
@@ -168,12 +177,7 @@ impl MetadataService {
     }
     
     fn categorize_tag(&self, tag_name: &str) -> TagCategory {
-        match tag_name.to_lowercase().as_str() {
-            "action" | "comedy" | "drama" | "romance" | "horror" | "thriller" | "mystery" => TagCategory::Genre,
-            "school" | "military" | "supernatural" | "historical" | "space" => TagCategory::Theme,
-            "shounen" | "seinen" | "josei" | "shoujo" => TagCategory::Demographic,
-            _ => TagCategory::Content,
-        }
+        categorize_tag(tag_name)
     }
     
     pub async fn generate_episodes(&self, anime_id: Uuid, episode_count: u32) -> Vec<Episode> {
@@ -225,6 +229,17 @@ pub struct IngestResult {
     pub episodes: Vec<Episode>,
 }
 
+/// Category of an anime-offline-database tag; tags outside the known genre,
+/// theme and demographic names count as content tags
+pub fn categorize_tag(tag_name: &str) -> TagCategory {
+    match tag_name.to_lowercase().as_str() {
+        "action" | "comedy" | "drama" | "romance" | "horror" | "thriller" | "mystery" => TagCategory::Genre,
+        "school" | "military" | "supernatural" | "historical" | "space" => TagCategory::Theme,
+        "shounen" | "seinen" | "josei" | "shoujo" => TagCategory::Demographic,
+        _ => TagCategory::Content,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -9,6 +9,13 @@ pub mod search;
 pub mod health;
 pub mod resilient;
 pub mod data_loader;
+pub mod recommendations;
+pub mod watch_progress;
+pub mod watch_heartbeat;
+pub mod stream_sessions;
+pub mod popularity;
+pub mod transliteration;
+pub mod mailer;
 // pub mod crunchyroll_wrapper; // No longer needed - using crunchyroll-rs directly
 
 pub use metadata::MetadataService;
@@ -16,6 +23,10 @@ pub use auth::AuthService;
 pub use streaming::StreamingService;
 pub use database_v2::DatabaseService; // Use fixed v2 implementation
 pub use cache::CacheService;
-pub use search::SearchService;
+pub use search::{SearchService, SearchConfig, SortOrder, SeasonFilter, BrowseSort, ListSortField, ListSortOrder};
 pub use health::HealthService;
-pub use resilient::{ResilientClient, ResilientHttpClient, ResilienceConfig, ResilienceManager};
\ No newline at end of file
+pub use recommendations::{RecommendationService, RecommendationConfig};
+pub use watch_progress::WatchProgressService;
+pub use watch_heartbeat::WatchHeartbeatService;
+pub use stream_sessions::StreamSessionService;
+pub use resilient::{retry_with_backoff, JitterStrategy, ResilientClient, ResilientHttpClient, ResilienceConfig, ResilienceManager};
\ No newline at end of file
@@ -0,0 +1,169 @@
+// All-time popularity: a per-anime score bumped by user activity as it happens
+// and decayed continuously (half-life of a month) so old hits fade out.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use std::time::Duration;
+use crate::services::DatabaseService;
+
+/// Points for the first progress report on an episode
+pub const WATCH_WEIGHT: f64 = 1.0;
+/// Points for putting an anime on a watchlist
+pub const WATCHLIST_ADD_WEIGHT: f64 = 3.0;
+/// Points per rating star, so a 5.0 counts five times a 1.0
+pub const RATING_WEIGHT_PER_POINT: f64 = 1.0;
+
+/// Scores halve every this many days
+pub const HALF_LIFE_DAYS: f64 = 30.0;
+
+const DECAY_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// User activity that counts towards an anime's popularity
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PopularityEvent {
+    Watch,
+    WatchlistAdd,
+    Rating(f32),
+}
+
+impl PopularityEvent {
+    pub fn weight(&self) -> f64 {
+        match self {
+            PopularityEvent::Watch => WATCH_WEIGHT,
+            PopularityEvent::WatchlistAdd => WATCHLIST_ADD_WEIGHT,
+            PopularityEvent::Rating(rating) => rating.max(0.0) as f64 * RATING_WEIGHT_PER_POINT,
+        }
+    }
+}
+
+/// Multiplier for scores after `elapsed`: 0.5 after one half-life, 0.25 after two
+pub fn decay_factor(elapsed: chrono::Duration) -> f64 {
+    let days = elapsed.num_seconds().max(0) as f64 / 86_400.0;
+    0.5_f64.powf(days / HALF_LIFE_DAYS)
+}
+
+/// Applies the decay for whatever time passed since the previous run, so a
+/// late or skipped run catches up instead of losing the decay. The last run is
+/// kept in the database: restarts resume from it, and when several instances
+/// run the worker only one of them applies each decay.
+pub struct PopularityDecay {
+    last_run: DateTime<Utc>,
+}
+
+impl PopularityDecay {
+    /// Resume from the last run recorded in the database
+    pub async fn load(db: &DatabaseService, now: DateTime<Utc>) -> Result<Self> {
+        Ok(PopularityDecay { last_run: db.popularity_decayed_at(now).await? })
+    }
+
+    /// Decay every score up to `now`; returns the factor applied, 1.0 when
+    /// another instance decayed them first
+    pub async fn run(&mut self, db: &DatabaseService, now: DateTime<Utc>) -> Result<f64> {
+        let factor = decay_factor(now - self.last_run);
+        if factor >= 1.0 {
+            return Ok(1.0);
+        }
+        if db.decay_popularity(self.last_run, now, factor).await? {
+            self.last_run = now;
+            Ok(factor)
+        } else {
+            self.last_run = db.popularity_decayed_at(now).await?;
+            Ok(1.0)
+        }
+    }
+}
+
+/// Background task that decays popularity scores once a day
+pub async fn popularity_decay_worker(db: Arc<DatabaseService>) {
+    let mut decay = None;
+    // The first tick fires immediately, catching up on time spent down
+    let mut interval = tokio::time::interval(DECAY_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let decay = match decay.as_mut() {
+            Some(decay) => decay,
+            None => match PopularityDecay::load(&db, Utc::now()).await {
+                Ok(loaded) => decay.insert(loaded),
+                Err(e) => {
+                    tracing::warn!("Failed to read the popularity decay clock: {}", e);
+                    continue;
+                }
+            },
+        };
+
+        match decay.run(&db, Utc::now()).await {
+            Ok(factor) => tracing::debug!("Decayed popularity scores by {:.4}", factor),
+            Err(e) => tracing::warn!("Failed to decay popularity scores: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_weights() {
+        assert_eq!(PopularityEvent::Watch.weight(), 1.0);
+        assert_eq!(PopularityEvent::WatchlistAdd.weight(), 3.0);
+        assert_eq!(PopularityEvent::Rating(4.5).weight(), 4.5);
+        assert_eq!(PopularityEvent::Rating(-1.0).weight(), 0.0);
+
+        // Two watches and a watchlist add on one title, a 5-star rating on another
+        let score = |events: &[PopularityEvent]| events.iter().map(PopularityEvent::weight).sum::<f64>();
+        assert_eq!(
+            score(&[PopularityEvent::Watch, PopularityEvent::Watch, PopularityEvent::WatchlistAdd]),
+            5.0
+        );
+        assert_eq!(score(&[PopularityEvent::Rating(5.0)]), 5.0);
+    }
+
+    #[test]
+    fn test_decay_halves_monthly() {
+        assert_eq!(decay_factor(chrono::Duration::zero()), 1.0);
+        assert!((decay_factor(chrono::Duration::days(30)) - 0.5).abs() < 1e-9);
+        assert!((decay_factor(chrono::Duration::days(60)) - 0.25).abs() < 1e-9);
+        // Thirty daily runs compound to the same monthly halving
+        let daily = decay_factor(chrono::Duration::days(1));
+        assert!((daily.powi(30) - 0.5).abs() < 1e-9);
+        // A clock stepping backwards never inflates scores
+        assert_eq!(decay_factor(chrono::Duration::days(-3)), 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_decay_run_catches_up_on_elapsed_time() {
+        let db = DatabaseService::new("memory://").await.unwrap();
+        db.initialize_schema().await.unwrap();
+        let start = Utc::now();
+        let mut decay = PopularityDecay::load(&db, start).await.unwrap();
+
+        let factor = decay.run(&db, start + chrono::Duration::days(30)).await.unwrap();
+        assert!((factor - 0.5).abs() < 1e-9);
+
+        // Running again at the same instant changes nothing
+        let factor = decay.run(&db, start + chrono::Duration::days(30)).await.unwrap();
+        assert_eq!(factor, 1.0);
+    }
+
+    #[tokio::test]
+    async fn test_decay_is_shared_across_instances_and_restarts() {
+        let db = DatabaseService::new("memory://").await.unwrap();
+        db.initialize_schema().await.unwrap();
+        let start = Utc::now();
+        let mut first = PopularityDecay::load(&db, start).await.unwrap();
+        let mut second = PopularityDecay::load(&db, start + chrono::Duration::hours(1)).await.unwrap();
+
+        let day = start + chrono::Duration::days(1);
+        assert!(first.run(&db, day).await.unwrap() < 1.0);
+        // The second instance finds the day already decayed and adopts its clock
+        assert_eq!(second.run(&db, day).await.unwrap(), 1.0);
+        assert_eq!(second.last_run, day);
+
+        // A restart a month later catches up on the whole month
+        let restarted = PopularityDecay::load(&db, day + chrono::Duration::days(30)).await.unwrap();
+        assert_eq!(restarted.last_run, day);
+    }
+}
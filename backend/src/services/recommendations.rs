@@ -0,0 +1,500 @@
+// Recommendation service with a configurable fallback chain
+// Graph-based recommendations are empty for users without history, so each
+// request walks the chain until a source produces results.
+
+use anyhow::{Result, bail};
+use chrono::Datelike;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+use crate::models::{AnimeSummary, RecommendedAnime, Season, SimilarAnime};
+use crate::services::DatabaseService;
+
+/// Window used to rank trending anime by recent watch activity
+const TRENDING_WINDOW_DAYS: u32 = 7;
+
+/// Closest matches kept per anime when rebuilding tag similarities
+pub const SIMILAR_PER_ANIME: usize = 20;
+
+const SIMILARITY_REBUILD_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecommendationSource {
+    /// Personalized results from the user's watch/like graph
+    Graph,
+    /// Most watched titles over the trending window
+    Trending,
+    /// Best rated titles airing this season
+    TopRated,
+    /// Most recently added titles
+    Recent,
+}
+
+impl RecommendationSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecommendationSource::Graph => "graph",
+            RecommendationSource::Trending => "trending",
+            RecommendationSource::TopRated => "top_rated",
+            RecommendationSource::Recent => "recent",
+        }
+    }
+}
+
+impl FromStr for RecommendationSource {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "graph" => Ok(RecommendationSource::Graph),
+            "trending" => Ok(RecommendationSource::Trending),
+            "top_rated" => Ok(RecommendationSource::TopRated),
+            "recent" => Ok(RecommendationSource::Recent),
+            other => bail!("Unknown recommendation source: {}", other),
+        }
+    }
+}
+
+/// Recommendation configuration
+#[derive(Clone, Debug)]
+pub struct RecommendationConfig {
+    /// Sources tried in order until one returns results
+    pub chain: Vec<RecommendationSource>,
+}
+
+impl Default for RecommendationConfig {
+    fn default() -> Self {
+        RecommendationConfig {
+            chain: vec![
+                RecommendationSource::Graph,
+                RecommendationSource::Trending,
+                RecommendationSource::TopRated,
+                RecommendationSource::Recent,
+            ],
+        }
+    }
+}
+
+impl RecommendationConfig {
+    /// Reads RECOMMENDATION_FALLBACK_CHAIN, e.g. "graph,trending,top_rated,recent"
+    pub fn from_env() -> Self {
+        let chain = std::env::var("RECOMMENDATION_FALLBACK_CHAIN")
+            .ok()
+            .and_then(|s| match Self::parse_chain(&s) {
+                Ok(chain) => Some(chain),
+                Err(e) => {
+                    tracing::warn!("Ignoring RECOMMENDATION_FALLBACK_CHAIN: {}", e);
+                    None
+                }
+            });
+
+        match chain {
+            Some(chain) => RecommendationConfig { chain },
+            None => RecommendationConfig::default(),
+        }
+    }
+
+    pub fn parse_chain(s: &str) -> Result<Vec<RecommendationSource>> {
+        let chain = s
+            .split(',')
+            .filter(|part| !part.trim().is_empty())
+            .map(RecommendationSource::from_str)
+            .collect::<Result<Vec<_>>>()?;
+
+        if chain.is_empty() {
+            bail!("Fallback chain must contain at least one source");
+        }
+
+        Ok(chain)
+    }
+}
+
+#[derive(Debug)]
+pub struct Recommendations {
+    /// Source that produced the results, or None if every source was empty
+    pub source: Option<RecommendationSource>,
+    pub items: Vec<AnimeSummary>,
+}
+
+impl Recommendations {
+    /// True when the results did not come from the first source in the chain
+    pub fn is_fallback(&self, chain: &[RecommendationSource]) -> bool {
+        self.source.is_some() && self.source != chain.first().copied()
+    }
+}
+
+/// Walk `chain` in order and return the first non-empty result.
+/// A failing source is logged and skipped rather than aborting the chain.
+pub async fn resolve_chain<F, Fut>(chain: &[RecommendationSource], mut fetch: F) -> Recommendations
+where
+    F: FnMut(RecommendationSource) -> Fut,
+    Fut: Future<Output = Result<Vec<AnimeSummary>>>,
+{
+    for &source in chain {
+        match fetch(source).await {
+            Ok(items) if !items.is_empty() => {
+                return Recommendations { source: Some(source), items };
+            }
+            Ok(_) => tracing::debug!("Recommendation source {} was empty", source.as_str()),
+            Err(e) => tracing::warn!("Recommendation source {} failed: {}", source.as_str(), e),
+        }
+    }
+
+    Recommendations { source: None, items: Vec::new() }
+}
+
+/// Rank candidate anime by how many distinct tag names they share with the
+/// user's seed anime (liked or watched), strongest overlap first. Each result
+/// carries a reason naming the seed it overlaps most, e.g.
+/// "Shares 3 tags with Demon Slayer". Candidates sharing nothing are dropped.
+pub fn rank_by_shared_tags(
+    seeds: &[(String, Vec<String>)],
+    candidates: Vec<(Uuid, Vec<String>)>,
+    limit: usize,
+) -> Vec<(Uuid, String)> {
+    let normalize = |tags: &[String]| -> HashSet<String> {
+        tags.iter().map(|tag| tag.trim().to_lowercase()).collect()
+    };
+    let seeds: Vec<(&str, HashSet<String>)> = seeds
+        .iter()
+        .map(|(title, tags)| (title.as_str(), normalize(tags)))
+        .collect();
+    let seed_tags: HashSet<&String> = seeds.iter().flat_map(|(_, tags)| tags).collect();
+
+    let mut ranked: Vec<(usize, usize, Uuid, String)> = candidates
+        .into_iter()
+        .filter_map(|(id, tags)| {
+            let tags = normalize(&tags);
+            let shared = tags.iter().filter(|tag| seed_tags.contains(tag)).count();
+            let (title, best) = seeds
+                .iter()
+                .map(|(title, seed)| (*title, seed.intersection(&tags).count()))
+                .max_by_key(|(_, count)| *count)?;
+            if shared == 0 || best == 0 {
+                return None;
+            }
+
+            let noun = if best == 1 { "tag" } else { "tags" };
+            Some((shared, best, id, format!("Shares {} {} with {}", best, noun, title)))
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+    ranked.into_iter().take(limit).map(|(_, _, id, reason)| (id, reason)).collect()
+}
+
+/// Content similarity from tags. Each anime is a TF-IDF vector over its tag
+/// names: a tag appears at most once per title, so the weight is just the tag's
+/// smoothed inverse document frequency, and rare tags count for more than ones
+/// nearly every title has. Pairs are scored by cosine similarity and each anime
+/// keeps its `per_anime` closest matches, best first. Anime without tags, or
+/// sharing none, get an empty list.
+pub fn tag_similarity_matrix(anime: &[(Uuid, Vec<String>)], per_anime: usize) -> Vec<(Uuid, Vec<(Uuid, f32)>)> {
+    let documents: Vec<HashSet<String>> = anime
+        .iter()
+        .map(|(_, tags)| tags.iter().map(|tag| tag.trim().to_lowercase()).collect())
+        .collect();
+
+    // Inverted index: tag -> titles carrying it
+    let mut postings: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, tags) in documents.iter().enumerate() {
+        for tag in tags {
+            postings.entry(tag.as_str()).or_default().push(index);
+        }
+    }
+
+    let total = documents.len() as f64;
+    let idf: HashMap<&str, f64> = postings
+        .iter()
+        .map(|(tag, titles)| (*tag, ((1.0 + total) / (1.0 + titles.len() as f64)).ln() + 1.0))
+        .collect();
+    let norms: Vec<f64> = documents
+        .iter()
+        .map(|tags| tags.iter().map(|tag| idf[tag.as_str()].powi(2)).sum::<f64>().sqrt())
+        .collect();
+
+    anime
+        .iter()
+        .enumerate()
+        .map(|(index, (id, _))| {
+            let mut dot: HashMap<usize, f64> = HashMap::new();
+            for tag in &documents[index] {
+                let weight = idf[tag.as_str()].powi(2);
+                for &other in &postings[tag.as_str()] {
+                    if other != index {
+                        *dot.entry(other).or_default() += weight;
+                    }
+                }
+            }
+
+            let mut similar: Vec<(Uuid, f32)> = dot
+                .into_iter()
+                .map(|(other, dot)| (anime[other].0, (dot / (norms[index] * norms[other])) as f32))
+                .collect();
+            similar.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+            similar.truncate(per_anime);
+
+            (*id, similar)
+        })
+        .collect()
+}
+
+/// Outcome of a similarity rebuild
+#[derive(Debug, Clone, Serialize)]
+pub struct RebuildReport {
+    pub anime: usize,
+    pub edges: usize,
+}
+
+pub struct RecommendationService {
+    db: Arc<DatabaseService>,
+    config: RecommendationConfig,
+    // Serializes the hourly rebuild with ones triggered by admins
+    rebuild_lock: tokio::sync::Mutex<()>,
+}
+
+impl RecommendationService {
+    pub fn new(db: Arc<DatabaseService>, config: RecommendationConfig) -> Self {
+        RecommendationService {
+            db,
+            config,
+            rebuild_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    pub fn config(&self) -> &RecommendationConfig {
+        &self.config
+    }
+
+    /// Recommendations for a user; `user_id` is None for accounts without graph identity
+    pub async fn recommend_for_user(&self, user_id: Option<Uuid>, limit: usize) -> Recommendations {
+        resolve_chain(&self.config.chain, |source| self.fetch(source, user_id, limit)).await
+    }
+
+    /// "Because you watched" picks explained by shared tags; users without
+    /// watch or like history get this season's top rated anime instead
+    pub async fn because_you_watched(&self, user_id: &str, limit: usize) -> Result<Vec<RecommendedAnime>> {
+        let recommendations = self.db.get_recommendations_for_user(user_id, limit).await?;
+        if !recommendations.is_empty() {
+            return Ok(recommendations);
+        }
+
+        let now = chrono::Utc::now();
+        let season = Season::from_month(now.month());
+        let top_rated = self.db.get_top_rated_seasonal(now.year() as u16, season.as_str(), limit).await?;
+
+        Ok(top_rated
+            .into_iter()
+            .map(|anime| RecommendedAnime {
+                anime,
+                reason: "Top rated this season".to_string(),
+            })
+            .collect())
+    }
+
+    /// Anime most like `anime_id` by tag content, as of the last rebuild
+    pub async fn similar_to(&self, anime_id: Uuid, limit: usize) -> Result<Vec<SimilarAnime>> {
+        self.db.get_tag_similar_anime(anime_id, limit).await
+    }
+
+    /// Recompute tag similarity across the whole catalog and rewrite the
+    /// `is_similar` edges it owns
+    pub async fn rebuild_similarities(&self) -> Result<RebuildReport> {
+        let _guard = self.rebuild_lock.lock().await;
+
+        let anime = self.db.get_anime_tag_names().await?;
+        let count = anime.len();
+        let matrix = tag_similarity_matrix(&anime, SIMILAR_PER_ANIME);
+        let edges = self.db.replace_tag_similarities(matrix).await?;
+
+        Ok(RebuildReport { anime: count, edges })
+    }
+
+    async fn fetch(&self, source: RecommendationSource, user_id: Option<Uuid>, limit: usize) -> Result<Vec<AnimeSummary>> {
+        match source {
+            RecommendationSource::Graph => match user_id {
+                Some(user_id) => Ok(self.db
+                    .get_recommendations_for_user(&user_id.to_string(), limit)
+                    .await?
+                    .into_iter()
+                    .map(|recommendation| recommendation.anime)
+                    .collect()),
+                None => Ok(Vec::new()),
+            },
+            RecommendationSource::Trending => {
+                self.db.get_trending_anime(TRENDING_WINDOW_DAYS, limit).await
+            }
+            RecommendationSource::TopRated => {
+                let now = chrono::Utc::now();
+                let season = Season::from_month(now.month());
+                self.db.get_top_rated_seasonal(now.year() as u16, season.as_str(), limit).await
+            }
+            RecommendationSource::Recent => self.db.list_anime(limit, 0).await,
+        }
+    }
+}
+
+/// Handle to the hourly similarity rebuild, held in `AppState`.
+/// Dropping the last clone of the state stops the task.
+pub struct SimilarityRebuildTask {
+    handle: JoinHandle<()>,
+}
+
+impl SimilarityRebuildTask {
+    pub fn spawn(service: Arc<RecommendationService>) -> Self {
+        SimilarityRebuildTask {
+            handle: tokio::spawn(similarity_rebuild_worker(service)),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.handle.is_finished()
+    }
+}
+
+impl Drop for SimilarityRebuildTask {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn similarity_rebuild_worker(service: Arc<RecommendationService>) {
+    let mut interval = tokio::time::interval(SIMILARITY_REBUILD_INTERVAL);
+    // Edges from the previous run are still stored, so skip the immediate first tick
+    interval.tick().await;
+
+    loop {
+        interval.tick().await;
+
+        match service.rebuild_similarities().await {
+            Ok(report) => tracing::info!(
+                "Rebuilt tag similarities for {} anime ({} edges)",
+                report.anime,
+                report.edges
+            ),
+            Err(e) => tracing::warn!("Failed to rebuild tag similarities: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AnimeStatus, AnimeType};
+
+    fn summary(title: &str) -> AnimeSummary {
+        AnimeSummary {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            poster_url: String::new(),
+            episodes: 12,
+            status: AnimeStatus::Finished,
+            anime_type: AnimeType::TV,
+            imdb_rating: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_new_user_falls_back_to_non_empty_source() {
+        let chain = RecommendationConfig::default().chain;
+
+        // A brand-new user: no graph history, nothing trending or rated this season
+        let result = resolve_chain(&chain, |source| async move {
+            match source {
+                RecommendationSource::Recent => Ok(vec![summary("Fresh Release")]),
+                _ => Ok(Vec::new()),
+            }
+        }).await;
+
+        assert_eq!(result.source, Some(RecommendationSource::Recent));
+        assert!(!result.items.is_empty());
+        assert!(result.is_fallback(&chain));
+    }
+
+    #[tokio::test]
+    async fn test_chain_stops_at_first_non_empty_source() {
+        let chain = RecommendationConfig::default().chain;
+        let mut called = Vec::new();
+
+        let result = resolve_chain(&chain, |source| {
+            called.push(source);
+            async move {
+                match source {
+                    RecommendationSource::Graph => bail!("graph unavailable"),
+                    _ => Ok(vec![summary(source.as_str())]),
+                }
+            }
+        }).await;
+
+        assert_eq!(result.source, Some(RecommendationSource::Trending));
+        assert_eq!(called, vec![RecommendationSource::Graph, RecommendationSource::Trending]);
+    }
+
+    #[test]
+    fn test_rank_by_shared_tags() {
+        let tags = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        let seeds = vec![
+            ("Demon Slayer".to_string(), tags(&["Action", "Shounen", "Supernatural"])),
+            ("Frieren".to_string(), tags(&["Fantasy", "Drama"])),
+        ];
+        let close = Uuid::new_v4();
+        let loose = Uuid::new_v4();
+        let unrelated = Uuid::new_v4();
+
+        let ranked = rank_by_shared_tags(&seeds, vec![
+            (loose, tags(&["drama", "Romance"])),
+            (unrelated, tags(&["Sports"])),
+            (close, tags(&["Action", "Shounen", "Supernatural", "Fantasy"])),
+        ], 10);
+
+        assert_eq!(ranked, vec![
+            (close, "Shares 3 tags with Demon Slayer".to_string()),
+            (loose, "Shares 1 tag with Frieren".to_string()),
+        ]);
+        assert_eq!(rank_by_shared_tags(&seeds, vec![(close, tags(&["Action"]))], 0), vec![]);
+        assert!(rank_by_shared_tags(&[], vec![(close, tags(&["Action"]))], 10).is_empty());
+    }
+
+    #[test]
+    fn test_tag_similarity_prefers_rare_shared_tags() {
+        let tags = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        let mecha = Uuid::new_v4();
+        let mecha_too = Uuid::new_v4();
+        let action = Uuid::new_v4();
+        let untagged = Uuid::new_v4();
+
+        let matrix = tag_similarity_matrix(&[
+            (mecha, tags(&["Action", "Mecha"])),
+            (mecha_too, tags(&["action", "Mecha", "Space"])),
+            (action, tags(&["Action", "Comedy"])),
+            (untagged, vec![]),
+        ], 10);
+        let similar: HashMap<Uuid, Vec<(Uuid, f32)>> = matrix.into_iter().collect();
+
+        // Sharing the rarer Mecha tag beats sharing Action, which most titles have
+        let ranked: Vec<Uuid> = similar[&mecha].iter().map(|(id, _)| *id).collect();
+        assert_eq!(ranked, vec![mecha_too, action]);
+        assert!(similar[&mecha].iter().all(|(_, score)| *score > 0.0 && *score <= 1.0));
+
+        // Cosine similarity is symmetric
+        let score = |from: Uuid, to: Uuid| similar[&from].iter().find(|(id, _)| *id == to).unwrap().1;
+        assert!((score(mecha, action) - score(action, mecha)).abs() < 1e-6);
+
+        assert!(similar[&untagged].is_empty());
+        assert_eq!(tag_similarity_matrix(&[(mecha, tags(&["Mecha"])), (mecha_too, tags(&["Mecha"]))], 0)[0].1, vec![]);
+    }
+
+    #[test]
+    fn test_parse_chain() {
+        let chain = RecommendationConfig::parse_chain("top_rated, recent").unwrap();
+        assert_eq!(chain, vec![RecommendationSource::TopRated, RecommendationSource::Recent]);
+
+        assert!(RecommendationConfig::parse_chain("graph,popular").is_err());
+        assert!(RecommendationConfig::parse_chain("").is_err());
+    }
+}
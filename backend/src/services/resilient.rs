@@ -9,8 +9,43 @@ use tokio::sync::{Mutex, RwLock};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use async_trait::async_trait;
+use ring::rand::{SecureRandom, SystemRandom};
 use std::future::Future;
 
+/// How the exponential backoff delay is randomized before each retry, so that
+/// clients failing together do not retry in lockstep
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JitterStrategy {
+    /// Sleep exactly the computed delay
+    None,
+    /// Sleep a uniformly random time in `[0, delay]`
+    #[default]
+    Full,
+    /// Sleep half the delay plus a random time in `[0, delay / 2]`
+    Equal,
+}
+
+impl JitterStrategy {
+    /// The time to sleep for a computed backoff of `delay_ms`
+    pub fn apply(self, delay_ms: u64) -> u64 {
+        match self {
+            JitterStrategy::None => delay_ms,
+            JitterStrategy::Full => random_up_to(delay_ms),
+            JitterStrategy::Equal => delay_ms / 2 + random_up_to(delay_ms - delay_ms / 2),
+        }
+    }
+}
+
+/// Uniformly random value in `[0, max]`
+fn random_up_to(max: u64) -> u64 {
+    let mut bytes = [0u8; 8];
+    if SystemRandom::new().fill(&mut bytes).is_err() {
+        // Without randomness, backing off the full delay is the safe choice
+        return max;
+    }
+    u64::from_le_bytes(bytes) % max.saturating_add(1)
+}
+
 /// Configuration for resilient client behavior
 #[derive(Debug, Clone)]
 pub struct ResilienceConfig {
@@ -28,6 +63,11 @@ pub struct ResilienceConfig {
     pub recovery_timeout_secs: u64,
     /// Connection pool size (for HTTP clients)
     pub pool_size: usize,
+    /// Randomization applied to each backoff delay
+    pub jitter: JitterStrategy,
+    /// Per-host settings for `ResilientHttpClient`, keyed by host name.
+    /// Hosts without an entry use this config; overrides are not nested.
+    pub host_overrides: HashMap<String, ResilienceConfig>,
 }
 
 impl Default for ResilienceConfig {
@@ -40,18 +80,67 @@ impl Default for ResilienceConfig {
             failure_threshold: 5,
             recovery_timeout_secs: 60,
             pool_size: 10,
+            jitter: JitterStrategy::default(),
+            host_overrides: HashMap::new(),
         }
     }
 }
 
+impl ResilienceConfig {
+    /// Settings for requests to `host`: its override if one is registered
+    pub fn for_host(&self, host: &str) -> &ResilienceConfig {
+        self.host_overrides.get(host).unwrap_or(self)
+    }
+}
+
+/// Run `attempt` until it succeeds, at most `max_retries + 1` times, sleeping
+/// the jittered exponential backoff of `config` between attempts. For one-off
+/// operations such as connecting at startup; no circuit breaker or timeout applies.
+pub async fn retry_with_backoff<F, Fut, R>(config: &ResilienceConfig, name: &str, mut attempt: F) -> Result<R>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<R>>,
+{
+    let attempts = config.max_retries + 1;
+    let mut delay_ms = config.base_delay_ms;
+
+    for n in 1..=attempts {
+        tracing::info!("{} (attempt {}/{})", name, n, attempts);
+        match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(e) if n == attempts => {
+                return Err(e.context(format!("{} failed after {} attempts", name, attempts)));
+            }
+            Err(e) => {
+                tracing::warn!("{} failed (attempt {}/{}): {:#}", name, n, attempts, e);
+                tokio::time::sleep(Duration::from_millis(config.jitter.apply(delay_ms))).await;
+                delay_ms = (delay_ms * 2).min(config.max_delay_ms);
+            }
+        }
+    }
+
+    unreachable!("max_retries + 1 is at least one attempt")
+}
+
 /// Circuit breaker states
 #[derive(Debug, Clone, PartialEq)]
-enum CircuitState {
+pub enum CircuitState {
     Closed,
     Open(DateTime<Utc>),
     HalfOpen,
 }
 
+impl CircuitState {
+    /// Numeric encoding for metrics: 0 closed, 1 open, 2 half-open
+    pub fn gauge_value(&self) -> i64 {
+        match self {
+            CircuitState::Closed => 0,
+            CircuitState::Open(_) => 1,
+            CircuitState::HalfOpen => 2,
+        }
+    }
+}
+
 /// Circuit breaker for handling failures
 struct CircuitBreaker {
     state: RwLock<CircuitState>,
@@ -154,7 +243,7 @@ impl<T> ResilientClient<T> {
                     attempt + 1,
                     self.config.max_retries + 1
                 );
-                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                tokio::time::sleep(Duration::from_millis(self.config.jitter.apply(delay_ms))).await;
                 delay_ms = (delay_ms * 2).min(self.config.max_delay_ms);
             }
 
@@ -254,9 +343,21 @@ impl ResilientHttpClient {
         drop(breakers);
         let mut breakers = self.circuit_breakers.write().await;
         
-        let breaker = Arc::new(CircuitBreaker::new(self.config.clone()));
-        breakers.insert(host.to_string(), breaker.clone());
-        breaker
+        // Another request may have created it while we waited for the write lock
+        let breaker = breakers
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(CircuitBreaker::new(self.config.for_host(host).clone())));
+        breaker.clone()
+    }
+
+    /// Current breaker state for every host this client has contacted
+    pub async fn circuit_states(&self) -> Vec<(String, CircuitState)> {
+        let breakers = self.circuit_breakers.read().await;
+        let mut states = Vec::with_capacity(breakers.len());
+        for (host, breaker) in breakers.iter() {
+            states.push((host.clone(), breaker.state.read().await.clone()));
+        }
+        states
     }
 
     /// Execute an HTTP request with resilience
@@ -276,19 +377,21 @@ impl ResilientHttpClient {
             return Err(anyhow::anyhow!("Circuit breaker is open for {}", host));
         }
 
+        // Retry with the same settings the host's breaker was built from
+        let config = &breaker.config;
         let mut last_error = None;
-        let mut delay_ms = self.config.base_delay_ms;
+        let mut delay_ms = config.base_delay_ms;
 
-        for attempt in 0..=self.config.max_retries {
+        for attempt in 0..=config.max_retries {
             if attempt > 0 {
                 tracing::info!(
                     "Retrying request to {} (attempt {}/{})",
                     host,
                     attempt + 1,
-                    self.config.max_retries + 1
+                    config.max_retries + 1
                 );
-                tokio::time::sleep(Duration::from_millis(delay_ms)).await;
-                delay_ms = (delay_ms * 2).min(self.config.max_delay_ms);
+                tokio::time::sleep(Duration::from_millis(config.jitter.apply(delay_ms))).await;
+                delay_ms = (delay_ms * 2).min(config.max_delay_ms);
             }
 
             let client = self.pool.get_client().await;
@@ -301,7 +404,7 @@ impl ResilientHttpClient {
                 Err(e) => {
                     tracing::warn!("Request failed: {}", e);
                     last_error = Some(e);
-                    if attempt == self.config.max_retries {
+                    if attempt == config.max_retries {
                         breaker.record_failure().await;
                     }
                 }
@@ -322,14 +425,12 @@ pub trait ResilientService {
 /// Manager for all resilient services
 pub struct ResilienceManager {
     services: Arc<RwLock<HashMap<String, Box<dyn ResilientService + Send + Sync>>>>,
-    config: ResilienceConfig,
 }
 
 impl ResilienceManager {
-    pub fn new(config: ResilienceConfig) -> Self {
+    pub fn new(_config: ResilienceConfig) -> Self {
         Self {
             services: Arc::new(RwLock::new(HashMap::new())),
-            config,
         }
     }
 
@@ -377,11 +478,40 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_exponential_backoff() {
+    async fn test_host_override_opens_independently() {
+        let mut config = ResilienceConfig {
+            max_retries: 0,
+            failure_threshold: 3,
+            ..Default::default()
+        };
+        config.host_overrides.insert("beta.crunchyroll.com".to_string(), ResilienceConfig {
+            max_retries: 0,
+            failure_threshold: 1,
+            ..Default::default()
+        });
+        let client = ResilientHttpClient::new(config).unwrap();
+
+        let fail = |_| async { Err::<reqwest::Response, _>(anyhow::anyhow!("unavailable")) };
+        let _ = client.request("https://beta.crunchyroll.com/content", fail).await;
+        let _ = client.request("https://metadata.internal/anime", fail).await;
+
+        let states: HashMap<String, CircuitState> = client.circuit_states().await.into_iter().collect();
+        assert!(matches!(states["beta.crunchyroll.com"], CircuitState::Open(_)));
+        assert_eq!(states["metadata.internal"], CircuitState::Closed);
+
+        // The default host only opens once it reaches its own threshold
+        for _ in 0..2 {
+            let _ = client.request("https://metadata.internal/anime", fail).await;
+        }
+        assert!(client.get_circuit_breaker("metadata.internal").await.is_open().await);
+    }
+
+    async fn backoff_elapsed_ms(jitter: JitterStrategy) -> u128 {
         let config = ResilienceConfig {
             max_retries: 2,
             base_delay_ms: 100,
             max_delay_ms: 1000,
+            jitter,
             ..Default::default()
         };
 
@@ -391,19 +521,70 @@ mod tests {
         let _ = client.execute("test", |_| async {
             Err::<(), _>(anyhow::anyhow!("Test error"))
         }).await;
-        
-        // Should have delays of 100ms + 200ms = 300ms minimum
-        assert!(start.elapsed().as_millis() >= 300);
+        start.elapsed().as_millis()
+    }
+
+    #[tokio::test]
+    async fn test_retry_with_backoff_counts_attempts() {
+        let config = ResilienceConfig {
+            max_retries: 2,
+            base_delay_ms: 1,
+            jitter: JitterStrategy::None,
+            ..Default::default()
+        };
+
+        let mut calls = 0;
+        let result: Result<()> = retry_with_backoff(&config, "Failing", || {
+            calls += 1;
+            async { Err(anyhow::anyhow!("down")) }
+        }).await;
+        assert_eq!(calls, 3);
+        assert!(result.unwrap_err().to_string().contains("after 3 attempts"));
+
+        let mut calls = 0;
+        let result = retry_with_backoff(&config, "Flaky", || {
+            calls += 1;
+            let ok = calls == 2;
+            async move { if ok { Ok(calls) } else { Err(anyhow::anyhow!("not yet")) } }
+        }).await;
+        assert_eq!(result.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_exponential_backoff() {
+        // Delays of 100ms + 200ms; the upper bounds leave room for scheduling
+        let elapsed = backoff_elapsed_ms(JitterStrategy::None).await;
+        assert!((300..600).contains(&elapsed), "no jitter took {}ms", elapsed);
+
+        // Full jitter sleeps anywhere up to the computed delays
+        let elapsed = backoff_elapsed_ms(JitterStrategy::Full).await;
+        assert!(elapsed < 600, "full jitter took {}ms", elapsed);
+
+        // Equal jitter always waits at least half of them
+        let elapsed = backoff_elapsed_ms(JitterStrategy::Equal).await;
+        assert!((150..600).contains(&elapsed), "equal jitter took {}ms", elapsed);
+    }
+
+    #[test]
+    fn test_jitter_bounds() {
+        for _ in 0..1000 {
+            assert_eq!(JitterStrategy::None.apply(200), 200);
+            assert!(JitterStrategy::Full.apply(200) <= 200);
+            assert!((100..=200).contains(&JitterStrategy::Equal.apply(200)));
+            assert!((50..=101).contains(&JitterStrategy::Equal.apply(101)));
+        }
+        assert_eq!(JitterStrategy::Full.apply(0), 0);
+        assert_eq!(ResilienceConfig::default().jitter, JitterStrategy::Full);
     }
 
     #[tokio::test]
     async fn test_connection_pool_rotation() {
         let pool = HttpConnectionPool::new(3, Duration::from_secs(30)).unwrap();
         
-        let client1 = pool.get_client().await;
-        let client2 = pool.get_client().await;
-        let client3 = pool.get_client().await;
-        let client4 = pool.get_client().await;
+        let _client1 = pool.get_client().await;
+        let _client2 = pool.get_client().await;
+        let _client3 = pool.get_client().await;
+        let _client4 = pool.get_client().await;
         
         // Fourth client should be the same as first (rotation)
         // Note: This is a simplified test, actual comparison would be more complex
@@ -1,24 +1,367 @@
 // T031: Search engine with full-text search
 // Reference: spec.md FR-002 for search requirements
 
-use anyhow::{Result, Context};
-use crate::models::{Anime, AnimeSummary, Tag};
+use anyhow::{bail, Result, Context};
+use crate::models::{AnimeStatus, AnimeSummary, AnimeType};
+use crate::services::transliteration::{contains_kana, romaji_key};
 use crate::services::DatabaseService;
+use serde::Deserialize;
+use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+
+/// A search box query split into fielded clauses.
+///
+/// Supported prefixes are `title:`, `tag:` (repeatable) and `year:`; values may be
+/// double-quoted to include spaces, e.g. `title:"spy x family" tag:action year:2022`.
+/// Anything else, including unknown prefixes, is kept as free text.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQuery {
+    pub free_text: String,
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub year: Option<u16>,
+    /// Free text and title are compact romaji keys (see `transliterated`)
+    pub romaji: bool,
+}
+
+impl SearchQuery {
+    pub fn parse(input: &str) -> Self {
+        let mut query = SearchQuery::default();
+        let mut free_text = Vec::new();
+        
+        for token in tokenize(input) {
+            let field = token
+                .split_once(':')
+                .filter(|(_, value)| !value.is_empty());
+            
+            match field {
+                Some((prefix, value)) if prefix.eq_ignore_ascii_case("title") => {
+                    query.title = Some(match query.title.take() {
+                        Some(title) => format!("{} {}", title, value),
+                        None => value.to_string(),
+                    });
+                }
+                Some((prefix, value)) if prefix.eq_ignore_ascii_case("tag") => {
+                    query.tags.push(value.to_string());
+                }
+                Some((prefix, value)) if prefix.eq_ignore_ascii_case("year") => {
+                    match value.parse() {
+                        Ok(year) => query.year = Some(year),
+                        Err(_) => free_text.push(token),
+                    }
+                }
+                _ => free_text.push(token),
+            }
+        }
+        
+        query.free_text = free_text.join(" ");
+        query
+    }
+    
+    /// True when at least one prefix was recognised
+    pub fn is_fielded(&self) -> bool {
+        self.title.is_some() || !self.tags.is_empty() || self.year.is_some()
+    }
+    
+    /// The text titles are scored against: the title clause, else the free
+    /// text, trimmed and lowercased
+    pub fn match_text(&self) -> String {
+        self.title.as_deref().unwrap_or(&self.free_text).trim().to_lowercase()
+    }
+    
+    /// For text containing kana, the same query with its free text and title
+    /// reduced to romaji keys, to be matched against romaji titles
+    pub fn transliterated(&self) -> Option<SearchQuery> {
+        let has_kana = contains_kana(&self.free_text)
+            || self.title.as_deref().is_some_and(contains_kana);
+        if self.romaji || !has_kana {
+            return None;
+        }
+        
+        Some(SearchQuery {
+            free_text: romaji_key(&self.free_text),
+            title: self.title.as_deref().map(romaji_key),
+            tags: self.tags.clone(),
+            year: self.year,
+            romaji: true,
+        })
+    }
+}
+
+/// Split on whitespace, keeping double-quoted runs together and dropping the quotes
+fn tokenize(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    
+    for c in input.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    
+    tokens
+}
+
+/// Secondary sort for results that share a relevance score
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreaker {
+    /// Higher IMDb rating first; unrated titles last
+    #[default]
+    Rating,
+    /// Most recently aired season first
+    Recent,
+    /// Title A-Z
+    Alphabetical,
+}
+
+impl FromStr for TieBreaker {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "rating" => Ok(TieBreaker::Rating),
+            "recent" => Ok(TieBreaker::Recent),
+            "alphabetical" => Ok(TieBreaker::Alphabetical),
+            other => bail!("Unknown search tie-breaker: {}", other),
+        }
+    }
+}
+
+impl TieBreaker {
+    /// SurrealQL ORDER BY terms; NONE sorts below any rating, so descending
+    /// puts unrated titles last
+    fn order_by(&self) -> &'static str {
+        match self {
+            TieBreaker::Rating => "imdb.rating DESC",
+            TieBreaker::Recent => "anime_season.year DESC, season_index DESC",
+            TieBreaker::Alphabetical => "title_key",
+        }
+    }
+}
+
+/// Result order requested with `?sort=` on search
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// Relevance
+    #[default]
+    Default,
+    /// Highest popularity score first
+    Popularity,
+}
+
+impl SortOrder {
+    /// SurrealQL ORDER BY list over the fields `search_anime_matches` selects:
+    /// matches of the query as typed before transliterated ones, then
+    /// relevance, `tie_breaker` and title so the order is stable across
+    /// requests. Popularity ranks ahead of all of them.
+    pub fn order_by(&self, tie_breaker: TieBreaker) -> String {
+        let relevance = format!("transliterated, relevance DESC, {}, title", tie_breaker.order_by());
+        match self {
+            SortOrder::Default => relevance,
+            SortOrder::Popularity => format!("popularity DESC, {}", relevance),
+        }
+    }
+}
+
+/// Narrowing of a season listing; every given field must match, and so must
+/// every tag (by name, case-insensitively)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SeasonFilter {
+    pub anime_type: Option<AnimeType>,
+    pub status: Option<AnimeStatus>,
+    pub tags: Vec<String>,
+}
+
+impl SeasonFilter {
+    /// `type:status:tags` for cache keys, with tags in sorted order
+    pub fn cache_key(&self) -> String {
+        let mut tags: Vec<String> = self.tags.iter().map(|tag| tag.to_lowercase()).collect();
+        tags.sort_unstable();
+        format!(
+            "{}:{}:{}",
+            self.anime_type.as_ref().map_or("any".to_string(), |anime_type| format!("{:?}", anime_type)),
+            self.status.as_ref().map_or("any".to_string(), |status| format!("{:?}", status)),
+            tags.join(","),
+        )
+    }
+}
+
+/// Most suggestions `GET /api/search/suggest` returns
+pub const SUGGEST_LIMIT: usize = 8;
+
+/// Shorter typeahead queries match too much to be useful, so they get no suggestions
+pub const SUGGEST_MIN_CHARS: usize = 2;
+
+/// The typeahead prefix for `input`: trimmed, lowercased, inner whitespace
+/// collapsed; None when shorter than `SUGGEST_MIN_CHARS`
+pub fn normalize_suggest_query(input: &str) -> Option<String> {
+    let query = input.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+    (query.chars().count() >= SUGGEST_MIN_CHARS).then_some(query)
+}
+
+/// Field `GET /api/anime` orders by with `?sort=`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListSortField {
+    Title,
+    Rating,
+    #[serde(alias = "episodes")]
+    EpisodeCount,
+    CreatedAt,
+}
+
+/// Direction of `GET /api/anime` with `?order=`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ListSortOrder {
+    Asc,
+    Desc,
+}
+
+impl ListSortField {
+    /// Titles read A to Z by default; ratings, episode counts and dates highest first
+    pub fn default_order(&self) -> ListSortOrder {
+        match self {
+            ListSortField::Title => ListSortOrder::Asc,
+            _ => ListSortOrder::Desc,
+        }
+    }
+
+    /// SurrealQL ORDER BY list. Unrated anime come last in either direction,
+    /// through the `unrated` field `list_anime_sorted` selects; ties fall back to title.
+    pub fn order_by(&self, order: ListSortOrder) -> String {
+        let direction = match order {
+            ListSortOrder::Asc => "ASC",
+            ListSortOrder::Desc => "DESC",
+        };
+        match self {
+            ListSortField::Title => format!("title {}", direction),
+            ListSortField::Rating => format!("unrated ASC, imdb.rating {}, title", direction),
+            ListSortField::EpisodeCount => format!("episodes {}, title", direction),
+            ListSortField::CreatedAt => format!("created_at {}, title", direction),
+        }
+    }
+}
+
+/// Values `BrowseSort` accepts before the optional `:asc` / `:desc`
+pub const BROWSE_SORT_FIELDS: &[&str] = &["title", "rating", "episodes", "popularity"];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BrowseSortField {
+    #[default]
+    Title,
+    Rating,
+    Episodes,
+    Popularity,
+}
+
+/// Season listing order from `?sort=field[:asc|desc]`. Titles default to
+/// ascending, the numeric fields to highest first.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BrowseSort {
+    pub field: BrowseSortField,
+    pub descending: bool,
+}
+
+impl BrowseSort {
+    /// `field:asc|desc` for cache keys
+    pub fn cache_key(&self) -> String {
+        format!("{:?}:{}", self.field, if self.descending { "desc" } else { "asc" })
+    }
+    
+    /// SurrealQL ORDER BY list; ties fall back to title
+    pub fn order_by(&self) -> String {
+        let direction = if self.descending { "DESC" } else { "ASC" };
+        match self.field {
+            BrowseSortField::Title => format!("title {}", direction),
+            BrowseSortField::Rating => format!("imdb.rating {}, title", direction),
+            BrowseSortField::Episodes => format!("episodes {}, title", direction),
+            BrowseSortField::Popularity => format!("popularity {}, title", direction),
+        }
+    }
+}
+
+impl FromStr for BrowseSort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (field, direction) = match s.trim().split_once(':') {
+            Some((field, direction)) => (field, Some(direction)),
+            None => (s.trim(), None),
+        };
+
+        let field = match field.to_lowercase().as_str() {
+            "title" => BrowseSortField::Title,
+            "rating" => BrowseSortField::Rating,
+            "episodes" => BrowseSortField::Episodes,
+            "popularity" => BrowseSortField::Popularity,
+            other => return Err(format!("Unknown sort field: {}", other)),
+        };
+        let descending = match direction.map(str::to_lowercase).as_deref() {
+            None => field != BrowseSortField::Title,
+            Some("asc") => false,
+            Some("desc") => true,
+            Some(other) => return Err(format!("Unknown sort direction: {} (expected asc or desc)", other)),
+        };
+
+        Ok(BrowseSort { field, descending })
+    }
+}
+
+/// Search configuration
+#[derive(Clone, Debug, Default)]
+pub struct SearchConfig {
+    pub tie_breaker: TieBreaker,
+}
+
+impl SearchConfig {
+    /// Reads SEARCH_TIE_BREAKER: "rating" (default), "recent" or "alphabetical"
+    pub fn from_env() -> Self {
+        let tie_breaker = std::env::var("SEARCH_TIE_BREAKER")
+            .ok()
+            .and_then(|s| match TieBreaker::from_str(&s) {
+                Ok(tie_breaker) => Some(tie_breaker),
+                Err(e) => {
+                    tracing::warn!("Ignoring SEARCH_TIE_BREAKER: {}", e);
+                    None
+                }
+            })
+            .unwrap_or_default();
+
+        SearchConfig { tie_breaker }
+    }
+}
 
 pub struct SearchService {
     db: Arc<DatabaseService>,
+    config: SearchConfig,
 }
 
 impl SearchService {
-    pub fn new(db: Arc<DatabaseService>) -> Self {
-        SearchService { db }
+    pub fn new(db: Arc<DatabaseService>, config: SearchConfig) -> Self {
+        SearchService { db, config }
     }
     
-    pub async fn search_anime(&self, query: &str) -> Result<Vec<AnimeSummary>> {
-        // Use database search functionality
-        self.db.search_anime(query).await
+    /// A page of matches for a search box query and the total number of matches.
+    /// Plain queries become a fuzzy title/synonym search; see `SearchQuery` for prefixes.
+    pub async fn search_anime(&self, query: &str, sort: SortOrder, limit: usize, offset: usize) -> Result<(Vec<AnimeSummary>, usize)> {
+        let query = SearchQuery::parse(query);
+        let order_by = sort.order_by(self.config.tie_breaker);
+        let (page, total) = self.db.search_anime_matches(&query, &order_by, limit, offset).await?;
+        
+        Ok((page.into_iter().map(AnimeSummary::from).collect(), total))
     }
     
     pub async fn search_by_tag(&self, tag_name: &str) -> Result<Vec<AnimeSummary>> {
@@ -48,9 +391,28 @@ impl SearchService {
         Ok(results)
     }
     
-    pub async fn search_by_season(&self, year: u16, season: &str) -> Result<Vec<AnimeSummary>> {
-        // Use optimized database method
-        self.db.get_seasonal_anime(year, season).await
+    /// A season narrowed by `filter` and ordered by `sort`, with the size of
+    /// the whole filtered listing. `page` is (limit, offset); without it the
+    /// full listing is returned.
+    pub async fn search_by_season(
+        &self,
+        year: u16,
+        season: &str,
+        filter: &SeasonFilter,
+        sort: BrowseSort,
+        page: Option<(usize, usize)>,
+    ) -> Result<(Vec<AnimeSummary>, usize)> {
+        let (limit, offset) = match page {
+            Some((limit, offset)) => (Some(limit), offset),
+            None => (None, 0),
+        };
+        let results = self.db.get_seasonal_anime(year, season, filter, sort, limit, offset).await?;
+        
+        let total = match page {
+            Some(_) => self.db.count_seasonal_anime(year, season, filter).await?,
+            None => results.len(),
+        };
+        Ok((results, total))
     }
     
     pub async fn get_trending(&self, limit: usize) -> Result<Vec<AnimeSummary>> {
@@ -72,10 +434,99 @@ mod tests {
     #[tokio::test]
     async fn test_search_service_creation() {
         let db = Arc::new(DatabaseService::new("memory://").await.unwrap());
-        let search = SearchService::new(db);
+        let search = SearchService::new(db, SearchConfig::default());
         
         // Should be able to search even with empty database
-        let results = search.search_anime("test").await.unwrap();
+        let (results, total) = search.search_anime("test", SortOrder::Default, 20, 0).await.unwrap();
         assert_eq!(results.len(), 0);
+        assert_eq!(total, 0);
+    }
+    
+    #[test]
+    fn test_suggest_query_normalization() {
+        assert_eq!(normalize_suggest_query("  Shingeki   NO "), Some("shingeki no".to_string()));
+        assert_eq!(normalize_suggest_query("ナル"), Some("ナル".to_string()));
+        assert_eq!(normalize_suggest_query(" a "), None);
+        assert_eq!(normalize_suggest_query(""), None);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_parse_fielded_query() {
+        let query = SearchQuery::parse("title:titan tag:action year:2013");
+        
+        assert_eq!(query.title.as_deref(), Some("titan"));
+        assert_eq!(query.tags, vec!["action"]);
+        assert_eq!(query.year, Some(2013));
+        assert!(query.free_text.is_empty());
+        assert!(query.is_fielded());
+    }
+    
+    #[test]
+    fn test_parse_quoted_values_and_multiple_tags() {
+        let query = SearchQuery::parse(r#"title:"spy x family" tag:comedy tag:"slice of life" anya"#);
+        
+        assert_eq!(query.title.as_deref(), Some("spy x family"));
+        assert_eq!(query.tags, vec!["comedy", "slice of life"]);
+        assert_eq!(query.free_text, "anya");
+    }
+    
+    #[test]
+    fn test_parse_plain_and_unknown_prefixes() {
+        let plain = SearchQuery::parse("attack on titan");
+        assert_eq!(plain.free_text, "attack on titan");
+        assert!(!plain.is_fielded());
+        
+        // Unknown prefixes and unparseable years stay literal
+        let query = SearchQuery::parse("re:zero studio:wit year:soon");
+        assert_eq!(query.free_text, "re:zero studio:wit year:soon");
+        assert!(!query.is_fielded());
+    }
+    
+    #[test]
+    fn test_kana_queries_transliterate() {
+        assert!(SearchQuery::parse("attack on titan").transliterated().is_none());
+        
+        let romaji = SearchQuery::parse("シンゲキ tag:action").transliterated().unwrap();
+        assert_eq!(romaji.free_text, "shingeki");
+        assert_eq!(romaji.tags, vec!["action"]);
+        assert!(romaji.romaji);
+        assert!(romaji.transliterated().is_none());
+    }
+    
+    #[test]
+    fn test_search_order_by() {
+        assert_eq!(
+            SortOrder::Default.order_by(TieBreaker::Rating),
+            "transliterated, relevance DESC, imdb.rating DESC, title"
+        );
+        // Popularity first; equally popular results keep their relevance order
+        assert_eq!(
+            SortOrder::Popularity.order_by(TieBreaker::Recent),
+            "popularity DESC, transliterated, relevance DESC, anime_season.year DESC, season_index DESC, title"
+        );
+    }
+    
+    #[test]
+    fn test_parse_tie_breaker() {
+        assert_eq!("Recent".parse::<TieBreaker>().unwrap(), TieBreaker::Recent);
+        assert_eq!(" alphabetical ".parse::<TieBreaker>().unwrap(), TieBreaker::Alphabetical);
+        assert!("popularity".parse::<TieBreaker>().is_err());
+    }
+    
+    #[test]
+    fn test_parse_browse_sort() {
+        assert_eq!(BrowseSort::default().order_by(), "title ASC");
+        assert_eq!("title".parse::<BrowseSort>().unwrap(), BrowseSort::default());
+        
+        let rating: BrowseSort = "rating:desc".parse().unwrap();
+        assert_eq!(rating, BrowseSort { field: BrowseSortField::Rating, descending: true });
+        assert_eq!(rating.order_by(), "imdb.rating DESC, title");
+        
+        // Numeric fields default to highest first
+        assert_eq!("popularity".parse::<BrowseSort>().unwrap(), BrowseSort { field: BrowseSortField::Popularity, descending: true });
+        assert_eq!("Episodes:ASC".parse::<BrowseSort>().unwrap().order_by(), "episodes ASC, title");
+        
+        assert!("score".parse::<BrowseSort>().is_err());
+        assert!("rating:sideways".parse::<BrowseSort>().is_err());
+    }
+}
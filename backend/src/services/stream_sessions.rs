@@ -0,0 +1,312 @@
+// Resumable stream grants
+// Every stream response comes with a short-lived resumption token. After a
+// network blip the player redeems it for a fresh URL instead of repeating the
+// stream request, so recovery is not rate limited, does not count as another
+// concurrent stream, and skips provider resolution while the manifest is valid.
+//
+// Started streams are also kept per user, with the device they play on, so an
+// optional concurrency limit can name the devices already streaming.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+use crate::services::CacheService;
+
+/// How long a resumption token can be redeemed after it was issued
+pub const RESUME_TOKEN_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Streams started within this window count towards a user's active streams
+pub const ACTIVE_STREAM_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+/// The stream a user was granted, as remembered for resumption
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamGrant {
+    pub user_id: String,
+    /// Device of the session the stream was started from
+    #[serde(default)]
+    pub device_id: Option<Uuid>,
+    #[serde(default)]
+    pub device_name: Option<String>,
+    pub anime_id: Uuid,
+    pub episode_number: u32,
+    /// Quality the client asked for; negotiated again if the manifest is re-resolved
+    pub quality: String,
+    /// Manifest exactly as returned to the player
+    pub manifest: serde_json::Value,
+    /// When the manifest's signed URLs stop working
+    pub manifest_expires_at: DateTime<Utc>,
+}
+
+impl StreamGrant {
+    /// True while the cached manifest can be handed out again as-is
+    pub fn manifest_is_valid(&self, now: DateTime<Utc>) -> bool {
+        self.manifest_expires_at > now
+    }
+}
+
+/// A started stream, counted towards the user's active streams until
+/// `ACTIVE_STREAM_WINDOW` after it started
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActiveStream {
+    pub device_id: Option<Uuid>,
+    pub device_name: Option<String>,
+    pub started_at: DateTime<Utc>,
+}
+
+impl ActiveStream {
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        now - self.started_at < chrono::Duration::from_std(ACTIVE_STREAM_WINDOW).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ResumeError {
+    #[error("Resumption token is invalid or expired")]
+    Expired,
+    #[error("Resumption token was issued to another user")]
+    WrongUser,
+}
+
+/// Starting a stream would exceed the user's concurrent stream limit
+#[derive(Debug, thiserror::Error)]
+#[error("{}", streaming_on_message(.active))]
+pub struct StreamLimitError {
+    pub limit: usize,
+    /// The streams that are using up the limit
+    pub active: Vec<ActiveStream>,
+}
+
+/// "Streaming on Living Room TV and Phone"; unnamed devices are "another device"
+pub fn streaming_on_message(active: &[ActiveStream]) -> String {
+    let mut names: Vec<&str> = active
+        .iter()
+        .map(|stream| stream.device_name.as_deref().unwrap_or("another device"))
+        .collect();
+    names.dedup();
+
+    match names.split_last() {
+        None => "Streaming on another device".to_string(),
+        Some((last, [])) => format!("Streaming on {}", last),
+        Some((last, rest)) => format!("Streaming on {} and {}", rest.join(", "), last),
+    }
+}
+
+/// Reads MAX_CONCURRENT_STREAMS; unset or 0 means no limit
+pub fn max_concurrent_streams_from_env() -> Option<usize> {
+    std::env::var("MAX_CONCURRENT_STREAMS")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .filter(|&limit| limit > 0)
+}
+
+pub struct StreamSessionService {
+    cache: Arc<Mutex<CacheService>>,
+    max_concurrent: Option<usize>,
+}
+
+impl StreamSessionService {
+    pub fn new(cache: Arc<Mutex<CacheService>>) -> Self {
+        StreamSessionService { cache, max_concurrent: None }
+    }
+
+    /// Refuse new streams while a user already has `limit` active ones
+    pub fn with_max_concurrent(mut self, limit: Option<usize>) -> Self {
+        self.max_concurrent = limit;
+        self
+    }
+
+    /// Record a newly started stream and return its resumption token. A new
+    /// stream replaces any earlier one from the same device; beyond that it
+    /// fails with `StreamLimitError` once the user is at the limit.
+    pub async fn start(&self, grant: &StreamGrant) -> Result<String> {
+        let now = Utc::now();
+        {
+            let mut cache = self.cache.lock().await;
+            let mut active: Vec<ActiveStream> = cache.get_active_streams(&grant.user_id).await?;
+            active.retain(|stream| {
+                stream.is_active(now) && (grant.device_id.is_none() || stream.device_id != grant.device_id)
+            });
+
+            if let Some(limit) = self.max_concurrent {
+                if active.len() >= limit {
+                    return Err(StreamLimitError { limit, active }.into());
+                }
+            }
+
+            active.push(ActiveStream {
+                device_id: grant.device_id,
+                device_name: grant.device_name.clone(),
+                started_at: now,
+            });
+            cache.store_active_streams(&grant.user_id, &active, ACTIVE_STREAM_WINDOW).await?;
+        }
+
+        self.issue(grant).await
+    }
+
+    /// Stop counting the device's streams; returns how many were active
+    pub async fn end_device_streams(&self, user_id: &str, device_id: Uuid) -> Result<usize> {
+        let now = Utc::now();
+        let mut cache = self.cache.lock().await;
+        let mut active: Vec<ActiveStream> = cache.get_active_streams(user_id).await?;
+        active.retain(|stream| stream.is_active(now));
+
+        let before = active.len();
+        active.retain(|stream| stream.device_id != Some(device_id));
+        let ended = before - active.len();
+
+        if ended > 0 {
+            cache.store_active_streams(user_id, &active, ACTIVE_STREAM_WINDOW).await?;
+        }
+        Ok(ended)
+    }
+
+    /// Store `grant` under a new resumption token without counting a new stream
+    pub async fn issue(&self, grant: &StreamGrant) -> Result<String> {
+        let token = Uuid::new_v4().simple().to_string();
+        self.cache.lock().await
+            .store_stream_grant(&token, grant, RESUME_TOKEN_TTL)
+            .await?;
+
+        Ok(token)
+    }
+
+    /// Redeem a resumption token. Tokens are single use; the caller issues a new
+    /// one alongside the resumed stream.
+    pub async fn resume(&self, user_id: &str, token: &str) -> Result<StreamGrant> {
+        let mut cache = self.cache.lock().await;
+        let grant: StreamGrant = cache
+            .get_stream_grant(token)
+            .await?
+            .ok_or(ResumeError::Expired)?;
+
+        if grant.user_id != user_id {
+            return Err(ResumeError::WrongUser.into());
+        }
+
+        cache.delete_stream_grant(token).await?;
+        Ok(grant)
+    }
+
+    pub async fn active_streams(&self, user_id: &str) -> Result<u64> {
+        let now = Utc::now();
+        let active: Vec<ActiveStream> = self.cache.lock().await.get_active_streams(user_id).await?;
+        Ok(active.iter().filter(|stream| stream.is_active(now)).count() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::cache::MemoryCache;
+
+    fn service() -> StreamSessionService {
+        let cache = CacheService::with_backend(Arc::new(MemoryCache::new()));
+        StreamSessionService::new(Arc::new(Mutex::new(cache)))
+    }
+
+    fn grant(user_id: &str) -> StreamGrant {
+        StreamGrant {
+            user_id: user_id.to_string(),
+            device_id: None,
+            device_name: None,
+            anime_id: Uuid::new_v4(),
+            episode_number: 1,
+            quality: "auto".to_string(),
+            manifest: serde_json::json!({ "streams": [] }),
+            manifest_expires_at: Utc::now() + chrono::Duration::minutes(15),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_does_not_count_a_new_stream() {
+        let sessions = service();
+        let token = sessions.start(&grant("u1")).await.unwrap();
+        assert_eq!(sessions.active_streams("u1").await.unwrap(), 1);
+
+        let resumed = sessions.resume("u1", &token).await.unwrap();
+        sessions.issue(&resumed).await.unwrap();
+
+        assert_eq!(sessions.active_streams("u1").await.unwrap(), 1);
+        assert!(resumed.manifest_is_valid(Utc::now()));
+    }
+
+    #[tokio::test]
+    async fn test_resume_rejects_spent_unknown_and_foreign_tokens() {
+        let sessions = service();
+        let token = sessions.start(&grant("u1")).await.unwrap();
+
+        let foreign = sessions.resume("u2", &token).await.unwrap_err();
+        assert!(matches!(foreign.downcast_ref::<ResumeError>(), Some(ResumeError::WrongUser)));
+
+        // A foreign attempt leaves the token usable for its owner, once
+        sessions.resume("u1", &token).await.unwrap();
+        let spent = sessions.resume("u1", &token).await.unwrap_err();
+        assert!(matches!(spent.downcast_ref::<ResumeError>(), Some(ResumeError::Expired)));
+
+        let unknown = sessions.resume("u1", "not-a-token").await.unwrap_err();
+        assert!(matches!(unknown.downcast_ref::<ResumeError>(), Some(ResumeError::Expired)));
+    }
+
+    fn device_grant(user_id: &str, device_id: Uuid, name: &str) -> StreamGrant {
+        StreamGrant {
+            device_id: Some(device_id),
+            device_name: Some(name.to_string()),
+            ..grant(user_id)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_limit_names_streaming_devices() {
+        let sessions = service().with_max_concurrent(Some(1));
+        let tv = Uuid::new_v4();
+        sessions.start(&device_grant("u1", tv, "Living Room TV")).await.unwrap();
+
+        // The same device moving on to the next episode replaces its stream
+        sessions.start(&device_grant("u1", tv, "Living Room TV")).await.unwrap();
+        assert_eq!(sessions.active_streams("u1").await.unwrap(), 1);
+
+        let refused = sessions.start(&device_grant("u1", Uuid::new_v4(), "Phone")).await.unwrap_err();
+        let limit = refused.downcast_ref::<StreamLimitError>().expect("limit error");
+        assert_eq!(limit.limit, 1);
+        assert_eq!(limit.to_string(), "Streaming on Living Room TV");
+
+        // Other users are unaffected; ending the TV's stream frees the slot
+        sessions.start(&grant("u2")).await.unwrap();
+        assert_eq!(sessions.end_device_streams("u1", tv).await.unwrap(), 1);
+        sessions.start(&device_grant("u1", Uuid::new_v4(), "Phone")).await.unwrap();
+    }
+
+    #[test]
+    fn test_streaming_on_message() {
+        let stream = |name: Option<&str>| ActiveStream {
+            device_id: None,
+            device_name: name.map(str::to_string),
+            started_at: Utc::now(),
+        };
+
+        assert_eq!(streaming_on_message(&[]), "Streaming on another device");
+        assert_eq!(streaming_on_message(&[stream(Some("Phone"))]), "Streaming on Phone");
+        assert_eq!(
+            streaming_on_message(&[stream(Some("Living Room TV")), stream(None), stream(Some("Phone"))]),
+            "Streaming on Living Room TV, another device and Phone"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resume_rejects_expired_token() {
+        let sessions = service();
+        sessions.cache.lock().await
+            .store_stream_grant("short-lived", &grant("u1"), Duration::from_millis(10))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let expired = sessions.resume("u1", "short-lived").await.unwrap_err();
+        assert!(matches!(expired.downcast_ref::<ResumeError>(), Some(ResumeError::Expired)));
+    }
+}
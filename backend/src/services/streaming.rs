@@ -3,15 +3,133 @@
 
 use anyhow::{Result, Context, bail};
 use crunchyroll_rs::{Crunchyroll, Episode, Series, Season};
-use crunchyroll_rs::media::Stream;
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
 use crate::models::Session;
 use crate::services::auth::AuthService;
+use crate::services::{CacheService, ResilienceConfig, ResilientHttpClient};
+
+/// Crunchyroll API used when CRUNCHYROLL_API_URL is unset
+pub const DEFAULT_CRUNCHYROLL_API_URL: &str = "https://www.crunchyroll.com";
+
+/// Per-attempt timeout for API probes
+pub const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Renditions assumed when the upstream list cannot be fetched, best first
+pub const DEFAULT_RENDITIONS: [&str; 4] = ["1080p", "720p", "480p", "360p"];
+
+/// How long an episode's upstream rendition list is reused
+pub const RENDITION_CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Royalty-free HLS sample (Big Buck Bunny, CC BY 3.0) served in demo mode
+/// when DEMO_STREAM_URL is unset
+pub const DEFAULT_DEMO_STREAM_URL: &str = "https://test-streams.mux.dev/x36xhzz/x36xhzz.m3u8";
+
+/// Lifetime of a demo stream URL, matching what Crunchyroll hands out
+pub const DEMO_STREAM_TTL: Duration = Duration::from_secs(15 * 60);
+
+/// Provider name reported in manifests
+pub const PROVIDER_CRUNCHYROLL: &str = "crunchyroll";
+pub const PROVIDER_DEMO: &str = "demo";
+
+/// Demo streaming: every episode plays one static manifest and Crunchyroll is
+/// never contacted, so the stream pipeline can be exercised without an account
+#[derive(Debug, Clone)]
+pub struct DemoStreamConfig {
+    pub enabled: bool,
+    pub manifest_url: String,
+}
+
+impl Default for DemoStreamConfig {
+    fn default() -> Self {
+        DemoStreamConfig {
+            enabled: false,
+            manifest_url: DEFAULT_DEMO_STREAM_URL.to_string(),
+        }
+    }
+}
+
+impl DemoStreamConfig {
+    /// Reads STREAMING_DEMO_MODE (default off) and DEMO_STREAM_URL
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("STREAMING_DEMO_MODE")
+            .map(|value| matches!(value.to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(false);
+        let manifest_url = std::env::var("DEMO_STREAM_URL")
+            .ok()
+            .filter(|url| !url.is_empty())
+            .unwrap_or_else(|| DEFAULT_DEMO_STREAM_URL.to_string());
+        
+        DemoStreamConfig { enabled, manifest_url }
+    }
+}
 
 #[derive(Clone)]
 pub struct StreamingService {
     auth_service: Arc<tokio::sync::Mutex<AuthService>>,
+    cache: Arc<tokio::sync::Mutex<CacheService>>,
+    probe: Arc<CrunchyrollProbe>,
+    demo: DemoStreamConfig,
+}
+
+/// Lightweight reachability check against the Crunchyroll API index endpoint
+pub struct CrunchyrollProbe {
+    http: ResilientHttpClient,
+    url: String,
+}
+
+impl CrunchyrollProbe {
+    /// Probe `<base_url>/index/v2`. One retry absorbs a transient failure
+    /// before it counts against the circuit breaker.
+    pub fn new(base_url: &str) -> Result<Self> {
+        let http = ResilientHttpClient::new(ResilienceConfig {
+            max_retries: 1,
+            timeout_secs: PROBE_TIMEOUT.as_secs(),
+            pool_size: 1,
+            ..ResilienceConfig::default()
+        })?;
+        
+        Ok(CrunchyrollProbe {
+            http,
+            url: format!("{}/index/v2", base_url.trim_end_matches('/')),
+        })
+    }
+    
+    /// Reads CRUNCHYROLL_API_URL, defaulting to the public API
+    pub fn from_env() -> Result<Self> {
+        let base_url = std::env::var("CRUNCHYROLL_API_URL")
+            .unwrap_or_else(|_| DEFAULT_CRUNCHYROLL_API_URL.to_string());
+        Self::new(&base_url)
+    }
+    
+    /// Request the index endpoint and return its status. Server errors and
+    /// timeouts are retried and surface as errors; other statuses are returned.
+    #[tracing::instrument(skip_all, err)]
+    pub async fn ping(&self, access_token: Option<&str>) -> Result<reqwest::StatusCode> {
+        let response = self.http
+            .request(&self.url, |client| {
+                let mut request = client.get(&self.url).timeout(PROBE_TIMEOUT);
+                if let Some(token) = access_token {
+                    request = request.bearer_auth(token);
+                }
+                async move {
+                    let response = request.send().await?;
+                    if response.status().is_server_error() {
+                        bail!("Crunchyroll API returned {}", response.status());
+                    }
+                    Ok(response)
+                }
+            })
+            .await?;
+        
+        Ok(response.status())
+    }
+    
+    /// Circuit breaker state per probed host
+    pub async fn circuit_states(&self) -> Vec<(String, crate::services::resilient::CircuitState)> {
+        self.http.circuit_states().await
+    }
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -31,18 +149,56 @@ pub struct StreamingManifest {
     pub streams: Vec<VideoStream>,
     pub thumbnail: Option<String>,
     pub duration: u32,
+    /// Where the streams come from: "crunchyroll" or "demo"
+    pub provider: String,
+    /// Players overlay a badge so demo footage is never mistaken for the episode
+    pub watermark: bool,
 }
 
 impl StreamingService {
-    pub fn new(auth_service: Arc<tokio::sync::Mutex<AuthService>>) -> Self {
-        StreamingService { auth_service }
+    pub fn new(
+        auth_service: Arc<tokio::sync::Mutex<AuthService>>,
+        cache: Arc<tokio::sync::Mutex<CacheService>>,
+        probe: CrunchyrollProbe,
+    ) -> Self {
+        StreamingService {
+            auth_service,
+            cache,
+            probe: Arc::new(probe),
+            demo: DemoStreamConfig::default(),
+        }
+    }
+    
+    /// Serve `demo.manifest_url` for every episode when `demo.enabled`
+    pub fn with_demo(mut self, demo: DemoStreamConfig) -> Self {
+        self.demo = demo;
+        self
+    }
+    
+    pub fn demo_mode(&self) -> bool {
+        self.demo.enabled
     }
     
+    pub fn api_probe(&self) -> &CrunchyrollProbe {
+        &self.probe
+    }
+    
+    /// Access token of the current Crunchyroll login, used to authenticate probes
+    pub async fn probe_token(&self) -> Option<String> {
+        let client = self.auth_service.lock().await.service_crunchyroll_client()?;
+        Some(client.access_token().await)
+    }
+    
+    #[tracing::instrument(skip(self, session), err)]
     pub async fn get_episode_stream(
         &self,
         session: &Session,
         crunchyroll_episode_id: &str,
     ) -> Result<StreamingManifest> {
+        if self.demo.enabled {
+            return Ok(self.demo_manifest(crunchyroll_episode_id));
+        }
+        
         // Get authenticated Crunchyroll client
         let mut auth = self.auth_service.lock().await;
         let cr_client = auth.get_crunchyroll_client(session).await?;
@@ -62,9 +218,83 @@ impl StreamingService {
             streams: video_streams,
             thumbnail: None, // episode.thumbnail field may not exist in crunchyroll_rs
             duration: 0, // episode.duration_ms field may not exist
+            provider: PROVIDER_CRUNCHYROLL.to_string(),
+            watermark: false,
         })
     }
     
+    /// The demo sample in every rendition, so quality negotiation still applies
+    fn demo_manifest(&self, crunchyroll_episode_id: &str) -> StreamingManifest {
+        let expires_at = chrono::Utc::now()
+            + chrono::Duration::from_std(DEMO_STREAM_TTL).unwrap_or_default();
+        let streams = DEFAULT_RENDITIONS
+            .iter()
+            .map(|resolution| VideoStream {
+                url: self.demo.manifest_url.clone(),
+                resolution: resolution.to_string(),
+                audio_language: "en-US".to_string(),
+                subtitle_language: None,
+                hardsub: false,
+                expires_at,
+            })
+            .collect();
+        
+        StreamingManifest {
+            episode_id: Uuid::new_v4(),
+            crunchyroll_id: crunchyroll_episode_id.to_string(),
+            streams,
+            thumbnail: None,
+            duration: 0,
+            provider: PROVIDER_DEMO.to_string(),
+            watermark: true,
+        }
+    }
+    
+    /// Video renditions Crunchyroll offers for an episode, best first, e.g.
+    /// ["1080p", "720p"]. Cached per episode for `RENDITION_CACHE_TTL`.
+    #[tracing::instrument(skip(self, session), err)]
+    pub async fn available_renditions(
+        &self,
+        session: &Session,
+        crunchyroll_episode_id: &str,
+    ) -> Result<Vec<String>> {
+        if self.demo.enabled {
+            return Ok(DEFAULT_RENDITIONS.iter().map(|quality| quality.to_string()).collect());
+        }
+        
+        let key = CacheService::renditions_key(crunchyroll_episode_id);
+        match self.cache.lock().await.get::<Vec<String>>(&key).await {
+            Ok(Some(renditions)) => return Ok(renditions),
+            Ok(None) => {}
+            Err(e) => tracing::warn!("Failed to read cached renditions: {}", e),
+        }
+        
+        let cr_client = self.auth_service.lock().await.get_crunchyroll_client(session).await?;
+        let episode = self.fetch_episode(&cr_client, crunchyroll_episode_id).await?;
+        let stream = episode.stream().await?;
+        let stream_data = stream.stream_data(None).await;
+        
+        // Release the stream slot whether or not the request succeeded
+        if let Err(e) = stream.invalidate().await {
+            tracing::warn!("Failed to invalidate stream data: {}", e);
+        }
+        
+        let stream_data = stream_data?.context("No stream data for episode")?;
+        let renditions = renditions_from_heights(
+            stream_data.video.iter().filter_map(|video| video.resolution()).map(|r| r.height),
+        );
+        if renditions.is_empty() {
+            bail!("Episode has no video renditions");
+        }
+        
+        if let Err(e) = self.cache.lock().await.set(&key, &renditions, RENDITION_CACHE_TTL).await {
+            tracing::warn!("Failed to cache renditions: {}", e);
+        }
+        
+        Ok(renditions)
+    }
+    
+    #[tracing::instrument(skip(self, session), err)]
     pub async fn get_adaptive_stream(
         &self,
         session: &Session,
@@ -80,6 +310,7 @@ impl StreamingService {
             .context("Requested quality not available")
     }
     
+    #[tracing::instrument(skip(self, session), err)]
     pub async fn get_series_episodes(
         &self,
         session: &Session,
@@ -101,7 +332,7 @@ impl StreamingService {
             for episode in episodes {
                 all_episodes.push(EpisodeMetadata {
                     crunchyroll_id: episode.id.clone(),
-                    episode_number: episode.episode_number.map(|n| n as u32),
+                    episode_number: episode.episode_number,
                     title: Some(episode.title.clone()),
                     description: Some(episode.description.clone()),
                     thumbnail: episode.images.first().map(|t| t.source.to_string()),
@@ -170,12 +401,33 @@ impl StreamingService {
         // Streams are already in VideoStream format
         Ok(streams)
     }
+}
+
+/// Rendition labels for the given video heights, best first without duplicates
+fn renditions_from_heights(heights: impl IntoIterator<Item = u64>) -> Vec<String> {
+    let mut heights: Vec<u64> = heights.into_iter().collect();
+    heights.sort_unstable_by(|a, b| b.cmp(a));
+    heights.dedup();
+    heights.into_iter().map(|height| format!("{}p", height)).collect()
+}
+
+fn rendition_height(quality: &str) -> Option<u64> {
+    quality.strip_suffix('p')?.parse().ok()
+}
+
+/// Pick the rendition to serve from `available` (best first): the requested
+/// quality if offered, otherwise the next lower one. "auto" and unrecognised
+/// values get the best rendition; requests below every rendition get the lowest.
+pub fn negotiate_quality(requested: &str, available: &[String]) -> Option<String> {
+    let Some(wanted) = rendition_height(requested) else {
+        return available.first().cloned();
+    };
     
-    fn extract_resolution(&self, _stream: &Stream) -> String {
-        // Resolution info not directly available in Stream struct
-        // Would need to parse from URL or use streaming data
-        "1080p".to_string()
-    }
+    available
+        .iter()
+        .find(|quality| rendition_height(quality).is_some_and(|height| height <= wanted))
+        .or_else(|| available.last())
+        .cloned()
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -262,4 +514,27 @@ mod tests {
         assert!(manifest.contains("RESOLUTION=1920x1080"));
         assert!(manifest.contains("1080p.m3u8"));
     }
+    
+    #[test]
+    fn test_renditions_from_heights() {
+        assert_eq!(
+            renditions_from_heights([480, 1080, 720, 1080, 360]),
+            vec!["1080p", "720p", "480p", "360p"]
+        );
+        assert!(renditions_from_heights([]).is_empty());
+    }
+    
+    #[test]
+    fn test_negotiate_quality() {
+        let available: Vec<String> = vec!["1080p".into(), "720p".into(), "360p".into()];
+        
+        assert_eq!(negotiate_quality("720p", &available).as_deref(), Some("720p"));
+        // Not offered: next lower rendition
+        assert_eq!(negotiate_quality("480p", &available).as_deref(), Some("360p"));
+        assert_eq!(negotiate_quality("2160p", &available).as_deref(), Some("1080p"));
+        // Below everything offered: lowest rendition
+        assert_eq!(negotiate_quality("240p", &available).as_deref(), Some("360p"));
+        assert_eq!(negotiate_quality("auto", &available).as_deref(), Some("1080p"));
+        assert_eq!(negotiate_quality("auto", &[]), None);
+    }
 }
\ No newline at end of file
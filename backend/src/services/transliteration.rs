@@ -0,0 +1,220 @@
+// Kana to romaji transliteration for search
+// Japanese-input users type kana while titles are stored in romaji (or kanji),
+// so kana queries and stored titles are both reduced to compact romaji keys.
+// Modified Hepburn from a fixed table; kanji pass through unchanged.
+
+/// Hepburn romaji for each hiragana; katakana are mapped onto these first
+const HIRAGANA: &[(char, &str)] = &[
+    ('あ', "a"), ('い', "i"), ('う', "u"), ('え', "e"), ('お', "o"),
+    ('か', "ka"), ('き', "ki"), ('く', "ku"), ('け', "ke"), ('こ', "ko"),
+    ('が', "ga"), ('ぎ', "gi"), ('ぐ', "gu"), ('げ', "ge"), ('ご', "go"),
+    ('さ', "sa"), ('し', "shi"), ('す', "su"), ('せ', "se"), ('そ', "so"),
+    ('ざ', "za"), ('じ', "ji"), ('ず', "zu"), ('ぜ', "ze"), ('ぞ', "zo"),
+    ('た', "ta"), ('ち', "chi"), ('つ', "tsu"), ('て', "te"), ('と', "to"),
+    ('だ', "da"), ('ぢ', "ji"), ('づ', "zu"), ('で', "de"), ('ど', "do"),
+    ('な', "na"), ('に', "ni"), ('ぬ', "nu"), ('ね', "ne"), ('の', "no"),
+    ('は', "ha"), ('ひ', "hi"), ('ふ', "fu"), ('へ', "he"), ('ほ', "ho"),
+    ('ば', "ba"), ('び', "bi"), ('ぶ', "bu"), ('べ', "be"), ('ぼ', "bo"),
+    ('ぱ', "pa"), ('ぴ', "pi"), ('ぷ', "pu"), ('ぺ', "pe"), ('ぽ', "po"),
+    ('ま', "ma"), ('み', "mi"), ('む', "mu"), ('め', "me"), ('も', "mo"),
+    ('や', "ya"), ('ゆ', "yu"), ('よ', "yo"),
+    ('ら', "ra"), ('り', "ri"), ('る', "ru"), ('れ', "re"), ('ろ', "ro"),
+    ('わ', "wa"), ('ゐ', "i"), ('ゑ', "e"), ('を', "o"), ('ん', "n"),
+    ('ゔ', "vu"),
+    ('ぁ', "a"), ('ぃ', "i"), ('ぅ', "u"), ('ぇ', "e"), ('ぉ', "o"),
+    ('ゃ', "ya"), ('ゅ', "yu"), ('ょ', "yo"), ('ゎ', "wa"),
+];
+
+/// Small kana that combine with the kana before them
+const SMALL_Y: [char; 3] = ['ゃ', 'ゅ', 'ょ'];
+const SMALL_VOWELS: [char; 5] = ['ぁ', 'ぃ', 'ぅ', 'ぇ', 'ぉ'];
+
+/// Doubles the following consonant
+const SOKUON: char = 'っ';
+/// Katakana long vowel mark
+const CHOONPU: char = 'ー';
+
+/// Katakana block offset from the matching hiragana
+const KATAKANA_OFFSET: u32 = 0x60;
+
+fn to_hiragana(c: char) -> char {
+    match c {
+        'ァ'..='ヶ' => char::from_u32(c as u32 - KATAKANA_OFFSET).unwrap_or(c),
+        c => c,
+    }
+}
+
+fn romaji(c: char) -> Option<&'static str> {
+    HIRAGANA.iter().find(|(kana, _)| *kana == c).map(|(_, romaji)| *romaji)
+}
+
+pub fn is_kana(c: char) -> bool {
+    matches!(c, 'ぁ'..='ゖ' | 'ァ'..='ヺ' | CHOONPU)
+}
+
+pub fn contains_kana(text: &str) -> bool {
+    text.chars().any(is_kana)
+}
+
+/// Transliterate hiragana and katakana to Hepburn romaji, leaving everything
+/// else as is. Digraphs combine (きょ → kyo, ファ → fa) and っ doubles the next
+/// consonant (がっこう → gakko). Long vowels are written without macrons, the
+/// way most romaji titles are: ー, おお, おう and うう shorten to one vowel
+/// (とうきょう → tokyo, コーヒー → kohi).
+pub fn kana_to_romaji(text: &str) -> String {
+    let chars: Vec<char> = text.chars().map(to_hiragana).collect();
+    let mut output = String::new();
+    // Last vowel written for a kana, to shorten long vowels; None after other text
+    let mut last_vowel: Option<char> = None;
+    let mut double_next = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == SOKUON {
+            double_next = true;
+            i += 1;
+            continue;
+        }
+        if c == CHOONPU {
+            i += 1;
+            continue;
+        }
+
+        let Some(base) = romaji(c) else {
+            output.push(c);
+            last_vowel = None;
+            double_next = false;
+            i += 1;
+            continue;
+        };
+
+        let next = chars.get(i + 1).copied();
+        let mut syllable = base.to_string();
+        if let Some(small) = next.filter(|next| SMALL_Y.contains(next)).filter(|_| base.len() > 1 && base.ends_with('i')) {
+            // きゃ → kya, しゃ → sha, じゃ → ja
+            let stem = &base[..base.len() - 1];
+            let glide = &romaji(small).unwrap_or_default()[1..];
+            syllable = if stem.ends_with("sh") || stem.ends_with("ch") || stem.ends_with('j') {
+                format!("{}{}", stem, glide)
+            } else {
+                format!("{}y{}", stem, glide)
+            };
+            i += 1;
+        } else if let Some(small) = next.filter(|next| SMALL_VOWELS.contains(next)).filter(|_| base.len() > 1 || c == 'う') {
+            // ファ → fa, ティ → ti, シェ → she, ウィ → wi
+            let stem = if c == 'う' { "w" } else { base.trim_end_matches(['a', 'i', 'u', 'e', 'o']) };
+            syllable = format!("{}{}", stem, romaji(small).unwrap_or_default());
+            i += 1;
+        }
+
+        let vowel = syllable.chars().last();
+        let long_vowel = syllable.len() == 1
+            && matches!((last_vowel, vowel), (Some('o'), Some('o' | 'u')) | (Some('u'), Some('u')));
+        if !long_vowel {
+            if double_next {
+                match syllable.as_bytes()[0] {
+                    b'c' => output.push('t'),
+                    first if !b"aiueon".contains(&first) => output.push(first as char),
+                    _ => {}
+                }
+            }
+            output.push_str(&syllable);
+        }
+
+        last_vowel = vowel;
+        double_next = false;
+        i += 1;
+    }
+
+    output
+}
+
+/// Lowercase romaji with only letters and digits kept, so "Shingeki no Kyojin"
+/// and しんげきのきょじん both become "shingekinokyojin"
+pub fn romaji_key(text: &str) -> String {
+    kana_to_romaji(text)
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Search keys for a title and its synonyms, without duplicates or empty keys
+pub fn romaji_titles<'a>(titles: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for key in titles.into_iter().map(romaji_key) {
+        if !key.is_empty() && !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_monographs() {
+        assert_eq!(kana_to_romaji("しんげき"), "shingeki");
+        assert_eq!(kana_to_romaji("つなみ"), "tsunami");
+        assert_eq!(kana_to_romaji("ふじさん"), "fujisan");
+        assert_eq!(kana_to_romaji("シンゲキ"), "shingeki");
+        assert_eq!(kana_to_romaji("ナルト"), "naruto");
+    }
+
+    #[test]
+    fn test_digraphs() {
+        assert_eq!(kana_to_romaji("きょじん"), "kyojin");
+        assert_eq!(kana_to_romaji("しゃしん"), "shashin");
+        assert_eq!(kana_to_romaji("ちゃ"), "cha");
+        assert_eq!(kana_to_romaji("じゅう"), "ju");
+        assert_eq!(kana_to_romaji("りょこう"), "ryoko");
+        assert_eq!(kana_to_romaji("ピョン"), "pyon");
+        assert_eq!(kana_to_romaji("ファイト"), "faito");
+        assert_eq!(kana_to_romaji("ティー"), "ti");
+        assert_eq!(kana_to_romaji("ウィッチ"), "witchi");
+        assert_eq!(kana_to_romaji("シェフ"), "shefu");
+        assert_eq!(kana_to_romaji("ヴァイオレット"), "vaioretto");
+    }
+
+    #[test]
+    fn test_sokuon() {
+        assert_eq!(kana_to_romaji("がっこう"), "gakko");
+        assert_eq!(kana_to_romaji("まっちゃ"), "matcha");
+        assert_eq!(kana_to_romaji("ハッピー"), "happi");
+        assert_eq!(kana_to_romaji("いっしょ"), "issho");
+    }
+
+    #[test]
+    fn test_long_vowels() {
+        assert_eq!(kana_to_romaji("とうきょう"), "tokyo");
+        assert_eq!(kana_to_romaji("おおさか"), "osaka");
+        assert_eq!(kana_to_romaji("すうがく"), "sugaku");
+        assert_eq!(kana_to_romaji("コーヒー"), "kohi");
+        assert_eq!(kana_to_romaji("ドラゴンボール"), "doragonboru");
+        // Not a long vowel: the vowels belong to different syllables
+        assert_eq!(kana_to_romaji("かわいい"), "kawaii");
+    }
+
+    #[test]
+    fn test_mixed_script_passes_through() {
+        assert_eq!(kana_to_romaji("進撃のきょじん"), "進撃nokyojin");
+        assert_eq!(kana_to_romaji("Re:ゼロ"), "Re:zero");
+        assert!(contains_kana("Re:ゼロ"));
+        assert!(!contains_kana("進撃巨人 Attack on Titan"));
+    }
+
+    #[test]
+    fn test_romaji_key() {
+        assert_eq!(romaji_key("Shingeki no Kyojin"), "shingekinokyojin");
+        assert_eq!(romaji_key("しんげきのきょじん"), "shingekinokyojin");
+        assert_eq!(romaji_key("Kaguya-sama: Love is War"), "kaguyasamaloveiswar");
+        assert_eq!(
+            romaji_titles(["Shingeki no Kyojin", "シンゲキノキョジン", "Attack on Titan", ""]),
+            vec!["shingekinokyojin", "attackontitan"]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+// Live watch sessions for the VideoPlayer
+// The player opens one SSE stream per episode it plays and POSTs its position
+// back instead of polling. The server pushes a ping every 30 seconds and, when
+// the reported bandwidth calls for it, a rendition to switch to. Positions are
+// kept in memory while the stream is open and handed to WatchProgressService
+// once the last stream of that user and episode disconnects.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+use crate::models::PlaybackPosition;
+use crate::services::WatchProgressService;
+
+/// How often an open heartbeat stream is pinged
+pub const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Events a slow client may fall behind by before it misses some
+const CHANNEL_CAPACITY: usize = 16;
+
+/// Renditions recommended by `recommend_quality`, best first, with the
+/// bandwidth in kbit/s each needs to play without stalling
+const RENDITION_BANDWIDTH_KBPS: [(&str, u32); 4] = [
+    ("1080p", 5_000),
+    ("720p", 2_500),
+    ("480p", 1_000),
+    ("360p", 500),
+];
+
+/// Server-driven events on a heartbeat stream
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HeartbeatEvent {
+    Ping,
+    QualitySwitch { quality: String },
+}
+
+/// The rendition to play at `bandwidth_kbps`: the best one it sustains, or the
+/// lowest if it sustains none
+pub fn recommend_quality(bandwidth_kbps: u32) -> &'static str {
+    RENDITION_BANDWIDTH_KBPS
+        .iter()
+        .find(|(_, needed)| bandwidth_kbps >= *needed)
+        .or(RENDITION_BANDWIDTH_KBPS.last())
+        .map(|(quality, _)| *quality)
+        .unwrap_or("360p")
+}
+
+type SessionKey = (String, Uuid);
+
+struct WatchSession {
+    events: broadcast::Sender<HeartbeatEvent>,
+    /// Open streams; the session ends when the last one disconnects
+    connections: usize,
+    last_position: Option<PlaybackPosition>,
+    /// Last rendition recommended, so a switch is only sent when it changes
+    quality: Option<&'static str>,
+}
+
+/// Open watch sessions by user and episode. A user's tabs playing the same
+/// episode share a session; other users' sessions never see its events.
+pub struct WatchHeartbeatService {
+    sessions: Mutex<HashMap<SessionKey, WatchSession>>,
+    watch_progress: Arc<WatchProgressService>,
+}
+
+/// One open heartbeat stream. Dropping it disconnects the stream, and the last
+/// disconnect of a session saves its position.
+pub struct HeartbeatSubscription {
+    pub events: broadcast::Receiver<HeartbeatEvent>,
+    service: Arc<WatchHeartbeatService>,
+    key: SessionKey,
+}
+
+impl WatchHeartbeatService {
+    pub fn new(watch_progress: Arc<WatchProgressService>) -> Self {
+        WatchHeartbeatService {
+            sessions: Mutex::new(HashMap::new()),
+            watch_progress,
+        }
+    }
+
+    /// Open a stream on the user's session for `episode_id`, starting the session if needed
+    pub fn subscribe(self: &Arc<Self>, user_id: &str, episode_id: Uuid) -> HeartbeatSubscription {
+        let key = (user_id.to_string(), episode_id);
+        let mut sessions = self.sessions.lock().unwrap();
+        let session = sessions.entry(key.clone()).or_insert_with(|| WatchSession {
+            events: broadcast::channel(CHANNEL_CAPACITY).0,
+            connections: 0,
+            last_position: None,
+            quality: None,
+        });
+        session.connections += 1;
+
+        HeartbeatSubscription {
+            events: session.events.subscribe(),
+            service: self.clone(),
+            key,
+        }
+    }
+
+    /// Record the position reported on an open session, recommending a
+    /// rendition if `bandwidth_kbps` was measured. False if the user has no
+    /// stream open for the episode, in which case nothing is recorded.
+    pub fn record_position(&self, user_id: &str, position: PlaybackPosition, bandwidth_kbps: Option<u32>) -> bool {
+        let mut sessions = self.sessions.lock().unwrap();
+        let Some(session) = sessions.get_mut(&(user_id.to_string(), position.episode_id)) else {
+            return false;
+        };
+        session.last_position = Some(position);
+
+        if let Some(bandwidth_kbps) = bandwidth_kbps {
+            let quality = recommend_quality(bandwidth_kbps);
+            if session.quality != Some(quality) {
+                session.quality = Some(quality);
+                // No receivers only means every stream is between reconnects
+                let _ = session.events.send(HeartbeatEvent::QualitySwitch { quality: quality.to_string() });
+            }
+        }
+        true
+    }
+
+    /// Number of open sessions
+    pub fn session_count(&self) -> usize {
+        self.sessions.lock().unwrap().len()
+    }
+
+    fn disconnect(&self, key: &SessionKey) {
+        let ended = {
+            let mut sessions = self.sessions.lock().unwrap();
+            match sessions.get_mut(key) {
+                Some(session) if session.connections > 1 => {
+                    session.connections -= 1;
+                    None
+                }
+                Some(_) => sessions.remove(key),
+                None => None,
+            }
+        };
+
+        let Some(position) = ended.and_then(|session| session.last_position) else {
+            return;
+        };
+        let watch_progress = self.watch_progress.clone();
+        let user_id = key.0.clone();
+        // Called from Drop, which cannot await the save
+        tokio::spawn(async move {
+            if let Err(e) = watch_progress.save_position(&user_id, &position).await {
+                tracing::warn!("Failed to save playback position for {} on disconnect: {}", user_id, e);
+            }
+        });
+    }
+}
+
+impl Drop for HeartbeatSubscription {
+    fn drop(&mut self) {
+        self.service.disconnect(&self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recommend_quality() {
+        assert_eq!(recommend_quality(8_000), "1080p");
+        assert_eq!(recommend_quality(5_000), "1080p");
+        assert_eq!(recommend_quality(3_000), "720p");
+        assert_eq!(recommend_quality(1_200), "480p");
+        assert_eq!(recommend_quality(600), "360p");
+        // Below every rendition the lowest still plays best
+        assert_eq!(recommend_quality(100), "360p");
+    }
+
+    #[test]
+    fn test_events_serialize_with_their_type() {
+        assert_eq!(serde_json::to_value(HeartbeatEvent::Ping).unwrap(), serde_json::json!({ "type": "ping" }));
+        assert_eq!(
+            serde_json::to_value(HeartbeatEvent::QualitySwitch { quality: "720p".to_string() }).unwrap(),
+            serde_json::json!({ "type": "quality_switch", "quality": "720p" })
+        );
+    }
+}
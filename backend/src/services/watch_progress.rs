@@ -0,0 +1,167 @@
+// Playback position store for resuming episodes
+// The VideoPlayer saves a heartbeat every few seconds, so positions live in the
+// cache (hot for a week after the last save) and a background task flushes them
+// to the `watch_progress` table, which serves reads once the cache entry is gone.
+
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+use crate::models::{ContinueWatchingItem, PlaybackPosition, WatchProgress};
+use crate::services::{CacheService, DatabaseService};
+
+/// How long a position stays in the cache after its last save
+pub const HOT_PROGRESS_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// How often cached positions are flushed to the database
+pub const PROGRESS_FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Positions read per hold of the cache lock, so heartbeats from other
+/// requests get in between batches
+const PROGRESS_BATCH: usize = 100;
+
+pub struct WatchProgressService {
+    cache: Arc<Mutex<CacheService>>,
+    db: Arc<DatabaseService>,
+}
+
+impl WatchProgressService {
+    pub fn new(cache: Arc<Mutex<CacheService>>, db: Arc<DatabaseService>) -> Self {
+        WatchProgressService { cache, db }
+    }
+
+    /// Record a position in the cache, writing straight to the database if the cache is down
+    pub async fn save_position(&self, user_id: &str, position: &PlaybackPosition) -> Result<()> {
+        let cached = self.cache.lock().await
+            .store_progress(user_id, position, HOT_PROGRESS_TTL)
+            .await;
+
+        if let Err(e) = cached {
+            tracing::warn!("Failed to cache playback position for {}, writing through: {}", user_id, e);
+            self.db.save_playback_position(user_id, position).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_position(&self, user_id: &str, episode_id: Uuid) -> Result<Option<PlaybackPosition>> {
+        let cached = self.cache.lock().await
+            .get_progress(user_id, &episode_id.to_string())
+            .await;
+
+        match cached {
+            Ok(Some(position)) => Ok(Some(position)),
+            Ok(None) => self.db.get_playback_position(user_id, episode_id).await,
+            Err(e) => {
+                tracing::warn!("Failed to read cached playback position for {}: {}", user_id, e);
+                self.db.get_playback_position(user_id, episode_id).await
+            }
+        }
+    }
+
+    /// Every cached position of `user_id`, read from the per-user index
+    async fn cached_positions(&self, user_id: &str) -> Result<Vec<PlaybackPosition>> {
+        let episode_ids = self.cache.lock().await.progress_episode_ids(user_id).await?;
+        let mut positions = Vec::with_capacity(episode_ids.len());
+        
+        for batch in episode_ids.chunks(PROGRESS_BATCH) {
+            positions.extend(self.cache.lock().await.get_progress_many(user_id, batch).await?);
+        }
+        
+        Ok(positions)
+    }
+
+    /// The position in whichever episode of the anime the user played most recently
+    pub async fn latest_for_anime(&self, user_id: &str, anime_id: Uuid) -> Result<Option<PlaybackPosition>> {
+        let hot = match self.cached_positions(user_id).await {
+            Ok(positions) => positions
+                .into_iter()
+                .filter(|position| position.anime_id == anime_id)
+                .max_by_key(|position| position.updated_at),
+            Err(e) => {
+                tracing::warn!("Failed to scan cached playback positions for {}: {}", user_id, e);
+                None
+            }
+        };
+
+        let cold = self.db.get_latest_playback_position(user_id, anime_id).await?;
+
+        Ok(match (hot, cold) {
+            (Some(hot), Some(cold)) => Some(if hot.updated_at >= cold.updated_at { hot } else { cold }),
+            (hot, cold) => hot.or(cold),
+        })
+    }
+
+    /// Unfinished episodes from the watch history, with positions refreshed from
+    /// any newer cached heartbeat. Newest first, at most one per anime.
+    pub async fn continue_watching(&self, user_id: &str, limit: usize) -> Result<Vec<ContinueWatchingItem>> {
+        let mut items = self.db.get_continue_watching(user_id, limit).await?;
+        
+        let hot = match self.cached_positions(user_id).await {
+            Ok(positions) => positions,
+            Err(e) => {
+                tracing::warn!("Failed to scan cached playback positions for {}: {}", user_id, e);
+                Vec::new()
+            }
+        };
+        
+        for position in hot {
+            let Some(item) = items.iter_mut().find(|item| item.episode_id == position.episode_id) else { continue };
+            if position.updated_at > item.watched_at {
+                item.progress_ms = position.position as u64 * 1000;
+                if position.duration > 0 {
+                    item.total_duration_ms = position.duration as u64 * 1000;
+                }
+                item.watched_at = position.updated_at;
+            }
+        }
+        
+        // A fresher heartbeat may have reached the end of the episode
+        items.retain(|item| {
+            !WatchProgress::is_completed((item.progress_ms / 1000) as u32, (item.total_duration_ms / 1000) as u32, false)
+        });
+        items.sort_by_key(|item| std::cmp::Reverse(item.watched_at));
+        
+        Ok(items)
+    }
+    
+    /// Persist every cached position saved since the previous flush, a batch
+    /// at a time. Returns the number of positions written.
+    pub async fn flush(&self) -> Result<usize> {
+        let dirty = self.cache.lock().await.dirty_progress().await?;
+        let mut flushed = 0;
+
+        for batch in dirty.chunks(PROGRESS_BATCH) {
+            let positions = self.cache.lock().await.take_dirty_progress(batch).await?;
+
+            for (user_id, position) in positions {
+                if let Err(e) = self.db.save_playback_position(&user_id, &position).await {
+                    // The next flush retries this batch; later ones are still marked
+                    if let Err(mark) = self.cache.lock().await.mark_progress_dirty(batch).await {
+                        tracing::warn!("Failed to re-mark unflushed playback positions: {}", mark);
+                    }
+                    return Err(e);
+                }
+                flushed += 1;
+            }
+        }
+
+        Ok(flushed)
+    }
+}
+
+/// Background task that periodically flushes cached positions to the database
+pub async fn watch_progress_flush_worker(service: Arc<WatchProgressService>) {
+    let mut interval = tokio::time::interval(PROGRESS_FLUSH_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        match service.flush().await {
+            Ok(0) => {}
+            Ok(flushed) => tracing::debug!("Flushed {} playback positions", flushed),
+            Err(e) => tracing::warn!("Failed to flush playback positions: {}", e),
+        }
+    }
+}
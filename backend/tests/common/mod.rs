@@ -1,7 +1,8 @@
 // Common test utilities for integration and contract tests
+// Every test file includes this module and uses only part of it
+#![allow(dead_code)]
 
 use kensho_backend::db::connection::AppState;
-use once_cell::sync::Lazy;
 use std::net::SocketAddr;
 use uuid::Uuid;
 
@@ -12,14 +13,23 @@ pub struct TestApp {
 }
 
 pub async fn spawn_app() -> TestApp {
+    spawn_app_with(|_| {}).await
+}
+
+/// Like `spawn_app`, with a hook to adjust configuration before the router is built
+pub async fn spawn_app_with(configure: impl FnOnce(&mut AppState)) -> TestApp {
     // Use a unique database for each test
     let db_name = format!("test_{}", Uuid::new_v4().to_string().replace("-", ""));
     let database_url = format!("memory://{}", db_name);
     let redis_url = "redis://:kensho_redis_pass@localhost:6379".to_string();
     let jwt_secret = "test_secret_key_for_testing_only".to_string();
     
+    // Suites create their fixtures anonymously; tests of the admin guard
+    // turn it back on through `spawn_app_with`
+    std::env::set_var("KENSHO_OPEN_WRITE", "true");
+    
     // Create application state
-    let state = AppState::new(&database_url, &redis_url, jwt_secret)
+    let mut state = AppState::new(&database_url, &redis_url, jwt_secret)
         .await
         .expect("Failed to create application state");
     
@@ -28,6 +38,13 @@ pub async fn spawn_app() -> TestApp {
         .await
         .expect("Failed to initialize database schema");
     
+    // Every suite shares 127.0.0.1 and one Redis, so per-IP limits would trip
+    // across unrelated tests; rate limit tests turn them back on
+    state.rate_limit.limit_anonymous = false;
+    state.rate_limit.routes.clear();
+    
+    configure(&mut state);
+    
     // Build the application
     let app = kensho_backend::api::routes::create_router(state.clone());
     
@@ -40,7 +57,7 @@ pub async fn spawn_app() -> TestApp {
     
     // Spawn the server in the background
     tokio::spawn(async move {
-        axum::serve(listener, app)
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
             .await
             .expect("Failed to start server");
     });
@@ -50,7 +67,7 @@ pub async fn spawn_app() -> TestApp {
     
     // Wait for server to be ready
     for _ in 0..10 {
-        if client.get(&format!("{}/api/health", address))
+        if client.get(format!("{}/api/health", address))
             .send()
             .await
             .is_ok() 
@@ -67,21 +84,11 @@ pub async fn spawn_app() -> TestApp {
     }
 }
 
-// Helper function to create test authentication token
-pub fn create_test_token(state: &AppState) -> String {
-    use jsonwebtoken::{encode, Header, EncodingKey};
-    use kensho_backend::models::Claims;
-    use chrono::{Utc, Duration};
-    
-    let claims = Claims {
-        sub: Uuid::new_v4().to_string(),
-        exp: (Utc::now() + Duration::hours(1)).timestamp() as usize,
-        iat: Utc::now().timestamp() as usize,
-    };
-    
-    encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(state.jwt_secret.as_ref()),
-    ).expect("Failed to create test token")
-}
\ No newline at end of file
+// Helper function to create test authentication token, backed by a real session
+pub async fn create_test_token(state: &AppState) -> String {
+    state.auth.lock().await
+        .create_session(&Uuid::new_v4().to_string(), String::new())
+        .await
+        .expect("Failed to create test session")
+        .token
+}
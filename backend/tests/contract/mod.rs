@@ -1,4 +1,6 @@
 // Contract tests module - verifies API endpoints match OpenAPI specification
+// Each test file includes ../common/mod.rs for itself
+#![allow(clippy::duplicate_mod)]
 
 pub mod test_anime_get;
 pub mod test_search;
@@ -7,4 +9,17 @@ pub mod test_episodes_get;
 pub mod test_auth_login;
 pub mod test_auth_logout;
 pub mod test_auth_refresh;
-pub mod test_stream;
\ No newline at end of file
+pub mod test_stream;
+pub mod test_recommendations;
+pub mod test_watchlist;
+pub mod test_episodes_stream;
+pub mod test_episodes_create;
+pub mod test_catalog_write_auth;
+pub mod test_anime_etag;
+pub mod test_episodes_batch;
+pub mod test_tags;
+pub mod test_episodes_air_dates;
+pub mod test_season_calendar;
+#[cfg(feature = "metrics")]
+pub mod test_health_metrics;
+pub mod test_anime_list_sort;
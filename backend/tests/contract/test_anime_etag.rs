@@ -0,0 +1,154 @@
+// Contract test: ETag / If-None-Match on GET /api/anime/{id}, its episode list
+// and seasonal browse
+
+use serde_json::json;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp) -> String {
+    create_anime_in(app, 2021).await
+}
+
+async fn create_anime_in(app: &TestApp, year: u16) -> String {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": "Cached Anime",
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "summer", "year": year },
+            "synopsis": "Test anime for conditional requests",
+            "poster_url": "https://example.com/cached.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    created["id"].as_str().unwrap().to_string()
+}
+
+async fn conditional_get(app: &TestApp, path: &str, etag: Option<&str>) -> reqwest::Response {
+    let mut request = app.client.get(format!("{}{}", app.address, path));
+    if let Some(etag) = etag {
+        request = request.header("If-None-Match", etag);
+    }
+    request.send().await.expect("Failed to send request")
+}
+
+fn etag_of(response: &reqwest::Response) -> String {
+    response.headers()
+        .get("etag")
+        .expect("Response must carry an ETag")
+        .to_str()
+        .unwrap()
+        .to_string()
+}
+
+#[tokio::test]
+async fn anime_detail_revalidates_until_updated() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+    let path = format!("/api/anime/{}", anime_id);
+
+    let first = conditional_get(&app, &path, None).await;
+    assert_eq!(first.status().as_u16(), 200);
+    let etag = etag_of(&first);
+
+    let second = conditional_get(&app, &path, Some(&etag)).await;
+    assert_eq!(second.status().as_u16(), 304);
+    assert_eq!(etag_of(&second), etag);
+    assert!(second.bytes().await.unwrap().is_empty());
+
+    let response = app.client
+        .patch(format!("{}{}", app.address, path))
+        .json(&json!({ "synopsis": "Updated synopsis" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+
+    let third = conditional_get(&app, &path, Some(&etag)).await;
+    assert_eq!(third.status().as_u16(), 200);
+    assert_ne!(etag_of(&third), etag);
+    let body: serde_json::Value = third.json().await.unwrap();
+    assert_eq!(body["synopsis"], "Updated synopsis");
+}
+
+#[tokio::test]
+async fn episode_list_revalidates_until_episodes_change() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+    let path = format!("/api/anime/{}/episodes", anime_id);
+
+    let first = conditional_get(&app, &path, None).await;
+    assert_eq!(first.status().as_u16(), 200);
+    let etag = etag_of(&first);
+
+    assert_eq!(conditional_get(&app, &path, Some(&etag)).await.status().as_u16(), 304);
+    assert_eq!(conditional_get(&app, &path, Some("\"stale\"")).await.status().as_u16(), 200);
+
+    app.client
+        .post(format!("{}{}", app.address, path))
+        .json(&json!({ "episodes": [{ "episode_number": 1, "title": "Pilot" }] }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(conditional_get(&app, &path, Some(&etag)).await.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn season_browse_revalidates_until_the_season_changes() {
+    let app = spawn_app().await;
+    let anime_id = create_anime_in(&app, 1979).await;
+    let path = "/api/browse/season/1979/summer";
+
+    let first = conditional_get(&app, path, None).await;
+    assert_eq!(first.status().as_u16(), 200);
+    let etag = etag_of(&first);
+
+    assert_eq!(conditional_get(&app, path, Some(&etag)).await.status().as_u16(), 304);
+    let mismatch = conditional_get(&app, path, Some("\"stale\"")).await;
+    assert_eq!(mismatch.status().as_u16(), 200);
+    assert_eq!(etag_of(&mismatch), etag);
+
+    app.client
+        .patch(format!("{}/api/anime/{}", app.address, anime_id))
+        .json(&json!({ "title": "Renamed Cached Anime" }))
+        .send()
+        .await
+        .unwrap();
+
+    let changed = conditional_get(&app, path, Some(&etag)).await;
+    assert_eq!(changed.status().as_u16(), 200);
+    assert_ne!(etag_of(&changed), etag);
+}
+
+#[tokio::test]
+async fn cached_response_keeps_its_etag() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+    let path = format!("/api/anime/{}", anime_id);
+
+    let first = conditional_get(&app, &path, None).await;
+    let etag = etag_of(&first);
+
+    // Served from the response cache, with the ETag stored alongside the body
+    let second = conditional_get(&app, &path, None).await;
+    assert_eq!(second.headers()["x-cache"], "HIT");
+    assert_eq!(etag_of(&second), etag);
+
+    let revalidated = conditional_get(&app, &path, Some(&etag)).await;
+    assert_eq!(revalidated.status().as_u16(), 304);
+    assert_eq!(revalidated.headers()["x-cache"], "HIT");
+    assert!(revalidated.bytes().await.unwrap().is_empty());
+}
@@ -31,7 +31,7 @@ async fn get_anime_returns_200_with_valid_id() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -43,7 +43,7 @@ async fn get_anime_returns_200_with_valid_id() {
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/anime/{}", app.address, anime_id))
+        .get(format!("{}/api/anime/{}", app.address, anime_id))
         .send()
         .await
         .expect("Failed to send request");
@@ -65,7 +65,7 @@ async fn get_anime_returns_404_with_invalid_id() {
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/anime/{}", app.address, non_existent_id))
+        .get(format!("{}/api/anime/{}", app.address, non_existent_id))
         .send()
         .await
         .expect("Failed to send request");
@@ -84,7 +84,7 @@ async fn get_anime_returns_400_with_invalid_uuid() {
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/anime/not-a-valid-uuid", app.address))
+        .get(format!("{}/api/anime/not-a-valid-uuid", app.address))
         .send()
         .await
         .expect("Failed to send request");
@@ -116,7 +116,7 @@ async fn get_anime_response_matches_openapi_schema() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -127,7 +127,7 @@ async fn get_anime_response_matches_openapi_schema() {
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/anime/{}", app.address, anime_id))
+        .get(format!("{}/api/anime/{}", app.address, anime_id))
         .send()
         .await
         .expect("Failed to send request");
@@ -0,0 +1,128 @@
+// Contract test GET /api/anime?sort=&order=
+
+use chrono::{Duration, Utc};
+use kensho_backend::models::{Anime, AnimeSeason, AnimeStatus, AnimeType, ImdbData, Season};
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+/// Three anime created a day apart, oldest first; the unrated one is in the middle
+async fn seed(app: &TestApp) {
+    let catalog = [
+        ("Bebop", 26, Some(8.9), 3),
+        ("Alchemist", 64, None, 2),
+        ("Clannad", 23, Some(8.0), 1),
+    ];
+    for (title, episodes, rating, days_ago) in catalog {
+        let created_at = Utc::now() - Duration::days(days_ago);
+        let anime = Anime {
+            id: uuid::Uuid::new_v4(),
+            title: title.to_string(),
+            synonyms: vec![],
+            sources: vec![],
+            episodes,
+            status: AnimeStatus::Finished,
+            anime_type: AnimeType::TV,
+            anime_season: AnimeSeason { season: Season::Spring, year: 2006 },
+            synopsis: String::new(),
+            poster_url: "https://example.com/sort.jpg".to_string(),
+            posters: vec![],
+            stored_episode_count: 0,
+            popularity: 0.0,
+            romaji_titles: vec![],
+            imdb: rating.map(|rating| ImdbData {
+                id: String::new(),
+                rating,
+                votes: 0,
+                last_updated: Utc::now(),
+            }),
+            created_at,
+            updated_at: created_at,
+        };
+        app.state.db.create_anime(&anime).await.expect("Failed to create anime");
+    }
+}
+
+async fn titles(app: &TestApp, query: &str) -> Vec<String> {
+    let response = app.client
+        .get(format!("{}/api/anime?{}", app.address, query))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200, "{}", query);
+    
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["items"].as_array().unwrap()
+        .iter()
+        .map(|item| item["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn sort_by_title() {
+    let app = spawn_app().await;
+    seed(&app).await;
+    
+    assert_eq!(titles(&app, "sort=title").await, ["Alchemist", "Bebop", "Clannad"]);
+    assert_eq!(titles(&app, "sort=title&order=desc").await[0], "Clannad");
+}
+
+#[tokio::test]
+async fn sort_by_rating_puts_unrated_last_in_both_orders() {
+    let app = spawn_app().await;
+    seed(&app).await;
+    
+    assert_eq!(titles(&app, "sort=rating&order=desc").await, ["Bebop", "Clannad", "Alchemist"]);
+    assert_eq!(titles(&app, "sort=rating&order=asc").await, ["Clannad", "Bebop", "Alchemist"]);
+}
+
+#[tokio::test]
+async fn sort_by_episode_count() {
+    let app = spawn_app().await;
+    seed(&app).await;
+    
+    assert_eq!(titles(&app, "sort=episode_count").await[0], "Alchemist");
+    assert_eq!(titles(&app, "sort=episode_count&order=asc").await[0], "Clannad");
+}
+
+#[tokio::test]
+async fn sort_by_created_at() {
+    let app = spawn_app().await;
+    seed(&app).await;
+    
+    assert_eq!(titles(&app, "sort=created_at").await, ["Clannad", "Alchemist", "Bebop"]);
+    assert_eq!(titles(&app, "sort=created_at&order=asc").await[0], "Bebop");
+}
+
+#[tokio::test]
+async fn sorted_listing_pages_by_offset_not_cursor() {
+    let app = spawn_app().await;
+    seed(&app).await;
+    
+    assert_eq!(titles(&app, "sort=title&limit=2&offset=2").await, ["Clannad"]);
+    
+    // Cursors belong to the default order
+    let first_page: serde_json::Value = app.client
+        .get(format!("{}/api/anime?limit=1", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request")
+        .json()
+        .await
+        .unwrap();
+    let cursor = first_page["next_cursor"].as_str().unwrap();
+    let response = app.client
+        .get(format!("{}/api/anime?sort=title&cursor={}", app.address, cursor))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 400);
+    
+    let response = app.client
+        .get(format!("{}/api/anime?sort=popularity", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 400);
+}
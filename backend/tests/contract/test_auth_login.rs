@@ -19,7 +19,7 @@ async fn auth_login_returns_200_with_valid_credentials() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/login", app.address))
+        .post(format!("{}/api/auth/login", app.address))
         .json(&login_data)
         .send()
         .await
@@ -48,7 +48,7 @@ async fn auth_login_returns_401_with_invalid_credentials() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/login", app.address))
+        .post(format!("{}/api/auth/login", app.address))
         .json(&login_data)
         .send()
         .await
@@ -73,7 +73,7 @@ async fn auth_login_returns_400_with_missing_username() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/login", app.address))
+        .post(format!("{}/api/auth/login", app.address))
         .json(&login_data)
         .send()
         .await
@@ -97,7 +97,7 @@ async fn auth_login_returns_400_with_missing_password() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/login", app.address))
+        .post(format!("{}/api/auth/login", app.address))
         .json(&login_data)
         .send()
         .await
@@ -122,7 +122,7 @@ async fn auth_login_response_matches_openapi_schema() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/login", app.address))
+        .post(format!("{}/api/auth/login", app.address))
         .json(&login_data)
         .send()
         .await
@@ -160,7 +160,7 @@ async fn auth_login_jwt_contains_valid_claims() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/login", app.address))
+        .post(format!("{}/api/auth/login", app.address))
         .json(&login_data)
         .send()
         .await
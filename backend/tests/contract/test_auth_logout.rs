@@ -1,7 +1,6 @@
 // T013: Contract test POST /api/auth/logout
 // Reference: contracts/openapi.yaml lines 187-202
 
-use serde_json::json;
 
 #[path = "../common/mod.rs"]
 mod common;
@@ -11,11 +10,11 @@ use common::{spawn_app, create_test_token};
 async fn auth_logout_returns_200_with_valid_token() {
     // Arrange
     let app = spawn_app().await;
-    let token = create_test_token();
+    let token = create_test_token(&app.state).await;
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/logout", app.address))
+        .post(format!("{}/api/auth/logout", app.address))
         .header("Authorization", format!("Bearer {}", token))
         .send()
         .await
@@ -36,7 +35,7 @@ async fn auth_logout_returns_401_without_token() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/logout", app.address))
+        .post(format!("{}/api/auth/logout", app.address))
         .send()
         .await
         .expect("Failed to send request");
@@ -57,7 +56,7 @@ async fn auth_logout_returns_401_with_invalid_token() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/logout", app.address))
+        .post(format!("{}/api/auth/logout", app.address))
         .header("Authorization", format!("Bearer {}", invalid_token))
         .send()
         .await
@@ -81,7 +80,7 @@ async fn auth_logout_returns_401_with_expired_token() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/logout", app.address))
+        .post(format!("{}/api/auth/logout", app.address))
         .header("Authorization", format!("Bearer {}", expired_token))
         .send()
         .await
@@ -98,11 +97,11 @@ async fn auth_logout_returns_401_with_expired_token() {
 async fn auth_logout_invalidates_token_for_future_requests() {
     // Arrange
     let app = spawn_app().await;
-    let token = create_test_token();
+    let token = create_test_token(&app.state).await;
     
     // First logout
     let logout_response = app.client
-        .post(&format!("{}/api/auth/logout", app.address))
+        .post(format!("{}/api/auth/logout", app.address))
         .header("Authorization", format!("Bearer {}", token))
         .send()
         .await
@@ -112,7 +111,7 @@ async fn auth_logout_invalidates_token_for_future_requests() {
     
     // Act - Try to use the same token again
     let response = app.client
-        .get(&format!("{}/api/anime", app.address))
+        .get(format!("{}/api/anime", app.address))
         .header("Authorization", format!("Bearer {}", token))
         .send()
         .await
@@ -132,11 +131,11 @@ async fn auth_logout_invalidates_token_for_future_requests() {
 async fn auth_logout_response_matches_openapi_schema() {
     // Arrange
     let app = spawn_app().await;
-    let token = create_test_token();
+    let token = create_test_token(&app.state).await;
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/logout", app.address))
+        .post(format!("{}/api/auth/logout", app.address))
         .header("Authorization", format!("Bearer {}", token))
         .send()
         .await
@@ -5,7 +5,7 @@ use serde_json::json;
 
 #[path = "../common/mod.rs"]
 mod common;
-use common::{spawn_app, create_test_token};
+use common::spawn_app;
 
 #[tokio::test]
 async fn auth_refresh_returns_200_with_valid_refresh_token() {
@@ -18,7 +18,7 @@ async fn auth_refresh_returns_200_with_valid_refresh_token() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/refresh", app.address))
+        .post(format!("{}/api/auth/refresh", app.address))
         .json(&refresh_data)
         .send()
         .await
@@ -46,7 +46,7 @@ async fn auth_refresh_returns_401_with_invalid_refresh_token() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/refresh", app.address))
+        .post(format!("{}/api/auth/refresh", app.address))
         .json(&refresh_data)
         .send()
         .await
@@ -71,7 +71,7 @@ async fn auth_refresh_returns_401_with_expired_refresh_token() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/refresh", app.address))
+        .post(format!("{}/api/auth/refresh", app.address))
         .json(&refresh_data)
         .send()
         .await
@@ -98,7 +98,7 @@ async fn auth_refresh_returns_400_with_missing_refresh_token() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/refresh", app.address))
+        .post(format!("{}/api/auth/refresh", app.address))
         .json(&refresh_data)
         .send()
         .await
@@ -122,7 +122,7 @@ async fn auth_refresh_returns_new_tokens() {
     
     // Act - First refresh
     let response1 = app.client
-        .post(&format!("{}/api/auth/refresh", app.address))
+        .post(format!("{}/api/auth/refresh", app.address))
         .json(&refresh_data)
         .send()
         .await
@@ -138,7 +138,7 @@ async fn auth_refresh_returns_new_tokens() {
     });
     
     let response2 = app.client
-        .post(&format!("{}/api/auth/refresh", app.address))
+        .post(format!("{}/api/auth/refresh", app.address))
         .json(&refresh_data2)
         .send()
         .await
@@ -165,7 +165,7 @@ async fn auth_refresh_response_matches_openapi_schema() {
     
     // Act
     let response = app.client
-        .post(&format!("{}/api/auth/refresh", app.address))
+        .post(format!("{}/api/auth/refresh", app.address))
         .json(&refresh_data)
         .send()
         .await
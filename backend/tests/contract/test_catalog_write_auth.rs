@@ -0,0 +1,108 @@
+// Contract test: anime and episode mutations require an admin session
+
+use kensho_backend::middleware::WriteAccessConfig;
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app_with, TestApp};
+
+async fn guarded_app() -> TestApp {
+    spawn_app_with(|state| state.write_access = WriteAccessConfig { open_write: false }).await
+}
+
+async fn session_token(app: &TestApp, admin: bool) -> String {
+    app.state.auth.lock().await
+        .create_session_with_role(&Uuid::new_v4().to_string(), String::new(), admin)
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+fn anime_payload() -> serde_json::Value {
+    json!({
+        "title": "Guarded Anime",
+        "synonyms": [],
+        "sources": [],
+        "episodes": 12,
+        "status": "FINISHED",
+        "anime_type": "TV",
+        "anime_season": { "season": "winter", "year": 2021 },
+        "synopsis": "Test anime for write authorization",
+        "poster_url": "https://example.com/guarded.jpg",
+        "tags": []
+    })
+}
+
+async fn create_anime(app: &TestApp, token: Option<&str>) -> reqwest::Response {
+    let mut request = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&anime_payload());
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    request.send().await.expect("Failed to send create request")
+}
+
+#[tokio::test]
+async fn non_admin_writes_are_forbidden() {
+    let app = guarded_app().await;
+    let admin_token = session_token(&app, true).await;
+    let user_token = session_token(&app, false).await;
+
+    let created: serde_json::Value = create_anime(&app, Some(&admin_token)).await.json().await.unwrap();
+    let anime_id = created["id"].as_str().unwrap();
+
+    let responses = vec![
+        create_anime(&app, Some(&user_token)).await,
+        app.client
+            .patch(format!("{}/api/anime/{}", app.address, anime_id))
+            .header("Authorization", format!("Bearer {}", user_token))
+            .json(&json!({ "title": "Renamed" }))
+            .send()
+            .await
+            .unwrap(),
+        app.client
+            .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+            .header("Authorization", format!("Bearer {}", user_token))
+            .json(&json!({ "episodes": [{ "episode_number": 1, "title": "Pilot" }] }))
+            .send()
+            .await
+            .unwrap(),
+    ];
+
+    for response in responses {
+        assert_eq!(response.status().as_u16(), 403);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["code"], "FORBIDDEN");
+        assert!(body["message"].is_string());
+    }
+}
+
+#[tokio::test]
+async fn anonymous_writes_are_unauthorized() {
+    let app = guarded_app().await;
+
+    let response = create_anime(&app, None).await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn admin_can_create_and_update_anime() {
+    let app = guarded_app().await;
+    let token = session_token(&app, true).await;
+
+    let response = create_anime(&app, Some(&token)).await;
+    assert_eq!(response.status().as_u16(), 201);
+    let created: serde_json::Value = response.json().await.unwrap();
+
+    let response = app.client
+        .patch(format!("{}/api/anime/{}", app.address, created["id"].as_str().unwrap()))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "title": "Renamed" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+}
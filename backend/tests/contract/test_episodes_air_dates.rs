@@ -0,0 +1,94 @@
+// Contract test GET /api/anime/{id}/episodes?from=&to=
+
+use serde_json::json;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime_with_episodes(app: &TestApp) -> String {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": "Air Date Anime",
+            "synonyms": [],
+            "sources": [],
+            "episodes": 5,
+            "status": "ONGOING",
+            "anime_type": "TV",
+            "anime_season": { "season": "spring", "year": 2024 },
+            "synopsis": "Test anime for air date filtering",
+            "poster_url": "https://example.com/airdate.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    let anime_id = created["id"].as_str().unwrap().to_string();
+
+    let response = app.client
+        .post(format!("{}/api/anime/{}/episodes/batch", app.address, anime_id))
+        .json(&json!({ "episodes": [
+            {"episode_number": 1, "air_date": "2024-04-01"},
+            {"episode_number": 2, "air_date": "2024-04-08"},
+            {"episode_number": 3},
+            {"episode_number": 4, "air_date": "2024-04-15"},
+            {"episode_number": 5, "air_date": "2024-04-22"}
+        ]}))
+        .send()
+        .await
+        .expect("Failed to create episodes");
+    assert_eq!(response.status().as_u16(), 201);
+
+    anime_id
+}
+
+async fn episode_numbers(app: &TestApp, anime_id: &str, query: &str) -> Vec<u64> {
+    let response = app.client
+        .get(format!("{}/api/anime/{}/episodes?{}", app.address, anime_id, query))
+        .send()
+        .await
+        .expect("Failed to get episodes");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    let numbers: Vec<u64> = body["items"].as_array().unwrap()
+        .iter()
+        .map(|episode| episode["episode_number"].as_u64().unwrap())
+        .collect();
+    assert_eq!(body["total"].as_u64().unwrap(), numbers.len() as u64);
+    numbers
+}
+
+#[tokio::test]
+async fn episodes_filter_by_air_date_window() {
+    let app = spawn_app().await;
+    let anime_id = create_anime_with_episodes(&app).await;
+
+    // Without bounds every episode is listed, undated ones included
+    assert_eq!(episode_numbers(&app, &anime_id, "").await, vec![1, 2, 3, 4, 5]);
+
+    // Bounds are inclusive and undated episodes drop out
+    assert_eq!(episode_numbers(&app, &anime_id, "from=2024-04-08&to=2024-04-15").await, vec![2, 4]);
+    assert_eq!(episode_numbers(&app, &anime_id, "from=2024-04-09").await, vec![4, 5]);
+    assert_eq!(episode_numbers(&app, &anime_id, "to=2024-04-08").await, vec![1, 2]);
+    assert!(episode_numbers(&app, &anime_id, "from=2025-01-01").await.is_empty());
+}
+
+#[tokio::test]
+async fn episodes_reject_invalid_air_date_window() {
+    let app = spawn_app().await;
+    let anime_id = create_anime_with_episodes(&app).await;
+
+    for query in ["from=2024-04-15&to=2024-04-01", "from=last-week"] {
+        let response = app.client
+            .get(format!("{}/api/anime/{}/episodes?{}", app.address, anime_id, query))
+            .send()
+            .await
+            .expect("Failed to get episodes");
+        assert_eq!(response.status().as_u16(), 400, "query {}", query);
+    }
+}
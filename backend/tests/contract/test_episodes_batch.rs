@@ -0,0 +1,143 @@
+// Contract test POST /api/anime/{id}/episodes/batch
+
+use serde_json::json;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp) -> String {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": "Partial Batch Anime",
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "spring", "year": 2023 },
+            "synopsis": "Test anime for partial batch episode creation",
+            "poster_url": "https://example.com/partial.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    created["id"].as_str().unwrap().to_string()
+}
+
+async fn post_batch(app: &TestApp, anime_id: &str, episodes: serde_json::Value) -> reqwest::Response {
+    app.client
+        .post(format!("{}/api/anime/{}/episodes/batch", app.address, anime_id))
+        .json(&json!({ "episodes": episodes }))
+        .send()
+        .await
+        .expect("Failed to send request")
+}
+
+async fn episode_numbers(app: &TestApp, anime_id: &str) -> Vec<u64> {
+    let listed: serde_json::Value = app.client
+        .get(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .send()
+        .await
+        .expect("Failed to get episodes")
+        .json()
+        .await
+        .unwrap();
+
+    listed["items"].as_array().unwrap()
+        .iter()
+        .map(|episode| episode["episode_number"].as_u64().unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn batch_creates_every_episode() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+
+    let response = post_batch(&app, &anime_id, json!([
+        {"episode_number": 1, "title": "First", "thumbnail_url": "https://example.com/1.jpg"},
+        {"episode_number": 2, "title": "Second", "duration": 1440}
+    ])).await;
+
+    assert_eq!(response.status().as_u16(), 201);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let created = body["created"].as_array().unwrap();
+    assert_eq!(created.len(), 2);
+    assert_eq!(created[0]["episode_number"], 1);
+    assert_eq!(created[0]["title"], "First");
+    assert_eq!(created[1]["duration"], 1440);
+    assert!(created.iter().all(|episode| uuid::Uuid::parse_str(episode["id"].as_str().unwrap()).is_ok()));
+    assert_eq!(body["errors"].as_array().unwrap().len(), 0);
+
+    assert_eq!(episode_numbers(&app, &anime_id).await, vec![1, 2]);
+}
+
+#[tokio::test]
+async fn batch_reports_existing_episodes_with_multi_status() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+    assert_eq!(post_batch(&app, &anime_id, json!([{"episode_number": 2}])).await.status().as_u16(), 201);
+
+    let response = post_batch(&app, &anime_id, json!([
+        {"episode_number": 1, "title": "First"},
+        {"episode_number": 2, "title": "Second again"},
+        {"episode_number": 3, "title": "Third"}
+    ])).await;
+
+    assert_eq!(response.status().as_u16(), 207);
+    let body: serde_json::Value = response.json().await.unwrap();
+    let created: Vec<u64> = body["created"].as_array().unwrap()
+        .iter()
+        .map(|episode| episode["episode_number"].as_u64().unwrap())
+        .collect();
+    assert_eq!(created, vec![1, 3]);
+
+    let errors = body["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0]["index"], 1);
+    assert_eq!(errors[0]["episode_number"], 2);
+    assert!(errors[0]["message"].is_string());
+
+    assert_eq!(episode_numbers(&app, &anime_id).await, vec![1, 2, 3]);
+
+    // Nothing left to create
+    let response = post_batch(&app, &anime_id, json!([{"episode_number": 3}])).await;
+    assert_eq!(response.status().as_u16(), 409);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["created"].as_array().unwrap().len(), 0);
+    assert_eq!(body["errors"][0]["episode_number"], 3);
+}
+
+#[tokio::test]
+async fn batch_with_repeated_numbers_is_rejected_whole() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+
+    let response = post_batch(&app, &anime_id, json!([
+        {"episode_number": 1, "title": "First"},
+        {"episode_number": 1, "title": "First again"}
+    ])).await;
+
+    assert_eq!(response.status().as_u16(), 422);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "Validation failed");
+    assert!(body["details"]["episodes"].is_object() || body["details"]["episodes"].is_array());
+
+    // Invalid numbers and URLs are rejected the same way
+    for episodes in [
+        json!([{"episode_number": 0}]),
+        json!([{"episode_number": 1, "thumbnail_url": "not a url"}]),
+        json!([]),
+    ] {
+        assert_eq!(post_batch(&app, &anime_id, episodes).await.status().as_u16(), 422);
+    }
+
+    assert!(episode_numbers(&app, &anime_id).await.is_empty());
+}
@@ -0,0 +1,177 @@
+// Contract test POST /api/anime/{id}/episodes
+
+use serde_json::json;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp) -> String {
+    let anime_data = json!({
+        "title": "Episode Batch Anime",
+        "synonyms": [],
+        "sources": [],
+        "episodes": 12,
+        "status": "FINISHED",
+        "anime_type": "TV",
+        "anime_season": {
+            "season": "fall",
+            "year": 2022
+        },
+        "synopsis": "Test anime for batch episode creation",
+        "poster_url": "https://example.com/batch.jpg",
+        "tags": []
+    });
+    
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&anime_data)
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    
+    created["id"].as_str().unwrap().to_string()
+}
+
+async fn list_episodes(app: &TestApp, anime_id: &str) -> serde_json::Value {
+    app.client
+        .get(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .send()
+        .await
+        .expect("Failed to get episodes")
+        .json()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn create_episodes_returns_written_episodes_in_request_order() {
+    // Arrange
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+    
+    // Act
+    let response = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({
+            "episodes": [
+                {"episode_number": 3, "title": "Third"},
+                {"episode_number": 1, "title": "First"}
+            ]
+        }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 201);
+    
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["created"], 2);
+    
+    let episodes = body["episodes"].as_array().unwrap();
+    assert_eq!(episodes.len(), 2);
+    assert_eq!(episodes[0]["episode_number"], 3);
+    assert_eq!(episodes[0]["title"], "Third");
+    assert_eq!(episodes[1]["episode_number"], 1);
+    assert_eq!(episodes[1]["title"], "First");
+    assert!(episodes.iter().all(|e| e["id"].is_string()));
+    
+    // `ids` lists the same episodes, in the same order
+    let ids = body["ids"].as_array().unwrap();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.iter().all(|id| uuid::Uuid::parse_str(id.as_str().unwrap()).is_ok()));
+    assert_eq!(ids[0], episodes[0]["id"]);
+    assert_eq!(ids[1], episodes[1]["id"]);
+}
+
+#[tokio::test]
+async fn create_episodes_rejects_duplicates_within_batch() {
+    // Arrange
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+    
+    // Act
+    let response = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({
+            "episodes": [
+                {"episode_number": 1, "title": "First"},
+                {"episode_number": 2, "title": "Second"},
+                {"episode_number": 1, "title": "First again"}
+            ]
+        }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert: repeats within a batch are a validation error, not a conflict
+    assert_eq!(response.status().as_u16(), 422);
+    
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "Validation failed");
+    assert!(body["details"]["episodes[2].episode_number"].is_string());
+    assert!(body["details"].get("episodes[0].episode_number").is_none());
+    
+    // Nothing from the rejected payload was written
+    assert_eq!(list_episodes(&app, &anime_id).await["total"], 0);
+}
+
+#[tokio::test]
+async fn create_episodes_rejects_existing_numbers_without_partial_writes() {
+    // Arrange
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+    let url = format!("{}/api/anime/{}/episodes", app.address, anime_id);
+    
+    app.client
+        .post(&url)
+        .json(&json!({ "episodes": [{"episode_number": 1, "title": "First"}] }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Act
+    let response = app.client
+        .post(&url)
+        .json(&json!({
+            "episodes": [
+                {"episode_number": 2, "title": "Second"},
+                {"episode_number": 1, "title": "First (Remastered)"}
+            ]
+        }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 409);
+    
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert!(body["details"]["episodes[1].episode_number"].is_string());
+    
+    // Episode 2 was not written either
+    assert_eq!(list_episodes(&app, &anime_id).await["total"], 1);
+}
+
+#[tokio::test]
+async fn create_episodes_rejects_episode_number_zero() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+    
+    let response = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({ "episodes": [{"episode_number": 0}] }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    assert_eq!(response.status().as_u16(), 422);
+    
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "Validation failed");
+    assert!(body["details"]["episodes[0].episode_number"].is_string());
+}
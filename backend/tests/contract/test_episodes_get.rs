@@ -31,7 +31,7 @@ async fn get_episodes_returns_200_with_valid_anime_id() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -69,14 +69,14 @@ async fn get_episodes_returns_200_with_valid_anime_id() {
     });
     
     app.client
-        .post(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .json(&episodes_data)
         .send()
-        .await;
+        .await.expect("Failed to send request");
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .get(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .send()
         .await
         .expect("Failed to send request");
@@ -102,7 +102,7 @@ async fn get_episodes_returns_404_for_non_existent_anime() {
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/anime/{}/episodes", app.address, non_existent_id))
+        .get(format!("{}/api/anime/{}/episodes", app.address, non_existent_id))
         .send()
         .await
         .expect("Failed to send request");
@@ -137,7 +137,7 @@ async fn get_episodes_returns_empty_list_when_no_episodes() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -148,7 +148,7 @@ async fn get_episodes_returns_empty_list_when_no_episodes() {
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .get(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .send()
         .await
         .expect("Failed to send request");
@@ -185,7 +185,7 @@ async fn get_episodes_response_matches_openapi_schema() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -209,14 +209,14 @@ async fn get_episodes_response_matches_openapi_schema() {
     });
     
     app.client
-        .post(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .json(&episodes_data)
         .send()
-        .await;
+        .await.expect("Failed to send request");
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .get(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .send()
         .await
         .expect("Failed to send request");
@@ -276,7 +276,7 @@ async fn get_episodes_sorted_by_episode_number() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -297,14 +297,14 @@ async fn get_episodes_sorted_by_episode_number() {
     });
     
     app.client
-        .post(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .json(&episodes_data)
         .send()
-        .await;
+        .await.expect("Failed to send request");
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .get(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .send()
         .await
         .expect("Failed to send request");
@@ -314,9 +314,9 @@ async fn get_episodes_sorted_by_episode_number() {
     let episodes = episodes_response["episodes"].as_array().unwrap();
     
     assert_eq!(episodes.len(), 5);
-    for i in 0..5 {
+    for (i, episode) in episodes.iter().enumerate() {
         assert_eq!(
-            episodes[i]["episode_number"].as_u64().unwrap(),
+            episode["episode_number"].as_u64().unwrap(),
             (i + 1) as u64,
             "Episodes should be sorted by episode_number"
         );
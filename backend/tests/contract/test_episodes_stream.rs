@@ -0,0 +1,144 @@
+// Contract test GET /api/anime/{id}/episodes/stream
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_long_series(app: &TestApp, episode_count: u32) -> String {
+    let anime_data = json!({
+        "title": "One Piece",
+        "synonyms": [],
+        "sources": [],
+        "episodes": episode_count,
+        "status": "ONGOING",
+        "anime_type": "TV",
+        "anime_season": {
+            "season": "fall",
+            "year": 1999
+        },
+        "synopsis": "A very long series",
+        "poster_url": "https://example.com/op.jpg",
+        "tags": []
+    });
+    
+    let create_response = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&anime_data)
+        .send()
+        .await
+        .expect("Failed to create anime");
+    
+    assert_eq!(create_response.status().as_u16(), 201);
+    let created_anime: serde_json::Value = create_response.json().await.unwrap();
+    let anime_id = created_anime["id"].as_str().unwrap().to_string();
+    
+    // Insert in reverse so ordering must come from the query, not insertion order
+    let episodes: Vec<_> = (1..=episode_count)
+        .rev()
+        .map(|n| json!({ "episode_number": n, "title": format!("Episode {}", n) }))
+        .collect();
+    
+    let response = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({ "episodes": episodes }))
+        .send()
+        .await
+        .expect("Failed to create episodes");
+    
+    assert_eq!(response.status().as_u16(), 201);
+    anime_id
+}
+
+async fn stream_episode_numbers(app: &TestApp, url: &str) -> Vec<u64> {
+    let response = app.client
+        .get(url)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response.headers()["content-type"],
+        "application/x-ndjson"
+    );
+    
+    let body = response.text().await.unwrap();
+    assert!(body.is_empty() || body.ends_with('\n'), "Every record must be newline-terminated");
+    
+    body.lines()
+        .map(|line| {
+            let episode: serde_json::Value = serde_json::from_str(line)
+                .expect("Each line must be a standalone JSON document");
+            assert!(episode["id"].is_string());
+            episode["episode_number"].as_u64().unwrap()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn stream_episodes_yields_all_episodes_in_order() {
+    // Arrange - more episodes than a single page of the cursor
+    let app = spawn_app().await;
+    let anime_id = create_long_series(&app, 150).await;
+    
+    // Act
+    let numbers = stream_episode_numbers(
+        &app,
+        &format!("{}/api/anime/{}/episodes/stream", app.address, anime_id),
+    ).await;
+    
+    // Assert
+    assert_eq!(numbers, (1..=150).collect::<Vec<u64>>());
+}
+
+#[tokio::test]
+async fn stream_episodes_respects_from_and_to() {
+    // Arrange
+    let app = spawn_app().await;
+    let anime_id = create_long_series(&app, 20).await;
+    
+    // Act
+    let numbers = stream_episode_numbers(
+        &app,
+        &format!("{}/api/anime/{}/episodes/stream?from=5&to=9", app.address, anime_id),
+    ).await;
+    
+    // Assert
+    assert_eq!(numbers, vec![5, 6, 7, 8, 9]);
+}
+
+#[tokio::test]
+async fn stream_episodes_rejects_inverted_range() {
+    // Arrange
+    let app = spawn_app().await;
+    let anime_id = create_long_series(&app, 3).await;
+    
+    // Act
+    let response = app.client
+        .get(format!("{}/api/anime/{}/episodes/stream?from=3&to=1", app.address, anime_id))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn stream_episodes_returns_404_for_unknown_anime() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    // Act
+    let response = app.client
+        .get(format!("{}/api/anime/{}/episodes/stream", app.address, Uuid::new_v4()))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -0,0 +1,29 @@
+// Contract test GET /api/health/metrics
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::spawn_app;
+
+#[tokio::test]
+async fn health_metrics_returns_prometheus_text() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    // Act
+    let response = app.client
+        .get(format!("{}/api/health/metrics", app.address))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let content_type = response.headers()["content-type"].to_str().unwrap().to_string();
+    assert!(content_type.starts_with("text/plain"), "unexpected content type {}", content_type);
+    
+    let body = response.text().await.expect("Failed to read body");
+    assert!(body.contains("http_requests_total"), "no request counter in:\n{}", body);
+    assert!(body.contains("db_anime_count"));
+    assert!(body.contains("redis_connected 1"));
+    assert!(body.contains("circuit_breaker_open_count"));
+}
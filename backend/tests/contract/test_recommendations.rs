@@ -0,0 +1,151 @@
+// Contract tests GET /api/recommendations and GET /api/anime/{id}/similar
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, create_test_token};
+
+fn assert_anime_summary_schema(summary: &serde_json::Value) {
+    assert!(summary["id"].is_string(), "id must be a string");
+    assert!(summary["title"].is_string(), "title must be a string");
+    assert!(summary["poster_url"].is_string(), "poster_url must be a string");
+    assert!(summary["episodes"].is_number(), "episodes must be a number");
+    assert!(summary["status"].is_string(), "status must be a string");
+    assert!(summary["anime_type"].is_string(), "anime_type must be a string");
+    assert!(summary.get("imdb_rating").is_some(), "imdb_rating must be present");
+}
+
+#[tokio::test]
+async fn recommendations_returns_401_without_token() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    // Act
+    let response = app.client
+        .get(format!("{}/api/recommendations", app.address))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn recommendations_returns_anime_summaries_with_token() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = create_test_token(&app.state).await;
+    
+    // Act
+    let response = app.client
+        .get(format!("{}/api/recommendations?limit=100", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    
+    let body: serde_json::Value = response.json().await.unwrap();
+    let recommendations = body["recommendations"].as_array().expect("recommendations must be an array");
+    assert!(recommendations.len() <= 50, "limit must be capped at 50");
+    assert!(body["fallback"].is_boolean());
+    
+    for summary in recommendations {
+        assert_anime_summary_schema(summary);
+    }
+}
+
+#[tokio::test]
+async fn recommendations_fall_back_for_new_user() {
+    // Arrange - a fresh session has no watch or like history
+    let app = spawn_app().await;
+    let token = create_test_token(&app.state).await;
+    
+    // Act
+    let response = app.client
+        .get(format!("{}/api/recommendations", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    
+    let body: serde_json::Value = response.json().await.unwrap();
+    let recommendations = body["recommendations"].as_array().expect("recommendations must be an array");
+    assert!(!recommendations.is_empty(), "new users should receive fallback recommendations");
+    assert_eq!(body["fallback"].as_bool(), Some(true));
+    assert!(
+        matches!(body["source"].as_str(), Some("trending" | "top_rated" | "recent")),
+        "source must name the fallback that produced results"
+    );
+}
+
+#[tokio::test]
+async fn similar_returns_anime_summaries() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    let anime_data = json!({
+        "title": "Similar Source",
+        "synonyms": [],
+        "sources": [],
+        "episodes": 12,
+        "status": "FINISHED",
+        "anime_type": "TV",
+        "anime_season": {
+            "season": "spring",
+            "year": 2023
+        },
+        "synopsis": "Source anime for similarity lookups",
+        "poster_url": "https://example.com/poster.jpg",
+        "tags": ["Action"]
+    });
+    
+    let create_response = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&anime_data)
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    let created: serde_json::Value = create_response.json().await.unwrap();
+    let anime_id = created["id"].as_str().unwrap();
+    
+    // Act
+    let response = app.client
+        .get(format!("{}/api/anime/{}/similar", app.address, anime_id))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    
+    let body: serde_json::Value = response.json().await.unwrap();
+    for summary in body["similar"].as_array().expect("similar must be an array") {
+        assert_anime_summary_schema(summary);
+    }
+}
+
+#[tokio::test]
+async fn similar_returns_404_for_unknown_anime() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    // Act
+    let response = app.client
+        .get(format!("{}/api/anime/{}/similar", app.address, Uuid::new_v4()))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -30,7 +30,7 @@ async fn search_returns_200_with_results() {
     });
     
     let _create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -38,7 +38,7 @@ async fn search_returns_200_with_results() {
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/search?q=Steins", app.address))
+        .get(format!("{}/api/search?q=Steins", app.address))
         .send()
         .await
         .expect("Failed to send request");
@@ -65,7 +65,7 @@ async fn search_returns_empty_results_for_no_match() {
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/search?q=NonExistentAnime123456", app.address))
+        .get(format!("{}/api/search?q=NonExistentAnime123456", app.address))
         .send()
         .await
         .expect("Failed to send request");
@@ -86,7 +86,7 @@ async fn search_returns_400_without_query_param() {
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/search", app.address))
+        .get(format!("{}/api/search", app.address))
         .send()
         .await
         .expect("Failed to send request");
@@ -119,7 +119,7 @@ async fn search_response_matches_openapi_schema() {
         });
         
         let _create = app.client
-            .post(&format!("{}/api/anime", app.address))
+            .post(format!("{}/api/anime", app.address))
             .json(&anime_data)
             .send()
             .await;
@@ -127,7 +127,7 @@ async fn search_response_matches_openapi_schema() {
     
     // Act
     let response = app.client
-        .get(&format!("{}/api/search?q=Test", app.address))
+        .get(format!("{}/api/search?q=Test", app.address))
         .send()
         .await
         .expect("Failed to send request");
@@ -178,14 +178,14 @@ async fn search_filters_by_title_and_synonyms() {
     });
     
     let _create = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await;
     
     // Act - Search by synonym
     let response = app.client
-        .get(&format!("{}/api/search?q=デスノート", app.address))
+        .get(format!("{}/api/search?q=デスノート", app.address))
         .send()
         .await
         .expect("Failed to send request");
@@ -195,4 +195,138 @@ async fn search_filters_by_title_and_synonyms() {
     let results = search_results["results"].as_array().unwrap();
     assert!(!results.is_empty(), "Should find anime by synonym");
     assert_eq!(results[0]["title"].as_str().unwrap(), "Death Note");
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn search_supports_fielded_title_and_year() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    for (title, year) in [("Attack on Titan", 2013), ("Attack on Titan Final Season", 2020)] {
+        let anime_data = json!({
+            "title": title,
+            "synonyms": [],
+            "sources": [],
+            "episodes": 25,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": {
+                "season": "spring",
+                "year": year
+            },
+            "synopsis": "Humanity fights for survival against giant humanoid Titans",
+            "poster_url": "https://example.com/aot.jpg",
+            "tags": []
+        });
+        
+        app.client
+            .post(format!("{}/api/anime", app.address))
+            .json(&anime_data)
+            .send()
+            .await
+            .expect("Failed to create anime");
+    }
+    
+    // Act
+    let response = app.client
+        .get(format!("{}/api/search", app.address))
+        .query(&[("q", r#"title:"attack on titan" year:2013"#)])
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    
+    let search_results: serde_json::Value = response.json().await.expect("Failed to parse response");
+    let results = search_results["results"].as_array().unwrap();
+    assert!(!results.is_empty(), "Should find the 2013 season");
+    
+    for result in results {
+        assert_eq!(result["title"].as_str().unwrap(), "Attack on Titan");
+    }
+}
+
+#[tokio::test]
+async fn legacy_results_field_carries_deprecation_header() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = app.state.auth.lock().await
+        .create_session(&uuid::Uuid::new_v4().to_string(), String::new())
+        .await
+        .expect("Failed to create session")
+        .token;
+    
+    // Act
+    let legacy = app.client
+        .get(format!("{}/api/search?q=anything", app.address))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    let envelope = app.client
+        .get(format!("{}/api/user/watchlist", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(legacy.status().as_u16(), 200);
+    assert_eq!(legacy.headers()["deprecation"], "true");
+    assert_eq!(legacy.headers()["x-deprecated-fields"], "results");
+    
+    let body: serde_json::Value = legacy.json().await.unwrap();
+    assert_eq!(body["results"], body["items"]);
+    
+    assert_eq!(envelope.status().as_u16(), 200);
+    assert!(envelope.headers().get("deprecation").is_none());
+}
+
+#[tokio::test]
+async fn search_total_counts_every_match() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    for i in 0..25 {
+        let anime_data = json!({
+            "title": format!("Pagination Probe {}", i),
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": {
+                "season": "summer",
+                "year": 2021
+            },
+            "synopsis": "Test anime for search pagination",
+            "poster_url": "https://example.com/probe.jpg",
+            "tags": []
+        });
+        
+        app.client
+            .post(format!("{}/api/anime", app.address))
+            .json(&anime_data)
+            .send()
+            .await
+            .expect("Failed to create anime");
+    }
+    
+    // Act
+    let response = app.client
+        .get(format!("{}/api/search", app.address))
+        .query(&[("q", "title:\"pagination probe\""), ("limit", "10")])
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    
+    let search_results: serde_json::Value = response.json().await.expect("Failed to parse response");
+    assert_eq!(search_results["items"].as_array().unwrap().len(), 10);
+    assert_eq!(search_results["total"], 25);
+    assert_eq!(search_results["page"], 1);
+    assert_eq!(search_results["limit"], 10);
+    assert_eq!(search_results["has_more"], true);
+}
@@ -0,0 +1,97 @@
+// Contract test GET /api/browse/season/{year}/{season}/calendar
+
+use serde_json::json;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp, title: &str, episodes: u32, air_dates: &[&str]) -> String {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": title,
+            "synonyms": [],
+            "sources": [],
+            "episodes": episodes,
+            "status": "ONGOING",
+            "anime_type": "TV",
+            "anime_season": { "season": "spring", "year": 2024 },
+            "synopsis": "Test anime for the season calendar",
+            "poster_url": "https://example.com/calendar.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    let anime_id = created["id"].as_str().unwrap().to_string();
+
+    if !air_dates.is_empty() {
+        let episodes: Vec<_> = air_dates.iter().enumerate()
+            .map(|(i, air_date)| json!({ "episode_number": i + 1, "air_date": air_date }))
+            .collect();
+        let response = app.client
+            .post(format!("{}/api/anime/{}/episodes/batch", app.address, anime_id))
+            .json(&json!({ "episodes": episodes }))
+            .send()
+            .await
+            .expect("Failed to create episodes");
+        assert_eq!(response.status().as_u16(), 201);
+    }
+
+    anime_id
+}
+
+#[tokio::test]
+async fn season_calendar_places_titles_by_episode_air_dates() {
+    let app = spawn_app().await;
+    let airing = create_anime(&app, "Calendar Airing", 12, &["2024-04-06", "2024-04-13"]).await;
+    let finished = create_anime(&app, "Calendar Finished", 2, &["2024-04-02", "2024-04-09"]).await;
+    let undated = create_anime(&app, "Calendar Undated", 12, &[]).await;
+
+    let response = app.client
+        .get(format!("{}/api/browse/season/2024/spring/calendar", app.address))
+        .send()
+        .await
+        .expect("Failed to get season calendar");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["year"], 2024);
+    assert_eq!(body["season"], "spring");
+
+    // The season may hold other runs' anime; look at ours only
+    let entries = body["entries"].as_array().unwrap();
+    let entry = |id: &str| entries.iter().find(|entry| entry["anime_id"] == id).cloned();
+
+    assert_eq!(entry(&airing), Some(json!({
+        "anime_id": airing,
+        "title": "Calendar Airing",
+        "weekday": "saturday",
+        "air_time": null,
+        "first_air_date": "2024-04-06",
+        "last_air_date": null,
+        "episodes": 12
+    })));
+    let finished = entry(&finished).expect("finished anime on the calendar");
+    assert_eq!(finished["weekday"], "tuesday");
+    assert_eq!(finished["last_air_date"], "2024-04-09");
+    // Nothing to place an undated title by
+    assert!(entry(&undated).is_none());
+}
+
+#[tokio::test]
+async fn season_calendar_rejects_unknown_season() {
+    let app = spawn_app().await;
+
+    let response = app.client
+        .get(format!("{}/api/browse/season/2024/monsoon/calendar", app.address))
+        .send()
+        .await
+        .expect("Failed to get season calendar");
+
+    assert_eq!(response.status().as_u16(), 400);
+}
@@ -0,0 +1,236 @@
+// Contract test GET/POST /api/tags and GET /api/tags/{id}/anime
+
+use kensho_backend::models::{Tag, TagCategory};
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp, title: &str) -> Uuid {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": title,
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "spring", "year": 2021 },
+            "synopsis": "Test anime for tag listings",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    created["id"].as_str().unwrap().parse().unwrap()
+}
+
+async fn create_tag(app: &TestApp, name: &str, category: TagCategory, anime: &[Uuid]) -> Uuid {
+    let tag = app.state.db
+        .create_tag(&Tag::new(name.to_string(), category))
+        .await
+        .expect("Failed to create tag");
+    for anime_id in anime {
+        app.state.db
+            .create_anime_tag_relationship(*anime_id, tag.id, 1.0)
+            .await
+            .expect("Failed to tag anime");
+    }
+    tag.id
+}
+
+async fn session_token(app: &TestApp, admin: bool) -> String {
+    app.state.auth.lock().await
+        .create_session_with_role(&Uuid::new_v4().to_string(), String::new(), admin)
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+async fn post_tag(app: &TestApp, token: &str, body: serde_json::Value) -> (u16, serde_json::Value) {
+    let response = app.client
+        .post(format!("{}/api/tags", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .send()
+        .await
+        .expect("Failed to send request");
+    let status = response.status().as_u16();
+    (status, response.json().await.unwrap_or_default())
+}
+
+async fn get_json(app: &TestApp, path: &str) -> (u16, serde_json::Value) {
+    let response = app.client
+        .get(format!("{}{}", app.address, path))
+        .send()
+        .await
+        .expect("Failed to send request");
+    let status = response.status().as_u16();
+    (status, response.json().await.unwrap_or_default())
+}
+
+#[tokio::test]
+async fn list_tags_groups_by_category_with_counts() {
+    let app = spawn_app().await;
+    let first = create_anime(&app, "Tagged One").await;
+    let second = create_anime(&app, "Tagged Two").await;
+    let run = Uuid::new_v4().simple().to_string();
+
+    let genre = create_tag(&app, &format!("Slice of Life {}", run), TagCategory::Genre, &[first, second]).await;
+    let demographic = create_tag(&app, &format!("Seinen {}", run), TagCategory::Demographic, &[second]).await;
+    let unused = create_tag(&app, &format!("Mecha {}", run), TagCategory::Theme, &[]).await;
+
+    let (status, body) = get_json(&app, "/api/tags").await;
+    assert_eq!(status, 200);
+
+    let groups = body["items"].as_array().unwrap();
+    let categories: Vec<&str> = groups.iter().map(|group| group["category"].as_str().unwrap()).collect();
+    assert_eq!(categories, ["genre", "theme", "demographic", "content"]);
+
+    let find = |id: Uuid, category: &str| {
+        groups.iter()
+            .find(|group| group["category"] == category)
+            .and_then(|group| group["tags"].as_array().unwrap().iter().find(|tag| tag["id"] == id.to_string()))
+            .cloned()
+            .unwrap_or_else(|| panic!("tag {} missing from {}", id, category))
+    };
+
+    let genre = find(genre, "genre");
+    assert_eq!(genre["anime_count"], 2);
+    assert_eq!(genre["slug"], format!("slice-of-life-{}", run));
+    assert_eq!(find(demographic, "demographic")["anime_count"], 1);
+    assert_eq!(find(unused, "theme")["anime_count"], 0);
+    assert!(body["total"].as_u64().unwrap() >= 3);
+}
+
+#[tokio::test]
+async fn list_tags_filters_by_category() {
+    let app = spawn_app().await;
+    let run = Uuid::new_v4().simple().to_string();
+    let genre = create_tag(&app, &format!("Mystery {}", run), TagCategory::Genre, &[]).await;
+    let theme = create_tag(&app, &format!("Music {}", run), TagCategory::Theme, &[]).await;
+
+    let (status, body) = get_json(&app, "/api/tags?category=genre").await;
+    assert_eq!(status, 200);
+
+    let groups = body["items"].as_array().unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0]["category"], "genre");
+    let ids: Vec<&str> = groups[0]["tags"].as_array().unwrap()
+        .iter()
+        .map(|tag| tag["id"].as_str().unwrap())
+        .collect();
+    assert!(ids.contains(&genre.to_string().as_str()));
+    assert!(!ids.contains(&theme.to_string().as_str()));
+    assert_eq!(body["total"].as_u64().unwrap() as usize, ids.len());
+
+    let (status, _) = get_json(&app, "/api/tags?category=vibes").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test]
+async fn create_tag_requires_admin() {
+    let app = spawn_app().await;
+    let name = format!("Iyashikei {}", Uuid::new_v4().simple());
+    let body = json!({ "name": name, "category": "genre", "description": "Healing" });
+
+    let user = session_token(&app, false).await;
+    let (status, _) = post_tag(&app, &user, body.clone()).await;
+    assert_eq!(status, 403);
+
+    let admin = session_token(&app, true).await;
+    let (status, created) = post_tag(&app, &admin, body).await;
+    assert_eq!(status, 201);
+    assert_eq!(created["name"], name.as_str());
+    assert_eq!(created["category"], "genre");
+    assert_eq!(created["description"], "Healing");
+    assert_eq!(created["anime_count"], 0);
+
+    let (_, listed) = get_json(&app, "/api/tags?category=genre").await;
+    assert!(listed["items"][0]["tags"].as_array().unwrap().iter().any(|tag| tag["id"] == created["id"]));
+
+    let (status, _) = post_tag(&app, &admin, json!({ "name": "  ", "category": "genre" })).await;
+    assert_eq!(status, 422);
+}
+
+#[tokio::test]
+async fn create_tag_rejects_duplicate_names_ignoring_case() {
+    let app = spawn_app().await;
+    let admin = session_token(&app, true).await;
+    let name = format!("Mahou Shoujo {}", Uuid::new_v4().simple());
+
+    let (status, created) = post_tag(&app, &admin, json!({ "name": name, "category": "genre" })).await;
+    assert_eq!(status, 201);
+
+    // Another category does not make it a different tag
+    let (status, body) = post_tag(&app, &admin, json!({ "name": name.to_uppercase(), "category": "theme" })).await;
+    assert_eq!(status, 409);
+    assert_eq!(body["id"], created["id"]);
+}
+
+#[tokio::test]
+async fn concurrent_creates_of_one_name_make_one_tag() {
+    let app = spawn_app().await;
+    let admin = session_token(&app, true).await;
+    let name = format!("Isekai {}", Uuid::new_v4().simple());
+
+    let ((first, _), (second, _)) = tokio::join!(
+        post_tag(&app, &admin, json!({ "name": name, "category": "genre" })),
+        post_tag(&app, &admin, json!({ "name": name.to_lowercase(), "category": "genre" })),
+    );
+    let mut statuses = [first, second];
+    statuses.sort();
+    assert_eq!(statuses, [201, 409]);
+}
+
+#[tokio::test]
+async fn tag_anime_is_paginated() {
+    let app = spawn_app().await;
+    let mut tagged = Vec::new();
+    for i in 0..3 {
+        tagged.push(create_anime(&app, &format!("Tagged {}", i)).await);
+    }
+    let untagged = create_anime(&app, "Untagged").await;
+    let tag = create_tag(&app, &format!("Isekai {}", Uuid::new_v4()), TagCategory::Theme, &tagged).await;
+
+    let (status, page) = get_json(&app, &format!("/api/tags/{}/anime?limit=2", tag)).await;
+    assert_eq!(status, 200);
+    assert_eq!(page["items"].as_array().unwrap().len(), 2);
+    assert_eq!(page["has_more"], true);
+    assert!(page["items"][0]["poster_url"].is_string());
+
+    let cursor = page["next_cursor"].as_str().unwrap();
+    let (status, last) = get_json(&app, &format!("/api/tags/{}/anime?limit=2&cursor={}", tag, cursor)).await;
+    assert_eq!(status, 200);
+    assert_eq!(last["has_more"], false);
+    assert!(last["next_cursor"].is_null());
+
+    let seen: Vec<String> = page["items"].as_array().unwrap()
+        .iter()
+        .chain(last["items"].as_array().unwrap())
+        .map(|item| item["id"].as_str().unwrap().to_string())
+        .collect();
+    let expected: Vec<String> = tagged.iter().map(Uuid::to_string).collect();
+    assert_eq!(seen, expected);
+    assert!(!seen.contains(&untagged.to_string()));
+}
+
+#[tokio::test]
+async fn tag_anime_returns_404_for_unknown_tag() {
+    let app = spawn_app().await;
+
+    let (status, body) = get_json(&app, &format!("/api/tags/{}/anime", Uuid::new_v4())).await;
+    assert_eq!(status, 404);
+    assert_eq!(body["error"], "Tag not found");
+
+    let (status, _) = get_json(&app, "/api/tags/not-a-uuid/anime").await;
+    assert_eq!(status, 400);
+}
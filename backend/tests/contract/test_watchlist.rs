@@ -0,0 +1,234 @@
+// Contract tests for /api/user/watchlist (POST, GET, PUT, DELETE)
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, create_test_token, TestApp};
+
+async fn create_anime(app: &TestApp, title: &str) -> String {
+    let anime_data = json!({
+        "title": title,
+        "synonyms": [],
+        "sources": [],
+        "episodes": 12,
+        "status": "FINISHED",
+        "anime_type": "TV",
+        "anime_season": {
+            "season": "spring",
+            "year": 2024
+        },
+        "synopsis": "Watchlist contract test anime",
+        "poster_url": "https://example.com/poster.jpg",
+        "tags": []
+    });
+    
+    let response = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&anime_data)
+        .send()
+        .await
+        .expect("Failed to create anime");
+    
+    let anime: serde_json::Value = response.json().await.unwrap();
+    anime["id"].as_str().unwrap().to_string()
+}
+
+async fn get_watchlist(app: &TestApp, token: &str) -> serde_json::Value {
+    let response = app.client
+        .get(format!("{}/api/user/watchlist", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to get watchlist");
+    
+    assert_eq!(response.status().as_u16(), 200);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn watchlist_returns_401_without_token() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    // Act
+    let response = app.client
+        .get(format!("{}/api/user/watchlist", app.address))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn watchlist_items_include_anime_summary() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = create_test_token(&app.state).await;
+    let anime_id = create_anime(&app, "Watchlist Summary").await;
+    
+    // Act
+    let add_response = app.client
+        .post(format!("{}/api/user/watchlist", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "anime_id": anime_id, "status": "watching" }))
+        .send()
+        .await
+        .expect("Failed to add to watchlist");
+    
+    // Assert
+    assert_eq!(add_response.status().as_u16(), 201);
+    
+    let watchlist = get_watchlist(&app, &token).await;
+    let items = watchlist["items"].as_array().expect("items must be an array");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["anime_id"], anime_id);
+    assert_eq!(items[0]["status"], "watching");
+    assert!(items[0]["added_at"].is_string());
+    assert_eq!(items[0]["anime"]["id"], anime_id);
+    assert!(items[0]["anime"]["poster_url"].is_string(), "poster_url must be available for rendering");
+}
+
+#[tokio::test]
+async fn duplicate_add_updates_existing_entry() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = create_test_token(&app.state).await;
+    let anime_id = create_anime(&app, "Watchlist Duplicate").await;
+    
+    // Act
+    for status in ["planned", "watching"] {
+        app.client
+            .post(format!("{}/api/user/watchlist", app.address))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&json!({ "anime_id": anime_id, "status": status }))
+            .send()
+            .await
+            .expect("Failed to add to watchlist");
+    }
+    
+    // Assert
+    let watchlist = get_watchlist(&app, &token).await;
+    let items = watchlist["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1, "duplicate adds must not create a second entry");
+    assert_eq!(items[0]["status"], "watching");
+}
+
+#[tokio::test]
+async fn put_updates_status_and_rejects_unknown_values() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = create_test_token(&app.state).await;
+    let anime_id = create_anime(&app, "Watchlist Update").await;
+    
+    app.client
+        .post(format!("{}/api/user/watchlist", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "anime_id": anime_id }))
+        .send()
+        .await
+        .expect("Failed to add to watchlist");
+    
+    // Act
+    let update = app.client
+        .put(format!("{}/api/user/watchlist", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "anime_id": anime_id, "status": "completed" }))
+        .send()
+        .await
+        .expect("Failed to update watchlist");
+    
+    let invalid = app.client
+        .put(format!("{}/api/user/watchlist", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "anime_id": anime_id, "status": "paused" }))
+        .send()
+        .await
+        .expect("Failed to send invalid update");
+    
+    let missing = app.client
+        .put(format!("{}/api/user/watchlist", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "anime_id": Uuid::new_v4(), "status": "dropped" }))
+        .send()
+        .await
+        .expect("Failed to send update for unlisted anime");
+    
+    // Assert
+    assert_eq!(update.status().as_u16(), 200);
+    let entry: serde_json::Value = update.json().await.unwrap();
+    assert_eq!(entry["status"], "completed");
+    assert_eq!(invalid.status().as_u16(), 400);
+    assert_eq!(missing.status().as_u16(), 404);
+    
+    // Shelved for later, then back to the plan
+    for (status, stored) in [("on_hold", "on_hold"), ("plan_to_watch", "planned")] {
+        let update = app.client
+            .put(format!("{}/api/user/watchlist", app.address))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&json!({ "anime_id": anime_id, "status": status }))
+            .send()
+            .await
+            .expect("Failed to update watchlist");
+        assert_eq!(update.status().as_u16(), 200);
+        let entry: serde_json::Value = update.json().await.unwrap();
+        assert_eq!(entry["status"], stored);
+    }
+}
+
+#[tokio::test]
+async fn delete_is_idempotent() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = create_test_token(&app.state).await;
+    let anime_id = create_anime(&app, "Watchlist Delete").await;
+    
+    app.client
+        .post(format!("{}/api/user/watchlist", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "anime_id": anime_id }))
+        .send()
+        .await
+        .expect("Failed to add to watchlist");
+    
+    // Act & Assert - deleting twice succeeds both times
+    for _ in 0..2 {
+        let response = app.client
+            .delete(format!("{}/api/user/watchlist", app.address))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&json!({ "anime_id": anime_id }))
+            .send()
+            .await
+            .expect("Failed to delete from watchlist");
+        
+        assert_eq!(response.status().as_u16(), 204);
+    }
+    
+    let watchlist = get_watchlist(&app, &token).await;
+    assert!(watchlist["items"].as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn watchlists_are_isolated_per_user() {
+    // Arrange
+    let app = spawn_app().await;
+    let owner = create_test_token(&app.state).await;
+    let other = create_test_token(&app.state).await;
+    let anime_id = create_anime(&app, "Watchlist Isolation").await;
+    
+    // Act
+    app.client
+        .post(format!("{}/api/user/watchlist", app.address))
+        .header("Authorization", format!("Bearer {}", owner))
+        .json(&json!({ "anime_id": anime_id }))
+        .send()
+        .await
+        .expect("Failed to add to watchlist");
+    
+    // Assert
+    assert_eq!(get_watchlist(&app, &owner).await["items"].as_array().unwrap().len(), 1);
+    assert!(get_watchlist(&app, &other).await["items"].as_array().unwrap().is_empty());
+}
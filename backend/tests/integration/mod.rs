@@ -1,6 +1,6 @@
 // Integration tests module
-
-mod common;
+// Each test file includes ../common/mod.rs for itself
+#![allow(clippy::duplicate_mod)]
 
 // Integration test modules
 mod test_search_scenario;
@@ -8,4 +8,44 @@ mod test_auth_flow;
 mod test_streaming;
 mod test_session;
 mod test_seasonal_browse;
-mod test_performance;
\ No newline at end of file
+mod test_performance;
+mod test_user_rate_limit;
+mod test_episode_counts;
+mod test_bulk_import;
+mod test_watch_history;
+mod test_playback_position;
+mod test_preferences;
+mod test_continue_watching;
+mod test_user_recommendations;
+mod test_stream_resume;
+mod test_catalog_changes;
+mod test_stream_quality;
+#[cfg(feature = "metrics")]
+mod test_metrics;
+mod test_popularity_sort;
+mod test_content_recommendations;
+mod test_demo_streaming;
+mod test_cursor_pagination;
+mod test_kana_search;
+mod test_dynamic_cors;
+mod test_episode_batch;
+mod test_browse_filters;
+mod test_duplicate_anime;
+mod test_year_browse;
+mod test_devices;
+mod test_episode_numbering;
+mod test_response_cache;
+mod test_anime_delete;
+mod test_rate_limit;
+mod test_graph_relationships;
+mod test_watch_heartbeat;
+mod test_user_preferences;
+mod test_request_id;
+mod test_request_size_limit;
+mod test_offline_import;
+mod test_health_probes;
+mod test_api_versioning;
+mod test_search_suggest;
+mod test_offline_upsert;
+mod test_registration;
+mod test_cache_purge;
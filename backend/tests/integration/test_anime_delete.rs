@@ -0,0 +1,133 @@
+// Integration test for DELETE /api/anime/{id}: admin only, and the anime's
+// episodes and catalog edges go with it
+
+use kensho_backend::models::{Tag, TagCategory};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn session_token(app: &TestApp, admin: bool) -> String {
+    app.state.auth.lock().await
+        .create_session_with_role(&Uuid::new_v4().to_string(), String::new(), admin)
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+async fn create_anime(app: &TestApp, title: &str) -> Uuid {
+    let created: Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": format!("{} {}", title, Uuid::new_v4().simple()),
+            "synonyms": [],
+            "sources": [],
+            "episodes": 3,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "fall", "year": 2019 },
+            "synopsis": "Test anime for cascading deletes",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    created["id"].as_str().unwrap().parse().unwrap()
+}
+
+async fn delete(app: &TestApp, token: &str, anime_id: Uuid) -> reqwest::Response {
+    app.client
+        .delete(format!("{}/api/anime/{}", app.address, anime_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to send request")
+}
+
+#[tokio::test]
+async fn delete_cascades_to_episodes_and_edges() {
+    // Arrange
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app, "Doomed").await;
+    let sequel_id = create_anime(&app, "Doomed Sequel").await;
+    let similar_id = create_anime(&app, "Doomed Lookalike").await;
+
+    let response = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({ "episodes": [
+            { "episode_number": 1 },
+            { "episode_number": 2 },
+            { "episode_number": 3 }
+        ] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 201);
+
+    let tag = app.state.db
+        .create_tag(&Tag::new(format!("Tragedy {}", Uuid::new_v4().simple()), TagCategory::Theme))
+        .await
+        .unwrap();
+    app.state.db.create_anime_tag_relationship(anime_id, tag.id, 1.0).await.unwrap();
+    app.state.db.create_sequel_relationship(sequel_id, anime_id).await.unwrap();
+    app.state.db.create_similarity_relationship(similar_id, anime_id, 0.8).await.unwrap();
+
+    // Act
+    let admin = session_token(&app, true).await;
+    let response = delete(&app, &admin, anime_id).await;
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let report: Value = response.json().await.unwrap();
+    assert_eq!(report["anime_deleted"], true);
+    assert_eq!(report["episodes_deleted"], 3);
+    // Three belongs_to, one has_tag, one is_sequel, one is_similar
+    assert_eq!(report["edges_deleted"], 6);
+
+    assert!(app.state.db.get_anime(anime_id).await.unwrap().is_none());
+    assert_eq!(app.state.db.get_anime_episodes(anime_id).await.unwrap().len(), 0);
+    assert!(app.state.db.get_anime_tags(anime_id).await.unwrap().is_empty());
+
+    let response = app.client
+        .get(format!("{}/api/anime/{}", app.address, anime_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+
+    // The related anime and the tag itself stay
+    assert!(app.state.db.get_anime(sequel_id).await.unwrap().is_some());
+    assert!(app.state.db.get_anime(similar_id).await.unwrap().is_some());
+    assert!(app.state.db.get_tag(tag.id).await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn delete_requires_admin_and_known_anime() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app, "Protected").await;
+
+    let response = app.client
+        .delete(format!("{}/api/anime/{}", app.address, anime_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 401);
+
+    let user = session_token(&app, false).await;
+    assert_eq!(delete(&app, &user, anime_id).await.status().as_u16(), 403);
+    assert!(app.state.db.get_anime(anime_id).await.unwrap().is_some());
+
+    let admin = session_token(&app, true).await;
+    assert_eq!(delete(&app, &admin, Uuid::new_v4()).await.status().as_u16(), 404);
+
+    // Deleting twice finds nothing the second time
+    assert_eq!(delete(&app, &admin, anime_id).await.status().as_u16(), 200);
+    assert_eq!(delete(&app, &admin, anime_id).await.status().as_u16(), 404);
+}
@@ -0,0 +1,99 @@
+// Integration test for API version prefixes and Accept negotiation
+
+use serde_json::json;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+const V2_MEDIA_TYPE: &str = "application/vnd.kensho.v2+json";
+
+async fn create_anime(app: &TestApp) -> String {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": "Versioned Anime",
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "spring", "year": 2024 },
+            "synopsis": "Test anime for API versioning",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    created["id"].as_str().unwrap().to_string()
+}
+
+async fn get(app: &TestApp, path: &str, accept: Option<&str>) -> reqwest::Response {
+    let mut request = app.client.get(format!("{}{}", app.address, path));
+    if let Some(accept) = accept {
+        request = request.header("Accept", accept);
+    }
+    request.send().await.expect("Failed to send request")
+}
+
+fn api_version(response: &reqwest::Response) -> &str {
+    response.headers()["x-kensho-api-version"].to_str().unwrap()
+}
+
+#[tokio::test]
+async fn anime_resolves_under_every_version_prefix() {
+    let app = spawn_app().await;
+    let id = create_anime(&app).await;
+    
+    for (path, version) in [
+        (format!("/api/anime/{}", id), "v1"),
+        (format!("/api/v1/anime/{}", id), "v1"),
+        (format!("/api/v2/anime/{}", id), "v2"),
+    ] {
+        let response = get(&app, &path, None).await;
+        assert_eq!(response.status().as_u16(), 200, "GET {}", path);
+        assert_eq!(api_version(&response), version, "GET {}", path);
+        let anime: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(anime["id"], id.as_str());
+    }
+}
+
+#[tokio::test]
+async fn accept_header_selects_the_version() {
+    let app = spawn_app().await;
+    let id = create_anime(&app).await;
+    
+    let response = get(&app, &format!("/api/anime/{}", id), Some(V2_MEDIA_TYPE)).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(api_version(&response), "v2");
+    
+    let response = get(&app, &format!("/api/anime/{}", id), Some("application/vnd.kensho.v1+json")).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(api_version(&response), "v1");
+    
+    // Agreeing prefix and header are fine
+    let response = get(&app, &format!("/api/v2/anime/{}", id), Some(V2_MEDIA_TYPE)).await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn unsupported_or_conflicting_versions_are_not_acceptable() {
+    let app = spawn_app().await;
+    let id = create_anime(&app).await;
+    
+    let response = get(&app, &format!("/api/v9/anime/{}", id), None).await;
+    assert_eq!(response.status().as_u16(), 406);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["code"], "UNSUPPORTED_API_VERSION");
+    assert_eq!(body["details"]["supported"], json!(["v1", "v2"]));
+    
+    let response = get(&app, &format!("/api/anime/{}", id), Some("application/vnd.kensho.v9+json")).await;
+    assert_eq!(response.status().as_u16(), 406);
+    
+    let response = get(&app, &format!("/api/v1/anime/{}", id), Some(V2_MEDIA_TYPE)).await;
+    assert_eq!(response.status().as_u16(), 406);
+}
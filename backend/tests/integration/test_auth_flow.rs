@@ -19,7 +19,7 @@ async fn complete_auth_flow_with_crunchyroll() {
     });
     
     let login_response = app.client
-        .post(&format!("{}/api/auth/login", app.address))
+        .post(format!("{}/api/auth/login", app.address))
         .json(&login_data)
         .send()
         .await
@@ -33,7 +33,7 @@ async fn complete_auth_flow_with_crunchyroll() {
     
     // Step 2: Use access token to access protected endpoint
     let anime_response = app.client
-        .get(&format!("{}/api/anime", app.address))
+        .get(format!("{}/api/anime", app.address))
         .header("Authorization", format!("Bearer {}", access_token))
         .send()
         .await
@@ -50,7 +50,7 @@ async fn complete_auth_flow_with_crunchyroll() {
     });
     
     let refresh_response = app.client
-        .post(&format!("{}/api/auth/refresh", app.address))
+        .post(format!("{}/api/auth/refresh", app.address))
         .json(&refresh_data)
         .send()
         .await
@@ -62,7 +62,7 @@ async fn complete_auth_flow_with_crunchyroll() {
         
         // Step 4: Use new access token
         let anime_response2 = app.client
-            .get(&format!("{}/api/anime", app.address))
+            .get(format!("{}/api/anime", app.address))
             .header("Authorization", format!("Bearer {}", new_access_token))
             .send()
             .await
@@ -75,7 +75,7 @@ async fn complete_auth_flow_with_crunchyroll() {
         
         // Step 5: Logout
         let logout_response = app.client
-            .post(&format!("{}/api/auth/logout", app.address))
+            .post(format!("{}/api/auth/logout", app.address))
             .header("Authorization", format!("Bearer {}", new_access_token))
             .send()
             .await
@@ -97,7 +97,7 @@ async fn token_expires_after_timeout() {
     });
     
     let login_response = app.client
-        .post(&format!("{}/api/auth/login", app.address))
+        .post(format!("{}/api/auth/login", app.address))
         .json(&login_data)
         .send()
         .await
@@ -127,7 +127,7 @@ async fn multiple_login_sessions_are_independent() {
     });
     
     let user1_response = app.client
-        .post(&format!("{}/api/auth/login", app.address))
+        .post(format!("{}/api/auth/login", app.address))
         .json(&user1_data)
         .send()
         .await
@@ -140,7 +140,7 @@ async fn multiple_login_sessions_are_independent() {
     });
     
     let user2_response = app.client
-        .post(&format!("{}/api/auth/login", app.address))
+        .post(format!("{}/api/auth/login", app.address))
         .json(&user2_data)
         .send()
         .await
@@ -158,7 +158,7 @@ async fn multiple_login_sessions_are_independent() {
         
         // Logout user1
         let logout1_response = app.client
-            .post(&format!("{}/api/auth/logout", app.address))
+            .post(format!("{}/api/auth/logout", app.address))
             .header("Authorization", format!("Bearer {}", user1_token))
             .send()
             .await
@@ -168,7 +168,7 @@ async fn multiple_login_sessions_are_independent() {
         
         // User2 should still be able to access protected endpoints
         let user2_access = app.client
-            .get(&format!("{}/api/anime", app.address))
+            .get(format!("{}/api/anime", app.address))
             .header("Authorization", format!("Bearer {}", user2_token))
             .send()
             .await
@@ -179,4 +179,213 @@ async fn multiple_login_sessions_are_independent() {
             "User2 token should still be valid after user1 logout"
         );
     }
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn reused_refresh_token_revokes_token_family() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    let login_data = json!({
+        "email": "test@example.com",
+        "password": "password"
+    });
+    
+    let login_response = app.client
+        .post(format!("{}/api/auth/login", app.address))
+        .json(&login_data)
+        .send()
+        .await
+        .expect("Failed to login");
+    
+    assert_eq!(login_response.status().as_u16(), 200, "Mock login should succeed");
+    
+    let auth_tokens: serde_json::Value = login_response.json().await.unwrap();
+    let refresh_token = auth_tokens["refresh_token"].as_str().unwrap().to_string();
+    let refresh_data = json!({
+        "refresh_token": refresh_token
+    });
+    
+    // Act - First refresh rotates the token
+    let first = app.client
+        .post(format!("{}/api/auth/refresh", app.address))
+        .json(&refresh_data)
+        .send()
+        .await
+        .expect("Failed to send first refresh");
+    
+    assert_eq!(first.status().as_u16(), 200, "First refresh should succeed");
+    
+    let rotated: serde_json::Value = first.json().await.unwrap();
+    let new_access_token = rotated["token"].as_str().unwrap().to_string();
+    let new_refresh_token = rotated["refresh_token"].as_str().unwrap().to_string();
+    assert_ne!(new_refresh_token, refresh_token, "Refresh should issue a new refresh token");
+    
+    // Act - Replaying the original token
+    let second = app.client
+        .post(format!("{}/api/auth/refresh", app.address))
+        .json(&refresh_data)
+        .send()
+        .await
+        .expect("Failed to send second refresh");
+    
+    // Assert
+    assert_eq!(second.status().as_u16(), 401, "Reused refresh token must be rejected");
+    
+    let error_response: serde_json::Value = second.json().await.unwrap();
+    assert_eq!(error_response["code"], "TOKEN_REUSE_DETECTED");
+    
+    // The whole family is revoked: the rotated refresh token and its access token are dead
+    let third = app.client
+        .post(format!("{}/api/auth/refresh", app.address))
+        .json(&json!({ "refresh_token": new_refresh_token }))
+        .send()
+        .await
+        .expect("Failed to send refresh with rotated token");
+    
+    assert_eq!(third.status().as_u16(), 401, "Rotated refresh token should be revoked with its family");
+    
+    let protected = app.client
+        .get(format!("{}/api/recommendations", app.address))
+        .header("Authorization", format!("Bearer {}", new_access_token))
+        .send()
+        .await
+        .expect("Failed to call protected endpoint");
+    
+    assert_eq!(protected.status().as_u16(), 401, "Access token from a revoked family must be rejected");
+}
+
+#[tokio::test]
+async fn logged_out_access_token_is_rejected() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    let login_response = app.client
+        .post(format!("{}/api/auth/login", app.address))
+        .json(&json!({
+            "email": "test@example.com",
+            "password": "password"
+        }))
+        .send()
+        .await
+        .expect("Failed to login");
+    
+    assert_eq!(login_response.status().as_u16(), 200, "Mock login should succeed");
+    
+    let auth_tokens: serde_json::Value = login_response.json().await.unwrap();
+    let access_token = auth_tokens["token"].as_str().unwrap().to_string();
+    
+    // Act
+    let logout_response = app.client
+        .post(format!("{}/api/auth/logout", app.address))
+        .header("Authorization", format!("Bearer {}", access_token))
+        .send()
+        .await
+        .expect("Failed to logout");
+    
+    assert_eq!(logout_response.status().as_u16(), 200, "Logout should succeed");
+    
+    let protected = app.client
+        .get(format!("{}/api/recommendations", app.address))
+        .header("Authorization", format!("Bearer {}", access_token))
+        .send()
+        .await
+        .expect("Failed to call protected endpoint");
+    
+    // Assert
+    assert_eq!(protected.status().as_u16(), 401, "Logged out token must be rejected before it expires");
+    
+    let error_response: serde_json::Value = protected.json().await.unwrap();
+    assert_eq!(error_response["error"], "Token has been revoked, please login again");
+}
+
+#[tokio::test]
+async fn every_refresh_rotates_the_refresh_token() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    let login_response = app.client
+        .post(format!("{}/api/auth/login", app.address))
+        .json(&json!({
+            "email": "test@example.com",
+            "password": "password"
+        }))
+        .send()
+        .await
+        .expect("Failed to login");
+    
+    assert_eq!(login_response.status().as_u16(), 200, "Mock login should succeed");
+    
+    let auth_tokens: serde_json::Value = login_response.json().await.unwrap();
+    let mut refresh_token = auth_tokens["refresh_token"].as_str().unwrap().to_string();
+    let mut seen = vec![refresh_token.clone()];
+    
+    // Act & Assert - each rotated token is itself good for exactly one refresh
+    for _ in 0..3 {
+        let response = app.client
+            .post(format!("{}/api/auth/refresh", app.address))
+            .json(&json!({ "refresh_token": refresh_token }))
+            .send()
+            .await
+            .expect("Failed to refresh");
+        
+        assert_eq!(response.status().as_u16(), 200, "Refresh with the latest token should succeed");
+        
+        let rotated: serde_json::Value = response.json().await.unwrap();
+        assert!(rotated["token"].is_string(), "Refresh should return an access token");
+        refresh_token = rotated["refresh_token"].as_str().unwrap().to_string();
+        
+        assert!(!seen.contains(&refresh_token), "Every refresh should issue a fresh refresh token");
+        seen.push(refresh_token.clone());
+    }
+    
+    let protected = app.client
+        .get(format!("{}/api/recommendations", app.address))
+        .header("Authorization", format!("Bearer {}", auth_tokens["token"].as_str().unwrap()))
+        .send()
+        .await
+        .expect("Failed to call protected endpoint");
+    
+    assert_ne!(protected.status().as_u16(), 401, "Rotation alone must not end the session");
+}
+
+#[tokio::test]
+async fn logout_revokes_the_refresh_token() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    let login_response = app.client
+        .post(format!("{}/api/auth/login", app.address))
+        .json(&json!({
+            "email": "test@example.com",
+            "password": "password"
+        }))
+        .send()
+        .await
+        .expect("Failed to login");
+    
+    assert_eq!(login_response.status().as_u16(), 200, "Mock login should succeed");
+    
+    let auth_tokens: serde_json::Value = login_response.json().await.unwrap();
+    let access_token = auth_tokens["token"].as_str().unwrap().to_string();
+    let refresh_token = auth_tokens["refresh_token"].as_str().unwrap().to_string();
+    
+    // Act
+    let logout_response = app.client
+        .post(format!("{}/api/auth/logout", app.address))
+        .header("Authorization", format!("Bearer {}", access_token))
+        .send()
+        .await
+        .expect("Failed to logout");
+    
+    assert_eq!(logout_response.status().as_u16(), 200, "Logout should succeed");
+    
+    let refresh_response = app.client
+        .post(format!("{}/api/auth/refresh", app.address))
+        .json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .expect("Failed to send refresh");
+    
+    // Assert
+    assert_eq!(refresh_response.status().as_u16(), 401, "Refresh token must not outlive its session");
+}
@@ -0,0 +1,103 @@
+// Integration test for composed filters and sorting on seasonal browse
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp, title: &str, anime_type: &str, status: &str, episodes: u32, tags: &[&str]) -> String {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": title,
+            "synonyms": [],
+            "sources": [],
+            "episodes": episodes,
+            "status": status,
+            "anime_type": anime_type,
+            "anime_season": { "season": "summer", "year": 1987 },
+            "synopsis": "Test anime for browse filters",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": tags
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    created["id"].as_str().unwrap().to_string()
+}
+
+async fn browse(app: &TestApp, query: &str) -> reqwest::Response {
+    app.client
+        .get(format!("{}/api/browse/season/1987/summer?{}", app.address, query))
+        .send()
+        .await
+        .expect("Failed to browse season")
+}
+
+fn ids(body: &serde_json::Value) -> Vec<String> {
+    body["items"].as_array().unwrap()
+        .iter()
+        .map(|item| item["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn filters_compose_and_total_counts_the_filtered_set() {
+    let app = spawn_app().await;
+    // A tag only this run uses keeps other runs' anime out of the season
+    let run = format!("Run{}", Uuid::new_v4().simple());
+
+    let long = create_anime(&app, "Long Ongoing", "TV", "ONGOING", 24, &[&run, "Drama"]).await;
+    let short = create_anime(&app, "Short Ongoing", "TV", "ONGOING", 12, &[&run]).await;
+    create_anime(&app, "Finished Show", "TV", "FINISHED", 13, &[&run]).await;
+    create_anime(&app, "Ongoing Movie", "MOVIE", "ONGOING", 1, &[&run]).await;
+
+    let response = browse(&app, &format!("type=TV&status=ONGOING&tag={}&sort=episodes:desc", run)).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(ids(&body), vec![long.clone(), short.clone()]);
+    assert_eq!(body["total"], 2);
+
+    let body: serde_json::Value = browse(&app, &format!("type=TV&status=ONGOING&tag={}&sort=episodes:asc", run)).await.json().await.unwrap();
+    assert_eq!(ids(&body), vec![short.clone(), long.clone()]);
+
+    // Repeated tags must all be present
+    let body: serde_json::Value = browse(&app, &format!("tag={}&tag=drama", run)).await.json().await.unwrap();
+    assert_eq!(ids(&body), vec![long.clone()]);
+
+    // Pages of the filtered, sorted listing report the filtered total
+    let body: serde_json::Value = browse(&app, &format!("tag={}&sort=episodes:asc&page=2&limit=1", run)).await.json().await.unwrap();
+    assert_eq!(ids(&body), vec![short]);
+    assert_eq!(body["total"], 4);
+    assert_eq!(body["has_more"], true);
+
+    // Cursor pages count the filtered set too
+    let body: serde_json::Value = browse(&app, &format!("tag={}&type=MOVIE&limit=10", run)).await.json().await.unwrap();
+    assert_eq!(body["total"], 1);
+}
+
+#[tokio::test]
+async fn invalid_filter_values_list_accepted_values() {
+    let app = spawn_app().await;
+
+    let response = browse(&app, "type=SERIES&sort=score").await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    let errors = body["details"]["errors"].as_array().unwrap();
+    let message = |field: &str| errors.iter()
+        .find(|error| error["field"] == field)
+        .and_then(|error| error["message"].as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    assert!(message("type").contains("MOVIE"));
+    assert!(message("sort").contains("popularity"));
+    assert_eq!(browse(&app, "sort=rating:sideways").await.status().as_u16(), 400);
+}
@@ -0,0 +1,140 @@
+// Integration test for admin bulk ingest of anime-offline-database entries
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn session_token(app: &TestApp, admin: bool) -> String {
+    app.state.auth.lock().await
+        .create_session_with_role(&Uuid::new_v4().to_string(), String::new(), admin)
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+/// `count` offline-database entries with source URLs unique to this run
+fn offline_fixture(count: usize) -> Vec<serde_json::Value> {
+    let run = Uuid::new_v4();
+    
+    (0..count)
+        .map(|i| json!({
+            "sources": [format!("https://myanimelist.net/anime/{}-{}", run, i)],
+            "title": format!("Bulk Fixture {}", i),
+            "type": "TV",
+            "episodes": 12,
+            "status": "FINISHED",
+            "animeSeason": { "season": "SPRING", "year": 2020 },
+            "picture": format!("https://cdn.example.com/{}/{}.jpg", run, i),
+            "thumbnail": format!("https://cdn.example.com/{}/{}-thumb.jpg", run, i),
+            "duration": { "value": 1440, "unit": "SECONDS" },
+            "score": null,
+            "synonyms": [],
+            "studios": ["Fixture Studio"],
+            "producers": [],
+            "relatedAnime": [],
+            "tags": ["action"]
+        }))
+        .collect()
+}
+
+async fn post_entries(app: &TestApp, token: &str, entries: &[serde_json::Value]) -> reqwest::Response {
+    app.client
+        .post(format!("{}/api/anime/bulk", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&entries)
+        .send()
+        .await
+        .expect("Failed to send bulk import")
+}
+
+#[tokio::test]
+async fn bulk_import_requires_admin_claim() {
+    // Arrange
+    let app = spawn_app().await;
+    let entries = offline_fixture(1);
+    let user_token = session_token(&app, false).await;
+    
+    // Act
+    let anonymous = app.client
+        .post(format!("{}/api/anime/bulk", app.address))
+        .json(&entries)
+        .send()
+        .await
+        .expect("Failed to send bulk import");
+    let non_admin = post_entries(&app, &user_token, &entries).await;
+    
+    // Assert
+    assert_eq!(anonymous.status().as_u16(), 401);
+    assert_eq!(non_admin.status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn bulk_import_is_idempotent() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = session_token(&app, true).await;
+    let entries = offline_fixture(500);
+    
+    // Act
+    let first = post_entries(&app, &token, &entries).await;
+    assert_eq!(first.status().as_u16(), 200);
+    let first: serde_json::Value = first.json().await.unwrap();
+    
+    let second = post_entries(&app, &token, &entries).await;
+    assert_eq!(second.status().as_u16(), 200);
+    let second: serde_json::Value = second.json().await.unwrap();
+    
+    // Assert
+    assert_eq!(first["imported"], 500);
+    assert_eq!(first["skipped"], 0);
+    assert_eq!(first["errors"].as_array().unwrap().len(), 0);
+    
+    assert_eq!(second["imported"], 0, "Re-import must not duplicate anime");
+    assert_eq!(second["skipped"], 500);
+}
+
+#[tokio::test]
+async fn bulk_import_accepts_multipart_database_file() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = session_token(&app, true).await;
+    let mut entries = offline_fixture(3);
+    
+    // A duplicate source inside the same upload is skipped, an invalid entry is reported
+    entries.push(entries[0].clone());
+    entries.push(json!({ "title": "Missing everything" }));
+    
+    let database_file = json!({
+        "$schema": "https://example.com/anime-offline-database.schema.json",
+        "license": { "name": "ODbL-1.0", "url": "https://opendatacommons.org/licenses/odbl/1-0/" },
+        "repository": "https://github.com/manami-project/anime-offline-database",
+        "scoreRange": { "minInclusive": 1.0, "maxInclusive": 10.0 },
+        "lastUpdate": "2024-01-01",
+        "data": entries
+    });
+    
+    let file = reqwest::multipart::Part::bytes(serde_json::to_vec(&database_file).unwrap())
+        .file_name("anime-offline-database.json")
+        .mime_str("application/json")
+        .unwrap();
+    let form = reqwest::multipart::Form::new().part("file", file);
+    
+    // Act
+    let response = app.client
+        .post(format!("{}/api/anime/bulk", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .multipart(form)
+        .send()
+        .await
+        .expect("Failed to upload database file");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(report["imported"], 3);
+    assert_eq!(report["skipped"], 1);
+    assert_eq!(report["errors"].as_array().unwrap().len(), 1);
+}
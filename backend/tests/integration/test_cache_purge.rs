@@ -0,0 +1,51 @@
+// Integration test for purging cache schema-version namespaces, which is
+// reserved for admins
+
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn session_token(app: &TestApp, admin: bool) -> String {
+    app.state.auth.lock().await
+        .create_session_with_role(&Uuid::new_v4().to_string(), String::new(), admin)
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+async fn purge(app: &TestApp, token: Option<&str>, version: u32) -> reqwest::Response {
+    let mut request = app.client.delete(format!("{}/api/admin/cache/{}", app.address, version));
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    request.send().await.expect("Failed to purge cache version")
+}
+
+#[tokio::test]
+async fn only_admins_can_purge_a_cache_version() {
+    // Arrange
+    let app = spawn_app().await;
+    let user = session_token(&app, false).await;
+    let admin = session_token(&app, true).await;
+    let stale_version = app.state.cache.lock().await.version() + 9000;
+
+    // Act & Assert
+    assert_eq!(purge(&app, None, stale_version).await.status().as_u16(), 401);
+    assert_eq!(purge(&app, Some(&user), stale_version).await.status().as_u16(), 403);
+
+    let purged = purge(&app, Some(&admin), stale_version).await;
+    assert_eq!(purged.status().as_u16(), 200);
+    let body: serde_json::Value = purged.json().await.unwrap();
+    assert_eq!(body["version"], stale_version);
+}
+
+#[tokio::test]
+async fn active_cache_version_cannot_be_purged() {
+    let app = spawn_app().await;
+    let admin = session_token(&app, true).await;
+    let active_version = app.state.cache.lock().await.version();
+
+    assert_eq!(purge(&app, Some(&admin), active_version).await.status().as_u16(), 400);
+}
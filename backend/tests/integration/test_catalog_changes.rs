@@ -0,0 +1,144 @@
+// Integration test for the partner catalog change feed
+
+use chrono::{DateTime, Utc};
+use kensho_backend::middleware::ApiKeyConfig;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app_with, TestApp};
+
+const PARTNER_KEY: &str = "partner-key";
+
+async fn spawn_catalog_app() -> TestApp {
+    spawn_app_with(|state| {
+        state.api_keys = ApiKeyConfig::parse("partner-key=catalog:read,stream-key=stream");
+    })
+    .await
+}
+
+async fn create_anime(app: &TestApp, title: &str) -> String {
+    let created: Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": title,
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "fall", "year": 2024 },
+            "synopsis": "Test anime for the catalog change feed",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    created["id"].as_str().unwrap().to_string()
+}
+
+async fn get_changes(app: &TestApp, key: Option<&str>, query: &[(&str, String)]) -> reqwest::Response {
+    let mut request = app.client
+        .get(format!("{}/api/catalog/changes", app.address))
+        .query(query);
+    if let Some(key) = key {
+        request = request.header("X-API-Key", key);
+    }
+    request.send().await.expect("Failed to fetch changes")
+}
+
+/// Follow cursors from `since`, returning every (id, change) and the final watermark
+async fn collect_changes(app: &TestApp, since: DateTime<Utc>, limit: usize) -> (Vec<(String, String)>, String) {
+    let mut changes = Vec::new();
+    let mut cursor: Option<String> = None;
+
+    loop {
+        let mut query = vec![("since", since.to_rfc3339()), ("limit", limit.to_string())];
+        if let Some(cursor) = &cursor {
+            query.push(("cursor", cursor.clone()));
+        }
+
+        let response = get_changes(app, Some(PARTNER_KEY), &query).await;
+        assert_eq!(response.status(), 200);
+        let page: Value = response.json().await.unwrap();
+
+        let items = page["items"].as_array().unwrap();
+        assert!(items.len() <= limit);
+        for item in items {
+            changes.push((
+                item["id"].as_str().unwrap().to_string(),
+                item["change"].as_str().unwrap().to_string(),
+            ));
+        }
+
+        if !page["has_more"].as_bool().unwrap() {
+            return (changes, page["latest_change"].as_str().unwrap().to_string());
+        }
+        cursor = Some(page["next_cursor"].as_str().unwrap().to_string());
+    }
+}
+
+#[tokio::test]
+async fn test_changes_between_watermarks() {
+    let app = spawn_catalog_app().await;
+
+    let kept = create_anime(&app, "Catalog Kept").await;
+    let removed = create_anime(&app, "Catalog Removed").await;
+
+    let (initial, watermark) = collect_changes(&app, Utc::now() - chrono::Duration::hours(1), 100).await;
+    assert!(initial.contains(&(kept.clone(), "created".to_string())));
+    assert!(initial.contains(&(removed.clone(), "created".to_string())));
+    let watermark: DateTime<Utc> = watermark.parse().unwrap();
+
+    // Mutations after the first watermark
+    let response = app.client
+        .patch(format!("{}/api/anime/{}", app.address, kept))
+        .json(&json!({ "title": "Catalog Kept (Renamed)" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let added = create_anime(&app, "Catalog Added").await;
+    app.state.db.delete_anime(Uuid::parse_str(&removed).unwrap()).await.unwrap();
+
+    // Paged two at a time to cross a cursor boundary
+    let (diff, second_watermark) = collect_changes(&app, watermark, 2).await;
+    assert_eq!(diff, vec![
+        (kept, "updated".to_string()),
+        (added, "created".to_string()),
+        (removed, "deleted".to_string()),
+    ]);
+
+    // Nothing changed after the second watermark
+    let (empty, unchanged) = collect_changes(&app, second_watermark.parse().unwrap(), 2).await;
+    assert!(empty.is_empty());
+    assert_eq!(unchanged, second_watermark);
+}
+
+#[tokio::test]
+async fn test_changes_require_catalog_scope() {
+    let app = spawn_catalog_app().await;
+    let query = [("since", Utc::now().to_rfc3339())];
+
+    assert_eq!(get_changes(&app, None, &query).await.status(), 401);
+    assert_eq!(get_changes(&app, Some("unknown-key"), &query).await.status(), 401);
+    assert_eq!(get_changes(&app, Some("stream-key"), &query).await.status(), 403);
+    assert_eq!(get_changes(&app, Some(PARTNER_KEY), &query).await.status(), 200);
+}
+
+#[tokio::test]
+async fn test_changes_outside_retention_and_bad_cursor() {
+    let app = spawn_catalog_app().await;
+
+    let expired = [("since", (Utc::now() - chrono::Duration::days(91)).to_rfc3339())];
+    assert_eq!(get_changes(&app, Some(PARTNER_KEY), &expired).await.status(), 410);
+
+    let bad_cursor = [("since", Utc::now().to_rfc3339()), ("cursor", "not-a-cursor".to_string())];
+    assert_eq!(get_changes(&app, Some(PARTNER_KEY), &bad_cursor).await.status(), 400);
+}
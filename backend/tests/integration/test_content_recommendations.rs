@@ -0,0 +1,117 @@
+// Integration test for content-based recommendations from rebuilt tag similarity
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn session_token(app: &TestApp, admin: bool) -> String {
+    app.state.auth.lock().await
+        .create_session_with_role(&Uuid::new_v4().to_string(), String::new(), admin)
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+async fn create_anime(app: &TestApp, title: &str, tags: &[String]) -> Uuid {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": title,
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "spring", "year": 2022 },
+            "synopsis": "Test anime for content recommendations",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": tags
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    Uuid::parse_str(created["id"].as_str().unwrap()).unwrap()
+}
+
+async fn rebuild(app: &TestApp, token: &str) -> reqwest::Response {
+    app.client
+        .get(format!("{}/api/admin/recommendations/rebuild", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to trigger rebuild")
+}
+
+#[tokio::test]
+async fn rebuild_recommends_anime_with_related_tags() {
+    let app = spawn_app().await;
+
+    // Tags unique to this run, so anime from other tests never share them
+    let run = Uuid::new_v4();
+    let tag = |name: &str| format!("{}-{}", name, run);
+
+    let giant_robots = create_anime(&app, "Giant Robots", &[tag("mecha"), tag("space"), tag("military")]).await;
+    let robot_war = create_anime(&app, "Robot War", &[tag("mecha"), tag("space"), tag("drama")]).await;
+    let space_drama = create_anime(&app, "Space Drama", &[tag("space"), tag("drama"), tag("romance")]).await;
+    let cooking = create_anime(&app, "Cooking Club", &[tag("cooking"), tag("comedy")]).await;
+
+    let response = rebuild(&app, &session_token(&app, true).await).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let report: serde_json::Value = response.json().await.unwrap();
+    assert!(report["anime"].as_u64().unwrap() >= 4);
+
+    let response = app.client
+        .get(format!("{}/api/anime/{}/recommendations?limit=10", app.address, giant_robots))
+        .send()
+        .await
+        .expect("Failed to fetch recommendations");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    let items = body["recommendations"].as_array().unwrap();
+    let ids: Vec<String> = items.iter().map(|item| item["id"].as_str().unwrap().to_string()).collect();
+
+    // Two shared tags outrank one; nothing in common means no recommendation
+    assert_eq!(ids, vec![robot_war.to_string(), space_drama.to_string()]);
+    assert!(!ids.contains(&cooking.to_string()));
+    assert!(items[0]["score"].as_f64().unwrap() > items[1]["score"].as_f64().unwrap());
+
+    // Rebuilding again replaces the edges rather than duplicating them
+    rebuild(&app, &session_token(&app, true).await).await;
+    let body: serde_json::Value = app.client
+        .get(format!("{}/api/anime/{}/recommendations", app.address, giant_robots))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["total"], 2);
+}
+
+#[tokio::test]
+async fn rebuild_requires_admin() {
+    let app = spawn_app().await;
+
+    let response = rebuild(&app, &session_token(&app, false).await).await;
+    assert_eq!(response.status().as_u16(), 403);
+}
+
+#[tokio::test]
+async fn recommendations_for_unknown_anime_return_404() {
+    let app = spawn_app().await;
+
+    let response = app.client
+        .get(format!("{}/api/anime/{}/recommendations", app.address, Uuid::new_v4()))
+        .send()
+        .await
+        .expect("Failed to fetch recommendations");
+    assert_eq!(response.status().as_u16(), 404);
+}
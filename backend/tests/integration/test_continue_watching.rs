@@ -0,0 +1,125 @@
+// Integration test for the "Continue watching" list of unfinished episodes
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn session_token(app: &TestApp) -> String {
+    app.state.auth.lock().await
+        .create_session(&Uuid::new_v4().to_string(), String::new())
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+/// Create an anime with `count` episodes and return (anime_id, episode_ids)
+async fn create_series(app: &TestApp, title: &str, count: u32) -> (String, Vec<String>) {
+    let anime_data = json!({
+        "title": title,
+        "synonyms": [],
+        "sources": [],
+        "episodes": count,
+        "status": "FINISHED",
+        "anime_type": "TV",
+        "anime_season": { "season": "summer", "year": 2024 },
+        "synopsis": "Test anime for continue watching",
+        "poster_url": "https://example.com/test.jpg",
+        "tags": []
+    });
+    
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&anime_data)
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    let anime_id = created["id"].as_str().unwrap().to_string();
+    
+    let episodes: Vec<_> = (1..=count)
+        .map(|n| json!({ "episode_number": n, "title": format!("Episode {}", n) }))
+        .collect();
+    
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({ "episodes": episodes }))
+        .send()
+        .await
+        .expect("Failed to create episodes")
+        .json()
+        .await
+        .unwrap();
+    
+    let episode_ids = created["ids"].as_array().unwrap()
+        .iter()
+        .map(|id| id.as_str().unwrap().to_string())
+        .collect();
+    
+    (anime_id, episode_ids)
+}
+
+async fn record(app: &TestApp, token: &str, episode_id: &str, progress: u32) {
+    let response = app.client
+        .post(format!("{}/api/user/watch-history", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "episode_id": episode_id,
+            "progress": progress,
+            "total_duration": 1440
+        }))
+        .send()
+        .await
+        .expect("Failed to record watch progress");
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+async fn continue_watching(app: &TestApp, token: &str) -> Vec<serde_json::Value> {
+    let response = app.client
+        .get(format!("{}/api/user/continue-watching", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to fetch continue watching");
+    assert_eq!(response.status().as_u16(), 200);
+    
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn continue_watching_is_empty_without_history() {
+    let app = spawn_app().await;
+    let token = session_token(&app).await;
+    
+    assert!(continue_watching(&app, &token).await.is_empty());
+}
+
+#[tokio::test]
+async fn continue_watching_orders_by_recency_with_one_entry_per_anime() {
+    let app = spawn_app().await;
+    let token = session_token(&app).await;
+    let (first_anime, first_episodes) = create_series(&app, "Continue First", 3).await;
+    let (second_anime, second_episodes) = create_series(&app, "Continue Second", 2).await;
+    
+    record(&app, &token, &first_episodes[0], 300).await;
+    record(&app, &token, &second_episodes[0], 600).await;
+    record(&app, &token, &first_episodes[1], 120).await;
+    // Finished episodes never show up
+    record(&app, &token, &first_episodes[2], 1440).await;
+    
+    let items = continue_watching(&app, &token).await;
+    
+    assert_eq!(items.len(), 2, "One entry per anime");
+    assert_eq!(items[0]["anime_id"], first_anime.as_str());
+    assert_eq!(items[0]["episode_id"], first_episodes[1].as_str());
+    assert_eq!(items[0]["episode_number"], 2);
+    assert_eq!(items[0]["anime_title"], "Continue First");
+    assert_eq!(items[0]["progress_ms"], 120_000);
+    assert_eq!(items[0]["total_duration_ms"], 1_440_000);
+    assert_eq!(items[1]["anime_id"], second_anime.as_str());
+    assert_eq!(items[1]["episode_id"], second_episodes[0].as_str());
+}
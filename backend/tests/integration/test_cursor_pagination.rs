@@ -0,0 +1,135 @@
+// Integration test for cursor pagination on the anime list and seasonal browse
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp, title: &str, year: u16) -> String {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": title,
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "fall", "year": year },
+            "synopsis": "Test anime for cursor pagination",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    created["id"].as_str().unwrap().to_string()
+}
+
+async fn get_page(app: &TestApp, path: &str, cursor: Option<&str>) -> serde_json::Value {
+    let mut url = format!("{}{}", app.address, path);
+    if let Some(cursor) = cursor {
+        url = format!("{}&cursor={}", url, cursor);
+    }
+
+    let response = app.client.get(&url).send().await.expect("Failed to fetch page");
+    assert_eq!(response.status().as_u16(), 200);
+    response.json().await.unwrap()
+}
+
+/// Follow `next_cursor` from the first page to the last, inserting one more
+/// anime in `year` after the first page. Returns every id seen, in order.
+async fn paginate_with_insert(app: &TestApp, path: &str, year: u16) -> (Vec<String>, String) {
+    let mut seen = Vec::new();
+    let mut cursor: Option<String> = None;
+    let mut inserted = None;
+
+    loop {
+        let page = get_page(app, path, cursor.as_deref()).await;
+        let items = page["items"].as_array().unwrap();
+        assert!(items.len() <= 2);
+        seen.extend(items.iter().map(|item| item["id"].as_str().unwrap().to_string()));
+
+        if inserted.is_none() {
+            inserted = Some(create_anime(app, &format!("Late {}", Uuid::new_v4()), year).await);
+        }
+
+        match page["next_cursor"].as_str() {
+            Some(next) => {
+                assert_eq!(page["has_more"], true);
+                cursor = Some(next.to_string());
+            }
+            None => {
+                assert_eq!(page["has_more"], false);
+                break;
+            }
+        }
+    }
+
+    (seen, inserted.unwrap())
+}
+
+#[tokio::test]
+async fn anime_list_cursor_survives_inserts() {
+    let app = spawn_app().await;
+    let mut ids = Vec::new();
+    for i in 0..5 {
+        ids.push(create_anime(&app, &format!("Paged {} {}", i, Uuid::new_v4()), 1983).await);
+    }
+
+    let (seen, inserted) = paginate_with_insert(&app, "/api/anime?limit=2", 1983).await;
+    ids.push(inserted);
+
+    // The database may hold anime from other tests; ours appear once each, in creation order
+    let ours: Vec<String> = seen.into_iter().filter(|id| ids.contains(id)).collect();
+    assert_eq!(ours, ids);
+}
+
+#[tokio::test]
+async fn seasonal_browse_cursor_survives_inserts() {
+    let app = spawn_app().await;
+    let mut ids = Vec::new();
+    for i in 0..5 {
+        ids.push(create_anime(&app, &format!("Seasonal {} {}", i, Uuid::new_v4()), 1979).await);
+    }
+
+    let (seen, inserted) = paginate_with_insert(&app, "/api/browse/season/1979/fall?limit=2", 1979).await;
+    ids.push(inserted);
+
+    let ours: Vec<String> = seen.into_iter().filter(|id| ids.contains(id)).collect();
+    assert_eq!(ours, ids);
+}
+
+#[tokio::test]
+async fn offset_pagination_and_invalid_cursors() {
+    let app = spawn_app().await;
+    for i in 0..3 {
+        create_anime(&app, &format!("Offset {} {}", i, Uuid::new_v4()), 1985).await;
+    }
+
+    // Offset paging still works without a cursor
+    let page = get_page(&app, "/api/anime?limit=2&offset=0", None).await;
+    assert_eq!(page["items"].as_array().unwrap().len(), 2);
+    assert_eq!(page["has_more"], true);
+    assert!(page["next_cursor"].is_null());
+
+    // Browse without paging parameters still returns the whole season
+    let page = get_page(&app, "/api/browse/season/1985/fall?status=finished", None).await;
+    assert!(page["items"].as_array().unwrap().len() >= 3);
+    assert!(page["next_cursor"].is_null());
+
+    for path in ["/api/anime?cursor=not-a-cursor", "/api/browse/season/1985/fall?cursor=not-a-cursor"] {
+        let response = app.client
+            .get(format!("{}{}", app.address, path))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 400);
+    }
+}
@@ -0,0 +1,121 @@
+// Integration test for demo streaming mode: the full stream pipeline runs
+// against a static sample manifest without contacting Crunchyroll
+
+use kensho_backend::services::streaming::DemoStreamConfig;
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app_with, TestApp};
+
+// Unroutable, so any attempt to actually fetch it would fail the test
+const DEMO_URL: &str = "http://127.0.0.1:9/demo/sample.m3u8";
+
+async fn demo_app() -> TestApp {
+    spawn_app_with(|state| {
+        state.streaming = Arc::new((*state.streaming).clone().with_demo(DemoStreamConfig {
+            enabled: true,
+            manifest_url: DEMO_URL.to_string(),
+        }));
+    })
+    .await
+}
+
+/// Create an anime with one episode and return (anime_id, episode_id)
+async fn create_episode(app: &TestApp) -> (String, String) {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": "Demo Series",
+            "synonyms": [],
+            "sources": [],
+            "episodes": 1,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "fall", "year": 2024 },
+            "synopsis": "Test anime for demo streaming",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    let anime_id = created["id"].as_str().unwrap().to_string();
+
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({ "episodes": [{"episode_number": 1, "title": "Episode 1", "duration": 1440}] }))
+        .send()
+        .await
+        .expect("Failed to create episodes")
+        .json()
+        .await
+        .unwrap();
+    let episode_id = created["ids"][0].as_str().unwrap().to_string();
+
+    (anime_id, episode_id)
+}
+
+#[tokio::test]
+async fn demo_stream_runs_the_full_pipeline() {
+    let app = demo_app().await;
+    let token = app.state.auth.lock().await
+        .create_session(&Uuid::new_v4().to_string(), String::new())
+        .await
+        .expect("Failed to create session")
+        .token;
+    let (anime_id, episode_id) = create_episode(&app).await;
+
+    let response = app.client
+        .get(format!("{}/api/stream/{}/1?quality=720p", app.address, anime_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to get stream");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let stream: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(stream["provider"], "demo");
+    assert_eq!(stream["watermark"], true);
+    assert_eq!(stream["quality"], "720p");
+    assert_eq!(stream["streams"][0]["url"], DEMO_URL);
+    assert_eq!(stream["streams"][0]["resolution"], "720p");
+    let expires_at: chrono::DateTime<chrono::Utc> = stream["streams"][0]["expires_at"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(expires_at > chrono::Utc::now());
+    assert_eq!(stream["active_streams"], 1);
+
+    // Resuming hands back the same demo stream without counting a new one
+    let resume_token = stream["resume_token"].as_str().expect("Stream should carry a resume token");
+    let resumed: serde_json::Value = app.client
+        .post(format!("{}/api/stream/resume", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "resume_token": resume_token }))
+        .send()
+        .await
+        .expect("Failed to resume stream")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(resumed["provider"], "demo");
+    assert_eq!(resumed["streams"][0]["url"], DEMO_URL);
+    assert_eq!(resumed["active_streams"], 1);
+
+    // Playback heartbeats are accepted as for any other stream
+    let response = app.client
+        .post(format!("{}/api/user/playback-position", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "episode_id": episode_id, "position": 30, "duration": 1440 }))
+        .send()
+        .await
+        .expect("Failed to save position");
+    assert_eq!(response.status().as_u16(), 200);
+}
@@ -0,0 +1,227 @@
+// Integration test for the device registry: devices named at login, the
+// concurrent stream 409 naming them, and revoking a device
+
+use std::sync::Arc;
+use serde_json::{json, Value};
+use uuid::Uuid;
+use kensho_backend::services::StreamSessionService;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, spawn_app_with, TestApp};
+
+const PASSWORD: &str = "correct horse battery";
+
+async fn register_user(app: &TestApp) -> String {
+    let id = Uuid::new_v4().simple();
+    let email = format!("devices-{}@example.com", id);
+    let response = app.client
+        .post(format!("{}/api/auth/register", app.address))
+        .json(&json!({ "username": format!("devices_{}", &id.to_string()[..12]), "email": email, "password": PASSWORD }))
+        .send()
+        .await
+        .expect("Failed to register");
+    assert_eq!(response.status().as_u16(), 201);
+
+    // Skip the emailed link; registration tests cover it
+    let body: Value = response.json().await.unwrap();
+    let user_id = body["user_id"].as_str().unwrap().parse().unwrap();
+    assert!(app.state.db.mark_email_verified(user_id).await.unwrap());
+    email
+}
+
+async fn login_on(app: &TestApp, email: &str, device: &str) -> String {
+    let response = app.client
+        .post(format!("{}/api/auth/login", app.address))
+        .json(&json!({
+            "email": email,
+            "password": PASSWORD,
+            "device": { "name": device, "platform": "tvos", "client_version": "1.4.0" }
+        }))
+        .send()
+        .await
+        .expect("Failed to log in");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body: Value = response.json().await.unwrap();
+    body["token"].as_str().unwrap().to_string()
+}
+
+async fn devices(app: &TestApp, token: &str) -> Vec<Value> {
+    let body: Value = app.client
+        .get(format!("{}/api/auth/devices", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to list devices")
+        .json()
+        .await
+        .unwrap();
+    body["devices"].as_array().unwrap().clone()
+}
+
+async fn create_episode(app: &TestApp) -> String {
+    let created: Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": format!("Device Series {}", Uuid::new_v4().simple()),
+            "synonyms": [],
+            "sources": [],
+            "episodes": 1,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "spring", "year": 2024 },
+            "synopsis": "Test anime for device stream limits",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    let anime_id = created["id"].as_str().unwrap().to_string();
+
+    app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({ "episodes": [{"episode_number": 1, "title": "Episode 1", "duration": 1440}] }))
+        .send()
+        .await
+        .expect("Failed to create episodes");
+
+    anime_id
+}
+
+async fn stream(app: &TestApp, token: &str, anime_id: &str) -> reqwest::Response {
+    app.client
+        .get(format!("{}/api/stream/{}/1", app.address, anime_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to get stream")
+}
+
+async fn spawn_app_with_stream_limit(limit: usize) -> TestApp {
+    spawn_app_with(|state| {
+        state.stream_sessions = Arc::new(
+            StreamSessionService::new(state.cache.clone()).with_max_concurrent(Some(limit))
+        );
+    }).await
+}
+
+#[tokio::test]
+async fn login_registers_the_named_device() {
+    let app = spawn_app().await;
+    let email = register_user(&app).await;
+
+    let tv = login_on(&app, &email, "Living Room TV").await;
+    let listed = devices(&app, &tv).await;
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["name"], "Living Room TV");
+    assert_eq!(listed[0]["platform"], "tvos");
+    assert_eq!(listed[0]["client_version"], "1.4.0");
+    assert_eq!(listed[0]["current"], true);
+    assert_eq!(listed[0]["sessions"].as_array().unwrap().len(), 1);
+
+    // Signing in again on the same device reuses its record
+    login_on(&app, &email, "Living Room TV").await;
+    let phone = login_on(&app, &email, "Phone").await;
+    let listed = devices(&app, &phone).await;
+    assert_eq!(listed.len(), 2);
+
+    let tv_device = listed.iter().find(|device| device["name"] == "Living Room TV").unwrap();
+    assert_eq!(tv_device["sessions"].as_array().unwrap().len(), 2);
+    assert_eq!(tv_device["current"], false);
+
+    // A blank device name is rejected rather than silently dropped
+    let response = app.client
+        .post(format!("{}/api/auth/login", app.address))
+        .json(&json!({ "email": email, "password": PASSWORD, "device": { "name": "  " } }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn stream_limit_names_the_device_in_use() {
+    let app = spawn_app_with_stream_limit(1).await;
+    let email = register_user(&app).await;
+    let anime_id = create_episode(&app).await;
+
+    let tv = login_on(&app, &email, "Living Room TV").await;
+    assert_eq!(stream(&app, &tv, &anime_id).await.status().as_u16(), 200);
+
+    let phone = login_on(&app, &email, "Phone").await;
+    let response = stream(&app, &phone, &anime_id).await;
+    assert_eq!(response.status().as_u16(), 409);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], "CONCURRENT_STREAM_LIMIT");
+    assert_eq!(body["message"], "Streaming on Living Room TV");
+    assert_eq!(body["limit"], 1);
+    assert_eq!(body["active_streams"][0]["device_name"], "Living Room TV");
+
+    // The streaming device itself can move on to another stream
+    assert_eq!(stream(&app, &tv, &anime_id).await.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn revoking_a_device_ends_its_sessions_and_streams() {
+    let app = spawn_app_with_stream_limit(1).await;
+    let email = register_user(&app).await;
+    let anime_id = create_episode(&app).await;
+
+    let tv = login_on(&app, &email, "Living Room TV").await;
+    let tv_again = login_on(&app, &email, "Living Room TV").await;
+    assert_eq!(stream(&app, &tv, &anime_id).await.status().as_u16(), 200);
+
+    let phone = login_on(&app, &email, "Phone").await;
+    assert_eq!(stream(&app, &phone, &anime_id).await.status().as_u16(), 409);
+
+    let tv_id = devices(&app, &phone).await
+        .into_iter()
+        .find(|device| device["name"] == "Living Room TV")
+        .unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let response = app.client
+        .delete(format!("{}/api/auth/devices/{}", app.address, tv_id))
+        .header("Authorization", format!("Bearer {}", phone))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["revoked_sessions"], 2);
+    assert_eq!(body["ended_streams"], 1);
+
+    // Both TV sessions are signed out, and its stream no longer counts
+    for token in [&tv, &tv_again] {
+        let response = app.client
+            .get(format!("{}/api/auth/devices", app.address))
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 401);
+    }
+    assert_eq!(stream(&app, &phone, &anime_id).await.status().as_u16(), 200);
+
+    let listed = devices(&app, &phone).await;
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0]["name"], "Phone");
+
+    // The device is gone now
+    let response = app.client
+        .delete(format!("{}/api/auth/devices/{}", app.address, tv_id))
+        .header("Authorization", format!("Bearer {}", phone))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -0,0 +1,51 @@
+// Integration test for rejecting anime whose sources are already stored
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn post_anime(app: &TestApp, title: &str, sources: &[&str]) -> reqwest::Response {
+    app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": title,
+            "synonyms": [],
+            "sources": sources,
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "winter", "year": 2020 },
+            "synopsis": "Test anime for duplicate sources",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+}
+
+#[tokio::test]
+async fn posting_the_same_sources_twice_conflicts() {
+    let app = spawn_app().await;
+    let source = format!("https://myanimelist.net/anime/{}", Uuid::new_v4().as_u128() % 1_000_000);
+
+    let first = post_anime(&app, "Original Import", &[&source]).await;
+    assert_eq!(first.status().as_u16(), 201);
+    let created: serde_json::Value = first.json().await.unwrap();
+    let id = created["id"].as_str().unwrap();
+
+    // Any shared source is enough, even alongside new ones
+    let other = format!("https://anilist.co/anime/{}", Uuid::new_v4());
+    let second = post_anime(&app, "Original Import Again", &[&other, &source]).await;
+    assert_eq!(second.status().as_u16(), 409);
+    let body: serde_json::Value = second.json().await.unwrap();
+    assert_eq!(body["code"], "CONFLICT");
+    assert!(body["message"].as_str().unwrap().contains(id));
+
+    let stored = app.state.db.find_anime_by_source(&source).await.unwrap().unwrap();
+    assert_eq!(stored.id.to_string(), id);
+    assert!(app.state.db.find_anime_by_source(&other).await.unwrap().is_none());
+}
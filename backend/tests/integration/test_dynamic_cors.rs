@@ -0,0 +1,128 @@
+// Integration test for the runtime CORS allow-list kept in Redis
+
+use kensho_backend::middleware::CorsConfig;
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app_with, TestApp};
+
+async fn strict_cors_app() -> TestApp {
+    spawn_app_with(|state| {
+        state.cors = CorsConfig {
+            static_origins: vec!["http://localhost:8080".to_string()],
+            permissive: false,
+        };
+    })
+    .await
+}
+
+async fn session_token(app: &TestApp, admin: bool) -> String {
+    app.state.auth.lock().await
+        .create_session_with_role(&Uuid::new_v4().to_string(), String::new(), admin)
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+/// `Access-Control-Allow-Origin` returned for a request from `origin`
+async fn allow_origin(app: &TestApp, origin: &str) -> Option<String> {
+    let response = app.client
+        .get(format!("{}/api/health", app.address))
+        .header("Origin", origin)
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 200);
+
+    response.headers()
+        .get("access-control-allow-origin")
+        .map(|value| value.to_str().unwrap().to_string())
+}
+
+async fn add_origin(app: &TestApp, token: &str, origin: &str) -> reqwest::Response {
+    app.client
+        .post(format!("{}/api/admin/cors/origins", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "origin": origin }))
+        .send()
+        .await
+        .expect("Failed to add origin")
+}
+
+async fn remove_origin(app: &TestApp, token: &str, origin: &str) -> reqwest::Response {
+    app.client
+        .delete(format!(
+            "{}/api/admin/cors/origins/{}",
+            app.address,
+            url::form_urlencoded::byte_serialize(origin.as_bytes()).collect::<String>()
+        ))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to remove origin")
+}
+
+#[tokio::test]
+async fn added_origin_is_allowed_within_the_cache_window() {
+    let app = strict_cors_app().await;
+    let admin = session_token(&app, true).await;
+    let origin = format!("https://{}.partner.example", Uuid::new_v4().simple());
+
+    // Static origins are always allowed; unknown ones get no CORS headers
+    assert_eq!(allow_origin(&app, "http://localhost:8080").await.as_deref(), Some("http://localhost:8080"));
+    assert_eq!(allow_origin(&app, &origin).await, None);
+
+    let response = add_origin(&app, &admin, &origin).await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["origin"], origin.as_str());
+
+    // Reflected straight away, well inside the 60 second snapshot lifetime
+    assert_eq!(allow_origin(&app, &origin).await.as_deref(), Some(origin.as_str()));
+    assert_eq!(add_origin(&app, &admin, &origin).await.status().as_u16(), 200);
+
+    // Preflights from the new origin are answered with credentials allowed
+    let response = app.client
+        .request(reqwest::Method::OPTIONS, format!("{}/api/anime", app.address))
+        .header("Origin", &origin)
+        .header("Access-Control-Request-Method", "POST")
+        .send()
+        .await
+        .expect("Failed to send preflight");
+    assert!(response.status().is_success());
+    assert_eq!(response.headers()["access-control-allow-origin"], origin.as_str());
+    assert_eq!(response.headers()["access-control-allow-credentials"], "true");
+    assert!(response.headers()["access-control-allow-methods"].to_str().unwrap().contains("POST"));
+
+    // Removal is reflected as quickly
+    assert_eq!(remove_origin(&app, &admin, &origin).await.status().as_u16(), 200);
+    assert_eq!(allow_origin(&app, &origin).await, None);
+    assert_eq!(remove_origin(&app, &admin, &origin).await.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn managing_origins_requires_an_admin() {
+    let app = strict_cors_app().await;
+    let user = session_token(&app, false).await;
+    let admin = session_token(&app, true).await;
+    let origin = format!("https://{}.partner.example", Uuid::new_v4().simple());
+
+    assert_eq!(add_origin(&app, &user, &origin).await.status().as_u16(), 403);
+    assert_eq!(remove_origin(&app, &user, &origin).await.status().as_u16(), 403);
+
+    let response = app.client
+        .post(format!("{}/api/admin/cors/origins", app.address))
+        .json(&json!({ "origin": origin }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(allow_origin(&app, &origin).await, None);
+
+    // Only bare http(s) origins can be allowed
+    for invalid in ["*", "null", "https://partner.example/app", "javascript:alert(1)"] {
+        assert_eq!(add_origin(&app, &admin, invalid).await.status().as_u16(), 400);
+    }
+}
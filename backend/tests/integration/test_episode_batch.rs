@@ -0,0 +1,91 @@
+// Integration test for whole-batch validation in DatabaseService::batch_create_episodes
+
+use kensho_backend::middleware::AppError;
+use kensho_backend::models::Episode;
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp) -> Uuid {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": "Batch Validation Anime",
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "winter", "year": 2022 },
+            "synopsis": "Test anime for batch episode validation",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    created["id"].as_str().unwrap().parse().unwrap()
+}
+
+#[tokio::test]
+async fn duplicate_numbers_reject_the_whole_batch() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+
+    let mut bad_thumbnail = Episode::new(anime_id, 3);
+    bad_thumbnail.thumbnail_url = Some("not a url".to_string());
+    let batch = vec![
+        Episode::new(anime_id, 1),
+        Episode::new(anime_id, 2),
+        Episode::new(anime_id, 1),
+        bad_thumbnail,
+    ];
+
+    let error = app.state.db.batch_create_episodes(anime_id, batch).await.unwrap_err();
+    let Some(AppError::ValidationError(errors)) = error.downcast_ref::<AppError>() else {
+        panic!("expected a validation error, got {:?}", error);
+    };
+
+    // Every problem is reported at once, by field path
+    let fields: Vec<&str> = errors.iter().map(|error| error.field.as_str()).collect();
+    assert_eq!(fields, ["episodes[2].episode_number", "episodes[3].thumbnail_url"]);
+
+    // Nothing from the batch was written
+    assert!(app.state.db.get_anime_episodes(anime_id).await.unwrap().is_empty());
+
+    let created = app.state.db
+        .batch_create_episodes(anime_id, vec![Episode::new(anime_id, 1), Episode::new(anime_id, 2)])
+        .await
+        .unwrap();
+    assert_eq!(created.len(), 2);
+    assert_eq!(app.state.db.get_anime_episodes(anime_id).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn nonexistent_anime_is_not_found() {
+    let app = spawn_app().await;
+    let anime_id = Uuid::new_v4();
+
+    let error = app.state.db
+        .batch_create_episodes(anime_id, vec![Episode::new(anime_id, 1)])
+        .await
+        .unwrap_err();
+    assert!(matches!(error.downcast_ref::<AppError>(), Some(AppError::NotFound(_))));
+    assert!(app.state.db.get_anime_episodes(anime_id).await.unwrap().is_empty());
+
+    // The HTTP endpoint maps it to 404
+    let response = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({ "episodes": [{"episode_number": 1}] }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -0,0 +1,119 @@
+// Integration test for declared vs stored episode counts and doctor reconciliation
+
+use chrono::Utc;
+use kensho_backend::middleware::response_cache::invalidate_anime_detail_response;
+use kensho_backend::models::{Anime, AnimeSeason, AnimeStatus, AnimeType, Episode, Season};
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_partially_imported_anime(app: &TestApp) -> Anime {
+    let anime = Anime {
+        id: Uuid::new_v4(),
+        title: "Partially Imported".to_string(),
+        synonyms: vec![],
+        sources: vec![],
+        episodes: 12,
+        status: AnimeStatus::Ongoing,
+        anime_type: AnimeType::TV,
+        anime_season: AnimeSeason {
+            season: Season::Spring,
+            year: 2024,
+        },
+        synopsis: "Only some episodes made it in".to_string(),
+        poster_url: "https://example.com/poster.jpg".to_string(),
+        posters: vec![],
+        stored_episode_count: 0,
+        popularity: 0.0,
+        romaji_titles: Vec::new(),
+        imdb: None,
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+    };
+    
+    app.state.db.create_anime(&anime).await.expect("Failed to create anime");
+    
+    for number in 1..=3 {
+        app.state.db
+            .create_episode(&Episode::new(anime.id, number))
+            .await
+            .expect("Failed to create episode");
+    }
+    
+    anime
+}
+
+async fn fetch_detail(app: &TestApp, id: Uuid) -> serde_json::Value {
+    let response = app.client
+        .get(format!("{}/api/anime/{}", app.address, id))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    assert_eq!(response.status().as_u16(), 200);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn anime_detail_reports_declared_and_available_episodes() {
+    // Arrange
+    let app = spawn_app().await;
+    let anime = create_partially_imported_anime(&app).await;
+    
+    // Act
+    let detail = fetch_detail(&app, anime.id).await;
+    
+    // Assert
+    assert_eq!(detail["declared_episodes"], 12);
+    assert_eq!(detail["available_episodes"], 3);
+}
+
+#[tokio::test]
+async fn episode_upsert_and_delete_keep_counter_in_sync() {
+    // Arrange
+    let app = spawn_app().await;
+    let anime = create_partially_imported_anime(&app).await;
+    let episodes = app.state.db.get_anime_episodes(anime.id).await.unwrap();
+    
+    // Act - re-upserting an existing episode must not double-count
+    app.state.db.upsert_episode(&episodes[0]).await.unwrap();
+    app.state.db.delete_episode(anime.id, episodes[1].id).await.unwrap();
+    
+    // Assert
+    let stored = app.state.db.get_anime(anime.id).await.unwrap().unwrap();
+    assert_eq!(stored.stored_episode_count, 2);
+}
+
+#[tokio::test]
+async fn doctor_reconciles_corrupted_episode_counter() {
+    // Arrange
+    let app = spawn_app().await;
+    let anime = create_partially_imported_anime(&app).await;
+    
+    let mut corrupted = app.state.db.get_anime(anime.id).await.unwrap().unwrap();
+    corrupted.stored_episode_count = 9;
+    app.state.db.update_anime(&corrupted).await.unwrap();
+    
+    // Act - a dry run reports without writing
+    let report = app.state.db.reconcile_episode_counts(false).await.unwrap();
+    assert_eq!(report.len(), 1);
+    assert_eq!(report[0].recorded, 9);
+    assert_eq!(report[0].actual, 3);
+    assert_eq!(fetch_detail(&app, anime.id).await["available_episodes"], 9);
+    
+    let fixes = app.state.db.reconcile_episode_counts(true).await.unwrap();
+    
+    // Assert
+    assert_eq!(fixes.len(), 1);
+    assert_eq!(fixes[0].anime_id, anime.id);
+    
+    // doctor runs outside the server, so the cached detail lives out its TTL
+    invalidate_anime_detail_response(&app.state, anime.id).await;
+    
+    let detail = fetch_detail(&app, anime.id).await;
+    assert_eq!(detail["declared_episodes"], 12);
+    assert_eq!(detail["available_episodes"], 3);
+    assert!(app.state.db.reconcile_episode_counts(true).await.unwrap().is_empty());
+}
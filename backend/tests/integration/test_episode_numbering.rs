@@ -0,0 +1,165 @@
+// Integration test for absolute vs per-season episode numbering: imports are
+// renumbered on the way in, older imports by the admin action, and the episode
+// list can report either number
+
+use kensho_backend::models::Episode;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn session_token(app: &TestApp, admin: bool) -> String {
+    app.state.auth.lock().await
+        .create_session_with_role(&Uuid::new_v4().to_string(), String::new(), admin)
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+async fn create_anime(app: &TestApp) -> Uuid {
+    let created: Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": format!("Long Runner {}", Uuid::new_v4().simple()),
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "ONGOING",
+            "anime_type": "TV",
+            "anime_season": { "season": "fall", "year": 2023 },
+            "synopsis": "Test anime for episode numbering",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    created["id"].as_str().unwrap().parse().unwrap()
+}
+
+async fn episode_numbers(app: &TestApp, anime_id: Uuid, numbering: &str) -> Vec<(u64, Option<u64>)> {
+    let body: Value = app.client
+        .get(format!("{}/api/anime/{}/episodes?numbering={}", app.address, anime_id, numbering))
+        .send()
+        .await
+        .expect("Failed to list episodes")
+        .json()
+        .await
+        .unwrap();
+
+    body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| (item["episode_number"].as_u64().unwrap(), item["absolute_number"].as_u64()))
+        .collect()
+}
+
+#[tokio::test]
+async fn absolute_imports_are_renumbered_per_season() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+
+    let response = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({
+            "episodes": (1042..=1044).map(|number| json!({ "episode_number": number })).collect::<Vec<_>>()
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 201);
+
+    assert_eq!(
+        episode_numbers(&app, anime_id, "season").await,
+        [(1, Some(1042)), (2, Some(1043)), (3, Some(1044))]
+    );
+    assert_eq!(
+        episode_numbers(&app, anime_id, "absolute").await,
+        [(1042, Some(1042)), (1043, Some(1043)), (1044, Some(1044))]
+    );
+
+    // A later episode with its absolute number continues the season
+    app.state.db.upsert_episode(&Episode::new(anime_id, 1045)).await.unwrap();
+    assert_eq!(episode_numbers(&app, anime_id, "season").await[3], (4, Some(1045)));
+
+    // Deep links keep using the per-season number
+    let token = session_token(&app, false).await;
+    let response = app.client
+        .get(format!("{}/api/stream/{}/4", app.address, anime_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+
+    // Unknown numbering schemes are rejected
+    let response = app.client
+        .get(format!("{}/api/anime/{}/episodes?numbering=broadcast", app.address, anime_id))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn explicit_absolute_numbers_are_stored_as_given() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+
+    let response = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({
+            "episodes": [
+                { "episode_number": 1, "absolute_number": 1042 },
+                { "episode_number": 2, "absolute_number": 1043 }
+            ]
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 201);
+
+    assert_eq!(episode_numbers(&app, anime_id, "season").await, [(1, Some(1042)), (2, Some(1043))]);
+}
+
+#[tokio::test]
+async fn admin_renumbers_earlier_absolute_imports() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app).await;
+
+    // Written before normalization existed: raw absolute numbers
+    for number in 1042..=1044 {
+        app.state.db.create_episode(&Episode::new(anime_id, number)).await.unwrap();
+    }
+    assert_eq!(episode_numbers(&app, anime_id, "season").await[0], (1042, None));
+
+    let url = format!("{}/api/admin/anime/{}/episodes/renumber", app.address, anime_id);
+    let user = session_token(&app, false).await;
+    let response = app.client.post(&url).header("Authorization", format!("Bearer {}", user)).send().await.unwrap();
+    assert_eq!(response.status().as_u16(), 403);
+
+    let admin = session_token(&app, true).await;
+    let response = app.client.post(&url).header("Authorization", format!("Bearer {}", admin)).send().await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+
+    let report: Value = response.json().await.unwrap();
+    assert_eq!(report["offset"], 1041);
+    assert_eq!(report["renumbered"], 3);
+    assert_eq!(
+        episode_numbers(&app, anime_id, "season").await,
+        [(1, Some(1042)), (2, Some(1043)), (3, Some(1044))]
+    );
+
+    // Running it again finds nothing left to do
+    let response = app.client.post(&url).header("Authorization", format!("Bearer {}", admin)).send().await.unwrap();
+    let report: Value = response.json().await.unwrap();
+    assert_eq!(report["offset"], Value::Null);
+    assert_eq!(report["renumbered"], 0);
+}
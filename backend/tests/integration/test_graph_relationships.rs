@@ -0,0 +1,118 @@
+// Integration test for graph edges written with typed record ids: a related tag
+// is found again by `get_anime_tags`, and the 2-hop tag traversal finds anime
+// sharing it
+
+use kensho_backend::models::{Tag, TagCategory};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp, title: &str) -> Uuid {
+    let created: Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": format!("{} {}", title, Uuid::new_v4().simple()),
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "winter", "year": 2022 },
+            "synopsis": "Test anime for graph relationships",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    created["id"].as_str().unwrap().parse().unwrap()
+}
+
+async fn create_tag(app: &TestApp, name: &str) -> Tag {
+    app.state.db
+        .create_tag(&Tag::new(format!("{} {}", name, Uuid::new_v4().simple()), TagCategory::Genre))
+        .await
+        .expect("Failed to create tag")
+}
+
+#[tokio::test]
+async fn related_tag_is_returned_by_get_anime_tags() {
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app, "Tagged").await;
+    let tag = create_tag(&app, "Cyberpunk").await;
+
+    app.state.db.create_anime_tag_relationship(anime_id, tag.id, 0.9).await.unwrap();
+
+    let tags = app.state.db.get_anime_tags(anime_id).await.unwrap();
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].id, tag.id);
+    assert_eq!(tags[0].name, tag.name);
+
+    let relevance = app.state.db.get_anime_tags_with_relevance(anime_id).await.unwrap();
+    assert!((relevance[0].1 - 0.9).abs() < f32::EPSILON);
+
+    let detail: Value = app.client
+        .get(format!("{}/api/anime/{}", app.address, anime_id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(detail["tags"][0]["id"], tag.id.to_string());
+}
+
+#[tokio::test]
+async fn two_hop_traversal_finds_anime_sharing_a_tag() {
+    // Arrange
+    let app = spawn_app().await;
+    let anime_id = create_anime(&app, "Original").await;
+    let sibling_id = create_anime(&app, "Sibling").await;
+    let stranger_id = create_anime(&app, "Stranger").await;
+    let shared = create_tag(&app, "Space Opera").await;
+    let other = create_tag(&app, "Cooking").await;
+
+    for id in [anime_id, sibling_id] {
+        app.state.db.create_anime_tag_relationship(id, shared.id, 1.0).await.unwrap();
+    }
+    app.state.db.create_anime_tag_relationship(stranger_id, other.id, 1.0).await.unwrap();
+
+    // Act
+    let similar = app.state.db.get_similar_anime(anime_id, 10).await.unwrap();
+
+    // Assert
+    let ids: Vec<Uuid> = similar.iter().map(|anime| anime.id).collect();
+    assert_eq!(ids, [sibling_id], "Only the anime sharing a tag, never the anime itself");
+
+    let body: Value = app.client
+        .get(format!("{}/api/anime/{}/similar", app.address, anime_id))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["similar"][0]["id"], sibling_id.to_string());
+}
+
+#[tokio::test]
+async fn sequel_and_similarity_edges_link_records() {
+    let app = spawn_app().await;
+    let first = create_anime(&app, "Part One").await;
+    let second = create_anime(&app, "Part Two").await;
+
+    app.state.db.create_sequel_relationship(second, first).await.unwrap();
+    app.state.db.create_similarity_relationship(first, second, 0.7).await.unwrap();
+
+    // Cascading deletion finds both edges through the anime's record id
+    let report = app.state.db.delete_anime_cascade(first).await.unwrap();
+    assert_eq!(report.edges_deleted, 2);
+}
@@ -0,0 +1,57 @@
+// Integration test for the Kubernetes health probes
+
+use kensho_backend::services::health::refresh_component_health;
+use kensho_backend::services::DatabaseService;
+use std::sync::Arc;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, spawn_app_with, TestApp};
+
+async fn get(app: &TestApp, path: &str) -> reqwest::Response {
+    app.client
+        .get(format!("{}{}", app.address, path))
+        .send()
+        .await
+        .expect("Failed to send request")
+}
+
+#[tokio::test]
+async fn health_reports_checked_components() {
+    let app = spawn_app().await;
+    refresh_component_health(&app.state.health, &app.state).await;
+    
+    let health: serde_json::Value = get(&app, "/api/health").await.json().await.unwrap();
+    let database = health["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|check| check["name"] == "database")
+        .unwrap_or_else(|| panic!("no database check in {}", health));
+    assert_eq!(database["status"], "healthy");
+    assert!(health["version"].is_string());
+}
+
+#[tokio::test]
+async fn unreachable_database_fails_readiness_but_not_liveness() {
+    let app = spawn_app_with(|state| {
+        state.db = Arc::new(DatabaseService::disconnected());
+    }).await;
+    refresh_component_health(&app.state.health, &app.state).await;
+    
+    let ready = get(&app, "/api/health/ready").await;
+    assert_eq!(ready.status().as_u16(), 503);
+    let ready: serde_json::Value = ready.json().await.unwrap();
+    assert_eq!(ready["ready"], false);
+    assert!(
+        ready["failing_checks"].as_array().unwrap().iter().any(|name| name == "database"),
+        "database not failing in {}", ready
+    );
+    
+    let live = get(&app, "/api/health/live").await;
+    assert_eq!(live.status().as_u16(), 200);
+    let live: serde_json::Value = live.json().await.unwrap();
+    assert_eq!(live["alive"], true);
+    
+    assert_eq!(get(&app, "/api/health").await.status().as_u16(), 503);
+}
@@ -0,0 +1,80 @@
+// Integration test for kana queries finding romaji-titled anime
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp, title: &str, synonyms: &[&str]) -> String {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": title,
+            "synonyms": synonyms,
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "summer", "year": 2019 },
+            "synopsis": "Test anime for kana search",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    created["id"].as_str().unwrap().to_string()
+}
+
+async fn search_ids(app: &TestApp, query: &str) -> Vec<String> {
+    let response = app.client
+        .get(format!("{}/api/search", app.address))
+        .query(&[("q", query), ("limit", "100")])
+        .send()
+        .await
+        .expect("Failed to search");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body: serde_json::Value = response.json().await.unwrap();
+    body["items"].as_array().unwrap()
+        .iter()
+        .map(|item| item["id"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn katakana_query_finds_romaji_title() {
+    let app = spawn_app().await;
+    let id = create_anime(&app, &format!("Hoshizora Tanken {}", Uuid::new_v4()), &[]).await;
+
+    assert!(search_ids(&app, "ホシゾラ").await.contains(&id));
+    // Whole titles typed without spaces still match the spaced romaji
+    assert!(search_ids(&app, "ほしぞらたんけん").await.contains(&id));
+    assert!(!search_ids(&app, "ホシゾラタンケンナイ").await.contains(&id));
+}
+
+#[tokio::test]
+async fn kana_query_merges_raw_and_transliterated_matches() {
+    let app = spawn_app().await;
+    let run = Uuid::new_v4().simple().to_string();
+
+    // One anime lists the kana as a synonym, the other only has a romaji title
+    let kana_synonym = create_anime(&app, &format!("Star Voyage {}", run), &["ギンガリョコウ"]).await;
+    let romaji_title = create_anime(&app, &format!("Ginga Ryoko {}", run), &[]).await;
+
+    let ids = search_ids(&app, "ギンガリョコウ").await;
+    let position = |id: &String| ids.iter().position(|found| found == id);
+    let (Some(raw), Some(romaji)) = (position(&kana_synonym), position(&romaji_title)) else {
+        panic!("Both anime should be found, got {:?}", ids);
+    };
+
+    // Raw matches come first and nothing is listed twice
+    assert!(raw < romaji);
+    assert_eq!(ids.iter().filter(|id| **id == kana_synonym).count(), 1);
+}
@@ -0,0 +1,101 @@
+// Integration test for the Prometheus metrics endpoint
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::spawn_app;
+
+#[tokio::test]
+async fn health_requests_are_counted_in_metrics() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    // Act
+    let health = app.client
+        .get(format!("{}/api/health", app.address))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(health.status().as_u16(), 200);
+    
+    let response = app.client
+        .get(format!("{}/metrics", app.address))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let content_type = response.headers()["content-type"].to_str().unwrap().to_string();
+    assert!(content_type.starts_with("text/plain"), "unexpected content type {}", content_type);
+    
+    let body = response.text().await.expect("Failed to read body");
+    let counter = body
+        .lines()
+        .find(|line| line.starts_with(r#"http_requests_total{method="GET",route="/api/health",status="200"}"#))
+        .unwrap_or_else(|| panic!("no /api/health counter in:\n{}", body));
+    
+    // spawn_app polls /api/health until the server is up, so the count is at least one
+    let count: u64 = counter.rsplit(' ').next().unwrap().parse().unwrap();
+    assert!(count >= 1);
+    assert!(body.contains("http_request_duration_seconds_bucket"));
+}
+
+/// Value of the series starting with `prefix`, 0 when it was never recorded
+fn series_value(body: &str, prefix: &str) -> u64 {
+    body.lines()
+        .find(|line| line.starts_with(prefix))
+        .map(|line| line.rsplit(' ').next().unwrap().parse().unwrap())
+        .unwrap_or(0)
+}
+
+async fn scrape(app: &common::TestApp) -> String {
+    app.client
+        .get(format!("{}/metrics", app.address))
+        .send()
+        .await
+        .expect("Failed to scrape metrics")
+        .text()
+        .await
+        .expect("Failed to read body")
+}
+
+#[tokio::test]
+async fn status_classes_and_cache_lookups_are_counted() {
+    let app = spawn_app().await;
+    let before = scrape(&app).await;
+    
+    let missing = app.client
+        .get(format!("{}/api/anime/{}", app.address, uuid::Uuid::new_v4()))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(missing.status().as_u16(), 404);
+    
+    for _ in 0..2 {
+        let search = app.client
+            .get(format!("{}/api/search?q=metrics-{}", app.address, uuid::Uuid::new_v4()))
+            .send()
+            .await
+            .expect("Failed to send request");
+        assert_eq!(search.status().as_u16(), 200);
+    }
+    
+    let after = scrape(&app).await;
+    let grew = |prefix: &str| series_value(&after, prefix) - series_value(&before, prefix);
+    assert!(grew(r#"http_responses_total{class="4xx"}"#) >= 1);
+    assert!(grew(r#"http_responses_total{class="2xx"}"#) >= 2);
+    assert!(grew(r#"cache_requests_total{cache="anime",result="miss"}"#) >= 1);
+    assert!(grew(r#"cache_requests_total{cache="search",result="miss"}"#) >= 2);
+}
+
+#[tokio::test]
+async fn metrics_are_not_on_the_api_port_when_metrics_port_is_set() {
+    let app = common::spawn_app_with(|state| state.metrics_endpoint.port = Some(0)).await;
+    
+    let response = app.client
+        .get(format!("{}/metrics", app.address))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -0,0 +1,87 @@
+// Integration test for importing an anime-offline-database file with
+// deduplication, tags and relationship edges
+
+use kensho_backend::services::data_loader::{import_offline_database, ImportReport};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::spawn_app;
+
+fn entry(title: &str, sources: &[String], season: (&str, u16), tags: &[String], related: &[String]) -> Value {
+    json!({
+        "sources": sources,
+        "title": title,
+        "type": "TV",
+        "episodes": 12,
+        "status": "FINISHED",
+        "animeSeason": { "season": season.0, "year": season.1 },
+        "picture": "https://cdn.example.com/poster.jpg",
+        "thumbnail": "https://cdn.example.com/thumb.jpg",
+        "duration": { "value": 1440, "unit": "SECONDS" },
+        "score": null,
+        "synonyms": [],
+        "studios": ["Fixture Studio"],
+        "producers": [],
+        "relatedAnime": related,
+        "tags": tags
+    })
+}
+
+/// Four entries unique to this run: a show, its second season, a related show,
+/// and a duplicate of the first by one of its sources
+fn write_fixture(run: Uuid) -> (PathBuf, Vec<String>) {
+    let mal = |n: u32| format!("https://myanimelist.net/anime/{}-{}", run, n);
+    let anilist = format!("https://anilist.co/anime/{}-1", run);
+    let tag = |name: &str| format!("{} {}", name, run.simple());
+
+    let data = vec![
+        entry(&format!("Fixture {}", run), &[mal(1), anilist.clone()], ("SPRING", 2020), &[tag("action"), tag("drama")], &[mal(2)]),
+        entry(&format!("Fixture {} Season 2", run), &[mal(2)], ("SPRING", 2021), &[tag("action")], &[mal(1)]),
+        entry(&format!("Other {}", run), &[mal(3)], ("FALL", 2020), &[tag("comedy")], &[mal(1), mal(99)]),
+        entry(&format!("Duplicate {}", run), &[anilist], ("SPRING", 2020), &[tag("action")], &[]),
+    ];
+
+    let database = json!({
+        "$schema": "https://example.com/anime-offline-database.schema.json",
+        "license": { "name": "ODbL", "url": "https://example.com/LICENSE" },
+        "repository": "https://github.com/manami-project/anime-offline-database",
+        "scoreRange": { "minInclusive": 1.0, "maxInclusive": 10.0 },
+        "lastUpdate": "2025-08-04",
+        "data": data
+    });
+
+    let path = std::env::temp_dir().join(format!("anime-offline-database-{}.json", run));
+    std::fs::write(&path, serde_json::to_vec(&database).unwrap()).unwrap();
+    (path, vec![mal(1), mal(2), mal(3)])
+}
+
+#[tokio::test]
+async fn import_counts_created_skipped_tags_and_relationships() {
+    // Arrange
+    let app = spawn_app().await;
+    let (path, sources) = write_fixture(Uuid::new_v4());
+
+    // Act
+    let report = import_offline_database(&app.state.db, &path, 2).await.expect("Import failed");
+
+    // Assert
+    assert_eq!(report, ImportReport {
+        created: 3,
+        skipped: 1,
+        malformed: 0,
+        tags_created: 3,
+        relationships_created: 2,
+    });
+
+    let first = app.state.db.find_anime_by_source(&sources[0]).await.unwrap().expect("First entry stored");
+    assert_eq!(app.state.db.get_anime_tags(first.id).await.unwrap().len(), 2);
+
+    // Everything is known now, so a second run only skips
+    let again = import_offline_database(&app.state.db, &path, 2).await.expect("Import failed");
+    assert_eq!(again, ImportReport { skipped: 4, ..ImportReport::default() });
+
+    std::fs::remove_file(path).ok();
+}
@@ -0,0 +1,75 @@
+// Integration test for merging re-imported offline database entries into the
+// anime already stored, instead of duplicating them
+
+use kensho_backend::models::anime_offline_db::AnimeOfflineDatabase;
+use kensho_backend::models::{Episode, Tag, TagCategory};
+use kensho_backend::services::database_v2::UpsertOutcome;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/anime-offline-database-10.json");
+
+/// Upsert every fixture entry; returns the stored anime ids by title and how
+/// many entries were created, updated and left unchanged
+async fn import_fixture(app: &TestApp) -> (HashMap<String, Uuid>, [usize; 3]) {
+    let mut entries = AnimeOfflineDatabase::stream_entries(FIXTURE).expect("Fixture streams");
+    let score_range = entries.score_range.clone();
+    let mut ids = HashMap::new();
+    let mut counts = [0; 3];
+
+    for entry in entries.by_ref() {
+        let entry = entry.unwrap();
+        let outcome = app.state.db
+            .upsert_anime_from_offline_entry(&entry, &score_range)
+            .await
+            .expect("Upsert failed");
+        counts[match outcome {
+            UpsertOutcome::Created(_) => 0,
+            UpsertOutcome::Updated(_) => 1,
+            UpsertOutcome::Unchanged(_) => 2,
+        }] += 1;
+        ids.insert(entry.title, outcome.anime().id);
+    }
+    (ids, counts)
+}
+
+#[tokio::test]
+async fn importing_the_same_fixture_twice_merges_into_existing_anime() {
+    // Arrange
+    let app = spawn_app().await;
+    let (first_ids, first_counts) = import_fixture(&app).await;
+
+    // The tenth entry shares the first one's MAL source, so it lands on the same record
+    assert_eq!(first_counts[0], 9);
+    assert_eq!(first_counts[0] + first_counts[1] + first_counts[2], 10);
+    assert_eq!(app.state.db.get_anime_count().await.unwrap(), 9);
+    assert_eq!(first_ids["!NVADE SHOW!"], first_ids["!NVADE SHOW! (Re-release)"]);
+
+    let anime_id = first_ids["!NVADE SHOW!"];
+    let tag = app.state.db
+        .create_tag(&Tag::new(format!("Upsert {}", Uuid::new_v4().simple()), TagCategory::Genre))
+        .await
+        .unwrap();
+    app.state.db.create_anime_tag_relationship(anime_id, tag.id, 1.0).await.unwrap();
+    app.state.db.create_episode(&Episode::new(anime_id, 1)).await.unwrap();
+
+    // Act
+    let (second_ids, second_counts) = import_fixture(&app).await;
+
+    // Assert
+    assert_eq!(second_counts, [0, 0, 10]);
+    assert_eq!(app.state.db.get_anime_count().await.unwrap(), 9);
+    assert_eq!(second_ids, first_ids);
+
+    let tags = app.state.db.get_anime_tags(anime_id).await.unwrap();
+    assert!(tags.iter().any(|stored| stored.id == tag.id));
+    assert_eq!(app.state.db.get_anime_episodes(anime_id).await.unwrap().len(), 1);
+
+    let merged = app.state.db.get_anime(anime_id).await.unwrap().expect("Merged anime stored");
+    assert_eq!(merged.stored_episode_count, 1);
+    assert!(merged.sources.contains(&"https://anilist.co/anime/142051".to_string()));
+}
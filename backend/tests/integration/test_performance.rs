@@ -33,7 +33,7 @@ async fn api_responses_under_200ms_threshold() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -65,7 +65,7 @@ async fn api_responses_under_200ms_threshold() {
                 .get(&url)
                 .send()
                 .await
-                .expect(&format!("Failed to get {}", name));
+                .unwrap_or_else(|_| panic!("Failed to get {}", name));
             
             let duration = start.elapsed();
             response_times.push(duration);
@@ -125,7 +125,7 @@ async fn concurrent_requests_maintain_performance() {
         });
         
         app.client
-            .post(&format!("{}/api/anime", app.address))
+            .post(format!("{}/api/anime", app.address))
             .json(&anime_data)
             .send()
             .await
@@ -146,7 +146,7 @@ async fn concurrent_requests_maintain_performance() {
             let req_start = Instant::now();
             
             let response = client
-                .get(&format!("{}/api/anime", address))
+                .get(format!("{}/api/anime", address))
                 .send()
                 .await;
             
@@ -165,12 +165,10 @@ async fn concurrent_requests_maintain_performance() {
     let mut successful = 0;
     let mut total_response_time = Duration::ZERO;
     
-    for result in results {
-        if let Ok((_, success, duration)) = result {
-            if success {
-                successful += 1;
-                total_response_time += duration;
-            }
+    for (_, success, duration) in results.into_iter().flatten() {
+        if success {
+            successful += 1;
+            total_response_time += duration;
         }
     }
     
@@ -224,7 +222,7 @@ async fn database_query_performance() {
         });
         
         app.client
-            .post(&format!("{}/api/anime", app.address))
+            .post(format!("{}/api/anime", app.address))
             .json(&anime_data)
             .send()
             .await
@@ -247,7 +245,7 @@ async fn database_query_performance() {
             .get(&url)
             .send()
             .await
-            .expect(&format!("Failed query: {}", name));
+            .unwrap_or_else(|_| panic!("Failed query: {}", name));
         
         let duration = start.elapsed();
         
@@ -289,7 +287,7 @@ async fn cache_improves_response_times() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -329,6 +327,8 @@ async fn cache_improves_response_times() {
         .expect("Third request failed");
     let duration3 = start3.elapsed();
     assert!(response3.status().is_success());
+    assert_eq!(response1.headers()["x-cache"], "MISS");
+    assert_eq!(response3.headers()["x-cache"], "HIT");
     
     println!(
         "Cache test - 1st: {}ms, 2nd: {}ms, 3rd: {}ms",
@@ -349,7 +349,7 @@ async fn cache_improves_response_times() {
 async fn authenticated_endpoints_performance() {
     // Arrange
     let app = spawn_app().await;
-    let token = create_test_token();
+    let token = create_test_token(&app.state).await;
     
     // Test authenticated endpoint performance
     let endpoints = vec![
@@ -370,7 +370,7 @@ async fn authenticated_endpoints_performance() {
                 .header("Authorization", format!("Bearer {}", token))
                 .send()
                 .await
-                .expect(&format!("Failed to get {}", name));
+                .unwrap_or_else(|_| panic!("Failed to get {}", name));
             
             let duration = start.elapsed();
             times.push(duration);
@@ -396,4 +396,89 @@ async fn authenticated_endpoints_performance() {
             avg_millis
         );
     }
-}
\ No newline at end of file
+}
+/// p99 of `samples`
+fn p99(mut samples: Vec<Duration>) -> Duration {
+    samples.sort();
+    let index = ((samples.len() as f64 * 0.99).ceil() as usize).saturating_sub(1);
+    samples[index.min(samples.len() - 1)]
+}
+
+async fn seasonal_browse_p99(db: &kensho_backend::services::DatabaseService, rounds: usize) -> Duration {
+    use kensho_backend::models::AnimeStatus;
+    use kensho_backend::services::{BrowseSort, SeasonFilter};
+    
+    let filter = SeasonFilter { status: Some(AnimeStatus::Finished), ..SeasonFilter::default() };
+    let seasons = ["winter", "spring", "summer", "fall"];
+    
+    let mut samples = Vec::with_capacity(rounds);
+    for round in 0..rounds {
+        let year = 2000 + (round % 25) as u16;
+        let start = Instant::now();
+        let page = db
+            .get_seasonal_anime(year, seasons[round % 4], &filter, BrowseSort::default(), Some(24), 0)
+            .await
+            .expect("Failed to browse season");
+        samples.push(start.elapsed());
+        assert!(!page.is_empty(), "{} {} returned nothing", seasons[round % 4], year);
+    }
+    p99(samples)
+}
+
+#[tokio::test]
+#[ignore] // Seeds 10,000 anime; run with --ignored
+async fn bench_seasonal_browse() {
+    use kensho_backend::models::{Anime, AnimeSeason, AnimeStatus, AnimeType, Season};
+    use kensho_backend::services::database_v2::SEASON_BROWSE_INDEX;
+    
+    let app = spawn_app().await;
+    
+    // 25 years x 4 seasons, 100 anime each. Status alternates across years
+    // rather than seasons, so every season has finished anime to browse
+    let seasons = [Season::Winter, Season::Spring, Season::Summer, Season::Fall];
+    let catalog: Vec<Anime> = (0..10_000)
+        .map(|i| Anime {
+            id: uuid::Uuid::new_v4(),
+            title: format!("Seasonal Bench {}", i),
+            synonyms: vec![],
+            sources: vec![],
+            episodes: 12,
+            status: if (i / 4) % 2 == 0 { AnimeStatus::Finished } else { AnimeStatus::Ongoing },
+            anime_type: AnimeType::TV,
+            anime_season: AnimeSeason {
+                season: seasons[i % 4].clone(),
+                year: 2000 + ((i / 4) % 25) as u16,
+            },
+            synopsis: String::new(),
+            poster_url: "https://example.com/bench.jpg".to_string(),
+            posters: vec![],
+            stored_episode_count: 0,
+            popularity: 0.0,
+            romaji_titles: vec![],
+            imdb: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        })
+        .collect();
+    let created = app.state.db.batch_create_anime(catalog).await.expect("Failed to seed anime");
+    assert_eq!(created, 10_000);
+    
+    // Warm up, then measure with the compound index in place
+    seasonal_browse_p99(&app.state.db, 20).await;
+    let indexed = seasonal_browse_p99(&app.state.db, 200).await;
+    
+    app.state.db.remove_index("anime", SEASON_BROWSE_INDEX).await.expect("Failed to remove index");
+    app.state.db.remove_index("anime", "anime_season").await.expect("Failed to remove index");
+    let unindexed = seasonal_browse_p99(&app.state.db, 200).await;
+    
+    println!(
+        "Seasonal browse over 10,000 anime: p99 {}ms without season indexes, {}ms with",
+        unindexed.as_millis(),
+        indexed.as_millis()
+    );
+    assert!(
+        indexed < Duration::from_millis(50),
+        "Seasonal browse p99 {}ms exceeds 50ms with the index",
+        indexed.as_millis()
+    );
+}
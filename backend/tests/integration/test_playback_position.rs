@@ -0,0 +1,208 @@
+// Integration test for saving and resuming playback positions
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn session_token(app: &TestApp) -> String {
+    app.state.auth.lock().await
+        .create_session(&Uuid::new_v4().to_string(), String::new())
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+/// Create an anime with two episodes and return (anime_id, episode_ids)
+async fn create_series(app: &TestApp) -> (String, Vec<String>) {
+    let anime_data = json!({
+        "title": "Playback Series",
+        "synonyms": [],
+        "sources": [],
+        "episodes": 2,
+        "status": "FINISHED",
+        "anime_type": "TV",
+        "anime_season": { "season": "spring", "year": 2024 },
+        "synopsis": "Test anime for playback positions",
+        "poster_url": "https://example.com/test.jpg",
+        "tags": []
+    });
+    
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&anime_data)
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    let anime_id = created["id"].as_str().unwrap().to_string();
+    
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({
+            "episodes": [
+                {"episode_number": 1, "title": "Episode 1", "duration": 1440},
+                {"episode_number": 2, "title": "Episode 2", "duration": 1440}
+            ]
+        }))
+        .send()
+        .await
+        .expect("Failed to create episodes")
+        .json()
+        .await
+        .unwrap();
+    
+    let episode_ids = created["ids"].as_array().unwrap()
+        .iter()
+        .map(|id| id.as_str().unwrap().to_string())
+        .collect();
+    
+    (anime_id, episode_ids)
+}
+
+async fn save(app: &TestApp, token: &str, episode_id: &str, position: u32) -> reqwest::Response {
+    app.client
+        .post(format!("{}/api/user/playback-position", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "episode_id": episode_id,
+            "position": position,
+            "duration": 1440
+        }))
+        .send()
+        .await
+        .expect("Failed to save position")
+}
+
+async fn get(app: &TestApp, token: &str, path: &str) -> reqwest::Response {
+    app.client
+        .get(format!("{}/api/user/playback-position/{}", app.address, path))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to get position")
+}
+
+#[tokio::test]
+async fn playback_position_round_trips_and_clamps() {
+    let app = spawn_app().await;
+    let token = session_token(&app).await;
+    let (_, episodes) = create_series(&app).await;
+    
+    // Nothing saved yet
+    assert_eq!(get(&app, &token, &episodes[0]).await.status().as_u16(), 404);
+    
+    assert_eq!(save(&app, &token, &episodes[0], 600).await.status().as_u16(), 200);
+    let saved: serde_json::Value = get(&app, &token, &episodes[0]).await.json().await.unwrap();
+    assert_eq!(saved["episode_id"], episodes[0].as_str());
+    assert_eq!(saved["position"], 600);
+    assert_eq!(saved["duration"], 1440);
+    assert!(saved["updated_at"].is_string());
+    
+    // Rapid heartbeats are still visible immediately, and overshoot clamps to the end
+    let clamped: serde_json::Value = save(&app, &token, &episodes[0], 2000).await.json().await.unwrap();
+    assert_eq!(clamped["position"], 1440);
+    let resumed: serde_json::Value = get(&app, &token, &episodes[0]).await.json().await.unwrap();
+    assert_eq!(resumed["position"], 1440);
+}
+
+#[tokio::test]
+async fn latest_position_for_anime_is_most_recent_episode() {
+    let app = spawn_app().await;
+    let token = session_token(&app).await;
+    let (anime_id, episodes) = create_series(&app).await;
+    
+    assert_eq!(get(&app, &token, &format!("anime/{}", anime_id)).await.status().as_u16(), 404);
+    
+    save(&app, &token, &episodes[0], 1400).await;
+    save(&app, &token, &episodes[1], 120).await;
+    
+    let latest: serde_json::Value = get(&app, &token, &format!("anime/{}", anime_id)).await.json().await.unwrap();
+    assert_eq!(latest["episode_id"], episodes[1].as_str());
+    assert_eq!(latest["episode_number"], 2);
+    assert_eq!(latest["position"], 120);
+}
+
+#[tokio::test]
+async fn playback_positions_are_scoped_per_user() {
+    let app = spawn_app().await;
+    let alice = session_token(&app).await;
+    let bob = session_token(&app).await;
+    let (_, episodes) = create_series(&app).await;
+    
+    // Clients may name the fields with their unit
+    let response = app.client
+        .post(format!("{}/api/user/playback-position", app.address))
+        .header("Authorization", format!("Bearer {}", alice))
+        .json(&json!({
+            "episode_id": episodes[0],
+            "position_secs": 600,
+            "duration_secs": 1440
+        }))
+        .send()
+        .await
+        .expect("Failed to save position");
+    assert_eq!(response.status().as_u16(), 200);
+    
+    // Bob has not watched it, so there is nothing to resume
+    assert_eq!(get(&app, &bob, &episodes[0]).await.status().as_u16(), 404);
+    
+    save(&app, &bob, &episodes[0], 60).await;
+    let alices: serde_json::Value = get(&app, &alice, &episodes[0]).await.json().await.unwrap();
+    assert_eq!(alices["position"], 600);
+    let bobs: serde_json::Value = get(&app, &bob, &episodes[0]).await.json().await.unwrap();
+    assert_eq!(bobs["position"], 60);
+}
+
+#[tokio::test]
+async fn playback_positions_require_auth_and_known_episode() {
+    let app = spawn_app().await;
+    let token = session_token(&app).await;
+    
+    let unauthenticated = app.client
+        .get(format!("{}/api/user/playback-position/{}", app.address, Uuid::new_v4()))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(unauthenticated.status().as_u16(), 401);
+    
+    let unknown = save(&app, &token, &Uuid::new_v4().to_string(), 10).await;
+    assert_eq!(unknown.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn flushed_position_survives_cache_eviction() {
+    let app = spawn_app().await;
+    let user_id = Uuid::new_v4().to_string();
+    let token = app.state.auth.lock().await
+        .create_session(&user_id, String::new())
+        .await
+        .expect("Failed to create session")
+        .token;
+    let (anime_id, episodes) = create_series(&app).await;
+    
+    let saved: serde_json::Value = save(&app, &token, &episodes[0], 900).await.json().await.unwrap();
+    assert_eq!(saved["position"], 900);
+    
+    // Persist hot positions, then drop the cached copy as if Redis had evicted it
+    let flushed = app.state.watch_progress.flush().await.expect("Failed to flush positions");
+    assert!(flushed >= 1);
+    
+    app.state.cache.lock().await
+        .delete_progress(&user_id, &episodes[0])
+        .await
+        .expect("Failed to evict position");
+    
+    let resumed = get(&app, &token, &episodes[0]).await;
+    assert_eq!(resumed.status().as_u16(), 200);
+    let resumed: serde_json::Value = resumed.json().await.unwrap();
+    assert_eq!(resumed["position"], 900);
+    assert_eq!(resumed["updated_at"], saved["updated_at"]);
+    
+    let latest: serde_json::Value = get(&app, &token, &format!("anime/{}", anime_id)).await.json().await.unwrap();
+    assert_eq!(latest["episode_id"], episodes[0].as_str());
+}
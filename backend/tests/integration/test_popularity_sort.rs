@@ -0,0 +1,97 @@
+// Integration test for the all-time popularity sort on browse
+
+use kensho_backend::middleware::response_cache::invalidate_catalog_responses;
+use kensho_backend::models::WatchlistStatus;
+use kensho_backend::services::popularity::PopularityDecay;
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp, title: &str) -> Uuid {
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": title,
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "winter", "year": 1998 },
+            "synopsis": "Test anime for popularity sorting",
+            "poster_url": "https://example.com/test.jpg",
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+
+    Uuid::parse_str(created["id"].as_str().unwrap()).unwrap()
+}
+
+async fn popularity(app: &TestApp, id: Uuid) -> f64 {
+    app.state.db.get_anime(id).await.unwrap().unwrap().popularity
+}
+
+/// The two ids in browse order; the season may also hold anime from other runs
+async fn browse_order(app: &TestApp, ids: [Uuid; 2]) -> Vec<Uuid> {
+    let result: serde_json::Value = app.client
+        .get(format!("{}/api/browse/season/1998/winter?sort=popularity", app.address))
+        .send()
+        .await
+        .expect("Failed to browse season")
+        .json()
+        .await
+        .unwrap();
+
+    result["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| Uuid::parse_str(item["id"].as_str().unwrap()).unwrap())
+        .filter(|id| ids.contains(id))
+        .collect()
+}
+
+#[tokio::test]
+async fn browse_sort_by_popularity_follows_activity() {
+    let app = spawn_app().await;
+    let veteran = create_anime(&app, &format!("Veteran {}", Uuid::new_v4())).await;
+    let newcomer = create_anime(&app, &format!("Newcomer {}", Uuid::new_v4())).await;
+
+    // Two watchlist adds (3 each) and a 4-star rating
+    for _ in 0..2 {
+        let user_id = Uuid::new_v4().to_string();
+        app.state.db.upsert_watchlist_entry(&user_id, veteran, WatchlistStatus::Planned).await.unwrap();
+    }
+    app.state.db.track_user_likes(&Uuid::new_v4().to_string(), veteran, 4.0).await.unwrap();
+    assert!((popularity(&app, veteran).await - 10.0).abs() < 1e-9);
+
+    // Re-adding an entry only changes its status, so it scores nothing
+    let user_id = Uuid::new_v4().to_string();
+    app.state.db.upsert_watchlist_entry(&user_id, newcomer, WatchlistStatus::Planned).await.unwrap();
+    app.state.db.upsert_watchlist_entry(&user_id, newcomer, WatchlistStatus::Watching).await.unwrap();
+    assert!((popularity(&app, newcomer).await - 3.0).abs() < 1e-9);
+
+    assert_eq!(browse_order(&app, [veteran, newcomer]).await, vec![veteran, newcomer]);
+
+    // A month of decay halves both scores and keeps the order
+    let start = app.state.db.popularity_decayed_at(chrono::Utc::now()).await.unwrap();
+    let mut decay = PopularityDecay::load(&app.state.db, start).await.unwrap();
+    decay.run(&app.state.db, start + chrono::Duration::days(30)).await.unwrap();
+    assert!((popularity(&app, veteran).await - 5.0).abs() < 1e-9);
+    assert!((popularity(&app, newcomer).await - 1.5).abs() < 1e-9);
+
+    // Fresh activity on the newcomer overtakes the faded veteran
+    app.state.db.track_user_likes(&Uuid::new_v4().to_string(), newcomer, 5.0).await.unwrap();
+    assert!((popularity(&app, newcomer).await - 6.5).abs() < 1e-9);
+
+    // Browse pages are cached; activity only reorders them once they expire
+    invalidate_catalog_responses(&app.state).await;
+    assert_eq!(browse_order(&app, [veteran, newcomer]).await, vec![newcomer, veteran]);
+}
@@ -0,0 +1,85 @@
+// Integration test for reading and partially updating user preferences
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::spawn_app;
+
+#[tokio::test]
+async fn preferences_default_then_merge_partial_updates() {
+    let app = spawn_app().await;
+    let token = app.state.auth.lock().await
+        .create_session(&Uuid::new_v4().to_string(), String::new())
+        .await
+        .expect("Failed to create session")
+        .token;
+    let url = format!("{}/api/user/preferences", app.address);
+    
+    // Never saved: defaults
+    let defaults: serde_json::Value = app.client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to get preferences")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(defaults["quality"], "auto");
+    assert_eq!(defaults["language"], "en");
+    
+    let response = app.client
+        .put(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "language": "ja", "skip_intro": true }))
+        .send()
+        .await
+        .expect("Failed to set preferences");
+    assert_eq!(response.status().as_u16(), 200);
+    
+    // Only quality changes; the earlier fields survive
+    let response = app.client
+        .put(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "quality": "720p" }))
+        .send()
+        .await
+        .expect("Failed to set preferences");
+    assert_eq!(response.status().as_u16(), 200);
+    
+    let saved: serde_json::Value = app.client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to get preferences")
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(saved["quality"], "720p");
+    assert_eq!(saved["language"], "ja");
+    assert_eq!(saved["skip_intro"], true);
+    
+    // Invalid values are rejected and leave the stored preferences alone
+    let response = app.client
+        .put(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "quality": "4k" }))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 422);
+    
+    let saved: serde_json::Value = app.client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(saved["quality"], "720p");
+}
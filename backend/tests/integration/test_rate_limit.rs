@@ -0,0 +1,129 @@
+// Integration test for anonymous rate limiting: buckets per client IP behind a
+// trusted proxy, stricter limits on login, and the 429 response shape
+
+use std::time::Duration;
+use kensho_backend::middleware::RouteRateLimit;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app_with, TestApp};
+
+const LIMIT: u32 = 3;
+
+async fn spawn_limited_app() -> TestApp {
+    spawn_app_with(|state| {
+        state.rate_limit.limit_anonymous = true;
+        state.rate_limit.trust_forwarded_for = true;
+        state.rate_limit.max_requests = LIMIT;
+        state.rate_limit.burst = 0;
+        state.rate_limit.window = Duration::from_secs(60);
+        state.rate_limit.routes = vec![RouteRateLimit {
+            prefix: "/api/auth/login".to_string(),
+            max_requests: 1,
+            window: Duration::from_secs(60),
+            burst: 0,
+        }];
+    }).await
+}
+
+/// A documentation-range address of its own, so parallel runs never share a bucket
+fn unique_ip() -> String {
+    let bytes = Uuid::new_v4().into_bytes();
+    format!("2001:db8::{:x}:{:x}", u16::from_be_bytes([bytes[0], bytes[1]]), u16::from_be_bytes([bytes[2], bytes[3]]))
+}
+
+async fn get_from(app: &TestApp, ip: &str, path: &str) -> reqwest::Response {
+    app.client
+        .get(format!("{}{}", app.address, path))
+        .header("X-Forwarded-For", format!("{}, 10.0.0.1", ip))
+        .send()
+        .await
+        .expect("Failed to send request")
+}
+
+async fn wait_for_fresh_window() {
+    // Counters are per-minute; avoid straddling a window boundary
+    let secs_into_window = chrono::Utc::now().timestamp() % 60;
+    if secs_into_window > 55 {
+        tokio::time::sleep(Duration::from_secs((61 - secs_into_window) as u64)).await;
+    }
+}
+
+#[tokio::test]
+async fn client_ips_are_limited_independently() {
+    // Arrange
+    let app = spawn_limited_app().await;
+    let (first, second) = (unique_ip(), unique_ip());
+    wait_for_fresh_window().await;
+    
+    // Act - spend the first client's whole allowance
+    for expected_remaining in (0..LIMIT).rev() {
+        let response = get_from(&app, &first, "/api/health").await;
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(response.headers()["x-ratelimit-limit"], LIMIT.to_string().as_str());
+        assert_eq!(response.headers()["x-ratelimit-remaining"], expected_remaining.to_string().as_str());
+    }
+    let throttled = get_from(&app, &first, "/api/health").await;
+    
+    // Assert
+    assert_eq!(throttled.status().as_u16(), 429);
+    let retry_after: u64 = throttled.headers()["retry-after"].to_str().unwrap().parse().unwrap();
+    assert!((1..=60).contains(&retry_after));
+    assert_eq!(throttled.headers()["x-ratelimit-remaining"], "0");
+    assert!(throttled.headers().contains_key("x-ratelimit-reset"));
+    let body: Value = throttled.json().await.unwrap();
+    assert_eq!(body["code"], "RATE_LIMITED");
+    
+    let other = get_from(&app, &second, "/api/health").await;
+    assert_eq!(other.status().as_u16(), 200, "The second client has its own bucket");
+    assert_eq!(other.headers()["x-ratelimit-remaining"], (LIMIT - 1).to_string().as_str());
+}
+
+#[tokio::test]
+async fn login_has_a_stricter_bucket_of_its_own() {
+    // Arrange
+    let app = spawn_limited_app().await;
+    let ip = unique_ip();
+    wait_for_fresh_window().await;
+    
+    let login = || {
+        app.client
+            .post(format!("{}/api/auth/login", app.address))
+            .header("X-Forwarded-For", ip.as_str())
+            .json(&json!({ "email": "nobody@example.com", "password": "wrong password" }))
+            .send()
+    };
+    
+    // Act
+    let first = login().await.unwrap();
+    let second = login().await.unwrap();
+    
+    // Assert
+    assert_ne!(first.status().as_u16(), 429);
+    assert_eq!(second.status().as_u16(), 429);
+    assert_eq!(second.headers()["x-ratelimit-limit"], "1");
+    
+    // Browsing from the same address is counted separately
+    let response = get_from(&app, &ip, "/api/health").await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers()["x-ratelimit-remaining"], (LIMIT - 1).to_string().as_str());
+}
+
+#[tokio::test]
+async fn forwarded_for_is_ignored_unless_trusted() {
+    let app = spawn_app_with(|state| {
+        state.rate_limit.limit_anonymous = true;
+        state.rate_limit.trust_forwarded_for = false;
+        state.rate_limit.max_requests = 1_000_000;
+    }).await;
+    
+    // Without trust every spoofed address lands in the peer's bucket
+    let first = get_from(&app, &unique_ip(), "/api/health").await;
+    let second = get_from(&app, &unique_ip(), "/api/health").await;
+    let remaining = |response: &reqwest::Response| -> u64 {
+        response.headers()["x-ratelimit-remaining"].to_str().unwrap().parse().unwrap()
+    };
+    assert!(remaining(&second) < remaining(&first));
+}
@@ -0,0 +1,103 @@
+// Integration test for registration with email verification: accounts cannot
+// log in until the link issued at registration has been followed
+
+use kensho_backend::services::mailer::MemoryMailer;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, spawn_app_with, TestApp};
+
+const PASSWORD: &str = "correct horse battery";
+
+async fn register(app: &TestApp, username: &str, email: &str, password: &str) -> reqwest::Response {
+    app.client
+        .post(format!("{}/api/auth/register", app.address))
+        .json(&json!({ "username": username, "email": email, "password": password }))
+        .send()
+        .await
+        .expect("Failed to register")
+}
+
+async fn login(app: &TestApp, email: &str) -> reqwest::Response {
+    app.client
+        .post(format!("{}/api/auth/login", app.address))
+        .json(&json!({ "email": email, "password": PASSWORD }))
+        .send()
+        .await
+        .expect("Failed to log in")
+}
+
+/// The token in the verification link mailed to `email`
+fn verification_token_for(outbox: &MemoryMailer, email: &str) -> String {
+    let sent = outbox.sent_to(email);
+    assert_eq!(sent.len(), 1, "one verification email to {}", email);
+
+    let (_, link) = sent[0].body.split_once("?token=").expect("verification link in the email");
+    link.split_whitespace().next().unwrap().to_string()
+}
+
+fn unique(prefix: &str) -> String {
+    format!("{}{}", prefix, &Uuid::new_v4().simple().to_string()[..12])
+}
+
+#[tokio::test]
+async fn registration_requires_email_verification_before_login() {
+    // Arrange
+    let outbox = Arc::new(MemoryMailer::default());
+    let mailer = outbox.clone();
+    let app = spawn_app_with(|state| state.mail.mailer = mailer).await;
+    let email = format!("{}@example.com", unique("verify-"));
+
+    // Act
+    let response = register(&app, &unique("fan_"), &email, PASSWORD).await;
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 201);
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["verification_required"], true);
+    assert!(body["user_id"].is_string());
+    assert!(body.get("token").is_none());
+
+    assert_eq!(login(&app, &email).await.status().as_u16(), 403);
+
+    let token = verification_token_for(&outbox, &email);
+    let verify = |token: String| {
+        let url = format!("{}/api/auth/verify?token={}", app.address, token);
+        let client = app.client.clone();
+        async move { client.get(&url).send().await.expect("Failed to verify") }
+    };
+    assert_eq!(verify(token.clone()).await.status().as_u16(), 200);
+    assert_eq!(login(&app, &email).await.status().as_u16(), 200);
+
+    // Tokens work once
+    assert_eq!(verify(token).await.status().as_u16(), 400);
+    assert_eq!(verify("not-a-token".to_string()).await.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn duplicate_username_conflicts() {
+    let app = spawn_app().await;
+    let username = unique("taken_");
+
+    let first = register(&app, &username, &format!("{}@example.com", unique("a-")), PASSWORD).await;
+    assert_eq!(first.status().as_u16(), 201);
+
+    // Usernames are compared case-insensitively
+    let second = register(&app, &username.to_uppercase(), &format!("{}@example.com", unique("b-")), PASSWORD).await;
+    assert_eq!(second.status().as_u16(), 409);
+    let body: Value = second.json().await.unwrap();
+    assert!(body["error"].as_str().unwrap().contains("username"));
+}
+
+#[tokio::test]
+async fn weak_password_is_rejected() {
+    let app = spawn_app().await;
+
+    let response = register(&app, &unique("weak_"), &format!("{}@example.com", unique("weak-")), "short").await;
+    assert_eq!(response.status().as_u16(), 400);
+    let body: Value = response.json().await.unwrap();
+    assert!(body["details"].as_str().unwrap().contains("Password must be at least 8 characters"));
+}
@@ -0,0 +1,54 @@
+// Integration test for request ids: every response carries X-Request-Id, and
+// error bodies report the same id
+
+use serde_json::Value;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::spawn_app;
+
+#[tokio::test]
+async fn error_response_request_id_matches_header() {
+    let app = spawn_app().await;
+    let token = app.state.auth.lock().await
+        .create_session(&Uuid::new_v4().to_string(), String::new())
+        .await
+        .expect("Failed to create session")
+        .token;
+
+    // Not an admin, so the delete is refused with an ErrorResponse
+    let response = app.client
+        .delete(format!("{}/api/anime/{}", app.address, Uuid::new_v4()))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 403);
+
+    let header = response.headers()["x-request-id"].to_str().unwrap().to_string();
+    let id = Uuid::parse_str(&header).expect("Generated request ids are UUIDs");
+    assert_eq!(id.get_version_num(), 7);
+
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], "FORBIDDEN");
+    assert_eq!(body["request_id"], header.as_str());
+}
+
+#[tokio::test]
+async fn incoming_request_id_is_honored() {
+    let app = spawn_app().await;
+
+    let response = app.client
+        .get(format!("{}/api/health", app.address))
+        .header("X-Request-Id", "edge-proxy-7f3a")
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.headers()["x-request-id"], "edge-proxy-7f3a");
+
+    // Each request gets its own id otherwise
+    let first = app.client.get(format!("{}/api/health", app.address)).send().await.unwrap();
+    let second = app.client.get(format!("{}/api/health", app.address)).send().await.unwrap();
+    assert_ne!(first.headers()["x-request-id"], second.headers()["x-request-id"]);
+}
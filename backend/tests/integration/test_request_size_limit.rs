@@ -0,0 +1,72 @@
+// Integration test for request body size limits, global and per route
+
+use serde_json::Value;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app_with, TestApp};
+
+const MAX_BYTES: usize = 1024;
+const BULK_MAX_BYTES: usize = 4096;
+
+async fn spawn_limited_app() -> TestApp {
+    spawn_app_with(|state| {
+        state.request_size.max_bytes = MAX_BYTES;
+        state.request_size.bulk_import_max_bytes = BULK_MAX_BYTES;
+    }).await
+}
+
+/// A JSON object of exactly `len` bytes: `fields` padded with an ignored string
+fn json_body(fields: &str, len: usize) -> String {
+    let empty = format!("{{{}\"pad\":\"\"}}", fields);
+    let body = format!("{{{}\"pad\":\"{}\"}}", fields, "x".repeat(len - empty.len()));
+    assert_eq!(body.len(), len);
+    body
+}
+
+async fn post(app: &TestApp, path: &str, token: Option<&str>, body: String) -> reqwest::Response {
+    let mut request = app.client
+        .post(format!("{}/api{}", app.address, path))
+        .header("Content-Type", "application/json")
+        .body(body);
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    request.send().await.expect("Failed to send request")
+}
+
+#[tokio::test]
+async fn body_over_the_limit_is_rejected_with_413() {
+    let app = spawn_limited_app().await;
+    let path = format!("/anime/{}/episodes/batch", Uuid::new_v4());
+
+    let under = post(&app, &path, None, json_body("\"episodes\":[],", MAX_BYTES - 1)).await;
+    assert_ne!(under.status().as_u16(), 413);
+
+    let at = post(&app, &path, None, json_body("\"episodes\":[],", MAX_BYTES)).await;
+    assert_ne!(at.status().as_u16(), 413);
+
+    let over = post(&app, &path, None, json_body("\"episodes\":[],", MAX_BYTES + 1)).await;
+    assert_eq!(over.status().as_u16(), 413);
+    let body: Value = over.json().await.unwrap();
+    assert_eq!(body["code"], "PAYLOAD_TOO_LARGE");
+    assert_eq!(body["details"]["max_bytes"], MAX_BYTES);
+}
+
+#[tokio::test]
+async fn bulk_import_has_its_own_higher_limit() {
+    let app = spawn_limited_app().await;
+    let token = app.state.auth.lock().await
+        .create_session_with_role(&Uuid::new_v4().to_string(), String::new(), true)
+        .await
+        .expect("Failed to create session")
+        .token;
+
+    // Over the global limit, but within the bulk override
+    let under = post(&app, "/anime/bulk", Some(&token), json_body("\"data\":[],", BULK_MAX_BYTES - 1)).await;
+    assert_eq!(under.status().as_u16(), 200);
+
+    let over = post(&app, "/anime/bulk", Some(&token), json_body("\"data\":[],", BULK_MAX_BYTES + 1)).await;
+    assert_eq!(over.status().as_u16(), 413);
+}
@@ -0,0 +1,148 @@
+// Integration test for cached anime detail, seasonal browse and search responses
+
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp, title: &str, year: u16) -> Value {
+    app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": title,
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "spring", "year": year },
+            "synopsis": "Test anime for response caching",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap()
+}
+
+async fn get(app: &TestApp, path: &str) -> reqwest::Response {
+    let response = app.client
+        .get(format!("{}{}", app.address, path))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 200);
+    response
+}
+
+fn x_cache(response: &reqwest::Response) -> &str {
+    response.headers()["x-cache"].to_str().unwrap()
+}
+
+#[tokio::test]
+async fn second_get_is_served_from_cache() {
+    // Arrange
+    let app = spawn_app().await;
+    let title = format!("Cached {}", Uuid::new_v4().simple());
+    let anime = create_anime(&app, &title, 1987).await;
+    let id = anime["id"].as_str().unwrap();
+    
+    let paths = [
+        format!("/api/anime/{}", id),
+        "/api/browse/season/1987/spring".to_string(),
+        format!("/api/search?q={}&limit=5", title.replace(' ', "+")),
+    ];
+    
+    for path in &paths {
+        // Act
+        let first = get(&app, path).await;
+        let second = get(&app, path).await;
+        
+        // Assert
+        assert_eq!(x_cache(&first), "MISS", "{}", path);
+        assert_eq!(x_cache(&second), "HIT", "{}", path);
+        assert_eq!(
+            second.headers()["content-type"],
+            first.headers()["content-type"],
+        );
+        let first: Value = first.json().await.unwrap();
+        let second: Value = second.json().await.unwrap();
+        assert_eq!(first, second);
+    }
+    
+    // Reordered query parameters share the entry
+    let reordered = get(&app, &format!("/api/search?limit=5&q={}", title.replace(' ', "+"))).await;
+    assert_eq!(x_cache(&reordered), "HIT");
+    
+    let stats = app.state.cache.lock().await.response_stats();
+    assert!(stats.hits >= 4);
+    assert!(stats.misses >= 3);
+}
+
+#[tokio::test]
+async fn writes_invalidate_cached_responses() {
+    // Arrange
+    let app = spawn_app().await;
+    let title = format!("Before {}", Uuid::new_v4().simple());
+    let anime = create_anime(&app, &title, 1988).await;
+    let id = anime["id"].as_str().unwrap();
+    let detail = format!("/api/anime/{}", id);
+    let season = "/api/browse/season/1988/spring";
+    
+    get(&app, &detail).await;
+    get(&app, season).await;
+    assert_eq!(x_cache(&get(&app, &detail).await), "HIT");
+    
+    // Act - PATCH drops the detail and the season it airs in
+    let renamed = format!("After {}", Uuid::new_v4().simple());
+    let response = app.client
+        .patch(format!("{}{}", app.address, detail))
+        .json(&json!({ "title": renamed }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    
+    // Assert
+    let fresh = get(&app, &detail).await;
+    assert_eq!(x_cache(&fresh), "MISS");
+    assert_eq!(fresh.json::<Value>().await.unwrap()["title"], renamed.as_str());
+    
+    assert_eq!(x_cache(&get(&app, season).await), "MISS");
+    
+    // New anime in the season show up immediately
+    get(&app, season).await;
+    create_anime(&app, &format!("Sibling {}", Uuid::new_v4().simple()), 1988).await;
+    assert_eq!(x_cache(&get(&app, season).await), "MISS");
+    
+    // Adding episodes changes the detail's available count
+    get(&app, &detail).await;
+    let response = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, id))
+        .json(&json!({ "episodes": [{ "episode_number": 1 }] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status().as_u16(), 201);
+    
+    let fresh = get(&app, &detail).await;
+    assert_eq!(x_cache(&fresh), "MISS");
+    assert_eq!(fresh.json::<Value>().await.unwrap()["available_episodes"], 1);
+}
+
+#[tokio::test]
+async fn errors_are_not_cached() {
+    let app = spawn_app().await;
+    let path = format!("{}/api/anime/{}", app.address, Uuid::new_v4());
+    
+    for _ in 0..2 {
+        let response = app.client.get(&path).send().await.unwrap();
+        assert_eq!(response.status().as_u16(), 404);
+        assert_eq!(response.headers()["x-cache"], "MISS");
+    }
+}
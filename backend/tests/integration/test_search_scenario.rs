@@ -31,7 +31,7 @@ async fn user_can_search_and_discover_anime() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&spy_family_data)
         .send()
         .await
@@ -65,15 +65,15 @@ async fn user_can_search_and_discover_anime() {
         ]
     });
     
-    let episodes_response = app.client
-        .post(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+    let _episodes_response = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .json(&episodes_data)
         .send()
         .await;
     
     // Act - User searches for "SPY x FAMILY"
     let search_response = app.client
-        .get(&format!("{}/api/search?q=SPY", app.address))
+        .get(format!("{}/api/search?q=SPY", app.address))
         .send()
         .await
         .expect("Failed to search");
@@ -88,7 +88,7 @@ async fn user_can_search_and_discover_anime() {
     
     // Act - User clicks on the anime to view details
     let detail_response = app.client
-        .get(&format!("{}/api/anime/{}", app.address, anime_id))
+        .get(format!("{}/api/anime/{}", app.address, anime_id))
         .send()
         .await
         .expect("Failed to get anime details");
@@ -103,7 +103,7 @@ async fn user_can_search_and_discover_anime() {
     
     // Act - User checks episodes
     let episodes_response = app.client
-        .get(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .get(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .send()
         .await
         .expect("Failed to get episodes");
@@ -140,14 +140,14 @@ async fn user_can_search_by_japanese_title() {
     });
     
     let _create = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await;
     
     // Act - Search by Japanese title
     let response = app.client
-        .get(&format!("{}/api/search?q=君の名は", app.address))
+        .get(format!("{}/api/search?q=君の名は", app.address))
         .send()
         .await
         .expect("Failed to search");
@@ -192,7 +192,7 @@ async fn user_can_discover_anime_by_partial_title() {
         });
         
         let _create = app.client
-            .post(&format!("{}/api/anime", app.address))
+            .post(format!("{}/api/anime", app.address))
             .json(&anime_data)
             .send()
             .await;
@@ -200,7 +200,7 @@ async fn user_can_discover_anime_by_partial_title() {
     
     // Act - Search with partial title
     let response = app.client
-        .get(&format!("{}/api/search?q=Attack", app.address))
+        .get(format!("{}/api/search?q=Attack", app.address))
         .send()
         .await
         .expect("Failed to search");
@@ -0,0 +1,85 @@
+// Integration test for GET /api/search/suggest typeahead
+
+use kensho_backend::models::{Anime, AnimeSeason, AnimeStatus, AnimeType, ImdbData, Season};
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_rated(app: &TestApp, title: &str, synonyms: &[&str], rating: f32) {
+    let anime = Anime {
+        id: uuid::Uuid::new_v4(),
+        title: title.to_string(),
+        synonyms: synonyms.iter().map(|s| s.to_string()).collect(),
+        sources: vec![],
+        episodes: 12,
+        status: AnimeStatus::Finished,
+        anime_type: AnimeType::TV,
+        anime_season: AnimeSeason { season: Season::Spring, year: 2020 },
+        synopsis: String::new(),
+        poster_url: "https://example.com/suggest.jpg".to_string(),
+        posters: vec![],
+        stored_episode_count: 0,
+        popularity: 0.0,
+        romaji_titles: vec![],
+        imdb: Some(ImdbData {
+            id: String::new(),
+            rating,
+            votes: 0,
+            last_updated: chrono::Utc::now(),
+        }),
+        created_at: chrono::Utc::now(),
+        updated_at: chrono::Utc::now(),
+    };
+    app.state.db.create_anime(&anime).await.expect("Failed to create anime");
+}
+
+async fn suggest(app: &TestApp, q: &str) -> Vec<serde_json::Value> {
+    let response = app.client
+        .get(format!("{}/api/search/suggest", app.address))
+        .query(&[("q", q)])
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 200);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn one_character_query_suggests_nothing() {
+    let app = spawn_app().await;
+    create_rated(&app, "Akira", &[], 8.0).await;
+    
+    assert!(suggest(&app, "a").await.is_empty());
+    assert!(suggest(&app, "").await.is_empty());
+}
+
+#[tokio::test]
+async fn prefix_matches_are_ordered_by_rating() {
+    let app = spawn_app().await;
+    create_rated(&app, "Zebra Patrol", &[], 5.0).await;
+    create_rated(&app, "Zenith Knights", &[], 9.0).await;
+    create_rated(&app, "Blue Skies", &["Zephyr Run"], 7.0).await;
+    create_rated(&app, "Knights of Zen", &[], 9.5).await;
+    
+    let titles: Vec<String> = suggest(&app, "  ZE ").await
+        .iter()
+        .map(|s| s["title"].as_str().unwrap().to_string())
+        .collect();
+    // A synonym prefix matches too; a word later in the title does not
+    assert_eq!(titles, vec!["Zenith Knights", "Blue Skies", "Zebra Patrol"]);
+    
+    let first = &suggest(&app, "zen").await[0];
+    assert_eq!(first["title"], "Zenith Knights");
+    assert!(first["id"].as_str().is_some_and(|id| uuid::Uuid::parse_str(id).is_ok()));
+}
+
+#[tokio::test]
+async fn suggestions_are_capped_at_eight() {
+    let app = spawn_app().await;
+    for i in 0..10 {
+        create_rated(&app, &format!("Capped Series {}", i), &[], i as f32).await;
+    }
+    
+    assert_eq!(suggest(&app, "capped").await.len(), 8);
+}
@@ -67,7 +67,7 @@ async fn browse_current_season_shows_ongoing_anime() {
     // Insert all anime
     for anime_data in current_season_anime {
         app.client
-            .post(&format!("{}/api/anime", app.address))
+            .post(format!("{}/api/anime", app.address))
             .json(&anime_data)
             .send()
             .await
@@ -75,7 +75,7 @@ async fn browse_current_season_shows_ongoing_anime() {
     }
     
     app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&past_anime)
         .send()
         .await
@@ -83,7 +83,7 @@ async fn browse_current_season_shows_ongoing_anime() {
     
     // Act - Browse Winter 2024
     let response = app.client
-        .get(&format!("{}/api/browse/season/2024/winter", app.address))
+        .get(format!("{}/api/browse/season/2024/winter", app.address))
         .send()
         .await
         .expect("Failed to browse season");
@@ -133,7 +133,7 @@ async fn seasonal_browse_supports_pagination() {
         });
         
         app.client
-            .post(&format!("{}/api/anime", app.address))
+            .post(format!("{}/api/anime", app.address))
             .json(&anime_data)
             .send()
             .await
@@ -142,7 +142,7 @@ async fn seasonal_browse_supports_pagination() {
     
     // Act - Get first page
     let page1_response = app.client
-        .get(&format!("{}/api/browse/season/2024/spring?page=1&limit=10", app.address))
+        .get(format!("{}/api/browse/season/2024/spring?page=1&limit=10", app.address))
         .send()
         .await
         .expect("Failed to get page 1");
@@ -157,7 +157,7 @@ async fn seasonal_browse_supports_pagination() {
     
     // Act - Get second page
     let page2_response = app.client
-        .get(&format!("{}/api/browse/season/2024/spring?page=2&limit=10", app.address))
+        .get(format!("{}/api/browse/season/2024/spring?page=2&limit=10", app.address))
         .send()
         .await
         .expect("Failed to get page 2");
@@ -222,7 +222,7 @@ async fn browse_filters_by_anime_type() {
         });
         
         app.client
-            .post(&format!("{}/api/anime", app.address))
+            .post(format!("{}/api/anime", app.address))
             .json(&anime_data)
             .send()
             .await
@@ -231,7 +231,7 @@ async fn browse_filters_by_anime_type() {
     
     // Act - Filter for TV shows only
     let tv_response = app.client
-        .get(&format!("{}/api/browse/season/2024/summer?type=TV", app.address))
+        .get(format!("{}/api/browse/season/2024/summer?type=TV", app.address))
         .send()
         .await
         .expect("Failed to filter by TV");
@@ -249,7 +249,7 @@ async fn browse_filters_by_anime_type() {
     
     // Act - Filter for movies
     let movie_response = app.client
-        .get(&format!("{}/api/browse/season/2024/summer?type=MOVIE", app.address))
+        .get(format!("{}/api/browse/season/2024/summer?type=MOVIE", app.address))
         .send()
         .await
         .expect("Failed to filter by MOVIE");
@@ -293,7 +293,7 @@ async fn browse_filters_by_status() {
         });
         
         app.client
-            .post(&format!("{}/api/anime", app.address))
+            .post(format!("{}/api/anime", app.address))
             .json(&anime_data)
             .send()
             .await
@@ -302,7 +302,7 @@ async fn browse_filters_by_status() {
     
     // Act - Filter for ongoing anime
     let ongoing_response = app.client
-        .get(&format!("{}/api/browse/season/2024/fall?status=ONGOING", app.address))
+        .get(format!("{}/api/browse/season/2024/fall?status=ONGOING", app.address))
         .send()
         .await
         .expect("Failed to filter by status");
@@ -351,7 +351,7 @@ async fn browse_supports_tag_filtering() {
         });
         
         app.client
-            .post(&format!("{}/api/anime", app.address))
+            .post(format!("{}/api/anime", app.address))
             .json(&anime_data)
             .send()
             .await
@@ -360,7 +360,7 @@ async fn browse_supports_tag_filtering() {
     
     // Act - Filter for Action tag
     let action_response = app.client
-        .get(&format!("{}/api/browse/season/2023/spring?tag=Action", app.address))
+        .get(format!("{}/api/browse/season/2023/spring?tag=Action", app.address))
         .send()
         .await
         .expect("Failed to filter by tag");
@@ -381,7 +381,7 @@ async fn browse_supports_tag_filtering() {
     
     // Act - Filter for multiple tags (if supported)
     let multi_tag_response = app.client
-        .get(&format!("{}/api/browse/season/2023/spring?tag=Action&tag=Romance", app.address))
+        .get(format!("{}/api/browse/season/2023/spring?tag=Action&tag=Romance", app.address))
         .send()
         .await
         .expect("Failed to filter by multiple tags");
@@ -436,7 +436,7 @@ async fn browse_shows_upcoming_anime_for_future_seasons() {
     
     for anime_data in upcoming_anime {
         app.client
-            .post(&format!("{}/api/anime", app.address))
+            .post(format!("{}/api/anime", app.address))
             .json(&anime_data)
             .send()
             .await
@@ -445,7 +445,7 @@ async fn browse_shows_upcoming_anime_for_future_seasons() {
     
     // Act - Browse future season
     let response = app.client
-        .get(&format!("{}/api/browse/season/2025/spring", app.address))
+        .get(format!("{}/api/browse/season/2025/spring", app.address))
         .send()
         .await
         .expect("Failed to browse future season");
@@ -2,7 +2,6 @@
 // Tests user preferences, watch history, and session continuity
 
 use serde_json::json;
-use uuid::Uuid;
 
 #[path = "../common/mod.rs"]
 mod common;
@@ -12,7 +11,7 @@ use common::{spawn_app, create_test_token};
 async fn user_preferences_persist_across_sessions() {
     // Arrange
     let app = spawn_app().await;
-    let token = create_test_token();
+    let token = create_test_token(&app.state).await;
     
     // Step 1: Set user preferences
     let preferences = json!({
@@ -24,7 +23,7 @@ async fn user_preferences_persist_across_sessions() {
     });
     
     let set_response = app.client
-        .put(&format!("{}/api/user/preferences", app.address))
+        .put(format!("{}/api/user/preferences", app.address))
         .header("Authorization", format!("Bearer {}", token))
         .json(&preferences)
         .send()
@@ -35,7 +34,7 @@ async fn user_preferences_persist_across_sessions() {
     
     // Step 2: Retrieve preferences in new session
     let get_response = app.client
-        .get(&format!("{}/api/user/preferences", app.address))
+        .get(format!("{}/api/user/preferences", app.address))
         .header("Authorization", format!("Bearer {}", token))
         .send()
         .await
@@ -46,15 +45,15 @@ async fn user_preferences_persist_across_sessions() {
     let saved_preferences: serde_json::Value = get_response.json().await.unwrap();
     assert_eq!(saved_preferences["language"].as_str().unwrap(), "en");
     assert_eq!(saved_preferences["quality"].as_str().unwrap(), "1080p");
-    assert_eq!(saved_preferences["autoplay"].as_bool().unwrap(), true);
-    assert_eq!(saved_preferences["skip_intro"].as_bool().unwrap(), true);
+    assert!(saved_preferences["autoplay"].as_bool().unwrap());
+    assert!(saved_preferences["skip_intro"].as_bool().unwrap());
 }
 
 #[tokio::test]
 async fn watch_history_is_tracked_per_user() {
     // Arrange
     let app = spawn_app().await;
-    let user1_token = create_test_token();
+    let user1_token = create_test_token(&app.state).await;
     
     // Create anime and episodes
     let anime_data = json!({
@@ -74,7 +73,7 @@ async fn watch_history_is_tracked_per_user() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -92,21 +91,16 @@ async fn watch_history_is_tracked_per_user() {
         ]
     });
     
-    app.client
-        .post(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
-        .json(&episodes_data)
-        .send()
-        .await;
-    
     let episodes_response = app.client
-        .get(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&episodes_data)
         .send()
         .await
-        .expect("Failed to get episodes");
+        .expect("Failed to create episodes");
     
     let episodes_result: serde_json::Value = episodes_response.json().await.unwrap();
-    let episode1_id = episodes_result["episodes"][0]["id"].as_str().unwrap();
-    let episode2_id = episodes_result["episodes"][1]["id"].as_str().unwrap();
+    let episode1_id = episodes_result["ids"][0].as_str().unwrap();
+    let episode2_id = episodes_result["ids"][1].as_str().unwrap();
     
     // Step 1: Record watch progress for episode 1
     let watch_data = json!({
@@ -117,7 +111,7 @@ async fn watch_history_is_tracked_per_user() {
     });
     
     let watch_response = app.client
-        .post(&format!("{}/api/user/watch-history", app.address))
+        .post(format!("{}/api/user/watch-history", app.address))
         .header("Authorization", format!("Bearer {}", user1_token))
         .json(&watch_data)
         .send()
@@ -135,11 +129,11 @@ async fn watch_history_is_tracked_per_user() {
     });
     
     app.client
-        .post(&format!("{}/api/user/watch-history", app.address))
+        .post(format!("{}/api/user/watch-history", app.address))
         .header("Authorization", format!("Bearer {}", user1_token))
         .json(&complete_data)
         .send()
-        .await;
+        .await.expect("Failed to send request");
     
     // Step 3: Start episode 2
     let watch_data2 = json!({
@@ -150,15 +144,15 @@ async fn watch_history_is_tracked_per_user() {
     });
     
     app.client
-        .post(&format!("{}/api/user/watch-history", app.address))
+        .post(format!("{}/api/user/watch-history", app.address))
         .header("Authorization", format!("Bearer {}", user1_token))
         .json(&watch_data2)
         .send()
-        .await;
+        .await.expect("Failed to send request");
     
     // Step 4: Get watch history
     let history_response = app.client
-        .get(&format!("{}/api/user/watch-history", app.address))
+        .get(format!("{}/api/user/watch-history", app.address))
         .header("Authorization", format!("Bearer {}", user1_token))
         .send()
         .await
@@ -166,7 +160,7 @@ async fn watch_history_is_tracked_per_user() {
     
     if history_response.status().is_success() {
         let history: serde_json::Value = history_response.json().await.unwrap();
-        let episodes = history["episodes"].as_array().unwrap_or(&vec![]);
+        let episodes = history["episodes"].as_array().cloned().unwrap_or_default();
         
         // Should have history for watched episodes
         assert!(episodes.len() >= 2, "Should have watch history for multiple episodes");
@@ -185,7 +179,7 @@ async fn session_continues_after_token_refresh() {
     });
     
     let login_response = app.client
-        .post(&format!("{}/api/auth/login", app.address))
+        .post(format!("{}/api/auth/login", app.address))
         .json(&login_data)
         .send()
         .await
@@ -202,11 +196,11 @@ async fn session_continues_after_token_refresh() {
         });
         
         app.client
-            .put(&format!("{}/api/user/preferences", app.address))
+            .put(format!("{}/api/user/preferences", app.address))
             .header("Authorization", format!("Bearer {}", access_token))
             .json(&preference)
             .send()
-            .await;
+            .await.expect("Failed to send request");
         
         // Step 3: Refresh token
         let refresh_data = json!({
@@ -214,7 +208,7 @@ async fn session_continues_after_token_refresh() {
         });
         
         let refresh_response = app.client
-            .post(&format!("{}/api/auth/refresh", app.address))
+            .post(format!("{}/api/auth/refresh", app.address))
             .json(&refresh_data)
             .send()
             .await
@@ -226,7 +220,7 @@ async fn session_continues_after_token_refresh() {
             
             // Step 4: Verify session continues with new token
             let pref_response = app.client
-                .get(&format!("{}/api/user/preferences", app.address))
+                .get(format!("{}/api/user/preferences", app.address))
                 .header("Authorization", format!("Bearer {}", new_access_token))
                 .send()
                 .await
@@ -251,10 +245,10 @@ async fn session_continues_after_token_refresh() {
 async fn watchlist_is_maintained_per_user() {
     // Arrange
     let app = spawn_app().await;
-    let token = create_test_token();
+    let token = create_test_token(&app.state).await;
     
     // Create multiple anime
-    let anime_ids: Vec<String> = vec![];
+    let _anime_ids: Vec<String> = vec![];
     for i in 1..=3 {
         let anime_data = json!({
             "title": format!("Watchlist Anime {}", i),
@@ -273,7 +267,7 @@ async fn watchlist_is_maintained_per_user() {
         });
         
         let response = app.client
-            .post(&format!("{}/api/anime", app.address))
+            .post(format!("{}/api/anime", app.address))
             .json(&anime_data)
             .send()
             .await
@@ -290,7 +284,7 @@ async fn watchlist_is_maintained_per_user() {
             });
             
             let add_response = app.client
-                .post(&format!("{}/api/user/watchlist", app.address))
+                .post(format!("{}/api/user/watchlist", app.address))
                 .header("Authorization", format!("Bearer {}", token))
                 .json(&watchlist_data)
                 .send()
@@ -306,7 +300,7 @@ async fn watchlist_is_maintained_per_user() {
     
     // Get watchlist
     let watchlist_response = app.client
-        .get(&format!("{}/api/user/watchlist", app.address))
+        .get(format!("{}/api/user/watchlist", app.address))
         .header("Authorization", format!("Bearer {}", token))
         .send()
         .await
@@ -314,7 +308,7 @@ async fn watchlist_is_maintained_per_user() {
     
     if watchlist_response.status().is_success() {
         let watchlist: serde_json::Value = watchlist_response.json().await.unwrap();
-        let items = watchlist["items"].as_array().unwrap_or(&vec![]);
+        let items = watchlist["items"].as_array().cloned().unwrap_or_default();
         
         assert_eq!(items.len(), 3, "Should have 3 anime in watchlist");
         
@@ -328,7 +322,7 @@ async fn watchlist_is_maintained_per_user() {
             });
             
             let update_response = app.client
-                .put(&format!("{}/api/user/watchlist", app.address))
+                .put(format!("{}/api/user/watchlist", app.address))
                 .header("Authorization", format!("Bearer {}", token))
                 .json(&update_data)
                 .send()
@@ -347,7 +341,7 @@ async fn watchlist_is_maintained_per_user() {
 async fn resume_playback_from_last_position() {
     // Arrange
     let app = spawn_app().await;
-    let token = create_test_token();
+    let token = create_test_token(&app.state).await;
     
     // Create anime with episode
     let anime_data = json!({
@@ -367,7 +361,7 @@ async fn resume_playback_from_last_position() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -382,20 +376,15 @@ async fn resume_playback_from_last_position() {
         ]
     });
     
-    app.client
-        .post(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
-        .json(&episodes_data)
-        .send()
-        .await;
-    
     let episodes_response = app.client
-        .get(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&episodes_data)
         .send()
         .await
-        .expect("Failed to get episodes");
+        .expect("Failed to create episodes");
     
     let episodes_result: serde_json::Value = episodes_response.json().await.unwrap();
-    let episode_id = episodes_result["episodes"][0]["id"].as_str().unwrap();
+    let episode_id = episodes_result["ids"][0].as_str().unwrap();
     
     // Step 1: Save playback position
     let position_data = json!({
@@ -405,7 +394,7 @@ async fn resume_playback_from_last_position() {
     });
     
     let save_response = app.client
-        .post(&format!("{}/api/user/playback-position", app.address))
+        .post(format!("{}/api/user/playback-position", app.address))
         .header("Authorization", format!("Bearer {}", token))
         .json(&position_data)
         .send()
@@ -419,7 +408,7 @@ async fn resume_playback_from_last_position() {
     
     // Step 2: Get resume position
     let resume_response = app.client
-        .get(&format!("{}/api/user/playback-position/{}", app.address, episode_id))
+        .get(format!("{}/api/user/playback-position/{}", app.address, episode_id))
         .header("Authorization", format!("Bearer {}", token))
         .send()
         .await
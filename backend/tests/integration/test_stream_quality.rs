@@ -0,0 +1,95 @@
+// Integration test for negotiating stream quality against an episode's renditions
+
+use kensho_backend::services::CacheService;
+use kensho_backend::services::streaming::RENDITION_CACHE_TTL;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_episode(app: &TestApp) -> String {
+    let created: Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": "Quality Series",
+            "synonyms": [],
+            "sources": [],
+            "episodes": 1,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "spring", "year": 2024 },
+            "synopsis": "Test anime for quality negotiation",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    let anime_id = created["id"].as_str().unwrap().to_string();
+    
+    app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({ "episodes": [{"episode_number": 1, "title": "Episode 1", "duration": 1440}] }))
+        .send()
+        .await
+        .expect("Failed to create episodes");
+    
+    anime_id
+}
+
+async fn get_stream(app: &TestApp, token: &str, anime_id: &str, quality: Option<&str>) -> Value {
+    let mut request = app.client
+        .get(format!("{}/api/stream/{}/1", app.address, anime_id))
+        .header("Authorization", format!("Bearer {}", token));
+    if let Some(quality) = quality {
+        request = request.query(&[("quality", quality)]);
+    }
+    
+    let response = request.send().await.expect("Failed to get stream");
+    assert_eq!(response.status().as_u16(), 200);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn stream_negotiates_quality_against_cached_renditions() {
+    let app = spawn_app().await;
+    let token = app.state.auth.lock().await
+        .create_session(&Uuid::new_v4().to_string(), String::new())
+        .await
+        .expect("Failed to create session")
+        .token;
+    let anime_id = create_episode(&app).await;
+    
+    // Renditions already fetched for this episode are served from the cache
+    let renditions = vec!["720p".to_string(), "360p".to_string()];
+    app.state.cache.lock().await
+        .set(&CacheService::renditions_key(&format!("CR_{}_E1", anime_id)), &renditions, RENDITION_CACHE_TTL)
+        .await
+        .unwrap();
+    
+    let stream = get_stream(&app, &token, &anime_id, Some("720p")).await;
+    assert_eq!(stream["quality"], "720p");
+    assert_eq!(stream["available_qualities"], json!(renditions));
+    assert_eq!(stream["streams"][0]["resolution"], "720p");
+    
+    // Unavailable qualities fall back to the next lower rendition
+    assert_eq!(get_stream(&app, &token, &anime_id, Some("480p")).await["quality"], "360p");
+    assert_eq!(get_stream(&app, &token, &anime_id, Some("1080p")).await["quality"], "720p");
+    assert_eq!(get_stream(&app, &token, &anime_id, Some("auto")).await["quality"], "720p");
+    
+    // Without a query parameter the saved preference is negotiated
+    let response = app.client
+        .put(format!("{}/api/user/preferences", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "quality": "480p" }))
+        .send()
+        .await
+        .expect("Failed to set preferences");
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(get_stream(&app, &token, &anime_id, None).await["quality"], "360p");
+}
@@ -0,0 +1,123 @@
+// Integration test for resuming a stream after a transient disconnect
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_episode(app: &TestApp) -> String {
+    let anime_data = json!({
+        "title": "Resume Series",
+        "synonyms": [],
+        "sources": [],
+        "episodes": 1,
+        "status": "FINISHED",
+        "anime_type": "TV",
+        "anime_season": { "season": "spring", "year": 2024 },
+        "synopsis": "Test anime for stream resumption",
+        "poster_url": "https://example.com/test.jpg",
+        "tags": []
+    });
+    
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&anime_data)
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    let anime_id = created["id"].as_str().unwrap().to_string();
+    
+    app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({ "episodes": [{"episode_number": 1, "title": "Episode 1", "duration": 1440}] }))
+        .send()
+        .await
+        .expect("Failed to create episodes");
+    
+    anime_id
+}
+
+async fn resume(app: &TestApp, token: &str, resume_token: &str) -> reqwest::Response {
+    app.client
+        .post(format!("{}/api/stream/resume", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({ "resume_token": resume_token }))
+        .send()
+        .await
+        .expect("Failed to resume stream")
+}
+
+#[tokio::test]
+async fn resumed_stream_keeps_url_without_counting_a_new_stream() {
+    let app = spawn_app().await;
+    let user_id = Uuid::new_v4().to_string();
+    let token = app.state.auth.lock().await
+        .create_session(&user_id, String::new())
+        .await
+        .expect("Failed to create session")
+        .token;
+    let anime_id = create_episode(&app).await;
+    
+    let stream: serde_json::Value = app.client
+        .get(format!("{}/api/stream/{}/1", app.address, anime_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to get stream")
+        .json()
+        .await
+        .unwrap();
+    let resume_token = stream["resume_token"].as_str().expect("Stream should carry a resume token");
+    assert_eq!(stream["active_streams"], 1);
+    
+    let response = resume(&app, &token, resume_token).await;
+    assert_eq!(response.status().as_u16(), 200);
+    
+    let resumed: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(resumed["streams"][0]["url"], stream["streams"][0]["url"]);
+    assert_eq!(resumed["active_streams"], 1, "Resuming must not count as a new stream");
+    assert_eq!(app.state.stream_sessions.active_streams(&user_id).await.unwrap(), 1);
+    
+    // Tokens are single use; the resumed stream carries the next one
+    let next_token = resumed["resume_token"].as_str().unwrap();
+    assert_ne!(next_token, resume_token);
+    assert_eq!(resume(&app, &token, resume_token).await.status().as_u16(), 401);
+    assert_eq!(resume(&app, &token, next_token).await.status().as_u16(), 200);
+}
+
+#[tokio::test]
+async fn resume_rejects_unknown_token_and_other_users() {
+    let app = spawn_app().await;
+    let owner = app.state.auth.lock().await
+        .create_session(&Uuid::new_v4().to_string(), String::new())
+        .await
+        .expect("Failed to create session")
+        .token;
+    let other = app.state.auth.lock().await
+        .create_session(&Uuid::new_v4().to_string(), String::new())
+        .await
+        .expect("Failed to create session")
+        .token;
+    let anime_id = create_episode(&app).await;
+    
+    assert_eq!(resume(&app, &owner, "expired-or-made-up").await.status().as_u16(), 401);
+    
+    let stream: serde_json::Value = app.client
+        .get(format!("{}/api/stream/{}/1", app.address, anime_id))
+        .header("Authorization", format!("Bearer {}", owner))
+        .send()
+        .await
+        .expect("Failed to get stream")
+        .json()
+        .await
+        .unwrap();
+    let resume_token = stream["resume_token"].as_str().unwrap();
+    
+    assert_eq!(resume(&app, &other, resume_token).await.status().as_u16(), 403);
+    assert_eq!(resume(&app, &owner, resume_token).await.status().as_u16(), 200);
+}
@@ -31,7 +31,7 @@ async fn authenticated_user_can_stream_anime() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -53,28 +53,24 @@ async fn authenticated_user_can_stream_anime() {
         ]
     });
     
-    app.client
-        .post(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+    // The created episodes come back with their ids
+    let created_episodes: serde_json::Value = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .json(&episodes_data)
         .send()
-        .await;
-    
-    // Get episodes to find episode ID
-    let episodes_response = app.client
-        .get(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
-        .send()
         .await
-        .expect("Failed to get episodes");
-    
-    let episodes_result: serde_json::Value = episodes_response.json().await.unwrap();
-    let episode_id = episodes_result["episodes"][0]["id"].as_str().unwrap();
+        .expect("Failed to create episodes")
+        .json()
+        .await
+        .unwrap();
+    let episode_id = created_episodes["ids"][0].as_str().unwrap();
     
     // Step 1: Authenticate user
-    let token = create_test_token();
+    let token = create_test_token(&app.state).await;
     
     // Step 2: Request stream URL
     let stream_response = app.client
-        .get(&format!("{}/api/stream/{}", app.address, episode_id))
+        .get(format!("{}/api/stream/{}", app.address, episode_id))
         .header("Authorization", format!("Bearer {}", token))
         .send()
         .await
@@ -97,7 +93,7 @@ async fn unauthenticated_user_cannot_stream() {
     
     // Act - Try to stream without authentication
     let response = app.client
-        .get(&format!("{}/api/stream/{}", app.address, episode_id))
+        .get(format!("{}/api/stream/{}", app.address, episode_id))
         .send()
         .await
         .expect("Failed to send request");
@@ -133,7 +129,7 @@ async fn streaming_respects_quality_preferences() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -148,29 +144,26 @@ async fn streaming_respects_quality_preferences() {
         ]
     });
     
-    app.client
-        .post(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+    // The created episodes come back with their ids
+    let created_episodes: serde_json::Value = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .json(&episodes_data)
         .send()
-        .await;
-    
-    let episodes_response = app.client
-        .get(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
-        .send()
         .await
-        .expect("Failed to get episodes");
-    
-    let episodes_result: serde_json::Value = episodes_response.json().await.unwrap();
-    let episode_id = episodes_result["episodes"][0]["id"].as_str().unwrap();
+        .expect("Failed to create episodes")
+        .json()
+        .await
+        .unwrap();
+    let episode_id = created_episodes["ids"][0].as_str().unwrap();
     
-    let token = create_test_token();
+    let token = create_test_token(&app.state).await;
     
     // Test different quality settings
     let qualities = vec!["auto", "1080p", "720p", "480p"];
     
     for quality in qualities {
         let response = app.client
-            .get(&format!("{}/api/stream/{}?quality={}", app.address, episode_id, quality))
+            .get(format!("{}/api/stream/{}?quality={}", app.address, episode_id, quality))
             .header("Authorization", format!("Bearer {}", token))
             .send()
             .await
@@ -212,7 +205,7 @@ async fn stream_url_expires_after_timeout() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -227,26 +220,23 @@ async fn stream_url_expires_after_timeout() {
         ]
     });
     
-    app.client
-        .post(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+    // The created episodes come back with their ids
+    let created_episodes: serde_json::Value = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .json(&episodes_data)
         .send()
-        .await;
-    
-    let episodes_response = app.client
-        .get(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
-        .send()
         .await
-        .expect("Failed to get episodes");
-    
-    let episodes_result: serde_json::Value = episodes_response.json().await.unwrap();
-    let episode_id = episodes_result["episodes"][0]["id"].as_str().unwrap();
+        .expect("Failed to create episodes")
+        .json()
+        .await
+        .unwrap();
+    let episode_id = created_episodes["ids"][0].as_str().unwrap();
     
-    let token = create_test_token();
+    let token = create_test_token(&app.state).await;
     
     // Act - Get stream URL
     let response = app.client
-        .get(&format!("{}/api/stream/{}", app.address, episode_id))
+        .get(format!("{}/api/stream/{}", app.address, episode_id))
         .header("Authorization", format!("Bearer {}", token))
         .send()
         .await
@@ -289,7 +279,7 @@ async fn concurrent_streams_are_handled_correctly() {
     });
     
     let create_response = app.client
-        .post(&format!("{}/api/anime", app.address))
+        .post(format!("{}/api/anime", app.address))
         .json(&anime_data)
         .send()
         .await
@@ -306,22 +296,19 @@ async fn concurrent_streams_are_handled_correctly() {
         ]
     });
     
-    app.client
-        .post(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
+    // The created episodes come back with their ids
+    let created_episodes: serde_json::Value = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
         .json(&episodes_data)
         .send()
-        .await;
-    
-    let episodes_response = app.client
-        .get(&format!("{}/api/anime/{}/episodes", app.address, anime_id))
-        .send()
         .await
-        .expect("Failed to get episodes");
-    
-    let episodes_result: serde_json::Value = episodes_response.json().await.unwrap();
-    let episodes = episodes_result["episodes"].as_array().unwrap();
+        .expect("Failed to create episodes")
+        .json()
+        .await
+        .unwrap();
+    let episodes = created_episodes["episodes"].as_array().unwrap();
     
-    let token = create_test_token();
+    let token = create_test_token(&app.state).await;
     
     // Act - Request multiple streams concurrently
     let mut handles = vec![];
@@ -334,7 +321,7 @@ async fn concurrent_streams_are_handled_correctly() {
         
         let handle = tokio::spawn(async move {
             client
-                .get(&format!("{}/api/stream/{}", address, episode_id))
+                .get(format!("{}/api/stream/{}", address, episode_id))
                 .header("Authorization", format!("Bearer {}", token_clone))
                 .send()
                 .await
@@ -0,0 +1,110 @@
+// Integration test for saving and loading user preferences
+
+use kensho_backend::services::CacheService;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn session_token(app: &TestApp, user_id: &str) -> String {
+    app.state.auth.lock().await
+        .create_session(user_id, String::new())
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+async fn get_preferences(app: &TestApp, token: &str) -> reqwest::Response {
+    app.client
+        .get(format!("{}/api/user/preferences", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to get preferences")
+}
+
+async fn put_preferences(app: &TestApp, token: &str, body: Value) -> reqwest::Response {
+    app.client
+        .put(format!("{}/api/user/preferences", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .send()
+        .await
+        .expect("Failed to set preferences")
+}
+
+#[tokio::test]
+async fn new_user_gets_default_preferences() {
+    let app = spawn_app().await;
+    let token = session_token(&app, &Uuid::new_v4().to_string()).await;
+
+    let response = get_preferences(&app, &token).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let preferences: Value = response.json().await.unwrap();
+    assert_eq!(preferences["language"], "en");
+    assert_eq!(preferences["subtitle_language"], "en");
+    assert_eq!(preferences["quality"], "auto");
+    assert_eq!(preferences["autoplay"], true);
+    assert_eq!(preferences["skip_intro"], false);
+}
+
+#[tokio::test]
+async fn preferences_round_trip_across_sessions() {
+    let app = spawn_app().await;
+    let user_id = Uuid::new_v4().to_string();
+    let token = session_token(&app, &user_id).await;
+
+    let response = put_preferences(&app, &token, json!({
+        "language": "ja",
+        "subtitle_language": "en-US",
+        "quality": "720p",
+        "autoplay": false,
+        "skip_intro": true
+    })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // Evict the cached copy so the read comes from the database
+    app.state.cache.lock().await
+        .delete(&CacheService::preferences_key(&user_id))
+        .await
+        .expect("Failed to evict preferences");
+
+    let other_session = session_token(&app, &user_id).await;
+    let preferences: Value = get_preferences(&app, &other_session).await.json().await.unwrap();
+    assert_eq!(preferences["language"], "ja");
+    assert_eq!(preferences["subtitle_language"], "en-US");
+    assert_eq!(preferences["quality"], "720p");
+    assert_eq!(preferences["autoplay"], false);
+    assert_eq!(preferences["skip_intro"], true);
+}
+
+#[tokio::test]
+async fn invalid_preferences_are_rejected_and_not_saved() {
+    let app = spawn_app().await;
+    let token = session_token(&app, &Uuid::new_v4().to_string()).await;
+
+    let response = put_preferences(&app, &token, json!({ "quality": "4k" })).await;
+    assert_eq!(response.status().as_u16(), 422);
+
+    let response = put_preferences(&app, &token, json!({ "language": "english" })).await;
+    assert_eq!(response.status().as_u16(), 422);
+
+    let preferences: Value = get_preferences(&app, &token).await.json().await.unwrap();
+    assert_eq!(preferences["quality"], "auto");
+    assert_eq!(preferences["language"], "en");
+}
+
+#[tokio::test]
+async fn preferences_require_auth() {
+    let app = spawn_app().await;
+
+    let response = app.client
+        .get(format!("{}/api/user/preferences", app.address))
+        .send()
+        .await
+        .expect("Failed to send request");
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -0,0 +1,82 @@
+// Integration test for per-user rate limiting of authenticated requests
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn session_token(app: &TestApp, user_id: &str) -> String {
+    app.state.auth.lock().await
+        .create_session(user_id, String::new())
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+#[tokio::test]
+async fn exhausting_one_users_limit_does_not_affect_another_user() {
+    // Arrange
+    let app = spawn_app().await;
+    let suffix = uuid::Uuid::new_v4();
+    let token_a = session_token(&app, &format!("rate_limit_user_a_{}", suffix)).await;
+    let token_b = session_token(&app, &format!("rate_limit_user_b_{}", suffix)).await;
+    let limit = app.state.rate_limit.user.limit();
+    let url = format!("{}/api/stream/{}/1", app.address, uuid::Uuid::new_v4());
+    
+    // Counters are per-minute; avoid straddling a window boundary
+    let secs_into_window = chrono::Utc::now().timestamp() % 60;
+    if secs_into_window > 50 {
+        tokio::time::sleep(std::time::Duration::from_secs((61 - secs_into_window) as u64)).await;
+    }
+    
+    // Act - spend user A's whole allowance for this window
+    for _ in 0..limit {
+        let response = app.client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", token_a))
+            .send()
+            .await
+            .expect("Failed to send request");
+        
+        assert_ne!(response.status().as_u16(), 429, "Requests within the limit must not be throttled");
+    }
+    
+    let throttled = app.client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token_a))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(throttled.status().as_u16(), 429, "User A should be rate limited");
+    assert!(throttled.headers().contains_key("retry-after"));
+    
+    let other_user = app.client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token_b))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    assert_ne!(other_user.status().as_u16(), 429, "User B has a separate counter");
+    assert_eq!(
+        other_user.headers()["x-ratelimit-remaining"].to_str().unwrap(),
+        (limit - 1).to_string()
+    );
+}
+
+#[tokio::test]
+async fn user_rate_limit_requires_authentication() {
+    // Arrange
+    let app = spawn_app().await;
+    
+    // Act
+    let response = app.client
+        .get(format!("{}/api/stream/{}/1", app.address, uuid::Uuid::new_v4()))
+        .send()
+        .await
+        .expect("Failed to send request");
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
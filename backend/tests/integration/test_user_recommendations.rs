@@ -0,0 +1,89 @@
+// Integration test for "because you watched" recommendations
+
+use uuid::Uuid;
+use serde_json::json;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp, title: &str, tags: &[&str]) -> Uuid {
+    let anime_data = json!({
+        "title": title,
+        "synonyms": [],
+        "sources": [],
+        "episodes": 12,
+        "status": "FINISHED",
+        "anime_type": "TV",
+        "anime_season": { "season": "spring", "year": 2023 },
+        "synopsis": "Test anime for recommendations",
+        "poster_url": "https://example.com/test.jpg",
+        "tags": tags
+    });
+    
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&anime_data)
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    
+    Uuid::parse_str(created["id"].as_str().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn recommendations_exclude_liked_anime_and_explain_overlap() {
+    let app = spawn_app().await;
+    let user_id = Uuid::new_v4().to_string();
+    let token = app.state.auth.lock().await
+        .create_session(&user_id, String::new())
+        .await
+        .expect("Failed to create session")
+        .token;
+    
+    let liked = create_anime(&app, "Liked Slayer", &["Action", "Shounen", "Supernatural"]).await;
+    let close = create_anime(&app, "Close Match", &["Action", "Shounen", "Supernatural"]).await;
+    let loose = create_anime(&app, "Loose Match", &["Action", "Romance"]).await;
+    
+    app.state.db.track_user_likes(&user_id, liked, 5.0).await.expect("Failed to seed like");
+    
+    let response = app.client
+        .get(format!("{}/api/user/recommendations?limit=10", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to fetch recommendations");
+    assert_eq!(response.status().as_u16(), 200);
+    
+    let items: Vec<serde_json::Value> = response.json().await.unwrap();
+    let ids: Vec<&str> = items.iter().map(|item| item["id"].as_str().unwrap()).collect();
+    
+    assert!(!ids.contains(&liked.to_string().as_str()), "Liked anime must not be recommended");
+    assert_eq!(ids.first(), Some(&close.to_string().as_str()));
+    assert!(ids.contains(&loose.to_string().as_str()));
+    assert_eq!(items[0]["reason"], "Shares 3 tags with Liked Slayer");
+}
+
+#[tokio::test]
+async fn recommendations_fall_back_without_history() {
+    let app = spawn_app().await;
+    let token = app.state.auth.lock().await
+        .create_session(&Uuid::new_v4().to_string(), String::new())
+        .await
+        .expect("Failed to create session")
+        .token;
+    
+    let response = app.client
+        .get(format!("{}/api/user/recommendations", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to fetch recommendations");
+    assert_eq!(response.status().as_u16(), 200);
+    
+    let items: Vec<serde_json::Value> = response.json().await.unwrap();
+    assert!(items.iter().all(|item| item["reason"] == "Top rated this season"));
+}
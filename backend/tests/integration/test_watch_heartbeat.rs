@@ -0,0 +1,188 @@
+// Integration test for the live watch heartbeat: an SSE stream per episode
+// with positions POSTed back, saved once the stream disconnects
+
+use serde_json::{json, Value};
+use std::time::Duration;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+/// How long to wait for an event before failing
+const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A user id and a session token for it
+async fn session(app: &TestApp) -> (String, String) {
+    let user_id = Uuid::new_v4().to_string();
+    let token = app.state.auth.lock().await
+        .create_session(&user_id, String::new())
+        .await
+        .expect("Failed to create session")
+        .token;
+    (user_id, token)
+}
+
+/// Create an anime with one episode and return the episode's id
+async fn create_episode(app: &TestApp) -> Uuid {
+    let created: Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": "Heartbeat Series",
+            "synonyms": [],
+            "sources": [],
+            "episodes": 1,
+            "status": "FINISHED",
+            "anime_type": "TV",
+            "anime_season": { "season": "spring", "year": 2024 },
+            "synopsis": "Test anime for watch heartbeats",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": []
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    let anime_id = created["id"].as_str().unwrap();
+
+    let created: Value = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({
+            "episodes": [{"episode_number": 1, "title": "Episode 1", "duration": 1440}]
+        }))
+        .send()
+        .await
+        .expect("Failed to create episodes")
+        .json()
+        .await
+        .unwrap();
+    created["ids"][0].as_str().unwrap().parse().unwrap()
+}
+
+/// Reads the JSON `data:` of server-sent events off a response body
+struct EventReader {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl EventReader {
+    async fn next(&mut self) -> Value {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let block: String = self.buffer.drain(..end + 2).collect();
+                let data: String = block
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("data:"))
+                    .map(str::trim_start)
+                    .collect();
+                if data.is_empty() {
+                    continue;
+                }
+                return serde_json::from_str(&data).expect("Event data is JSON");
+            }
+
+            let chunk = tokio::time::timeout(EVENT_TIMEOUT, self.response.chunk())
+                .await
+                .expect("Timed out waiting for an event")
+                .expect("Failed to read the event stream")
+                .expect("Event stream ended");
+            self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+}
+
+async fn open_stream(app: &TestApp, token: &str, episode_id: Uuid) -> reqwest::Response {
+    app.client
+        .get(format!("{}/api/watch/{}/heartbeat", app.address, episode_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to open heartbeat stream")
+}
+
+async fn report(app: &TestApp, token: &str, episode_id: Uuid, body: Value) -> reqwest::Response {
+    app.client
+        .post(format!("{}/api/watch/{}/heartbeat", app.address, episode_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&body)
+        .send()
+        .await
+        .expect("Failed to report heartbeat")
+}
+
+#[tokio::test]
+async fn heartbeat_stream_pushes_events_and_saves_position_on_disconnect() {
+    // Arrange
+    let app = spawn_app().await;
+    let (user_id, token) = session(&app).await;
+    let episode_id = create_episode(&app).await;
+
+    // Act
+    let response = open_stream(&app, &token, episode_id).await;
+
+    // Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/event-stream"));
+    let mut events = EventReader { response, buffer: String::new() };
+
+    // Pinged as soon as the stream opens
+    assert_eq!(events.next().await, json!({ "type": "ping" }));
+
+    let reported = report(&app, &token, episode_id, json!({ "position": 120, "duration": 1440, "bandwidth_kbps": 1800 })).await;
+    assert_eq!(reported.status().as_u16(), 200);
+    assert_eq!(events.next().await, json!({ "type": "quality_switch", "quality": "480p" }));
+
+    // Only changes of rendition are pushed
+    report(&app, &token, episode_id, json!({ "position": 125, "duration": 1440, "bandwidth_kbps": 1900 })).await;
+    report(&app, &token, episode_id, json!({ "position": 130, "duration": 1440, "bandwidth_kbps": 6000 })).await;
+    assert_eq!(events.next().await, json!({ "type": "quality_switch", "quality": "1080p" }));
+
+    // Held in memory while the stream is open
+    assert!(app.state.watch_progress.get_position(&user_id, episode_id).await.unwrap().is_none());
+
+    drop(events);
+
+    let mut saved = None;
+    for _ in 0..50 {
+        saved = app.state.watch_progress.get_position(&user_id, episode_id).await.unwrap();
+        if saved.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let saved = saved.expect("Position saved after the stream disconnected");
+    assert_eq!(saved.position, 130);
+    assert_eq!(saved.duration, 1440);
+    assert_eq!(app.state.watch_heartbeat.session_count(), 0);
+}
+
+#[tokio::test]
+async fn heartbeat_without_open_stream_saves_straight_away() {
+    let app = spawn_app().await;
+    let (user_id, token) = session(&app).await;
+    let episode_id = create_episode(&app).await;
+
+    let reported = report(&app, &token, episode_id, json!({ "position": 60, "duration": 1440 })).await;
+    assert_eq!(reported.status().as_u16(), 200);
+
+    let saved = app.state.watch_progress.get_position(&user_id, episode_id).await.unwrap();
+    assert_eq!(saved.map(|position| position.position), Some(60));
+}
+
+#[tokio::test]
+async fn heartbeat_requires_auth_and_a_known_episode() {
+    let app = spawn_app().await;
+    let (_, token) = session(&app).await;
+
+    let missing = open_stream(&app, &token, Uuid::new_v4()).await;
+    assert_eq!(missing.status().as_u16(), 404);
+
+    let unauthenticated = app.client
+        .get(format!("{}/api/watch/{}/heartbeat", app.address, Uuid::new_v4()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(unauthenticated.status().as_u16(), 401);
+}
@@ -0,0 +1,220 @@
+// Integration test for per-user watch history with progress and completion
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn session_token(app: &TestApp) -> String {
+    app.state.auth.lock().await
+        .create_session(&Uuid::new_v4().to_string(), String::new())
+        .await
+        .expect("Failed to create session")
+        .token
+}
+
+/// Create an anime with `count` episodes and return their ids in episode order
+async fn create_series(app: &TestApp, count: u32) -> Vec<String> {
+    let anime_data = json!({
+        "title": "Watch History Series",
+        "synonyms": [],
+        "sources": [],
+        "episodes": count,
+        "status": "FINISHED",
+        "anime_type": "TV",
+        "anime_season": { "season": "winter", "year": 2024 },
+        "synopsis": "Test anime for watch history",
+        "poster_url": "https://example.com/test.jpg",
+        "tags": []
+    });
+    
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&anime_data)
+        .send()
+        .await
+        .expect("Failed to create anime")
+        .json()
+        .await
+        .unwrap();
+    let anime_id = created["id"].as_str().unwrap();
+    
+    let episodes: Vec<_> = (1..=count)
+        .map(|n| json!({ "episode_number": n, "title": format!("Episode {}", n) }))
+        .collect();
+    
+    let created: serde_json::Value = app.client
+        .post(format!("{}/api/anime/{}/episodes", app.address, anime_id))
+        .json(&json!({ "episodes": episodes }))
+        .send()
+        .await
+        .expect("Failed to create episodes")
+        .json()
+        .await
+        .unwrap();
+    
+    created["ids"].as_array().unwrap()
+        .iter()
+        .map(|id| id.as_str().unwrap().to_string())
+        .collect()
+}
+
+async fn record(app: &TestApp, token: &str, episode_id: &str, progress: u32, completed: bool) -> reqwest::Response {
+    app.client
+        .post(format!("{}/api/user/watch-history", app.address))
+        .header("Authorization", format!("Bearer {}", token))
+        .json(&json!({
+            "episode_id": episode_id,
+            "progress": progress,
+            "total_duration": 1440,
+            "completed": completed
+        }))
+        .send()
+        .await
+        .expect("Failed to record watch progress")
+}
+
+async fn history(app: &TestApp, token: &str, query: &str) -> serde_json::Value {
+    let response = app.client
+        .get(format!("{}/api/user/watch-history{}", app.address, query))
+        .header("Authorization", format!("Bearer {}", token))
+        .send()
+        .await
+        .expect("Failed to get watch history");
+    
+    assert_eq!(response.status().as_u16(), 200);
+    response.json().await.unwrap()
+}
+
+#[tokio::test]
+async fn reposting_progress_updates_the_same_entry() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = session_token(&app).await;
+    let episodes = create_series(&app, 3).await;
+    
+    // Act
+    assert_eq!(record(&app, &token, &episodes[0], 720, false).await.status().as_u16(), 200);
+    let updated = record(&app, &token, &episodes[0], 1400, false).await;
+    
+    // Assert - 1400 of 1440 seconds is past the completion threshold
+    assert_eq!(updated.status().as_u16(), 200);
+    let updated: serde_json::Value = updated.json().await.unwrap();
+    assert_eq!(updated["progress"], 1400);
+    assert_eq!(updated["completed"], true);
+    
+    let history = history(&app, &token, "").await;
+    assert_eq!(history["total"], 1, "Re-posting must not append a duplicate entry");
+    assert_eq!(history["episodes"][0]["episode"]["id"], episodes[0].as_str());
+    assert_eq!(history["episodes"][0]["anime"]["title"], "Watch History Series");
+}
+
+#[tokio::test]
+async fn history_is_most_recent_first_and_paginated() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = session_token(&app).await;
+    let episodes = create_series(&app, 3).await;
+    
+    for episode_id in &episodes {
+        record(&app, &token, episode_id, 360, false).await;
+    }
+    
+    // Act
+    let first_page = history(&app, &token, "?limit=2").await;
+    let second_page = history(&app, &token, "?limit=2&offset=2").await;
+    
+    // Assert
+    assert_eq!(first_page["total"], 3);
+    let first: Vec<_> = first_page["episodes"].as_array().unwrap()
+        .iter()
+        .map(|item| item["episode_number"].as_u64().unwrap())
+        .collect();
+    assert_eq!(first, vec![3, 2]);
+    
+    assert_eq!(second_page["episodes"].as_array().unwrap().len(), 1);
+    assert_eq!(second_page["episodes"][0]["episode_number"], 1);
+    assert_eq!(second_page["episodes"][0]["completed"], false);
+}
+
+#[tokio::test]
+async fn watch_history_is_private_to_each_user() {
+    // Arrange
+    let app = spawn_app().await;
+    let owner = session_token(&app).await;
+    let other = session_token(&app).await;
+    let episodes = create_series(&app, 1).await;
+    
+    // Act
+    record(&app, &owner, &episodes[0], 1440, true).await;
+    
+    // Assert
+    assert_eq!(history(&app, &owner, "").await["total"], 1);
+    assert_eq!(history(&app, &other, "").await["total"], 0);
+}
+
+#[tokio::test]
+async fn unknown_episode_returns_404() {
+    // Arrange
+    let app = spawn_app().await;
+    let token = session_token(&app).await;
+    
+    // Act
+    let response = record(&app, &token, &Uuid::new_v4().to_string(), 10, false).await;
+    
+    // Assert
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn upsert_watch_progress_updates_in_place() {
+    // Arrange
+    let app = spawn_app().await;
+    let user_id = Uuid::new_v4().to_string();
+    let episodes = create_series(&app, 2).await;
+    let episode_id: Uuid = episodes[1].parse().unwrap();
+    
+    // Act
+    app.state.db.upsert_watch_progress(&user_id, episode_id, 300, 1440, false).await.unwrap();
+    let updated = app.state.db
+        .upsert_watch_progress(&user_id, episode_id, 900, 1440, false)
+        .await
+        .unwrap()
+        .expect("episode exists");
+    
+    // Assert
+    assert_eq!(updated.progress, 900);
+    assert_eq!(updated.episode_number, 2);
+    
+    let (entries, total) = app.state.db.get_watch_history(&user_id, 20, 0).await.unwrap();
+    assert_eq!(total, 1);
+    assert_eq!(entries[0].progress.progress, 900);
+    assert_eq!(entries[0].anime.title, "Watch History Series");
+    assert_eq!(entries[0].anime.id, updated.anime_id);
+    
+    // Unknown episodes are not recorded
+    assert!(app.state.db.upsert_watch_progress(&user_id, Uuid::new_v4(), 10, 1440, false).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn upsert_watch_progress_is_isolated_per_user() {
+    // Arrange
+    let app = spawn_app().await;
+    let (first, second) = (Uuid::new_v4().to_string(), Uuid::new_v4().to_string());
+    let episodes = create_series(&app, 1).await;
+    let episode_id: Uuid = episodes[0].parse().unwrap();
+    
+    // Act
+    app.state.db.upsert_watch_progress(&first, episode_id, 600, 1440, false).await.unwrap();
+    app.state.db.upsert_watch_progress(&second, episode_id, 1440, 1440, true).await.unwrap();
+    
+    // Assert - each user sees only their own progress
+    let (first_entries, _) = app.state.db.get_watch_history(&first, 20, 0).await.unwrap();
+    let (second_entries, _) = app.state.db.get_watch_history(&second, 20, 0).await.unwrap();
+    assert_eq!(first_entries.len(), 1);
+    assert_eq!((first_entries[0].progress.progress, first_entries[0].progress.completed), (600, false));
+    assert_eq!(second_entries.len(), 1);
+    assert_eq!((second_entries[0].progress.progress, second_entries[0].progress.completed), (1440, true));
+}
@@ -0,0 +1,87 @@
+// Integration test for browsing a whole year grouped by season
+
+use serde_json::json;
+use uuid::Uuid;
+
+#[path = "../common/mod.rs"]
+mod common;
+use common::{spawn_app, TestApp};
+
+async fn create_anime(app: &TestApp, title: &str, season: &str, year: u16, anime_type: &str, tags: &[&str]) {
+    let response = app.client
+        .post(format!("{}/api/anime", app.address))
+        .json(&json!({
+            "title": title,
+            "synonyms": [],
+            "sources": [],
+            "episodes": 12,
+            "status": "FINISHED",
+            "anime_type": anime_type,
+            "anime_season": { "season": season, "year": year },
+            "synopsis": "Test anime for year browse",
+            "poster_url": "https://example.com/test.jpg",
+            "tags": tags
+        }))
+        .send()
+        .await
+        .expect("Failed to create anime");
+    assert_eq!(response.status().as_u16(), 201);
+}
+
+fn titles(body: &serde_json::Value, season: &str) -> Vec<String> {
+    body["seasons"][season].as_array().unwrap()
+        .iter()
+        .map(|item| item["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn year_browse_groups_by_season_with_filters() {
+    let app = spawn_app().await;
+    // A tag only this run uses keeps other runs' anime out of the year
+    let run = format!("Run{}", Uuid::new_v4().simple());
+
+    create_anime(&app, "Winter B", "winter", 1989, "TV", &[&run]).await;
+    create_anime(&app, "Winter A", "winter", 1989, "TV", &[&run]).await;
+    create_anime(&app, "Summer Movie", "summer", 1989, "MOVIE", &[&run]).await;
+    create_anime(&app, "Fall Show", "fall", 1989, "TV", &[&run]).await;
+    create_anime(&app, "Next Year", "winter", 1990, "TV", &[&run]).await;
+
+    let body: serde_json::Value = app.client
+        .get(format!("{}/api/browse/year/1989?tag={}", app.address, run))
+        .send()
+        .await
+        .expect("Failed to browse year")
+        .json()
+        .await
+        .unwrap();
+
+    assert_eq!(body["year"], 1989);
+    assert_eq!(titles(&body, "winter"), vec!["Winter A", "Winter B"]);
+    assert!(titles(&body, "spring").is_empty());
+    assert_eq!(titles(&body, "summer"), vec!["Summer Movie"]);
+    assert_eq!(titles(&body, "fall"), vec!["Fall Show"]);
+    assert_eq!(body["totals"], json!({ "winter": 2, "spring": 0, "summer": 1, "fall": 1 }));
+    assert_eq!(body["total"], 4);
+
+    // Same filters and sort as the seasonal listing
+    let body: serde_json::Value = app.client
+        .get(format!("{}/api/browse/year/1989?tag={}&type=TV&sort=title:desc", app.address, run))
+        .send()
+        .await
+        .expect("Failed to browse year")
+        .json()
+        .await
+        .unwrap();
+
+    assert_eq!(titles(&body, "winter"), vec!["Winter B", "Winter A"]);
+    assert!(titles(&body, "summer").is_empty());
+    assert_eq!(body["total"], 3);
+
+    let invalid = app.client
+        .get(format!("{}/api/browse/year/1989?status=airing", app.address))
+        .send()
+        .await
+        .expect("Failed to browse year");
+    assert_eq!(invalid.status().as_u16(), 400);
+}
@@ -0,0 +1,135 @@
+// Streaming a large anime-offline-database file must not hold the file in
+// memory: the heap high-water mark while every entry is read stays a small
+// fraction of the file size. A test binary of its own, since it counts
+// allocations through a global allocator.
+
+use kensho_backend::models::anime_offline_db::AnimeOfflineDatabase;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+const ENTRIES: usize = 10_000;
+/// Every this many entries is replaced with one that does not fit the schema
+const MALFORMED_EVERY: usize = 1_000;
+
+const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/anime-offline-database-10.json");
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, tracking live heap bytes and their high-water mark
+struct PeakAllocator;
+
+impl PeakAllocator {
+    fn grew(size: usize) {
+        let now = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(now, Ordering::Relaxed);
+    }
+
+    fn shrank(size: usize) {
+        ALLOCATED.fetch_sub(size, Ordering::Relaxed);
+    }
+
+    /// Start a new high-water mark from the current allocation
+    fn reset_peak() -> usize {
+        let now = ALLOCATED.load(Ordering::Relaxed);
+        PEAK.store(now, Ordering::Relaxed);
+        now
+    }
+}
+
+unsafe impl GlobalAlloc for PeakAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            PeakAllocator::grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        PeakAllocator::shrank(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                PeakAllocator::grew(new_size - layout.size());
+            } else {
+                PeakAllocator::shrank(layout.size() - new_size);
+            }
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAllocator = PeakAllocator;
+
+/// Writes a database of `ENTRIES` copies of the fixture's first entry, each with
+/// its own source and title, one entry at a time
+fn write_large_database(path: &Path) {
+    let fixture: serde_json::Value = serde_json::from_slice(&std::fs::read(FIXTURE).unwrap()).unwrap();
+    let template = fixture["data"][0].clone();
+
+    let mut out = BufWriter::new(std::fs::File::create(path).unwrap());
+    let mut header = fixture.clone();
+    header.as_object_mut().unwrap().remove("data");
+    let header = header.to_string();
+    // The header object without its closing brace, then the data array
+    write!(out, "{},\"data\":[", &header[..header.len() - 1]).unwrap();
+
+    for index in 0..ENTRIES {
+        if index > 0 {
+            out.write_all(b",").unwrap();
+        }
+        let entry = if index % MALFORMED_EVERY == 0 {
+            serde_json::json!({ "sources": format!("https://myanimelist.net/anime/{}", index), "title": index })
+        } else {
+            let mut entry = template.clone();
+            entry["sources"] = serde_json::json!([format!("https://myanimelist.net/anime/{}", index)]);
+            entry["title"] = serde_json::json!(format!("Generated anime {}", index));
+            entry
+        };
+        serde_json::to_writer(&mut out, &entry).unwrap();
+    }
+    out.write_all(b"]}").unwrap();
+    out.flush().unwrap();
+}
+
+#[test]
+fn test_streaming_10k_entries_keeps_peak_allocation_bounded() {
+    let path: PathBuf = std::env::temp_dir().join(format!("anime-offline-database-{}.json", uuid::Uuid::new_v4()));
+    write_large_database(&path);
+    let file_size = std::fs::metadata(&path).unwrap().len() as usize;
+
+    let baseline = PeakAllocator::reset_peak();
+    let mut entries = AnimeOfflineDatabase::stream_entries(&path).unwrap();
+    let mut yielded = 0;
+    let mut last_title = String::new();
+    for entry in entries.by_ref() {
+        let entry = entry.unwrap();
+        yielded += 1;
+        last_title = entry.title;
+    }
+    let malformed = entries.malformed();
+    drop(entries);
+    let peak = PEAK.load(Ordering::Relaxed) - baseline;
+    std::fs::remove_file(&path).ok();
+
+    let expected_malformed = ENTRIES / MALFORMED_EVERY;
+    assert_eq!(malformed, expected_malformed);
+    assert_eq!(yielded, ENTRIES - expected_malformed);
+    assert_eq!(last_title, format!("Generated anime {}", ENTRIES - 1));
+
+    // Loading the file whole would need at least its size; the stream only
+    // ever holds its buffer of parsed entries
+    assert!(
+        peak < file_size / 4,
+        "peak allocation {} bytes while streaming a {} byte file",
+        peak, file_size
+    );
+}
@@ -1,11 +1,11 @@
 use kensho_backend::services::metadata::{MetadataService, OfflineAnimeEntry};
 use kensho_backend::services::database_simplified::DatabaseService;
-use kensho_backend::models::{Anime, Episode};
-use serde_json;
+use kensho_backend::models::Episode;
 use std::fs;
 use uuid::Uuid;
 
 #[tokio::test]
+#[ignore] // Requires .data/anime-offline-database.json
 async fn test_import_sample_data() {
     println!("Starting anime metadata ingestion test");
     
@@ -64,6 +64,7 @@ async fn test_import_sample_data() {
                                         air_date: None,
                                         synopsis: None,
                                         thumbnail_url: None,
+                                        absolute_number: None,
                                         created_at: chrono::Utc::now(),
                                         updated_at: chrono::Utc::now(),
                                     };
@@ -102,10 +103,10 @@ async fn test_import_sample_data() {
     println!("Total anime in database: {}", count);
     
     // Test fetching some data
-    let all_anime = db.get_all_anime().await
+    let all_anime = db.list_anime(5, 0).await
         .expect("Failed to fetch anime");
     println!("\nImported anime:");
-    for anime in all_anime.iter().take(5) {
+    for anime in &all_anime {
         println!("- {} ({} episodes)", anime.title, anime.episodes);
     }
     
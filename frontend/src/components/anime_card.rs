@@ -1,16 +1,26 @@
 use dioxus::prelude::*;
 use dioxus_router::prelude::*;
 use crate::models::AnimeSummary;
+use crate::services::data_saver::use_data_saver;
+use crate::services::prefetch::use_prefetch;
 
 #[component]
 pub fn AnimeCard(anime: AnimeSummary) -> Element {
     let nav = navigator();
     let anime_id = anime.id.clone();
+    let prefetch_id = anime.id.clone();
+    let data_saver = *use_data_saver().read();
+    let prefetcher = use_prefetch();
+    let mut poster_requested = use_signal(|| false);
+    
+    let poster_src = data_saver.image_url(&anime.poster_url);
+    let show_poster = !data_saver.defer_posters() || *poster_requested.read();
     
     rsx! {
         div { 
             class: "anime-card",
             onclick: move |_| { let _ = nav.push(format!("/anime/{}", anime_id)); },
+            onmouseenter: move |_| { prefetcher.prefetch_anime(&prefetch_id); },
             style: "
                 background: rgba(26, 26, 46, 0.6);
                 border-radius: 12px;
@@ -26,14 +36,36 @@ pub fn AnimeCard(anime: AnimeSummary) -> Element {
                     aspect-ratio: 3/4;
                     overflow: hidden;
                 ",
-                img {
-                    src: {anime.poster_url.clone()},
-                    alt: {anime.title.clone()},
-                    style: "
-                        width: 100%;
-                        height: 100%;
-                        object-fit: cover;
-                    ",
+                if show_poster {
+                    img {
+                        src: poster_src ,
+                        alt: anime.title.clone() ,
+                        loading: "lazy",
+                        style: "
+                            width: 100%;
+                            height: 100%;
+                            object-fit: cover;
+                        ",
+                    }
+                } else {
+                    // Data saver on mobile: the first tap loads the poster instead of navigating
+                    button {
+                        class: "poster-placeholder",
+                        onclick: move |evt| {
+                            evt.stop_propagation();
+                            poster_requested.set(true);
+                        },
+                        style: "
+                            width: 100%;
+                            height: 100%;
+                            background: rgba(102, 126, 234, 0.1);
+                            border: none;
+                            color: #a0a0b0;
+                            font-size: 0.875rem;
+                            cursor: pointer;
+                        ",
+                        "Tap to load poster"
+                    }
                 }
                 
                 // Status badge
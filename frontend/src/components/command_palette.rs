@@ -0,0 +1,448 @@
+// Global command palette (Ctrl/Cmd+K): jump to pages, flip settings and open
+// anime from live search results without touching the mouse
+
+use std::rc::Rc;
+use dioxus::prelude::*;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use crate::models::AnimeSummary;
+use crate::services::api::ApiClient;
+use crate::services::data_saver::use_data_saver;
+use crate::services::theme::use_theme;
+
+const PALETTE_ID: &str = "command-palette";
+const INPUT_ID: &str = "command-palette-input";
+
+/// Same season the navbar's Browse link opens
+const BROWSE_PATH: &str = "/browse/2024/FALL";
+
+/// Shorter queries only filter actions; search starts at this length
+const MIN_SEARCH_LENGTH: usize = 2;
+const MAX_ANIME_RESULTS: usize = 5;
+
+/// Whether the palette is showing. Shared through context as
+/// `Signal<CommandPaletteState>` so the navbar button can open it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CommandPaletteState {
+    pub open: bool,
+}
+
+/// The palette signal provided at the app root
+pub fn use_command_palette() -> Signal<CommandPaletteState> {
+    use_context::<Signal<CommandPaletteState>>()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PaletteCommand {
+    Navigate(String),
+    ToggleTheme,
+    ToggleDataSaver,
+}
+
+/// One row of the palette
+#[derive(Clone, Debug, PartialEq)]
+pub struct PaletteEntry {
+    pub label: String,
+    pub hint: &'static str,
+    pub command: PaletteCommand,
+}
+
+impl PaletteEntry {
+    fn new(label: &str, hint: &'static str, command: PaletteCommand) -> Self {
+        PaletteEntry {
+            label: label.to_string(),
+            hint,
+            command,
+        }
+    }
+
+    fn anime(anime: &AnimeSummary) -> Self {
+        PaletteEntry {
+            label: anime.title.clone(),
+            hint: "Anime",
+            command: PaletteCommand::Navigate(format!("/anime/{}", anime.id)),
+        }
+    }
+}
+
+/// Everything the palette can do besides opening an anime
+pub fn actions() -> Vec<PaletteEntry> {
+    vec![
+        PaletteEntry::new("Go to Browse", "Page", PaletteCommand::Navigate(BROWSE_PATH.to_string())),
+        PaletteEntry::new("Go to Watchlist", "Page", PaletteCommand::Navigate("/watchlist".to_string())),
+        PaletteEntry::new("Go to Settings", "Page", PaletteCommand::Navigate("/settings".to_string())),
+        PaletteEntry::new("Toggle theme", "Setting", PaletteCommand::ToggleTheme),
+        PaletteEntry::new("Toggle data saver", "Setting", PaletteCommand::ToggleDataSaver),
+    ]
+}
+
+/// Case-insensitive subsequence match of `query` against `candidate`, ignoring
+/// whitespace in the query. Higher scores are better: runs of consecutive
+/// characters and matches at the start of a word count extra. None when the
+/// query's characters do not all appear in order.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    let mut matched = 0;
+    let mut score = 0;
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+
+    for c in candidate.chars().flat_map(char::to_lowercase) {
+        if matched < query.len() && c == query[matched] {
+            score += 1;
+            if previous_matched {
+                score += 2;
+            }
+            if previous.map_or(true, |p| !p.is_alphanumeric()) {
+                score += 3;
+            }
+            matched += 1;
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous = Some(c);
+    }
+
+    (matched == query.len()).then_some(score)
+}
+
+/// Actions matching `query`, best first; all of them for an empty query
+pub fn matching_actions(query: &str) -> Vec<PaletteEntry> {
+    let mut scored: Vec<(u32, PaletteEntry)> = actions()
+        .into_iter()
+        .filter_map(|entry| fuzzy_score(query, &entry.label).map(|score| (score, entry)))
+        .collect();
+
+    // Stable, so ties keep the listing order
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+fn document() -> Option<web_sys::Document> {
+    web_sys::window()?.document()
+}
+
+fn focus_input() {
+    let input = document()
+        .and_then(|document| document.get_element_by_id(INPUT_ID))
+        .and_then(|element| element.dyn_into::<web_sys::HtmlElement>().ok());
+
+    if let Some(input) = input {
+        let _ = input.focus();
+    }
+}
+
+/// Document-level keys: Ctrl/Cmd+K toggles the palette, and Tab is held back
+/// while it is open so focus cannot leave it
+fn use_palette_shortcut(mut state: Signal<CommandPaletteState>) {
+    let listener = use_hook(|| {
+        let listener = Closure::<dyn FnMut(web_sys::KeyboardEvent)>::new(move |event: web_sys::KeyboardEvent| {
+            let open = state.peek().open;
+
+            if (event.ctrl_key() || event.meta_key()) && event.key().eq_ignore_ascii_case("k") {
+                event.prevent_default();
+                state.write().open = !open;
+            } else if open && event.key() == "Tab" {
+                event.prevent_default();
+                focus_input();
+            }
+        });
+
+        if let Some(document) = document() {
+            let callback: &js_sys::Function = listener.as_ref().unchecked_ref();
+            let _ = document.add_event_listener_with_callback("keydown", callback);
+        }
+        Rc::new(listener)
+    });
+
+    use_drop(move || {
+        if let Some(document) = document() {
+            let callback: &js_sys::Function = (*listener).as_ref().unchecked_ref();
+            let _ = document.remove_event_listener_with_callback("keydown", callback);
+        }
+    });
+}
+
+#[component]
+pub fn CommandPalette(on_navigate: EventHandler<String>) -> Element {
+    let mut state = use_command_palette();
+    let mut theme = use_theme();
+    let mut data_saver = use_data_saver();
+    let mut query = use_signal(String::new);
+    let mut selected = use_signal(|| 0usize);
+    let mut anime_results = use_signal(Vec::<AnimeSummary>::new);
+
+    use_palette_shortcut(state);
+
+    if !state.read().open {
+        return None;
+    }
+
+    let mut entries = matching_actions(&query.read());
+    entries.extend(anime_results.read().iter().take(MAX_ANIME_RESULTS).map(PaletteEntry::anime));
+    let count = entries.len();
+    let current = (*selected.read()).min(count.saturating_sub(1));
+    let commands: Vec<PaletteCommand> = entries.iter().map(|entry| entry.command.clone()).collect();
+
+    let mut close = move || {
+        state.write().open = false;
+        query.set(String::new());
+        selected.set(0);
+        anime_results.set(Vec::new());
+    };
+
+    let mut run = move |command: PaletteCommand| {
+        match command {
+            PaletteCommand::Navigate(path) => on_navigate.call(path),
+            PaletteCommand::ToggleTheme => theme.write().toggle(),
+            PaletteCommand::ToggleDataSaver => {
+                let enabled = data_saver.read().enabled;
+                data_saver.write().set_enabled(!enabled);
+            }
+        }
+        close();
+    };
+
+    let mut search = move |text: String| {
+        if text.trim().chars().count() < MIN_SEARCH_LENGTH {
+            anime_results.set(Vec::new());
+            return;
+        }
+
+        spawn(async move {
+            match ApiClient::new().search(text.trim()).await {
+                // Drop answers to queries the user has already typed past
+                Ok(results) if *query.peek() == text => anime_results.set(results),
+                Ok(_) => {}
+                Err(e) => tracing::debug!("Palette search failed: {}", e),
+            }
+        });
+    };
+
+    rsx! {
+        div {
+            class: "command-palette-backdrop",
+            onclick: move |_| close(),
+            style: "
+                position: fixed;
+                inset: 0;
+                background: rgba(0, 0, 0, 0.6);
+                backdrop-filter: blur(4px);
+                display: flex;
+                justify-content: center;
+                align-items: flex-start;
+                padding-top: 15vh;
+                z-index: 2000;
+            ",
+
+            div {
+                id: PALETTE_ID,
+                role: "dialog",
+                "aria-modal": "true",
+                "aria-label": "Command palette",
+                onclick: move |evt| evt.stop_propagation(),
+                style: "
+                    width: min(600px, 90vw);
+                    background: rgba(26, 26, 46, 0.98);
+                    border: 1px solid rgba(138, 43, 226, 0.4);
+                    border-radius: 12px;
+                    box-shadow: 0 20px 50px rgba(0,0,0,0.6);
+                    overflow: hidden;
+                ",
+
+                input {
+                    id: INPUT_ID,
+                    r#type: "text",
+                    value: "{query}",
+                    placeholder: "Type a command or search anime...",
+                    autocomplete: "off",
+                    role: "combobox",
+                    "aria-expanded": "true",
+                    "aria-controls": "command-palette-results",
+                    onmounted: move |evt| async move {
+                        let _ = evt.set_focus(true).await;
+                    },
+                    oninput: move |evt| {
+                        let text = evt.value();
+                        query.set(text.clone());
+                        selected.set(0);
+                        search(text);
+                    },
+                    onkeydown: move |evt: KeyboardEvent| {
+                        match evt.key() {
+                            Key::ArrowDown if count > 0 => selected.set((current + 1) % count),
+                            Key::ArrowUp if count > 0 => selected.set((current + count - 1) % count),
+                            Key::Enter => {
+                                if let Some(command) = commands.get(current).cloned() {
+                                    run(command);
+                                }
+                            }
+                            Key::Escape => close(),
+                            _ => {}
+                        }
+                    },
+                    style: "
+                        width: 100%;
+                        padding: 1rem 1.25rem;
+                        background: transparent;
+                        border: none;
+                        border-bottom: 1px solid rgba(255,255,255,0.1);
+                        color: white;
+                        font-size: 1.05rem;
+                        outline: none;
+                    ",
+                }
+
+                if entries.is_empty() {
+                    p {
+                        style: "padding: 1rem 1.25rem; color: #a0a0b0;",
+                        "No matching commands"
+                    }
+                }
+
+                ul {
+                    id: "command-palette-results",
+                    role: "listbox",
+                    style: "list-style: none; max-height: 360px; overflow-y: auto;",
+
+                    {entries.into_iter().enumerate().map(|(index, entry)| {
+                        let command = entry.command.clone();
+                        let is_selected = index == current;
+                        let aria_selected = if is_selected { "true" } else { "false" };
+                        rsx! {
+                            li {
+                                key: "{index}-{entry.label}",
+                                role: "option",
+                                "aria-selected": aria_selected,
+                                onclick: move |_| run(command.clone()),
+                                onmouseenter: move |_| selected.set(index),
+                                style: format!("
+                                    display: flex;
+                                    justify-content: space-between;
+                                    padding: 0.75rem 1.25rem;
+                                    cursor: pointer;
+                                    color: white;
+                                    background: {};
+                                ", if is_selected { "rgba(138, 43, 226, 0.3)" } else { "transparent" }),
+
+                                span { {entry.label} }
+                                span {
+                                    style: "color: #a0a0b0; font-size: 0.85rem;",
+                                    {entry.hint}
+                                }
+                            }
+                        }
+                    })}
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use crate::services::data_saver::DataSaver;
+    use crate::services::theme::Theme;
+    use gloo_timers::future::TimeoutFuture;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const TEST_ROOT_ID: &str = "command-palette-test";
+
+    thread_local! {
+        static NAVIGATED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+    }
+
+    #[wasm_bindgen_test]
+    fn test_fuzzy_matching() {
+        assert!(fuzzy_score("wtch", "Go to Watchlist").is_some());
+        assert!(fuzzy_score("data saver", "Toggle data saver").is_some());
+        assert!(fuzzy_score("xyz", "Go to Settings").is_none());
+        assert_eq!(fuzzy_score("", "Go to Browse"), Some(0));
+
+        // Word starts and runs beat scattered letters
+        assert!(fuzzy_score("set", "Go to Settings") > fuzzy_score("set", "Toggle data saver theme"));
+
+        let labels: Vec<String> = matching_actions("theme").into_iter().map(|entry| entry.label).collect();
+        assert_eq!(labels, ["Toggle theme"]);
+        assert_eq!(matching_actions("").len(), actions().len());
+    }
+
+    fn palette_test_app() -> Element {
+        use_context_provider(|| Signal::new(DataSaver { enabled: false }));
+        use_context_provider(|| Signal::new(Theme::Dark));
+        use_context_provider(|| Signal::new(CommandPaletteState::default()));
+
+        rsx! {
+            CommandPalette {
+                on_navigate: move |path: String| NAVIGATED.with(|navigated| navigated.borrow_mut().push(path)),
+            }
+        }
+    }
+
+    fn key_event(key: &str, ctrl: bool) -> web_sys::KeyboardEvent {
+        let init = web_sys::KeyboardEventInit::new();
+        init.set_key(key);
+        init.set_ctrl_key(ctrl);
+        init.set_bubbles(true);
+        init.set_cancelable(true);
+        web_sys::KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_chord_query_and_enter_navigate() {
+        let document = document().unwrap();
+        let root = document.create_element("div").unwrap();
+        root.set_id(TEST_ROOT_ID);
+        document.body().unwrap().append_child(&root).unwrap();
+
+        dioxus::web::launch::launch_cfg(palette_test_app, dioxus::web::Config::new().rootname(TEST_ROOT_ID));
+        TimeoutFuture::new(50).await;
+        assert!(document.get_element_by_id(PALETTE_ID).is_none());
+
+        // Ctrl+K opens the palette with the input focused
+        document.dispatch_event(&key_event("k", true)).unwrap();
+        TimeoutFuture::new(50).await;
+        let input: web_sys::HtmlInputElement = document
+            .get_element_by_id(INPUT_ID)
+            .expect("palette should be open")
+            .dyn_into()
+            .unwrap();
+        assert_eq!(document.active_element().map(|element| element.id()).as_deref(), Some(INPUT_ID));
+
+        // Tab does not move focus out
+        let tab = key_event("Tab", false);
+        document.dispatch_event(&tab).unwrap();
+        assert!(tab.default_prevented());
+
+        input.set_value("wtch");
+        let init = web_sys::EventInit::new();
+        init.set_bubbles(true);
+        input.dispatch_event(&web_sys::Event::new_with_event_init_dict("input", &init).unwrap()).unwrap();
+        TimeoutFuture::new(50).await;
+
+        // The best match is selected; Enter runs it and closes the palette
+        input.dispatch_event(&key_event("Enter", false)).unwrap();
+        TimeoutFuture::new(50).await;
+        assert_eq!(NAVIGATED.with(|navigated| navigated.borrow().clone()), ["/watchlist"]);
+        assert!(document.get_element_by_id(PALETTE_ID).is_none());
+
+        // Escape closes without running anything
+        document.dispatch_event(&key_event("k", true)).unwrap();
+        TimeoutFuture::new(50).await;
+        let input = document.get_element_by_id(INPUT_ID).expect("palette should reopen");
+        input.dispatch_event(&key_event("Escape", false)).unwrap();
+        TimeoutFuture::new(50).await;
+        assert!(document.get_element_by_id(PALETTE_ID).is_none());
+        assert_eq!(NAVIGATED.with(|navigated| navigated.borrow().len()), 1);
+    }
+}
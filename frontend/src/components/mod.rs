@@ -4,10 +4,13 @@ pub mod video_player;
 pub mod anime_card;
 pub mod episode_list;
 pub mod navbar;
+pub mod command_palette;
+pub mod user_avatar;
+pub mod toast;
 
-pub use ip_hub::IpHub;
 pub use search_bar::SearchBar;
 pub use video_player::VideoPlayer;
-pub use anime_card::{AnimeCard, AnimeGrid};
+pub use anime_card::AnimeGrid;
 pub use episode_list::EpisodeList;
-pub use navbar::{NavBar, MobileNavBar};
\ No newline at end of file
+pub use navbar::NavBar;
+pub use toast::ToastContainer;
@@ -1,13 +1,24 @@
 use dioxus::prelude::*;
 use dioxus_router::prelude::*;
 use crate::services::auth::AuthState;
+use super::command_palette::{use_command_palette, CommandPalette};
+use super::user_avatar::UserAvatar;
 
 #[component]
 pub fn NavBar() -> Element {
     let mut auth_state = use_context::<Signal<AuthState>>();
+    let mut palette = use_command_palette();
     let nav = navigator();
     
     let is_authenticated = auth_state.read().is_authenticated();
+    // No display name is stored yet; the email's local part stands in for it
+    let username = auth_state
+        .read()
+        .user_email
+        .as_deref()
+        .and_then(|email| email.split('@').next())
+        .unwrap_or("User")
+        .to_string();
     
     let handle_logout = move |_| {
         auth_state.write().logout();
@@ -98,6 +109,19 @@ pub fn NavBar() -> Element {
                             "Browse"
                         }
                         
+                        Link {
+                            to: "/settings",
+                            class: "nav-link",
+                            style: "
+                                color: #e0e0e0;
+                                text-decoration: none;
+                                padding: 0.5rem 1rem;
+                                border-radius: 0.5rem;
+                                transition: all 0.3s;
+                            ",
+                            "Settings"
+                        }
+                        
                         if is_authenticated {
                             Link {
                                 to: "/watchlist",
@@ -143,6 +167,24 @@ pub fn NavBar() -> Element {
                         }
                     }
                     
+                    // Command palette button (also Ctrl/Cmd+K)
+                    button {
+                        class: "command-palette-button",
+                        "aria-label": "Open command palette",
+                        title: "Command palette (Ctrl+K)",
+                        onclick: move |_| palette.write().open = true,
+                        style: "
+                            background: transparent;
+                            color: #e0e0e0;
+                            border: 1px solid rgba(255,255,255,0.2);
+                            padding: 0.3rem 0.6rem;
+                            border-radius: 0.5rem;
+                            font-size: 0.85rem;
+                            cursor: pointer;
+                        ",
+                        "⌘K"
+                    }
+                    
                     // User menu
                     div {
                         class: "user-menu",
@@ -152,21 +194,7 @@ pub fn NavBar() -> Element {
                             div {
                                 style: "display: flex; align-items: center; gap: 1rem;",
                                 
-                                // User avatar
-                                div {
-                                    style: "
-                                        width: 32px;
-                                        height: 32px;
-                                        border-radius: 50%;
-                                        background: linear-gradient(45deg, #667eea 0%, #764ba2 100%);
-                                        display: flex;
-                                        align-items: center;
-                                        justify-content: center;
-                                        color: white;
-                                        font-weight: bold;
-                                    ",
-                                    "U"
-                                }
+                                UserAvatar { username: username, size: 32 }
                                 
                                 button {
                                     onclick: handle_logout,
@@ -200,6 +228,12 @@ pub fn NavBar() -> Element {
                 }
             }
         }
+        
+        CommandPalette {
+            on_navigate: move |path: String| {
+                nav.push(path);
+            }
+        }
     }
 }
 
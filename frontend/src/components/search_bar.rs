@@ -2,16 +2,18 @@ use dioxus::prelude::*;
 use dioxus_router::prelude::*;
 use crate::services::api::ApiClient;
 use crate::models::AnimeSummary;
+use crate::services::data_saver::use_data_saver;
 
 #[component]
 pub fn SearchBar() -> Element {
+    let data_saver = use_data_saver();
     let mut query = use_signal(String::new);
-    let mut results = use_signal(|| Vec::<AnimeSummary>::new());
+    let mut results = use_signal(Vec::<AnimeSummary>::new);
     let mut is_searching = use_signal(|| false);
     let mut show_dropdown = use_signal(|| false);
     let nav = navigator();
     
-    let mut search = move |_| {
+    let search = move |_| {
         let search_query = query.read().clone();
         if search_query.len() < 2 {
             results.set(Vec::new());
@@ -50,7 +52,7 @@ pub fn SearchBar() -> Element {
                 
                 input {
                     r#type: "text",
-                    value: {query.read().clone()},
+                    value: query.read().clone() ,
                     oninput: move |e| query.set(e.value()),
                     onkeyup: search,
                     onfocus: move |_| show_dropdown.set(true),
@@ -125,7 +127,7 @@ pub fn SearchBar() -> Element {
                             ",
                             
                             img {
-                                src: {result.poster_url},
+                                src: data_saver.read().image_url(&result.poster_url) ,
                                 style: "
                                     width: 50px;
                                     height: 70px;
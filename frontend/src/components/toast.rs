@@ -0,0 +1,245 @@
+// Global toast notifications: pages report success and failure through
+// `use_toasts()`, and the `ToastContainer` mounted at the app root shows them
+// bottom-right until they time out or are dismissed
+
+use std::collections::VecDeque;
+use dioxus::prelude::*;
+use gloo_timers::future::TimeoutFuture;
+use uuid::Uuid;
+
+const CONTAINER_ID: &str = "toast-container";
+
+/// Older toasts are dropped once this many are showing
+const MAX_TOASTS: usize = 5;
+
+#[allow(dead_code)] // Until a page confirms an action with a toast
+const SUCCESS_DURATION_MS: u32 = 3_000;
+const WARNING_DURATION_MS: u32 = 5_000;
+/// Errors stay up longer, since they usually need reading
+const ERROR_DURATION_MS: u32 = 7_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToastKind {
+    #[allow(dead_code)]
+    Success,
+    Error,
+    Warning,
+}
+
+impl ToastKind {
+    fn accent(&self) -> &'static str {
+        match self {
+            ToastKind::Success => "#2ecc71",
+            ToastKind::Error => "#e74c3c",
+            ToastKind::Warning => "#f1c40f",
+        }
+    }
+
+    fn icon(&self) -> &'static str {
+        match self {
+            ToastKind::Success => "✓",
+            ToastKind::Error => "✕",
+            ToastKind::Warning => "!",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Toast {
+    pub id: Uuid,
+    pub kind: ToastKind,
+    pub message: String,
+    pub duration_ms: u32,
+}
+
+/// The toasts showing, oldest first. Provided at the app root as
+/// `Signal<VecDeque<Toast>>`; pages reach it through `use_toasts`.
+#[derive(Clone, Copy, PartialEq)]
+pub struct ToastService {
+    toasts: Signal<VecDeque<Toast>>,
+}
+
+/// The toast service of the app root
+pub fn use_toasts() -> ToastService {
+    ToastService {
+        toasts: use_context::<Signal<VecDeque<Toast>>>(),
+    }
+}
+
+impl ToastService {
+    /// Show `message` for `duration_ms`; returns the toast's id
+    pub fn push(&mut self, kind: ToastKind, message: impl Into<String>, duration_ms: u32) -> Uuid {
+        let toast = Toast {
+            id: Uuid::new_v4(),
+            kind,
+            message: message.into(),
+            duration_ms,
+        };
+        let id = toast.id;
+
+        let mut toasts = self.toasts.write();
+        toasts.push_back(toast);
+        while toasts.len() > MAX_TOASTS {
+            toasts.pop_front();
+        }
+        id
+    }
+
+    #[allow(dead_code)]
+    pub fn success(&mut self, message: impl Into<String>) -> Uuid {
+        self.push(ToastKind::Success, message, SUCCESS_DURATION_MS)
+    }
+
+    pub fn error(&mut self, message: impl Into<String>) -> Uuid {
+        self.push(ToastKind::Error, message, ERROR_DURATION_MS)
+    }
+
+    pub fn warning(&mut self, message: impl Into<String>) -> Uuid {
+        self.push(ToastKind::Warning, message, WARNING_DURATION_MS)
+    }
+
+    pub fn dismiss(&mut self, id: Uuid) {
+        self.toasts.write().retain(|toast| toast.id != id);
+    }
+}
+
+/// Renders the toasts bottom-right; mount once, next to the router
+#[component]
+pub fn ToastContainer() -> Element {
+    let service = use_toasts();
+    let toasts: Vec<Toast> = service.toasts.read().iter().cloned().collect();
+
+    rsx! {
+        div {
+            id: CONTAINER_ID,
+            role: "status",
+            "aria-live": "polite",
+            style: "
+                position: fixed;
+                right: 1.5rem;
+                bottom: 1.5rem;
+                display: flex;
+                flex-direction: column;
+                gap: 0.75rem;
+                z-index: 3000;
+                pointer-events: none;
+            ",
+
+            for toast in toasts {
+                ToastItem { key: "{toast.id}", toast: toast.clone() }
+            }
+        }
+    }
+}
+
+#[component]
+fn ToastItem(toast: Toast) -> Element {
+    let mut service = use_toasts();
+    let id = toast.id;
+    let duration_ms = toast.duration_ms;
+
+    // Dropped with the component, so a toast closed early leaves no timer behind
+    use_effect(move || {
+        spawn(async move {
+            TimeoutFuture::new(duration_ms).await;
+            service.dismiss(id);
+        });
+    });
+
+    rsx! {
+        div {
+            class: "toast",
+            "data-kind": format!("{:?}", toast.kind).to_lowercase(),
+            style: format!("
+                display: flex;
+                align-items: center;
+                gap: 0.75rem;
+                min-width: 260px;
+                max-width: 400px;
+                padding: 0.85rem 1rem;
+                background: rgba(26, 26, 46, 0.98);
+                border-left: 4px solid {};
+                border-radius: 8px;
+                box-shadow: 0 10px 30px rgba(0,0,0,0.5);
+                color: white;
+                pointer-events: auto;
+                animation: toast-slide-in 0.25s ease-out;
+            ", toast.kind.accent()),
+
+            span {
+                style: format!("color: {}; font-weight: bold;", toast.kind.accent()),
+                {toast.kind.icon()}
+            }
+            span { style: "flex: 1;", {toast.message.clone()} }
+            button {
+                "aria-label": "Dismiss notification",
+                onclick: move |_| service.dismiss(id),
+                style: "
+                    background: none;
+                    border: none;
+                    color: #a0a0b0;
+                    cursor: pointer;
+                    font-size: 1rem;
+                ",
+                "×"
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const TEST_ROOT_ID: &str = "toast-test";
+    const MESSAGE: &str = "Failed to load seasonal anime";
+
+    fn toast_test_app() -> Element {
+        use_context_provider(|| Signal::new(VecDeque::<Toast>::new()));
+        let mut toasts = use_toasts();
+
+        rsx! {
+            button {
+                id: "toast-test-fire",
+                onclick: move |_| {
+                    toasts.push(ToastKind::Error, MESSAGE, 200);
+                },
+            }
+            ToastContainer {}
+        }
+    }
+
+    fn container_text(document: &web_sys::Document) -> String {
+        document
+            .get_element_by_id(CONTAINER_ID)
+            .and_then(|container| container.text_content())
+            .unwrap_or_default()
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_fired_toast_appears_then_expires() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let root = document.create_element("div").unwrap();
+        root.set_id(TEST_ROOT_ID);
+        document.body().unwrap().append_child(&root).unwrap();
+
+        dioxus::web::launch::launch_cfg(toast_test_app, dioxus::web::Config::new().rootname(TEST_ROOT_ID));
+        TimeoutFuture::new(50).await;
+        assert!(!container_text(&document).contains(MESSAGE));
+
+        let fire: web_sys::HtmlElement = wasm_bindgen::JsCast::dyn_into(
+            document.get_element_by_id("toast-test-fire").unwrap(),
+        ).unwrap();
+        fire.click();
+        TimeoutFuture::new(50).await;
+        assert!(container_text(&document).contains(MESSAGE));
+        assert!(document.query_selector("#toast-container .toast[data-kind=\"error\"]").unwrap().is_some());
+
+        // Gone once its 200ms are up
+        TimeoutFuture::new(300).await;
+        assert!(!container_text(&document).contains(MESSAGE));
+    }
+}
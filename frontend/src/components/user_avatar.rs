@@ -0,0 +1,151 @@
+use dioxus::prelude::*;
+use dioxus_router::prelude::*;
+use crate::services::auth::AuthState;
+
+/// Up to two letters: the first of each whitespace-separated word
+pub fn initials(username: &str) -> String {
+    username
+        .split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// Gravatar image for `email` at `size` pixels
+pub fn gravatar_url(email: &str, size: u32) -> String {
+    let hash = md5::compute(email.trim().to_lowercase());
+    format!("https://www.gravatar.com/avatar/{:x}?s={}", hash, size)
+}
+
+/// Round avatar with a Profile / Settings menu on hover. Shows the
+/// Gravatar for the signed-in user's email when there is one, initials
+/// otherwise
+#[component]
+pub fn UserAvatar(username: String, size: u32) -> Element {
+    let auth_state = use_context::<Signal<AuthState>>();
+    let mut menu_open = use_signal(|| false);
+
+    let gravatar = auth_state.read().user_email.as_deref().map(|email| gravatar_url(email, size));
+    let initials = initials(&username);
+    let font_size = size / 2;
+
+    rsx! {
+        div {
+            class: "user-avatar",
+            style: "position: relative;",
+            onmouseenter: move |_| menu_open.set(true),
+            onmouseleave: move |_| menu_open.set(false),
+
+            if let Some(src) = gravatar {
+                img {
+                    class: "user-avatar-image",
+                    src: "{src}",
+                    alt: "{username}",
+                    width: "{size}",
+                    height: "{size}",
+                    style: "border-radius: 50%; display: block;",
+                }
+            } else {
+                div {
+                    class: "user-avatar-initials",
+                    title: "{username}",
+                    style: "
+                        width: {size}px;
+                        height: {size}px;
+                        border-radius: 50%;
+                        background: linear-gradient(45deg, #667eea 0%, #764ba2 100%);
+                        display: flex;
+                        align-items: center;
+                        justify-content: center;
+                        color: white;
+                        font-weight: bold;
+                        font-size: {font_size}px;
+                    ",
+                    "{initials}"
+                }
+            }
+
+            if *menu_open.read() {
+                div {
+                    class: "user-avatar-menu",
+                    style: "
+                        position: absolute;
+                        top: 100%;
+                        right: 0;
+                        min-width: 140px;
+                        padding: 0.5rem 0;
+                        background: rgba(0,0,0,0.95);
+                        border: 1px solid rgba(138, 43, 226, 0.3);
+                        border-radius: 0.5rem;
+                        display: flex;
+                        flex-direction: column;
+                    ",
+
+                    Link {
+                        to: "/profile",
+                        style: "color: #e0e0e0; text-decoration: none; padding: 0.5rem 1rem;",
+                        "Profile"
+                    }
+                    Link {
+                        to: "/settings",
+                        style: "color: #e0e0e0; text-decoration: none; padding: 0.5rem 1rem;",
+                        "Settings"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gloo_timers::future::TimeoutFuture;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const TEST_ROOT_ID: &str = "user-avatar-test";
+
+    #[wasm_bindgen_test]
+    fn test_initials_and_gravatar() {
+        assert_eq!(initials("John Doe"), "JD");
+        assert_eq!(initials("  mary  ann  lee "), "MA");
+        assert_eq!(initials("demo"), "D");
+        assert_eq!(initials(""), "");
+        assert_eq!(
+            gravatar_url(" MyEmailAddress@example.com ", 64),
+            "https://www.gravatar.com/avatar/0bc83cb571cd1c50ba6f3e8a78ef1346?s=64"
+        );
+    }
+
+    fn avatar_test_app() -> Element {
+        use_context_provider(|| Signal::new(AuthState {
+            access_token: Some("token".to_string()),
+            refresh_token: None,
+            user_email: None,
+        }));
+
+        rsx! {
+            UserAvatar { username: "John Doe", size: 32 }
+        }
+    }
+
+    #[wasm_bindgen_test]
+    async fn test_renders_initials() {
+        let document = web_sys::window().unwrap().document().unwrap();
+        let root = document.create_element("div").unwrap();
+        root.set_id(TEST_ROOT_ID);
+        document.body().unwrap().append_child(&root).unwrap();
+
+        dioxus::web::launch::launch_cfg(avatar_test_app, dioxus::web::Config::new().rootname(TEST_ROOT_ID));
+        TimeoutFuture::new(50).await;
+
+        let initials = root
+            .query_selector(".user-avatar-initials")
+            .unwrap()
+            .expect("avatar should show initials without an email");
+        assert_eq!(initials.text_content().as_deref(), Some("JD"));
+    }
+}
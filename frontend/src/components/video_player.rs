@@ -1,9 +1,73 @@
 use dioxus::prelude::*;
+use wasm_bindgen::JsCast;
+use crate::services::api::ApiClient;
+use crate::services::data_saver::use_data_saver;
 
+/// Plays `stream_url`. Given the stream's `resume_token` and the user's
+/// `auth_token`, a network error swaps in a resumed URL before giving up.
+/// With an `episode_id` the playback position is saved periodically, less
+/// often in data saver mode. A `watermark` stream (demo mode) is badged in
+/// the corner.
 #[component]
-pub fn VideoPlayer(stream_url: String) -> Element {
+pub fn VideoPlayer(
+    stream_url: String,
+    resume_token: Option<String>,
+    auth_token: Option<String>,
+    episode_id: Option<String>,
+    #[props(default)]
+    watermark: bool,
+) -> Element {
+    let data_saver = use_data_saver();
     let mut is_loading = use_signal(|| true);
     let mut has_error = use_signal(|| false);
+    let mut current_url = use_signal(|| stream_url.clone());
+    let mut current_resume_token = use_signal(|| resume_token.clone());
+    let mut last_report_ms = use_signal(|| 0.0);
+    
+    let report_auth_token = auth_token.clone();
+    let on_time_update = move |_| {
+        let (Some(episode), Some(auth)) = (episode_id.clone(), report_auth_token.clone()) else {
+            return;
+        };
+        
+        let now = js_sys::Date::now();
+        if now - *last_report_ms.read() < data_saver.read().progress_report_interval_ms() {
+            return;
+        }
+        let Some(video) = player_video_element() else {
+            return;
+        };
+        last_report_ms.set(now);
+        
+        let position = video.current_time() as u32;
+        let duration = video.duration();
+        let duration = if duration.is_finite() { duration as u32 } else { 0 };
+        spawn(async move {
+            if let Err(e) = ApiClient::new().save_playback_position(&episode, position, duration, &auth).await {
+                tracing::warn!("Failed to save playback position: {}", e);
+            }
+        });
+    };
+    
+    let on_video_error = move |_| {
+        let (Some(resume), Some(auth)) = (current_resume_token.read().clone(), auth_token.clone()) else {
+            has_error.set(true);
+            return;
+        };
+        
+        spawn(async move {
+            match ApiClient::new().resume_stream(&resume, &auth).await {
+                Ok(manifest) if !manifest.streams.is_empty() => {
+                    current_url.set(manifest.streams[0].url.clone());
+                    current_resume_token.set(manifest.resume_token);
+                }
+                _ => {
+                    current_resume_token.set(None);
+                    has_error.set(true);
+                }
+            }
+        });
+    };
     
     use_effect(move || {
         // In production, this would initialize HLS.js or native video player
@@ -25,6 +89,27 @@ pub fn VideoPlayer(stream_url: String) -> Element {
                 overflow: hidden;
             ",
             
+            if watermark {
+                div {
+                    style: "
+                        position: absolute;
+                        top: 0.75rem;
+                        right: 0.75rem;
+                        z-index: 10;
+                        padding: 0.25rem 0.6rem;
+                        background: rgba(0,0,0,0.6);
+                        border: 1px solid rgba(255,255,255,0.4);
+                        border-radius: 6px;
+                        color: white;
+                        font-size: 0.75rem;
+                        font-weight: 600;
+                        letter-spacing: 0.05em;
+                        pointer-events: none;
+                    ",
+                    "DEMO"
+                }
+            }
+            
             if *is_loading.read() {
                 div {
                     style: "
@@ -107,7 +192,9 @@ pub fn VideoPlayer(stream_url: String) -> Element {
                 }
             } else {
                 video {
-                    src: {stream_url.clone()},
+                    src: current_url.read().clone(),
+                    onerror: on_video_error,
+                    ontimeupdate: on_time_update,
                     controls: true,
                     autoplay: true,
                     style: "
@@ -181,4 +268,13 @@ pub fn VideoPlayer(stream_url: String) -> Element {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+fn player_video_element() -> Option<web_sys::HtmlVideoElement> {
+    web_sys::window()?
+        .document()?
+        .query_selector(".video-player video")
+        .ok()??
+        .dyn_into::<web_sys::HtmlVideoElement>()
+        .ok()
+}
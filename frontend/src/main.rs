@@ -7,10 +7,17 @@ mod models;
 mod services;
 
 use services::auth::AuthState;
+use services::data_saver::DataSaver;
+use services::theme::Theme;
+use components::command_palette::CommandPaletteState;
+use components::toast::Toast;
+use components::ToastContainer;
 use pages::Home;
 use pages::Login;
 use pages::Series;
 use pages::Browse;
+use pages::SeasonPlan;
+use pages::Settings;
 
 #[derive(Clone, Routable, Debug, PartialEq)]
 enum Route {
@@ -22,6 +29,10 @@ enum Route {
     Series { id: String },
     #[route("/browse/:year/:season")]
     Browse { year: i32, season: String },
+    #[route("/browse/:year/:season/plan")]
+    SeasonPlan { year: i32, season: String },
+    #[route("/settings")]
+    Settings {},
     #[route("/:..route")]
     PageNotFound { route: Vec<String> },
 }
@@ -37,8 +48,17 @@ fn main() {
 
 fn app() -> Element {
     use_context_provider(|| Signal::new(AuthState::default()));
+    use_context_provider(|| Signal::new(DataSaver::default()));
+    use_context_provider(|| {
+        let theme = Theme::stored();
+        theme.apply();
+        Signal::new(theme)
+    });
+    use_context_provider(|| Signal::new(CommandPaletteState::default()));
+    use_context_provider(|| Signal::new(std::collections::VecDeque::<Toast>::new()));
     rsx! {
         Router::<Route> {}
+        ToastContainer {}
     }
 }
 
@@ -14,6 +14,20 @@ pub struct Anime {
     pub rating: Option<f32>,
     #[serde(default)]
     pub tags: Vec<String>,
+    /// Episodes actually stored; may lag the declared count after partial imports
+    #[serde(default)]
+    pub available_episodes: Option<i32>,
+}
+
+impl Anime {
+    pub fn episode_label(&self) -> String {
+        match self.available_episodes {
+            Some(available) if available < self.episode_count => {
+                format!("{} of {} episodes available", available, self.episode_count)
+            }
+            _ => format!("{} Episodes", self.episode_count),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -43,7 +57,14 @@ pub struct AnimeSummary {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SearchResponse {
     pub results: Vec<AnimeSummary>,
+    /// Number of matches across all pages
     pub total: usize,
+    #[serde(default)]
+    pub page: usize,
+    #[serde(default)]
+    pub limit: usize,
+    #[serde(default)]
+    pub has_more: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -54,12 +75,43 @@ pub struct SeasonalBrowseResponse {
     pub total: usize,
 }
 
+/// Per-season values of one year
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SeasonGroups<T> {
+    pub winter: T,
+    pub spring: T,
+    pub summer: T,
+    pub fall: T,
+}
+
+impl<T> SeasonGroups<T> {
+    /// Seasons in broadcast order with their names
+    pub fn in_order(&self) -> [(&'static str, &T); 4] {
+        [
+            ("winter", &self.winter),
+            ("spring", &self.spring),
+            ("summer", &self.summer),
+            ("fall", &self.fall),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct YearBrowseResponse {
+    pub year: i32,
+    pub seasons: SeasonGroups<Vec<AnimeSummary>>,
+    pub totals: SeasonGroups<usize>,
+    pub total: usize,
+}
+
+#[allow(dead_code)] // Until the login page stops mocking
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct LoginRequest {
     pub email: String,
     pub password: String,
 }
 
+#[allow(dead_code)] // Until the login page stops mocking
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct LoginResponse {
     pub access_token: String,
@@ -72,4 +124,87 @@ pub struct StreamUrl {
     pub url: String,
     pub quality: String,
     pub expires_at: String,
-}
\ No newline at end of file
+    /// "demo" when the server plays a sample instead of the episode
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub watermark: bool,
+}
+/// One playable rendition from a stream manifest
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StreamVariant {
+    pub url: String,
+    pub resolution: String,
+}
+
+/// Stream manifest with the token used to resume it after a network error
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StreamManifest {
+    pub streams: Vec<StreamVariant>,
+    #[serde(default)]
+    pub resume_token: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CalendarEntry {
+    pub anime_id: String,
+    pub title: String,
+    /// Lowercase English weekday name, e.g. "monday"
+    pub weekday: String,
+    /// Local broadcast time as "HH:MM", if known
+    #[serde(default)]
+    pub air_time: Option<String>,
+    /// First airing date as "YYYY-MM-DD"
+    pub first_air_date: String,
+    /// Final episode airing date as "YYYY-MM-DD", if known
+    #[serde(default)]
+    pub last_air_date: Option<String>,
+    #[serde(default)]
+    pub episodes: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SeasonCalendarResponse {
+    pub year: i32,
+    pub season: String,
+    pub entries: Vec<CalendarEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WatchlistItem {
+    pub anime_id: String,
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WatchlistResponse {
+    pub items: Vec<WatchlistItem>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_episode_label_shows_partial_availability() {
+        let mut anime: Anime = serde_json::from_value(serde_json::json!({
+            "id": "a1",
+            "title": "Partial Import",
+            "description": "",
+            "poster_url": "",
+            "episodes": 12,
+            "status": "ongoing",
+            "anime_type": "TV",
+            "imdb_rating": null,
+            "available_episodes": 3
+        })).unwrap();
+        assert_eq!(anime.episode_label(), "3 of 12 episodes available");
+
+        anime.available_episodes = Some(12);
+        assert_eq!(anime.episode_label(), "12 Episodes");
+
+        anime.available_episodes = None;
+        assert_eq!(anime.episode_label(), "12 Episodes");
+    }
+}
@@ -1,14 +1,19 @@
 use dioxus::prelude::*;
 use dioxus_router::prelude::*;
 use crate::components::{SearchBar, AnimeGrid, NavBar};
+use crate::components::toast::use_toasts;
 use crate::services::api::ApiClient;
-use crate::models::AnimeSummary;
+use crate::models::{AnimeSummary, YearBrowseResponse};
 
 #[component]
 pub fn Browse(year: i32, season: String) -> Element {
-    let mut anime_list = use_signal(|| Vec::<AnimeSummary>::new());
+    let mut anime_list = use_signal(Vec::<AnimeSummary>::new);
+    let mut year_listing = use_signal(|| None::<YearBrowseResponse>);
+    // Whole-year mode lists all four seasons at once
+    let mut year_view = use_signal(|| false);
     let mut is_loading = use_signal(|| true);
-    let nav = navigator();
+    let mut toasts = use_toasts();
+    let _nav = navigator();
     
     // Create local copy for UI use
     let season_display = season.clone();
@@ -17,19 +22,34 @@ pub fn Browse(year: i32, season: String) -> Element {
     let (prev_year, prev_season) = get_prev_season(year, &season);
     let (next_year, next_season) = get_next_season(year, &season);
     
-    // Load seasonal anime
+    // Load seasonal anime, or the whole year
     use_effect(move || {
         let year = year;
         let season = season.clone();
+        let whole_year = *year_view.read();
+        is_loading.set(true);
         spawn(async move {
             let api = ApiClient::new();
             
-            match api.browse_seasonal(year, &season).await {
-                Ok(anime) => {
-                    anime_list.set(anime);
+            if whole_year {
+                match api.browse_year(year).await {
+                    Ok(listing) => {
+                        year_listing.set(Some(listing));
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to load year anime: {}", e);
+                        toasts.error(format!("Couldn't load {} anime", year));
+                    }
                 }
-                Err(e) => {
-                    tracing::error!("Failed to load seasonal anime: {}", e);
+            } else {
+                match api.browse_seasonal(year, &season).await {
+                    Ok(anime) => {
+                        anime_list.set(anime);
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to load seasonal anime: {}", e);
+                        toasts.error(format!("Couldn't load {} {} anime", season_display_name(&season), year));
+                    }
                 }
             }
             
@@ -37,6 +57,25 @@ pub fn Browse(year: i32, season: String) -> Element {
         });
     });
     
+    let title = if *year_view.read() {
+        format!("{} Anime", year)
+    } else {
+        format!("{} {} Anime", season_display_name(&season_display), year)
+    };
+    let year_seasons: Vec<(&'static str, Vec<AnimeSummary>)> = year_listing
+        .read()
+        .as_ref()
+        .map(|listing| listing.seasons.in_order().map(|(name, anime)| (name, anime.clone())).to_vec())
+        .unwrap_or_default();
+    let pressed = |active: bool| if active { "true" } else { "false" };
+    let mode_style = |active: bool| format!("
+        padding: 0.5rem 1rem;
+        border: none;
+        cursor: pointer;
+        color: white;
+        background: {};
+    ", if active { "#667eea" } else { "rgba(255,255,255,0.1)" });
+    
     rsx! {
         div { class: "browse-page",
             style: "min-height: 100vh; background: #0a0a0a;",
@@ -61,7 +100,7 @@ pub fn Browse(year: i32, season: String) -> Element {
                             color: white;
                             margin-bottom: 1rem;
                         ",
-                        {format!("{} {} Anime", season_display_name(&season_display), year)}
+                        {title}
                     }
                     
                     // Season navigation
@@ -96,6 +135,39 @@ pub fn Browse(year: i32, season: String) -> Element {
                             ",
                             "Next →"
                         }
+                        
+                        // Season / whole year toggle
+                        div {
+                            role: "group",
+                            "aria-label": "Browse mode",
+                            style: "display: flex; border-radius: 8px; overflow: hidden;",
+                            
+                            button {
+                                "aria-pressed": pressed(!*year_view.read()),
+                                onclick: move |_| year_view.set(false),
+                                style: mode_style(!*year_view.read()),
+                                "Season"
+                            }
+                            button {
+                                "aria-pressed": pressed(*year_view.read()),
+                                onclick: move |_| year_view.set(true),
+                                style: mode_style(*year_view.read()),
+                                "Whole year"
+                            }
+                        }
+                        
+                        Link {
+                            to: format!("/browse/{}/{}/plan", year, season_display),
+                            style: "
+                                margin-left: auto;
+                                padding: 0.5rem 1rem;
+                                background: #667eea;
+                                color: white;
+                                border-radius: 8px;
+                                text-decoration: none;
+                            ",
+                            "My season plan"
+                        }
                     }
                 }
             }
@@ -125,6 +197,28 @@ pub fn Browse(year: i32, season: String) -> Element {
                             ",
                         }
                     }
+                } else if *year_view.read() {
+                    for (season_name, anime) in year_seasons {
+                        section {
+                            key: "{season_name}",
+                            style: "margin-bottom: 3rem;",
+
+                            h2 {
+                                style: "color: white; font-size: 1.5rem; margin-bottom: 1rem;",
+                                {format!("{} {}", season_display_name(season_name), year)}
+                                span {
+                                    style: "color: #a0a0b0; font-size: 1rem; margin-left: 0.5rem;",
+                                    {format!("({})", anime.len())}
+                                }
+                            }
+
+                            if anime.is_empty() {
+                                p { style: "color: #a0a0b0;", "Nothing this season." }
+                            } else {
+                                AnimeGrid { anime: anime.clone() }
+                            }
+                        }
+                    }
                 } else if anime_list.read().is_empty() {
                     div {
                         style: "
@@ -170,7 +264,7 @@ fn get_next_season(year: i32, season: &str) -> (i32, String) {
     }
 }
 
-fn season_display_name(season: &str) -> &str {
+pub(crate) fn season_display_name(season: &str) -> &str {
     match season.to_lowercase().as_str() {
         "winter" => "Winter",
         "spring" => "Spring",
@@ -5,8 +5,8 @@ use crate::models::AnimeSummary;
 
 #[component]
 pub fn Home() -> Element {
-    let mut recent_anime = use_signal(|| Vec::<AnimeSummary>::new());
-    let mut popular_anime = use_signal(|| Vec::<AnimeSummary>::new());
+    let mut recent_anime = use_signal(Vec::<AnimeSummary>::new);
+    let mut popular_anime = use_signal(Vec::<AnimeSummary>::new);
     let mut is_loading = use_signal(|| true);
     
     // Load initial data
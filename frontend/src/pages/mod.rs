@@ -2,8 +2,12 @@ pub mod home;
 pub mod login;
 pub mod series;
 pub mod browse;
+pub mod season_plan;
+pub mod settings;
 
 pub use home::Home;
 pub use login::Login;
 pub use series::Series;
-pub use browse::Browse;
\ No newline at end of file
+pub use browse::Browse;
+pub use season_plan::SeasonPlan;
+pub use settings::Settings;
\ No newline at end of file
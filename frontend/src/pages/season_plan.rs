@@ -0,0 +1,256 @@
+use dioxus::prelude::*;
+use dioxus_router::prelude::*;
+use wasm_bindgen::JsCast;
+use crate::components::NavBar;
+use crate::pages::browse::season_display_name;
+use crate::services::api::ApiClient;
+use crate::services::auth::AuthState;
+use crate::services::season_plan::{generate_ics, group_by_weekday, weekday_display_name};
+use crate::models::CalendarEntry;
+
+// Hide interactive controls and switch to a light palette when printing
+const PRINT_STYLES: &str = "
+    @media print {
+        .no-print { display: none !important; }
+        .season-plan-page { background: white !important; color: black !important; }
+        .season-plan-page table { color: black !important; }
+        .season-plan-page th, .season-plan-page td { border-color: #999 !important; }
+    }
+";
+
+#[component]
+pub fn SeasonPlan(year: i32, season: String) -> Element {
+    let auth_state = use_context::<Signal<AuthState>>();
+    let mut entries = use_signal(Vec::<CalendarEntry>::new);
+    let mut watchlist = use_signal(Vec::<String>::new);
+    let mut is_loading = use_signal(|| true);
+    let mut error = use_signal(|| None::<String>);
+
+    let season_display = season.clone();
+    let file_name = format!("kensho-{}-{}.ics", season.to_lowercase(), year);
+
+    // Load the season calendar and, if signed in, the user's watchlist
+    use_effect(move || {
+        let season = season.clone();
+        let token = auth_state.read().access_token.clone();
+        spawn(async move {
+            let api = ApiClient::new();
+
+            match api.get_season_calendar(year, &season).await {
+                Ok(calendar) => entries.set(calendar.entries),
+                Err(e) => {
+                    tracing::error!("Failed to load season calendar: {}", e);
+                    error.set(Some(e));
+                }
+            }
+
+            if let Some(token) = token {
+                match api.get_watchlist(&token).await {
+                    Ok(list) => watchlist.set(list.items.into_iter().map(|i| i.anime_id).collect()),
+                    Err(e) => tracing::error!("Failed to load watchlist: {}", e),
+                }
+            }
+
+            is_loading.set(false);
+        });
+    });
+
+    // The plan only covers shows the user is tracking; fall back to the full season when signed out
+    let is_authenticated = auth_state.read().is_authenticated();
+    let planned: Vec<CalendarEntry> = entries
+        .read()
+        .iter()
+        .filter(|e| !is_authenticated || watchlist.read().contains(&e.anime_id))
+        .cloned()
+        .collect();
+    let suggestions: Vec<CalendarEntry> = if is_authenticated {
+        entries
+            .read()
+            .iter()
+            .filter(|e| !watchlist.read().contains(&e.anime_id))
+            .cloned()
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let groups = group_by_weekday(&planned);
+    let ics_entries = planned.clone();
+
+    rsx! {
+        style { {PRINT_STYLES} }
+
+        div { class: "season-plan-page",
+            style: "min-height: 100vh; background: #0a0a0a; color: white;",
+
+            div { class: "no-print", NavBar {} }
+
+            main {
+                style: "padding: 2rem; max-width: 1000px; margin: 0 auto;",
+
+                h1 {
+                    style: "font-size: 2rem; font-weight: 600; margin-bottom: 1rem;",
+                    {format!("My {} {} Season Plan", season_display_name(&season_display), year)}
+                }
+
+                div { class: "no-print",
+                    style: "display: flex; gap: 1rem; margin-bottom: 2rem;",
+
+                    button {
+                        style: "
+                            padding: 0.5rem 1rem;
+                            background: #667eea;
+                            color: white;
+                            border: none;
+                            border-radius: 8px;
+                            cursor: pointer;
+                        ",
+                        onclick: move |_| {
+                            if let Some(window) = web_sys::window() {
+                                let _ = window.print();
+                            }
+                        },
+                        "Print"
+                    }
+
+                    button {
+                        style: "
+                            padding: 0.5rem 1rem;
+                            background: rgba(255,255,255,0.1);
+                            color: white;
+                            border: none;
+                            border-radius: 8px;
+                            cursor: pointer;
+                        ",
+                        disabled: planned.is_empty(),
+                        onclick: move |_| {
+                            let ics = generate_ics(&ics_entries);
+                            if let Err(e) = download_file(&file_name, &ics, "text/calendar") {
+                                tracing::error!("Failed to export calendar: {}", e);
+                            }
+                        },
+                        "Export .ics"
+                    }
+
+                    Link {
+                        to: format!("/browse/{}/{}", year, season_display),
+                        style: "
+                            padding: 0.5rem 1rem;
+                            color: #667eea;
+                            text-decoration: none;
+                        ",
+                        "← Back to season"
+                    }
+                }
+
+                if *is_loading.read() {
+                    p { style: "color: #a0a0b0;", "Loading schedule..." }
+                } else if let Some(err) = error.read().as_ref() {
+                    p { style: "color: #ff6b6b;", {format!("Could not load the schedule: {}", err)} }
+                } else if groups.is_empty() {
+                    p { style: "color: #a0a0b0;", "Nothing planned for this season yet." }
+                } else {
+                    for (day, day_entries) in groups {
+                        section {
+                            key: "{day}",
+                            style: "margin-bottom: 1.5rem; break-inside: avoid;",
+                            h2 {
+                                style: "font-size: 1.25rem; margin-bottom: 0.5rem; color: #667eea;",
+                                {weekday_display_name(&day)}
+                            }
+                            table {
+                                style: "width: 100%; border-collapse: collapse;",
+                                thead {
+                                    tr {
+                                        th { style: "text-align: left; padding: 0.5rem; border-bottom: 1px solid #333;", "Time" }
+                                        th { style: "text-align: left; padding: 0.5rem; border-bottom: 1px solid #333;", "Title" }
+                                        th { style: "text-align: left; padding: 0.5rem; border-bottom: 1px solid #333;", "Episodes" }
+                                    }
+                                }
+                                tbody {
+                                    for entry in day_entries {
+                                        tr {
+                                            key: "{entry.anime_id}",
+                                            td { style: "padding: 0.5rem; border-bottom: 1px solid #222;",
+                                                {entry.air_time.clone().unwrap_or_else(|| "TBA".to_string())}
+                                            }
+                                            td { style: "padding: 0.5rem; border-bottom: 1px solid #222;", "{entry.title}" }
+                                            td { style: "padding: 0.5rem; border-bottom: 1px solid #222;",
+                                                {if entry.episodes > 0 { entry.episodes.to_string() } else { "?".to_string() }}
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                // Shows airing this season that aren't on the watchlist yet
+                if !suggestions.is_empty() {
+                    section { class: "no-print",
+                        style: "margin-top: 2rem;",
+                        h2 { style: "font-size: 1.25rem; margin-bottom: 0.5rem;", "Also airing this season" }
+                        ul {
+                            style: "list-style: none; padding: 0;",
+                            for entry in suggestions {
+                                li {
+                                    key: "{entry.anime_id}",
+                                    style: "display: flex; justify-content: space-between; padding: 0.5rem 0; border-bottom: 1px solid #222;",
+                                    span { "{entry.title}" }
+                                    button {
+                                        style: "
+                                            padding: 0.25rem 0.75rem;
+                                            background: rgba(102,126,234,0.2);
+                                            color: #667eea;
+                                            border: 1px solid #667eea;
+                                            border-radius: 6px;
+                                            cursor: pointer;
+                                        ",
+                                        onclick: move |_| {
+                                            let anime_id = entry.anime_id.clone();
+                                            let token = auth_state.read().access_token.clone();
+                                            spawn(async move {
+                                                let Some(token) = token else { return };
+                                                match ApiClient::new().add_to_watchlist(&anime_id, &token).await {
+                                                    Ok(()) => watchlist.write().push(anime_id),
+                                                    Err(e) => tracing::error!("Failed to add to watchlist: {}", e),
+                                                }
+                                            });
+                                        },
+                                        "+ Add"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Trigger a browser download of `contents` via a temporary object URL
+fn download_file(file_name: &str, contents: &str, mime_type: &str) -> Result<(), String> {
+    let window = web_sys::window().ok_or("No window available")?;
+    let document = window.document().ok_or("No document available")?;
+
+    let parts = js_sys::Array::of1(&wasm_bindgen::JsValue::from_str(contents));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)
+        .map_err(|e| format!("{:?}", e))?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)
+        .map_err(|e| format!("{:?}", e))?;
+
+    let anchor = document
+        .create_element("a")
+        .map_err(|e| format!("{:?}", e))?
+        .dyn_into::<web_sys::HtmlElement>()
+        .map_err(|_| "Failed to create download link".to_string())?;
+    anchor.set_attribute("href", &url).map_err(|e| format!("{:?}", e))?;
+    anchor.set_attribute("download", file_name).map_err(|e| format!("{:?}", e))?;
+    anchor.click();
+
+    let _ = web_sys::Url::revoke_object_url(&url);
+    Ok(())
+}
@@ -1,16 +1,23 @@
 use dioxus::prelude::*;
 use dioxus_router::prelude::*;
 use crate::components::{NavBar, VideoPlayer, EpisodeList};
+use crate::components::toast::use_toasts;
 use crate::services::api::ApiClient;
+use crate::services::auth::AuthState;
+use crate::services::data_saver::use_data_saver;
 use crate::models::{Anime, Episode};
 
 #[component]
 pub fn Series(id: String) -> Element {
+    let auth_state = use_context::<Signal<AuthState>>();
+    let data_saver = use_data_saver();
     let mut anime = use_signal(|| None::<Anime>);
-    let mut episodes = use_signal(|| Vec::<Episode>::new());
+    let mut episodes = use_signal(Vec::<Episode>::new);
     let mut selected_episode = use_signal(|| None::<Episode>);
     let mut is_loading = use_signal(|| true);
     let mut current_stream = use_signal(|| None::<String>);
+    let mut stream_watermark = use_signal(|| false);
+    let mut toasts = use_toasts();
     
     // Load anime data
     use_effect(move || {
@@ -25,6 +32,7 @@ pub fn Series(id: String) -> Element {
                 }
                 Err(e) => {
                     tracing::error!("Failed to load anime: {}", e);
+                    toasts.error("Couldn't load this anime");
                 }
             }
             
@@ -38,6 +46,7 @@ pub fn Series(id: String) -> Element {
                 }
                 Err(e) => {
                     tracing::error!("Failed to load episodes: {}", e);
+                    toasts.warning("Couldn't load the episode list");
                 }
             }
             
@@ -85,8 +94,8 @@ pub fn Series(id: String) -> Element {
                         
                         // Poster
                         img {
-                            src: {anime_data.poster_url.clone()},
-                            alt: {anime_data.title.clone()},
+                            src: data_saver.read().image_url(&anime_data.poster_url) ,
+                            alt: anime_data.title.clone() ,
                             style: "
                                 width: 100%;
                                 border-radius: 8px;
@@ -127,7 +136,7 @@ pub fn Series(id: String) -> Element {
                                         border-radius: 20px;
                                         font-size: 0.875rem;
                                     ",
-                                    {format!("{} Episodes", anime_data.episode_count)}
+                                    {anime_data.episode_label()}
                                 }
                                 
                                 span {
@@ -163,7 +172,14 @@ pub fn Series(id: String) -> Element {
                     if let Some(stream_url) = current_stream.read().as_ref() {
                         div {
                             style: "margin-bottom: 2rem;",
-                            VideoPlayer { stream_url: stream_url.clone() }
+                            // Keyed so a new episode remounts the player with fresh state
+                            VideoPlayer {
+                                key: "{stream_url}",
+                                stream_url: stream_url.clone(),
+                                episode_id: selected_episode.read().as_ref().map(|ep| ep.id.clone()),
+                                auth_token: auth_state.read().access_token.clone(),
+                                watermark: *stream_watermark.read(),
+                            }
                         }
                     }
                     
@@ -197,7 +213,26 @@ pub fn Series(id: String) -> Element {
                                 episodes: episodes.read().clone(),
                                 on_select: move |ep: Episode| {
                                     selected_episode.set(Some(ep.clone()));
-                                    current_stream.set(Some(format!("https://example.com/stream/{}", ep.id)));
+                                    
+                                    let Some(token) = auth_state.read().access_token.clone() else {
+                                        stream_watermark.set(false);
+                                        current_stream.set(Some(format!("https://example.com/stream/{}", ep.id)));
+                                        return;
+                                    };
+                                    // 480p while saving data; otherwise the default quality
+                                    let quality = data_saver.read().stream_quality();
+                                    spawn(async move {
+                                        match ApiClient::new().get_stream_url(&ep.anime_id, ep.episode_number, quality, &token).await {
+                                            Ok(stream) => {
+                                                stream_watermark.set(stream.watermark);
+                                                current_stream.set(Some(stream.url));
+                                            }
+                                            Err(e) => {
+                                                tracing::error!("Failed to load stream: {}", e);
+                                                toasts.error(format!("Couldn't start episode {}", ep.episode_number));
+                                            }
+                                        }
+                                    });
                                 }
                             }
                         }
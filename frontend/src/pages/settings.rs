@@ -0,0 +1,74 @@
+use dioxus::prelude::*;
+use crate::components::NavBar;
+use crate::services::data_saver::{use_data_saver, DATA_SAVER_STREAM_QUALITY};
+
+#[component]
+pub fn Settings() -> Element {
+    let mut data_saver = use_data_saver();
+    let enabled = data_saver.read().enabled;
+    
+    rsx! {
+        div { class: "settings-page",
+            style: "min-height: 100vh; background: #0a0a0a;",
+            
+            NavBar {}
+            
+            div {
+                style: "max-width: 800px; margin: 0 auto; padding: 2rem;",
+                
+                h1 {
+                    style: "
+                        font-size: 2rem;
+                        font-weight: 700;
+                        color: white;
+                        margin-bottom: 1.5rem;
+                    ",
+                    "Settings"
+                }
+                
+                label {
+                    style: "
+                        display: flex;
+                        align-items: flex-start;
+                        gap: 1rem;
+                        background: rgba(26, 26, 46, 0.5);
+                        border-radius: 12px;
+                        padding: 1.5rem;
+                        cursor: pointer;
+                    ",
+                    
+                    input {
+                        r#type: "checkbox",
+                        id: "data-saver-toggle",
+                        checked: enabled,
+                        onchange: move |_| {
+                            data_saver.write().set_enabled(!enabled);
+                        },
+                        style: "margin-top: 0.25rem;",
+                    }
+                    
+                    div {
+                        h2 {
+                            style: "
+                                font-size: 1.125rem;
+                                font-weight: 600;
+                                color: white;
+                                margin-bottom: 0.5rem;
+                            ",
+                            "Data saver"
+                        }
+                        p {
+                            style: "color: #a0a0b0; line-height: 1.6;",
+                            {format!(
+                                "Loads small images, skips prefetching, waits for a tap before loading posters on mobile, \
+                                 requests {} streams and saves watch progress less often. \
+                                 Turns on automatically when your browser asks to save data.",
+                                DATA_SAVER_STREAM_QUALITY
+                            )}
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,12 @@
-use gloo_net::http::Request;
+use gloo_net::http::{Request, RequestBuilder};
 use serde::Serialize;
+use wasm_bindgen::JsCast;
 use crate::models::*;
 
+/// Readable cookie the backend sets when it runs in cookie session mode
+const CSRF_COOKIE: &str = "kensho_csrf";
+const CSRF_HEADER: &str = "X-CSRF-Token";
+
 #[derive(Clone)]
 pub struct ApiClient {
     base_url: String,
@@ -15,6 +20,7 @@ impl ApiClient {
         }
     }
 
+    #[allow(dead_code)]
     pub fn with_base_url(base_url: String) -> Self {
         Self { base_url }
     }
@@ -28,20 +34,22 @@ impl ApiClient {
             .header("Authorization", &format!("Bearer {}", token))
     }
 
+    #[allow(dead_code)] // Until the login page stops mocking
     fn post_json<T: Serialize>(&self, path: &str, body: &T) -> Result<gloo_net::http::Request, gloo_net::Error> {
-        Request::post(&format!("{}{}", self.base_url, path))
+        with_csrf(Request::post(&format!("{}{}", self.base_url, path)))
             .header("Content-Type", "application/json")
             .body(serde_json::to_string(body).unwrap())
     }
 
     fn post_json_with_auth<T: Serialize>(&self, path: &str, body: &T, token: &str) -> Result<gloo_net::http::Request, gloo_net::Error> {
-        Request::post(&format!("{}{}", self.base_url, path))
+        with_csrf(Request::post(&format!("{}{}", self.base_url, path)))
             .header("Content-Type", "application/json")
             .header("Authorization", &format!("Bearer {}", token))
             .body(serde_json::to_string(body).unwrap())
     }
 
     // Health check
+    #[allow(dead_code)]
     pub async fn health_check(&self) -> Result<bool, String> {
         match self.request("/health").send().await {
             Ok(resp) if resp.ok() => Ok(true),
@@ -50,7 +58,8 @@ impl ApiClient {
         }
     }
 
-    // Authentication endpoints
+    // Authentication endpoints, unused until the login page stops mocking
+    #[allow(dead_code)]
     pub async fn login(&self, email: String, password: String) -> Result<LoginResponse, String> {
         let req = LoginRequest { email, password };
         
@@ -64,8 +73,9 @@ impl ApiClient {
         }
     }
 
+    #[allow(dead_code)]
     pub async fn logout(&self, token: &str) -> Result<(), String> {
-        match Request::post(&format!("{}/auth/logout", self.base_url))
+        match with_csrf(Request::post(&format!("{}/auth/logout", self.base_url)))
             .header("Authorization", &format!("Bearer {}", token))
             .send().await {
             Ok(resp) if resp.ok() => Ok(()),
@@ -118,6 +128,57 @@ impl ApiClient {
         }
     }
 
+    /// Every season of `year` in one request
+    pub async fn browse_year(&self, year: i32) -> Result<YearBrowseResponse, String> {
+        let url = format!("/browse/year/{}", year);
+        
+        match self.request(&url).send().await {
+            Ok(resp) if resp.ok() => {
+                resp.json::<YearBrowseResponse>().await
+                    .map_err(|e| format!("Failed to parse year anime: {}", e))
+            },
+            Ok(resp) => Err(format!("Browse failed: {}", resp.status())),
+            Err(e) => Err(format!("Network error: {}", e)),
+        }
+    }
+
+    pub async fn get_season_calendar(&self, year: i32, season: &str) -> Result<SeasonCalendarResponse, String> {
+        let url = format!("/browse/season/{}/{}/calendar", year, season);
+        
+        match self.request(&url).send().await {
+            Ok(resp) if resp.ok() => {
+                resp.json::<SeasonCalendarResponse>().await
+                    .map_err(|e| format!("Failed to parse season calendar: {}", e))
+            },
+            Ok(resp) => Err(format!("Failed to get season calendar: {}", resp.status())),
+            Err(e) => Err(format!("Network error: {}", e)),
+        }
+    }
+
+    // Watchlist endpoints (require authentication)
+    pub async fn get_watchlist(&self, token: &str) -> Result<WatchlistResponse, String> {
+        match self.request_with_auth("/user/watchlist", token).send().await {
+            Ok(resp) if resp.ok() => {
+                resp.json::<WatchlistResponse>().await
+                    .map_err(|e| format!("Failed to parse watchlist: {}", e))
+            },
+            Ok(resp) if resp.status() == 401 => Err("Authentication required".to_string()),
+            Ok(resp) => Err(format!("Failed to get watchlist: {}", resp.status())),
+            Err(e) => Err(format!("Network error: {}", e)),
+        }
+    }
+
+    pub async fn add_to_watchlist(&self, anime_id: &str, token: &str) -> Result<(), String> {
+        let body = serde_json::json!({ "anime_id": anime_id });
+        
+        match self.post_json_with_auth("/user/watchlist", &body, token).unwrap().send().await {
+            Ok(resp) if resp.ok() => Ok(()),
+            Ok(resp) if resp.status() == 401 => Err("Authentication required".to_string()),
+            Ok(resp) => Err(format!("Failed to add to watchlist: {}", resp.status())),
+            Err(e) => Err(format!("Network error: {}", e)),
+        }
+    }
+
     // Episode endpoints
     pub async fn get_episodes(&self, anime_id: &str) -> Result<Vec<Episode>, String> {
         let url = format!("/anime/{}/episodes", anime_id);
@@ -133,8 +194,8 @@ impl ApiClient {
     }
 
     // Streaming endpoint (requires authentication)
-    pub async fn get_stream_url(&self, anime_id: &str, episode: i32, token: &str) -> Result<StreamUrl, String> {
-        let url = format!("/stream/{}/{}", anime_id, episode);
+    pub async fn get_stream_url(&self, anime_id: &str, episode: i32, quality: &str, token: &str) -> Result<StreamUrl, String> {
+        let url = format!("/stream/{}/{}?quality={}", anime_id, episode, urlencoding::encode(quality));
         
         match self.request_with_auth(&url, token).send().await {
             Ok(resp) if resp.ok() => {
@@ -146,6 +207,62 @@ impl ApiClient {
             Err(e) => Err(format!("Network error: {}", e)),
         }
     }
+
+    /// Save how far into an episode the user has watched, in seconds
+    pub async fn save_playback_position(&self, episode_id: &str, position: u32, duration: u32, token: &str) -> Result<(), String> {
+        let body = serde_json::json!({
+            "episode_id": episode_id,
+            "position": position,
+            "duration": duration,
+        });
+        
+        match self.post_json_with_auth("/user/playback-position", &body, token).unwrap().send().await {
+            Ok(resp) if resp.ok() => Ok(()),
+            Ok(resp) if resp.status() == 401 => Err("Authentication required".to_string()),
+            Ok(resp) => Err(format!("Failed to save playback position: {}", resp.status())),
+            Err(e) => Err(format!("Network error: {}", e)),
+        }
+    }
+
+    /// Recover a dropped stream without starting a new one
+    pub async fn resume_stream(&self, resume_token: &str, token: &str) -> Result<StreamManifest, String> {
+        let body = serde_json::json!({ "resume_token": resume_token });
+        
+        match self.post_json_with_auth("/stream/resume", &body, token).unwrap().send().await {
+            Ok(resp) if resp.ok() => {
+                resp.json::<StreamManifest>().await
+                    .map_err(|e| format!("Failed to parse stream: {}", e))
+            },
+            Ok(resp) if resp.status() == 401 => Err("Resumption token expired".to_string()),
+            Ok(resp) => Err(format!("Failed to resume stream: {}", resp.status())),
+            Err(e) => Err(format!("Network error: {}", e)),
+        }
+    }
+}
+
+/// Attach the double-submit CSRF header to a mutating request.
+/// The CSRF cookie only exists in cookie session mode, so bearer deployments send nothing extra.
+fn with_csrf(builder: RequestBuilder) -> RequestBuilder {
+    match csrf_token() {
+        Some(token) => builder.header(CSRF_HEADER, &token),
+        None => builder,
+    }
+}
+
+fn csrf_token() -> Option<String> {
+    let document = web_sys::window()?
+        .document()?
+        .dyn_into::<web_sys::HtmlDocument>()
+        .ok()?;
+    csrf_token_from_cookies(&document.cookie().ok()?)
+}
+
+fn csrf_token_from_cookies(cookies: &str) -> Option<String> {
+    cookies
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, value)| *name == CSRF_COOKIE && !value.is_empty())
+        .map(|(_, value)| value.to_string())
 }
 
 impl Default for ApiClient {
@@ -159,4 +276,19 @@ mod urlencoding {
     pub fn encode(s: &str) -> String {
         js_sys::encode_uri_component(s).as_string().unwrap()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csrf_token_from_cookies() {
+        assert_eq!(
+            csrf_token_from_cookies("theme=dark; kensho_csrf=abc123; lang=en").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(csrf_token_from_cookies("theme=dark"), None);
+        assert_eq!(csrf_token_from_cookies("kensho_csrf="), None);
+    }
+}
@@ -0,0 +1,192 @@
+// Data saver mode for metered connections: smaller images, no prefetching,
+// lower stream quality and less frequent progress reports
+
+use dioxus::prelude::*;
+use wasm_bindgen::JsValue;
+
+const STORAGE_KEY: &str = "data_saver";
+
+/// Stream quality requested when the user has not picked one
+pub const DEFAULT_STREAM_QUALITY: &str = "1080p";
+pub const DATA_SAVER_STREAM_QUALITY: &str = "480p";
+
+/// How often the player saves the playback position
+pub const PROGRESS_REPORT_INTERVAL_MS: f64 = 10_000.0;
+pub const DATA_SAVER_PROGRESS_REPORT_INTERVAL_MS: f64 = 60_000.0;
+
+/// Viewports narrower than this count as mobile for deferred poster loading
+const MOBILE_MAX_WIDTH: f64 = 768.0;
+
+/// Shared through context as `Signal<DataSaver>`; see `use_data_saver`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DataSaver {
+    pub enabled: bool,
+}
+
+impl Default for DataSaver {
+    fn default() -> Self {
+        // An explicit choice in settings wins over the browser's Save-Data hint
+        let stored = local_storage()
+            .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+            .map(|value| value == "true");
+
+        Self {
+            enabled: stored.unwrap_or_else(browser_save_data),
+        }
+    }
+}
+
+impl DataSaver {
+    /// Turn the mode on or off and remember the choice
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+
+        if let Some(storage) = local_storage() {
+            let _ = storage.set_item(STORAGE_KEY, if enabled { "true" } else { "false" });
+        }
+    }
+
+    /// URL to load for an image: the small variant while saving data
+    pub fn image_url(&self, url: &str) -> String {
+        if self.enabled {
+            small_image_variant(url)
+        } else {
+            url.to_string()
+        }
+    }
+
+    pub fn prefetch_enabled(&self) -> bool {
+        !self.enabled
+    }
+
+    /// Posters wait for a tap on mobile while saving data
+    pub fn defer_posters(&self) -> bool {
+        self.enabled && is_mobile()
+    }
+
+    pub fn stream_quality(&self) -> &'static str {
+        if self.enabled {
+            DATA_SAVER_STREAM_QUALITY
+        } else {
+            DEFAULT_STREAM_QUALITY
+        }
+    }
+
+    pub fn progress_report_interval_ms(&self) -> f64 {
+        if self.enabled {
+            DATA_SAVER_PROGRESS_REPORT_INTERVAL_MS
+        } else {
+            PROGRESS_REPORT_INTERVAL_MS
+        }
+    }
+}
+
+/// The data saver signal provided at the app root
+pub fn use_data_saver() -> Signal<DataSaver> {
+    use_context::<Signal<DataSaver>>()
+}
+
+/// Small variant of a poster URL. MyAnimeList's CDN serves thumbnails under the
+/// same name with a `t` suffix; other hosts get a `size=small` query parameter.
+pub fn small_image_variant(url: &str) -> String {
+    if url.is_empty() {
+        return String::new();
+    }
+
+    if url.contains("cdn.myanimelist.net") {
+        if let Some(dot) = url.rfind('.') {
+            let (stem, extension) = url.split_at(dot);
+            if stem.ends_with('t') {
+                return url.to_string();
+            }
+            return format!("{}t{}", stem, extension);
+        }
+    }
+
+    if url.contains("size=small") {
+        url.to_string()
+    } else if url.contains('?') {
+        format!("{}&size=small", url)
+    } else {
+        format!("{}?size=small", url)
+    }
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+/// `navigator.connection.saveData`, where the Network Information API exists
+fn browser_save_data() -> bool {
+    let Some(window) = web_sys::window() else {
+        return false;
+    };
+
+    js_sys::Reflect::get(window.as_ref(), &JsValue::from_str("navigator"))
+        .and_then(|navigator| js_sys::Reflect::get(&navigator, &JsValue::from_str("connection")))
+        .and_then(|connection| js_sys::Reflect::get(&connection, &JsValue::from_str("saveData")))
+        .ok()
+        .and_then(|save_data| save_data.as_bool())
+        .unwrap_or(false)
+}
+
+fn is_mobile() -> bool {
+    web_sys::window()
+        .and_then(|window| window.inner_width().ok())
+        .and_then(|width| width.as_f64())
+        .map(|width| width < MOBILE_MAX_WIDTH)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::prefetch::Prefetcher;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    const MAL_POSTER: &str = "https://cdn.myanimelist.net/images/anime/1615/149911.jpg";
+
+    #[wasm_bindgen_test]
+    fn test_small_image_variant() {
+        assert_eq!(
+            small_image_variant(MAL_POSTER),
+            "https://cdn.myanimelist.net/images/anime/1615/149911t.jpg"
+        );
+        assert_eq!(
+            small_image_variant("https://cdn.myanimelist.net/images/anime/1615/149911t.jpg"),
+            "https://cdn.myanimelist.net/images/anime/1615/149911t.jpg"
+        );
+        assert_eq!(
+            small_image_variant("https://example.com/poster.jpg"),
+            "https://example.com/poster.jpg?size=small"
+        );
+        assert_eq!(
+            small_image_variant("https://example.com/poster.jpg?v=2"),
+            "https://example.com/poster.jpg?v=2&size=small"
+        );
+    }
+
+    #[wasm_bindgen_test]
+    fn test_toggling_data_saver() {
+        let mut data_saver = DataSaver { enabled: false };
+        assert_eq!(data_saver.image_url(MAL_POSTER), MAL_POSTER);
+        assert!(Prefetcher::new(data_saver).is_active());
+        assert_eq!(data_saver.stream_quality(), "1080p");
+
+        data_saver.set_enabled(true);
+        assert_eq!(
+            data_saver.image_url(MAL_POSTER),
+            "https://cdn.myanimelist.net/images/anime/1615/149911t.jpg"
+        );
+        assert!(!Prefetcher::new(data_saver).prefetch_anime("anime-1"));
+        assert_eq!(data_saver.stream_quality(), "480p");
+        assert!(data_saver.progress_report_interval_ms() > PROGRESS_REPORT_INTERVAL_MS);
+
+        // The choice is remembered across reloads
+        assert!(DataSaver::default().enabled);
+        data_saver.set_enabled(false);
+        assert!(!DataSaver::default().enabled);
+    }
+}
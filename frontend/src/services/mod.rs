@@ -1,2 +1,6 @@
 pub mod api;
-pub mod auth;
\ No newline at end of file
+pub mod auth;
+pub mod data_saver;
+pub mod prefetch;
+pub mod season_plan;
+pub mod theme;
@@ -0,0 +1,55 @@
+// Card prefetching: warm anime details on hover so the series page opens instantly
+
+use dioxus::prelude::*;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use crate::services::api::ApiClient;
+use crate::services::data_saver::{use_data_saver, DataSaver};
+
+thread_local! {
+    /// Anime already prefetched in this session
+    static PREFETCHED: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Prefetcher {
+    active: bool,
+}
+
+impl Prefetcher {
+    pub fn new(data_saver: DataSaver) -> Self {
+        Self {
+            active: data_saver.prefetch_enabled(),
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Fetch an anime's details in the background. Returns whether a request
+    /// was started: never while saving data, and once per anime otherwise.
+    pub fn prefetch_anime(&self, anime_id: &str) -> bool {
+        if !self.is_active() {
+            return false;
+        }
+
+        let first_time = PREFETCHED.with(|prefetched| prefetched.borrow_mut().insert(anime_id.to_string()));
+        if !first_time {
+            return false;
+        }
+
+        let anime_id = anime_id.to_string();
+        wasm_bindgen_futures::spawn_local(async move {
+            if let Err(e) = ApiClient::new().get_anime(&anime_id).await {
+                tracing::debug!("Prefetch of {} failed: {}", anime_id, e);
+            }
+        });
+        true
+    }
+}
+
+/// Prefetcher following the current data saver setting
+pub fn use_prefetch() -> Prefetcher {
+    Prefetcher::new(*use_data_saver().read())
+}
@@ -0,0 +1,180 @@
+// Season plan helpers: weekday grouping and client-side ICS export
+
+use chrono::{NaiveDate, NaiveTime, Utc};
+use crate::models::CalendarEntry;
+
+pub const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// Group calendar entries by weekday (Monday first), sorted by air time within a day.
+/// Days with no entries are omitted; entries with an unknown weekday go last.
+pub fn group_by_weekday(entries: &[CalendarEntry]) -> Vec<(String, Vec<CalendarEntry>)> {
+    let mut groups: Vec<(String, Vec<CalendarEntry>)> = Vec::new();
+    
+    for day in WEEKDAYS {
+        let mut day_entries: Vec<CalendarEntry> = entries
+            .iter()
+            .filter(|e| e.weekday.to_lowercase() == day)
+            .cloned()
+            .collect();
+        
+        if !day_entries.is_empty() {
+            day_entries.sort_by(|a, b| a.air_time.cmp(&b.air_time).then(a.title.cmp(&b.title)));
+            groups.push((day.to_string(), day_entries));
+        }
+    }
+    
+    let unscheduled: Vec<CalendarEntry> = entries
+        .iter()
+        .filter(|e| !WEEKDAYS.contains(&e.weekday.to_lowercase().as_str()))
+        .cloned()
+        .collect();
+    
+    if !unscheduled.is_empty() {
+        groups.push(("unscheduled".to_string(), unscheduled));
+    }
+    
+    groups
+}
+
+pub fn weekday_display_name(day: &str) -> String {
+    let mut chars = day.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().collect::<String>() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+/// Generate an iCalendar document with one weekly-recurring VEVENT per airing slot
+pub fn generate_ics(entries: &[CalendarEntry]) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Kensho//Season Plan//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    
+    for entry in entries {
+        let Ok(first) = NaiveDate::parse_from_str(&entry.first_air_date, "%Y-%m-%d") else {
+            continue;
+        };
+        let time = entry
+            .air_time
+            .as_deref()
+            .and_then(|t| NaiveTime::parse_from_str(t, "%H:%M").ok())
+            .unwrap_or_else(|| NaiveTime::from_hms_opt(0, 0, 0).unwrap());
+        let start = first.and_time(time);
+        
+        // Stop recurring after the final episode; fall back to the episode count
+        let rrule = match entry
+            .last_air_date
+            .as_deref()
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        {
+            Some(last) => format!(
+                "RRULE:FREQ=WEEKLY;UNTIL={}",
+                last.and_time(time).format("%Y%m%dT%H%M%S")
+            ),
+            None if entry.episodes > 0 => format!("RRULE:FREQ=WEEKLY;COUNT={}", entry.episodes),
+            None => "RRULE:FREQ=WEEKLY".to_string(),
+        };
+        
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}-{}@kensho", entry.anime_id, entry.first_air_date));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART:{}", start.format("%Y%m%dT%H%M%S")));
+        lines.push("DURATION:PT30M".to_string());
+        lines.push(rrule);
+        lines.push(format!("SUMMARY:{}", escape_text(&entry.title)));
+        lines.push("END:VEVENT".to_string());
+    }
+    
+    lines.push("END:VCALENDAR".to_string());
+    
+    // RFC 5545 requires CRLF line endings
+    let mut ics = lines.join("\r\n");
+    ics.push_str("\r\n");
+    ics
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, weekday: &str, time: Option<&str>) -> CalendarEntry {
+        CalendarEntry {
+            anime_id: format!("id-{}", title.to_lowercase().replace(' ', "-")),
+            title: title.to_string(),
+            weekday: weekday.to_string(),
+            air_time: time.map(|t| t.to_string()),
+            first_air_date: "2024-01-06".to_string(),
+            last_air_date: Some("2024-03-23".to_string()),
+            episodes: 12,
+        }
+    }
+
+    #[test]
+    fn test_group_by_weekday_orders_days_and_times() {
+        let entries = vec![
+            entry("Late Saturday", "saturday", Some("23:30")),
+            entry("Monday Show", "monday", Some("20:00")),
+            entry("Early Saturday", "Saturday", Some("09:00")),
+            entry("Mystery", "tbd", None),
+        ];
+
+        let groups = group_by_weekday(&entries);
+        let days: Vec<&str> = groups.iter().map(|(d, _)| d.as_str()).collect();
+        assert_eq!(days, vec!["monday", "saturday", "unscheduled"]);
+
+        let saturday: Vec<&str> = groups[1].1.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(saturday, vec!["Early Saturday", "Late Saturday"]);
+    }
+
+    #[test]
+    fn test_generated_ics_parses() {
+        let mut no_end = entry("Ongoing, Show; Part 2", "sunday", None);
+        no_end.last_air_date = None;
+        let entries = vec![entry("Frieren", "friday", Some("23:00")), no_end];
+
+        let ics = generate_ics(&entries);
+        assert!(ics.contains("\r\n"));
+
+        let mut parser = ical::IcalParser::new(std::io::BufReader::new(ics.as_bytes()));
+        let calendar = parser.next().expect("one calendar").expect("valid ICS");
+        assert_eq!(calendar.events.len(), 2);
+
+        let prop = |idx: usize, name: &str| {
+            calendar.events[idx]
+                .properties
+                .iter()
+                .find(|p| p.name == name)
+                .and_then(|p| p.value.clone())
+                .unwrap()
+        };
+
+        assert_eq!(prop(0, "DTSTART"), "20240106T230000");
+        assert_eq!(prop(0, "RRULE"), "FREQ=WEEKLY;UNTIL=20240323T230000");
+        assert_eq!(prop(1, "RRULE"), "FREQ=WEEKLY;COUNT=12");
+        assert_eq!(prop(1, "SUMMARY"), "Ongoing\\, Show\\; Part 2");
+    }
+
+    #[test]
+    fn test_weekday_display_name() {
+        assert_eq!(weekday_display_name("wednesday"), "Wednesday");
+    }
+}
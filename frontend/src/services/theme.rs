@@ -0,0 +1,95 @@
+// Light/dark theme. Pages are styled for dark; the light theme is applied by
+// the `data-theme` attribute on <html>, which index.html maps to an inverted
+// palette (media stays as is)
+
+use dioxus::prelude::*;
+
+const STORAGE_KEY: &str = "theme";
+const THEME_ATTRIBUTE: &str = "data-theme";
+
+/// Shared through context as `Signal<Theme>`; see `use_theme`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Theme {
+    /// The theme chosen last time, or dark
+    pub fn stored() -> Self {
+        local_storage()
+            .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+            .map(|value| Self::from_name(&value))
+            .unwrap_or_default()
+    }
+
+    fn from_name(name: &str) -> Self {
+        match name {
+            "light" => Theme::Light,
+            _ => Theme::Dark,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+        }
+    }
+
+    /// Switch between light and dark, apply it to the page and remember it
+    pub fn toggle(&mut self) {
+        *self = match self {
+            Theme::Dark => Theme::Light,
+            Theme::Light => Theme::Dark,
+        };
+
+        if let Some(storage) = local_storage() {
+            let _ = storage.set_item(STORAGE_KEY, self.name());
+        }
+        self.apply();
+    }
+
+    /// Set the `data-theme` attribute the page styles key off
+    pub fn apply(&self) {
+        let root = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.document_element());
+
+        if let Some(root) = root {
+            let _ = root.set_attribute(THEME_ATTRIBUTE, self.name());
+        }
+    }
+}
+
+/// The theme signal provided at the app root
+pub fn use_theme() -> Signal<Theme> {
+    use_context::<Signal<Theme>>()
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    fn test_toggling_theme() {
+        let mut theme = Theme::Dark;
+        theme.toggle();
+        assert_eq!(theme, Theme::Light);
+        assert_eq!(Theme::stored(), Theme::Light);
+
+        let root = web_sys::window().unwrap().document().unwrap().document_element().unwrap();
+        assert_eq!(root.get_attribute(THEME_ATTRIBUTE).as_deref(), Some("light"));
+
+        theme.toggle();
+        assert_eq!(Theme::stored(), Theme::Dark);
+    }
+}
@@ -12,7 +12,7 @@ pub mod user_journey;
 pub mod test_utils;
 
 #[cfg(test)]
-mod test_runner {
+pub mod test_runner {
     use wasm_bindgen_test::*;
     
     wasm_bindgen_test_configure!(run_in_browser);
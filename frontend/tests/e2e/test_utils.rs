@@ -26,6 +26,12 @@ pub struct PageObject {
     document: Document,
 }
 
+impl Default for PageObject {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PageObject {
     pub fn new() -> Self {
         let window = web_sys::window().expect("Window should exist");
@@ -82,8 +88,7 @@ impl PageObject {
     /// Get element text
     pub fn get_text(&self, selector: &str) -> Option<String> {
         self.find_element(selector)
-            .map(|el| el.text_content())
-            .flatten()
+            .and_then(|el| el.text_content())
     }
     
     /// Check if element exists
@@ -269,6 +274,12 @@ pub struct PerformanceMetrics {
     marks: std::collections::HashMap<String, f64>,
 }
 
+impl Default for PerformanceMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PerformanceMetrics {
     pub fn new() -> Self {
         Self {
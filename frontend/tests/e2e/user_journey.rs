@@ -3,8 +3,7 @@
 //! Tests the full user experience from landing page through authentication,
 //! search, discovery, and streaming initiation.
 
-use dioxus::prelude::*;
-use dioxus_web::launch;
+use wasm_bindgen::JsCast;
 use wasm_bindgen_test::*;
 use web_sys::{HtmlElement, HtmlInputElement};
 